@@ -27,7 +27,7 @@ use zkevm_circuits::util::Challenges;
 
 use crate::{
     batch::BatchHash,
-    constants::{ACC_LEN, DIGEST_LEN, MAX_AGG_SNARKS},
+    constants::{ACC_LEN, DIGEST_LEN},
     core::{assign_batch_hashes, extract_proof_and_instances_with_pairing_check},
     util::parse_hash_digest_cells,
     ConfigParams,
@@ -35,12 +35,13 @@ use crate::{
 
 use super::AggregationConfig;
 
-/// Aggregation circuit that does not re-expose any public inputs from aggregated snarks
+/// Aggregation circuit that does not re-expose any public inputs from aggregated snarks.
+/// It aggregates a batch of up to N_SNARKS chunks, i.e. the batch capacity of its verifier.
 #[derive(Clone)]
-pub struct AggregationCircuit {
+pub struct AggregationCircuit<const N_SNARKS: usize> {
     pub svk: KzgSuccinctVerifyingKey<G1Affine>,
     // the input snarks for the aggregation circuit
-    // it is padded already so it will have a fixed length of MAX_AGG_SNARKS
+    // it is padded already so it will have a fixed length of N_SNARKS
     pub snarks_with_padding: Vec<SnarkWitness>,
     // the public instance for this circuit consists of
     // - an accumulator (12 elements)
@@ -50,18 +51,23 @@ pub struct AggregationCircuit {
     pub as_proof: Value<Vec<u8>>,
     // batch hash circuit for which the snarks are generated
     // the chunks in this batch are also padded already
-    pub batch_hash: BatchHash,
+    pub batch_hash: BatchHash<N_SNARKS>,
 }
 
-impl AggregationCircuit {
+impl<const N_SNARKS: usize> AggregationCircuit<N_SNARKS> {
     pub fn new(
         params: &ParamsKZG<Bn256>,
         snarks_with_padding: &[Snark],
         rng: impl Rng + Send,
-        batch_hash: BatchHash,
+        batch_hash: BatchHash<N_SNARKS>,
     ) -> Result<Self, snark_verifier::Error> {
         let timer = start_timer!(|| "generate aggregation circuit");
 
+        assert_eq!(
+            snarks_with_padding.len(),
+            N_SNARKS,
+            "input snarks do not match N_SNARKS"
+        );
         // sanity check: snarks's public input matches chunk_hashes
         for (chunk, snark) in batch_hash
             .chunks_with_padding
@@ -117,7 +123,7 @@ impl AggregationCircuit {
     }
 }
 
-impl Circuit<Fr> for AggregationCircuit {
+impl<const N_SNARKS: usize> Circuit<Fr> for AggregationCircuit<N_SNARKS> {
     type Config = (AggregationConfig, Challenges);
     type FloorPlanner = SimpleFloorPlanner;
     fn without_witnesses(&self) -> Self {
@@ -230,7 +236,7 @@ impl Circuit<Fr> for AggregationCircuit {
                 },
             )?;
 
-            assert_eq!(snark_inputs.len(), MAX_AGG_SNARKS * DIGEST_LEN);
+            assert_eq!(snark_inputs.len(), N_SNARKS * DIGEST_LEN);
             (accumulator_instances, snark_inputs)
         };
         end_timer!(timer);
@@ -251,14 +257,10 @@ impl Circuit<Fr> for AggregationCircuit {
             let timer = start_timer!(|| "extract hash");
             // orders:
             // - batch_public_input_hash
-            // - chunk\[i\].piHash for i in \[0, MAX_AGG_SNARKS)
+            // - chunk\[i\].piHash for i in \[0, N_SNARKS)
             // - batch_data_hash_preimage
             let preimages = self.batch_hash.extract_hash_preimages();
-            assert_eq!(
-                preimages.len(),
-                MAX_AGG_SNARKS + 2,
-                "error extracting preimages"
-            );
+            assert_eq!(preimages.len(), N_SNARKS + 2, "error extracting preimages");
             end_timer!(timer);
 
             let timer = start_timer!(|| ("assign hash cells").to_string());
@@ -268,7 +270,7 @@ impl Circuit<Fr> for AggregationCircuit {
                 .iter()
                 .map(|chunk| !chunk.is_padding)
                 .collect::<Vec<_>>();
-            let hash_digest_cells = assign_batch_hashes(
+            let hash_digest_cells = assign_batch_hashes::<N_SNARKS>(
                 &config,
                 &mut layouter,
                 challenges,
@@ -281,7 +283,7 @@ impl Circuit<Fr> for AggregationCircuit {
         };
         // digests
         let (batch_pi_hash_digest, chunk_pi_hash_digests, _potential_batch_data_hash_digest) =
-            parse_hash_digest_cells(&hash_digest_cells);
+            parse_hash_digest_cells::<N_SNARKS>(&hash_digest_cells);
 
         // ==============================================
         // step 3: assert public inputs to the snarks are correct
@@ -313,7 +315,7 @@ impl Circuit<Fr> for AggregationCircuit {
                     return Ok(());
                 }
 
-                for i in 0..MAX_AGG_SNARKS {
+                for i in 0..N_SNARKS {
                     for j in 0..4 {
                         for k in 0..8 {
                             let mut t1 = Fr::default();
@@ -377,7 +379,7 @@ impl Circuit<Fr> for AggregationCircuit {
     }
 }
 
-impl CircuitExt<Fr> for AggregationCircuit {
+impl<const N_SNARKS: usize> CircuitExt<Fr> for AggregationCircuit<N_SNARKS> {
     fn num_instance(&self) -> Vec<usize> {
         // 12 elements from accumulator
         // 32 elements from batch's public_input_hash
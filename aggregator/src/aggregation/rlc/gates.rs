@@ -10,6 +10,9 @@ use crate::{constants::LOG_DEGREE, util::assert_equal};
 
 use super::RlcConfig;
 
+/// The constants assigned by [`RlcConfig::init`] to the first rows of the fixed column.
+const INIT_CONSTANTS: [u64; 9] = [0, 1, 2, 5, 9, 13, 32, 136, 1 << 32];
+
 impl RlcConfig {
     /// initialize the chip with fixed cells
     pub(crate) fn init(&self, region: &mut Region<Fr>) -> Result<(), Error> {
@@ -62,33 +65,6 @@ impl RlcConfig {
         }
     }
 
-    #[inline]
-    pub(crate) fn five_cell(&self, region_index: RegionIndex) -> Cell {
-        Cell {
-            region_index,
-            row_offset: 3,
-            column: self.fixed.into(),
-        }
-    }
-
-    #[inline]
-    pub(crate) fn nine_cell(&self, region_index: RegionIndex) -> Cell {
-        Cell {
-            region_index,
-            row_offset: 4,
-            column: self.fixed.into(),
-        }
-    }
-
-    #[inline]
-    pub(crate) fn thirteen_cell(&self, region_index: RegionIndex) -> Cell {
-        Cell {
-            region_index,
-            row_offset: 5,
-            column: self.fixed.into(),
-        }
-    }
-
     #[inline]
     pub(crate) fn thirty_two_cell(&self, region_index: RegionIndex) -> Cell {
         Cell {
@@ -131,6 +107,34 @@ impl RlcConfig {
         res
     }
 
+    /// Load a constant, constrained by a cell of the fixed column: the one set by `init` if
+    /// any, otherwise a new one fixed in the row of the loaded cell.
+    pub(crate) fn load_constant(
+        &self,
+        region: &mut Region<Fr>,
+        f: &Fr,
+        offset: &mut usize,
+    ) -> Result<AssignedCell<Fr, Fr>, Error> {
+        let row_offset = match INIT_CONSTANTS.iter().position(|c| Fr::from(*c) == *f) {
+            Some(row_offset) => row_offset,
+            None => {
+                // the first rows of the fixed column are reserved for `init`
+                *offset = (*offset).max(INIT_CONSTANTS.len());
+                region.assign_fixed(|| "const", self.fixed, *offset, || Value::known(*f))?;
+                *offset
+            }
+        };
+
+        let res = self.load_private(region, f, offset)?;
+        let fixed_cell = Cell {
+            region_index: res.cell().region_index,
+            row_offset,
+            column: self.fixed.into(),
+        };
+        region.constrain_equal(fixed_cell, res.cell())?;
+        Ok(res)
+    }
+
     pub(crate) fn read_challenge(
         &self,
         region: &mut Region<Fr>,
@@ -4,38 +4,36 @@
 use eth_types::{Field, H256};
 use ethers_core::utils::keccak256;
 
-use crate::constants::MAX_AGG_SNARKS;
+use super::{chunk::ChunkHash, error::AggregatorError};
 
-use super::chunk::ChunkHash;
-
-#[derive(Default, Debug, Clone)]
-/// A batch is a set of MAX_AGG_SNARKS num of continuous chunks
+#[derive(Debug, Clone)]
+/// A batch is a set of N_SNARKS num of continuous chunks
 /// - the first k chunks are from real traces
-/// - the last (#MAX_AGG_SNARKS-k) chunks are from empty traces
+/// - the last (#N_SNARKS-k) chunks are from empty traces
 /// A BatchHash consists of 2 hashes.
 /// - batch_pi_hash   := keccak(chain_id || chunk_0.prev_state_root || chunk_k-1.post_state_root ||
 ///   chunk_k-1.withdraw_root || batch_data_hash)
 /// - batch_data_hash := keccak(chunk_0.data_hash || ... || chunk_k-1.data_hash)
-pub struct BatchHash {
+pub struct BatchHash<const N_SNARKS: usize> {
     pub(crate) chain_id: u64,
     // chunks with padding.
     // - the first [0..number_of_valid_chunks) are real ones
-    // - the last [number_of_valid_chunks, MAX_AGG_SNARKS) are padding
-    pub(crate) chunks_with_padding: [ChunkHash; MAX_AGG_SNARKS],
+    // - the last [number_of_valid_chunks, N_SNARKS) are padding
+    pub(crate) chunks_with_padding: [ChunkHash; N_SNARKS],
     pub(crate) data_hash: H256,
     pub(crate) public_input_hash: H256,
     pub(crate) number_of_valid_chunks: usize,
 }
 
-impl BatchHash {
-    /// Build Batch hash from an ordered list of #MAX_AGG_SNARKS of chunks.
-    #[allow(dead_code)]
-    pub fn construct(chunks_with_padding: &[ChunkHash]) -> Self {
-        assert_eq!(
-            chunks_with_padding.len(),
-            MAX_AGG_SNARKS,
-            "input chunk slice does not match MAX_AGG_SNARKS"
-        );
+impl<const N_SNARKS: usize> BatchHash<N_SNARKS> {
+    /// Build Batch hash from an ordered list of #N_SNARKS of chunks.
+    pub fn construct(chunks_with_padding: &[ChunkHash]) -> Result<Self, AggregatorError> {
+        if chunks_with_padding.len() != N_SNARKS {
+            return Err(AggregatorError::InvalidChunkCount {
+                expected: N_SNARKS,
+                actual: chunks_with_padding.len(),
+            });
+        }
 
         let number_of_valid_chunks = match chunks_with_padding
             .iter()
@@ -43,13 +41,12 @@ impl BatchHash {
             .find(|(_index, chunk)| chunk.is_padding)
         {
             Some((index, _)) => index,
-            None => MAX_AGG_SNARKS,
+            None => N_SNARKS,
         };
 
-        assert_ne!(
-            number_of_valid_chunks, 0,
-            "input chunk slice does not contain real chunks"
-        );
+        if number_of_valid_chunks == 0 {
+            return Err(AggregatorError::NoRealChunk);
+        }
         log::trace!("build a Batch with {number_of_valid_chunks} real chunks");
 
         log::trace!("chunks with padding");
@@ -60,34 +57,31 @@ impl BatchHash {
         // ========================
         // sanity checks
         // ========================
-        // todo: return errors instead
-        for i in 0..MAX_AGG_SNARKS - 1 {
-            assert_eq!(
-                chunks_with_padding[i].chain_id,
-                chunks_with_padding[i + 1].chain_id,
-            );
-            if chunks_with_padding[i + 1].is_padding {
-                assert_eq!(
-                    chunks_with_padding[i + 1].data_hash,
-                    chunks_with_padding[i].data_hash
-                );
-                assert_eq!(
-                    chunks_with_padding[i + 1].prev_state_root,
-                    chunks_with_padding[i].prev_state_root
-                );
-                assert_eq!(
-                    chunks_with_padding[i + 1].post_state_root,
-                    chunks_with_padding[i].post_state_root
-                );
-                assert_eq!(
-                    chunks_with_padding[i + 1].withdraw_root,
-                    chunks_with_padding[i].withdraw_root
-                );
-            } else {
-                assert_eq!(
-                    chunks_with_padding[i].post_state_root,
-                    chunks_with_padding[i + 1].prev_state_root,
-                );
+        for i in 0..N_SNARKS - 1 {
+            let (chunk, next_chunk) = (&chunks_with_padding[i], &chunks_with_padding[i + 1]);
+            if chunk.chain_id != next_chunk.chain_id {
+                return Err(AggregatorError::ChainIdMismatch {
+                    index: i + 1,
+                    expected: chunk.chain_id,
+                    actual: next_chunk.chain_id,
+                });
+            }
+            if next_chunk.is_padding {
+                if next_chunk.data_hash != chunk.data_hash
+                    || next_chunk.prev_state_root != chunk.prev_state_root
+                    || next_chunk.post_state_root != chunk.post_state_root
+                    || next_chunk.withdraw_root != chunk.withdraw_root
+                {
+                    return Err(AggregatorError::InvalidPaddedChunk { index: i + 1 });
+                }
+            } else if chunk.is_padding {
+                return Err(AggregatorError::RealChunkAfterPadding { index: i + 1 });
+            } else if chunk.post_state_root != next_chunk.prev_state_root {
+                return Err(AggregatorError::NonContinuousChunk {
+                    index: i + 1,
+                    expected: chunk.post_state_root,
+                    actual: next_chunk.prev_state_root,
+                });
             }
         }
 
@@ -111,32 +105,28 @@ impl BatchHash {
         let preimage = [
             chunks_with_padding[0].chain_id.to_be_bytes().as_ref(),
             chunks_with_padding[0].prev_state_root.as_bytes(),
-            chunks_with_padding[MAX_AGG_SNARKS - 1]
-                .post_state_root
-                .as_bytes(),
-            chunks_with_padding[MAX_AGG_SNARKS - 1]
-                .withdraw_root
-                .as_bytes(),
+            chunks_with_padding[N_SNARKS - 1].post_state_root.as_bytes(),
+            chunks_with_padding[N_SNARKS - 1].withdraw_root.as_bytes(),
             data_hash.as_slice(),
         ]
         .concat();
         let public_input_hash = keccak256(preimage);
 
-        Self {
+        Ok(Self {
             chain_id: chunks_with_padding[0].chain_id,
             chunks_with_padding: chunks_with_padding.try_into().unwrap(), // safe unwrap
             data_hash: data_hash.into(),
             public_input_hash: public_input_hash.into(),
             number_of_valid_chunks,
-        }
+        })
     }
 
     /// Extract all the hash inputs that will ever be used.
-    /// There are MAX_AGG_SNARKS + 2 hashes.
+    /// There are N_SNARKS + 2 hashes.
     ///
     /// orders:
     /// - batch_public_input_hash
-    /// - chunk\[i\].piHash for i in \[0, MAX_AGG_SNARKS)
+    /// - chunk\[i\].piHash for i in \[0, N_SNARKS)
     /// - batch_data_hash_preimage
     pub(crate) fn extract_hash_preimages(&self) -> Vec<Vec<u8>> {
         let mut res = vec![];
@@ -151,10 +141,10 @@ impl BatchHash {
         let batch_public_input_hash_preimage = [
             self.chain_id.to_be_bytes().as_ref(),
             self.chunks_with_padding[0].prev_state_root.as_bytes(),
-            self.chunks_with_padding[MAX_AGG_SNARKS - 1]
+            self.chunks_with_padding[N_SNARKS - 1]
                 .post_state_root
                 .as_bytes(),
-            self.chunks_with_padding[MAX_AGG_SNARKS - 1]
+            self.chunks_with_padding[N_SNARKS - 1]
                 .withdraw_root
                 .as_bytes(),
             self.data_hash.as_bytes(),
@@ -162,7 +152,7 @@ impl BatchHash {
         .concat();
         res.push(batch_public_input_hash_preimage);
 
-        // compute piHash for each chunk for i in [0..MAX_AGG_SNARKS)
+        // compute piHash for each chunk for i in [0..N_SNARKS)
         // chunk[i].piHash =
         // keccak(
         //        chain id ||
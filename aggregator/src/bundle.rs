@@ -0,0 +1,220 @@
+//! This module implements `Bundle` related data types and the bundle circuit.
+//! A bundle is a list of continuous batches, whose proofs are aggregated into a single one so
+//! that the verification cost on L1 no longer grows with the number of batches.
+
+use eth_types::H256;
+use ethers_core::utils::keccak256;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    halo2curves::bn256::{Bn256, Fr},
+    plonk::{Circuit, ConstraintSystem, Error, Selector},
+    poly::kzg::commitment::ParamsKZG,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use snark_verifier_sdk::{CircuitExt, Snark};
+use std::iter::repeat;
+
+use crate::{AggregationCircuit, AggregatorError, BatchHash, ChunkHash};
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+/// The public inputs of a batch, i.e. the preimage of the public input hash of a batch proof
+/// - batch_pi_hash := keccak(chain_id || prev_state_root || post_state_root || withdraw_root ||
+///   data_hash)
+pub struct BatchInfo {
+    /// Chain identifier
+    pub chain_id: u64,
+    /// state root before this batch
+    pub prev_state_root: H256,
+    /// state root after this batch
+    pub post_state_root: H256,
+    /// the withdraw root after this batch
+    pub withdraw_root: H256,
+    /// the data hash of this batch, keccak(chunk_0.data_hash || ... || chunk_k-1.data_hash)
+    pub data_hash: H256,
+}
+
+impl BatchInfo {
+    /// Build from the real chunks of a batch, in order.
+    pub fn from_chunk_hashes(chunk_hashes: &[ChunkHash]) -> Result<Self, AggregatorError> {
+        let (first_chunk, last_chunk) = match (chunk_hashes.first(), chunk_hashes.last()) {
+            (Some(first_chunk), Some(last_chunk)) => (first_chunk, last_chunk),
+            _ => return Err(AggregatorError::NoRealChunk),
+        };
+
+        // batch's data hash is build as
+        //  keccak( chunk[0].data_hash || ... || chunk[k-1].data_hash)
+        let preimage = chunk_hashes
+            .iter()
+            .flat_map(|chunk_hash| chunk_hash.data_hash.0.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            chain_id: first_chunk.chain_id,
+            prev_state_root: first_chunk.prev_state_root,
+            post_state_root: last_chunk.post_state_root,
+            withdraw_root: last_chunk.withdraw_root,
+            data_hash: keccak256(preimage).into(),
+        })
+    }
+
+    /// Public input hash of the batch proof.
+    pub fn public_input_hash(&self) -> H256 {
+        self.as_chunk_hash(false).public_input_hash()
+    }
+
+    /// A batch's public input preimage has the same layout as a chunk's, so the batches are
+    /// aggregated in a bundle the same way as the chunks in a batch.
+    pub(crate) fn as_chunk_hash(&self, is_padding: bool) -> ChunkHash {
+        ChunkHash {
+            chain_id: self.chain_id,
+            prev_state_root: self.prev_state_root,
+            post_state_root: self.post_state_root,
+            withdraw_root: self.withdraw_root,
+            data_hash: self.data_hash,
+            is_padding,
+        }
+    }
+}
+
+impl<const N_SNARKS: usize> From<&BatchHash<N_SNARKS>> for BatchInfo {
+    fn from(batch_hash: &BatchHash<N_SNARKS>) -> Self {
+        let first_chunk = &batch_hash.chunks_with_padding[0];
+        let last_chunk = &batch_hash.chunks_with_padding[N_SNARKS - 1];
+
+        Self {
+            chain_id: batch_hash.chain_id,
+            prev_state_root: first_chunk.prev_state_root,
+            post_state_root: last_chunk.post_state_root,
+            withdraw_root: last_chunk.withdraw_root,
+            data_hash: batch_hash.data_hash,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A bundle is a set of N_BATCHES num of continuous batches
+/// - the first k batches are real ones
+/// - the last (#N_BATCHES-k) batches are padded with the last real one
+/// A BundleHash consists of 2 hashes.
+/// - bundle_pi_hash   := keccak(chain_id || batch_0.prev_state_root || batch_k-1.post_state_root ||
+///   batch_k-1.withdraw_root || bundle_data_hash)
+/// - bundle_data_hash := keccak(batch_0.data_hash || ... || batch_k-1.data_hash)
+///
+/// The hashes are built as the ones of a [`BatchHash`] whose chunks are the batches.
+pub struct BundleHash<const N_BATCHES: usize> {
+    pub(crate) batch_hash: BatchHash<N_BATCHES>,
+}
+
+impl<const N_BATCHES: usize> BundleHash<N_BATCHES> {
+    /// Build Bundle hash from an ordered list of 1 to N_BATCHES continuous batches.
+    pub fn construct(batches: &[BatchInfo]) -> Result<Self, AggregatorError> {
+        if !(1..=N_BATCHES).contains(&batches.len()) {
+            return Err(AggregatorError::InvalidChunkCount {
+                expected: N_BATCHES,
+                actual: batches.len(),
+            });
+        }
+
+        let padding_batch = batches.last().unwrap().as_chunk_hash(true);
+        let batches_with_padding = batches
+            .iter()
+            .map(|batch| batch.as_chunk_hash(false))
+            .chain(repeat(padding_batch))
+            .take(N_BATCHES)
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            batch_hash: BatchHash::construct(&batches_with_padding)?,
+        })
+    }
+
+    /// Number of the real batches in this bundle.
+    pub fn number_of_valid_batches(&self) -> usize {
+        self.batch_hash.number_of_valid_chunks
+    }
+
+    /// Public input hash of the bundle proof.
+    pub fn public_input_hash(&self) -> H256 {
+        self.batch_hash.public_input_hash
+    }
+}
+
+/// Bundle circuit that aggregates up to N_BATCHES batch snarks (layer-4), and exposes the
+/// accumulator and the bundle's public_input_hash.
+///
+/// It is an [`AggregationCircuit`] over the batches, so it constrains that
+/// - each snark's public input hash is built from its batch's public inputs
+/// - all batches use a same chain id
+/// - each batch's prev_state_root is the previous batch's post_state_root
+///
+/// It's configured by the `AGGREGATION_CONFIG` env var as well.
+#[derive(Clone)]
+pub struct BundleCircuit<const N_BATCHES: usize>(AggregationCircuit<N_BATCHES>);
+
+impl<const N_BATCHES: usize> BundleCircuit<N_BATCHES> {
+    /// The batch snarks are the real ones in order, they are padded to N_BATCHES here.
+    pub fn new(
+        params: &ParamsKZG<Bn256>,
+        batch_snarks: &[Snark],
+        rng: impl Rng + Send,
+        bundle_hash: BundleHash<N_BATCHES>,
+    ) -> Result<Self, snark_verifier::Error> {
+        assert_eq!(
+            batch_snarks.len(),
+            bundle_hash.number_of_valid_batches(),
+            "input snarks do not match the batches of bundle"
+        );
+
+        let padding_snark = batch_snarks.last().unwrap().clone();
+        let snarks_with_padding = batch_snarks
+            .iter()
+            .cloned()
+            .chain(repeat(padding_snark))
+            .take(N_BATCHES)
+            .collect::<Vec<_>>();
+
+        AggregationCircuit::new(params, &snarks_with_padding, rng, bundle_hash.batch_hash).map(Self)
+    }
+
+    pub fn as_proof(&self) -> Value<&[u8]> {
+        self.0.as_proof()
+    }
+}
+
+impl<const N_BATCHES: usize> Circuit<Fr> for BundleCircuit<N_BATCHES> {
+    type Config = <AggregationCircuit<N_BATCHES> as Circuit<Fr>>::Config;
+    type FloorPlanner = SimpleFloorPlanner;
+    fn without_witnesses(&self) -> Self {
+        Self(self.0.without_witnesses())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        AggregationCircuit::<N_BATCHES>::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<Fr>) -> Result<(), Error> {
+        self.0.synthesize(config, layouter)
+    }
+}
+
+impl<const N_BATCHES: usize> CircuitExt<Fr> for BundleCircuit<N_BATCHES> {
+    fn num_instance(&self) -> Vec<usize> {
+        self.0.num_instance()
+    }
+
+    // 12 elements from accumulator
+    // 32 elements from bundle's public_input_hash
+    fn instances(&self) -> Vec<Vec<Fr>> {
+        self.0.instances()
+    }
+
+    fn accumulator_indices() -> Option<Vec<(usize, usize)>> {
+        AggregationCircuit::<N_BATCHES>::accumulator_indices()
+    }
+
+    fn selectors(config: &Self::Config) -> Vec<Selector> {
+        AggregationCircuit::<N_BATCHES>::selectors(config)
+    }
+}
@@ -44,8 +44,13 @@ pub(crate) const LIMBS: usize = 3;
 /// number of bits in each limb in the ECC chip
 pub(crate) const BITS: usize = 88;
 
-/// Max number of snarks to be aggregated in a chunk.
-/// If the input size is less than this, dummy snarks
+/// Default max number of snarks to be aggregated in a batch, i.e. the N_SNARKS of the
+/// deployed [`AggregationCircuit`](crate::AggregationCircuit) and
+/// [`BatchHash`](crate::BatchHash). If the input size is less than this, dummy snarks
 /// will be padded.
-// TODO: update me(?)
 pub const MAX_AGG_SNARKS: usize = 15;
+
+/// Default max number of batches to be aggregated in a bundle, i.e. the N_BATCHES of the
+/// deployed [`BundleCircuit`](crate::BundleCircuit). If there are less batches, the last one
+/// will be padded.
+pub const MAX_BUNDLE_BATCHES: usize = 15;
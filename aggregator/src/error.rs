@@ -0,0 +1,99 @@
+//! Error module for the aggregator crate
+
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use eth_types::H256;
+use std::error::Error as StdError;
+
+/// Error type for the construction of chunk, batch and bundle hashes from untrusted inputs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AggregatorError {
+    /// The number of chunks (or batches) doesn't match the capacity of the circuit.
+    InvalidChunkCount {
+        /// Expected number, or the max number when padded by the callee
+        expected: usize,
+        /// Number of the input chunks
+        actual: usize,
+    },
+    /// The first chunk is a padded one.
+    NoRealChunk,
+    /// The chunk has a different chain id from the previous one.
+    ChainIdMismatch {
+        /// Index of the chunk
+        index: usize,
+        /// Chain id of the previous chunk
+        expected: u64,
+        /// Chain id of the chunk
+        actual: u64,
+    },
+    /// The chunk's prev_state_root is not the previous chunk's post_state_root.
+    NonContinuousChunk {
+        /// Index of the chunk
+        index: usize,
+        /// post_state_root of the previous chunk
+        expected: H256,
+        /// prev_state_root of the chunk
+        actual: H256,
+    },
+    /// The padded chunk is not a copy of the previous chunk.
+    InvalidPaddedChunk {
+        /// Index of the chunk
+        index: usize,
+    },
+    /// A real chunk follows a padded chunk.
+    RealChunkAfterPadding {
+        /// Index of the chunk
+        index: usize,
+    },
+    /// The L1 message of a block has a queue index before the already popped ones.
+    InvalidL1QueueIndex {
+        /// Number of the block
+        block_number: u64,
+        /// Max queue index of the L1 messages in the block
+        queue_index: u64,
+        /// Number of the L1 messages popped before the block
+        total_l1_popped: u64,
+    },
+}
+
+impl Display for AggregatorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::InvalidChunkCount { expected, actual } => {
+                write!(f, "invalid number of chunks: expected {expected}, got {actual}")
+            }
+            Self::NoRealChunk => write!(f, "no real chunk"),
+            Self::ChainIdMismatch {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "chunk {index} has chain id {actual}, the previous one has {expected}"
+            ),
+            Self::NonContinuousChunk {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "chunk {index} is not continuous: prev_state_root {actual:?} != previous post_state_root {expected:?}"
+            ),
+            Self::InvalidPaddedChunk { index } => {
+                write!(f, "padded chunk {index} differs from the previous chunk")
+            }
+            Self::RealChunkAfterPadding { index } => {
+                write!(f, "real chunk {index} follows a padded chunk")
+            }
+            Self::InvalidL1QueueIndex {
+                block_number,
+                queue_index,
+                total_l1_popped,
+            } => write!(
+                f,
+                "block {block_number} has L1 queue index {queue_index}, but {total_l1_popped} are already popped"
+            ),
+        }
+    }
+}
+
+impl StdError for AggregatorError {}
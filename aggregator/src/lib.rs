@@ -3,6 +3,9 @@ mod aggregation;
 /// This module implements `Batch` related data types.
 /// A batch is a list of chunk.
 mod batch;
+/// This module implements `Bundle` related data types and the bundle circuit.
+/// A bundle is a list of batches.
+mod bundle;
 // This module implements `Chunk` related data types.
 // A chunk is a list of blocks.
 mod chunk;
@@ -12,6 +15,8 @@ mod compression;
 mod constants;
 /// Core module for circuit assignment
 mod core;
+/// Errors of the hash constructions
+mod error;
 /// Parameters for compression circuit
 mod param;
 /// utilities
@@ -23,8 +28,10 @@ mod tests;
 pub use self::core::extract_proof_and_instances_with_pairing_check;
 pub use aggregation::*;
 pub use batch::BatchHash;
+pub use bundle::{BatchInfo, BundleCircuit, BundleHash};
 pub use chunk::ChunkHash;
 pub use compression::*;
-pub use constants::MAX_AGG_SNARKS;
 pub(crate) use constants::*;
+pub use constants::{MAX_AGG_SNARKS, MAX_BUNDLE_BATCHES};
+pub use error::AggregatorError;
 pub use param::*;
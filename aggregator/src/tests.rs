@@ -1,4 +1,5 @@
 mod aggregation;
+mod bundle;
 mod compression;
 mod mock_chunk;
 mod rlc;
@@ -8,7 +8,7 @@ use snark_verifier_sdk::{gen_pk, gen_snark_shplonk, verify_snark_shplonk, Circui
 
 use crate::{
     aggregation::AggregationCircuit, batch::BatchHash, constants::MAX_AGG_SNARKS, layer_0,
-    tests::mock_chunk::MockChunkCircuit, ChunkHash,
+    tests::mock_chunk::MockChunkCircuit, AggregatorError, ChunkHash,
 };
 
 #[test]
@@ -18,12 +18,83 @@ fn test_aggregation_circuit() {
     let k = 20;
 
     // This set up requires one round of keccak for chunk's data hash
-    let circuit = build_new_aggregation_circuit(2);
+    let circuit = build_new_aggregation_circuit::<MAX_AGG_SNARKS>(2);
     let instance = circuit.instances();
     let mock_prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
     mock_prover.assert_satisfied_par();
 }
 
+#[test]
+fn test_aggregation_circuit_smaller_batch() {
+    let k = 20;
+
+    // A batch of 5 chunks requires up to two rounds of keccak for chunk's data hash
+    for num_real_chunks in [4, 5] {
+        let circuit = build_new_aggregation_circuit::<5>(num_real_chunks);
+        let instance = circuit.instances();
+        let mock_prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
+        mock_prover.assert_satisfied_par();
+    }
+}
+
+#[test]
+fn test_batch_hash_invalid_chunks() {
+    let mut rng = test_rng();
+
+    let mut chunks = (0..3)
+        .map(|_| ChunkHash::mock_random_chunk_hash_for_testing(&mut rng))
+        .collect_vec();
+    for i in 0..2 {
+        chunks[i + 1].prev_state_root = chunks[i].post_state_root;
+    }
+    assert!(BatchHash::<3>::construct(&chunks).is_ok());
+
+    assert_eq!(
+        BatchHash::<4>::construct(&chunks).unwrap_err(),
+        AggregatorError::InvalidChunkCount {
+            expected: 4,
+            actual: 3
+        }
+    );
+
+    let mut non_continuous = chunks.clone();
+    non_continuous[2].prev_state_root = non_continuous[0].prev_state_root;
+    assert_eq!(
+        BatchHash::<3>::construct(&non_continuous).unwrap_err(),
+        AggregatorError::NonContinuousChunk {
+            index: 2,
+            expected: non_continuous[1].post_state_root,
+            actual: non_continuous[0].prev_state_root,
+        }
+    );
+
+    let mut other_chain = chunks.clone();
+    other_chain[1].chain_id = 1;
+    assert_eq!(
+        BatchHash::<3>::construct(&other_chain).unwrap_err(),
+        AggregatorError::ChainIdMismatch {
+            index: 1,
+            expected: 0,
+            actual: 1
+        }
+    );
+
+    let mut bad_padding = chunks.clone();
+    bad_padding[2] = ChunkHash::mock_padded_chunk_hash_for_testing(&chunks[1]);
+    bad_padding[2].data_hash = chunks[0].data_hash;
+    assert_eq!(
+        BatchHash::<3>::construct(&bad_padding).unwrap_err(),
+        AggregatorError::InvalidPaddedChunk { index: 2 }
+    );
+
+    let mut all_padding = chunks;
+    all_padding[0].is_padding = true;
+    assert_eq!(
+        BatchHash::<3>::construct(&all_padding).unwrap_err(),
+        AggregatorError::NoRealChunk
+    );
+}
+
 #[ignore = "it takes too much time"]
 #[test]
 fn test_aggregation_circuit_all_possible_num_snarks() {
@@ -34,7 +105,7 @@ fn test_aggregation_circuit_all_possible_num_snarks() {
     for i in 1..=MAX_AGG_SNARKS {
         println!("{i} real chunks and {} padded chunks", MAX_AGG_SNARKS - i);
         // This set up requires one round of keccak for chunk's data hash
-        let circuit = build_new_aggregation_circuit(i);
+        let circuit = build_new_aggregation_circuit::<MAX_AGG_SNARKS>(i);
         let instance = circuit.instances();
         let mock_prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
         mock_prover.assert_satisfied_par();
@@ -54,7 +125,7 @@ fn test_aggregation_circuit_full() {
     fs::create_dir(path).unwrap();
 
     // This set up requires one round of keccak for chunk's data hash
-    let circuit = build_new_aggregation_circuit(2);
+    let circuit = build_new_aggregation_circuit::<MAX_AGG_SNARKS>(2);
     let instance = circuit.instances();
     let mock_prover = MockProver::<Fr>::run(25, &circuit, instance).unwrap();
     mock_prover.assert_satisfied_par();
@@ -70,7 +141,7 @@ fn test_aggregation_circuit_full() {
     let snark = gen_snark_shplonk(&param, &pk, circuit.clone(), &mut rng, None::<String>);
     log::trace!("finished snark generation for circuit");
 
-    assert!(verify_snark_shplonk::<AggregationCircuit>(
+    assert!(verify_snark_shplonk::<AggregationCircuit<MAX_AGG_SNARKS>>(
         &param,
         snark,
         pk.get_vk()
@@ -78,11 +149,11 @@ fn test_aggregation_circuit_full() {
     log::trace!("finished verification for circuit");
 
     // This set up requires two rounds of keccak for chunk's data hash
-    let circuit = build_new_aggregation_circuit(5);
+    let circuit = build_new_aggregation_circuit::<MAX_AGG_SNARKS>(5);
     let snark = gen_snark_shplonk(&param, &pk, circuit, &mut rng, None::<String>);
     log::trace!("finished snark generation for circuit");
 
-    assert!(verify_snark_shplonk::<AggregationCircuit>(
+    assert!(verify_snark_shplonk::<AggregationCircuit<MAX_AGG_SNARKS>>(
         &param,
         snark,
         pk.get_vk()
@@ -90,7 +161,9 @@ fn test_aggregation_circuit_full() {
     log::trace!("finished verification for circuit");
 }
 
-fn build_new_aggregation_circuit(num_real_chunks: usize) -> AggregationCircuit {
+fn build_new_aggregation_circuit<const N_SNARKS: usize>(
+    num_real_chunks: usize,
+) -> AggregationCircuit<N_SNARKS> {
     // inner circuit: Mock circuit
     let k0 = 8;
 
@@ -107,7 +180,7 @@ fn build_new_aggregation_circuit(num_real_chunks: usize) -> AggregationCircuit {
         ChunkHash::mock_padded_chunk_hash_for_testing(&chunks_without_padding[num_real_chunks - 1]);
     let chunks_with_padding = [
         chunks_without_padding,
-        vec![padded_chunk; MAX_AGG_SNARKS - num_real_chunks],
+        vec![padded_chunk; N_SNARKS - num_real_chunks],
     ]
     .concat();
 
@@ -129,13 +202,12 @@ fn build_new_aggregation_circuit(num_real_chunks: usize) -> AggregationCircuit {
     // ==========================
     // padded chunks
     // ==========================
-    let padded_snarks =
-        { vec![real_snarks.last().unwrap().clone(); MAX_AGG_SNARKS - num_real_chunks] };
+    let padded_snarks = { vec![real_snarks.last().unwrap().clone(); N_SNARKS - num_real_chunks] };
 
     // ==========================
     // batch
     // ==========================
-    let batch_hash = BatchHash::construct(&chunks_with_padding);
+    let batch_hash = BatchHash::construct(&chunks_with_padding).unwrap();
 
     AggregationCircuit::new(
         &params,
@@ -0,0 +1,113 @@
+use ark_std::{end_timer, start_timer, test_rng};
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr, poly::commitment::Params};
+use itertools::Itertools;
+use snark_verifier::loader::halo2::halo2_ecc::halo2_base::utils::fs::gen_srs;
+use snark_verifier_sdk::{gen_pk, gen_snark_shplonk, verify_snark_shplonk, CircuitExt};
+
+use crate::{
+    batch::BatchHash, layer_0, tests::mock_chunk::MockChunkCircuit, AggregatorError, BatchInfo,
+    BundleCircuit, BundleHash, ChunkHash,
+};
+
+#[test]
+fn test_batch_info_public_input_hash() {
+    let mut rng = test_rng();
+
+    let chunk = ChunkHash::mock_random_chunk_hash_for_testing(&mut rng);
+    let padded_chunk = ChunkHash::mock_padded_chunk_hash_for_testing(&chunk);
+    let batch_hash = BatchHash::<3>::construct(&[chunk, padded_chunk, padded_chunk]).unwrap();
+
+    let batch = BatchInfo::from(&batch_hash);
+    assert_eq!(batch.prev_state_root, chunk.prev_state_root);
+    assert_eq!(batch.post_state_root, chunk.post_state_root);
+    assert_eq!(batch.public_input_hash(), batch_hash.public_input_hash);
+    assert_eq!(BatchInfo::from_chunk_hashes(&[chunk]).unwrap(), batch);
+}
+
+#[test]
+fn test_bundle_hash() {
+    let batches = mock_batches(3);
+
+    let bundle_hash = BundleHash::<5>::construct(&batches).unwrap();
+    assert_eq!(bundle_hash.number_of_valid_batches(), 3);
+
+    // A bundle of all the batches is the batch of all the chunks.
+    let chunks = batches
+        .iter()
+        .map(|batch| batch.as_chunk_hash(false))
+        .collect_vec();
+    let batch_hash = BatchHash::<3>::construct(&chunks).unwrap();
+    assert_eq!(
+        bundle_hash.public_input_hash(),
+        batch_hash.public_input_hash
+    );
+}
+
+#[test]
+fn test_bundle_hash_non_continuous() {
+    let mut batches = mock_batches(2);
+    batches[1].prev_state_root = batches[0].prev_state_root;
+
+    assert_eq!(
+        BundleHash::<5>::construct(&batches).unwrap_err(),
+        AggregatorError::NonContinuousChunk {
+            index: 1,
+            expected: batches[0].post_state_root,
+            actual: batches[0].prev_state_root,
+        }
+    );
+}
+
+#[test]
+fn test_bundle_circuit() {
+    let k = 20;
+
+    // This set up requires one round of keccak for bundle's data hash
+    let circuit = build_new_bundle_circuit::<5>(3);
+    let instance = circuit.instances();
+    let mock_prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
+    mock_prover.assert_satisfied_par();
+}
+
+fn mock_batches(num_batches: usize) -> Vec<BatchInfo> {
+    let mut rng = test_rng();
+
+    let mut batches = (0..num_batches)
+        .map(|_| {
+            let chunk = ChunkHash::mock_random_chunk_hash_for_testing(&mut rng);
+            BatchInfo {
+                chain_id: chunk.chain_id,
+                prev_state_root: chunk.prev_state_root,
+                post_state_root: chunk.post_state_root,
+                withdraw_root: chunk.withdraw_root,
+                data_hash: chunk.data_hash,
+            }
+        })
+        .collect_vec();
+    for i in 0..num_batches - 1 {
+        batches[i + 1].prev_state_root = batches[i].post_state_root;
+    }
+
+    batches
+}
+
+fn build_new_bundle_circuit<const N_BATCHES: usize>(
+    num_batches: usize,
+) -> BundleCircuit<N_BATCHES> {
+    // batch circuit: Mock circuit exposing the batch's public input hash
+    let k0 = 8;
+
+    let rng = test_rng();
+    let params = gen_srs(k0);
+
+    let batches = mock_batches(num_batches);
+    let batch_snarks = batches
+        .iter()
+        .map(|batch| MockChunkCircuit::new(true, batch.as_chunk_hash(false)))
+        .map(|circuit| layer_0!(circuit, MockChunkCircuit, params, k0, path))
+        .collect_vec();
+
+    let bundle_hash = BundleHash::construct(&batches).unwrap();
+
+    BundleCircuit::new(&params, &batch_snarks, rng, bundle_hash).unwrap()
+}
@@ -1,4 +1,4 @@
-use crate::constants::{DIGEST_LEN, INPUT_LEN_PER_ROUND, MAX_AGG_SNARKS};
+use crate::constants::{DIGEST_LEN, INPUT_LEN_PER_ROUND};
 use eth_types::Field;
 use halo2_proofs::{circuit::AssignedCell, halo2curves::bn256::Fr, plonk::Error};
 use itertools::Itertools;
@@ -35,10 +35,18 @@ pub(crate) fn get_data_hash_keccak_updates(max_snarks: usize) -> usize {
     data_hash_rounds + padding_round
 }
 
+/// The min number of valid snarks, for which the batch's data hash needs more than `rounds`
+/// keccak updates.
+pub(crate) fn get_data_hash_round_threshold(rounds: usize) -> usize {
+    (INPUT_LEN_PER_ROUND * rounds).div_ceil(DIGEST_LEN)
+}
+
 /// Return
 /// - the indices of the rows that contain the input preimages
 /// - the indices of the rows that contain the output digest
-pub(crate) fn get_indices(preimages: &[Vec<u8>]) -> (Vec<usize>, Vec<usize>) {
+pub(crate) fn get_indices<const N_SNARKS: usize>(
+    preimages: &[Vec<u8>],
+) -> (Vec<usize>, Vec<usize>) {
     let mut preimage_indices = vec![];
     let mut digest_indices = vec![];
     let mut round_ctr = 0;
@@ -46,7 +54,7 @@ pub(crate) fn get_indices(preimages: &[Vec<u8>]) -> (Vec<usize>, Vec<usize>) {
     let keccak_f_rows = get_num_rows_per_update();
     let inner_round_rows = get_num_rows_per_round();
 
-    for preimage in preimages.iter().take(MAX_AGG_SNARKS + 1) {
+    for preimage in preimages.iter().take(N_SNARKS + 1) {
         //  136 = 17 * 8 is the size in bytes of each
         //  input chunk that can be processed by Keccak circuit using absorb
 
@@ -82,7 +90,7 @@ pub(crate) fn get_indices(preimages: &[Vec<u8>]) -> (Vec<usize>, Vec<usize>) {
         }
     }
     // last hash is for data_hash and has various length, so we output all the possible cells
-    for _i in 0..get_data_hash_keccak_updates(MAX_AGG_SNARKS) {
+    for _i in 0..get_data_hash_keccak_updates(N_SNARKS) {
         for (j, _) in (0..INPUT_LEN_PER_ROUND).chunks(8).into_iter().enumerate() {
             let inner_offset = round_ctr * keccak_f_rows + (j + 1) * inner_round_rows;
             for k in 0..8 {
@@ -170,7 +178,7 @@ fn is_ascending(a: &[usize]) -> bool {
 
 #[inline]
 #[allow(clippy::type_complexity)]
-pub(crate) fn parse_hash_preimage_cells(
+pub(crate) fn parse_hash_preimage_cells<const N_SNARKS: usize>(
     hash_input_cells: &[AssignedCell<Fr, Fr>],
 ) -> (
     &[AssignedCell<Fr, Fr>],
@@ -182,13 +190,13 @@ pub(crate) fn parse_hash_preimage_cells(
     // we extract all those bytes
     let batch_pi_hash_preimage = &hash_input_cells[0..INPUT_LEN_PER_ROUND * 2];
     let mut chunk_pi_hash_preimages = vec![];
-    for i in 0..MAX_AGG_SNARKS {
+    for i in 0..N_SNARKS {
         chunk_pi_hash_preimages.push(
             &hash_input_cells[INPUT_LEN_PER_ROUND * 2 * (i + 1)..INPUT_LEN_PER_ROUND * 2 * (i + 2)],
         );
     }
     let potential_batch_data_hash_preimage =
-        &hash_input_cells[INPUT_LEN_PER_ROUND * 2 * (MAX_AGG_SNARKS + 1)..];
+        &hash_input_cells[INPUT_LEN_PER_ROUND * 2 * (N_SNARKS + 1)..];
 
     (
         batch_pi_hash_preimage,
@@ -199,7 +207,7 @@ pub(crate) fn parse_hash_preimage_cells(
 
 #[inline]
 #[allow(clippy::type_complexity)]
-pub(crate) fn parse_hash_digest_cells(
+pub(crate) fn parse_hash_digest_cells<const N_SNARKS: usize>(
     hash_output_cells: &[AssignedCell<Fr, Fr>],
 ) -> (
     &[AssignedCell<Fr, Fr>],
@@ -208,10 +216,10 @@ pub(crate) fn parse_hash_digest_cells(
 ) {
     let batch_pi_hash_digest = &hash_output_cells[0..DIGEST_LEN];
     let mut chunk_pi_hash_digests = vec![];
-    for i in 0..MAX_AGG_SNARKS {
+    for i in 0..N_SNARKS {
         chunk_pi_hash_digests.push(&hash_output_cells[DIGEST_LEN * (i + 1)..DIGEST_LEN * (i + 2)]);
     }
-    let potential_batch_data_hash_digest = &hash_output_cells[DIGEST_LEN * (MAX_AGG_SNARKS + 1)..];
+    let potential_batch_data_hash_digest = &hash_output_cells[DIGEST_LEN * (N_SNARKS + 1)..];
     (
         batch_pi_hash_digest,
         chunk_pi_hash_digests,
@@ -220,13 +228,13 @@ pub(crate) fn parse_hash_digest_cells(
 }
 
 #[inline]
-pub(crate) fn parse_pi_hash_rlc_cells(
+pub(crate) fn parse_pi_hash_rlc_cells<const N_SNARKS: usize>(
     data_rlc_cells: &[AssignedCell<Fr, Fr>],
 ) -> Vec<&AssignedCell<Fr, Fr>> {
     data_rlc_cells
         .iter()
         .skip(3) // the first 3 rlc cells are pad (1) + batch pi hash (2)
-        .take(MAX_AGG_SNARKS * 2) // each chunk hash takes 2 rounds
+        .take(N_SNARKS * 2) // each chunk hash takes 2 rounds
         .chunks(2)
         .into_iter()
         .map(|t| t.last().unwrap())
@@ -8,6 +8,7 @@ mod execution;
 mod input_state_ref;
 #[cfg(feature = "scroll")]
 mod l2;
+mod receipt;
 #[cfg(all(feature = "tracer-tests", feature = "enable-memory", test))]
 mod tracer_tests;
 mod transaction;
@@ -34,9 +35,10 @@ use eth_types::{
 };
 use ethers_providers::JsonRpcClient;
 pub use execution::{
-    BigModExp, CopyAccessList, CopyBytes, CopyDataType, CopyEvent, CopyEventStepsBuilder, CopyStep,
-    EcAddOp, EcMulOp, EcPairingOp, EcPairingPair, ExecState, ExecStep, ExpEvent, ExpStep,
-    NumberOrHash, PrecompileEvent, PrecompileEvents, N_BYTES_PER_PAIR, N_PAIRING_PER_OP, SHA256,
+    BigModExp, Blake2fOp, CopyAccessList, CopyBytes, CopyDataType, CopyEvent,
+    CopyEventStepsBuilder, CopyStep, EcAddOp, EcMulOp, EcPairingOp, EcPairingPair, ExecState,
+    ExecStep, ExpEvent, ExpStep, NumberOrHash, PrecompileEvent, PrecompileEvents,
+    BLAKE2F_INPUT_LEN, N_BYTES_PER_PAIR, N_PAIRING_PER_OP, RIPEMD160, SHA256,
 };
 use hex::decode_to_slice;
 
@@ -47,6 +49,11 @@ use itertools::Itertools;
 use log::warn;
 #[cfg(feature = "scroll")]
 use mpt_zktrie::state::ZktrieState;
+pub use receipt::{
+    bloom_indices, keccak_inputs_receipts, logs_bloom, receipt_key, receipts_data_bytes, Receipt,
+    ReceiptLog, ReceiptsTrie, N_BLOOM_BITS_PER_ITEM, N_BYTES_RECEIPTS_DATA,
+};
+use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashMap},
     iter,
@@ -59,7 +66,7 @@ pub use transaction::{
 use eth_types::evm_types::OpcodeId;
 
 /// Setup parameters for ECC-related precompile calls.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct PrecompileEcParams {
     /// Maximum number of EcAdd ops supported in one block.
     pub ec_add: usize,
@@ -80,7 +87,7 @@ impl Default for PrecompileEcParams {
 }
 
 /// Circuit Setup Parameters
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct CircuitsParams {
     /// Maximum number of rw operations in the state circuit (RwTable length /
     /// number of rows). This must be at least the number of rw operations
@@ -454,6 +461,18 @@ impl<'a> CircuitInputBuilder {
         );
         log::debug!("tx_receipt num: {}", self.block.container.tx_receipt.len());
         log::debug!("tx_log num: {}", self.block.container.tx_log.len());
+        log::debug!(
+            "transient_storage num: {}",
+            self.block.container.transient_storage.len()
+        );
+        log::debug!(
+            "tx_created_account num: {}",
+            self.block.container.tx_created_account.len()
+        );
+        log::debug!(
+            "tx_destructed_account num: {}",
+            self.block.container.tx_destructed_account.len()
+        );
         log::debug!("start num: {}", self.block.container.start.len());
     }
 
@@ -656,7 +675,7 @@ impl<'a> CircuitInputBuilder {
                                 "".to_string()
                             }
                         )
-                    } else if matches!(geth_step.op, OpcodeId::SSTORE) {
+                    } else if matches!(geth_step.op, OpcodeId::SSTORE | OpcodeId::TSTORE) {
                         format!(
                             "{:?} {:?} {:?}",
                             state_ref.call().map(|c| c.address),
@@ -694,6 +713,13 @@ impl<'a> CircuitInputBuilder {
             gen_associated_steps(&mut self.state_ref(&mut tx, &mut tx_ctx), ExecState::EndTx)?;
         tx.steps_mut().extend(end_tx_steps);
 
+        // Generate DestructAccount steps for the accounts self destructed in the tx
+        let destruct_account_steps = gen_associated_steps(
+            &mut self.state_ref(&mut tx, &mut tx_ctx),
+            ExecState::DestructAccount,
+        )?;
+        tx.steps_mut().extend(destruct_account_steps);
+
         self.sdb.commit_tx();
         self.block.txs.push(tx);
         log::trace!("handle_tx finished");
@@ -719,59 +745,142 @@ impl CircuitInputBuilder {
     }
 }
 
+/// Source of the keccak inputs of a block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KeccakSource {
+    /// Tx hashes and signed messages of the Tx circuit
+    Txs,
+    /// Messages and public keys of the ecrecover calls
+    Ecrecover,
+    /// Data and public input bytes of the PI circuit
+    Pi,
+    /// Trie nodes and bloom items of the receipts
+    Receipts,
+    /// Inputs hashed by the opcodes, e.g. SHA3 and CREATE2
+    Opcodes,
+}
+
 /// Return all the keccak inputs used during the processing of the current
 /// block.
 pub fn keccak_inputs(block: &Block, code_db: &CodeDB) -> Result<Vec<Vec<u8>>, Error> {
     let mut keccak_inputs = Vec::new();
+    for (source, inputs) in keccak_inputs_by_source(block, code_db)? {
+        keccak_inputs.extend(inputs);
+        log::debug!(
+            "keccak total len after {source:?}: {}",
+            keccak_inputs.iter().map(|i| i.len()).sum::<usize>()
+        );
+    }
+
+    let inputs_len: usize = keccak_inputs.iter().map(|k| k.len()).sum();
+    let inputs_num = keccak_inputs.len();
+    let keccak_inputs: Vec<_> = keccak_inputs.into_iter().unique().collect();
+    let inputs_len2: usize = keccak_inputs.iter().map(|k| k.len()).sum();
+    let inputs_num2 = keccak_inputs.len();
+    log::debug!("keccak inputs after dedup: input num {inputs_num}->{inputs_num2}, input total len {inputs_len}->{inputs_len2}");
+
+    // MPT Circuit
+    // TODO https://github.com/privacy-scaling-explorations/zkevm-circuits/issues/696
+    Ok(keccak_inputs)
+}
+
+/// Return the keccak inputs used during the processing of the current block,
+/// grouped by their source. Unlike [`keccak_inputs`], the inputs are not
+/// deduplicated.
+pub fn keccak_inputs_by_source(
+    block: &Block,
+    code_db: &CodeDB,
+) -> Result<Vec<(KeccakSource, Vec<Vec<u8>>)>, Error> {
     // Tx Circuit
     let txs: Vec<geth_types::Transaction> = block.txs.iter().map(|tx| tx.into()).collect();
-    keccak_inputs.extend_from_slice(&keccak_inputs_tx_circuit(&txs)?);
-    log::debug!(
-        "keccak total len after txs: {}",
-        keccak_inputs.iter().map(|i| i.len()).sum::<usize>()
-    );
+    let tx_inputs = keccak_inputs_tx_circuit(&txs)?;
     // Ecrecover
-    keccak_inputs.extend_from_slice(&keccak_inputs_sign_verify(
-        &block.precompile_events.get_ecrecover_events(),
-    ));
-    log::debug!(
-        "keccak total len after ecrecover: {}",
-        keccak_inputs.iter().map(|i| i.len()).sum::<usize>()
-    );
+    let ecrecover_inputs =
+        keccak_inputs_sign_verify(&block.precompile_events.get_ecrecover_events());
     // PI circuit
-    keccak_inputs.extend(keccak_inputs_pi_circuit(
+    let receipts = block.receipts();
+    let pi_inputs = keccak_inputs_pi_circuit(
         block.chain_id,
         block.start_l1_queue_index,
         block.prev_state_root,
         block.withdraw_root,
         &block.headers,
         block.txs(),
-    ));
+        &receipts,
+    );
+    let receipts_inputs = keccak_inputs_receipts(&receipts);
     // Bytecode Circuit
     for _bytecode in code_db.0.values() {
         // keccak_inputs.push(bytecode.clone());
     }
-    log::debug!(
-        "keccak total len after bytecodes: {}",
-        keccak_inputs.iter().map(|i| i.len()).sum::<usize>()
-    );
     // EVM Circuit
-    keccak_inputs.extend_from_slice(&block.sha3_inputs);
-    log::debug!(
-        "keccak total len after opcodes: {}",
-        keccak_inputs.iter().map(|i| i.len()).sum::<usize>()
-    );
-
-    let inputs_len: usize = keccak_inputs.iter().map(|k| k.len()).sum();
-    let inputs_num = keccak_inputs.len();
-    let keccak_inputs: Vec<_> = keccak_inputs.into_iter().unique().collect();
-    let inputs_len2: usize = keccak_inputs.iter().map(|k| k.len()).sum();
-    let inputs_num2 = keccak_inputs.len();
-    log::debug!("keccak inputs after dedup: input num {inputs_num}->{inputs_num2}, input total len {inputs_len}->{inputs_len2}");
+    let evm_inputs = block.sha3_inputs.clone();
+
+    Ok(vec![
+        (KeccakSource::Txs, tx_inputs),
+        (KeccakSource::Ecrecover, ecrecover_inputs),
+        (KeccakSource::Pi, pi_inputs),
+        (KeccakSource::Receipts, receipts_inputs),
+        (KeccakSource::Opcodes, evm_inputs),
+    ])
+}
 
-    // MPT Circuit
-    // TODO https://github.com/privacy-scaling-explorations/zkevm-circuits/issues/696
-    Ok(keccak_inputs)
+/// Return the lengths of the keccak inputs of [`keccak_inputs_by_source`] other than the
+/// ones of the receipts, with their source, without computing the inputs: the tx hashes and
+/// the signatures are not recovered. Like the inputs by source, the lengths are not
+/// deduplicated.
+pub fn keccak_input_lens(block: &Block) -> Vec<(KeccakSource, usize)> {
+    let pk_len = 64;
+    let dummy_msg_len = SignData::default().msg.len();
+    let dummy_hash_data_len = {
+        let (dummy_tx, dummy_sig) = get_dummy_tx();
+        dummy_tx.rlp_signed(&dummy_sig).len()
+    };
+    // Tx Circuit
+    let tx_lens = block
+        .txs
+        .iter()
+        .map(|tx| tx.rlp_bytes.len())
+        .chain(iter::once(dummy_hash_data_len));
+    let sign_lens = block
+        .txs
+        .iter()
+        .filter(|tx| {
+            tx.tx_type.is_l1_msg()
+                || tx.signature.v != 0
+                || !tx.signature.r.is_zero()
+                || !tx.signature.s.is_zero()
+        })
+        .map(|tx| {
+            if tx.tx_type.is_l1_msg() {
+                dummy_msg_len
+            } else {
+                tx.rlp_unsigned_bytes.len()
+            }
+        })
+        .chain(iter::once(dummy_msg_len))
+        .flat_map(|msg_len| [pk_len, msg_len]);
+    // Ecrecover
+    let ecrecover_lens = block
+        .precompile_events
+        .get_ecrecover_events()
+        .iter()
+        .map(|sig| sig.msg.len())
+        .chain(iter::once(dummy_msg_len))
+        .flat_map(|msg_len| [pk_len, msg_len]);
+    // PI circuit
+    let pi_lens = keccak_input_lens_pi_circuit(block.headers.len(), block.txs.len());
+    // EVM Circuit
+    let evm_lens = block.sha3_inputs.iter().map(Vec::len);
+
+    let with_source = |source| move |len| (source, len);
+    tx_lens
+        .chain(sign_lens)
+        .map(with_source(KeccakSource::Txs))
+        .chain(ecrecover_lens.map(with_source(KeccakSource::Ecrecover)))
+        .chain(pi_lens.into_iter().map(with_source(KeccakSource::Pi)))
+        .chain(evm_lens.map(with_source(KeccakSource::Opcodes)))
+        .collect()
 }
 
 /// Generate the keccak inputs required by the SignVerify Chip from the
@@ -810,6 +919,7 @@ fn keccak_inputs_pi_circuit(
     withdraw_trie_root: Word,
     block_headers: &BTreeMap<u64, BlockHead>,
     transactions: &[Transaction],
+    receipts: &[Receipt],
 ) -> Vec<Vec<u8>> {
     let mut total_l1_popped = start_l1_queue_index;
     log::debug!(
@@ -851,6 +961,8 @@ fn keccak_inputs_pi_circuit(
         }))
         // Tx Hashes
         .chain(transactions.iter().flat_map(|tx| tx.hash.to_fixed_bytes()))
+        // Receipts
+        .chain(receipts_data_bytes(receipts))
         .collect::<Vec<u8>>();
     let data_hash = H256(keccak256(&data_bytes));
     log::debug!(
@@ -869,9 +981,22 @@ fn keccak_inputs_pi_circuit(
         .chain(data_hash.to_fixed_bytes())
         .collect::<Vec<u8>>();
 
+    debug_assert_eq!(
+        [data_bytes.len(), pi_bytes.len()],
+        keccak_input_lens_pi_circuit(block_headers.len(), transactions.len())
+    );
     vec![data_bytes, pi_bytes]
 }
 
+/// Lengths of the keccak inputs of [`keccak_inputs_pi_circuit`]: the chunk data bytes, with the
+/// block values of each block, the tx hashes and the receipts data bytes, and the pi bytes.
+fn keccak_input_lens_pi_circuit(num_blocks: usize, num_txs: usize) -> [usize; 2] {
+    let block_values_len = 8 + 8 + 32 + 8 + 2;
+    let data_bytes_len = num_blocks * block_values_len + num_txs * 32 + N_BYTES_RECEIPTS_DATA;
+    let pi_bytes_len = 8 + 4 * 32;
+    [data_bytes_len, pi_bytes_len]
+}
+
 /// Generate the keccak inputs required by the Tx Circuit from the transactions.
 pub fn keccak_inputs_tx_circuit(txs: &[geth_types::Transaction]) -> Result<Vec<Vec<u8>>, Error> {
     let mut inputs = Vec::new();
@@ -2,14 +2,15 @@
 
 use super::{
     execution::{ExecState, PrecompileEvent, PrecompileEvents},
+    receipt::{Receipt, ReceiptLog},
     transaction::Transaction,
     CircuitsParams, CopyEvent, ExecStep, ExpEvent,
 };
 use crate::{
-    operation::{OperationContainer, RWCounter},
+    operation::{Operation, OperationContainer, RWCounter, TxLogField, TxReceiptField},
     Error,
 };
-use eth_types::{Address, Hash, ToWord, Word};
+use eth_types::{Address, Hash, ToAddress, ToBigEndian, ToWord, Word, H256};
 use std::collections::{BTreeMap, HashMap};
 
 /// Context of a [`Block`] which can mutate in a [`Transaction`].
@@ -327,6 +328,49 @@ impl Block {
             .unwrap_or(self.prev_state_root)
     }
 
+    /// The receipts of the transactions, rebuilt from their TxReceipt and
+    /// TxLog operations.
+    pub fn receipts(&self) -> Vec<Receipt> {
+        let mut receipts = self
+            .txs
+            .iter()
+            .map(|tx| Receipt {
+                tx_type: tx.tx_type.eip2718_type(),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        for op in self.container.tx_receipt.iter().map(Operation::op) {
+            let receipt = &mut receipts[op.tx_id - 1];
+            match op.field {
+                TxReceiptField::PostStateOrStatus => receipt.status = op.value as u8,
+                TxReceiptField::CumulativeGasUsed => receipt.cumulative_gas_used = op.value,
+                TxReceiptField::LogLength => receipt
+                    .logs
+                    .resize(op.value as usize, ReceiptLog::default()),
+            }
+        }
+        for op in self.container.tx_log.iter().map(Operation::op) {
+            let logs = &mut receipts[op.tx_id - 1].logs;
+            if logs.len() < op.log_id {
+                logs.resize(op.log_id, ReceiptLog::default());
+            }
+            let log = &mut logs[op.log_id - 1];
+            match op.field {
+                TxLogField::Address => log.address = op.value.to_address(),
+                TxLogField::Topic => {
+                    log.topics
+                        .resize(log.topics.len().max(op.index + 1), H256::zero());
+                    log.topics[op.index] = H256(op.value.to_be_bytes());
+                }
+                TxLogField::Data => {
+                    log.data.resize(log.data.len().max(op.index + 1), 0);
+                    log.data[op.index] = op.value.as_u64() as u8;
+                }
+            }
+        }
+        receipts
+    }
+
     #[cfg(test)]
     pub fn txs_mut(&mut self) -> &mut Vec<Transaction> {
         &mut self.txs
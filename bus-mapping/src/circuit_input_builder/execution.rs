@@ -14,6 +14,7 @@ use crate::{
 };
 use eth_types::{
     evm_types::{memory::MemoryWordRange, Gas, GasCost, MemoryAddress, OpcodeId, ProgramCounter},
+    serde_utils::{biguint, curve_point_opt, field},
     sign_types::SignData,
     Address, Field, GethExecStep, ToLittleEndian, Word, H256, U256,
 };
@@ -27,6 +28,8 @@ use halo2_proofs::{
     },
     plonk::Expression,
 };
+use num::BigUint;
+use serde::{Deserialize, Serialize};
 
 /// An execution step of the EVM.
 #[derive(Clone, Debug)]
@@ -151,6 +154,9 @@ pub enum ExecState {
     BeginTx,
     /// Virtual step End Tx
     EndTx,
+    /// Virtual step destructing a self destructed account, or clearing one of
+    /// its storage slots, after End Tx
+    DestructAccount,
     /// Virtual step End Block
     EndBlock,
 }
@@ -204,7 +210,7 @@ impl ExecState {
 }
 
 /// Defines the various source/destination types for a copy event.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum CopyDataType {
     /// When we need to pad the Copy rows of the circuit up to a certain maximum
     /// with rows that are not "useful".
@@ -347,7 +353,7 @@ impl_expr!(CopyDataType, u64::from);
 
 /// Defines a single copy step in a copy event. This type is unified over the
 /// source/destination row in the copy table.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CopyStep {
     /// Byte value copied in this step.
     pub value: u8,
@@ -358,7 +364,7 @@ pub struct CopyStep {
 }
 
 /// Defines an enum type that can hold either a number or a hash value.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NumberOrHash {
     /// Variant to indicate a number value.
     Number(usize),
@@ -377,7 +383,7 @@ pub enum NumberOrHash {
 ///
 /// Additionally, when the destination is memory, `bytes_write_prev` holds the memory content
 /// *before* the write.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct CopyBytes {
     /// Represents the list of (bytes, is_code, mask) copied during this copy event
     pub bytes: Vec<(u8, bool, bool)>,
@@ -408,7 +414,7 @@ impl CopyBytes {
 /// Save address, storage_key, storage_key_index and is_warm_prev
 /// to column value_word_rlc, value_word_rlc_prev, value and
 /// value_prev in copy circuit.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CopyAccessList {
     /// Access list address
     pub address: Address,
@@ -444,7 +450,7 @@ impl CopyAccessList {
 /// Defines a copy event associated with EVM opcodes such as CALLDATACOPY,
 /// CODECOPY, CREATE, etc. More information:
 /// <https://github.com/privacy-scaling-explorations/zkevm-specs/blob/master/specs/copy-proof.md>.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CopyEvent {
     /// Represents the start address at the source of the copy event.
     pub src_addr: u64,
@@ -499,6 +505,14 @@ impl CopyEvent {
             || self.dst_type == CopyDataType::AccessListStorageKeys
     }
 
+    /// Whether the event copies within the memory of a single call (MCOPY). Its source and
+    /// destination ranges may overlap, so all the words are read before any word is written.
+    pub fn is_memory_copy(&self) -> bool {
+        self.src_type == CopyDataType::Memory
+            && self.dst_type == CopyDataType::Memory
+            && self.src_id == self.dst_id
+    }
+
     /// Whether the RLC of data must be computed.
     pub fn has_rlc(&self) -> bool {
         matches!(
@@ -846,7 +860,7 @@ where
 }
 
 /// Intermediary multiplication step, representing `a * b == d (mod 2^256)`
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ExpStep {
     /// First multiplicand.
     pub a: Word,
@@ -867,7 +881,7 @@ impl From<(Word, Word, Word)> for ExpStep {
 }
 
 /// Event representating an exponentiation `a ^ b == d (mod 2^256)`.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ExpEvent {
     /// Base `a` for the exponentiation.
     pub base: Word,
@@ -895,7 +909,7 @@ impl Default for ExpEvent {
 }
 
 /// I/Os from all precompiled contract calls in a block.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct PrecompileEvents {
     /// All events.
     pub events: Vec<PrecompileEvent>,
@@ -950,12 +964,13 @@ impl PrecompileEvents {
             .iter()
             .cloned()
             .filter_map(|e| {
-                if let PrecompileEvent::EcPairing(op) = e {
-                    Some(*op)
+                if let PrecompileEvent::EcPairing(ops) = e {
+                    Some(ops)
                 } else {
                     None
                 }
             })
+            .flatten()
             .collect()
     }
     /// Get all Big Modexp events.
@@ -986,10 +1001,38 @@ impl PrecompileEvents {
             .cloned()
             .collect()
     }
+    /// Get all RIPEMD160 events.
+    pub fn get_ripemd160_events(&self) -> Vec<RIPEMD160> {
+        self.events
+            .iter()
+            .filter_map(|e| {
+                if let PrecompileEvent::RIPEMD160(op) = e {
+                    Some(op)
+                } else {
+                    None
+                }
+            })
+            .cloned()
+            .collect()
+    }
+    /// Get all Blake2f events.
+    pub fn get_blake2f_events(&self) -> Vec<Blake2fOp> {
+        self.events
+            .iter()
+            .filter_map(|e| {
+                if let PrecompileEvent::Blake2f(op) = e {
+                    Some(op)
+                } else {
+                    None
+                }
+            })
+            .cloned()
+            .collect()
+    }
 }
 
 /// I/O from a precompiled contract call.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum PrecompileEvent {
     /// Represents the I/O from Ecrecover call.
     Ecrecover(SignData),
@@ -997,12 +1040,16 @@ pub enum PrecompileEvent {
     EcAdd(EcAddOp),
     /// Represents the I/O from EcMul call.
     EcMul(EcMulOp),
-    /// Represents the I/O from EcPairing call.
-    EcPairing(Box<EcPairingOp>),
+    /// Represents the I/O from EcPairing call, chained over one or more pairing ops.
+    EcPairing(Vec<EcPairingOp>),
     /// Represents the I/O from Modexp call.
     ModExp(BigModExp),
     /// Represents the I/O from SHA256 call.
     SHA256(SHA256),
+    /// Represents the I/O from RIPEMD160 call.
+    RIPEMD160(RIPEMD160),
+    /// Represents the I/O from Blake2f call.
+    Blake2f(Blake2fOp),
 }
 
 impl Default for PrecompileEvent {
@@ -1012,13 +1059,14 @@ impl Default for PrecompileEvent {
 }
 
 /// EcAdd operation: P + Q = R
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EcAddOp {
     /// EVM input for first operand to EcAdd.
     pub p: (U256, U256),
     /// EVM input for second operand to EcAdd.
     pub q: (U256, U256),
     /// Addition of the first and second EC points.
+    #[serde(with = "curve_point_opt")]
     pub r: Option<G1Affine>,
 }
 
@@ -1114,13 +1162,15 @@ impl EcAddOp {
 }
 
 /// EcMul operation: s.P = R
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EcMulOp {
     /// The EVM inputs to the G1 point.
     pub p: (U256, U256),
     /// Scalar.
+    #[serde(with = "field")]
     pub s: Fr,
     /// Result for s.P = R, that is `None` in the case of an erroneous input.
+    #[serde(with = "curve_point_opt")]
     pub r: Option<G1Affine>,
 }
 
@@ -1223,14 +1273,15 @@ impl EcMulOp {
 }
 
 /// The number of pairing inputs per pairing operation. If the inputs provided to the precompile
-/// call are < 4, we append (G1::infinity, G2::generator) until we have the required no. of inputs.
+/// call are not a multiple of 4, we append (G1::infinity, G2::generator) until they are. A call
+/// with more than 4 pairs is split across several chained pairing operations.
 pub const N_PAIRING_PER_OP: usize = 4;
 
 /// The number of bytes taken to represent a pair (G1, G2).
 pub const N_BYTES_PER_PAIR: usize = 192;
 
 /// Pair of (G1, G2).
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcPairingPair {
     /// EVM inputs for the G1 point.
     pub g1_point: (U256, U256),
@@ -1316,9 +1367,9 @@ impl EcPairingPair {
             .collect()
     }
 
-    /// Padding pair for EcPairing operation. The pairing check is done with a constant number
-    /// `N_PAIRING_PER_OP` of (G1, G2) pairs. In case EVM inputs are less in number, we pad them
-    /// with `(G1::Infinity, G2::Infinity)` for simplicity.
+    /// Padding pair for EcPairing operation. The pairing check is done with a multiple of
+    /// `N_PAIRING_PER_OP` (G1, G2) pairs. In case EVM inputs are not, we pad them with
+    /// `(G1::Infinity, G2::Infinity)` for simplicity.
     pub fn padding_pair() -> Self {
         Self {
             g1_point: (U256::zero(), U256::zero()),
@@ -1332,17 +1383,23 @@ impl EcPairingPair {
 }
 
 /// EcPairing operation
-#[derive(Clone, Debug, PartialEq, Eq)]
+///
+/// An ecPairing call with more than [`N_PAIRING_PER_OP`] pairs is split into several ops, where
+/// every op but the first is `chained`, i.e. it multiplies its Miller loop result into the partial
+/// product of the previous op.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcPairingOp {
     /// tuples of G1 and G2 points supplied to the ECC circuit.
     pub pairs: [EcPairingPair; N_PAIRING_PER_OP],
-    /// Result from the pairing check.
+    /// Result from the pairing check over all pairs of the chain up to and including this op.
     pub output: Word,
-    /// Input bytes to the ecPairing call.
+    /// Whether this op continues the pairing check of the previous op.
+    pub chained: bool,
+    /// Input bytes to the ecPairing call. Only set for the last op of a chain.
     pub input_bytes: Vec<u8>,
-    /// Output bytes from the ecPairing call.
+    /// Output bytes from the ecPairing call. Only set for the last op of a chain.
     pub output_bytes: Vec<u8>,
-    /// Bytes returned back to the caller.
+    /// Bytes returned back to the caller. Only set for the last op of a chain.
     pub return_bytes: Vec<u8>,
 }
 
@@ -1376,6 +1433,7 @@ impl Default for EcPairingOp {
                 },
             ],
             output: Word::zero(),
+            chained: false,
             // It does not matter what the input bytes and return bytes are in this case, as this
             // operation is a filler op. It is not an op constructed from an EVM call to the
             // ecPairing precompiled contract. Hence the input/return bytes will not be
@@ -1427,34 +1485,127 @@ impl EcPairingOp {
 }
 
 /// Event representating an exponentiation `a ^ b == d (mod m)` in precompile modexp.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BigModExp {
     /// Base `a` for the exponentiation.
-    pub base: Word,
+    #[serde(with = "biguint")]
+    pub base: BigUint,
     /// Exponent `b` for the exponentiation.
-    pub exponent: Word,
+    #[serde(with = "biguint")]
+    pub exponent: BigUint,
     /// Modulus `m`
-    pub modulus: Word,
+    #[serde(with = "biguint")]
+    pub modulus: BigUint,
     /// Mod exponentiation result.
-    pub result: Word,
+    #[serde(with = "biguint")]
+    pub result: BigUint,
+    /// Size of the base in the call data, in bytes.
+    pub base_len: usize,
+    /// Size of the exponent in the call data, in bytes.
+    pub exponent_len: usize,
+    /// Size of the modulus in the call data, in bytes.
+    pub modulus_len: usize,
 }
 
 impl Default for BigModExp {
     fn default() -> Self {
         Self {
-            modulus: 1.into(),
+            modulus: 1u8.into(),
             base: Default::default(),
             exponent: Default::default(),
             result: Default::default(),
+            base_len: 0,
+            exponent_len: 0,
+            modulus_len: 1,
         }
     }
 }
 
 /// Event representating an SHA256 hash in precompile sha256.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct SHA256 {
     /// input bytes
     pub input: Vec<u8>,
     /// digest
     pub digest: [u8; 32],
 }
+
+/// Event representating a RIPEMD160 hash in precompile ripemd160.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RIPEMD160 {
+    /// input bytes
+    pub input: Vec<u8>,
+    /// digest
+    pub digest: [u8; 20],
+}
+
+/// Number of input bytes expected by the blake2f precompile.
+pub const BLAKE2F_INPUT_LEN: usize = 213;
+
+/// Blake2f operation: the compression function F of Blake2b as specified in EIP-152.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Blake2fOp {
+    /// Number of rounds, big-endian u32 in the input.
+    pub rounds: u32,
+    /// State vector h.
+    pub h: [u64; 8],
+    /// Message block m.
+    pub m: [u64; 16],
+    /// Offset counters t.
+    pub t: [u64; 2],
+    /// Final block indicator flag.
+    pub f: bool,
+    /// Output of the compression, i.e. the updated state vector in little-endian bytes.
+    pub output: Vec<u8>,
+}
+
+impl Blake2fOp {
+    /// Creates a new Blake2f op given input and output bytes from a successful precompile
+    /// call.
+    pub fn new_from_bytes(input: &[u8], output: &[u8]) -> Self {
+        assert_eq!(
+            input.len(),
+            BLAKE2F_INPUT_LEN,
+            "invalid blake2f input length"
+        );
+        assert!(
+            input[212] <= 1,
+            "invalid blake2f final block indicator flag"
+        );
+        assert_eq!(output.len(), 64, "blake2f output must be 64 bytes");
+
+        let u64_le = |bytes: &[u8]| u64::from_le_bytes(bytes.try_into().unwrap());
+        let mut h = [0u64; 8];
+        let mut m = [0u64; 16];
+        let mut t = [0u64; 2];
+        for (i, word) in input[4..68].chunks(8).enumerate() {
+            h[i] = u64_le(word);
+        }
+        for (i, word) in input[68..196].chunks(8).enumerate() {
+            m[i] = u64_le(word);
+        }
+        for (i, word) in input[196..212].chunks(8).enumerate() {
+            t[i] = u64_le(word);
+        }
+
+        Self {
+            rounds: u32::from_be_bytes(input[0..4].try_into().unwrap()),
+            h,
+            m,
+            t,
+            f: input[212] == 1,
+            output: output.to_vec(),
+        }
+    }
+
+    /// The 213 input bytes of the precompile call.
+    pub fn input_bytes(&self) -> Vec<u8> {
+        std::iter::empty()
+            .chain(self.rounds.to_be_bytes())
+            .chain(self.h.iter().flat_map(|w| w.to_le_bytes()))
+            .chain(self.m.iter().flat_map(|w| w.to_le_bytes()))
+            .chain(self.t.iter().flat_map(|w| w.to_le_bytes()))
+            .chain(std::iter::once(self.f as u8))
+            .collect()
+    }
+}
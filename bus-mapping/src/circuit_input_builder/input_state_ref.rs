@@ -16,8 +16,8 @@ use crate::{
     exec_trace::OperationRef,
     operation::{
         AccountField, AccountOp, CallContextField, CallContextOp, MemoryOp, Op, OpEnum, Operation,
-        StackOp, Target, TxAccessListAccountOp, TxAccessListAccountStorageOp, TxLogField, TxLogOp,
-        TxReceiptField, TxReceiptOp, RW,
+        StackOp, Target, TxAccessListAccountOp, TxAccessListAccountStorageOp, TxCreatedAccountOp,
+        TxDestructedAccountOp, TxLogField, TxLogOp, TxReceiptField, TxReceiptOp, RW,
     },
     precompile::{is_precompiled, PrecompileCalls},
     state_db::{CodeDB, StateDB},
@@ -147,6 +147,23 @@ impl<'a> CircuitInputStateRef<'a> {
         }
     }
 
+    /// Create a new DestructAccount step, following the EndTx step
+    pub fn new_destruct_account_step(&self) -> ExecStep {
+        let end_tx_step = self
+            .tx
+            .steps()
+            .last()
+            .expect("steps should have the EndTx step");
+        debug_assert_eq!(end_tx_step.exec_state, ExecState::EndTx);
+        ExecStep {
+            exec_state: ExecState::DestructAccount,
+            gas_left: end_tx_step.gas_left,
+            rwc: self.block_ctx.rwc,
+            log_id: end_tx_step.log_id,
+            ..Default::default()
+        }
+    }
+
     /// Push an [`Operation`](crate::operation::Operation) into the
     /// [`OperationContainer`](crate::operation::OperationContainer) with the
     /// next [`RWCounter`](crate::operation::RWCounter) and then adds a
@@ -504,7 +521,14 @@ impl<'a> CircuitInputStateRef<'a> {
                 AccountField::CodeHash => {
                     self.sdb.set_touched(&op.address);
                     let value = H256::from(op.value.to_be_bytes());
-                    account.code_hash = value;
+                    if value.is_zero() {
+                        // Writing code_hash=0 destructs the account (DestructAccount), which is
+                        // represented as an account with empty code in the StateDB.
+                        account.code_hash = CodeDB::empty_code_hash();
+                        account.code_size = Word::zero();
+                    } else {
+                        account.code_hash = value;
+                    }
                 }
                 AccountField::CodeSize => {
                     account.code_size = op.value;
@@ -637,6 +661,64 @@ impl<'a> CircuitInputStateRef<'a> {
         )
     }
 
+    /// Mark `address` as created in the current transaction (EIP-6780).
+    pub fn tx_created_account_write(
+        &mut self,
+        step: &mut ExecStep,
+        address: Address,
+    ) -> Result<(), Error> {
+        let is_created = self.sdb.is_created_in_tx(&address);
+        self.push_op_reversible(
+            step,
+            TxCreatedAccountOp {
+                tx_id: self.tx_ctx.id(),
+                address,
+                is_created: true,
+                is_created_prev: is_created,
+            },
+        )
+    }
+
+    /// Read whether `address` has been created in the current transaction
+    /// (EIP-6780).
+    pub fn tx_created_account_read(
+        &mut self,
+        step: &mut ExecStep,
+        address: Address,
+    ) -> Result<bool, Error> {
+        let is_created = self.sdb.is_created_in_tx(&address);
+        self.push_op(
+            step,
+            RW::READ,
+            TxCreatedAccountOp {
+                tx_id: self.tx_ctx.id(),
+                address,
+                is_created,
+                is_created_prev: is_created,
+            },
+        )?;
+        Ok(is_created)
+    }
+
+    /// Mark `address` as destructed at the end of the current transaction,
+    /// by `SELFDESTRUCT`.
+    pub fn tx_destructed_account_write(
+        &mut self,
+        step: &mut ExecStep,
+        address: Address,
+    ) -> Result<(), Error> {
+        let is_destructed = self.sdb.is_destructed(&address);
+        self.push_op_reversible(
+            step,
+            TxDestructedAccountOp {
+                tx_id: self.tx_ctx.id(),
+                address,
+                is_destructed: true,
+                is_destructed_prev: is_destructed,
+            },
+        )
+    }
+
     /// Push a write type [`TxAccessListAccountOp`] into the
     /// [`OperationContainer`](crate::operation::OperationContainer) with the
     /// next [`RWCounter`](crate::operation::RWCounter), and then
@@ -1197,6 +1279,30 @@ impl<'a> CircuitInputStateRef<'a> {
                     None
                 }
             }
+            OperationRef(Target::TransientStorage, idx) => {
+                let operation = &self.block.container.transient_storage[*idx];
+                if operation.rw().is_write() && operation.reversible() {
+                    Some(OpEnum::TransientStorage(operation.op().reverse()))
+                } else {
+                    None
+                }
+            }
+            OperationRef(Target::TxCreatedAccount, idx) => {
+                let operation = &self.block.container.tx_created_account[*idx];
+                if operation.rw().is_write() && operation.reversible() {
+                    Some(OpEnum::TxCreatedAccount(operation.op().reverse()))
+                } else {
+                    None
+                }
+            }
+            OperationRef(Target::TxDestructedAccount, idx) => {
+                let operation = &self.block.container.tx_destructed_account[*idx];
+                if operation.rw().is_write() && operation.reversible() {
+                    Some(OpEnum::TxDestructedAccount(operation.op().reverse()))
+                } else {
+                    None
+                }
+            }
             _ => None,
         }
     }
@@ -1229,6 +1335,26 @@ impl<'a> CircuitInputStateRef<'a> {
             OpEnum::TxRefund(op) => {
                 self.sdb.set_refund(op.value);
             }
+            OpEnum::TransientStorage(op) => {
+                self.sdb
+                    .set_transient_storage(&op.address, &op.key, &op.value);
+            }
+            OpEnum::TxCreatedAccount(op) => {
+                if !op.is_created_prev && op.is_created {
+                    self.sdb.add_created_account(op.address);
+                }
+                if op.is_created_prev && !op.is_created {
+                    self.sdb.remove_created_account(&op.address);
+                }
+            }
+            OpEnum::TxDestructedAccount(op) => {
+                if !op.is_destructed_prev && op.is_destructed {
+                    self.sdb.destruct_account(op.address);
+                }
+                if op.is_destructed_prev && !op.is_destructed {
+                    self.sdb.remove_destructed_account(&op.address);
+                }
+            }
             _ => unreachable!(),
         };
     }
@@ -1677,6 +1803,7 @@ impl<'a> CircuitInputStateRef<'a> {
                     OpcodeId::RETURNDATACOPY => Some(ExecError::ReturnDataOutOfBounds),
                     // Break write protection (CALL with value will be handled below)
                     OpcodeId::SSTORE
+                    | OpcodeId::TSTORE
                     | OpcodeId::CREATE
                     | OpcodeId::CREATE2
                     | OpcodeId::SELFDESTRUCT
@@ -1838,26 +1965,12 @@ impl<'a> CircuitInputStateRef<'a> {
                 // Therefore we postpone the oog handling to the implementor of callop.
                 if is_precompiled(&code_address) {
                     let precompile_call: PrecompileCalls = code_address[19].into();
-                    match precompile_call {
-                        PrecompileCalls::Ripemd160 | PrecompileCalls::Blake2F => {
-                            // Log the precompile address and gas left. Since this failure is mainly
-                            // caused by out of gas.
-                            log::trace!(
-                                "Precompile failed: code_address = {}, step.gas = {}",
-                                code_address,
-                                step.gas.0,
-                            );
-                            return Ok(Some(ExecError::PrecompileFailed));
-                        }
-                        pre_call => {
-                            log::trace!(
-                                "Precompile call failed: addr={:?}, step.gas={:?}",
-                                pre_call,
-                                step.gas.0
-                            );
-                            return Ok(None);
-                        }
-                    }
+                    log::trace!(
+                        "Precompile call failed: addr={:?}, step.gas={:?}",
+                        precompile_call,
+                        step.gas.0
+                    );
+                    return Ok(None);
                 }
             }
 
@@ -2216,6 +2329,67 @@ impl<'a> CircuitInputStateRef<'a> {
         Ok((read_steps, write_steps, prev_bytes))
     }
 
+    /// Generate copy steps for a copy within the memory of the current call (MCOPY). The source
+    /// and destination ranges may overlap, so all the source words are read before any
+    /// destination word is written.
+    pub(crate) fn gen_copy_steps_for_memory_to_memory(
+        &mut self,
+        exec_step: &mut ExecStep,
+        src_addr: impl Into<MemoryAddress>,
+        dst_addr: impl Into<MemoryAddress>,
+        copy_length: impl Into<MemoryAddress>,
+    ) -> Result<(CopyEventSteps, CopyEventSteps, Vec<u8>), Error> {
+        let copy_length = copy_length.into().0;
+        if copy_length == 0 {
+            return Ok((vec![], vec![], vec![]));
+        }
+
+        let src_addr = src_addr.into().0;
+        let call_ctx = self.call_ctx_mut()?;
+        // Extend call memory for the source range, the destination is extended below.
+        call_ctx
+            .memory
+            .extend_for_range(src_addr.into(), copy_length.into());
+        let src_memory = call_ctx.memory.clone();
+        let (src_range, dst_range, write_slot_bytes) = combine_copy_slot_bytes(
+            src_addr,
+            dst_addr.into().0,
+            copy_length,
+            &src_memory.0,
+            &mut call_ctx.memory,
+        );
+        let read_slot_bytes = src_memory.read_chunk(src_range);
+        debug_assert_eq!(read_slot_bytes.len(), write_slot_bytes.len());
+
+        let read_steps = CopyEventStepsBuilder::memory_range(src_range)
+            .source(read_slot_bytes.as_slice())
+            .build();
+        let write_steps = CopyEventStepsBuilder::memory_range(dst_range)
+            .source(write_slot_bytes.as_slice())
+            .build();
+
+        let mut src_chunk_index = src_range.start_slot().0;
+        let mut dst_chunk_index = dst_range.start_slot().0;
+        let mut prev_bytes: Vec<u8> = vec![];
+        // all memory word reads from source come before the writes to destination
+        for read_chunk in read_slot_bytes.chunks(32) {
+            let value = self.memory_read_word(exec_step, src_chunk_index.into())?;
+            debug_assert_eq!(Word::from_big_endian(read_chunk), value);
+            src_chunk_index += 32;
+        }
+        for write_chunk in write_slot_bytes.chunks(32) {
+            self.write_chunk_for_copy_step(
+                exec_step,
+                write_chunk,
+                dst_chunk_index,
+                &mut prev_bytes,
+            )?;
+            dst_chunk_index += 32;
+        }
+
+        Ok((read_steps, write_steps, prev_bytes))
+    }
+
     pub(crate) fn gen_copy_steps_for_log(
         &mut self,
         exec_step: &mut ExecStep,
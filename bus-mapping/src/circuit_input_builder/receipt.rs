@@ -0,0 +1,245 @@
+//! Receipts of the transactions of a chunk, rebuilt from their TxReceipt and
+//! TxLog operations, and the receipts trie and logs bloom committing to them.
+
+use eth_types::{Address, H256};
+use ethers_core::{
+    types::Bloom,
+    utils::{
+        keccak256,
+        rlp::{self, RlpStream},
+    },
+};
+use std::iter;
+
+/// Number of bloom bits set by each address or topic of a log.
+pub const N_BLOOM_BITS_PER_ITEM: usize = 3;
+
+/// Log entry of a receipt.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReceiptLog {
+    /// Address of the contract emitting the log
+    pub address: Address,
+    /// Topics of the log
+    pub topics: Vec<H256>,
+    /// Data of the log
+    pub data: Vec<u8>,
+}
+
+/// Receipt of a transaction. Its cumulative gas is the one of the rw table,
+/// which runs over all the transactions of the chunk.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Receipt {
+    /// EIP-2718 type of the transaction, 0 for a legacy transaction
+    pub tx_type: u8,
+    /// 1 if the transaction succeeded, 0 otherwise
+    pub status: u8,
+    /// Gas used by the transactions of the chunk up to this one included
+    pub cumulative_gas_used: u64,
+    /// Logs emitted by the transaction
+    pub logs: Vec<ReceiptLog>,
+}
+
+/// The indices of the bits of the 2048 bits bloom set by an address or a
+/// topic: each of the first 3 pairs of bytes of its hash gives an index from
+/// its 11 low bits.
+pub fn bloom_indices(item: &[u8]) -> [usize; N_BLOOM_BITS_PER_ITEM] {
+    let hash = keccak256(item);
+    [0, 1, 2].map(|i| ((hash[2 * i] as usize & 7) << 8) | hash[2 * i + 1] as usize)
+}
+
+fn accrue(bloom: &mut Bloom, item: &[u8]) {
+    for index in bloom_indices(item) {
+        // The bit 0 is the last bit of the last byte.
+        bloom.0[255 - index / 8] |= 1 << (index % 8);
+    }
+}
+
+impl ReceiptLog {
+    /// The addresses and topics setting bits of the bloom.
+    pub fn bloom_items(&self) -> impl Iterator<Item = &[u8]> {
+        iter::once(self.address.as_bytes()).chain(self.topics.iter().map(|topic| topic.as_bytes()))
+    }
+}
+
+impl Receipt {
+    /// The bloom of the logs of the receipt.
+    pub fn bloom(&self) -> Bloom {
+        let mut bloom = Bloom::zero();
+        for item in self.logs.iter().flat_map(ReceiptLog::bloom_items) {
+            accrue(&mut bloom, item);
+        }
+        bloom
+    }
+
+    /// The encoding of the receipt: the RLP list `[status, cumulative_gas_used,
+    /// bloom, logs]`, prefixed by the EIP-2718 type of its transaction if it
+    /// is typed.
+    pub fn rlp_bytes(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(4);
+        stream
+            .append(&self.status)
+            .append(&self.cumulative_gas_used)
+            .append(&self.bloom())
+            .begin_list(self.logs.len());
+        for log in self.logs.iter() {
+            stream
+                .begin_list(3)
+                .append(&log.address)
+                .append_list(&log.topics)
+                .append(&log.data);
+        }
+        (self.tx_type != 0)
+            .then_some(self.tx_type)
+            .into_iter()
+            .chain(stream.out())
+            .collect()
+    }
+}
+
+/// The bloom of all the logs of the receipts.
+pub fn logs_bloom(receipts: &[Receipt]) -> Bloom {
+    receipts.iter().fold(Bloom::zero(), |mut bloom, receipt| {
+        bloom.accrue_bloom(&receipt.bloom());
+        bloom
+    })
+}
+
+/// Number of bytes of [`receipts_data_bytes`]: the receipts root and the
+/// logs bloom, or none without the `receipts-root` feature.
+pub const N_BYTES_RECEIPTS_DATA: usize = if cfg!(feature = "receipts-root") {
+    32 + 256
+} else {
+    0
+};
+
+/// The receipts root and the logs bloom of a chunk, which follow its tx
+/// hashes in its data bytes with the `receipts-root` feature only. Without
+/// it, the data hash is the one of the rollup contract, which does not commit
+/// to the receipts.
+pub fn receipts_data_bytes(receipts: &[Receipt]) -> Vec<u8> {
+    if !cfg!(feature = "receipts-root") {
+        return vec![];
+    }
+    iter::empty()
+        .chain(ReceiptsTrie::new(receipts).root.to_fixed_bytes())
+        .chain(logs_bloom(receipts).to_fixed_bytes())
+        .collect()
+}
+
+/// The key of the receipt of the `index`-th transaction: the RLP encoding of
+/// its index.
+pub fn receipt_key(index: usize) -> Vec<u8> {
+    rlp::encode(&(index as u64)).to_vec()
+}
+
+/// The trie of the receipts of a chunk, keyed by [`receipt_key`] as the
+/// receipts trie of a block. All its nodes are at least 32 bytes long, as the
+/// receipts are, so that none of them is embedded in its parent.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReceiptsTrie {
+    /// Root of the trie
+    pub root: H256,
+    /// RLP encoding of the nodes, each following its children
+    pub nodes: Vec<Vec<u8>>,
+}
+
+impl ReceiptsTrie {
+    /// Build the trie of the receipts.
+    pub fn new(receipts: &[Receipt]) -> Self {
+        let mut leaves = receipts
+            .iter()
+            .enumerate()
+            .map(|(index, receipt)| {
+                let key = receipt_key(index)
+                    .into_iter()
+                    .flat_map(|byte| [byte >> 4, byte & 0xf])
+                    .collect::<Vec<_>>();
+                (key, receipt.rlp_bytes())
+            })
+            .collect::<Vec<_>>();
+        leaves.sort();
+
+        let mut nodes = vec![];
+        let root = if leaves.is_empty() {
+            // the hash of the RLP encoding of the empty string
+            H256(keccak256([0x80]))
+        } else {
+            Self::build_node(&leaves, 0, &mut nodes)
+        };
+        Self { root, nodes }
+    }
+
+    // The keys are sorted, so their common prefix is the one of the first and
+    // last keys, and no key is a prefix of another one as they are RLP
+    // encodings.
+    fn build_node(leaves: &[(Vec<u8>, Vec<u8>)], depth: usize, nodes: &mut Vec<Vec<u8>>) -> H256 {
+        let mut stream = RlpStream::new();
+        if let [(key, value)] = leaves {
+            stream
+                .begin_list(2)
+                .append(&hp_encode(&key[depth..], true))
+                .append(value);
+        } else {
+            let (first, last) = (&leaves[0].0, &leaves[leaves.len() - 1].0);
+            let prefix_len = first[depth..]
+                .iter()
+                .zip(&last[depth..])
+                .take_while(|(a, b)| a == b)
+                .count();
+            if prefix_len > 0 {
+                let child = Self::build_node(leaves, depth + prefix_len, nodes);
+                stream
+                    .begin_list(2)
+                    .append(&hp_encode(&first[depth..depth + prefix_len], false))
+                    .append(&child);
+            } else {
+                stream.begin_list(17);
+                for nibble in 0..16 {
+                    let group = leaves
+                        .iter()
+                        .filter(|(key, _)| key[depth] == nibble)
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    if group.is_empty() {
+                        stream.append_empty_data();
+                    } else {
+                        stream.append(&Self::build_node(&group, depth + 1, nodes));
+                    }
+                }
+                stream.append_empty_data();
+            }
+        }
+        let bytes = stream.out().to_vec();
+        debug_assert!(bytes.len() >= 32, "receipts trie nodes are not embedded");
+        let hash = H256(keccak256(&bytes));
+        nodes.push(bytes);
+        hash
+    }
+}
+
+/// Hex-prefix encoding of a path of nibbles.
+fn hp_encode(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let is_odd = nibbles.len() % 2 == 1;
+    let flag = 2 * is_leaf as u8 + is_odd as u8;
+    let (first, rest) = if is_odd {
+        ((flag << 4) | nibbles[0], &nibbles[1..])
+    } else {
+        (flag << 4, nibbles)
+    };
+    iter::once(first)
+        .chain(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]))
+        .collect()
+}
+
+/// Generate the keccak inputs required by the PI circuit to prove the
+/// receipts trie and the logs bloom: the nodes of the trie, and the addresses
+/// and topics of the logs.
+pub fn keccak_inputs_receipts(receipts: &[Receipt]) -> Vec<Vec<u8>> {
+    receipts
+        .iter()
+        .flat_map(|receipt| &receipt.logs)
+        .flat_map(ReceiptLog::bloom_items)
+        .map(<[u8]>::to_vec)
+        .chain(ReceiptsTrie::new(receipts).nodes)
+        .collect()
+}
@@ -9,10 +9,11 @@ use crate::{
 use eth_types::{
     evm_types::{gas_utils::tx_data_gas_cost, OpcodeId},
     geth_types,
-    geth_types::{get_rlp_unsigned, TxType},
+    geth_types::{get_blob_fields, get_rlp_signed, get_rlp_unsigned, TxType},
     AccessList, Address, GethExecTrace, Signature, Word, H256,
 };
 use ethers_core::utils::get_contract_address;
+use serde::{Deserialize, Serialize};
 
 /// Precision of transaction L1 fee
 pub const TX_L1_FEE_PRECISION: u64 = 1_000_000_000;
@@ -213,6 +214,10 @@ pub struct Transaction {
     pub l1_fee_committed: TxL1Fee,
     /// EIP2930
     pub access_list: Option<AccessList>,
+    /// EIP4844
+    pub max_fee_per_blob_gas: Word,
+    /// EIP4844
+    pub blob_versioned_hashes: Vec<H256>,
     /// Calls made in the transaction
     pub(crate) calls: Vec<Call>,
     /// Execution steps
@@ -235,6 +240,8 @@ impl From<&Transaction> for geth_types::Transaction {
             s: tx.signature.s,
             gas_fee_cap: Some(tx.gas_fee_cap),
             gas_tip_cap: Some(tx.gas_tip_cap),
+            max_fee_per_blob_gas: tx.max_fee_per_blob_gas,
+            blob_versioned_hashes: tx.blob_versioned_hashes.clone(),
             rlp_unsigned_bytes: tx.rlp_unsigned_bytes.clone(),
             rlp_bytes: tx.rlp_bytes.clone(),
             tx_type: tx.tx_type,
@@ -272,6 +279,8 @@ impl Transaction {
             l1_fee: Default::default(),
             l1_fee_committed: Default::default(),
             access_list: None,
+            max_fee_per_blob_gas: Word::zero(),
+            blob_versioned_hashes: Vec::new(),
         }
     }
 
@@ -344,6 +353,7 @@ impl Transaction {
         );
 
         let tx_type = TxType::get_tx_type(eth_tx);
+        let (max_fee_per_blob_gas, blob_versioned_hashes) = get_blob_fields(eth_tx);
         let (l1_fee, l1_fee_committed) = if tx_type.is_l1_msg() {
             Default::default()
         } else {
@@ -363,7 +373,7 @@ impl Transaction {
             block_num: eth_tx.block_number.unwrap().as_u64(),
             hash: eth_tx.hash,
             tx_type,
-            rlp_bytes: eth_tx.rlp().to_vec(),
+            rlp_bytes: get_rlp_signed(eth_tx),
             rlp_unsigned_bytes: get_rlp_unsigned(eth_tx),
             nonce: eth_tx.nonce.as_u64(),
             gas: eth_tx.gas.as_u64(),
@@ -385,6 +395,8 @@ impl Transaction {
             l1_fee,
             l1_fee_committed,
             access_list: eth_tx.access_list.clone(),
+            max_fee_per_blob_gas,
+            blob_versioned_hashes,
         })
     }
 
@@ -470,7 +482,7 @@ impl Transaction {
 }
 
 /// Transaction L1 fee for L1GasPriceOracle contract
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct TxL1Fee {
     /// L1 base fee
     pub base_fee: u64,
@@ -73,7 +73,7 @@ pub enum OogError {
     /// cost
     DynamicMemoryExpansion,
     /// Out of Gas for CALLDATACOPY, CODECOPY, EXTCODECOPY, RETURNDATACOPY,
-    /// which copy a specified chunk of memory
+    /// MCOPY, which copy a specified chunk of memory
     MemoryCopy,
     /// Out of Gas for BALANCE, EXTCODESIZE, EXTCODEHASH, which possibly touch
     /// an extra account
@@ -93,7 +93,7 @@ pub enum OogError {
     Call,
     /// Out of Gas for Precompile.
     /// ecrecover/ecadd/ecmul/ecpairing/identity oog can should be handled by this.
-    /// modexp oog is handled inside modexp gadget.
+    /// modexp and blake2f oog are handled inside their own gadgets.
     /// disabled precompiles are handled by PrecompileFailedGadget.
     Precompile,
     /// Out of Gas for CREATE and CREATE2
@@ -192,7 +192,8 @@ pub(crate) fn get_step_reported_error(op: &OpcodeId, error: GethExecError) -> Ex
                 OpcodeId::CALLDATACOPY
                 | OpcodeId::CODECOPY
                 | OpcodeId::EXTCODECOPY
-                | OpcodeId::RETURNDATACOPY => OogError::MemoryCopy,
+                | OpcodeId::RETURNDATACOPY
+                | OpcodeId::MCOPY => OogError::MemoryCopy,
                 OpcodeId::BALANCE | OpcodeId::EXTCODESIZE | OpcodeId::EXTCODEHASH => {
                     OogError::AccountAccess
                 }
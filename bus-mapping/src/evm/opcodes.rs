@@ -6,11 +6,10 @@ use crate::{
         NonceUintOverflowError, OogError,
     },
     evm::OpcodeId,
-    operation::{AccountField, AccountOp, TxAccessListAccountOp},
     Error,
 };
 use core::fmt::Debug;
-use eth_types::{evm_unimplemented, GethExecStep, ToAddress, ToWord, Word};
+use eth_types::{evm_unimplemented, GethExecStep};
 
 #[cfg(any(feature = "enable-memory", feature = "enable-stack"))]
 use crate::util::GETH_TRACE_CHECK_LEVEL;
@@ -22,6 +21,7 @@ mod address;
 mod arithmetic;
 mod balance;
 mod begin_end_tx;
+mod blobhash;
 mod blockhash;
 mod calldatacopy;
 mod calldataload;
@@ -41,6 +41,7 @@ mod extcodehash;
 mod extcodesize;
 mod gasprice;
 mod logs;
+mod mcopy;
 mod mload;
 mod mstore;
 mod number;
@@ -52,12 +53,15 @@ mod return_revert;
 mod returndatacopy;
 mod returndatasize;
 mod selfbalance;
+mod selfdestruct;
 mod sha3;
 mod sload;
 mod sstore;
 mod stackonlyop;
 mod stop;
 mod swap;
+mod tload;
+mod tstore;
 
 mod error_codestore;
 mod error_contract_address_collision;
@@ -68,6 +72,7 @@ mod error_oog_call;
 mod error_oog_log;
 mod error_oog_memory_copy;
 mod error_oog_precompile;
+mod error_oog_self_destruct;
 mod error_oog_sload_sstore;
 mod error_precompile_failed;
 mod error_return_data_outofbound;
@@ -83,7 +88,8 @@ use self::{pushn::PushN, sha3::Sha3};
 use address::Address;
 use arithmetic::ArithmeticOpcode;
 use balance::Balance;
-use begin_end_tx::{gen_begin_tx_steps, gen_end_tx_steps};
+use begin_end_tx::{gen_begin_tx_steps, gen_destruct_account_steps, gen_end_tx_steps};
+use blobhash::Blobhash;
 use blockhash::Blockhash;
 use calldatacopy::Calldatacopy;
 use calldataload::Calldataload;
@@ -103,6 +109,7 @@ use error_oog_account_access::ErrorOOGAccountAccess;
 use error_oog_call::OOGCall;
 use error_oog_log::ErrorOOGLog;
 use error_oog_memory_copy::OOGMemoryCopy;
+use error_oog_self_destruct::ErrorOOGSelfDestruct;
 use error_oog_sload_sstore::OOGSloadSstore;
 use error_precompile_failed::PrecompileFailed;
 use error_return_data_outofbound::ErrorReturnDataOutOfBound;
@@ -113,6 +120,7 @@ use extcodehash::Extcodehash;
 use extcodesize::Extcodesize;
 use gasprice::GasPrice;
 use logs::Log;
+use mcopy::Mcopy;
 use mload::Mload;
 use mstore::Mstore;
 use origin::Origin;
@@ -121,11 +129,14 @@ use return_revert::ReturnRevert;
 use returndatacopy::Returndatacopy;
 use returndatasize::Returndatasize;
 use selfbalance::Selfbalance;
+use selfdestruct::SelfDestruct;
 use sload::Sload;
 use sstore::Sstore;
 use stackonlyop::StackPopOnlyOpcode;
 use stop::Stop;
 use swap::Swap;
+use tload::Tload;
+use tstore::Tstore;
 
 /// Generic opcode trait which defines the logic of the
 /// [`Operation`](crate::operation::Operation) that should be generated for one
@@ -219,6 +230,7 @@ fn fn_gen_associated_ops(opcode_id: &OpcodeId) -> FnGenAssociatedOps {
         OpcodeId::RETURNDATACOPY => Returndatacopy::gen_associated_ops,
         OpcodeId::EXTCODEHASH => Extcodehash::gen_associated_ops,
         OpcodeId::BLOCKHASH => Blockhash::gen_associated_ops,
+        OpcodeId::BLOBHASH => Blobhash::gen_associated_ops,
         OpcodeId::COINBASE => GetBlockHeaderField::<{ OpcodeId::COINBASE }>::gen_associated_ops,
         OpcodeId::TIMESTAMP => GetBlockHeaderField::<{ OpcodeId::TIMESTAMP }>::gen_associated_ops,
         OpcodeId::NUMBER => GetBlockHeaderField::<{ OpcodeId::NUMBER }>::gen_associated_ops,
@@ -233,6 +245,9 @@ fn fn_gen_associated_ops(opcode_id: &OpcodeId) -> FnGenAssociatedOps {
         OpcodeId::MSTORE8 => Mstore::<true>::gen_associated_ops,
         OpcodeId::SLOAD => Sload::gen_associated_ops,
         OpcodeId::SSTORE => Sstore::gen_associated_ops,
+        OpcodeId::TLOAD => Tload::gen_associated_ops,
+        OpcodeId::TSTORE => Tstore::gen_associated_ops,
+        OpcodeId::MCOPY => Mcopy::gen_associated_ops,
         OpcodeId::JUMP => StackPopOnlyOpcode::<1>::gen_associated_ops,
         OpcodeId::JUMPI => StackPopOnlyOpcode::<2>::gen_associated_ops,
         OpcodeId::PC => Pc::gen_associated_ops,
@@ -282,10 +297,7 @@ fn fn_gen_associated_ops(opcode_id: &OpcodeId) -> FnGenAssociatedOps {
         OpcodeId::CREATE2 => Create::<true>::gen_associated_ops,
         OpcodeId::RETURN | OpcodeId::REVERT => ReturnRevert::gen_associated_ops,
         OpcodeId::INVALID(_) => Stop::gen_associated_ops,
-        OpcodeId::SELFDESTRUCT => {
-            log::debug!("Using dummy gen_selfdestruct_ops for opcode SELFDESTRUCT");
-            DummySelfDestruct::gen_associated_ops
-        }
+        OpcodeId::SELFDESTRUCT => SelfDestruct::gen_associated_ops,
         _ => {
             log::debug!("Using dummy gen_associated_ops for opcode {:?}", opcode_id);
             Dummy::gen_associated_ops
@@ -338,6 +350,9 @@ fn fn_gen_error_state_associated_ops(
         ExecError::OutOfGas(OogError::AccountAccess) => {
             Some(ErrorOOGAccountAccess::gen_associated_ops)
         }
+        ExecError::OutOfGas(OogError::SelfDestruct) => {
+            Some(ErrorOOGSelfDestruct::gen_associated_ops)
+        }
         // ExecError::
         ExecError::StackOverflow => Some(StackPopOnlyOpcode::<0, true>::gen_associated_ops),
         ExecError::StackUnderflow => Some(StackPopOnlyOpcode::<0, true>::gen_associated_ops),
@@ -528,6 +543,7 @@ pub fn gen_associated_steps(
     let fn_gen_associated_steps = match execution_step {
         ExecState::BeginTx => gen_begin_tx_steps,
         ExecState::EndTx => gen_end_tx_steps_adapt,
+        ExecState::DestructAccount => gen_destruct_account_steps,
         _ => {
             unreachable!()
         }
@@ -535,110 +551,3 @@ pub fn gen_associated_steps(
 
     fn_gen_associated_steps(state)
 }
-
-#[derive(Debug, Copy, Clone)]
-struct DummySelfDestruct;
-
-impl Opcode for DummySelfDestruct {
-    fn gen_associated_ops(
-        state: &mut CircuitInputStateRef,
-        geth_steps: &[GethExecStep],
-    ) -> Result<Vec<ExecStep>, Error> {
-        dummy_gen_selfdestruct_ops(state, geth_steps)
-    }
-}
-fn dummy_gen_selfdestruct_ops(
-    state: &mut CircuitInputStateRef,
-    geth_steps: &[GethExecStep],
-) -> Result<Vec<ExecStep>, Error> {
-    let geth_step = &geth_steps[0];
-    let mut exec_step = state.new_step(geth_step)?;
-    let sender = state.call()?.address;
-    let receiver = state.call_ctx_mut()?.stack.pop()?.to_address();
-    #[cfg(feature = "enable-stack")]
-    assert_eq!(receiver, geth_step.stack.last()?.to_address());
-
-    let is_warm = state.sdb.check_account_in_access_list(&receiver);
-    state.push_op_reversible(
-        &mut exec_step,
-        TxAccessListAccountOp {
-            tx_id: state.tx_ctx.id(),
-            address: receiver,
-            is_warm: true,
-            is_warm_prev: is_warm,
-        },
-    )?;
-
-    let (found, receiver_account) = state.sdb.get_account(&receiver);
-    if !found {
-        return Err(Error::AccountNotFound(receiver));
-    }
-    let receiver_account = &receiver_account.clone();
-    let (found, sender_account) = state.sdb.get_account(&sender);
-    if !found {
-        return Err(Error::AccountNotFound(sender));
-    }
-    let sender_account = &sender_account.clone();
-    let value = sender_account.balance;
-    log::trace!(
-        "self destruct, sender {:?} receiver {:?} value {:?}",
-        sender,
-        receiver,
-        value
-    );
-    // NOTE: In this dummy implementation we assume that the receiver already
-    // exists.
-
-    state.push_op_reversible(
-        &mut exec_step,
-        AccountOp {
-            address: sender,
-            field: AccountField::Balance,
-            value: Word::zero(),
-            value_prev: value,
-        },
-    )?;
-    state.push_op_reversible(
-        &mut exec_step,
-        AccountOp {
-            address: sender,
-            field: AccountField::Nonce,
-            value: Word::zero(),
-            value_prev: sender_account.nonce,
-        },
-    )?;
-    state.push_op_reversible(
-        &mut exec_step,
-        AccountOp {
-            address: sender,
-            field: AccountField::CodeHash,
-            value: Word::zero(),
-            value_prev: sender_account.code_hash.to_word(),
-        },
-    )?;
-    if receiver != sender {
-        state.transfer_to(
-            &mut exec_step,
-            receiver,
-            !receiver_account.is_empty(),
-            false,
-            value,
-            true,
-        )?;
-    }
-
-    if state.call()?.is_persistent {
-        state.sdb.destruct_account(sender);
-    }
-
-    if let Ok(caller) = state.caller_ctx_mut() {
-        caller.return_data.clear();
-    }
-    state.handle_return(
-        (None, None),
-        &mut [&mut exec_step],
-        geth_steps,
-        !state.call()?.is_root,
-    )?;
-    Ok(vec![exec_step])
-}
@@ -9,7 +9,8 @@ use crate::{
     },
     l2_predeployed::l1_gas_price_oracle,
     operation::{
-        AccountField, AccountOp, CallContextField, StorageOp, TxReceiptField, TxRefundOp, RW,
+        AccountField, AccountOp, CallContextField, StorageOp, TxDestructedAccountOp,
+        TxReceiptField, TxRefundOp, RW,
     },
     precompile::{execute_precompiled, is_precompiled, PrecompileCalls},
     state_db::CodeDB,
@@ -344,6 +345,9 @@ pub fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<Vec<ExecSt
                     value_prev: 0.into(),
                 },
             )?;
+            // EIP-6780, record the creation for a later SELFDESTRUCT
+            #[cfg(feature = "shanghai")]
+            state.tx_created_account_write(&mut exec_step, call.address)?;
             for (field, value) in [
                 (CallContextField::Depth, call.depth.into()),
                 (
@@ -422,11 +426,9 @@ pub fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<Vec<ExecSt
 
             // insert a copy event (input) generate word memory read for input.
             // we do not handle output / return since it is not part of the mined tx
-            let n_input_bytes = if let Some(input_len) = precompile_call.input_len() {
-                std::cmp::min(input_len as u64, call.call_data_length)
-            } else {
-                call.call_data_length
-            };
+            let n_input_bytes = precompile_call
+                .n_input_bytes(call.call_data_length as usize, &state.tx.input)
+                as u64;
             // we copy the truncated part or whole call data
             let src_addr = call.call_data_offset;
             let src_addr_end = call.call_data_offset.checked_add(n_input_bytes).unwrap();
@@ -653,6 +655,114 @@ pub fn gen_end_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Er
     Ok(exec_step)
 }
 
+/// Generate the DestructAccount steps after EndTx. For each account self
+/// destructed in the tx, one step clears each of its non-zero storage slots
+/// and a last step clears its code hash, nonce and balance.
+///
+/// Only the storage slots known to the StateDB (from the trace) are cleared,
+/// the storage trie itself can't be deleted.
+pub fn gen_destruct_account_steps(
+    state: &mut CircuitInputStateRef,
+) -> Result<Vec<ExecStep>, Error> {
+    let call = state.tx.calls()[0].clone();
+    let tx_id = state.tx_ctx.id();
+    let mut exec_steps = vec![];
+
+    for address in state.sdb.destructed_accounts() {
+        for key in state.sdb.non_zero_storage_keys(&address) {
+            let mut exec_step = state.new_destruct_account_step();
+            state.call_context_read(
+                &mut exec_step,
+                call.call_id,
+                CallContextField::TxId,
+                tx_id.into(),
+            )?;
+            state.push_op(
+                &mut exec_step,
+                RW::READ,
+                TxDestructedAccountOp {
+                    tx_id,
+                    address,
+                    is_destructed: true,
+                    is_destructed_prev: true,
+                },
+            )?;
+            let value_prev = *state.sdb.get_storage(&address, &key).1;
+            let committed_value = *state.sdb.get_committed_storage(&address, &key).1;
+            state.push_op(
+                &mut exec_step,
+                RW::WRITE,
+                StorageOp::new(
+                    address,
+                    key,
+                    Word::zero(),
+                    value_prev,
+                    tx_id,
+                    committed_value,
+                ),
+            )?;
+            state.sdb.set_storage(&address, &key, &Word::zero());
+            exec_steps.push(exec_step);
+        }
+
+        let mut exec_step = state.new_destruct_account_step();
+        state.call_context_read(
+            &mut exec_step,
+            call.call_id,
+            CallContextField::TxId,
+            tx_id.into(),
+        )?;
+        state.push_op(
+            &mut exec_step,
+            RW::WRITE,
+            TxDestructedAccountOp {
+                tx_id,
+                address,
+                is_destructed: false,
+                is_destructed_prev: true,
+            },
+        )?;
+        state.sdb.remove_destructed_account(&address);
+
+        let account = state.sdb.get_account(&address).1.clone();
+        // Write code hash first, the nonce of a contract account is at least 1 so it's still
+        // non-empty at this point.
+        state.account_write(
+            &mut exec_step,
+            address,
+            AccountField::CodeHash,
+            Word::zero(),
+            account.code_hash_read().to_word(),
+        )?;
+        state.account_write(
+            &mut exec_step,
+            address,
+            AccountField::Nonce,
+            Word::zero(),
+            account.nonce,
+        )?;
+        // The account may receive balance after SELFDESTRUCT in the same tx, which is burnt.
+        state.account_read(
+            &mut exec_step,
+            address,
+            AccountField::Balance,
+            account.balance,
+        )?;
+        if !account.balance.is_zero() {
+            state.account_write(
+                &mut exec_step,
+                address,
+                AccountField::Balance,
+                Word::zero(),
+                account.balance,
+            )?;
+        }
+        exec_steps.push(exec_step);
+    }
+
+    Ok(exec_steps)
+}
+
 pub(crate) fn begin_tx(
     state: &mut CircuitInputStateRef,
     exec_step: &mut ExecStep,
@@ -0,0 +1,46 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::CallContextField,
+    Error,
+};
+use eth_types::{GethExecStep, ToWord, Word};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the [`OpcodeId::BLOBHASH`](crate::evm::OpcodeId::BLOBHASH) `OpcodeId`.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Blobhash;
+
+impl Opcode for Blobhash {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        let index = state.stack_pop(&mut exec_step)?;
+        #[cfg(feature = "enable-stack")]
+        assert_eq!(index, geth_step.stack.last()?);
+
+        let tx_id = state.tx_ctx.id();
+        state.call_context_read(
+            &mut exec_step,
+            state.call()?.call_id,
+            CallContextField::TxId,
+            tx_id.into(),
+        )?;
+
+        // Out of range index returns zero.
+        let blob_hash = if index < state.tx.blob_versioned_hashes.len().into() {
+            state.tx.blob_versioned_hashes[index.as_usize()].to_word()
+        } else {
+            Word::zero()
+        };
+        #[cfg(feature = "enable-stack")]
+        assert_eq!(blob_hash, geth_steps[1].stack.last()?);
+        state.stack_push(&mut exec_step, blob_hash)?;
+
+        Ok(vec![exec_step])
+    }
+}
@@ -384,11 +384,10 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                 // rws also handle prev bytes internally.
                 let rw_counter_start = state.block_ctx.rwc;
                 let input_bytes = if callee_call.call_data_length > 0 {
-                    let n_input_bytes = if let Some(input_len) = precompile_call.input_len() {
-                        min(input_len, callee_call.call_data_length as usize)
-                    } else {
-                        callee_call.call_data_length as usize
-                    };
+                    let n_input_bytes = precompile_call.n_input_bytes(
+                        callee_call.call_data_length as usize,
+                        &state.caller_ctx()?.memory.0[args_offset..args_offset + args_length],
+                    );
                     let copy_steps = state.gen_copy_steps_for_precompile_calldata(
                         &mut exec_step,
                         callee_call.call_data_offset,
@@ -490,8 +489,14 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                 } else {
                     None
                 };
-                // modexp's oog error is handled in ModExpGadget
-                if has_oog_err && precompile_call != PrecompileCalls::Modexp {
+                // modexp's and blake2f's oog errors are handled in ModExpGadget and
+                // Blake2fGadget respectively
+                if has_oog_err
+                    && !matches!(
+                        precompile_call,
+                        PrecompileCalls::Modexp | PrecompileCalls::Blake2F
+                    )
+                {
                     log::debug!(
                         "precompile call ({:?}) runs out of gas: callee_gas_left_with_stipend = {}",
                         precompile_call,
@@ -813,9 +818,6 @@ pub mod tests {
                 address: Word::from(0x3),
                 stack_value: vec![(
                     Word::from(0x20),
-                    #[cfg(feature = "scroll")]
-                    Word::zero(),
-                    #[cfg(not(feature = "scroll"))]
                     word!("2c0c45d3ecab80fe060e5f1d7057cd2f8de5e557"),
                 )],
                 ..Default::default()
@@ -1005,16 +1007,10 @@ pub mod tests {
                 stack_value: vec![
                     (
                         Word::from(0x20),
-                        #[cfg(feature = "scroll")]
-                        word!("3af54fa5d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e13"),
-                        #[cfg(not(feature = "scroll"))]
                         word!("d282e6ad7f520e511f6c3e2b8c68059b9442be0454267ce079217e1319cde05b"),
                     ),
                     (
                         Word::from(0x0),
-                        #[cfg(feature = "scroll")]
-                        word!("0000000048c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f"),
-                        #[cfg(not(feature = "scroll"))]
                         word!("8c9bcf367e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5"),
                     ),
                 ],
@@ -287,6 +287,9 @@ impl<const IS_CREATE2: bool> Opcode for Create<IS_CREATE2> {
                     value_prev: 0.into(),
                 },
             )?;
+            // EIP-6780, record the creation for a later SELFDESTRUCT
+            #[cfg(feature = "shanghai")]
+            state.tx_created_account_write(&mut exec_step, callee.address)?;
 
             if length > 0 {
                 for (field, value) in [
@@ -0,0 +1,74 @@
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    error::{ExecError, OogError},
+    evm::{Opcode, OpcodeId},
+    operation::{AccountField, CallContextField, TxAccessListAccountOp, RW},
+    Error,
+};
+use eth_types::{GethExecStep, ToAddress, ToWord, U256};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the out of gas error of
+/// [`OpcodeId::SELFDESTRUCT`](crate::evm::OpcodeId::SELFDESTRUCT).
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct ErrorOOGSelfDestruct;
+
+impl Opcode for ErrorOOGSelfDestruct {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+        exec_step.error = Some(ExecError::OutOfGas(OogError::SelfDestruct));
+
+        assert_eq!(geth_step.op, OpcodeId::SELFDESTRUCT);
+        let beneficiary_word = state.stack_pop(&mut exec_step)?;
+        let beneficiary = beneficiary_word.to_address();
+        #[cfg(feature = "enable-stack")]
+        assert_eq!(beneficiary_word, geth_step.stack.last()?);
+
+        let call = state.call()?.clone();
+        state.call_context_read(
+            &mut exec_step,
+            call.call_id,
+            CallContextField::TxId,
+            U256::from(state.tx_ctx.id()),
+        )?;
+
+        // read `is_warm` state of beneficiary
+        let is_warm = state.sdb.check_account_in_access_list(&beneficiary);
+        state.push_op(
+            &mut exec_step,
+            RW::READ,
+            TxAccessListAccountOp {
+                tx_id: state.tx_ctx.id(),
+                address: beneficiary,
+                is_warm,
+                is_warm_prev: is_warm,
+            },
+        )?;
+
+        // The NEW_ACCOUNT gas is charged if the beneficiary doesn't exist and the balance of
+        // current account is not zero.
+        state.call_context_read(
+            &mut exec_step,
+            call.call_id,
+            CallContextField::CalleeAddress,
+            call.address.to_word(),
+        )?;
+        let beneficiary_code_hash = state.sdb.get_account(&beneficiary).1.code_hash_read();
+        state.account_read(
+            &mut exec_step,
+            beneficiary,
+            AccountField::CodeHash,
+            beneficiary_code_hash.to_word(),
+        )?;
+        let balance = state.sdb.get_balance(&call.address);
+        state.account_read(&mut exec_step, call.address, AccountField::Balance, balance)?;
+
+        // common error handling
+        state.handle_return((None, None), &mut [&mut exec_step], geth_steps, true)?;
+        Ok(vec![exec_step])
+    }
+}
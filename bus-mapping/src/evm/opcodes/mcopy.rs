@@ -0,0 +1,208 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{
+        CircuitInputStateRef, CopyBytes, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
+    },
+    Error,
+};
+use eth_types::{GethExecStep, Word};
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Mcopy;
+
+impl Opcode for Mcopy {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+        let dst_offset = state.stack_pop(&mut exec_step)?;
+        let src_offset = state.stack_pop(&mut exec_step)?;
+        let length = state.stack_pop(&mut exec_step)?;
+
+        #[cfg(feature = "enable-stack")]
+        {
+            assert_eq!(dst_offset, geth_step.stack.nth_last(0)?);
+            assert_eq!(src_offset, geth_step.stack.nth_last(1)?);
+            assert_eq!(length, geth_step.stack.nth_last(2)?);
+        }
+
+        let copy_event = gen_copy_event(state, dst_offset, src_offset, length, &mut exec_step)?;
+        state.push_copy(&mut exec_step, copy_event);
+        Ok(vec![exec_step])
+    }
+}
+
+fn gen_copy_event(
+    state: &mut CircuitInputStateRef,
+    dst_offset: Word,
+    src_offset: Word,
+    length: Word,
+    exec_step: &mut ExecStep,
+) -> Result<CopyEvent, Error> {
+    let rw_counter_start = state.block_ctx.rwc;
+
+    // Get low Uint64 of offsets, they are in range if length is non-zero.
+    let (dst_addr, src_addr, length) =
+        (dst_offset.low_u64(), src_offset.low_u64(), length.as_u64());
+
+    let (read_steps, write_steps, prev_bytes) =
+        state.gen_copy_steps_for_memory_to_memory(exec_step, src_addr, dst_addr, length)?;
+
+    let call_id = state.call()?.call_id;
+    Ok(CopyEvent {
+        src_type: CopyDataType::Memory,
+        src_id: NumberOrHash::Number(call_id),
+        src_addr,
+        src_addr_end: src_addr + length,
+        dst_type: CopyDataType::Memory,
+        dst_id: NumberOrHash::Number(call_id),
+        dst_addr,
+        log_id: None,
+        rw_counter_start,
+        copy_bytes: CopyBytes::new(read_steps, Some(write_steps), Some(prev_bytes)),
+        access_list: vec![],
+    })
+}
+
+#[cfg(all(test, feature = "cancun"))]
+mod mcopy_tests {
+    use crate::{
+        circuit_input_builder::{CopyDataType, ExecState, NumberOrHash},
+        mock::BlockData,
+        operation::{StackOp, RW},
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{MemoryAddress, OpcodeId, StackAddress},
+        geth_types::GethData,
+        Word,
+    };
+    use mock::test_ctx::{helpers::*, TestContext};
+    use pretty_assertions::assert_eq;
+
+    fn test_ok(dst_offset: usize, src_offset: usize, length: usize) {
+        // Fill the first 0x80 bytes of memory with 0x01, 0x02, ..., 0x80.
+        let mut code = bytecode! {};
+        for i in 0..4u8 {
+            let word: Vec<u8> = (0..32u8).map(|j| i * 32 + j + 1).collect();
+            code.push(32, Word::from_big_endian(&word));
+            code.push(1, 32 * i as u64);
+            code.write_op(OpcodeId::MSTORE);
+        }
+        code.append(&bytecode! {
+            PUSH32(length)
+            PUSH32(src_offset)
+            PUSH32(dst_offset)
+            MCOPY
+            STOP
+        });
+
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::MCOPY))
+            .unwrap();
+
+        let call_id = builder.block.txs()[0].calls()[0].call_id;
+        assert_eq!(
+            [0, 1, 2]
+                .map(|idx| &builder.block.container.stack[step.bus_mapping_instance[idx].as_usize()])
+                .map(|operation| (operation.rw(), operation.op())),
+            [
+                (
+                    RW::READ,
+                    &StackOp::new(call_id, StackAddress::from(1021), Word::from(dst_offset))
+                ),
+                (
+                    RW::READ,
+                    &StackOp::new(call_id, StackAddress::from(1022), Word::from(src_offset))
+                ),
+                (
+                    RW::READ,
+                    &StackOp::new(call_id, StackAddress::from(1023), Word::from(length))
+                ),
+            ]
+        );
+
+        // The expected memory after the copy, with the semantics of memmove.
+        let mut memory: Vec<u8> = (1..=0x80u8).collect();
+        memory.resize(
+            memory
+                .len()
+                .max(dst_offset + length)
+                .max(src_offset + length),
+            0,
+        );
+        memory.copy_within(src_offset..src_offset + length, dst_offset);
+
+        let copy_events = &builder.block.copy_events;
+        assert_eq!(copy_events.len(), 1);
+        let copy_event = &copy_events[0];
+        assert_eq!(copy_event.src_type, CopyDataType::Memory);
+        assert_eq!(copy_event.dst_type, CopyDataType::Memory);
+        assert_eq!(copy_event.src_id, NumberOrHash::Number(call_id));
+        assert_eq!(copy_event.dst_id, NumberOrHash::Number(call_id));
+        assert_eq!(copy_event.src_addr as usize, src_offset);
+        assert_eq!(copy_event.src_addr_end as usize, src_offset + length);
+        assert_eq!(copy_event.dst_addr as usize, dst_offset);
+
+        // All the word reads come before all the word writes.
+        let n_words = copy_event.full_length() as usize / 32;
+        let memory_ops: Vec<_> = step.bus_mapping_instance[3..]
+            .iter()
+            .map(|op_ref| &builder.block.container.memory[op_ref.as_usize()])
+            .collect();
+        assert_eq!(memory_ops.len(), 2 * n_words);
+        assert!(memory_ops[..n_words].iter().all(|op| op.rw() == RW::READ));
+        assert!(memory_ops[n_words..].iter().all(|op| op.rw() == RW::WRITE));
+
+        let dst_slot = dst_offset - dst_offset % 32;
+        for (i, op) in memory_ops[n_words..].iter().enumerate() {
+            let address = dst_slot + i * 32;
+            let expected = Word::from_big_endian(&memory[address..address + 32]);
+            assert_eq!(op.op().address(), &MemoryAddress::from(address));
+            assert_eq!(op.op().value(), expected);
+        }
+    }
+
+    #[test]
+    fn mcopy_non_overlapping() {
+        test_ok(0x80, 0x00, 0x40);
+    }
+
+    #[test]
+    fn mcopy_overlapping_forward() {
+        test_ok(0x10, 0x00, 0x50);
+    }
+
+    #[test]
+    fn mcopy_overlapping_backward() {
+        test_ok(0x00, 0x21, 0x50);
+    }
+
+    #[test]
+    fn mcopy_same_range() {
+        test_ok(0x20, 0x20, 0x40);
+    }
+
+    #[test]
+    fn mcopy_zero_length() {
+        test_ok(0x1000, 0x2000, 0x00);
+    }
+}
@@ -0,0 +1,24 @@
+use crate::{
+    circuit_input_builder::{Blake2fOp, PrecompileEvent},
+    precompile::{Blake2fAuxData, PrecompileAuxData},
+};
+
+pub(crate) fn opt_data(
+    input_bytes: &[u8],
+    output_bytes: &[u8],
+    return_bytes: &[u8],
+) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
+    let aux_data = Blake2fAuxData::new(input_bytes, output_bytes, return_bytes);
+    // only a successful call (well-formed input and enough gas) is verified by the blake2f
+    // circuit.
+    let opt_event = if output_bytes.is_empty() {
+        None
+    } else {
+        Some(PrecompileEvent::Blake2f(Blake2fOp::new_from_bytes(
+            input_bytes,
+            output_bytes,
+        )))
+    };
+
+    (opt_event, Some(PrecompileAuxData::Blake2f(aux_data)))
+}
@@ -1,11 +1,15 @@
 use eth_types::U256;
+use halo2_proofs::halo2curves::{
+    bn256::{multi_miller_loop, G2Prepared, Gt},
+    pairing::MillerLoopResult,
+};
 use itertools::Itertools;
 
 use crate::{
     circuit_input_builder::{
         EcPairingOp, EcPairingPair, PrecompileEvent, N_BYTES_PER_PAIR, N_PAIRING_PER_OP,
     },
-    precompile::{EcPairingAuxData, EcPairingError, PrecompileAuxData},
+    precompile::{EcPairingAuxData, EcPairingError, PrecompileAuxData, EC_PAIRING_MAX_INPUT_LEN},
 };
 
 pub(crate) fn opt_data(
@@ -37,9 +41,9 @@ pub(crate) fn opt_data(
         );
     }
 
-    let op = if !input_bytes.is_empty() {
-        if (input_bytes.len() > N_PAIRING_PER_OP * N_BYTES_PER_PAIR)
-            || (input_bytes.len() % N_BYTES_PER_PAIR != 0)
+    let ops = if !input_bytes.is_empty() {
+        if (input_bytes.len() % N_BYTES_PER_PAIR != 0)
+            || EC_PAIRING_MAX_INPUT_LEN.is_some_and(|max_len| input_bytes.len() > max_len)
         {
             return (
                 None,
@@ -48,12 +52,8 @@ pub(crate) fn opt_data(
                 )))),
             );
         }
-        debug_assert!(
-            input_bytes.len() % N_BYTES_PER_PAIR == 0
-                && input_bytes.len() <= N_PAIRING_PER_OP * N_BYTES_PER_PAIR
-        );
         // process input bytes.
-        let mut pairs = input_bytes
+        let pairs = input_bytes
             .chunks_exact(N_BYTES_PER_PAIR)
             .map(|chunk| {
                 // process <= 192 bytes chunk at a time.
@@ -71,30 +71,85 @@ pub(crate) fn opt_data(
                 }
             })
             .collect_vec();
-        // pad the pairs to make them of fixed size: N_PAIRING_PER_OP.
-        pairs.resize(N_PAIRING_PER_OP, EcPairingPair::padding_pair());
-        EcPairingOp {
-            pairs: <[_; N_PAIRING_PER_OP]>::try_from(pairs).unwrap(),
-            output: pairing_check,
-            input_bytes: input_bytes.to_vec(),
-            output_bytes: output_bytes.to_vec(),
-            return_bytes: return_bytes.to_vec(),
-        }
+        // split the pairs into chained ops of N_PAIRING_PER_OP pairs each, padding the last one.
+        // The product of the Miller loop results of the chain so far is carried from op to op, it
+        // is `None` once an invalid pair is met.
+        let n_ops = pairs.len().div_ceil(N_PAIRING_PER_OP);
+        let mut miller_loop_acc = Some(Gt::identity());
+        pairs
+            .chunks(N_PAIRING_PER_OP)
+            .enumerate()
+            .map(|(idx, chunk)| {
+                let is_last = idx + 1 == n_ops;
+                let mut op_pairs = chunk.to_vec();
+                op_pairs.resize(N_PAIRING_PER_OP, EcPairingPair::padding_pair());
+                EcPairingOp {
+                    pairs: <[_; N_PAIRING_PER_OP]>::try_from(op_pairs).unwrap(),
+                    output: if is_last {
+                        pairing_check
+                    } else {
+                        // the partial pairing check over all pairs processed so far.
+                        miller_loop_acc =
+                            miller_loop_acc.and_then(|acc| Some(acc + miller_loop_of(chunk)?));
+                        let partial_check = miller_loop_acc
+                            .as_ref()
+                            .is_some_and(|acc| acc.final_exponentiation() == Gt::identity());
+                        U256::from(partial_check as u64)
+                    },
+                    chained: idx > 0,
+                    input_bytes: if is_last {
+                        input_bytes.to_vec()
+                    } else {
+                        vec![]
+                    },
+                    output_bytes: if is_last {
+                        output_bytes.to_vec()
+                    } else {
+                        vec![]
+                    },
+                    return_bytes: if is_last {
+                        return_bytes.to_vec()
+                    } else {
+                        vec![]
+                    },
+                }
+            })
+            .collect_vec()
     } else {
         let pairs = [EcPairingPair::padding_pair(); N_PAIRING_PER_OP];
-        EcPairingOp {
+        vec![EcPairingOp {
             pairs,
             output: pairing_check,
+            chained: false,
             input_bytes: vec![],
             output_bytes: output_bytes.to_vec(),
             return_bytes: return_bytes.to_vec(),
-        }
+        }]
     };
+    let op = ops.last().cloned().expect("at least one ecPairing op");
 
     (
-        Some(PrecompileEvent::EcPairing(Box::new(op.clone()))),
+        Some(PrecompileEvent::EcPairing(ops)),
         Some(PrecompileAuxData::EcPairing(Box::new(Ok(
             EcPairingAuxData(op),
         )))),
     )
 }
+
+/// Returns the Miller loop result of the pairs, or `None` if any of them is invalid.
+fn miller_loop_of(pairs: &[EcPairingPair]) -> Option<Gt> {
+    let g1_g2s = pairs
+        .iter()
+        .map(|pair| pair.as_g1_g2())
+        .collect::<Option<Vec<_>>>()?;
+    let g2_prepared = g1_g2s
+        .iter()
+        .map(|(_, g2)| G2Prepared::from(*g2))
+        .collect_vec();
+    let terms = g1_g2s
+        .iter()
+        .zip(g2_prepared.iter())
+        .map(|((g1, _), g2)| (g1, g2))
+        .collect_vec();
+    Some(multi_miller_loop(&terms))
+}
@@ -2,19 +2,21 @@ use eth_types::{GethExecStep, ToWord, Word};
 
 use crate::{
     circuit_input_builder::{
-        Call, CircuitInputStateRef, ExecState, ExecStep, PrecompileEvent, SHA256,
+        Call, CircuitInputStateRef, ExecState, ExecStep, PrecompileEvent, RIPEMD160, SHA256,
     },
     operation::CallContextField,
     precompile::{PrecompileAuxData, PrecompileCalls},
     Error,
 };
 
+mod blake2f;
 mod ec_add;
 mod ec_mul;
 mod ec_pairing;
 mod ecrecover;
 mod modexp;
 
+use blake2f::opt_data as opt_data_blake2f;
 use ec_add::opt_data as opt_data_ec_add;
 use ec_mul::opt_data as opt_data_ec_mul;
 use ec_pairing::opt_data as opt_data_ec_pairing;
@@ -91,17 +93,25 @@ pub fn gen_ops(
                 return_bytes: return_bytes.to_vec(),
             }),
         ),
-        _ => {
-            log::warn!("precompile {:?} unsupported in circuits", precompile);
-            (
-                None,
-                Some(PrecompileAuxData::Base {
-                    input_bytes: input_bytes.to_vec(),
-                    output_bytes: output_bytes.to_vec(),
-                    return_bytes: return_bytes.to_vec(),
-                }),
-            )
-        }
+        PrecompileCalls::Ripemd160 => (
+            if output_bytes.is_empty() {
+                None
+            } else {
+                // the 20-bytes digest is left padded to 32 bytes
+                Some(PrecompileEvent::RIPEMD160(RIPEMD160 {
+                    input: input_bytes.to_vec(),
+                    digest: output_bytes[12..]
+                        .try_into()
+                        .expect("output bytes must be 32 bytes"),
+                }))
+            },
+            Some(PrecompileAuxData::RIPEMD160 {
+                input_bytes: input_bytes.to_vec(),
+                output_bytes: output_bytes.to_vec(),
+                return_bytes: return_bytes.to_vec(),
+            }),
+        ),
+        PrecompileCalls::Blake2F => opt_data_blake2f(input_bytes, output_bytes, return_bytes),
     };
     log::trace!("precompile event {opt_event:?}, aux data {aux_data:?}");
     if let Some(event) = opt_event {
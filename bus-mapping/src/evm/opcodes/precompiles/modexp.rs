@@ -3,7 +3,7 @@ use crate::{
     precompile::{ModExpAuxData, PrecompileAuxData},
 };
 
-use eth_types::Word;
+use num::BigUint;
 
 pub(crate) fn opt_data(
     input_bytes: &[u8],
@@ -12,11 +12,16 @@ pub(crate) fn opt_data(
 ) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
     let aux_data = ModExpAuxData::new(input_bytes, output_bytes, return_bytes);
     if aux_data.valid {
+        // the result is computed instead of taken from the output, which is empty if the call
+        // runs out of gas, as the exponent bit length is still looked up for the gas cost.
         let event = BigModExp {
-            base: Word::from_big_endian(&aux_data.inputs[0]),
-            exponent: Word::from_big_endian(&aux_data.inputs[1]),
-            modulus: Word::from_big_endian(&aux_data.inputs[2]),
-            result: Word::from_big_endian(&aux_data.output),
+            base: BigUint::from_bytes_be(&aux_data.inputs[0]),
+            exponent: BigUint::from_bytes_be(&aux_data.inputs[1]),
+            modulus: BigUint::from_bytes_be(&aux_data.inputs[2]),
+            result: BigUint::from_bytes_be(&aux_data.result()),
+            base_len: aux_data.input_lens[0].as_usize(),
+            exponent_len: aux_data.input_lens[1].as_usize(),
+            modulus_len: aux_data.input_lens[2].as_usize(),
         };
         (
             Some(PrecompileEvent::ModExp(event)),
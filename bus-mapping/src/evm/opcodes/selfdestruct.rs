@@ -0,0 +1,474 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::{AccountField, AccountOp, CallContextField, TxAccessListAccountOp},
+    Error,
+};
+use eth_types::{GethExecStep, ToAddress, ToWord, Word};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the
+/// [`OpcodeId::SELFDESTRUCT`](crate::evm::OpcodeId::SELFDESTRUCT) `OpcodeId`.
+///
+/// The balance of the executing account is moved to the beneficiary. Without
+/// the `shanghai` feature the account is always destructed (and the balance is
+/// burnt if the beneficiary is the account itself). With the `shanghai`
+/// feature, EIP-6780 semantics apply and the account is only destructed when
+/// it has been created in the same transaction.
+///
+/// Like geth, the account keeps its code, nonce and storage until the end of
+/// the transaction: SELFDESTRUCT only marks it as destructed, and the
+/// `DestructAccount` steps after `EndTx` clear it.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct SelfDestruct;
+
+impl Opcode for SelfDestruct {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+        let call = state.call()?.clone();
+
+        let beneficiary_word = state.stack_pop(&mut exec_step)?;
+        let beneficiary = beneficiary_word.to_address();
+        #[cfg(feature = "enable-stack")]
+        assert_eq!(beneficiary_word, geth_step.stack.last()?);
+
+        for (field, value) in [
+            (CallContextField::TxId, state.tx_ctx.id().into()),
+            (CallContextField::IsStatic, (call.is_static as u64).into()),
+            (CallContextField::CalleeAddress, call.address.to_word()),
+        ] {
+            state.call_context_read(&mut exec_step, call.call_id, field, value)?;
+        }
+        state.reversion_info_read(&mut exec_step, &call)?;
+
+        let is_warm = state.sdb.check_account_in_access_list(&beneficiary);
+        state.push_op_reversible(
+            &mut exec_step,
+            TxAccessListAccountOp {
+                tx_id: state.tx_ctx.id(),
+                address: beneficiary,
+                is_warm: true,
+                is_warm_prev: is_warm,
+            },
+        )?;
+
+        // Read beneficiary's code hash to check whether it exists, since the beneficiary is
+        // created (and charged NEW_ACCOUNT gas) if it's empty and the value is not zero.
+        let beneficiary_account = state.sdb.get_account(&beneficiary).1.clone();
+        let beneficiary_exists = !beneficiary_account.is_empty();
+        state.account_read(
+            &mut exec_step,
+            beneficiary,
+            AccountField::CodeHash,
+            beneficiary_account.code_hash_read().to_word(),
+        )?;
+
+        let address = call.address;
+        let (found, account) = state.sdb.get_account(&address);
+        if !found {
+            return Err(Error::AccountNotFound(address));
+        }
+        let account = account.clone();
+        let value = account.balance;
+        state.account_read(&mut exec_step, address, AccountField::Balance, value)?;
+
+        let is_self = beneficiary == address;
+        // EIP-6780: SELFDESTRUCT only destructs the account if it's created in the same
+        // transaction.
+        #[cfg(feature = "shanghai")]
+        let is_destructed = state.tx_created_account_read(&mut exec_step, address)?;
+        #[cfg(not(feature = "shanghai"))]
+        let is_destructed = true;
+        log::trace!(
+            "self destruct, address {:?} beneficiary {:?} value {:?} is_destructed {}",
+            address,
+            beneficiary,
+            value,
+            is_destructed
+        );
+
+        if is_destructed {
+            state.tx_destructed_account_write(&mut exec_step, address)?;
+        }
+
+        // The balance is moved to the beneficiary, or burnt if the beneficiary is the destructed
+        // account itself.
+        if !value.is_zero() && (!is_self || is_destructed) {
+            state.push_op_reversible(
+                &mut exec_step,
+                AccountOp {
+                    address,
+                    field: AccountField::Balance,
+                    value: Word::zero(),
+                    value_prev: value,
+                },
+            )?;
+        }
+        if !is_self {
+            state.transfer_to(
+                &mut exec_step,
+                beneficiary,
+                beneficiary_exists,
+                false,
+                value,
+                true,
+            )?;
+        }
+
+        state.call_context_read(
+            &mut exec_step,
+            call.call_id,
+            CallContextField::IsSuccess,
+            1.into(),
+        )?;
+        state.handle_return(
+            (None, None),
+            &mut [&mut exec_step],
+            geth_steps,
+            !call.is_root,
+        )?;
+
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(all(test, not(feature = "scroll")))]
+mod selfdestruct_tests {
+    use crate::{
+        circuit_input_builder::ExecState,
+        mock::BlockData,
+        operation::{
+            AccountField, AccountOp, CallContextField, CallContextOp, StorageOp, Target,
+            TxDestructedAccountOp, RW,
+        },
+        state_db::CodeDB,
+    };
+    use eth_types::{
+        address, bytecode, evm_types::OpcodeId, geth_types::GethData, ToWord, Word, U256,
+    };
+    use mock::TestContext;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn selfdestruct_to_other_account() {
+        let beneficiary = address!("0x0000000000000000000000000000000000cafe02");
+        let code = bytecode! {
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        };
+
+        let block: GethData = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000000010"))
+                    .balance(Word::from(1000u64))
+                    .nonce(Word::one())
+                    .code(code.clone());
+                accs[1]
+                    .address(address!("0x0000000000000000000000000000000000cafe01"))
+                    .balance(Word::from(1u64 << 20));
+                accs[2].address(beneficiary).balance(Word::from(1u64 << 10));
+            },
+            |mut txs, accs| {
+                txs[0].to(accs[0].address).from(accs[1].address);
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let transaction = &builder.block.txs()[0];
+        let call_id = transaction.calls()[0].call_id;
+        let step = transaction
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::SELFDESTRUCT))
+            .unwrap();
+        let container = &builder.block.container;
+
+        let account_ops = step
+            .bus_mapping_instance
+            .iter()
+            .filter(|op_ref| op_ref.target() == Target::Account)
+            .map(|op_ref| {
+                let operation = &container.account[op_ref.as_usize()];
+                (operation.rw(), operation.op().clone())
+            })
+            .collect::<Vec<_>>();
+
+        let address = address!("0x0000000000000000000000000000000000000010");
+        let beneficiary_balance = Word::from(1u64 << 10);
+        let mut expected = vec![];
+        expected.push((
+            RW::READ,
+            AccountOp {
+                address: beneficiary,
+                field: AccountField::CodeHash,
+                value: CodeDB::empty_code_hash().to_word(),
+                value_prev: CodeDB::empty_code_hash().to_word(),
+            },
+        ));
+        expected.push((
+            RW::READ,
+            AccountOp {
+                address,
+                field: AccountField::Balance,
+                value: Word::from(1000u64),
+                value_prev: Word::from(1000u64),
+            },
+        ));
+        expected.push((
+            RW::WRITE,
+            AccountOp {
+                address,
+                field: AccountField::Balance,
+                value: Word::zero(),
+                value_prev: Word::from(1000u64),
+            },
+        ));
+        expected.push((
+            RW::WRITE,
+            AccountOp {
+                address: beneficiary,
+                field: AccountField::Balance,
+                value: beneficiary_balance + Word::from(1000u64),
+                value_prev: beneficiary_balance,
+            },
+        ));
+        assert_eq!(account_ops, expected);
+
+        let destructed_ops = step
+            .bus_mapping_instance
+            .iter()
+            .filter(|op_ref| op_ref.target() == Target::TxDestructedAccount)
+            .map(|op_ref| container.tx_destructed_account[op_ref.as_usize()].clone())
+            .collect::<Vec<_>>();
+        if cfg!(feature = "shanghai") {
+            assert!(destructed_ops.is_empty());
+        } else {
+            assert_eq!(destructed_ops.len(), 1);
+            assert_eq!(destructed_ops[0].rw(), RW::WRITE);
+            assert_eq!(
+                destructed_ops[0].op(),
+                &TxDestructedAccountOp {
+                    tx_id: 1,
+                    address,
+                    is_destructed: true,
+                    is_destructed_prev: false,
+                }
+            );
+        }
+
+        let is_success = step
+            .bus_mapping_instance
+            .iter()
+            .filter(|op_ref| op_ref.target() == Target::CallContext)
+            .map(|op_ref| container.call_context[op_ref.as_usize()].clone())
+            .last()
+            .unwrap();
+        assert_eq!(is_success.rw(), RW::READ);
+        assert_eq!(
+            is_success.op(),
+            &CallContextOp {
+                call_id,
+                field: CallContextField::IsSuccess,
+                value: Word::one(),
+            }
+        );
+    }
+
+    // The account keeps its code, nonce and storage until the end of the transaction, so calling
+    // back into it after SELFDESTRUCT still runs its code, and the DestructAccount steps after
+    // EndTx clear it.
+    #[cfg(not(feature = "shanghai"))]
+    #[test]
+    fn selfdestruct_then_call_in_same_tx() {
+        let beneficiary = address!("0x0000000000000000000000000000000000cafe02");
+        let destructed = address!("0x0000000000000000000000000000000000000010");
+        let code = bytecode! {
+            PUSH1(1)
+            SLOAD
+            POP
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        };
+        let mut caller_code = bytecode! {};
+        for _ in 0..2 {
+            caller_code.append(&bytecode! {
+                PUSH1(0)
+                PUSH1(0)
+                PUSH1(0)
+                PUSH1(0)
+                PUSH1(0)
+                PUSH20(destructed.to_word())
+                PUSH2(50000)
+                CALL
+                POP
+            });
+        }
+        caller_code.append(&bytecode! {
+            PUSH20(destructed.to_word())
+            EXTCODEHASH
+            PUSH1(0)
+            SSTORE
+            PUSH20(destructed.to_word())
+            EXTCODESIZE
+            PUSH1(1)
+            SSTORE
+            STOP
+        });
+
+        let block: GethData = TestContext::<4, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(destructed)
+                    .balance(Word::from(1000u64))
+                    .nonce(Word::one())
+                    .code(code.clone())
+                    .storage([(Word::one(), Word::from(0x1234u64))].into_iter());
+                accs[1]
+                    .address(address!("0x0000000000000000000000000000000000cafe01"))
+                    .balance(Word::from(1u64 << 20));
+                accs[2].address(beneficiary).balance(Word::from(1u64 << 10));
+                accs[3]
+                    .address(address!("0x0000000000000000000000000000000000000020"))
+                    .code(caller_code);
+            },
+            |mut txs, accs| {
+                txs[0].to(accs[3].address).from(accs[1].address);
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let transaction = &builder.block.txs()[0];
+        let container = &builder.block.container;
+        let code_hash = CodeDB::hash(&code.code()).to_word();
+
+        // Both calls run the code and reach SELFDESTRUCT.
+        let selfdestruct_steps = transaction
+            .steps()
+            .iter()
+            .filter(|step| step.exec_state == ExecState::Op(OpcodeId::SELFDESTRUCT))
+            .count();
+        assert_eq!(selfdestruct_steps, 2);
+
+        // EXTCODEHASH and EXTCODESIZE still see the code of the destructed account.
+        let caller_storage = container
+            .storage
+            .iter()
+            .filter(|op| op.rw() == RW::WRITE && op.op().address != destructed)
+            .map(|op| (op.op().key, op.op().value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            caller_storage,
+            vec![
+                (U256::zero(), code_hash),
+                (U256::one(), code.code().len().into())
+            ]
+        );
+
+        // The DestructAccount steps after EndTx clear the storage, then the account.
+        let exec_states = transaction
+            .steps()
+            .iter()
+            .map(|step| step.exec_state.clone())
+            .skip_while(|exec_state| *exec_state != ExecState::EndTx)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            exec_states,
+            vec![
+                ExecState::EndTx,
+                ExecState::DestructAccount,
+                ExecState::DestructAccount
+            ]
+        );
+        let steps = &transaction.steps()[transaction.steps().len() - 2..];
+
+        let storage_ops = steps[0]
+            .bus_mapping_instance
+            .iter()
+            .filter(|op_ref| op_ref.target() == Target::Storage)
+            .map(|op_ref| container.storage[op_ref.as_usize()].op().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            storage_ops,
+            vec![StorageOp::new(
+                destructed,
+                Word::one(),
+                Word::zero(),
+                Word::from(0x1234u64),
+                1,
+                Word::from(0x1234u64),
+            )]
+        );
+
+        let account_ops = steps[1]
+            .bus_mapping_instance
+            .iter()
+            .filter(|op_ref| op_ref.target() == Target::Account)
+            .map(|op_ref| container.account[op_ref.as_usize()].op().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            account_ops,
+            vec![
+                AccountOp {
+                    address: destructed,
+                    field: AccountField::CodeHash,
+                    value: Word::zero(),
+                    value_prev: code_hash,
+                },
+                AccountOp {
+                    address: destructed,
+                    field: AccountField::Nonce,
+                    value: Word::zero(),
+                    value_prev: Word::one(),
+                },
+                AccountOp {
+                    address: destructed,
+                    field: AccountField::Balance,
+                    value: Word::zero(),
+                    value_prev: Word::zero(),
+                },
+            ]
+        );
+
+        let destructed_ops = container
+            .tx_destructed_account
+            .iter()
+            .map(|op| (op.rw(), op.op().is_destructed_prev, op.op().is_destructed))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            destructed_ops,
+            vec![
+                (RW::WRITE, false, true),
+                (RW::WRITE, true, true),
+                (RW::READ, true, true),
+                (RW::WRITE, true, false),
+            ]
+        );
+        assert!(builder.sdb.get_account(&destructed).1.is_empty());
+        assert!(builder
+            .sdb
+            .get_storage(&destructed, &Word::one())
+            .1
+            .is_zero());
+    }
+}
@@ -24,6 +24,9 @@ impl fmt::Debug for OperationRef {
                 Target::CallContext => "CallContext",
                 Target::TxReceipt => "TxReceipt",
                 Target::TxLog => "TxLog",
+                Target::TransientStorage => "TransientStorage",
+                Target::TxCreatedAccount => "TxCreatedAccount",
+                Target::TxDestructedAccount => "TxDestructedAccount",
             },
             self.1
         ))
@@ -9,6 +9,7 @@ pub use eth_types::evm_types::{MemoryAddress, StackAddress};
 
 use core::{cmp::Ordering, fmt, fmt::Debug};
 use eth_types::{Address, Word};
+use serde::{Deserialize, Serialize};
 use std::mem::swap;
 
 /// Marker that defines whether an Operation performs a `READ` or a `WRITE`.
@@ -35,7 +36,7 @@ impl RW {
 /// Wrapper type over `usize` which represents the global counter. The purpose
 /// of the `RWCounter` is to enforce that each Opcode/Instruction and Operation
 /// is unique and just executed once.
-#[derive(Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct RWCounter(pub usize);
 
 impl fmt::Debug for RWCounter {
@@ -106,6 +107,12 @@ pub enum Target {
     TxReceipt,
     /// Means the target of the operation is the TxLog.
     TxLog,
+    /// Means the target of the operation is the TransientStorage.
+    TransientStorage,
+    /// Means the target of the operation is the TxCreatedAccount.
+    TxCreatedAccount,
+    /// Means the target of the operation is the TxDestructedAccount.
+    TxDestructedAccount,
 }
 
 /// Trait used for Operation Kinds.
@@ -392,6 +399,106 @@ impl Ord for StorageOp {
     }
 }
 
+/// Represents a [`READ`](RW::READ)/[`WRITE`](RW::WRITE) into the transient
+/// storage (EIP-1153) implied by a `TLOAD`/`TSTORE` step of the
+/// [`ExecStep`](crate::circuit_input_builder::ExecStep).
+///
+/// The transient storage is discarded at the end of every transaction, so the
+/// `tx_id` is part of the key and the value before the first access of a
+/// transaction is always 0.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TransientStorageOp {
+    /// Transaction ID: Transaction index in the block starting at 1.
+    pub tx_id: usize,
+    /// Account Address
+    pub address: Address,
+    /// Transient Storage Key
+    pub key: Word,
+    /// Transient Storage Value after the operation
+    pub value: Word,
+    /// Transient Storage Value before the operation
+    pub value_prev: Word,
+}
+
+impl fmt::Debug for TransientStorageOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TransientStorageOp { ")?;
+        f.write_fmt(format_args!(
+            "tx_id: {:?}, addr: {:?}, key: {:?}, val_prev: 0x{:x}, val: 0x{:x}",
+            self.tx_id, self.address, self.key, self.value_prev, self.value
+        ))?;
+        f.write_str(" }")
+    }
+}
+
+impl TransientStorageOp {
+    /// Create a new instance of a `TransientStorageOp` from it's components.
+    pub const fn new(
+        address: Address,
+        key: Word,
+        value: Word,
+        value_prev: Word,
+        tx_id: usize,
+    ) -> TransientStorageOp {
+        TransientStorageOp {
+            tx_id,
+            address,
+            key,
+            value,
+            value_prev,
+        }
+    }
+
+    /// Returns the [`Target`] (operation type) of this operation.
+    pub const fn target(&self) -> Target {
+        Target::TransientStorage
+    }
+
+    /// Returns the [`Address`] corresponding to this transient storage operation.
+    pub const fn address(&self) -> &Address {
+        &self.address
+    }
+
+    /// Returns the [`Word`] used as key for this operation.
+    pub const fn key(&self) -> &Word {
+        &self.key
+    }
+
+    /// Returns the [`Word`] read or written by this operation.
+    pub const fn value(&self) -> &Word {
+        &self.value
+    }
+
+    /// Returns the [`Word`] at key found previous to this operation.
+    pub const fn value_prev(&self) -> &Word {
+        &self.value_prev
+    }
+}
+
+impl Op for TransientStorageOp {
+    fn into_enum(self) -> OpEnum {
+        OpEnum::TransientStorage(self)
+    }
+
+    fn reverse(&self) -> Self {
+        let mut rev = self.clone();
+        swap(&mut rev.value, &mut rev.value_prev);
+        rev
+    }
+}
+
+impl PartialOrd for TransientStorageOp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TransientStorageOp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.tx_id, &self.address, &self.key).cmp(&(&other.tx_id, &other.address, &other.key))
+    }
+}
+
 /// Represents a change in the Account AccessList implied by a `BeginTx`,
 /// `EXTCODECOPY`, `EXTCODESIZE`, `EXTCODEHASH` `BALANCE`, `SELFDESTRUCT`,
 /// `*CALL`* or `CREATE*` step.
@@ -443,6 +550,106 @@ impl Op for TxAccessListAccountOp {
     }
 }
 
+/// Represents whether an account has been created in the current transaction,
+/// written by a creation `BeginTx` or a `CREATE*` step and read by a
+/// `SELFDESTRUCT` step to apply EIP-6780.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TxCreatedAccountOp {
+    /// Transaction ID: Transaction index in the block starting at 1.
+    pub tx_id: usize,
+    /// Account Address
+    pub address: Address,
+    /// Whether the account is created in the transaction after the operation.
+    pub is_created: bool,
+    /// Whether the account is created in the transaction before the operation.
+    pub is_created_prev: bool,
+}
+
+impl fmt::Debug for TxCreatedAccountOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TxCreatedAccountOp { ")?;
+        f.write_fmt(format_args!(
+            "tx_id: {:?}, addr: {:?}, is_created_prev: {:?}, is_created: {:?}",
+            self.tx_id, self.address, self.is_created_prev, self.is_created
+        ))?;
+        f.write_str(" }")
+    }
+}
+
+impl PartialOrd for TxCreatedAccountOp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TxCreatedAccountOp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.tx_id, &self.address).cmp(&(&other.tx_id, &other.address))
+    }
+}
+
+impl Op for TxCreatedAccountOp {
+    fn into_enum(self) -> OpEnum {
+        OpEnum::TxCreatedAccount(self)
+    }
+
+    fn reverse(&self) -> Self {
+        let mut rev = self.clone();
+        swap(&mut rev.is_created, &mut rev.is_created_prev);
+        rev
+    }
+}
+
+/// Represents whether an account is pending destruction at the end of the
+/// current transaction, set by a `SELFDESTRUCT` step and cleared by the
+/// `DestructAccount` step which destructs it after `EndTx`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TxDestructedAccountOp {
+    /// Transaction ID: Transaction index in the block starting at 1.
+    pub tx_id: usize,
+    /// Account Address
+    pub address: Address,
+    /// Whether the account is pending destruction after the operation.
+    pub is_destructed: bool,
+    /// Whether the account is pending destruction before the operation.
+    pub is_destructed_prev: bool,
+}
+
+impl fmt::Debug for TxDestructedAccountOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TxDestructedAccountOp { ")?;
+        f.write_fmt(format_args!(
+            "tx_id: {:?}, addr: {:?}, is_destructed_prev: {:?}, is_destructed: {:?}",
+            self.tx_id, self.address, self.is_destructed_prev, self.is_destructed
+        ))?;
+        f.write_str(" }")
+    }
+}
+
+impl PartialOrd for TxDestructedAccountOp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TxDestructedAccountOp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.tx_id, &self.address).cmp(&(&other.tx_id, &other.address))
+    }
+}
+
+impl Op for TxDestructedAccountOp {
+    fn into_enum(self) -> OpEnum {
+        OpEnum::TxDestructedAccount(self)
+    }
+
+    fn reverse(&self) -> Self {
+        let mut rev = self.clone();
+        swap(&mut rev.is_destructed, &mut rev.is_destructed_prev);
+        rev
+    }
+}
+
 /// Represents a change in the Storage AccessList implied by an `SSTORE` or
 /// `SLOAD` step of the [`ExecStep`](crate::circuit_input_builder::ExecStep).
 #[derive(Clone, PartialEq, Eq)]
@@ -947,6 +1154,12 @@ pub enum OpEnum {
     TxReceipt(TxReceiptOp),
     /// TxLog
     TxLog(TxLogOp),
+    /// TransientStorage
+    TransientStorage(TransientStorageOp),
+    /// TxCreatedAccount
+    TxCreatedAccount(TxCreatedAccountOp),
+    /// TxDestructedAccount
+    TxDestructedAccount(TxDestructedAccountOp),
     /// Start
     Start(StartOp),
 }
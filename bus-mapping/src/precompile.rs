@@ -1,10 +1,14 @@
 //! precompile helpers
 
 use eth_types::{evm_types::GasCost, Address, ToBigEndian, Word};
+use num::{BigUint, Zero};
 use revm_precompile::{Precompile, PrecompileError, Precompiles};
+use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
-use crate::circuit_input_builder::{EcMulOp, EcPairingOp, N_BYTES_PER_PAIR, N_PAIRING_PER_OP};
+use crate::circuit_input_builder::{
+    EcMulOp, EcPairingOp, BLAKE2F_INPUT_LEN, N_BYTES_PER_PAIR, N_PAIRING_PER_OP,
+};
 
 /// Check if address is a precompiled or not.
 pub fn is_precompiled(address: &Address) -> bool {
@@ -34,18 +38,16 @@ pub(crate) fn execute_precompiled(
                 // Revm behavior is different from scroll evm,
                 // so we need to override the behavior of invalid input
                 match PrecompileCalls::from(address.0[19]) {
-                    PrecompileCalls::Blake2F | PrecompileCalls::Ripemd160 => {
-                        (vec![], gas, false, false)
-                    }
                     PrecompileCalls::Bn128Pairing => {
-                        if input.len() > N_PAIRING_PER_OP * N_BYTES_PER_PAIR {
+                        if EC_PAIRING_MAX_INPUT_LEN.is_some_and(|max_len| input.len() > max_len) {
                             (vec![], gas, false, false)
                         } else {
                             (return_value, gas_cost, false, true)
                         }
                     }
                     PrecompileCalls::Modexp => {
-                        let (input_valid, [_, _, modulus_len]) = ModExpAuxData::check_input(input);
+                        let (input_valid, [_, _, modulus_len]) =
+                            ModExpAuxData::<MODEXP_SIZE_LIMIT>::check_input(input);
                         if input_valid {
                             // detect some edge cases like modulus = 0
                             assert_eq!(modulus_len.as_usize(), return_value.len());
@@ -73,7 +75,12 @@ pub(crate) fn execute_precompiled(
 }
 
 /// Addresses of the precompiled contracts.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
+///
+/// The EIP-4844 point evaluation precompile (0x0a) is not supported: proving
+/// it needs BLS12-381 pairings, which the pinned halo2-lib, halo2curves and
+/// revm-precompile don't provide. Calls to 0x0a are handled as calls to a
+/// regular account.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, EnumIter, Serialize, Deserialize)]
 pub enum PrecompileCalls {
     /// Elliptic Curve Recovery
     Ecrecover = 0x01,
@@ -168,10 +175,21 @@ impl PrecompileCalls {
             _ => None,
         }
     }
+
+    /// Number of the call data bytes taken as the input of the precompile call, the rest is
+    /// ignored. The input of modexp ends after the operands specified at the head of the call
+    /// data, so `call_data` must include its first 96 bytes (or all of it if shorter).
+    pub fn n_input_bytes(&self, call_data_len: usize, call_data: &[u8]) -> usize {
+        let input_len = match self {
+            Self::Modexp => Some(ModExpAuxData::<MODEXP_SIZE_LIMIT>::input_len(call_data)),
+            _ => self.input_len(),
+        };
+        input_len.map_or(call_data_len, |input_len| input_len.min(call_data_len))
+    }
 }
 
 /// Auxiliary data for Ecrecover
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcrecoverAuxData {
     /// Keccak hash of the message being signed.
     pub msg_hash: Word,
@@ -227,24 +245,28 @@ impl EcrecoverAuxData {
     }
 }
 
-/// size limit of modexp
-pub const MODEXP_SIZE_LIMIT: usize = 32;
-/// size of input limit
-pub const MODEXP_INPUT_LIMIT: usize = 192;
-
-/// Auxiliary data for Modexp
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
-pub struct ModExpAuxData {
+/// Default size limit of each modexp operand (base, exponent and modulus) in bytes, a call with a
+/// larger operand is treated as a failed one. The limit is the `SIZE_LIMIT` parameter of
+/// [`ModExpAuxData`], and of the modexp table, gadget and circuit, which default to this value.
+/// The rows of the modexp circuit scale with it, and it must be a multiple of the 8-byte limbs of
+/// the circuit.
+pub const MODEXP_SIZE_LIMIT: usize = 256;
+/// size of input limit, i.e. the 3 lengths and the 3 operands
+pub const MODEXP_INPUT_LIMIT: usize = ModExpAuxData::<MODEXP_SIZE_LIMIT>::INPUT_LIMIT;
+
+/// Auxiliary data for Modexp, with operands of up to `SIZE_LIMIT` bytes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModExpAuxData<const SIZE_LIMIT: usize = MODEXP_SIZE_LIMIT> {
     /// The specified len of inputs: [base, exp, modulus]
     pub input_lens: [Word; 3],
-    /// Input value [base, exp, modulus], limited to SIZE_LIMIT
-    pub inputs: [[u8; MODEXP_SIZE_LIMIT]; 3],
+    /// Input value [base, exp, modulus], big-endian and left padded to SIZE_LIMIT bytes
+    pub inputs: [Vec<u8>; 3],
     /// Input valid.
     pub valid: bool,
     /// len of output, limited to lens of moduls, but can be 0
     pub output_len: usize,
-    /// output of modexp.
-    pub output: [u8; MODEXP_SIZE_LIMIT],
+    /// output of modexp, big-endian and left padded to SIZE_LIMIT bytes
+    pub output: Vec<u8>,
     /// Input to the modexp call.
     pub input_bytes: Vec<u8>,
     /// Output bytes from the modexp call.
@@ -253,16 +275,19 @@ pub struct ModExpAuxData {
     pub return_bytes: Vec<u8>,
 }
 
-impl ModExpAuxData {
-    /// If mem is smaller than U256, left pad zero
-    /// Or else, keep the least 32bytes.
-    fn parse_memory_to_value(mem: &[u8]) -> [u8; MODEXP_SIZE_LIMIT] {
-        let mut value_bytes = [0u8; MODEXP_SIZE_LIMIT];
+impl<const SIZE_LIMIT: usize> ModExpAuxData<SIZE_LIMIT> {
+    /// size of input limit, i.e. the 3 lengths and the 3 operands
+    pub const INPUT_LIMIT: usize = 96 + 3 * SIZE_LIMIT;
+
+    /// If mem is smaller than SIZE_LIMIT, left pad zero
+    /// Or else, keep the least SIZE_LIMIT bytes.
+    fn parse_memory_to_value(mem: &[u8]) -> Vec<u8> {
+        let mut value_bytes = vec![0u8; SIZE_LIMIT];
         if !mem.is_empty() {
-            let copy_len = mem.len().min(MODEXP_SIZE_LIMIT);
+            let copy_len = mem.len().min(SIZE_LIMIT);
             let src_offset = mem.len() - copy_len;
-            let dst_offset = MODEXP_SIZE_LIMIT - copy_len;
-            value_bytes.as_mut_slice()[dst_offset..].copy_from_slice(&mem[src_offset..]);
+            let dst_offset = SIZE_LIMIT - copy_len;
+            value_bytes[dst_offset..].copy_from_slice(&mem[src_offset..]);
         }
         value_bytes
     }
@@ -274,7 +299,7 @@ impl ModExpAuxData {
         let exp_len = Word::from_big_endian(i.next().unwrap_or(&[]));
         let modulus_len = Word::from_big_endian(i.next().unwrap_or(&[]));
 
-        let limit = Word::from(MODEXP_SIZE_LIMIT);
+        let limit = Word::from(SIZE_LIMIT);
 
         let input_valid = base_len <= limit && exp_len <= limit && modulus_len <= limit;
         log::debug!("modexp base_len {base_len} exp_len {exp_len} modulus_len {modulus_len}");
@@ -284,6 +309,20 @@ impl ModExpAuxData {
         (input_valid, [base_len, exp_len, modulus_len])
     }
 
+    /// Length of the input of a call with `call_data`: the 3 sizes, followed by the operands if
+    /// the sizes are valid.
+    pub fn input_len(call_data: &[u8]) -> usize {
+        let mut head = call_data[..call_data.len().min(96)].to_vec();
+        head.resize(96, 0);
+        let (input_valid, input_lens) = Self::check_input(&head);
+
+        96 + if input_valid {
+            input_lens.iter().map(Word::as_usize).sum::<usize>()
+        } else {
+            0
+        }
+    }
+
     /// Create a new instance of modexp auxiliary data.
     pub fn new(input: &[u8], output: &[u8], return_bytes: &[u8]) -> Self {
         let mut resized_input = input.to_vec();
@@ -301,40 +340,56 @@ impl ModExpAuxData {
             0
         };
 
-        let (base, exp, modulus) = if base_mem_len == 0 && modulus_mem_len == 0 {
-            // special case
-            ([0u8; 32], [0u8; 32], [0u8; 32])
-        } else {
-            // In non scroll mode, this can be dangerous.
-            // If base and mod are all 0, exp can be very huge.
-            resized_input.resize(96 + base_mem_len + exp_mem_len + modulus_mem_len, 0);
-            let mut cur_input_begin = &resized_input[96..];
-
-            let base = Self::parse_memory_to_value(&cur_input_begin[..base_mem_len]);
-            cur_input_begin = &cur_input_begin[base_mem_len..];
-            let exp = Self::parse_memory_to_value(&cur_input_begin[..exp_mem_len]);
-            cur_input_begin = &cur_input_begin[exp_mem_len..];
-            let modulus = Self::parse_memory_to_value(&cur_input_begin[..modulus_mem_len]);
-            (base, exp, modulus)
-        };
+        // the sizes are bounded by SIZE_LIMIT, or zero if the input is invalid
+        resized_input.resize(96 + base_mem_len + exp_mem_len + modulus_mem_len, 0);
+        let mut cur_input_begin = &resized_input[96..];
+
+        let base = Self::parse_memory_to_value(&cur_input_begin[..base_mem_len]);
+        cur_input_begin = &cur_input_begin[base_mem_len..];
+        let exp = Self::parse_memory_to_value(&cur_input_begin[..exp_mem_len]);
+        cur_input_begin = &cur_input_begin[exp_mem_len..];
+        let modulus = Self::parse_memory_to_value(&cur_input_begin[..modulus_mem_len]);
         let output_len = output.len();
-        let output = Self::parse_memory_to_value(output);
 
         Self {
             valid: input_valid,
             input_lens: [base_len, exp_len, modulus_len],
             inputs: [base, exp, modulus],
-            output,
             output_len,
             input_bytes: input.to_vec(),
             output_bytes: output.to_vec(),
+            output: Self::parse_memory_to_value(output),
             return_bytes: return_bytes.to_vec(),
         }
     }
+
+    /// Result of the modexp of the inputs, big-endian and left padded to SIZE_LIMIT bytes.
+    /// It's the output unless the call runs out of gas.
+    pub fn result(&self) -> Vec<u8> {
+        let [base, exp, modulus] = self
+            .inputs
+            .clone()
+            .map(|input| BigUint::from_bytes_be(&input));
+        let result = if modulus.is_zero() {
+            BigUint::zero()
+        } else {
+            base.modpow(&exp, &modulus)
+        };
+
+        Self::parse_memory_to_value(&result.to_bytes_be())
+    }
+
+    /// Bit length of the exponent, the dynamic gas cost depends on it (EIP-2565).
+    pub fn exp_bit_len(&self) -> usize {
+        let exp = &self.inputs[1];
+        exp.iter().position(|&byte| byte != 0).map_or(0, |idx| {
+            (exp.len() - idx) * 8 - exp[idx].leading_zeros() as usize
+        })
+    }
 }
 
 /// Auxiliary data for EcAdd, i.e. P + Q = R
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcAddAuxData {
     /// x co-ordinate of the first point.
     pub p_x: Word,
@@ -379,7 +434,7 @@ impl EcAddAuxData {
 }
 
 /// Auxiliary data for EcMul, i.e. s * P = R
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcMulAuxData {
     /// x co-ordinate of the point.
     pub p_x: Word,
@@ -425,31 +480,76 @@ impl EcMulAuxData {
     }
 }
 
-/// Auxiliary data for EcPairing.
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// Auxiliary data for Blake2f
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Blake2fAuxData {
+    /// Number of rounds, i.e. the first 4 input bytes as big-endian u32. Zero if the input
+    /// length is invalid.
+    pub rounds: u32,
+    /// Final block indicator flag, i.e. the last input byte. Zero if the input length is
+    /// invalid.
+    pub flag: u8,
+    /// Input bytes to the blake2f call.
+    pub input_bytes: Vec<u8>,
+    /// Output bytes from the blake2f call.
+    pub output_bytes: Vec<u8>,
+    /// Bytes returned back to the caller from the blake2f call.
+    pub return_bytes: Vec<u8>,
+}
+
+impl Blake2fAuxData {
+    /// Create a new instance of blake2f auxiliary data.
+    pub fn new(input: &[u8], output: &[u8], return_bytes: &[u8]) -> Self {
+        let (rounds, flag) = if input.len() == BLAKE2F_INPUT_LEN {
+            (
+                u32::from_be_bytes(input[0..4].try_into().unwrap()),
+                input[BLAKE2F_INPUT_LEN - 1],
+            )
+        } else {
+            (0, 0)
+        };
+
+        Self {
+            rounds,
+            flag,
+            input_bytes: input.to_vec(),
+            output_bytes: output.to_vec(),
+            return_bytes: return_bytes.to_vec(),
+        }
+    }
+
+    /// Whether the input is well-formed, i.e. 213 bytes with a boolean final block flag.
+    pub fn is_valid(&self) -> bool {
+        self.input_bytes.len() == BLAKE2F_INPUT_LEN && self.flag <= 1
+    }
+}
+
+/// Maximum length of the input to the ecPairing precompile call, if any. Scroll evm fails a call
+/// with more than [`N_PAIRING_PER_OP`] pairs, unless the `ec-pairing-chain` feature is enabled,
+/// in which case the pairs are split across several chained pairing ops.
+pub const EC_PAIRING_MAX_INPUT_LEN: Option<usize> =
+    if cfg!(all(feature = "scroll", not(feature = "ec-pairing-chain"))) {
+        Some(N_PAIRING_PER_OP * N_BYTES_PER_PAIR)
+    } else {
+        None
+    };
+
+/// Auxiliary data for EcPairing, i.e. the last op of the chain of pairing ops for the call.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcPairingAuxData(pub EcPairingOp);
 
 /// Erroneous bytes passed to the EcPairing precompile call.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EcPairingError {
     /// the calldatalength passed to EcPairing precompile call is expected to be:
-    /// 1. len(input) <= 768
-    /// 2. len(input) % 192 == 0
+    /// 1. len(input) % 192 == 0
+    /// 2. len(input) <= EC_PAIRING_MAX_INPUT_LEN, if any
     InvalidInputLen(Vec<u8>),
 }
 
 /// Auxiliary data attached to an internal state for precompile verification.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PrecompileAuxData {
-    /// Base precompile (used for SHA256, RIPEMD-160 and BLAKE2F).
-    Base {
-        /// input bytes to the identity call.
-        input_bytes: Vec<u8>,
-        /// output bytes from the identity call.
-        output_bytes: Vec<u8>,
-        /// bytes returned back to the caller from the identity call.
-        return_bytes: Vec<u8>,
-    },
     /// Identity.
     Identity {
         /// input bytes to the identity call.
@@ -468,6 +568,15 @@ pub enum PrecompileAuxData {
         /// bytes returned back to the caller from the sha256 call.
         return_bytes: Vec<u8>,
     },
+    /// RIPEMD160
+    RIPEMD160 {
+        /// input bytes to the ripemd160 call.
+        input_bytes: Vec<u8>,
+        /// output bytes from the ripemd160 call.
+        output_bytes: Vec<u8>,
+        /// bytes returned back to the caller from the ripemd160 call.
+        return_bytes: Vec<u8>,
+    },
     /// Ecrecover.
     Ecrecover(EcrecoverAuxData),
     /// Modexp.
@@ -478,6 +587,8 @@ pub enum PrecompileAuxData {
     EcMul(EcMulAuxData),
     /// EcPairing.
     EcPairing(Box<Result<EcPairingAuxData, EcPairingError>>),
+    /// Blake2f.
+    Blake2f(Blake2fAuxData),
 }
 
 impl Default for PrecompileAuxData {
@@ -485,3 +596,67 @@ impl Default for PrecompileAuxData {
         Self::Ecrecover(EcrecoverAuxData::default())
     }
 }
+
+#[cfg(test)]
+mod modexp_test {
+    use super::*;
+
+    // A call computing `base ** 1 % modulus`, with each of the operands of `len` bytes.
+    fn modexp_input(len: usize) -> Vec<u8> {
+        let mut input = Vec::new();
+        for _ in 0..3 {
+            input.extend_from_slice(&Word::from(len).to_be_bytes());
+        }
+        let mut exp = vec![0u8; len];
+        exp[len - 1] = 1;
+        input.extend(vec![0x11u8; len]);
+        input.extend(exp);
+        input.extend(vec![0xffu8; len]);
+        input
+    }
+
+    #[test]
+    fn modexp_aux_data_at_size_limit() {
+        let input = modexp_input(MODEXP_SIZE_LIMIT);
+        let data = ModExpAuxData::<MODEXP_SIZE_LIMIT>::new(&input, &[], &[]);
+        assert!(data.valid);
+        assert_eq!(data.inputs[0], vec![0x11u8; MODEXP_SIZE_LIMIT]);
+        assert_eq!(data.result(), vec![0x11u8; MODEXP_SIZE_LIMIT]);
+        assert_eq!(data.exp_bit_len(), 1);
+        assert_eq!(
+            ModExpAuxData::<MODEXP_SIZE_LIMIT>::input_len(&input),
+            MODEXP_INPUT_LIMIT
+        );
+    }
+
+    #[test]
+    fn modexp_aux_data_over_size_limit() {
+        // the operands are not truncated to the limit, the call fails instead
+        let input = modexp_input(MODEXP_SIZE_LIMIT + 1);
+        let data = ModExpAuxData::<MODEXP_SIZE_LIMIT>::new(&input, &[], &[]);
+        assert!(!data.valid);
+        assert_eq!(data.input_lens, [Word::from(MODEXP_SIZE_LIMIT + 1); 3]);
+        assert_eq!(
+            data.inputs,
+            [
+                vec![0u8; MODEXP_SIZE_LIMIT],
+                vec![0u8; MODEXP_SIZE_LIMIT],
+                vec![0u8; MODEXP_SIZE_LIMIT]
+            ]
+        );
+        assert_eq!(ModExpAuxData::<MODEXP_SIZE_LIMIT>::input_len(&input), 96);
+    }
+
+    #[test]
+    fn modexp_aux_data_configured_size_limit() {
+        let data = ModExpAuxData::<32>::new(&modexp_input(32), &[], &[]);
+        assert!(data.valid);
+        assert_eq!(data.result(), vec![0x11u8; 32]);
+        assert_eq!(ModExpAuxData::<32>::INPUT_LIMIT, 192);
+
+        let data = ModExpAuxData::<32>::new(&modexp_input(33), &[], &[]);
+        assert!(!data.valid);
+        // the default limit accepts it
+        assert!(ModExpAuxData::<MODEXP_SIZE_LIMIT>::new(&modexp_input(33), &[], &[]).valid);
+    }
+}
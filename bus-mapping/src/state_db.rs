@@ -135,9 +135,14 @@ pub struct StateDB {
     // state before current transaction, to calculate gas cost for some opcodes like sstore.
     // So both dirty storage and committed storage are needed.
     dirty_storage: HashMap<(Address, Word), Word>,
-    // Accounts that have been through `SELFDESTRUCT` under the situation that `is_persistent` is
-    // `true`. These accounts will be reset once `commit_tx` is called.
+    // Transient storage (EIP-1153), which is discarded when current transaction finishes.
+    transient_storage: HashMap<(Address, Word), Word>,
+    // Accounts that have been through `SELFDESTRUCT` in current transaction, and not reverted.
+    // They keep their code, nonce and storage until the `DestructAccount` steps after `EndTx`.
     destructed_account: HashSet<Address>,
+    // Accounts that have been created (via creation transaction or `CREATE*`) in current
+    // transaction, see EIP-6780.
+    created_account: HashSet<Address>,
     // Accounts that are still "empty", but an Account Rw {value_prev: 0x0, value: empty_code_hash}
     // has already been applied.
     // TODO: a better name?
@@ -156,6 +161,11 @@ impl StateDB {
         self.state.insert(*addr, acc);
     }
 
+    /// Remove the [`Account`] at `addr` from the state, returning it if it was found.
+    pub fn remove_account(&mut self, addr: &Address) -> Option<Account> {
+        self.state.remove(addr)
+    }
+
     /// Get a reference to the [`Account`] at `addr`.  Returns false and a zero
     /// [`Account`] when the [`Account`] wasn't found in the state.
     pub fn get_account(&self, addr: &Address) -> (bool, &Account) {
@@ -250,6 +260,20 @@ impl StateDB {
         self.dirty_storage.insert((*addr, *key), *value);
     }
 
+    /// Get the transient storage value at `addr` and `key`. Returns a zero
+    /// [`Word`] if it hasn't been written in current transaction.
+    pub fn get_transient_storage(&self, addr: &Address, key: &Word) -> Word {
+        self.transient_storage
+            .get(&(*addr, *key))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Set transient storage value at `addr` and `key`.
+    pub fn set_transient_storage(&mut self, addr: &Address, key: &Word, value: &Word) {
+        self.transient_storage.insert((*addr, *key), *value);
+    }
+
     /// Get balance of account with the given address.
     pub fn get_balance(&self, addr: &Address) -> Word {
         let (_, account) = self.get_account(addr);
@@ -307,12 +331,70 @@ impl StateDB {
         debug_assert!(exist);
     }
 
-    /// Set account as self destructed.
+    /// Set account as self destructed. The account is only destructed at the end of current
+    /// transaction.
     pub fn destruct_account(&mut self, addr: Address) {
-        self.state.insert(addr, Account::zero());
         self.destructed_account.insert(addr);
     }
 
+    /// Unset account as self destructed, when the `SELFDESTRUCT` is reverted or the account has
+    /// been destructed.
+    pub fn remove_destructed_account(&mut self, addr: &Address) {
+        let exist = self.destructed_account.remove(addr);
+        debug_assert!(exist);
+    }
+
+    /// Check whether the account at `addr` is self destructed in current transaction.
+    pub fn is_destructed(&self, addr: &Address) -> bool {
+        self.destructed_account.contains(addr)
+    }
+
+    /// Accounts self destructed in current transaction, sorted by address.
+    pub fn destructed_accounts(&self) -> Vec<Address> {
+        self.destructed_account
+            .iter()
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Keys of the non-zero storage slots of `addr` known to the StateDB, i.e. the ones loaded
+    /// from the traces or written in current transaction, sorted.
+    pub fn non_zero_storage_keys(&self, addr: &Address) -> Vec<Word> {
+        let (_, account) = self.get_account(addr);
+        account
+            .storage
+            .keys()
+            .chain(
+                self.dirty_storage
+                    .keys()
+                    .filter(|(address, _)| address == addr)
+                    .map(|(_, key)| key),
+            )
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .filter(|key| !self.get_storage(addr, key).1.is_zero())
+            .collect()
+    }
+
+    /// Record that the account at `addr` is created in current transaction.
+    pub fn add_created_account(&mut self, addr: Address) {
+        self.created_account.insert(addr);
+    }
+
+    /// Remove `addr` from the accounts created in current transaction.
+    pub fn remove_created_account(&mut self, addr: &Address) {
+        let exist = self.created_account.remove(addr);
+        debug_assert!(exist);
+    }
+
+    /// Check whether the account at `addr` is created in current transaction.
+    pub fn is_created_in_tx(&self, addr: &Address) -> bool {
+        self.created_account.contains(addr)
+    }
+
     /// Retrieve refund.
     pub fn refund(&self) -> u64 {
         self.refund
@@ -323,7 +405,7 @@ impl StateDB {
         self.refund = value;
     }
 
-    /// Clear access list and refund, and commit dirty storage.
+    /// Clear access list, transient storage and refund, and commit dirty storage.
     /// It should be invoked before processing
     /// with new transaction with the same [`StateDB`].
     pub fn commit_tx(&mut self) {
@@ -334,11 +416,16 @@ impl StateDB {
             *ptr = value;
         }
         self.dirty_storage = HashMap::new();
+        self.transient_storage = HashMap::new();
         self.touched_account = HashSet::new();
-        for addr in self.destructed_account.clone() {
-            let (_, account) = self.get_account_mut(&addr);
-            *account = ACCOUNT_ZERO.clone();
-        }
+        self.created_account = HashSet::new();
+        // The destructed accounts have been cleared by the `DestructAccount` steps.
+        debug_assert!(
+            self.destructed_account.is_empty(),
+            "destructed accounts left after the tx: {:?}",
+            self.destructed_account
+        );
+        self.destructed_account = HashSet::new();
         self.refund = 0;
     }
 }
@@ -1,7 +1,7 @@
 //! ..
-use eth_types::{Hash, U256};
+use eth_types::Hash;
 pub use eth_types::{KECCAK_CODE_HASH_EMPTY, POSEIDON_CODE_HASH_EMPTY};
-use halo2_proofs::halo2curves::{bn256::Fr, group::ff::PrimeField};
+pub use mpt_zktrie::state::builder::{hash_code_poseidon, POSEIDON_HASH_BYTES_IN_FIELD};
 use std::{convert::Infallible, str::FromStr, sync::LazyLock};
 
 /// ..
@@ -49,9 +49,6 @@ impl FromStr for GethTraceSanityCheckLevel {
     }
 }
 
-/// Default number of bytes to pack into a field element.
-pub const POSEIDON_HASH_BYTES_IN_FIELD: usize = 31;
-
 /// Default code hash
 pub fn hash_code(code: &[u8]) -> Hash {
     #[cfg(feature = "scroll")]
@@ -65,45 +62,6 @@ pub fn hash_code_keccak(code: &[u8]) -> Hash {
     eth_types::H256(ethers_core::utils::keccak256(code))
 }
 
-/// Poseidon code hash
-pub fn hash_code_poseidon(code: &[u8]) -> Hash {
-    use hash_circuit::hash::{Hashable, MessageHashable, HASHABLE_DOMAIN_SPEC};
-
-    let bytes_in_field = POSEIDON_HASH_BYTES_IN_FIELD;
-    let fls = (0..(code.len() / bytes_in_field))
-        .map(|i| i * bytes_in_field)
-        .map(|i| {
-            let mut buf: [u8; 32] = [0; 32];
-            U256::from_big_endian(&code[i..i + bytes_in_field]).to_little_endian(&mut buf);
-            Fr::from_bytes(&buf).unwrap()
-        });
-    let msgs: Vec<_> = fls
-        .chain(if code.len() % bytes_in_field == 0 {
-            None
-        } else {
-            let last_code = &code[code.len() - code.len() % bytes_in_field..];
-            // pad to bytes_in_field
-            let mut last_buf = vec![0u8; bytes_in_field];
-            last_buf.as_mut_slice()[..last_code.len()].copy_from_slice(last_code);
-            let mut buf: [u8; 32] = [0; 32];
-            U256::from_big_endian(&last_buf).to_little_endian(&mut buf);
-            Some(Fr::from_bytes(&buf).unwrap())
-        })
-        .collect();
-
-    let h = if msgs.is_empty() {
-        // the empty code hash is overlapped with simple hash on [0, 0]
-        // an issue in poseidon primitive prevent us calculate it from hash_msg
-        Fr::hash_with_domain([Fr::zero(), Fr::zero()], Fr::zero())
-    } else {
-        Fr::hash_msg(&msgs, Some(code.len() as u128 * HASHABLE_DOMAIN_SPEC))
-    };
-
-    let mut buf: [u8; 32] = [0; 32];
-    U256::from_little_endian(h.to_repr().as_ref()).to_big_endian(&mut buf);
-    Hash::from_slice(&buf)
-}
-
 #[test]
 fn test_empty_code_hash() {
     assert_eq!(*POSEIDON_CODE_HASH_EMPTY, hash_code_poseidon(&[]));
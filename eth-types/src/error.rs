@@ -33,6 +33,8 @@ pub enum Error {
     WordToMemAddr,
     /// Signature parsing error.
     Signature,
+    /// Invalid or unsupported Merkle-Patricia trie proof.
+    MptProof(String),
 }
 
 impl Display for Error {
@@ -282,10 +282,18 @@ pub enum OpcodeId {
     SELFBALANCE,
     /// `BASEFEE`
     BASEFEE,
+    /// `BLOBHASH`
+    BLOBHASH,
     /// `SLOAD`
     SLOAD,
     /// `SSTORE`
     SSTORE,
+    /// `TLOAD`
+    TLOAD,
+    /// `TSTORE`
+    TSTORE,
+    /// `MCOPY`
+    MCOPY,
     /// `GAS`
     GAS,
 
@@ -515,8 +523,12 @@ impl OpcodeId {
             OpcodeId::CHAINID => 0x46u8,
             OpcodeId::SELFBALANCE => 0x47u8,
             OpcodeId::BASEFEE => 0x48u8,
+            OpcodeId::BLOBHASH => 0x49u8,
             OpcodeId::SLOAD => 0x54u8,
             OpcodeId::SSTORE => 0x55u8,
+            OpcodeId::TLOAD => 0x5cu8,
+            OpcodeId::TSTORE => 0x5du8,
+            OpcodeId::MCOPY => 0x5eu8,
             OpcodeId::GAS => 0x5au8,
             OpcodeId::LOG0 => 0xa0u8,
             OpcodeId::LOG1 => 0xa1u8,
@@ -593,12 +605,16 @@ impl OpcodeId {
             OpcodeId::CHAINID => GasCost::QUICK,
             OpcodeId::SELFBALANCE => GasCost::FAST,
             OpcodeId::BASEFEE => GasCost::QUICK,
+            OpcodeId::BLOBHASH => GasCost::FASTEST,
             OpcodeId::POP => GasCost::QUICK,
             OpcodeId::MLOAD => GasCost::FASTEST,
             OpcodeId::MSTORE => GasCost::FASTEST,
             OpcodeId::MSTORE8 => GasCost::FASTEST,
             OpcodeId::SLOAD => GasCost::ZERO,
             OpcodeId::SSTORE => GasCost::ZERO,
+            OpcodeId::TLOAD => GasCost::WARM_ACCESS,
+            OpcodeId::TSTORE => GasCost::WARM_ACCESS,
+            OpcodeId::MCOPY => GasCost::FASTEST,
             OpcodeId::JUMP => GasCost::MID,
             OpcodeId::JUMPI => GasCost::SLOW,
             OpcodeId::PC => GasCost::QUICK,
@@ -757,12 +773,16 @@ impl OpcodeId {
             OpcodeId::CHAINID => (1, 1024),
             OpcodeId::SELFBALANCE => (1, 1024),
             OpcodeId::BASEFEE => (1, 1024),
+            OpcodeId::BLOBHASH => (0, 1023),
             OpcodeId::POP => (0, 1023),
             OpcodeId::MLOAD => (0, 1023),
             OpcodeId::MSTORE => (0, 1022),
             OpcodeId::MSTORE8 => (0, 1022),
             OpcodeId::SLOAD => (0, 1023),
             OpcodeId::SSTORE => (0, 1022),
+            OpcodeId::TLOAD => (0, 1023),
+            OpcodeId::TSTORE => (0, 1022),
+            OpcodeId::MCOPY => (0, 1021),
             OpcodeId::JUMP => (0, 1023),
             OpcodeId::JUMPI => (0, 1022),
             OpcodeId::PC => (1, 1024),
@@ -867,6 +887,7 @@ impl OpcodeId {
                 | OpcodeId::RETURNDATACOPY
                 | OpcodeId::CODECOPY
                 | OpcodeId::EXTCODECOPY
+                | OpcodeId::MCOPY
         )
     }
 
@@ -970,6 +991,12 @@ impl From<u8> for OpcodeId {
             0x58u8 => OpcodeId::PC,
             0x59u8 => OpcodeId::MSIZE,
             0x5bu8 => OpcodeId::JUMPDEST,
+            #[cfg(feature = "cancun")]
+            0x5cu8 => OpcodeId::TLOAD,
+            #[cfg(feature = "cancun")]
+            0x5du8 => OpcodeId::TSTORE,
+            #[cfg(feature = "cancun")]
+            0x5eu8 => OpcodeId::MCOPY,
             #[cfg(feature = "shanghai")]
             0x5fu8 => OpcodeId::PUSH0,
             0x60u8 => OpcodeId::PUSH1,
@@ -1061,6 +1088,8 @@ impl From<u8> for OpcodeId {
             0x47u8 => OpcodeId::SELFBALANCE,
             #[cfg(not(feature = "scroll"))]
             0x48u8 => OpcodeId::BASEFEE,
+            #[cfg(feature = "cancun")]
+            0x49u8 => OpcodeId::BLOBHASH,
             0x54u8 => OpcodeId::SLOAD,
             0x55u8 => OpcodeId::SSTORE,
             0x5au8 => OpcodeId::GAS,
@@ -1240,8 +1269,22 @@ impl FromStr for OpcodeId {
             "BASEFEE" => OpcodeId::BASEFEE,
             #[cfg(feature = "scroll")]
             "BASEFEE" => OpcodeId::INVALID(0x48),
-            "TLOAD" => OpcodeId::INVALID(0xb3),
-            "TSTORE" => OpcodeId::INVALID(0xb4),
+            #[cfg(feature = "cancun")]
+            "BLOBHASH" => OpcodeId::BLOBHASH,
+            #[cfg(not(feature = "cancun"))]
+            "BLOBHASH" => OpcodeId::INVALID(0x49),
+            #[cfg(feature = "cancun")]
+            "TLOAD" => OpcodeId::TLOAD,
+            #[cfg(not(feature = "cancun"))]
+            "TLOAD" => OpcodeId::INVALID(0x5c),
+            #[cfg(feature = "cancun")]
+            "TSTORE" => OpcodeId::TSTORE,
+            #[cfg(not(feature = "cancun"))]
+            "TSTORE" => OpcodeId::INVALID(0x5d),
+            #[cfg(feature = "cancun")]
+            "MCOPY" => OpcodeId::MCOPY,
+            #[cfg(not(feature = "cancun"))]
+            "MCOPY" => OpcodeId::INVALID(0x5e),
             _ => {
                 // Parse an invalid opcode value as reported by geth
                 static RE: LazyLock<Regex> = LazyLock::new(|| {
@@ -7,21 +7,24 @@ use crate::{
     AccessList, Address, Block, Bytes, Error, GethExecTrace, Hash, ToBigEndian, ToLittleEndian,
     Word, U64,
 };
-use ethers_core::types::{
-    transaction::eip2718::TypedTransaction, Eip1559TransactionRequest, Eip2930TransactionRequest,
-    NameOrAddress, TransactionRequest, H256,
+use ethers_core::{
+    types::{
+        transaction::eip2718::TypedTransaction, Eip1559TransactionRequest,
+        Eip2930TransactionRequest, NameOrAddress, OtherFields, TransactionRequest, H256,
+    },
+    utils::rlp::RlpStream,
 };
 use halo2_proofs::halo2curves::{group::ff::PrimeField, secp256k1::Fq};
 use num::Integer;
 use num_bigint::BigUint;
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Serialize, Serializer};
 use serde_with::serde_as;
 use sha3::{Digest, Keccak256};
 use std::collections::HashMap;
 use strum_macros::EnumIter;
 
 /// Tx type
-#[derive(Default, Debug, Copy, Clone, EnumIter, Serialize, PartialEq, Eq)]
+#[derive(Default, Debug, Copy, Clone, EnumIter, Serialize, Deserialize, PartialEq, Eq)]
 pub enum TxType {
     /// EIP 155 tx
     #[default]
@@ -34,6 +37,8 @@ pub enum TxType {
     Eip2930,
     /// L1 Message tx
     L1Msg,
+    /// EIP 4844 tx
+    Eip4844,
 }
 
 impl From<TxType> for usize {
@@ -74,11 +79,29 @@ impl TxType {
         matches!(*self, TxType::Eip2930)
     }
 
+    /// If this type is Eip4844 or not
+    pub fn is_eip4844(&self) -> bool {
+        matches!(*self, TxType::Eip4844)
+    }
+
+    /// The EIP-2718 type byte prefixing the encodings of a typed tx and of its
+    /// receipt, 0 for legacy txs.
+    pub fn eip2718_type(&self) -> u8 {
+        match *self {
+            TxType::Eip155 | TxType::PreEip155 => 0,
+            TxType::Eip2930 => 1,
+            TxType::Eip1559 => 2,
+            TxType::Eip4844 => 3,
+            TxType::L1Msg => 0x7e,
+        }
+    }
+
     /// Get the type of transaction
     pub fn get_tx_type(tx: &crate::Transaction) -> Self {
         match tx.transaction_type {
             Some(x) if x == U64::from(1) => Self::Eip2930,
             Some(x) if x == U64::from(2) => Self::Eip1559,
+            Some(x) if x == U64::from(3) => Self::Eip4844,
             Some(x) if x == U64::from(0x7e) => Self::L1Msg,
             _ => {
                 if cfg!(feature = "scroll") {
@@ -116,6 +139,10 @@ impl TxType {
                 assert!(v <= 1);
                 v
             }
+            TxType::Eip4844 => {
+                assert!(v <= 1);
+                v
+            }
             TxType::L1Msg => {
                 unreachable!("L1 msg does not have signature")
             }
@@ -151,6 +178,7 @@ pub fn get_rlp_unsigned(tx: &crate::Transaction) -> Vec<u8> {
             let typed_tx: TypedTransaction = tx.into();
             typed_tx.rlp().to_vec()
         }
+        TxType::Eip4844 => get_rlp_eip4844(tx, false),
         TxType::L1Msg => {
             // L1 msg does not have signature
             vec![]
@@ -158,6 +186,61 @@ pub fn get_rlp_unsigned(tx: &crate::Transaction) -> Vec<u8> {
     }
 }
 
+/// Get the RLP bytes of the signed tx
+pub fn get_rlp_signed(tx: &crate::Transaction) -> Vec<u8> {
+    match TxType::get_tx_type(tx) {
+        TxType::Eip4844 => get_rlp_eip4844(tx, true),
+        _ => tx.rlp().to_vec(),
+    }
+}
+
+/// Get the blob fields (max_fee_per_blob_gas, blob_versioned_hashes) of an EIP-4844 tx,
+/// which are not known by `ethers_core` and are kept in the `other` fields.
+pub fn get_blob_fields(tx: &crate::Transaction) -> (Word, Vec<H256>) {
+    let max_fee_per_blob_gas = tx
+        .other
+        .get_deserialized::<Word>("maxFeePerBlobGas")
+        .and_then(Result::ok)
+        .unwrap_or_default();
+    let blob_versioned_hashes = tx
+        .other
+        .get_deserialized::<Vec<H256>>("blobVersionedHashes")
+        .and_then(Result::ok)
+        .unwrap_or_default();
+
+    (max_fee_per_blob_gas, blob_versioned_hashes)
+}
+
+// `TypedTransaction` of ethers does not support EIP-4844 tx, so we encode it by ourselves:
+// 0x03 || rlp([chain_id, nonce, max_priority_fee_per_gas, max_fee_per_gas, gas_limit, to, value,
+// data, access_list, max_fee_per_blob_gas, blob_versioned_hashes, (y_parity, r, s)])
+fn get_rlp_eip4844(tx: &crate::Transaction, signed: bool) -> Vec<u8> {
+    let (max_fee_per_blob_gas, blob_versioned_hashes) = get_blob_fields(tx);
+
+    let mut rlp = RlpStream::new();
+    rlp.begin_unbounded_list();
+    rlp.append(&tx.chain_id.unwrap_or_default());
+    rlp.append(&tx.nonce);
+    rlp.append(&tx.max_priority_fee_per_gas.unwrap_or_default());
+    rlp.append(&tx.max_fee_per_gas.unwrap_or_default());
+    rlp.append(&tx.gas);
+    // blob tx can not be a contract creation
+    rlp.append(&tx.to.unwrap_or_default());
+    rlp.append(&tx.value);
+    rlp.append(&tx.input);
+    rlp.append(&tx.access_list.clone().unwrap_or_default());
+    rlp.append(&max_fee_per_blob_gas);
+    rlp.append_list(&blob_versioned_hashes);
+    if signed {
+        rlp.append(&tx.v);
+        rlp.append(&tx.r);
+        rlp.append(&tx.s);
+    }
+    rlp.finalize_unbounded_list();
+
+    [&[0x03], rlp.as_raw()].concat()
+}
+
 /// Definition of all of the data related to an account.
 #[serde_as]
 #[derive(PartialEq, Eq, Debug, Default, Clone, Serialize)]
@@ -277,6 +360,10 @@ pub struct Transaction {
     pub call_data: Bytes,
     /// Access list
     pub access_list: Option<AccessList>,
+    /// Max fee per blob gas (EIP4844)
+    pub max_fee_per_blob_gas: Word,
+    /// Blob versioned hashes (EIP4844)
+    pub blob_versioned_hashes: Vec<H256>,
 
     /// "v" value of the transaction signature
     pub v: u64,
@@ -296,6 +383,17 @@ pub struct Transaction {
 
 impl From<&Transaction> for crate::Transaction {
     fn from(tx: &Transaction) -> crate::Transaction {
+        let mut other = OtherFields::default();
+        if tx.tx_type.is_eip4844() {
+            other.insert(
+                "maxFeePerBlobGas".to_string(),
+                serde_json::to_value(tx.max_fee_per_blob_gas).unwrap(),
+            );
+            other.insert(
+                "blobVersionedHashes".to_string(),
+                serde_json::to_value(&tx.blob_versioned_hashes).unwrap(),
+            );
+        }
         crate::Transaction {
             from: tx.from,
             to: tx.to,
@@ -311,6 +409,8 @@ impl From<&Transaction> for crate::Transaction {
             r: tx.r,
             s: tx.s,
             hash: tx.hash,
+            transaction_type: tx.tx_type.is_eip4844().then_some(U64::from(3)),
+            other,
             ..Default::default()
         }
     }
@@ -318,6 +418,7 @@ impl From<&Transaction> for crate::Transaction {
 
 impl From<&crate::Transaction> for Transaction {
     fn from(tx: &crate::Transaction) -> Transaction {
+        let (max_fee_per_blob_gas, blob_versioned_hashes) = get_blob_fields(tx);
         Transaction {
             tx_type: TxType::get_tx_type(tx),
             from: tx.from,
@@ -330,10 +431,12 @@ impl From<&crate::Transaction> for Transaction {
             gas_fee_cap: tx.max_fee_per_gas,
             call_data: tx.input.clone(),
             access_list: tx.access_list.clone(),
+            max_fee_per_blob_gas,
+            blob_versioned_hashes,
             v: tx.v.as_u64(),
             r: tx.r,
             s: tx.s,
-            rlp_bytes: tx.rlp().to_vec(),
+            rlp_bytes: get_rlp_signed(tx),
             rlp_unsigned_bytes: get_rlp_unsigned(tx),
             hash: tx.hash,
         }
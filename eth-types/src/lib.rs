@@ -26,6 +26,7 @@ pub mod bytecode;
 pub mod evm_types;
 pub mod geth_types;
 pub mod l2_types;
+pub mod serde_utils;
 pub mod sign_types;
 
 use crate::evm_types::{Gas, GasCost, OpcodeId, ProgramCounter};
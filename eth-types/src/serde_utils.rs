@@ -0,0 +1,349 @@
+//! Serde helpers for foreign types that don't implement `Serialize` /
+//! `Deserialize`, to be used through `#[serde(with = "...")]`.
+
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    halo2curves::{
+        ff::{Field, PrimeField},
+        Coordinates,
+    },
+};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+fn field_from_repr<F: PrimeField<Repr = [u8; 32]>, E: Error>(repr: [u8; 32]) -> Result<F, E> {
+    Option::from(F::from_repr(repr)).ok_or_else(|| E::custom("non canonical field element"))
+}
+
+fn point_to_repr<C>(point: &C) -> ([u8; 32], [u8; 32])
+where
+    C: CurveAffine,
+    C::Base: PrimeField<Repr = [u8; 32]>,
+{
+    // The identity has no affine coordinates, it's encoded as (0, 0) like
+    // in the EVM precompiles.
+    let (x, y) = Option::<Coordinates<C>>::from(point.coordinates())
+        .map(|coords| (*coords.x(), *coords.y()))
+        .unwrap_or((C::Base::ZERO, C::Base::ZERO));
+    (x.to_repr(), y.to_repr())
+}
+
+fn point_from_repr<C, E>((x, y): ([u8; 32], [u8; 32])) -> Result<C, E>
+where
+    C: CurveAffine,
+    C::Base: PrimeField<Repr = [u8; 32]>,
+    E: Error,
+{
+    let (x, y) = (field_from_repr(x)?, field_from_repr(y)?);
+    Option::from(C::from_xy(x, y)).ok_or_else(|| E::custom("point is not on the curve"))
+}
+
+/// Prime field elements, encoded as their canonical 32 bytes representation.
+pub mod field {
+    use super::*;
+
+    /// Serialize a field element.
+    pub fn serialize<F, S>(value: &F, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        F: PrimeField<Repr = [u8; 32]>,
+        S: Serializer,
+    {
+        value.to_repr().serialize(serializer)
+    }
+
+    /// Deserialize a field element, rejecting non canonical encodings.
+    pub fn deserialize<'de, F, D>(deserializer: D) -> Result<F, D::Error>
+    where
+        F: PrimeField<Repr = [u8; 32]>,
+        D: Deserializer<'de>,
+    {
+        field_from_repr(<[u8; 32]>::deserialize(deserializer)?)
+    }
+}
+
+/// Affine curve points, encoded as their (x, y) coordinates.
+pub mod curve_point {
+    use super::*;
+
+    /// Serialize a curve point.
+    pub fn serialize<C, S>(point: &C, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        C: CurveAffine,
+        C::Base: PrimeField<Repr = [u8; 32]>,
+        S: Serializer,
+    {
+        point_to_repr(point).serialize(serializer)
+    }
+
+    /// Deserialize a curve point, rejecting points that are not on the curve.
+    pub fn deserialize<'de, C, D>(deserializer: D) -> Result<C, D::Error>
+    where
+        C: CurveAffine,
+        C::Base: PrimeField<Repr = [u8; 32]>,
+        D: Deserializer<'de>,
+    {
+        point_from_repr(Deserialize::deserialize(deserializer)?)
+    }
+}
+
+/// Optional affine curve points, see [`curve_point`].
+pub mod curve_point_opt {
+    use super::*;
+
+    /// Serialize an optional curve point.
+    pub fn serialize<C, S>(point: &Option<C>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        C: CurveAffine,
+        C::Base: PrimeField<Repr = [u8; 32]>,
+        S: Serializer,
+    {
+        point.as_ref().map(point_to_repr::<C>).serialize(serializer)
+    }
+
+    /// Deserialize an optional curve point.
+    pub fn deserialize<'de, C, D>(deserializer: D) -> Result<Option<C>, D::Error>
+    where
+        C: CurveAffine,
+        C::Base: PrimeField<Repr = [u8; 32]>,
+        D: Deserializer<'de>,
+    {
+        Option::deserialize(deserializer)?
+            .map(point_from_repr::<C, D::Error>)
+            .transpose()
+    }
+}
+
+/// Arbitrary precision unsigned integers, encoded as big endian bytes.
+pub mod biguint {
+    use num_bigint::BigUint;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serialize a `BigUint`.
+    pub fn serialize<S: Serializer>(value: &BigUint, serializer: S) -> Result<S::Ok, S::Error> {
+        value.to_bytes_be().serialize(serializer)
+    }
+
+    /// Deserialize a `BigUint`.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigUint, D::Error> {
+        Ok(BigUint::from_bytes_be(&Vec::<u8>::deserialize(
+            deserializer,
+        )?))
+    }
+}
+
+/// Blocks of geth, whose serde implementation relies on a self-describing format (`flatten`,
+/// skipped optional fields, ...). They are written as is to human readable formats, and field by
+/// field to compact binary formats such as bincode. Only the extra fields of the block and its
+/// txs, which are untyped, are kept as their JSON encoding.
+pub mod eth_block {
+    use crate::{AccessList, Address, Block, Bytes, Transaction, H256, H64, U256, U64};
+    use ethers_core::types::{Bloom, OtherFields, Withdrawal};
+    use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct BlockRepr {
+        hash: Option<H256>,
+        parent_hash: H256,
+        uncles_hash: H256,
+        author: Option<Address>,
+        state_root: H256,
+        transactions_root: H256,
+        receipts_root: H256,
+        number: Option<U64>,
+        gas_used: U256,
+        gas_limit: U256,
+        extra_data: Bytes,
+        logs_bloom: Option<Bloom>,
+        timestamp: U256,
+        difficulty: U256,
+        total_difficulty: Option<U256>,
+        seal_fields: Vec<Bytes>,
+        uncles: Vec<H256>,
+        transactions: Vec<TransactionRepr>,
+        size: Option<U256>,
+        mix_hash: Option<H256>,
+        nonce: Option<H64>,
+        base_fee_per_gas: Option<U256>,
+        withdrawals_root: Option<H256>,
+        withdrawals: Option<Vec<Withdrawal>>,
+        other: Vec<(String, String)>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct TransactionRepr {
+        hash: H256,
+        nonce: U256,
+        block_hash: Option<H256>,
+        block_number: Option<U64>,
+        transaction_index: Option<U64>,
+        from: Address,
+        to: Option<Address>,
+        value: U256,
+        gas_price: Option<U256>,
+        gas: U256,
+        input: Bytes,
+        v: U64,
+        r: U256,
+        s: U256,
+        transaction_type: Option<U64>,
+        access_list: Option<AccessList>,
+        max_priority_fee_per_gas: Option<U256>,
+        max_fee_per_gas: Option<U256>,
+        chain_id: Option<U256>,
+        other: Vec<(String, String)>,
+    }
+
+    fn other_to_repr<E: ser::Error>(other: &OtherFields) -> Result<Vec<(String, String)>, E> {
+        other
+            .iter()
+            .map(|(key, value)| {
+                Ok((
+                    key.clone(),
+                    serde_json::to_string(value).map_err(E::custom)?,
+                ))
+            })
+            .collect()
+    }
+
+    fn other_from_repr<E: de::Error>(other: Vec<(String, String)>) -> Result<OtherFields, E> {
+        let mut fields = OtherFields::default();
+        for (key, value) in other {
+            fields.insert(key, serde_json::from_str(&value).map_err(E::custom)?);
+        }
+        Ok(fields)
+    }
+
+    impl TransactionRepr {
+        fn new<E: ser::Error>(tx: &Transaction) -> Result<Self, E> {
+            Ok(Self {
+                hash: tx.hash,
+                nonce: tx.nonce,
+                block_hash: tx.block_hash,
+                block_number: tx.block_number,
+                transaction_index: tx.transaction_index,
+                from: tx.from,
+                to: tx.to,
+                value: tx.value,
+                gas_price: tx.gas_price,
+                gas: tx.gas,
+                input: tx.input.clone(),
+                v: tx.v,
+                r: tx.r,
+                s: tx.s,
+                transaction_type: tx.transaction_type,
+                access_list: tx.access_list.clone(),
+                max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+                max_fee_per_gas: tx.max_fee_per_gas,
+                chain_id: tx.chain_id,
+                other: other_to_repr::<E>(&tx.other)?,
+            })
+        }
+
+        fn into_transaction<E: de::Error>(self) -> Result<Transaction, E> {
+            Ok(Transaction {
+                hash: self.hash,
+                nonce: self.nonce,
+                block_hash: self.block_hash,
+                block_number: self.block_number,
+                transaction_index: self.transaction_index,
+                from: self.from,
+                to: self.to,
+                value: self.value,
+                gas_price: self.gas_price,
+                gas: self.gas,
+                input: self.input,
+                v: self.v,
+                r: self.r,
+                s: self.s,
+                transaction_type: self.transaction_type,
+                access_list: self.access_list,
+                max_priority_fee_per_gas: self.max_priority_fee_per_gas,
+                max_fee_per_gas: self.max_fee_per_gas,
+                chain_id: self.chain_id,
+                other: other_from_repr::<E>(self.other)?,
+            })
+        }
+    }
+
+    /// Serialize a block.
+    pub fn serialize<S: Serializer>(
+        block: &Block<Transaction>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return block.serialize(serializer);
+        }
+        BlockRepr {
+            hash: block.hash,
+            parent_hash: block.parent_hash,
+            uncles_hash: block.uncles_hash,
+            author: block.author,
+            state_root: block.state_root,
+            transactions_root: block.transactions_root,
+            receipts_root: block.receipts_root,
+            number: block.number,
+            gas_used: block.gas_used,
+            gas_limit: block.gas_limit,
+            extra_data: block.extra_data.clone(),
+            logs_bloom: block.logs_bloom,
+            timestamp: block.timestamp,
+            difficulty: block.difficulty,
+            total_difficulty: block.total_difficulty,
+            seal_fields: block.seal_fields.clone(),
+            uncles: block.uncles.clone(),
+            transactions: block
+                .transactions
+                .iter()
+                .map(TransactionRepr::new::<S::Error>)
+                .collect::<Result<_, _>>()?,
+            size: block.size,
+            mix_hash: block.mix_hash,
+            nonce: block.nonce,
+            base_fee_per_gas: block.base_fee_per_gas,
+            withdrawals_root: block.withdrawals_root,
+            withdrawals: block.withdrawals.clone(),
+            other: other_to_repr::<S::Error>(&block.other)?,
+        }
+        .serialize(serializer)
+    }
+
+    /// Deserialize a block.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Block<Transaction>, D::Error> {
+        if deserializer.is_human_readable() {
+            return Block::deserialize(deserializer);
+        }
+        let repr = BlockRepr::deserialize(deserializer)?;
+        Ok(Block {
+            hash: repr.hash,
+            parent_hash: repr.parent_hash,
+            uncles_hash: repr.uncles_hash,
+            author: repr.author,
+            state_root: repr.state_root,
+            transactions_root: repr.transactions_root,
+            receipts_root: repr.receipts_root,
+            number: repr.number,
+            gas_used: repr.gas_used,
+            gas_limit: repr.gas_limit,
+            extra_data: repr.extra_data,
+            logs_bloom: repr.logs_bloom,
+            timestamp: repr.timestamp,
+            difficulty: repr.difficulty,
+            total_difficulty: repr.total_difficulty,
+            seal_fields: repr.seal_fields,
+            uncles: repr.uncles,
+            transactions: repr
+                .transactions
+                .into_iter()
+                .map(TransactionRepr::into_transaction::<D::Error>)
+                .collect::<Result<_, _>>()?,
+            size: repr.size,
+            mix_hash: repr.mix_hash,
+            nonce: repr.nonce,
+            base_fee_per_gas: repr.base_fee_per_gas,
+            withdrawals_root: repr.withdrawals_root,
+            withdrawals: repr.withdrawals,
+            other: other_from_repr::<D::Error>(repr.other)?,
+        })
+    }
+}
@@ -3,6 +3,7 @@
 use crate::{
     address,
     geth_types::{Transaction, TxType},
+    serde_utils::{curve_point, field},
     word, Error, Word, H256,
 };
 use ethers_core::{
@@ -28,6 +29,7 @@ use halo2_proofs::{
     },
 };
 use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
 use sha3::digest::generic_array::GenericArray;
 use std::sync::LazyLock;
 use subtle::CtOption;
@@ -54,7 +56,8 @@ pub fn sign(randomness: Fq, sk: Fq, msg_hash: Fq) -> (Fq, Fq, u8) {
 
 /// Signature data required by the SignVerify Chip as input to verify a
 /// signature.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "SignDataRepr", into = "SignDataRepr")]
 pub struct SignData {
     /// Secp256k1 signature point (r, s, v)
     /// v must be 0 or 1
@@ -67,6 +70,45 @@ pub struct SignData {
     pub msg_hash: Fq,
 }
 
+#[derive(Serialize, Deserialize)]
+struct SignDataRepr {
+    #[serde(with = "field")]
+    r: Fq,
+    #[serde(with = "field")]
+    s: Fq,
+    v: u8,
+    #[serde(with = "curve_point")]
+    pk: Secp256k1Affine,
+    msg: Bytes,
+    #[serde(with = "field")]
+    msg_hash: Fq,
+}
+
+impl From<SignDataRepr> for SignData {
+    fn from(repr: SignDataRepr) -> Self {
+        Self {
+            signature: (repr.r, repr.s, repr.v),
+            pk: repr.pk,
+            msg: repr.msg,
+            msg_hash: repr.msg_hash,
+        }
+    }
+}
+
+impl From<SignData> for SignDataRepr {
+    fn from(sign_data: SignData) -> Self {
+        let (r, s, v) = sign_data.signature;
+        Self {
+            r,
+            s,
+            v,
+            pk: sign_data.pk,
+            msg: sign_data.msg,
+            msg_hash: sign_data.msg_hash,
+        }
+    }
+}
+
 /// Generate a dummy pre-eip155 tx in which
 /// (nonce=0, gas=0, gas_price=0, to=0, value=0, data="")
 /// using the dummy private key = 1
@@ -0,0 +1,64 @@
+//! Tracer backend calling into the cgo-built geth of `geth-utils`.
+
+#[cfg(feature = "scroll")]
+use crate::ChainConfig;
+use crate::{TraceConfig, Tracer};
+#[cfg(feature = "scroll")]
+use eth_types::l2_types::BlockTrace;
+use eth_types::{Error, GethExecTrace};
+use serde::de::DeserializeOwned;
+
+/// Tracer connecting to geth through `geth_utils`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GethTracer;
+
+impl Tracer for GethTracer {
+    #[cfg(not(feature = "scroll"))]
+    fn trace(&self, config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
+        call_geth(config)
+    }
+
+    #[cfg(feature = "scroll")]
+    fn trace(&self, config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
+        let block_trace = self.l2trace(config)?;
+
+        Ok(block_trace
+            .execution_results
+            .into_iter()
+            .map(From::from)
+            .collect::<Vec<_>>())
+    }
+
+    #[cfg(feature = "scroll")]
+    fn l2trace(&self, config: &TraceConfig) -> Result<BlockTrace, Error> {
+        let mut l2_config = config.clone();
+        if let Some(chain_config) = l2_config.chain_config.as_mut() {
+            chain_config.archimedes_block = Some(0);
+        } else {
+            l2_config.chain_config = Some(ChainConfig {
+                archimedes_block: Some(0),
+                shanghai_time: None,
+                cancun_time: None,
+                terminal_total_difficulty: None,
+                terminal_total_difficulty_passed: false,
+            });
+        }
+        call_geth(&l2_config)
+    }
+}
+
+fn call_geth<T: DeserializeOwned>(config: &TraceConfig) -> Result<T, Error> {
+    let trace_config = &serde_json::to_string_pretty(&config).unwrap();
+    log::trace!("trace config: {}", trace_config);
+    // Get the trace
+    let trace_string = geth_utils::trace(trace_config).map_err(|error| match error {
+        geth_utils::Error::TracingError(error) => Error::TracingError(error),
+    })?;
+
+    log::trace!("trace: {}", trace_string);
+
+    let mut deserializer = serde_json::Deserializer::from_str(&trace_string);
+    deserializer.disable_recursion_limit();
+    let deserializer = serde_stacker::Deserializer::new(&mut deserializer);
+    serde::Deserialize::deserialize(deserializer).map_err(Error::SerdeError)
+}
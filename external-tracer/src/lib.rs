@@ -1,5 +1,13 @@
 //! This module generates traces by connecting to an external tracer
 
+#[cfg(feature = "geth")]
+mod geth;
+mod revm_tracer;
+
+#[cfg(feature = "geth")]
+pub use geth::GethTracer;
+pub use revm_tracer::RevmTracer;
+
 #[cfg(feature = "scroll")]
 use eth_types::l2_types::BlockTrace;
 use eth_types::{
@@ -7,7 +15,7 @@ use eth_types::{
     Address, Error, GethExecTrace, Word,
 };
 use serde::Serialize;
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, str::FromStr};
 
 /// Configuration structure for `geth_utlis::trace`
 #[derive(Debug, Default, Clone, Serialize)]
@@ -83,6 +91,8 @@ pub struct ChainConfig {
     /// Shanghai switch time (nil = no fork, 0 = already on shanghai)
     /// Scroll EVM use the name `ShanghaiBlock` instead
     pub shanghai_time: Option<u64>,
+    /// Cancun switch time (nil = no fork, 0 = already on cancun)
+    pub cancun_time: Option<u64>,
     /// TerminalTotalDifficulty is the amount of total difficulty reached by
     /// the network that triggers the consensus upgrade.
     pub terminal_total_difficulty: Option<u64>,
@@ -98,66 +108,87 @@ impl ChainConfig {
         Self {
             archimedes_block: None,
             shanghai_time: Some(0),
+            cancun_time: None,
             terminal_total_difficulty: Some(0),
             terminal_total_difficulty_passed: true,
         }
     }
+
+    /// Create a chain config for Cancun fork.
+    pub fn cancun() -> Self {
+        Self {
+            cancun_time: Some(0),
+            ..Self::shanghai()
+        }
+    }
 }
 
-/// Creates a trace for the specified config
-#[cfg(not(feature = "scroll"))]
-pub fn trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
-    let trace_config = &serde_json::to_string_pretty(&config).unwrap();
-    log::trace!("trace config: {}", trace_config);
-    // Get the trace
-    let trace_string = geth_utils::trace(trace_config).map_err(|error| match error {
-        geth_utils::Error::TracingError(error) => Error::TracingError(error),
-    })?;
-
-    log::trace!("trace: {}", trace_string);
-
-    let mut deserializer = serde_json::Deserializer::from_str(&trace_string);
-    deserializer.disable_recursion_limit();
-    let deserializer = serde_stacker::Deserializer::new(&mut deserializer);
-    serde::Deserialize::deserialize(deserializer).map_err(Error::SerdeError)
+/// Generates traces from a [`TraceConfig`].
+pub trait Tracer {
+    /// Creates a trace for each of the transactions in the config.
+    fn trace(&self, config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error>;
+
+    /// Creates a l2-trace for the block in the config.
+    #[cfg(feature = "scroll")]
+    fn l2trace(&self, config: &TraceConfig) -> Result<BlockTrace, Error>;
 }
 
-/// Creates a l2-trace for the specified config
-#[cfg(feature = "scroll")]
-pub fn l2trace(config: &TraceConfig) -> Result<BlockTrace, Error> {
-    let mut l2_config = config.clone();
-    if let Some(chain_config) = l2_config.chain_config.as_mut() {
-        chain_config.archimedes_block = Some(0);
-    } else {
-        l2_config.chain_config = Some(ChainConfig {
-            archimedes_block: Some(0),
-            shanghai_time: None,
-            terminal_total_difficulty: None,
-            terminal_total_difficulty_passed: false,
-        });
+/// Backends implementing [`Tracer`], selectable at runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TracerBackend {
+    /// The cgo-built geth, see [`GethTracer`].
+    #[cfg(feature = "geth")]
+    Geth,
+    /// The in-process revm, see [`RevmTracer`].
+    Revm,
+}
+
+impl Default for TracerBackend {
+    fn default() -> Self {
+        #[cfg(feature = "geth")]
+        return Self::Geth;
+        #[cfg(not(feature = "geth"))]
+        return Self::Revm;
     }
-    let trace_config = &serde_json::to_string_pretty(&l2_config).unwrap();
-    log::trace!("trace config: {}", trace_config);
-    // Get the trace
-    let trace_string = geth_utils::trace(trace_config).map_err(|error| match error {
-        geth_utils::Error::TracingError(error) => Error::TracingError(error),
-    })?;
-
-    log::trace!("trace: {}", trace_string);
-
-    let mut deserializer = serde_json::Deserializer::from_str(&trace_string);
-    deserializer.disable_recursion_limit();
-    let deserializer = serde_stacker::Deserializer::new(&mut deserializer);
-    serde::Deserialize::deserialize(deserializer).map_err(Error::SerdeError)
 }
 
-#[cfg(feature = "scroll")]
+impl FromStr for TracerBackend {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            #[cfg(feature = "geth")]
+            "geth" => Ok(Self::Geth),
+            "revm" => Ok(Self::Revm),
+            _ => Err(Error::TracingError(format!("unknown tracer backend {s}"))),
+        }
+    }
+}
+
+impl TracerBackend {
+    /// Reads the backend from the `TRACER_BACKEND` env var, or uses the default one if unset.
+    pub fn from_env() -> Result<Self, Error> {
+        std::env::var("TRACER_BACKEND").map_or_else(|_| Ok(Self::default()), |s| s.parse())
+    }
+
+    /// Returns the tracer of this backend.
+    pub fn tracer(&self) -> Box<dyn Tracer> {
+        match self {
+            #[cfg(feature = "geth")]
+            Self::Geth => Box::new(GethTracer),
+            Self::Revm => Box::new(RevmTracer),
+        }
+    }
+}
+
+/// Creates a trace for the specified config, using the backend from [`TracerBackend::from_env`].
 pub fn trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
-    let block_trace = l2trace(config)?;
+    TracerBackend::from_env()?.tracer().trace(config)
+}
 
-    Ok(block_trace
-        .execution_results
-        .into_iter()
-        .map(From::from)
-        .collect::<Vec<_>>())
+/// Creates a l2-trace for the specified config, using the backend from
+/// [`TracerBackend::from_env`].
+#[cfg(feature = "scroll")]
+pub fn l2trace(config: &TraceConfig) -> Result<BlockTrace, Error> {
+    TracerBackend::from_env()?.tracer().l2trace(config)
 }
@@ -0,0 +1,1354 @@
+//! Tracer backend running the transactions in-process on revm.
+//!
+//! The revm is the scroll-tech fork the workspace already uses for the precompiles of
+//! bus-mapping, so both agree on the precompile and gas semantics.
+//!
+//! The struct logs, prestate and call trace are built to be identical to the ones returned by the
+//! geth backend, i.e. the `structLogger`, `prestateTracer` and `callTracer` of geth. The call
+//! trace is deserialized from the same JSON format as geth's.
+//!
+//! The fork's interpreter predates Cancun, so TLOAD, TSTORE, MCOPY and BLOBHASH are executed by
+//! the inspector when the interpreter doesn't know them. With the `scroll` feature, the opcodes
+//! disabled by l2geth halt as invalid, BLOCKHASH follows the scroll rule and the L1 fee is
+//! charged. The l2-trace is then built from the state of revm, see [`l2trace`].
+
+#[cfg(feature = "scroll")]
+mod l2trace;
+
+use crate::{LoggerConfig, TraceConfig, Tracer};
+#[cfg(feature = "enable-memory")]
+use eth_types::evm_types::Memory;
+#[cfg(feature = "enable-stack")]
+use eth_types::evm_types::Stack;
+#[cfg(feature = "enable-storage")]
+use eth_types::evm_types::Storage;
+#[cfg(feature = "scroll")]
+use eth_types::{
+    evm_types::gas_utils::tx_data_gas_cost,
+    l2_types::{BlockTrace, ExtraData},
+    ToAddress,
+};
+use eth_types::{
+    evm_types::{block_utils::calculate_block_hash, Gas, GasCost, OpcodeId},
+    geth_types::Transaction,
+    Address, Error, GethCallTrace, GethExecError, GethExecStep, GethExecTrace, GethPrestateTrace,
+    ToBigEndian, Word, H256,
+};
+use revm::{
+    db::InMemoryDB,
+    interpreter::{
+        gas::memory_gas, CallInputs, CallScheme, CreateInputs, Gas as RevmGas, InstructionResult,
+        Interpreter,
+    },
+    primitives::{
+        AccountInfo, BlockEnv, Bytecode, Bytes, CfgEnv, CreateScheme, ExecutionResult, Output,
+        ResultAndState, SpecId, TransactTo, TxEnv, B160, B256, U256,
+    },
+    Database, DatabaseCommit, EVMData, Inspector, EVM,
+};
+use serde_json::json;
+use std::collections::HashMap;
+
+/// Max number of history block hashes accessible by BLOCKHASH.
+const NUM_PREV_BLOCK_ALLOWED: u64 = 256;
+/// Max size of the stack.
+const STACK_LIMIT: u64 = 1024;
+/// Opcodes added by the Cancun fork.
+const CANCUN_OPCODES: [u8; 4] = [0x49, 0x5c, 0x5d, 0x5e];
+/// Opcodes disabled by l2geth, i.e. BASEFEE and SELFDESTRUCT.
+#[cfg(feature = "scroll")]
+const SCROLL_DISABLED_OPCODES: [u8; 2] = [0x48, 0xff];
+/// Address of the L1 gas price oracle, same as `l2_predeployed` of bus-mapping.
+#[cfg(feature = "scroll")]
+const L1_GAS_PRICE_ORACLE: [u8; 20] = [
+    0x53, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x02,
+];
+/// Slots of the L1 fee parameters in the L1 gas price oracle.
+#[cfg(feature = "scroll")]
+const BASE_FEE_SLOT: u64 = 1;
+#[cfg(feature = "scroll")]
+const OVERHEAD_SLOT: u64 = 2;
+#[cfg(feature = "scroll")]
+const SCALAR_SLOT: u64 = 3;
+
+/// Tracer running the transactions on revm, without any Go toolchain involved.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RevmTracer;
+
+impl Tracer for RevmTracer {
+    fn trace(&self, config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
+        let (_, txs) = execute(config)?;
+        Ok(txs.into_iter().map(|tx| tx.trace).collect())
+    }
+
+    #[cfg(feature = "scroll")]
+    fn l2trace(&self, config: &TraceConfig) -> Result<BlockTrace, Error> {
+        l2trace::block_trace(config)
+    }
+}
+
+/// A transaction executed by [`execute`].
+struct TxExecution {
+    trace: GethExecTrace,
+    /// Extra data of each of the struct logs.
+    #[cfg(feature = "scroll")]
+    extra_data: Vec<Option<ExtraData>>,
+    #[cfg(feature = "scroll")]
+    state: l2trace::TxState,
+}
+
+/// Runs the transactions of the config, returning the database with the state after them.
+fn execute(config: &TraceConfig) -> Result<(InMemoryDB, Vec<TxExecution>), Error> {
+    let txs_gas_limit = config
+        .transactions
+        .iter()
+        .fold(Word::zero(), |acc, tx| acc + tx.gas_limit);
+    if txs_gas_limit > config.block_constants.gas_limit {
+        return Err(Error::TracingError(format!(
+            "txs total gas: {txs_gas_limit} Exceeds block gas limit: {}",
+            config.block_constants.gas_limit
+        )));
+    }
+
+    let is_cancun = config
+        .chain_config
+        .as_ref()
+        .is_some_and(|chain_config| chain_config.cancun_time.is_some());
+    let mut evm = EVM::new();
+    evm.env.cfg = CfgEnv {
+        chain_id: U256::from(config.chain_id),
+        spec_id: spec_id(config),
+        ..Default::default()
+    };
+    evm.env.block = block_env(config);
+    evm.database(init_db(config));
+
+    let mut txs = Vec::with_capacity(config.transactions.len());
+    for (i, tx) in config.transactions.iter().enumerate() {
+        evm.env.tx = tx_env(tx);
+
+        let db = evm.db().expect("database is set");
+        #[cfg(feature = "scroll")]
+        let mut tx_state = l2trace::TxState::new(db, tx);
+        let l1_fee = charge_l1_fee(db, tx);
+
+        let mut logger = StructLogger::new(&config.logger_config, is_cancun, tx);
+        let ResultAndState { result, state } = evm.inspect(&mut logger).map_err(|err| {
+            Error::TracingError(format!("Failed to apply config.Transactions[{i}]: {err:?}"))
+        })?;
+
+        let db = evm.db().expect("database is set");
+        let mut prestate = HashMap::new();
+        for (address, account) in state.iter() {
+            let info = db
+                .basic(*address)
+                .expect("in-memory db never fails")
+                .unwrap_or_default();
+            let code = match info.code {
+                Some(code) => code,
+                None => db
+                    .code_by_hash(info.code_hash)
+                    .expect("in-memory db never fails"),
+            }
+            .original_bytes();
+            let storage = account
+                .storage
+                .iter()
+                .map(|(key, slot)| (from_u256(*key), from_u256(slot.original_value)))
+                .collect::<HashMap<_, _>>();
+            // The prestate is taken before the L1 fee is charged.
+            let balance = if *address == to_b160(tx.from) {
+                from_u256(info.balance) + l1_fee
+            } else {
+                from_u256(info.balance)
+            };
+            prestate.insert(
+                from_b160(*address),
+                GethPrestateTrace {
+                    balance: Some(balance),
+                    nonce: (info.nonce != 0).then_some(info.nonce),
+                    code: (!code.is_empty()).then(|| code.to_vec().into()),
+                    storage: (!storage.is_empty()).then_some(storage),
+                },
+            );
+        }
+        #[cfg(feature = "scroll")]
+        tx_state.touch(&state);
+        db.commit(state);
+        reward_l1_fee(db, config, l1_fee);
+
+        let (gas_used, failed, return_value) = match result {
+            ExecutionResult::Success {
+                gas_used, output, ..
+            } => match output {
+                Output::Call(output) | Output::Create(output, _) => (gas_used, false, output),
+            },
+            ExecutionResult::Revert { gas_used, output } => (gas_used, true, output),
+            ExecutionResult::Halt { gas_used, .. } => (gas_used, true, Bytes::new()),
+        };
+        let call_trace = logger.call_trace(tx, gas_used)?;
+        txs.push(TxExecution {
+            trace: GethExecTrace {
+                l1_fee: l1_fee.as_u64(),
+                gas: Gas(gas_used),
+                failed,
+                return_value: hex::encode(return_value),
+                struct_logs: logger.struct_logs,
+                #[cfg(feature = "scroll")]
+                account_after: tx_state.settle(db, config, tx),
+                #[cfg(not(feature = "scroll"))]
+                account_after: vec![],
+                prestate,
+                call_trace,
+            },
+            #[cfg(feature = "scroll")]
+            extra_data: logger.extra_data,
+            #[cfg(feature = "scroll")]
+            state: tx_state,
+        });
+    }
+
+    let db = evm.db.take().expect("database is set");
+    Ok((db, txs))
+}
+
+fn to_b160(address: Address) -> B160 {
+    B160(address.0)
+}
+
+fn from_b160(address: B160) -> Address {
+    Address::from(address.0)
+}
+
+fn to_u256(word: Word) -> U256 {
+    U256::from_be_bytes(word.to_be_bytes())
+}
+
+fn from_u256(value: U256) -> Word {
+    Word::from_big_endian(&value.to_be_bytes::<32>())
+}
+
+fn spec_id(config: &TraceConfig) -> SpecId {
+    match &config.chain_config {
+        Some(chain_config) if chain_config.shanghai_time.is_some() => SpecId::LATEST,
+        // geth always sets the random of the block context, so the merge rules apply.
+        _ => SpecId::MERGE,
+    }
+}
+
+fn block_env(config: &TraceConfig) -> BlockEnv {
+    let block = &config.block_constants;
+    BlockEnv {
+        number: U256::from(block.number.as_u64()),
+        coinbase: to_b160(block.coinbase),
+        timestamp: to_u256(block.timestamp),
+        difficulty: to_u256(block.difficulty),
+        // Same as geth, the difficulty is used as the randao.
+        prevrandao: Some(B256(block.difficulty.to_be_bytes())),
+        basefee: to_u256(block.base_fee),
+        gas_limit: to_u256(block.gas_limit),
+    }
+}
+
+fn init_db(config: &TraceConfig) -> InMemoryDB {
+    let mut db = InMemoryDB::default();
+    for (address, account) in &config.accounts {
+        db.insert_account_info(
+            to_b160(*address),
+            AccountInfo::new(
+                to_u256(account.balance),
+                account.nonce.as_u64(),
+                Bytecode::new_raw(account.code.to_vec().into()),
+            ),
+        );
+        for (key, value) in &account.storage {
+            db.insert_account_storage(to_b160(*address), to_u256(*key), to_u256(*value))
+                .expect("in-memory db never fails");
+        }
+    }
+
+    let number = config.block_constants.number.as_u64();
+    let first = number.saturating_sub(NUM_PREV_BLOCK_ALLOWED);
+    for n in first..number {
+        db.block_hashes
+            .insert(U256::from(n), B256(block_hash(config, n).to_be_bytes()));
+    }
+
+    db
+}
+
+/// Hash of a history block, as returned by BLOCKHASH.
+fn block_hash(config: &TraceConfig, n: u64) -> Word {
+    if cfg!(feature = "scroll") {
+        return calculate_block_hash(config.chain_id, n.into()).1;
+    }
+
+    // The hashes of the blocks missing from the history are zero, as in geth.
+    let number = config.block_constants.number.as_u64();
+    (config.history_hashes.len() as u64)
+        .checked_sub(number - n)
+        .map(|index| config.history_hashes[index as usize])
+        .unwrap_or_default()
+}
+
+/// Whether the transaction is an L1 message. As l2geth, a transaction without signature can only
+/// be run as an L1 message.
+#[cfg(feature = "scroll")]
+fn is_l1_msg(tx: &Transaction) -> bool {
+    tx.tx_type.is_l1_msg() || tx.r.is_zero()
+}
+
+#[cfg(not(feature = "scroll"))]
+fn is_l1_msg(tx: &Transaction) -> bool {
+    tx.tx_type.is_l1_msg()
+}
+
+fn tx_env(tx: &Transaction) -> TxEnv {
+    // A legacy gas price takes precedence over the fee cap and tip, as in geth. In l2geth, the gas
+    // of an L1 message is paid on L1.
+    let (gas_price, gas_priority_fee) = match tx.gas_price {
+        _ if cfg!(feature = "scroll") && is_l1_msg(tx) => (Word::zero(), None),
+        Some(gas_price) => (gas_price, None),
+        None => (tx.gas_fee_cap.unwrap_or_default(), tx.gas_tip_cap),
+    };
+    TxEnv {
+        caller: to_b160(tx.from),
+        gas_limit: tx.gas_limit.as_u64(),
+        gas_price: to_u256(gas_price),
+        gas_priority_fee: gas_priority_fee.map(to_u256),
+        transact_to: match tx.to {
+            Some(to) => TransactTo::Call(to_b160(to)),
+            None => TransactTo::Create(CreateScheme::Create),
+        },
+        value: to_u256(tx.value),
+        data: tx.call_data.to_vec().into(),
+        chain_id: None,
+        // The nonce of an L1 message is its queue index, which isn't checked against the sender.
+        nonce: (!is_l1_msg(tx)).then(|| tx.nonce.as_u64()),
+        access_list: tx
+            .access_list
+            .as_ref()
+            .map(|access_list| {
+                access_list
+                    .0
+                    .iter()
+                    .map(|item| {
+                        (
+                            to_b160(item.address),
+                            item.storage_keys
+                                .iter()
+                                .map(|key| U256::from_be_bytes(key.0))
+                                .collect(),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+/// Charges the L1 fee of the transaction to its sender, and returns the fee.
+#[cfg(feature = "scroll")]
+fn charge_l1_fee(db: &mut InMemoryDB, tx: &Transaction) -> Word {
+    // Same as `TxL1Fee` of bus-mapping.
+    const TX_L1_COMMIT_EXTRA_COST: u64 = 64;
+    const TX_L1_FEE_PRECISION: u64 = 1_000_000_000;
+
+    if is_l1_msg(tx) {
+        return Word::zero();
+    }
+
+    let [base_fee, fee_overhead, fee_scalar] =
+        [BASE_FEE_SLOT, OVERHEAD_SLOT, SCALAR_SLOT].map(|slot| {
+            db.storage(B160(L1_GAS_PRICE_ORACLE), U256::from(slot))
+                .map(from_u256)
+                .expect("in-memory db never fails")
+        });
+    let tx_l1_gas =
+        tx_data_gas_cost(&tx.rlp_bytes) + fee_overhead.as_u64() + TX_L1_COMMIT_EXTRA_COST;
+    let l1_fee = fee_scalar * base_fee * tx_l1_gas / TX_L1_FEE_PRECISION;
+
+    let caller = to_b160(tx.from);
+    let mut info = db
+        .basic(caller)
+        .expect("in-memory db never fails")
+        .unwrap_or_default();
+    info.balance = info.balance.saturating_sub(to_u256(l1_fee));
+    db.insert_account_info(caller, info);
+
+    l1_fee
+}
+
+#[cfg(not(feature = "scroll"))]
+fn charge_l1_fee(_db: &mut InMemoryDB, _tx: &Transaction) -> Word {
+    Word::zero()
+}
+
+/// Pays the L1 fee of a transaction to the coinbase.
+fn reward_l1_fee(db: &mut InMemoryDB, config: &TraceConfig, l1_fee: Word) {
+    if l1_fee.is_zero() {
+        return;
+    }
+    let coinbase = to_b160(config.block_constants.coinbase);
+    let mut info = db
+        .basic(coinbase)
+        .expect("in-memory db never fails")
+        .unwrap_or_default();
+    info.balance += to_u256(l1_fee);
+    db.insert_account_info(coinbase, info);
+}
+
+fn is_success(ret: InstructionResult) -> bool {
+    matches!(
+        ret,
+        InstructionResult::Stop | InstructionResult::Return | InstructionResult::SelfDestruct
+    )
+}
+
+/// Whether the unused gas of the callee is returned to the caller.
+fn returns_gas(ret: InstructionResult) -> bool {
+    is_success(ret)
+        || matches!(
+            ret,
+            InstructionResult::Revert
+                | InstructionResult::CallTooDeep
+                | InstructionResult::OutOfFund
+        )
+}
+
+fn error_string(ret: InstructionResult) -> String {
+    let error = match ret {
+        InstructionResult::Revert => GethExecError::ExecutionReverted,
+        InstructionResult::OutOfGas => GethExecError::OutOfGas,
+        InstructionResult::InvalidJump => GethExecError::InvalidJump,
+        InstructionResult::CallTooDeep => GethExecError::Depth,
+        InstructionResult::OutOfFund => GethExecError::InsufficientBalance,
+        InstructionResult::CreateCollision => GethExecError::ContractAddressCollision,
+        InstructionResult::CallNotAllowedInsideStatic
+        | InstructionResult::StateChangeDuringStaticCall => GethExecError::WriteProtection,
+        InstructionResult::OutOfOffset => GethExecError::ReturnDataOutOfBounds,
+        InstructionResult::CreateContractSizeLimit => GethExecError::MaxCodeSizeExceeded,
+        InstructionResult::CreateContractStartingWithEF => GethExecError::InvalidCode,
+        _ => return format!("{ret:?}"),
+    };
+    error.error().to_string()
+}
+
+/// Gas of the last callee that has returned to its caller.
+#[derive(Clone, Copy, Debug)]
+struct CalleeGas {
+    /// Unused gas returned to the caller.
+    returned: u64,
+    /// Gas forwarded to the callee that geth doesn't account in the gas cost of the step.
+    excluded: u64,
+}
+
+/// A step that has started but not ended yet.
+#[derive(Clone, Copy, Debug)]
+struct PendingStep {
+    index: usize,
+    opcode: u8,
+    gas: u64,
+    stack_len: u64,
+    /// Key read by SLOAD.
+    sload_key: Option<Word>,
+}
+
+/// A frame of the call trace.
+#[derive(Clone, Debug)]
+struct CallFrame {
+    call_type: &'static str,
+    from: Address,
+    to: Option<Address>,
+    gas: u64,
+    gas_used: u64,
+    output: Option<Bytes>,
+    error: Option<String>,
+    is_top: bool,
+    calls: Vec<CallFrame>,
+}
+
+impl CallFrame {
+    fn end(&mut self, ret: InstructionResult, remaining_gas: &RevmGas, output: &Bytes) {
+        self.gas_used = self.gas.saturating_sub(remaining_gas.remaining());
+        if is_success(ret) {
+            self.output = Some(output.clone());
+            return;
+        }
+        self.error = Some(error_string(ret));
+        if self.call_type.starts_with("CREATE") {
+            self.to = None;
+        }
+        if ret == InstructionResult::Revert && !output.is_empty() {
+            self.output = Some(output.clone());
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "calls": self.calls.iter().map(CallFrame::to_json).collect::<Vec<_>>(),
+            "error": self.error,
+            "from": self.from,
+            "gasUsed": format!("{:#x}", self.gas_used),
+            "output": self.output.as_ref().map(|output| format!("0x{}", hex::encode(output))),
+            "to": self.to,
+            "type": self.call_type,
+        })
+    }
+}
+
+/// Transient storage (EIP-1153) of a transaction, with a journal to revert failed calls.
+#[derive(Clone, Debug, Default)]
+struct TransientStorage {
+    values: HashMap<(Address, Word), Word>,
+    /// Previous values of the written slots.
+    journal: Vec<((Address, Word), Word)>,
+    /// Length of the journal when each frame of the call stack has been entered.
+    checkpoints: Vec<usize>,
+}
+
+impl TransientStorage {
+    fn get(&self, address: Address, key: Word) -> Word {
+        self.values
+            .get(&(address, key))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn set(&mut self, address: Address, key: Word, value: Word) {
+        let prev = self.values.insert((address, key), value);
+        self.journal
+            .push(((address, key), prev.unwrap_or_default()));
+    }
+
+    fn enter(&mut self) {
+        self.checkpoints.push(self.journal.len());
+    }
+
+    fn exit(&mut self, is_success: bool) {
+        let checkpoint = self.checkpoints.pop().unwrap_or_default();
+        if is_success {
+            return;
+        }
+        for (slot, prev) in self.journal.drain(checkpoint..).rev() {
+            self.values.insert(slot, prev);
+        }
+    }
+}
+
+/// Inspector collecting the same struct logs and call trace as geth.
+struct StructLogger {
+    config: LoggerConfig,
+    is_cancun: bool,
+    blob_hashes: Vec<H256>,
+    struct_logs: Vec<GethExecStep>,
+    pending_steps: Vec<PendingStep>,
+    /// Refund counter of each frame in the call stack.
+    refunds: Vec<i64>,
+    /// Storage accessed by SLOAD and SSTORE of each contract.
+    storage: HashMap<Address, HashMap<Word, Word>>,
+    transient_storage: TransientStorage,
+    /// Address of the contract executing the latest step.
+    address: Option<Address>,
+    callee_gas: Option<CalleeGas>,
+    call_frames: Vec<CallFrame>,
+    /// Frames ended without a parent frame.
+    root_frames: Vec<CallFrame>,
+    /// Receiver of the transaction.
+    #[cfg(feature = "scroll")]
+    tx_to: Option<Address>,
+    /// Extra data of each of the struct logs, as recorded by l2geth.
+    #[cfg(feature = "scroll")]
+    extra_data: Vec<Option<ExtraData>>,
+}
+
+impl StructLogger {
+    fn new(config: &LoggerConfig, is_cancun: bool, tx: &Transaction) -> Self {
+        Self {
+            config: config.clone(),
+            is_cancun,
+            blob_hashes: tx.blob_versioned_hashes.clone(),
+            struct_logs: vec![],
+            pending_steps: vec![],
+            refunds: vec![],
+            storage: HashMap::new(),
+            transient_storage: TransientStorage::default(),
+            address: None,
+            callee_gas: None,
+            call_frames: vec![],
+            root_frames: vec![],
+            #[cfg(feature = "scroll")]
+            tx_to: tx.to,
+            #[cfg(feature = "scroll")]
+            extra_data: vec![],
+        }
+    }
+
+    /// Whether the opcode is undefined in the traced chain, although revm may know it.
+    fn is_disabled(&self, opcode: u8) -> bool {
+        #[cfg(feature = "scroll")]
+        if SCROLL_DISABLED_OPCODES.contains(&opcode) {
+            return true;
+        }
+        !self.is_cancun && CANCUN_OPCODES.contains(&opcode)
+    }
+
+    /// Executes a Cancun opcode that the interpreter doesn't know. As geth, the stack is checked
+    /// before the constant gas is charged.
+    fn execute_cancun_op(
+        &mut self,
+        opcode: u8,
+        interp: &mut Interpreter,
+        is_static: bool,
+    ) -> InstructionResult {
+        let (num_pops, constant_gas) = match opcode {
+            // BLOBHASH
+            0x49 => (1, GasCost::FASTEST.0),
+            // TLOAD
+            0x5c => (1, GasCost::WARM_ACCESS.0),
+            // TSTORE
+            0x5d => (2, GasCost::WARM_ACCESS.0),
+            // MCOPY
+            0x5e => (3, GasCost::FASTEST.0),
+            _ => unreachable!("not a cancun opcode: {opcode:#x}"),
+        };
+        if interp.stack.len() < num_pops {
+            return InstructionResult::StackUnderflow;
+        }
+        if !interp.gas.record_cost(constant_gas) {
+            return InstructionResult::OutOfGas;
+        }
+
+        let mut pops = [U256::ZERO; 3];
+        for pop in pops.iter_mut().take(num_pops) {
+            *pop = interp.stack.pop().expect("stack length is checked");
+        }
+        let address = from_b160(interp.contract.address);
+        match opcode {
+            0x49 => {
+                let hash = usize::try_from(pops[0])
+                    .ok()
+                    .and_then(|index| self.blob_hashes.get(index))
+                    .map(|hash| U256::from_be_bytes(hash.0))
+                    .unwrap_or_default();
+                interp.stack.push(hash).expect("a value is popped");
+            }
+            0x5c => {
+                let value = self.transient_storage.get(address, from_u256(pops[0]));
+                interp
+                    .stack
+                    .push(to_u256(value))
+                    .expect("a value is popped");
+            }
+            0x5d => {
+                if is_static {
+                    return InstructionResult::StateChangeDuringStaticCall;
+                }
+                self.transient_storage
+                    .set(address, from_u256(pops[0]), from_u256(pops[1]));
+            }
+            0x5e => {
+                let (dst, src, len) = (pops[0], pops[1], pops[2]);
+                let Ok(len) = usize::try_from(len) else {
+                    return InstructionResult::OutOfGas;
+                };
+                if len == 0 {
+                    return InstructionResult::Continue;
+                }
+                let (Ok(dst), Ok(src)) = (usize::try_from(dst), usize::try_from(src)) else {
+                    return InstructionResult::OutOfGas;
+                };
+                let Some(end) = dst.max(src).checked_add(len) else {
+                    return InstructionResult::OutOfGas;
+                };
+                let num_words = |size: usize| (size as u64 + 31) / 32;
+                if !interp.gas.record_cost(GasCost::COPY.0 * num_words(len)) {
+                    return InstructionResult::OutOfGas;
+                }
+                let memory_size = num_words(end) as usize * 32;
+                if memory_size > interp.memory.len() {
+                    if !interp.gas.record_memory(memory_gas(memory_size / 32)) {
+                        return InstructionResult::OutOfGas;
+                    }
+                    interp.memory.resize(memory_size);
+                }
+                let data = interp.memory.get_slice(src, len).to_vec();
+                interp.memory.set(dst, &data);
+            }
+            _ => unreachable!(),
+        }
+
+        InstructionResult::Continue
+    }
+
+    /// Extra data of a step reading the code or state of other accounts. Same as l2geth, the code
+    /// of the receiver of the transaction comes first in the code list of the CALL-like opcodes.
+    #[cfg(feature = "scroll")]
+    fn trace_extra_data<DB: Database>(
+        &self,
+        op: OpcodeId,
+        stack: &[Word],
+        contract: Address,
+        data: &mut EVMData<'_, DB>,
+    ) -> Option<ExtraData> {
+        let nth_last =
+            |n: usize| (stack.len() > n).then(|| stack[stack.len() - 1 - n].to_address());
+        let (codes, accounts) = match op {
+            OpcodeId::CALL | OpcodeId::CALLCODE => (
+                vec![self.tx_to, nth_last(1)],
+                vec![Some(contract), nth_last(1)],
+            ),
+            OpcodeId::DELEGATECALL => (vec![self.tx_to, nth_last(1)], vec![]),
+            OpcodeId::STATICCALL => (vec![self.tx_to, nth_last(1)], vec![nth_last(1)]),
+            OpcodeId::SELFDESTRUCT => (vec![], vec![Some(contract), nth_last(0)]),
+            OpcodeId::SELFBALANCE => (vec![], vec![Some(contract)]),
+            OpcodeId::BALANCE | OpcodeId::EXTCODEHASH => (vec![], vec![nth_last(0)]),
+            OpcodeId::EXTCODESIZE | OpcodeId::EXTCODECOPY => (vec![nth_last(0)], vec![]),
+            _ => return None,
+        };
+
+        let code_list = codes
+            .into_iter()
+            .flatten()
+            .map(|address| {
+                let info = l2trace::peek_account(data, address);
+                l2trace::code(info.as_ref()).to_vec().into()
+            })
+            .collect::<Vec<_>>();
+        let proof_list = accounts
+            .into_iter()
+            .flatten()
+            .map(|address| {
+                let info = l2trace::peek_account(data, address);
+                l2trace::account_wrapper(address, info.as_ref())
+            })
+            .collect::<Vec<_>>();
+        Some(ExtraData {
+            code_list: (!code_list.is_empty()).then_some(code_list),
+            proof_list: (!proof_list.is_empty()).then_some(proof_list),
+        })
+    }
+
+    fn enter(&mut self, call_type: &'static str, caller: Address, to: Option<Address>, gas: u64) {
+        self.transient_storage.enter();
+        let is_top = self.call_frames.is_empty() && self.address.is_none();
+        self.call_frames.push(CallFrame {
+            call_type,
+            from: if is_top {
+                caller
+            } else {
+                self.address.unwrap_or(caller)
+            },
+            to,
+            gas,
+            gas_used: 0,
+            output: None,
+            error: None,
+            is_top,
+            calls: vec![],
+        });
+    }
+
+    fn exit(&mut self, ret: InstructionResult, remaining_gas: &RevmGas, out: &Bytes) {
+        self.transient_storage.exit(is_success(ret));
+        let Some(mut frame) = self.call_frames.pop() else {
+            return;
+        };
+        frame.end(ret, remaining_gas, out);
+        // As geth, calls failing the depth or balance check are not traced.
+        if matches!(
+            ret,
+            InstructionResult::CallTooDeep | InstructionResult::OutOfFund
+        ) {
+            return;
+        }
+        match self.call_frames.last_mut() {
+            Some(parent) => parent.calls.push(frame),
+            None => self.root_frames.push(frame),
+        }
+    }
+
+    fn call_trace(&mut self, tx: &Transaction, gas_used: u64) -> Result<GethCallTrace, Error> {
+        let root_frames = std::mem::take(&mut self.root_frames);
+        let mut root = match <[CallFrame; 1]>::try_from(root_frames) {
+            Ok([root]) if root.is_top => root,
+            Ok(root_frames) => self.tx_frame(tx, root_frames.into()),
+            Err(root_frames) => self.tx_frame(tx, root_frames),
+        };
+        root.gas_used = gas_used;
+        serde_json::from_value(root.to_json()).map_err(Error::SerdeError)
+    }
+
+    /// Frame of the transaction, for the case it's not seen by the inspector.
+    fn tx_frame(&self, tx: &Transaction, calls: Vec<CallFrame>) -> CallFrame {
+        CallFrame {
+            call_type: if tx.to.is_some() { "CALL" } else { "CREATE" },
+            from: tx.from,
+            to: tx.to,
+            gas: tx.gas_limit.as_u64(),
+            gas_used: 0,
+            output: None,
+            error: None,
+            is_top: true,
+            calls,
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for &mut StructLogger {
+    fn step(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        _: bool,
+    ) -> InstructionResult {
+        let depth = data.journaled_state.depth() as usize;
+        let opcode = interp.current_opcode();
+        let is_disabled = self.is_disabled(opcode);
+        let op = if is_disabled {
+            OpcodeId::INVALID(opcode)
+        } else {
+            OpcodeId::from(opcode)
+        };
+        let gas = interp.gas.remaining();
+        let stack = interp
+            .stack
+            .data()
+            .iter()
+            .copied()
+            .map(from_u256)
+            .collect::<Vec<_>>();
+
+        self.refunds.resize(depth, 0);
+        self.refunds[depth - 1] = interp.gas.refunded();
+        let refund = self.refunds.iter().sum::<i64>().max(0) as u64;
+        let address = from_b160(interp.contract.address);
+        self.address = Some(address);
+        self.callee_gas = None;
+
+        let is_sstore = op == OpcodeId::SSTORE && !self.config.disable_storage;
+        if is_sstore && stack.len() >= 2 {
+            self.storage
+                .entry(address)
+                .or_default()
+                .insert(stack[stack.len() - 1], stack[stack.len() - 2]);
+        }
+
+        #[cfg(feature = "scroll")]
+        {
+            let extra_data = self.trace_extra_data(op, &stack, address, data);
+            self.extra_data.push(extra_data);
+        }
+        if !is_disabled {
+            self.pending_steps.push(PendingStep {
+                index: self.struct_logs.len(),
+                opcode,
+                gas,
+                stack_len: stack.len() as u64,
+                sload_key: (op == OpcodeId::SLOAD)
+                    .then(|| stack.last().copied())
+                    .flatten(),
+            });
+        }
+        self.struct_logs.push(GethExecStep {
+            pc: interp.program_counter().into(),
+            op,
+            gas: Gas(gas),
+            gas_cost: GasCost(0),
+            refund: Gas(refund),
+            depth: depth as u16,
+            error: is_disabled.then_some(GethExecError::InvalidOpcode(op)),
+            #[cfg(feature = "enable-stack")]
+            stack: Stack(if self.config.disable_stack {
+                vec![]
+            } else {
+                stack
+            }),
+            #[cfg(feature = "enable-memory")]
+            memory: Memory(if self.config.enable_memory {
+                interp.memory.data().clone()
+            } else {
+                vec![]
+            }),
+            #[cfg(feature = "enable-storage")]
+            storage: Storage(if is_sstore {
+                self.storage.get(&address).cloned().unwrap_or_default()
+            } else {
+                HashMap::new()
+            }),
+        });
+
+        // The opcode halts the frame as invalid, without being executed.
+        if is_disabled {
+            return InstructionResult::OpcodeNotFound;
+        }
+        InstructionResult::Continue
+    }
+
+    fn step_end(
+        &mut self,
+        interp: &mut Interpreter,
+        _: &mut EVMData<'_, DB>,
+        is_static: bool,
+        eval: InstructionResult,
+    ) -> InstructionResult {
+        let pending = self
+            .pending_steps
+            .pop()
+            .expect("step_end must follow a step");
+
+        // The interpreter has already moved past the opcode, so the execution continues with the
+        // next opcode once the instruction result is reset.
+        let eval = if eval == InstructionResult::OpcodeNotFound
+            && CANCUN_OPCODES.contains(&pending.opcode)
+        {
+            let eval = self.execute_cancun_op(pending.opcode, interp, is_static);
+            interp.instruction_result = eval;
+            eval
+        } else {
+            eval
+        };
+        let struct_log = &mut self.struct_logs[pending.index];
+
+        // The gas cost of a step in geth includes the gas forwarded to a callee by CALL-like
+        // opcodes, but not the one forwarded by CREATE and CREATE2.
+        let callee_gas = self.callee_gas.take().unwrap_or(CalleeGas {
+            returned: 0,
+            excluded: 0,
+        });
+        struct_log.gas_cost = GasCost(
+            (pending.gas + callee_gas.returned)
+                .saturating_sub(interp.gas.remaining() + callee_gas.excluded),
+        );
+
+        // Same as geth, only errors raised before the execution of the opcode are recorded.
+        let (min_stack_ptr, max_stack_ptr) = struct_log.op.valid_stack_ptr_range();
+        struct_log.error = match eval {
+            InstructionResult::OutOfGas => Some(GethExecError::OutOfGas),
+            InstructionResult::StackUnderflow => Some(GethExecError::StackUnderflow {
+                stack_len: pending.stack_len,
+                required: STACK_LIMIT - max_stack_ptr as u64,
+            }),
+            InstructionResult::StackOverflow => Some(GethExecError::StackOverflow {
+                stack_len: pending.stack_len,
+                limit: STACK_LIMIT - min_stack_ptr as u64,
+            }),
+            InstructionResult::OpcodeNotFound
+            | InstructionResult::InvalidFEOpcode
+            | InstructionResult::NotActivated => Some(GethExecError::InvalidOpcode(struct_log.op)),
+            _ => None,
+        };
+
+        // The loaded value of SLOAD is only known once it's pushed onto the stack.
+        if let (Some(key), Some(value)) = (pending.sload_key, interp.stack.data().last().copied()) {
+            if struct_log.error.is_none() && !self.config.disable_storage {
+                let contract_storage = self
+                    .storage
+                    .entry(from_b160(interp.contract.address))
+                    .or_default();
+                contract_storage.insert(key, from_u256(value));
+                #[cfg(feature = "enable-storage")]
+                {
+                    struct_log.storage = Storage(contract_storage.clone());
+                }
+            }
+        }
+
+        InstructionResult::Continue
+    }
+
+    fn call(
+        &mut self,
+        _: &mut EVMData<'_, DB>,
+        inputs: &mut CallInputs,
+        _: bool,
+    ) -> (InstructionResult, RevmGas, Bytes) {
+        let call_type = match inputs.context.scheme {
+            CallScheme::Call => "CALL",
+            CallScheme::CallCode => "CALLCODE",
+            CallScheme::DelegateCall => "DELEGATECALL",
+            CallScheme::StaticCall => "STATICCALL",
+        };
+        self.enter(
+            call_type,
+            from_b160(inputs.context.caller),
+            Some(from_b160(inputs.contract)),
+            inputs.gas_limit,
+        );
+
+        (InstructionResult::Continue, RevmGas::new(0), Bytes::new())
+    }
+
+    fn call_end(
+        &mut self,
+        _: &mut EVMData<'_, DB>,
+        _: &CallInputs,
+        remaining_gas: RevmGas,
+        ret: InstructionResult,
+        out: Bytes,
+        _: bool,
+    ) -> (InstructionResult, RevmGas, Bytes) {
+        self.callee_gas = Some(CalleeGas {
+            returned: if returns_gas(ret) {
+                remaining_gas.remaining()
+            } else {
+                0
+            },
+            excluded: 0,
+        });
+        self.exit(ret, &remaining_gas, &out);
+
+        (ret, remaining_gas, out)
+    }
+
+    fn create(
+        &mut self,
+        _: &mut EVMData<'_, DB>,
+        inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<B160>, RevmGas, Bytes) {
+        let call_type = match inputs.scheme {
+            CreateScheme::Create => "CREATE",
+            CreateScheme::Create2 { .. } => "CREATE2",
+        };
+        self.enter(call_type, from_b160(inputs.caller), None, inputs.gas_limit);
+
+        (
+            InstructionResult::Continue,
+            None,
+            RevmGas::new(0),
+            Bytes::new(),
+        )
+    }
+
+    fn create_end(
+        &mut self,
+        _: &mut EVMData<'_, DB>,
+        inputs: &CreateInputs,
+        ret: InstructionResult,
+        address: Option<B160>,
+        remaining_gas: RevmGas,
+        out: Bytes,
+    ) -> (InstructionResult, Option<B160>, RevmGas, Bytes) {
+        self.callee_gas = Some(CalleeGas {
+            returned: if returns_gas(ret) {
+                remaining_gas.remaining()
+            } else {
+                0
+            },
+            excluded: inputs.gas_limit,
+        });
+        if let Some(frame) = self.call_frames.last_mut() {
+            frame.to = address.map(from_b160);
+        }
+        self.exit(ret, &remaining_gas, &out);
+
+        (ret, address, remaining_gas, out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChainConfig;
+    use eth_types::{
+        bytecode,
+        geth_types::{Account, BlockConstants},
+        Bytecode, ToWord,
+    };
+    use std::collections::BTreeMap;
+
+    fn trace_config(code: Bytecode, chain_config: ChainConfig) -> TraceConfig {
+        let contract = Address::repeat_byte(0xcc);
+        let sender = Address::repeat_byte(0xee);
+        TraceConfig {
+            chain_id: 1,
+            block_constants: BlockConstants {
+                number: 1.into(),
+                gas_limit: 10_000_000.into(),
+                ..Default::default()
+            },
+            accounts: BTreeMap::from([
+                (
+                    contract,
+                    Account {
+                        address: contract,
+                        code: code.into(),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    sender,
+                    Account {
+                        address: sender,
+                        balance: Word::from(10u64.pow(19)),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+            transactions: vec![Transaction {
+                from: sender,
+                to: Some(contract),
+                gas_limit: 1_000_000.into(),
+                gas_price: Some(1.into()),
+                ..Default::default()
+            }],
+            logger_config: LoggerConfig {
+                enable_memory: true,
+                disable_stack: false,
+                disable_storage: false,
+                enable_return_data: true,
+            },
+            chain_config: Some(chain_config),
+            ..Default::default()
+        }
+    }
+
+    fn steps(trace: &GethExecTrace) -> Vec<(OpcodeId, u64, u16)> {
+        trace
+            .struct_logs
+            .iter()
+            .map(|step| (step.op, step.gas_cost.0, step.depth))
+            .collect()
+    }
+
+    #[test]
+    fn revm_tracer_sstore_sload() {
+        let code = bytecode! {
+            PUSH1(0x02)
+            PUSH1(0x01)
+            SSTORE
+            PUSH1(0x01)
+            SLOAD
+            STOP
+        };
+        let traces = RevmTracer
+            .trace(&trace_config(code, ChainConfig::shanghai()))
+            .unwrap();
+        assert_eq!(traces.len(), 1);
+
+        let trace = &traces[0];
+        assert!(!trace.failed);
+        assert_eq!(trace.gas.0, 21_000 + 3 + 3 + 22_100 + 3 + 100);
+        assert_eq!(
+            steps(trace),
+            vec![
+                (OpcodeId::PUSH1, 3, 1),
+                (OpcodeId::PUSH1, 3, 1),
+                (OpcodeId::SSTORE, 22_100, 1),
+                (OpcodeId::PUSH1, 3, 1),
+                (OpcodeId::SLOAD, 100, 1),
+                (OpcodeId::STOP, 0, 1),
+            ]
+        );
+    }
+
+    #[cfg(feature = "cancun")]
+    #[test]
+    fn revm_tracer_cancun_opcodes() {
+        let code = bytecode! {
+            PUSH1(0x2a)
+            PUSH1(0x01)
+            TSTORE
+            PUSH1(0x01)
+            TLOAD
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(0x20) // length
+            PUSH1(0x00) // source offset
+            PUSH1(0x20) // destination offset
+            MCOPY
+            PUSH1(0x00)
+            BLOBHASH
+            PUSH1(0x20)
+            PUSH1(0x20)
+            RETURN
+        };
+        let traces = RevmTracer
+            .trace(&trace_config(code, ChainConfig::cancun()))
+            .unwrap();
+
+        let trace = &traces[0];
+        assert!(!trace.failed);
+        assert_eq!(trace.return_value, format!("{:064x}", 0x2a));
+        assert_eq!(trace.gas.0, 21_000 + 248);
+        assert_eq!(
+            steps(trace),
+            vec![
+                (OpcodeId::PUSH1, 3, 1),
+                (OpcodeId::PUSH1, 3, 1),
+                (OpcodeId::TSTORE, 100, 1),
+                (OpcodeId::PUSH1, 3, 1),
+                (OpcodeId::TLOAD, 100, 1),
+                (OpcodeId::PUSH1, 3, 1),
+                (OpcodeId::MSTORE, 6, 1),
+                (OpcodeId::PUSH1, 3, 1),
+                (OpcodeId::PUSH1, 3, 1),
+                (OpcodeId::PUSH1, 3, 1),
+                // 3 + 3 for the copied word + 3 for the memory expansion.
+                (OpcodeId::MCOPY, 9, 1),
+                (OpcodeId::PUSH1, 3, 1),
+                (OpcodeId::BLOBHASH, 3, 1),
+                (OpcodeId::PUSH1, 3, 1),
+                (OpcodeId::PUSH1, 3, 1),
+                (OpcodeId::RETURN, 0, 1),
+            ]
+        );
+
+        // Before Cancun, the opcodes are undefined.
+        let code = bytecode! {
+            PUSH1(0x01)
+            TLOAD
+            STOP
+        };
+        let traces = RevmTracer
+            .trace(&trace_config(code, ChainConfig::shanghai()))
+            .unwrap();
+        let trace = &traces[0];
+        assert!(trace.failed);
+        assert_eq!(trace.gas.0, 1_000_000);
+        assert_eq!(
+            trace.struct_logs[1].error,
+            Some(GethExecError::InvalidOpcode(OpcodeId::INVALID(0x5c)))
+        );
+    }
+
+    #[test]
+    fn transient_storage_reverts_failed_calls() {
+        let address = Address::repeat_byte(0xcc);
+        let mut storage = TransientStorage::default();
+        storage.enter();
+        storage.set(address, 1.into(), 1.into());
+
+        // The failed callee's writes are reverted.
+        storage.enter();
+        storage.set(address, 1.into(), 2.into());
+        storage.set(address, 2.into(), 3.into());
+        storage.exit(false);
+        assert_eq!(storage.get(address, 1.into()), 1.into());
+        assert_eq!(storage.get(address, 2.into()), Word::zero());
+
+        // The successful callee's writes are kept.
+        storage.enter();
+        storage.set(address, 2.into(), 4.into());
+        storage.exit(true);
+        assert_eq!(storage.get(address, 2.into()), 4.into());
+
+        storage.exit(false);
+        assert_eq!(storage.get(address, 1.into()), Word::zero());
+        assert_eq!(storage.get(address, 2.into()), Word::zero());
+    }
+
+    #[cfg(feature = "scroll")]
+    #[test]
+    fn revm_tracer_scroll_disabled_opcodes() {
+        for op in [OpcodeId::INVALID(0x48), OpcodeId::INVALID(0xff)] {
+            let mut code = bytecode! {
+                PUSH1(0x00)
+            };
+            code.write_op(op);
+            let traces = RevmTracer
+                .trace(&trace_config(code, ChainConfig::shanghai()))
+                .unwrap();
+
+            let trace = &traces[0];
+            assert!(trace.failed);
+            assert_eq!(trace.gas.0, 1_000_000);
+            assert_eq!(
+                trace.struct_logs[1].error,
+                Some(GethExecError::InvalidOpcode(op))
+            );
+        }
+    }
+
+    /// Code writing a word of memory, calling the sender with it and reverting with the output.
+    #[cfg(feature = "geth")]
+    fn call_and_revert_code() -> Bytecode {
+        bytecode! {
+            PUSH32(0xdeadbeefu64)
+            PUSH1(0x00)
+            MSTORE
+            // Call the sender, which has no code.
+            PUSH1(0x20)
+            PUSH1(0x00)
+            PUSH1(0x20)
+            PUSH1(0x00)
+            PUSH1(0x00)
+            PUSH20(Address::repeat_byte(0xee).to_word())
+            GAS
+            CALL
+            PUSH1(0x20)
+            PUSH1(0x00)
+            REVERT
+        }
+    }
+
+    #[cfg(feature = "geth")]
+    #[test]
+    fn revm_tracer_matches_geth() {
+        let config = trace_config(call_and_revert_code(), ChainConfig::shanghai());
+        let revm_traces = RevmTracer.trace(&config).unwrap();
+        let geth_traces = crate::GethTracer.trace(&config).unwrap();
+
+        assert_eq!(revm_traces.len(), geth_traces.len());
+        for (revm_trace, geth_trace) in revm_traces.iter().zip(geth_traces.iter()) {
+            assert_eq!(revm_trace.gas, geth_trace.gas);
+            assert_eq!(revm_trace.failed, geth_trace.failed);
+            assert_eq!(revm_trace.return_value, geth_trace.return_value);
+            assert_eq!(revm_trace.struct_logs, geth_trace.struct_logs);
+            assert_eq!(revm_trace.account_after, geth_trace.account_after);
+            assert_eq!(revm_trace.call_trace, geth_trace.call_trace);
+        }
+    }
+
+    #[cfg(all(feature = "geth", feature = "scroll"))]
+    #[test]
+    fn revm_l2trace_matches_geth() {
+        let sstore_code = bytecode! {
+            PUSH1(0x02)
+            PUSH1(0x01)
+            SSTORE
+            PUSH1(0x01)
+            SLOAD
+            STOP
+        };
+        for code in [call_and_revert_code(), sstore_code] {
+            let config = trace_config(code, ChainConfig::shanghai());
+            let revm_trace = RevmTracer.l2trace(&config).unwrap();
+            let geth_trace = crate::GethTracer.l2trace(&config).unwrap();
+
+            assert_eq!(revm_trace.chain_id, geth_trace.chain_id);
+            assert_eq!(revm_trace.coinbase, geth_trace.coinbase);
+            assert_eq!(revm_trace.header.number, geth_trace.header.number);
+            assert_eq!(revm_trace.header.state_root, geth_trace.header.state_root);
+            assert_eq!(
+                revm_trace.start_l1_queue_index,
+                geth_trace.start_l1_queue_index
+            );
+            // The hash of the transactions is the one l2geth computes when signing them.
+            let txs = |trace: &BlockTrace| {
+                trace
+                    .transactions
+                    .iter()
+                    .map(|tx| {
+                        let mut tx = serde_json::to_value(tx).unwrap();
+                        tx["txHash"].take();
+                        tx
+                    })
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(txs(&revm_trace), txs(&geth_trace));
+            assert_eq!(
+                serde_json::to_value(&revm_trace.storage_trace).unwrap(),
+                serde_json::to_value(&geth_trace.storage_trace).unwrap()
+            );
+            assert_eq!(
+                serde_json::to_value(&revm_trace.tx_storage_trace).unwrap(),
+                serde_json::to_value(&geth_trace.tx_storage_trace).unwrap()
+            );
+            assert_eq!(
+                serde_json::to_value(&revm_trace.execution_results).unwrap(),
+                serde_json::to_value(&geth_trace.execution_results).unwrap()
+            );
+        }
+    }
+}
@@ -0,0 +1,500 @@
+//! The l2-trace of the revm tracer.
+//!
+//! Same as l2geth, the state is committed to a zktrie, and the proofs of the accounts and storage
+//! slots touched by the block are all taken against the state before the block.
+
+use super::{
+    execute, from_b160, from_u256, is_l1_msg, to_b160, to_u256, TxExecution, BASE_FEE_SLOT,
+    L1_GAS_PRICE_ORACLE, OVERHEAD_SLOT, SCALAR_SLOT,
+};
+use crate::TraceConfig;
+use eth_types::{
+    geth_types::Transaction,
+    l2_types::{
+        AccountProofWrapper, AccountTrieProofs, BlockTrace, EthBlock, ExecStep, ExecutionResult,
+        ExtraData, StorageTrace, StorageTrieProofs, TransactionTrace,
+    },
+    Address, Bytes, Error, GethExecStep, ToBigEndian, Word, H256,
+};
+use mpt_zktrie::state::{
+    builder::{hash_code_poseidon, init_hash_scheme},
+    AccountData, ZkMemoryDb, ZkTrie,
+};
+use revm::{
+    db::InMemoryDB,
+    primitives::{create_address, AccountInfo, Bytecode, State, B160},
+    Database, EVMData,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Address of the L2 message queue, same as `l2_predeployed` of bus-mapping.
+const L2_MESSAGE_QUEUE: [u8; 20] = [
+    0x53, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+];
+/// Slot of the withdraw trie root in the L2 message queue.
+const WITHDRAW_TRIE_ROOT_SLOT: u64 = 0;
+/// Type of the L1 message transactions.
+const L1_MESSAGE_TX_TYPE: u8 = 0x7e;
+/// Prefix of the leaf nodes of the zktrie.
+const NODE_TYPE_LEAF: u8 = 4;
+
+/// Builds the l2-trace of the block in the config.
+pub(super) fn block_trace(config: &TraceConfig) -> Result<BlockTrace, Error> {
+    let mut trie = StateTrie::new(config)?;
+    let root_before = trie.root();
+    let (mut db, txs) = execute(config)?;
+
+    // As l2geth, the withdraw trie root and the L1 fee parameters are always proven.
+    let mut touched = BTreeMap::from([
+        (
+            Address::from(L2_MESSAGE_QUEUE),
+            BTreeSet::from([Word::from(WITHDRAW_TRIE_ROOT_SLOT)]),
+        ),
+        (
+            Address::from(L1_GAS_PRICE_ORACLE),
+            BTreeSet::from([BASE_FEE_SLOT, OVERHEAD_SLOT, SCALAR_SLOT].map(Word::from)),
+        ),
+    ]);
+    let mut tx_storage_trace = Vec::with_capacity(txs.len());
+    let mut execution_results = Vec::with_capacity(txs.len());
+    for (i, tx) in txs.into_iter().enumerate() {
+        let (proofs, storage_proofs) = trie.proofs(&tx.state.touched);
+        tx_storage_trace.push(StorageTrace {
+            // There is no state root between the transactions, only the ones of the block.
+            root_before: if i == 0 { root_before } else { H256::zero() },
+            root_after: H256::zero(),
+            proofs: Some(proofs),
+            storage_proofs,
+            deletion_proofs: vec![],
+        });
+        for (address, keys) in &tx.state.touched {
+            touched.entry(*address).or_default().extend(keys);
+        }
+        execution_results.push(execution_result(tx));
+    }
+
+    let (proofs, storage_proofs) = trie.proofs(&touched);
+    let deletion_proofs = trie.commit(&mut db, &touched)?;
+    let root_after = trie.root();
+    if let Some(tx_storage_trace) = tx_storage_trace.last_mut() {
+        tx_storage_trace.root_after = root_after;
+    }
+
+    let block = &config.block_constants;
+    let coinbase = account_info(&mut db, block.coinbase);
+    // Same as l2geth, the difficulty stands in for the parent hash when there is no history.
+    let parent_hash = config
+        .history_hashes
+        .last()
+        .unwrap_or(&block.difficulty)
+        .to_be_bytes();
+    Ok(BlockTrace {
+        chain_id: config.chain_id,
+        coinbase: account_wrapper(block.coinbase, coinbase.as_ref()),
+        header: EthBlock {
+            parent_hash: H256(parent_hash),
+            number: Some(block.number),
+            gas_limit: block.gas_limit,
+            timestamp: block.timestamp,
+            state_root: root_after,
+            base_fee_per_gas: Some(block.base_fee),
+            ..Default::default()
+        },
+        transactions: config.transactions.iter().map(transaction_trace).collect(),
+        execution_results,
+        storage_trace: StorageTrace {
+            root_before,
+            root_after,
+            proofs: Some(proofs),
+            storage_proofs,
+            deletion_proofs,
+        },
+        tx_storage_trace,
+        start_l1_queue_index: config.l1_queue_index,
+    })
+}
+
+fn execution_result(tx: TxExecution) -> ExecutionResult {
+    let TxExecution {
+        trace,
+        extra_data,
+        state,
+    } = tx;
+    let exec_steps = trace
+        .struct_logs
+        .into_iter()
+        .zip(extra_data)
+        .map(|(step, extra_data)| exec_step(step, extra_data))
+        .collect();
+    ExecutionResult {
+        l1_fee: trace.l1_fee.into(),
+        gas: trace.gas.0,
+        failed: trace.failed,
+        return_value: trace.return_value,
+        from: Some(state.from),
+        to: state.to,
+        account_after: trace.account_after,
+        account_created: state.created,
+        code_hash: state.code.as_ref().map(|code| hash_code_poseidon(code)),
+        byte_code: state.code.map(|code| format!("0x{}", hex::encode(code))),
+        exec_steps,
+        call_trace: trace.call_trace,
+        prestate: trace.prestate,
+    }
+}
+
+fn exec_step(step: GethExecStep, extra_data: Option<ExtraData>) -> ExecStep {
+    ExecStep {
+        pc: step.pc.0 as u64,
+        op: step.op,
+        gas: step.gas.0,
+        gas_cost: step.gas_cost.0,
+        refund: step.refund.0,
+        depth: step.depth as isize,
+        error: step.error,
+        #[cfg(feature = "enable-stack")]
+        stack: Some(step.stack.0),
+        #[cfg(feature = "enable-memory")]
+        memory: Some(
+            step.memory
+                .0
+                .chunks(32)
+                .map(Word::from_big_endian)
+                .collect(),
+        ),
+        #[cfg(feature = "enable-storage")]
+        storage: Some(step.storage.0),
+        extra_data,
+    }
+}
+
+fn transaction_trace(tx: &Transaction) -> TransactionTrace {
+    let is_l1_msg = is_l1_msg(tx);
+    // The chain id of a legacy transaction is encoded in its v, as in EIP-155.
+    let chain_id = if is_l1_msg || tx.v < 35 {
+        0
+    } else {
+        (tx.v - 35) / 2
+    };
+    TransactionTrace {
+        tx_hash: tx.hash,
+        type_: if is_l1_msg { L1_MESSAGE_TX_TYPE } else { 0 },
+        nonce: tx.nonce.as_u64(),
+        gas: tx.gas_limit.as_u64(),
+        gas_price: if is_l1_msg {
+            Word::zero()
+        } else {
+            tx.gas_price.unwrap_or_default()
+        },
+        from: tx.from,
+        to: tx.to,
+        chain_id: chain_id.into(),
+        value: tx.value,
+        data: tx.call_data.clone(),
+        is_create: tx.to.is_none(),
+        v: tx.v.into(),
+        r: tx.r,
+        s: tx.s,
+    }
+}
+
+/// State of a transaction recorded by the l2-trace, besides its geth trace.
+#[derive(Debug)]
+pub(super) struct TxState {
+    /// Sender before the transaction.
+    from: AccountProofWrapper,
+    /// Receiver before the transaction.
+    to: Option<AccountProofWrapper>,
+    /// Code of the receiver.
+    code: Option<Vec<u8>>,
+    /// Address of the contract created by the transaction.
+    created_address: Option<Address>,
+    /// Created contract after the transaction.
+    created: Option<AccountProofWrapper>,
+    /// Accounts and storage slots accessed by the transaction.
+    touched: BTreeMap<Address, BTreeSet<Word>>,
+}
+
+impl TxState {
+    /// Records the sender and receiver before the transaction and its L1 fee.
+    pub(super) fn new(db: &mut InMemoryDB, tx: &Transaction) -> Self {
+        let from = account_info(db, tx.from);
+        let to = tx.to.map(|to| (to, account_info(db, to)));
+        let created_address = tx.to.is_none().then(|| {
+            let nonce = from.as_ref().map_or(0, |info| info.nonce);
+            from_b160(create_address(to_b160(tx.from), nonce))
+        });
+        Self {
+            from: account_wrapper(tx.from, from.as_ref()),
+            to: to
+                .as_ref()
+                .map(|(to, info)| account_wrapper(*to, info.as_ref())),
+            code: to.map(|(_, info)| code(info.as_ref()).to_vec()),
+            created_address,
+            created: None,
+            touched: BTreeMap::new(),
+        }
+    }
+
+    /// Records the accounts and storage slots in the state changed by the transaction. The
+    /// precompiles are only loaded by revm unless they're called.
+    pub(super) fn touch(&mut self, state: &State) {
+        for (address, account) in state {
+            if !account.is_touched() && is_precompile(address) {
+                continue;
+            }
+            self.touched
+                .entry(from_b160(*address))
+                .or_default()
+                .extend(account.storage.keys().copied().map(from_u256));
+        }
+    }
+
+    /// Records the created contract once the transaction is committed with its L1 fee, and
+    /// returns the sender, receiver or created contract, and coinbase after the transaction.
+    pub(super) fn settle(
+        &mut self,
+        db: &mut InMemoryDB,
+        config: &TraceConfig,
+        tx: &Transaction,
+    ) -> Vec<AccountProofWrapper> {
+        if let Some(address) = self.created_address {
+            let info = account_info(db, address);
+            self.created = Some(account_wrapper(address, info.as_ref()));
+        }
+        [
+            Some(tx.from),
+            tx.to.or(self.created_address),
+            Some(config.block_constants.coinbase),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|address| {
+            self.touched.entry(address).or_default();
+            let info = account_info(db, address);
+            account_wrapper(address, info.as_ref())
+        })
+        .collect()
+    }
+}
+
+fn is_precompile(address: &B160) -> bool {
+    address.0[..19].iter().all(|byte| *byte == 0) && (1..=10).contains(&address.0[19])
+}
+
+/// State of an account in the database, or `None` if it doesn't exist. As l2geth deletes the
+/// empty accounts at the end of the transactions, they don't exist either.
+fn account_info<DB: Database>(db: &mut DB, address: Address) -> Option<AccountInfo> {
+    let mut info = db
+        .basic(to_b160(address))
+        .ok()
+        .flatten()
+        .filter(|info| !info.is_empty())?;
+    if info.code.is_none() {
+        info.code = db.code_by_hash(info.code_hash).ok();
+    }
+    Some(info)
+}
+
+/// Current state of an account in the transaction, read without warming it up.
+pub(super) fn peek_account<DB: Database>(
+    data: &mut EVMData<'_, DB>,
+    address: Address,
+) -> Option<AccountInfo> {
+    let Some(account) = data.journaled_state.state.get(&to_b160(address)) else {
+        return account_info(data.db, address);
+    };
+    let mut info = account.info.clone();
+    if info.code.is_none() {
+        info.code = data.db.code_by_hash(info.code_hash).ok();
+    }
+    (!info.is_empty()).then_some(info)
+}
+
+pub(super) fn code(info: Option<&AccountInfo>) -> revm::primitives::Bytes {
+    info.and_then(|info| info.code.as_ref())
+        .map(Bytecode::original_bytes)
+        .unwrap_or_default()
+}
+
+/// State of an account as recorded by l2geth, with zero code hashes if it doesn't exist.
+pub(super) fn account_wrapper(address: Address, info: Option<&AccountInfo>) -> AccountProofWrapper {
+    let (nonce, balance, keccak_code_hash, poseidon_code_hash) = match info {
+        Some(info) => (
+            info.nonce,
+            from_u256(info.balance),
+            H256(info.code_hash.0),
+            hash_code_poseidon(&code(Some(info))),
+        ),
+        None => (0, Word::zero(), H256::zero(), H256::zero()),
+    };
+    AccountProofWrapper {
+        address: Some(address),
+        nonce: Some(nonce),
+        balance: Some(balance),
+        keccak_code_hash: Some(keccak_code_hash),
+        poseidon_code_hash: Some(poseidon_code_hash),
+        storage: None,
+    }
+}
+
+fn trie_error(err: impl std::fmt::Debug) -> Error {
+    Error::TracingError(format!("zktrie: {err:?}"))
+}
+
+/// The zktrie of the state, sharing its database with the storage tries of the accounts.
+struct StateTrie {
+    trie: ZkTrie,
+}
+
+impl StateTrie {
+    /// Builds the trie of the accounts in the config.
+    fn new(config: &TraceConfig) -> Result<Self, Error> {
+        init_hash_scheme();
+        let zk_db = ZkMemoryDb::new();
+        let trie = zk_db
+            .new_trie(&[0; 32])
+            .expect("the empty trie always exists");
+        let mut state_trie = Self { trie };
+        for (address, account) in &config.accounts {
+            let info = AccountInfo::new(
+                to_u256(account.balance),
+                account.nonce.as_u64(),
+                Bytecode::new_raw(account.code.to_vec().into()),
+            );
+            // The empty accounts are deleted by l2geth when the state is committed.
+            if info.is_empty() {
+                continue;
+            }
+            let storage = account.storage.iter().map(|(key, value)| (*key, *value));
+            state_trie.update_account(*address, &info, storage)?;
+        }
+        Ok(state_trie)
+    }
+
+    fn root(&self) -> H256 {
+        H256(self.trie.root())
+    }
+
+    fn account(&self, address: Address) -> Option<AccountData> {
+        self.trie
+            .get_account(address.as_bytes())
+            .map(AccountData::from)
+    }
+
+    fn storage_trie(&self, account: Option<&AccountData>) -> ZkTrie {
+        let root = account.map_or([0; 32], |account| account.storage_root.0);
+        let zk_db = self.trie.get_db();
+        zk_db
+            .new_trie(&root)
+            .expect("the storage trie is in the database")
+    }
+
+    /// Proofs of the accounts and storage slots against the current state.
+    fn proofs(
+        &self,
+        touched: &BTreeMap<Address, BTreeSet<Word>>,
+    ) -> (AccountTrieProofs, StorageTrieProofs) {
+        let wrap_proof =
+            |proof: Vec<Vec<u8>>| proof.into_iter().map(Bytes::from).collect::<Vec<_>>();
+        let proofs = touched
+            .keys()
+            .map(|address| {
+                let proof = self.trie.prove(address.as_bytes()).unwrap_or_default();
+                (*address, wrap_proof(proof))
+            })
+            .collect();
+        let storage_proofs = touched
+            .iter()
+            .filter(|(_, keys)| !keys.is_empty())
+            .map(|(address, keys)| {
+                let storage_trie = self.storage_trie(self.account(*address).as_ref());
+                let proofs = keys
+                    .iter()
+                    .map(|key| {
+                        let proof = storage_trie.prove(&key.to_be_bytes()).unwrap_or_default();
+                        (*key, wrap_proof(proof))
+                    })
+                    .collect();
+                (*address, proofs)
+            })
+            .collect();
+        (proofs, storage_proofs)
+    }
+
+    /// Commits the touched accounts and storage slots of the database, and returns the deletion
+    /// proofs of the cleared storage slots.
+    fn commit(
+        &mut self,
+        db: &mut InMemoryDB,
+        touched: &BTreeMap<Address, BTreeSet<Word>>,
+    ) -> Result<Vec<Bytes>, Error> {
+        let mut deletion_proofs = vec![];
+        for (address, keys) in touched {
+            let Some(info) = account_info(db, *address) else {
+                if self.account(*address).is_some() {
+                    self.trie.delete(address.as_bytes());
+                }
+                continue;
+            };
+            let storage = keys
+                .iter()
+                .map(|key| {
+                    let value = db
+                        .storage(to_b160(*address), to_u256(*key))
+                        .expect("in-memory db never fails");
+                    (*key, from_u256(value))
+                })
+                .collect::<Vec<_>>();
+            deletion_proofs.extend(self.update_account(*address, &info, storage)?);
+        }
+        Ok(deletion_proofs)
+    }
+
+    /// Writes the account with the given storage slots, and returns the deletion proofs of the
+    /// cleared slots.
+    fn update_account(
+        &mut self,
+        address: Address,
+        info: &AccountInfo,
+        storage: impl IntoIterator<Item = (Word, Word)>,
+    ) -> Result<Vec<Bytes>, Error> {
+        let mut storage_trie = self.storage_trie(self.account(address).as_ref());
+        let mut deletion_proofs = vec![];
+        for (key, value) in storage {
+            let key = key.to_be_bytes();
+            if !value.is_zero() {
+                storage_trie
+                    .update_store(&key, &value.to_be_bytes())
+                    .map_err(trie_error)?;
+            } else if storage_trie.get_store(&key).is_some() {
+                storage_trie.delete(&key);
+                deletion_proofs.extend(deletion_proof(&storage_trie, &key));
+            }
+        }
+
+        let code = code(Some(info));
+        let nonce_codesize = (Word::from(code.len()) << 64) + Word::from(info.nonce);
+        let account = [
+            nonce_codesize.to_be_bytes(),
+            info.balance.to_be_bytes::<32>(),
+            storage_trie.root(),
+            info.code_hash.0,
+            hash_code_poseidon(&code).0,
+        ];
+        self.trie
+            .update_account(address.as_bytes(), &account)
+            .map_err(trie_error)?;
+        Ok(deletion_proofs)
+    }
+}
+
+/// Proof of the leaf moved up in place of a deleted one, which can't be rebuilt from the proofs
+/// of the deleted key alone. It's the sibling of the deleted leaf, if that is a leaf too.
+fn deletion_proof(trie: &ZkTrie, key: &[u8]) -> Option<Bytes> {
+    trie.prove(key)
+        .ok()?
+        .into_iter()
+        .find(|node| node.first() == Some(&NODE_TYPE_LEAF))
+        .map(Bytes::from)
+}
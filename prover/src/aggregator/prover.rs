@@ -5,7 +5,7 @@ use crate::{
     io::{force_to_read, try_to_read},
     BatchProof, ChunkProof,
 };
-use aggregator::{ChunkHash, MAX_AGG_SNARKS};
+use aggregator::{AggregatorError, BatchInfo, ChunkHash, MAX_AGG_SNARKS};
 use anyhow::{bail, Result};
 use sha2::{Digest, Sha256};
 use snark_verifier_sdk::Snark;
@@ -23,7 +23,8 @@ impl Prover {
     pub fn from_dirs(params_dir: &str, assets_dir: &str) -> Self {
         env::set_var("KECCAK_ROW", AGG_KECCAK_ROW.to_string());
 
-        let inner = common::Prover::from_params_dir(params_dir, &AGG_DEGREES);
+        let mut inner = common::Prover::from_params_dir(params_dir, &AGG_DEGREES);
+        inner.set_pk_dir(assets_dir, *common::SAVE_PKS);
         let chunk_protocol = force_to_read(assets_dir, &CHUNK_PROTOCOL_FILENAME);
 
         let raw_vk = try_to_read(assets_dir, &AGG_VK_FILENAME);
@@ -76,9 +77,8 @@ impl Prover {
             || {
                 chunk_hashes_proofs
                     .last()
-                    .unwrap()
-                    .0
-                    .public_input_hash()
+                    .map(|(chunk_hash, _)| chunk_hash.public_input_hash())
+                    .unwrap_or_default()
                     .to_low_u64_le()
                     .to_string()
             },
@@ -109,6 +109,36 @@ impl Prover {
         Ok(batch_proof)
     }
 
+    // Return the layer-4 snark of the batch, to be aggregated in a bundle instead of verified by
+    // its EVM proof.
+    pub fn load_or_gen_batch_snark(
+        &mut self,
+        name: &str,
+        chunk_hashes_proofs: Vec<(ChunkHash, ChunkProof)>,
+        output_dir: Option<&str>,
+    ) -> Result<(BatchInfo, Snark)> {
+        let chunk_hashes: Vec<_> = chunk_hashes_proofs.iter().map(|(hash, _)| *hash).collect();
+        let batch_info = BatchInfo::from_chunk_hashes(&chunk_hashes)?;
+
+        let layer3_snark =
+            self.load_or_gen_last_agg_snark(name, chunk_hashes_proofs, output_dir)?;
+
+        // Load or generate final compression thin snark (layer-4).
+        let layer4_snark = self.inner.load_or_gen_comp_snark(
+            name,
+            LayerId::Layer4.id(),
+            true,
+            LayerId::Layer4.degree(),
+            layer3_snark,
+            output_dir,
+        )?;
+        log::info!("Got final compression thin snark (layer-4): {name}");
+
+        self.check_and_clear_raw_vk();
+
+        Ok((batch_info, layer4_snark))
+    }
+
     // Generate previous snark before the final one.
     // Then it could be used to generate a normal or EVM proof for verification.
     pub fn load_or_gen_last_agg_snark(
@@ -118,9 +148,22 @@ impl Prover {
         output_dir: Option<&str>,
     ) -> Result<Snark> {
         let real_chunk_count = chunk_hashes_proofs.len();
-        assert!((1..=MAX_AGG_SNARKS).contains(&real_chunk_count));
+        if !(1..=MAX_AGG_SNARKS).contains(&real_chunk_count) {
+            return Err(AggregatorError::InvalidChunkCount {
+                expected: MAX_AGG_SNARKS,
+                actual: real_chunk_count,
+            }
+            .into());
+        }
 
         check_chunk_hashes(name, &chunk_hashes_proofs)?;
+        // The padding chunks are only added below.
+        if let Some(idx) = chunk_hashes_proofs
+            .iter()
+            .position(|(chunk_hash, _)| chunk_hash.is_padding)
+        {
+            bail!("{name} chunk-no-{idx} is a padding chunk");
+        }
         let (mut chunk_hashes, chunk_proofs): (Vec<_>, Vec<_>) =
             chunk_hashes_proofs.into_iter().unzip();
 
@@ -141,7 +184,7 @@ impl Prover {
         }
 
         // Load or generate aggregation snark (layer-3).
-        let layer3_snark = self.inner.load_or_gen_agg_snark(
+        let layer3_snark = self.inner.load_or_gen_agg_snark::<MAX_AGG_SNARKS>(
             name,
             LayerId::Layer3.id(),
             LayerId::Layer3.degree(),
@@ -0,0 +1,107 @@
+//! Prover daemon, see [`prover::daemon`] for the protocol.
+
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
+use prover::{
+    aggregator, bundle,
+    consts::{BATCH_PROTOCOL_FILENAME, CHUNK_PROTOCOL_FILENAME},
+    daemon::{self, DaemonConfig},
+    utils::{get_block_trace_from_file, init_env_and_log},
+    zkevm,
+};
+use std::{fs, path::Path};
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// Dir of the KZG params.
+    #[clap(long, default_value = "./params")]
+    params_dir: String,
+    /// Dir of the vks, the chunk protocol and the proving keys.
+    #[clap(long, default_value = "./assets")]
+    assets_dir: String,
+    /// Dir of the persisted jobs and their proofs.
+    #[clap(long, default_value = "./jobs")]
+    jobs_dir: String,
+    /// Path of the Unix socket accepting the requests.
+    #[clap(long, default_value = "./prover.sock")]
+    socket: String,
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run the daemon (default).
+    Daemon,
+    /// Generate the proving keys of all layers and save them to the assets dir, by proving a
+    /// chunk of the block traces, a batch of this chunk and a bundle of this batch.
+    GenPks {
+        /// Block trace files of the chunk.
+        #[clap(long = "trace", required = true)]
+        traces: Vec<String>,
+    },
+}
+
+fn main() -> Result<()> {
+    init_env_and_log("prover_daemon");
+    let args = Args::parse();
+
+    match &args.command {
+        None | Some(Command::Daemon) => daemon::run(DaemonConfig {
+            params_dir: args.params_dir,
+            assets_dir: args.assets_dir,
+            jobs_dir: args.jobs_dir,
+            socket_path: args.socket,
+        }),
+        Some(Command::GenPks { traces }) => gen_pks(&args.params_dir, &args.assets_dir, traces),
+    }
+}
+
+fn gen_pks(params_dir: &str, assets_dir: &str, traces: &[String]) -> Result<()> {
+    let chunk_trace = traces.iter().map(get_block_trace_from_file).collect();
+
+    let mut chunk_prover = zkevm::Prover::from_dirs(params_dir, assets_dir);
+    chunk_prover.inner.set_pk_dir(assets_dir, true);
+    let chunk_proof = chunk_prover.gen_chunk_proof(chunk_trace, Some("gen_pks"), None, None)?;
+    log::info!("gen-pks: got proving keys of the chunk layers");
+    // Release the chunk pks before generating the batch ones.
+    drop(chunk_prover);
+
+    // The batch prover requires the protocol of the chunk proofs.
+    let protocol_path = Path::new(assets_dir).join(&*CHUNK_PROTOCOL_FILENAME);
+    if !protocol_path.exists() {
+        fs::write(&protocol_path, &chunk_proof.protocol)?;
+        log::info!("gen-pks: wrote chunk protocol to {protocol_path:?}");
+    }
+
+    let chunk_hash = chunk_proof
+        .chunk_hash
+        .ok_or_else(|| anyhow!("chunk proof without chunk_info"))?;
+    let chunk_hashes_proofs = vec![(chunk_hash, chunk_proof)];
+    let mut batch_prover = aggregator::Prover::from_dirs(params_dir, assets_dir);
+    batch_prover.inner.set_pk_dir(assets_dir, true);
+    batch_prover.gen_agg_evm_proof(chunk_hashes_proofs.clone(), Some("gen_pks"), None)?;
+    log::info!("gen-pks: got proving keys of the batch layers");
+
+    let batch_info_snark =
+        batch_prover.load_or_gen_batch_snark("gen_pks", chunk_hashes_proofs, None)?;
+    drop(batch_prover);
+
+    // The bundle prover requires the protocol of the batch snarks.
+    let protocol_path = Path::new(assets_dir).join(&*BATCH_PROTOCOL_FILENAME);
+    if !protocol_path.exists() {
+        fs::write(
+            &protocol_path,
+            serde_json::to_vec(&batch_info_snark.1.protocol)?,
+        )?;
+        log::info!("gen-pks: wrote batch protocol to {protocol_path:?}");
+    }
+
+    let mut bundle_prover = bundle::Prover::from_dirs(params_dir, assets_dir);
+    bundle_prover.inner.set_pk_dir(assets_dir, true);
+    bundle_prover.gen_bundle_evm_proof(vec![batch_info_snark], Some("gen_pks"), None)?;
+    log::info!("gen-pks: got proving keys of the bundle layers");
+
+    Ok(())
+}
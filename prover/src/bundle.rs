@@ -0,0 +1,5 @@
+mod prover;
+mod verifier;
+
+pub use self::prover::Prover;
+pub use verifier::Verifier;
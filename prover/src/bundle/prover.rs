@@ -0,0 +1,207 @@
+use crate::{
+    common,
+    config::{LayerId, BUNDLE_DEGREES},
+    consts::{BATCH_PROTOCOL_FILENAME, BUNDLE_VK_FILENAME},
+    io::{force_to_read, try_to_read},
+    BundleProof,
+};
+use aggregator::{BatchInfo, MAX_BUNDLE_BATCHES};
+use anyhow::{bail, Result};
+use halo2_proofs::halo2curves::bn256::Fr;
+use sha2::{Digest, Sha256};
+use snark_verifier_sdk::Snark;
+
+/// Number of the accumulator elements in the instances of a batch snark (layer-4).
+const ACC_LEN: usize = 12;
+
+#[derive(Debug)]
+pub struct Prover {
+    // Make it public for testing with inner functions (unnecessary for FFI).
+    pub inner: common::Prover,
+    pub batch_protocol: Vec<u8>,
+    raw_vk: Option<Vec<u8>>,
+}
+
+impl Prover {
+    pub fn from_dirs(params_dir: &str, assets_dir: &str) -> Self {
+        let mut inner = common::Prover::from_params_dir(params_dir, &BUNDLE_DEGREES);
+        inner.set_pk_dir(assets_dir, *common::SAVE_PKS);
+        let batch_protocol = force_to_read(assets_dir, &BATCH_PROTOCOL_FILENAME);
+
+        let raw_vk = try_to_read(assets_dir, &BUNDLE_VK_FILENAME);
+        if raw_vk.is_none() {
+            log::warn!(
+                "bundle-prover: {} doesn't exist in {}",
+                *BUNDLE_VK_FILENAME,
+                assets_dir
+            );
+        }
+
+        Self {
+            inner,
+            batch_protocol,
+            raw_vk,
+        }
+    }
+
+    // Return true if batch snarks are valid (same protocol), false otherwise.
+    pub fn check_batch_snarks(&self, batch_snarks: &[Snark]) -> bool {
+        batch_snarks.iter().enumerate().all(|(i, snark)| {
+            let protocol = serde_json::to_vec(&snark.protocol).unwrap_or_default();
+            let result = protocol == self.batch_protocol;
+            if !result {
+                log::error!(
+                    "Non-match protocol of batch-snark index-{}: expected = {:x}, actual = {:x}",
+                    i,
+                    Sha256::digest(&self.batch_protocol),
+                    Sha256::digest(&protocol),
+                );
+            }
+
+            result
+        })
+    }
+
+    pub fn get_vk(&self) -> Option<Vec<u8>> {
+        self.inner
+            .raw_vk(LayerId::Layer6.id())
+            .or_else(|| self.raw_vk.clone())
+    }
+
+    // Return the EVM proof for verification.
+    // The batches are the layer-4 snarks of continuous batches, generated by
+    // `aggregator::Prover::load_or_gen_batch_snark`.
+    pub fn gen_bundle_evm_proof(
+        &mut self,
+        batch_infos_snarks: Vec<(BatchInfo, Snark)>,
+        name: Option<&str>,
+        output_dir: Option<&str>,
+    ) -> Result<BundleProof> {
+        let name = name.map_or_else(
+            || {
+                batch_infos_snarks
+                    .last()
+                    .map(|(batch_info, _)| batch_info.public_input_hash())
+                    .unwrap_or_default()
+                    .to_low_u64_le()
+                    .to_string()
+            },
+            |name| name.to_string(),
+        );
+
+        let layer5_snark = self.load_or_gen_bundle_snark(&name, batch_infos_snarks, output_dir)?;
+
+        // Load or generate final compression thin EVM proof (layer-6).
+        let evm_proof = self.inner.load_or_gen_comp_evm_proof(
+            &name,
+            LayerId::Layer6.id(),
+            true,
+            LayerId::Layer6.degree(),
+            layer5_snark,
+            output_dir,
+        )?;
+        log::info!("Got final compression thin EVM proof (layer-6): {name}");
+
+        self.check_and_clear_raw_vk();
+
+        let bundle_proof = BundleProof::from(evm_proof.proof);
+        if let Some(output_dir) = output_dir {
+            bundle_proof.dump(output_dir, &name)?;
+        }
+
+        Ok(bundle_proof)
+    }
+
+    // Generate previous snark before the final one.
+    // Then it could be used to generate a normal or EVM proof for verification.
+    pub fn load_or_gen_bundle_snark(
+        &mut self,
+        name: &str,
+        batch_infos_snarks: Vec<(BatchInfo, Snark)>,
+        output_dir: Option<&str>,
+    ) -> Result<Snark> {
+        let batch_count = batch_infos_snarks.len();
+        if !(1..=MAX_BUNDLE_BATCHES).contains(&batch_count) {
+            bail!("{name} has {batch_count} batches, expected 1 to {MAX_BUNDLE_BATCHES}");
+        }
+
+        check_batch_infos(name, &batch_infos_snarks)?;
+        let (batch_infos, batch_snarks): (Vec<_>, Vec<_>) = batch_infos_snarks.into_iter().unzip();
+
+        if !self.check_batch_snarks(&batch_snarks) {
+            bail!("non-match-batch-protocol: {name}");
+        }
+
+        // Load or generate bundle snark (layer-5).
+        let layer5_snark = self.inner.load_or_gen_bundle_snark::<MAX_BUNDLE_BATCHES>(
+            name,
+            LayerId::Layer5.id(),
+            LayerId::Layer5.degree(),
+            &batch_infos,
+            &batch_snarks,
+            output_dir,
+        )?;
+        log::info!("Got bundle snark (layer-5): {name}");
+
+        Ok(layer5_snark)
+    }
+
+    fn check_and_clear_raw_vk(&mut self) {
+        if self.raw_vk.is_some() {
+            // Check VK is same with the init one, and take (clear) init VK.
+            let gen_vk = self.inner.raw_vk(LayerId::Layer6.id()).unwrap_or_default();
+            let init_vk = self.raw_vk.take().unwrap_or_default();
+
+            if gen_vk != init_vk {
+                log::error!(
+                    "bundle-prover: generated VK is different with init one - gen_vk = {}, init_vk = {}",
+                    base64::encode(gen_vk),
+                    base64::encode(init_vk),
+                );
+            }
+        }
+    }
+}
+
+// Check the batches are continuous, on a same chain, and match their snarks.
+fn check_batch_infos(name: &str, batch_infos_snarks: &[(BatchInfo, Snark)]) -> Result<()> {
+    for (idx, (batch_info, snark)) in batch_infos_snarks.iter().enumerate() {
+        if !snark_matches_batch(snark, batch_info) {
+            bail!("{name} batch-no-{idx}, public input hash doesn't match the snark");
+        }
+
+        if idx == 0 {
+            continue;
+        }
+        let prev = &batch_infos_snarks[idx - 1].0;
+        if batch_info.chain_id != prev.chain_id {
+            bail!(
+                "{name} batch-no-{idx}, different chain_id: {} != {}",
+                batch_info.chain_id,
+                prev.chain_id
+            );
+        }
+        if batch_info.prev_state_root != prev.post_state_root {
+            bail!(
+                "{name} batch-no-{idx}, prev_state_root {} != post_state_root {} of previous batch",
+                batch_info.prev_state_root,
+                prev.post_state_root
+            );
+        }
+    }
+
+    Ok(())
+}
+
+// The instances of a batch snark are the accumulator and the bytes of its public input hash.
+fn snark_matches_batch(snark: &Snark, batch_info: &BatchInfo) -> bool {
+    let pi_hash = batch_info.public_input_hash();
+
+    snark.instances.first().map_or(false, |instances| {
+        instances.len() == ACC_LEN + pi_hash.as_bytes().len()
+            && instances[ACC_LEN..]
+                .iter()
+                .zip(pi_hash.as_bytes())
+                .all(|(value, byte)| *value == Fr::from(*byte as u64))
+    })
+}
@@ -1,5 +1,8 @@
 mod prover;
 mod verifier;
 
-pub use self::{prover::Prover, verifier::Verifier};
+pub use self::{
+    prover::{PkHeader, Prover, SAVE_PKS},
+    verifier::Verifier,
+};
 pub use aggregator::{ChunkHash, CompressionCircuit};
@@ -4,22 +4,32 @@ use halo2_proofs::{
     plonk::ProvingKey,
     poly::{commitment::Params, kzg::commitment::ParamsKZG},
 };
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::PathBuf,
+};
 
 mod aggregation;
+mod bundle;
 mod chunk;
 mod compression;
 mod evm;
 mod inner;
 mod mock;
+mod pk;
 mod utils;
 
+pub use pk::{PkHeader, SAVE_PKS};
+
 #[derive(Debug)]
 pub struct Prover {
     // degree -> params (use BTreeMap to find proper degree for params downsize)
     params_map: BTreeMap<u32, ParamsKZG<Bn256>>,
     // Cached id -> pk
     pk_map: HashMap<String, ProvingKey<G1Affine>>,
+    // Dir of the persisted pks, named `pk_<id>.bin`.
+    pk_dir: Option<PathBuf>,
+    save_pks: bool,
 }
 
 impl Prover {
@@ -27,6 +37,8 @@ impl Prover {
         Self {
             params_map,
             pk_map: HashMap::new(),
+            pk_dir: None,
+            save_pks: false,
         }
     }
 
@@ -65,6 +77,8 @@ impl Prover {
         Self {
             params_map,
             pk_map: HashMap::new(),
+            pk_dir: None,
+            save_pks: false,
         }
     }
 }
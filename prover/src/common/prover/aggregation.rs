@@ -11,7 +11,7 @@ use snark_verifier_sdk::Snark;
 use std::env;
 
 impl Prover {
-    pub fn gen_agg_snark(
+    pub fn gen_agg_snark<const N_SNARKS: usize>(
         &mut self,
         id: &str,
         degree: u32,
@@ -21,7 +21,7 @@ impl Prover {
     ) -> Result<Snark> {
         env::set_var("AGGREGATION_CONFIG", layer_config_path(id));
 
-        let batch_hash = BatchHash::construct(chunk_hashes);
+        let batch_hash = BatchHash::<N_SNARKS>::construct(chunk_hashes)?;
 
         let circuit =
             AggregationCircuit::new(self.params(degree), previous_snarks, &mut rng, batch_hash)
@@ -30,7 +30,7 @@ impl Prover {
         self.gen_snark(id, degree, &mut rng, circuit)
     }
 
-    pub fn load_or_gen_agg_snark(
+    pub fn load_or_gen_agg_snark<const N_SNARKS: usize>(
         &mut self,
         name: &str,
         id: &str,
@@ -50,7 +50,8 @@ impl Prover {
             Some(snark) => Ok(snark),
             None => {
                 let rng = gen_rng();
-                let result = self.gen_agg_snark(id, degree, rng, chunk_hashes, previous_snarks);
+                let result =
+                    self.gen_agg_snark::<N_SNARKS>(id, degree, rng, chunk_hashes, previous_snarks);
                 if let (Some(_), Ok(snark)) = (output_dir, &result) {
                     write_snark(&file_path, snark);
                 }
@@ -0,0 +1,63 @@
+use super::Prover;
+use crate::{
+    config::layer_config_path,
+    io::{load_snark, write_snark},
+    utils::gen_rng,
+};
+use aggregator::{BatchInfo, BundleCircuit, BundleHash};
+use anyhow::{anyhow, Result};
+use rand::Rng;
+use snark_verifier_sdk::Snark;
+use std::env;
+
+impl Prover {
+    pub fn gen_bundle_snark<const N_BATCHES: usize>(
+        &mut self,
+        id: &str,
+        degree: u32,
+        mut rng: impl Rng + Send,
+        batch_infos: &[BatchInfo],
+        batch_snarks: &[Snark],
+    ) -> Result<Snark> {
+        // The bundle circuit is configured as an aggregation circuit.
+        env::set_var("AGGREGATION_CONFIG", layer_config_path(id));
+
+        let bundle_hash = BundleHash::<N_BATCHES>::construct(batch_infos)?;
+
+        let circuit = BundleCircuit::new(self.params(degree), batch_snarks, &mut rng, bundle_hash)
+            .map_err(|err| anyhow!("Failed to construct bundle circuit: {err:?}"))?;
+
+        self.gen_snark(id, degree, &mut rng, circuit)
+    }
+
+    pub fn load_or_gen_bundle_snark<const N_BATCHES: usize>(
+        &mut self,
+        name: &str,
+        id: &str,
+        degree: u32,
+        batch_infos: &[BatchInfo],
+        batch_snarks: &[Snark],
+        output_dir: Option<&str>,
+    ) -> Result<Snark> {
+        let file_path = format!(
+            "{}/bundle_snark_{}_{}.json",
+            output_dir.unwrap_or_default(),
+            id,
+            name
+        );
+
+        match output_dir.and_then(|_| load_snark(&file_path).ok().flatten()) {
+            Some(snark) => Ok(snark),
+            None => {
+                let rng = gen_rng();
+                let result =
+                    self.gen_bundle_snark::<N_BATCHES>(id, degree, rng, batch_infos, batch_snarks);
+                if let (Some(_), Ok(snark)) = (output_dir, &result) {
+                    write_snark(&file_path, snark);
+                }
+
+                result
+            }
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use super::Prover;
+use crate::{
+    io::serialize_vk,
+    utils::{read_env_var, DEFAULT_SERDE_FORMAT},
+};
+use anyhow::{bail, Result};
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{keygen_vk, Circuit, ConstraintSystem, ProvingKey},
+    poly::kzg::commitment::ParamsKZG,
+};
+use memmap2::Mmap;
+use sha2::{Digest, Sha256};
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+/// Save the generated proving keys to the pk dir, they could be huge (GBs for the inner circuit).
+pub static SAVE_PKS: LazyLock<bool> =
+    LazyLock::new(|| read_env_var("SCROLL_PROVER_SAVE_PKS", false));
+
+const PK_MAGIC: &[u8; 8] = b"SCROLLPK";
+const PK_VERSION: u32 = 1;
+
+/// Header of a persisted proving key, used to reject the keys generated for another degree or
+/// another circuit config before reading them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PkHeader {
+    pub degree: u32,
+    /// SHA256 of the pinned constraint system of the circuit.
+    pub config_hash: [u8; 32],
+}
+
+impl PkHeader {
+    const LEN: usize = 8 + 4 + 4 + 32;
+
+    pub fn new<C: Circuit<Fr>>(degree: u32) -> Self {
+        Self {
+            degree,
+            config_hash: circuit_config_hash::<C>(),
+        }
+    }
+
+    fn write(&self, writer: &mut impl Write) -> Result<()> {
+        writer.write_all(PK_MAGIC)?;
+        writer.write_all(&PK_VERSION.to_le_bytes())?;
+        writer.write_all(&self.degree.to_le_bytes())?;
+        writer.write_all(&self.config_hash)?;
+
+        Ok(())
+    }
+
+    fn read(reader: &mut impl Read) -> Result<Self> {
+        let mut buf = [0; Self::LEN];
+        reader.read_exact(&mut buf)?;
+        if &buf[..8] != PK_MAGIC {
+            bail!("not a proving key file");
+        }
+        let version = u32::from_le_bytes(buf[8..12].try_into().unwrap());
+        if version != PK_VERSION {
+            bail!("unsupported proving key version {version}");
+        }
+
+        Ok(Self {
+            degree: u32::from_le_bytes(buf[12..16].try_into().unwrap()),
+            config_hash: buf[16..].try_into().unwrap(),
+        })
+    }
+}
+
+impl Prover {
+    /// Set the dir to load the proving keys from, and to save them to if `save` is true.
+    pub fn set_pk_dir(&mut self, pk_dir: &str, save: bool) {
+        self.pk_dir = Some(PathBuf::from(pk_dir));
+        self.save_pks = save;
+    }
+
+    pub fn pk_path(&self, id: &str) -> Option<PathBuf> {
+        self.pk_dir
+            .as_ref()
+            .map(|pk_dir| pk_dir.join(format!("pk_{id}.bin")))
+    }
+
+    /// Load the proving key of `id` from the pk dir, return `None` if it doesn't exist or is
+    /// stale.
+    pub(super) fn load_pk<C: Circuit<Fr>>(
+        &self,
+        id: &str,
+        header: &PkHeader,
+        params: &ParamsKZG<Bn256>,
+        circuit: &C,
+    ) -> Option<ProvingKey<G1Affine>> {
+        let path = self.pk_path(id).filter(|path| path.exists())?;
+
+        log::info!("Before load pk of {id} from {path:?}");
+        match read_pk::<C>(&path, header).and_then(|pk| check_vk(params, circuit, &pk).map(|_| pk))
+        {
+            Ok(pk) => {
+                log::info!("After load pk of {id}");
+                Some(pk)
+            }
+            Err(e) => {
+                log::warn!("Ignore proving key {path:?}: {e}");
+                None
+            }
+        }
+    }
+
+    pub(super) fn save_pk(&self, id: &str, header: &PkHeader, pk: &ProvingKey<G1Affine>) {
+        let path = match self.pk_path(id) {
+            Some(path) if self.save_pks => path,
+            _ => return,
+        };
+
+        log::info!("Before save pk of {id} to {path:?}");
+        match write_pk(&path, header, pk) {
+            Ok(()) => log::info!("After save pk of {id}"),
+            Err(e) => log::warn!("Failed to save proving key {path:?}: {e}"),
+        }
+    }
+}
+
+fn read_pk<C: Circuit<Fr>>(path: &Path, header: &PkHeader) -> Result<ProvingKey<G1Affine>> {
+    let file = File::open(path)?;
+
+    // Safety: the pk files are only replaced by renaming, never modified in place.
+    match unsafe { Mmap::map(&file) } {
+        Ok(mmap) => read_pk_from::<C>(&mut &mmap[..], header),
+        Err(e) => {
+            log::warn!("Failed to mmap {path:?}, fall back to read it: {e}");
+            read_pk_from::<C>(&mut BufReader::new(file), header)
+        }
+    }
+}
+
+fn read_pk_from<C: Circuit<Fr>>(
+    reader: &mut impl Read,
+    expected: &PkHeader,
+) -> Result<ProvingKey<G1Affine>> {
+    let header = PkHeader::read(reader)?;
+    if header.degree != expected.degree {
+        bail!(
+            "degree {} doesn't match the expected {}",
+            header.degree,
+            expected.degree
+        );
+    }
+    if header.config_hash != expected.config_hash {
+        bail!("generated for another circuit config");
+    }
+
+    Ok(ProvingKey::read::<_, C>(reader, DEFAULT_SERDE_FORMAT)?)
+}
+
+/// The header only covers the constraint system, not the fixed columns, e.g. the inner
+/// protocol that the compression and aggregation circuits embed as constants. So the verifying
+/// key of the circuit is generated, which is much cheaper than the proving key, and compared
+/// with the one of the loaded key.
+fn check_vk<C: Circuit<Fr>>(
+    params: &ParamsKZG<Bn256>,
+    circuit: &C,
+    pk: &ProvingKey<G1Affine>,
+) -> Result<()> {
+    let vk = keygen_vk(params, circuit)?;
+    if serialize_vk(&vk) != serialize_vk(pk.get_vk()) {
+        bail!("verifying key doesn't match the circuit");
+    }
+
+    Ok(())
+}
+
+fn write_pk(path: &Path, header: &PkHeader, pk: &ProvingKey<G1Affine>) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    // Write to a temporary file first, so a crash never leaves a truncated key behind.
+    let tmp_path = path.with_extension("tmp");
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+    header.write(&mut writer)?;
+    pk.write(&mut writer, DEFAULT_SERDE_FORMAT)?;
+    writer.into_inner()?.sync_all()?;
+    fs::rename(tmp_path, path)?;
+
+    Ok(())
+}
+
+/// Hash the constraint system (columns, gates, lookups and permutation) of the circuit, which
+/// changes with the circuit code and the layer configs.
+fn circuit_config_hash<C: Circuit<Fr>>() -> [u8; 32] {
+    let mut cs = ConstraintSystem::<Fr>::default();
+    C::configure(&mut cs);
+
+    let mut hasher = HashWriter(Sha256::new());
+    fmt::write(&mut hasher, format_args!("{:?}", cs.pinned())).unwrap();
+    hasher.0.finalize().into()
+}
+
+/// Hash the debug output without buffering it, since it's large for the super circuit.
+struct HashWriter(Sha256);
+
+impl fmt::Write for HashWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.update(s.as_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::gen_rng;
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        plonk::{keygen_pk2, Column, Error, Fixed},
+        poly::commitment::ParamsProver,
+    };
+
+    /// Circuit with the same constraint system for any constant, like a compression circuit
+    /// for any inner protocol.
+    #[derive(Clone)]
+    struct ConstantCircuit(Fr);
+
+    impl Circuit<Fr> for ConstantCircuit {
+        type Config = Column<Fixed>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            meta.fixed_column()
+        }
+
+        fn synthesize(
+            &self,
+            fixed: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "constant",
+                |mut region| region.assign_fixed(|| "constant", fixed, 0, || Value::known(self.0)),
+            )?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_pk_header_roundtrip() {
+        let header = PkHeader {
+            degree: 20,
+            config_hash: [7; 32],
+        };
+        let mut buf = vec![];
+        header.write(&mut buf).unwrap();
+        assert_eq!(buf.len(), PkHeader::LEN);
+        assert_eq!(PkHeader::read(&mut &buf[..]).unwrap(), header);
+
+        buf[0] = 0;
+        assert!(PkHeader::read(&mut &buf[..]).is_err());
+    }
+
+    #[test]
+    fn test_pk_of_another_constant_is_rejected() {
+        let dir = std::env::temp_dir().join(format!(
+            "prover_pk_{}_{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_subsec_nanos()
+        ));
+        let params = ParamsKZG::<Bn256>::setup(4, gen_rng());
+        let (circuit, other_circuit) = (ConstantCircuit(Fr::from(1)), ConstantCircuit(Fr::from(2)));
+
+        let mut prover = Prover::from_params(Default::default());
+        prover.set_pk_dir(&dir.to_string_lossy(), true);
+        let header = PkHeader::new::<ConstantCircuit>(4);
+        assert_eq!(header, PkHeader::new::<ConstantCircuit>(4));
+        let pk = keygen_pk2(&params, &circuit).unwrap();
+        prover.save_pk("constant", &header, &pk);
+
+        assert!(prover
+            .load_pk("constant", &header, &params, &circuit)
+            .is_some());
+        assert!(prover
+            .load_pk("constant", &header, &params, &other_circuit)
+            .is_none());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,4 +1,4 @@
-use super::Prover;
+use super::{PkHeader, Prover};
 use crate::io::serialize_vk;
 use anyhow::Result;
 use halo2_proofs::{
@@ -61,9 +61,21 @@ impl Prover {
             return Ok((&self.params_map[&degree], &self.pk_map[id]));
         }
 
-        log::info!("Before generate pk of {}", &id);
-        let pk = keygen_pk2(self.params(degree), circuit)?;
-        log::info!("After generate pk of {}", &id);
+        // Downsize params if needed, for both loading and generating pk.
+        self.params(degree);
+
+        let header = PkHeader::new::<C>(degree);
+        let pk = match self.load_pk(id, &header, &self.params_map[&degree], circuit) {
+            Some(pk) => pk,
+            None => {
+                log::info!("Before generate pk of {}", &id);
+                let pk = keygen_pk2(&self.params_map[&degree], circuit)?;
+                log::info!("After generate pk of {}", &id);
+
+                self.save_pk(id, &header, &pk);
+                pk
+            }
+        };
 
         self.pk_map.insert(id.to_string(), pk);
 
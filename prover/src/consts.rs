@@ -4,6 +4,16 @@ use std::sync::LazyLock;
 pub static AGG_KECCAK_ROW: LazyLock<usize> = LazyLock::new(|| read_env_var("AGG_KECCAK_ROW", 50));
 pub static AGG_VK_FILENAME: LazyLock<String> =
     LazyLock::new(|| read_env_var("AGG_VK_FILENAME", "agg_vk.vkey".to_string()));
+pub static BATCH_PROTOCOL_FILENAME: LazyLock<String> =
+    LazyLock::new(|| read_env_var("BATCH_PROTOCOL_FILENAME", "batch.protocol".to_string()));
+pub static BUNDLE_VK_FILENAME: LazyLock<String> =
+    LazyLock::new(|| read_env_var("BUNDLE_VK_FILENAME", "bundle_vk.vkey".to_string()));
+pub static BUNDLE_DEPLOYMENT_CODE_FILENAME: LazyLock<String> = LazyLock::new(|| {
+    read_env_var(
+        "BUNDLE_DEPLOYMENT_CODE_FILENAME",
+        "bundle_evm_verifier.bin".to_string(),
+    )
+});
 pub static CHUNK_PROTOCOL_FILENAME: LazyLock<String> =
     LazyLock::new(|| read_env_var("CHUNK_PROTOCOL_FILENAME", "chunk.protocol".to_string()));
 pub static CHUNK_VK_FILENAME: LazyLock<String> =
@@ -0,0 +1,268 @@
+//! Long-running prover daemon.
+//!
+//! The daemon keeps the params and the proving keys of the chunk and batch provers resident,
+//! and proves the jobs submitted over a Unix socket one at a time. Each job is persisted in
+//! its own dir with its input and proofs, so the queued and interrupted jobs are resumed after
+//! a restart, reusing the snarks already dumped by the provers.
+//!
+//! Each request and response is one line of JSON, the methods are:
+//! - `submit_chunk` with `{"name", "block_traces"}`, returns the queued [`Job`].
+//! - `submit_batch` with `{"name", "chunk_proofs"}`, returns the queued [`Job`].
+//! - `status` with `{"id"}`, returns the [`Job`] with its status and stage.
+//! - `list`, returns all the jobs.
+//! - `cancel` with `{"id"}`, a running job is cancelled before its next stage.
+//! - `proof` with `{"id"}`, returns the proof of a done job.
+//!
+//! The optional `name` of a job names its proof files, so it is restricted to `[A-Za-z0-9_-]`.
+
+mod job;
+mod server;
+mod store;
+
+pub use job::{Job, JobInput, JobKind, JobStatus};
+pub use server::{handle_request, serve, Request, Response};
+pub use store::JobStore;
+
+use crate::{aggregator, utils::chunk_trace_to_witness_block, zkevm};
+use anyhow::{anyhow, bail, Result};
+use std::{
+    any::Any,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{Arc, Condvar, Mutex},
+    thread,
+};
+
+/// Name of the batch proof dumped by [`aggregator::Prover::gen_agg_evm_proof`].
+const BATCH_PROOF_NAME: &str = "agg";
+
+#[derive(Clone, Debug)]
+pub struct DaemonConfig {
+    pub params_dir: String,
+    pub assets_dir: String,
+    /// Dir of the persisted jobs.
+    pub jobs_dir: String,
+    pub socket_path: String,
+}
+
+/// State shared by the server and the proving worker.
+#[derive(Debug)]
+pub struct Shared {
+    pub store: Mutex<JobStore>,
+    queued: Condvar,
+}
+
+impl Shared {
+    pub fn new(store: JobStore) -> Self {
+        Self {
+            store: Mutex::new(store),
+            queued: Condvar::new(),
+        }
+    }
+
+    pub fn submit(&self, name: Option<String>, input: JobInput) -> Result<Job> {
+        let job = self.store.lock().unwrap().submit(name, input)?;
+        log::info!("prover-daemon: job {} ({:?}) queued", job.id, job.kind);
+        self.queued.notify_one();
+        Ok(job)
+    }
+
+    /// Wait for the next queued job and mark it as running.
+    fn next_job(&self) -> Result<Job> {
+        let mut store = self.store.lock().unwrap();
+        loop {
+            if let Some(id) = store.next_queued() {
+                return store.update(id, |job| {
+                    job.status = JobStatus::Running;
+                    job.stage = Some("loading input".to_string());
+                });
+            }
+            store = self.queued.wait(store).unwrap();
+        }
+    }
+
+    /// Move a running job to its next stage, fails if the job has been cancelled meanwhile.
+    fn set_stage(&self, id: u64, stage: &str) -> Result<()> {
+        let job = self.store.lock().unwrap().update(id, |job| {
+            if !job.cancel_requested {
+                job.stage = Some(stage.to_string());
+            }
+        })?;
+        if job.cancel_requested {
+            bail!("job {id} cancelled");
+        }
+        Ok(())
+    }
+
+    fn finish(&self, id: u64, result: Result<()>) -> Result<()> {
+        let job = self.store.lock().unwrap().update(id, |job| {
+            job.stage = None;
+            job.status = match (&result, job.cancel_requested) {
+                (_, true) => JobStatus::Cancelled,
+                (Ok(()), false) => JobStatus::Done,
+                (Err(e), false) => {
+                    job.error = Some(e.to_string());
+                    JobStatus::Failed
+                }
+            };
+        })?;
+        log::info!("prover-daemon: job {id} {:?}", job.status);
+        Ok(())
+    }
+}
+
+/// Run the daemon, it only returns on error.
+pub fn run(config: DaemonConfig) -> Result<()> {
+    let shared = Arc::new(Shared::new(JobStore::open(&config.jobs_dir)?));
+    // Fail before accepting any job if the params or the assets cannot be loaded.
+    let mut worker = Worker::new(&config)?;
+
+    let worker_shared = shared.clone();
+    thread::spawn(move || loop {
+        if let Err(e) = worker.work(&worker_shared) {
+            log::error!("prover-daemon: worker error: {e}");
+        }
+    });
+
+    serve(&config.socket_path, shared)
+}
+
+/// Owns the provers, so their params and proving keys stay loaded between jobs.
+struct Worker {
+    chunk_prover: zkevm::Prover,
+    batch_prover: aggregator::Prover,
+}
+
+impl Worker {
+    fn new(config: &DaemonConfig) -> Result<Self> {
+        log::info!("prover-daemon: loading params and assets");
+        // The provers panic on missing params or assets.
+        let (chunk_prover, batch_prover) = catch_unwind(|| {
+            (
+                zkevm::Prover::from_dirs(&config.params_dir, &config.assets_dir),
+                aggregator::Prover::from_dirs(&config.params_dir, &config.assets_dir),
+            )
+        })
+        .map_err(|e| anyhow!("failed to load the provers: {}", panic_message(e)))?;
+        log::info!("prover-daemon: provers ready");
+
+        Ok(Self {
+            chunk_prover,
+            batch_prover,
+        })
+    }
+
+    fn work(&mut self, shared: &Shared) -> Result<()> {
+        let job = shared.next_job()?;
+        log::info!("prover-daemon: job {} ({:?}) started", job.id, job.kind);
+
+        // The provers panic on some invalid inputs, which must not kill the daemon.
+        let result = catch_unwind(AssertUnwindSafe(|| self.prove(shared, &job)))
+            .unwrap_or_else(|e| Err(anyhow!("prover panicked: {}", panic_message(e))));
+        shared.finish(job.id, result)
+    }
+
+    fn prove(&mut self, shared: &Shared, job: &Job) -> Result<()> {
+        let (input, job_dir) = {
+            let store = shared.store.lock().unwrap();
+            (
+                store.load_input(job.id)?,
+                store.job_dir(job.id).to_string_lossy().to_string(),
+            )
+        };
+
+        // The snarks of each stage are dumped in the job dir and loaded by the next one.
+        match input {
+            JobInput::Chunk { block_traces } => {
+                shared.set_stage(job.id, "generating inner and layer-1 snarks")?;
+                let witness_block = chunk_trace_to_witness_block(block_traces.clone())?;
+                self.chunk_prover.inner.load_or_gen_last_chunk_snark(
+                    &job.name,
+                    &witness_block,
+                    None,
+                    Some(&job_dir),
+                )?;
+
+                shared.set_stage(job.id, "generating chunk proof")?;
+                self.chunk_prover.gen_chunk_proof(
+                    block_traces,
+                    Some(&job.name),
+                    None,
+                    Some(&job_dir),
+                )?;
+            }
+            JobInput::Batch { chunk_proofs } => {
+                let chunk_hashes_proofs = chunk_proofs
+                    .into_iter()
+                    .map(|proof| {
+                        let chunk_hash = proof
+                            .chunk_hash
+                            .ok_or_else(|| anyhow!("chunk proof without chunk_info"))?;
+                        Ok((chunk_hash, proof))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                shared.set_stage(job.id, "generating layer-3 snark")?;
+                self.batch_prover.load_or_gen_last_agg_snark(
+                    &job.name,
+                    chunk_hashes_proofs.clone(),
+                    Some(&job_dir),
+                )?;
+
+                shared.set_stage(job.id, "generating batch proof")?;
+                self.batch_prover.gen_agg_evm_proof(
+                    chunk_hashes_proofs,
+                    Some(&job.name),
+                    Some(&job_dir),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn panic_message(e: Box<dyn Any + Send>) -> String {
+    e.downcast_ref::<String>()
+        .cloned()
+        .or_else(|| e.downcast_ref::<&str>().map(|s| s.to_string()))
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::fs;
+
+    #[test]
+    fn test_cancel_running_job() {
+        let dir = std::env::temp_dir().join(format!(
+            "prover_daemon_cancel_{}_{}",
+            std::process::id(),
+            Utc::now().timestamp_subsec_nanos()
+        ));
+        let shared = Shared::new(JobStore::open(&dir).unwrap());
+        shared
+            .submit(
+                None,
+                JobInput::Chunk {
+                    block_traces: vec![],
+                },
+            )
+            .unwrap();
+        let job = shared.next_job().unwrap();
+        assert_eq!(job.status, JobStatus::Running);
+        shared.set_stage(job.id, "first stage").unwrap();
+
+        handle_request(Request::Cancel { id: job.id }, &shared).unwrap();
+        // The job is aborted before its next stage, and not failed.
+        let err = shared.set_stage(job.id, "second stage").unwrap_err();
+        shared.finish(job.id, Err(err)).unwrap();
+        let job = shared.store.lock().unwrap().get(job.id).unwrap().clone();
+        assert_eq!(job.status, JobStatus::Cancelled);
+        assert_eq!(job.stage, None);
+        assert_eq!(job.error, None);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
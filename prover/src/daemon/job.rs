@@ -0,0 +1,60 @@
+use crate::ChunkProof;
+use eth_types::l2_types::BlockTrace;
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    Chunk,
+    Batch,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Done | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// Input of a proving job, persisted as `input.json` in the job dir.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JobInput {
+    Chunk { block_traces: Vec<BlockTrace> },
+    Batch { chunk_proofs: Vec<ChunkProof> },
+}
+
+impl JobInput {
+    pub fn kind(&self) -> JobKind {
+        match self {
+            Self::Chunk { .. } => JobKind::Chunk,
+            Self::Batch { .. } => JobKind::Batch,
+        }
+    }
+}
+
+/// State of a proving job, persisted as `job.json` in the job dir.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Job {
+    pub id: u64,
+    pub name: String,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    /// Current stage of a running job.
+    pub stage: Option<String>,
+    /// A running job is cancelled before its next stage.
+    pub cancel_requested: bool,
+    pub error: Option<String>,
+    /// Unix timestamps in seconds.
+    pub created_at: i64,
+    pub updated_at: i64,
+}
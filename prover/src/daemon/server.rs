@@ -0,0 +1,297 @@
+use super::{
+    job::{JobInput, JobKind, JobStatus},
+    Shared,
+};
+use crate::{BatchProof, ChunkProof};
+use anyhow::{anyhow, bail, Result};
+use eth_types::l2_types::BlockTrace;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::Path,
+    sync::Arc,
+    thread,
+};
+
+/// A request is one line of JSON, e.g. `{"method": "status", "params": {"id": 1}}`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum Request {
+    SubmitChunk {
+        name: Option<String>,
+        block_traces: Vec<BlockTrace>,
+    },
+    SubmitBatch {
+        name: Option<String>,
+        chunk_proofs: Vec<ChunkProof>,
+    },
+    Status {
+        id: u64,
+    },
+    List,
+    Cancel {
+        id: u64,
+    },
+    Proof {
+        id: u64,
+    },
+}
+
+/// The response to a request, one line of JSON.
+#[derive(Debug, Serialize)]
+pub struct Response {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl From<Result<Value>> for Response {
+    fn from(result: Result<Value>) -> Self {
+        match result {
+            Ok(result) => Self {
+                ok: true,
+                result: Some(result),
+                error: None,
+            },
+            Err(e) => Self {
+                ok: false,
+                result: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+/// Accept the clients on the Unix socket, each of them is served in its own thread.
+pub fn serve(socket_path: &str, shared: Arc<Shared>) -> Result<()> {
+    let listener = bind(socket_path)?;
+    log::info!("prover-daemon: listening on {socket_path}");
+
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let shared = shared.clone();
+        thread::spawn(move || {
+            if let Err(e) = handle_client(stream, &shared) {
+                log::warn!("prover-daemon: client error: {e}");
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Bind the Unix socket, fails if another daemon is listening on it.
+fn bind(socket_path: &str) -> Result<UnixListener> {
+    if Path::new(socket_path).exists() {
+        if UnixStream::connect(socket_path).is_ok() {
+            bail!("another daemon is listening on {socket_path}");
+        }
+        // Left by a previous run.
+        fs::remove_file(socket_path)?;
+    }
+    Ok(UnixListener::bind(socket_path)?)
+}
+
+fn handle_client(stream: UnixStream, shared: &Shared) -> Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response: Response = serde_json::from_str::<Request>(&line)
+            .map_err(|e| anyhow!("invalid request: {e}"))
+            .and_then(|request| handle_request(request, shared))
+            .into();
+        serde_json::to_writer(&mut writer, &response)?;
+        writer.write_all(b"\n")?;
+    }
+
+    Ok(())
+}
+
+pub fn handle_request(request: Request, shared: &Shared) -> Result<Value> {
+    match request {
+        Request::SubmitChunk { name, block_traces } => {
+            if block_traces.is_empty() {
+                bail!("empty chunk");
+            }
+            let job = shared.submit(name, JobInput::Chunk { block_traces })?;
+            Ok(serde_json::to_value(job)?)
+        }
+        Request::SubmitBatch { name, chunk_proofs } => {
+            if chunk_proofs.is_empty() {
+                bail!("empty batch");
+            }
+            if chunk_proofs.iter().any(|proof| proof.chunk_hash.is_none()) {
+                bail!("chunk proof without chunk_info");
+            }
+            let job = shared.submit(name, JobInput::Batch { chunk_proofs })?;
+            Ok(serde_json::to_value(job)?)
+        }
+        Request::Status { id } => {
+            let store = shared.store.lock().unwrap();
+            let job = store
+                .get(id)
+                .ok_or_else(|| anyhow!("job {id} doesn't exist"))?;
+            Ok(serde_json::to_value(job)?)
+        }
+        Request::List => {
+            let store = shared.store.lock().unwrap();
+            Ok(serde_json::to_value(store.jobs().collect::<Vec<_>>())?)
+        }
+        Request::Cancel { id } => {
+            let mut store = shared.store.lock().unwrap();
+            let status = store
+                .get(id)
+                .ok_or_else(|| anyhow!("job {id} doesn't exist"))?
+                .status;
+            let job = match status {
+                JobStatus::Queued => store.update(id, |job| job.status = JobStatus::Cancelled)?,
+                JobStatus::Running => store.update(id, |job| job.cancel_requested = true)?,
+                _ => bail!("job {id} is already {status:?}"),
+            };
+            Ok(serde_json::to_value(job)?)
+        }
+        Request::Proof { id } => {
+            let store = shared.store.lock().unwrap();
+            let job = store
+                .get(id)
+                .ok_or_else(|| anyhow!("job {id} doesn't exist"))?;
+            if job.status != JobStatus::Done {
+                bail!("job {id} is {:?}", job.status);
+            }
+            let job_dir = store.job_dir(id);
+            let job_dir = job_dir.to_string_lossy();
+            match job.kind {
+                JobKind::Chunk => Ok(serde_json::to_value(ChunkProof::from_json_file(
+                    &job_dir, &job.name,
+                )?)?),
+                JobKind::Batch => Ok(serde_json::to_value(BatchProof::from_json_file(
+                    &job_dir,
+                    super::BATCH_PROOF_NAME,
+                )?)?),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{daemon::JobStore, Proof};
+    use chrono::Utc;
+    use std::path::PathBuf;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "prover_daemon_server_{}_{}",
+            std::process::id(),
+            Utc::now().timestamp_subsec_nanos()
+        ))
+    }
+
+    fn request(shared: &Shared, request: &str) -> Result<Value> {
+        handle_request(serde_json::from_str(request)?, shared)
+    }
+
+    #[test]
+    fn test_handle_request() {
+        let dir = temp_dir();
+        let shared = Shared::new(JobStore::open(&dir).unwrap());
+        let block_trace = serde_json::to_string(&BlockTrace::default()).unwrap();
+        let submit_chunk = |name: &str| {
+            request(
+                &shared,
+                &format!(
+                    r#"{{"method": "submit_chunk", "params": {{"name": "{name}", "block_traces": [{block_trace}]}}}}"#
+                ),
+            )
+        };
+
+        // submit
+        let job = submit_chunk("chunk_1-a").unwrap();
+        assert_eq!(job["id"], 1);
+        assert_eq!(job["status"], "queued");
+        assert_eq!(job["kind"], "chunk");
+        for name in ["../../tmp/x", "a/b", "a.b", ""] {
+            assert!(submit_chunk(name).is_err(), "name {name:?}");
+        }
+        let err = request(
+            &shared,
+            r#"{"method": "submit_batch", "params": {"name": "b", "chunk_proofs": []}}"#,
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "empty batch");
+        let second = submit_chunk("chunk_2").unwrap();
+        assert_eq!(second["id"], 2);
+
+        // status
+        let job = request(&shared, r#"{"method": "status", "params": {"id": 1}}"#).unwrap();
+        assert_eq!(job["name"], "chunk_1-a");
+        assert_eq!(job["status"], "queued");
+        assert!(request(&shared, r#"{"method": "status", "params": {"id": 3}}"#).is_err());
+        let jobs = request(&shared, r#"{"method": "list"}"#).unwrap();
+        assert_eq!(jobs.as_array().unwrap().len(), 2);
+
+        // cancel
+        let job = request(&shared, r#"{"method": "cancel", "params": {"id": 2}}"#).unwrap();
+        assert_eq!(job["status"], "cancelled");
+        assert!(request(&shared, r#"{"method": "cancel", "params": {"id": 2}}"#).is_err());
+        shared
+            .store
+            .lock()
+            .unwrap()
+            .update(1, |job| job.status = JobStatus::Running)
+            .unwrap();
+        let job = request(&shared, r#"{"method": "cancel", "params": {"id": 1}}"#).unwrap();
+        assert_eq!(job["status"], "running");
+        assert_eq!(job["cancel_requested"], true);
+
+        // proof
+        let err = request(&shared, r#"{"method": "proof", "params": {"id": 1}}"#).unwrap_err();
+        assert_eq!(err.to_string(), "job 1 is Running");
+        let chunk_proof = ChunkProof {
+            protocol: vec![1, 2, 3],
+            proof: Proof::new(vec![4, 5, 6], &[], None),
+            chunk_hash: None,
+        };
+        {
+            let mut store = shared.store.lock().unwrap();
+            let job = store.update(1, |job| job.status = JobStatus::Done).unwrap();
+            chunk_proof
+                .dump(&store.job_dir(1).to_string_lossy(), &job.name)
+                .unwrap();
+        }
+        let proof = request(&shared, r#"{"method": "proof", "params": {"id": 1}}"#).unwrap();
+        assert_eq!(proof, serde_json::to_value(&chunk_proof).unwrap());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_bind_only_replaces_stale_socket() {
+        let dir = temp_dir();
+        fs::create_dir_all(&dir).unwrap();
+        let socket_path = dir.join("daemon.sock");
+        let socket_path = socket_path.to_str().unwrap();
+
+        let listener = bind(socket_path).unwrap();
+        let err = bind(socket_path).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("another daemon is listening on {socket_path}")
+        );
+        // The socket file is left when the daemon dies.
+        drop(listener);
+        assert!(Path::new(socket_path).exists());
+        bind(socket_path).unwrap();
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
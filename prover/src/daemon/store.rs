@@ -0,0 +1,207 @@
+use super::job::{Job, JobInput, JobStatus};
+use anyhow::{anyhow, bail, Result};
+use chrono::Utc;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+const JOB_FILENAME: &str = "job.json";
+const INPUT_FILENAME: &str = "input.json";
+
+/// Jobs of the daemon, each of them persisted in its own dir `<dir>/<id>`, which also holds
+/// the generated proofs.
+#[derive(Debug)]
+pub struct JobStore {
+    dir: PathBuf,
+    jobs: BTreeMap<u64, Job>,
+}
+
+impl JobStore {
+    /// Load the jobs persisted in `dir`. The jobs interrupted by a restart are queued again.
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        let mut jobs = BTreeMap::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path().join(JOB_FILENAME);
+            if !path.exists() {
+                continue;
+            }
+            let job: Job = serde_json::from_slice(&fs::read(&path)?)?;
+            jobs.insert(job.id, job);
+        }
+
+        let mut store = Self { dir, jobs };
+        let interrupted: Vec<_> = store
+            .jobs
+            .values()
+            .filter(|job| job.status == JobStatus::Running)
+            .map(|job| job.id)
+            .collect();
+        for id in interrupted {
+            log::warn!("prover-daemon: job {id} was interrupted, queue it again");
+            store.update(id, |job| {
+                job.status = JobStatus::Queued;
+                job.stage = None;
+            })?;
+        }
+        log::info!(
+            "prover-daemon: loaded {} jobs from {:?}",
+            store.jobs.len(),
+            store.dir
+        );
+
+        Ok(store)
+    }
+
+    pub fn job_dir(&self, id: u64) -> PathBuf {
+        self.dir.join(id.to_string())
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Job> {
+        self.jobs.get(&id)
+    }
+
+    pub fn jobs(&self) -> impl Iterator<Item = &Job> {
+        self.jobs.values()
+    }
+
+    /// The oldest queued job.
+    pub fn next_queued(&self) -> Option<u64> {
+        self.jobs
+            .values()
+            .find(|job| job.status == JobStatus::Queued)
+            .map(|job| job.id)
+    }
+
+    pub fn submit(&mut self, name: Option<String>, input: JobInput) -> Result<Job> {
+        if let Some(name) = &name {
+            validate_name(name)?;
+        }
+        let id = self.jobs.keys().next_back().map_or(1, |id| id + 1);
+        let now = Utc::now().timestamp();
+        let job = Job {
+            id,
+            name: name.unwrap_or_else(|| id.to_string()),
+            kind: input.kind(),
+            status: JobStatus::Queued,
+            stage: None,
+            cancel_requested: false,
+            error: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let job_dir = self.job_dir(id);
+        fs::create_dir_all(&job_dir)?;
+        write_atomic(&job_dir.join(INPUT_FILENAME), &serde_json::to_vec(&input)?)?;
+        write_atomic(&job_dir.join(JOB_FILENAME), &serde_json::to_vec(&job)?)?;
+        self.jobs.insert(id, job.clone());
+
+        Ok(job)
+    }
+
+    pub fn load_input(&self, id: u64) -> Result<JobInput> {
+        let buf = fs::read(self.job_dir(id).join(INPUT_FILENAME))?;
+        let mut deserializer = serde_json::Deserializer::from_slice(&buf);
+        deserializer.disable_recursion_limit();
+        let deserializer = serde_stacker::Deserializer::new(&mut deserializer);
+        Ok(serde::Deserialize::deserialize(deserializer)?)
+    }
+
+    /// Update the job and persist it.
+    pub fn update<F: FnOnce(&mut Job)>(&mut self, id: u64, f: F) -> Result<Job> {
+        let job = self
+            .jobs
+            .get_mut(&id)
+            .ok_or_else(|| anyhow!("job {id} doesn't exist"))?;
+        f(job);
+        job.updated_at = Utc::now().timestamp();
+
+        let job = job.clone();
+        write_atomic(
+            &self.job_dir(id).join(JOB_FILENAME),
+            &serde_json::to_vec(&job)?,
+        )?;
+
+        Ok(job)
+    }
+}
+
+/// The name of a job is part of the file names of its proofs, so only `[A-Za-z0-9_-]` is
+/// allowed.
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+    {
+        bail!("invalid job name {name:?}, only [A-Za-z0-9_-] is allowed");
+    }
+
+    Ok(())
+}
+
+/// Write to a temporary file first, so a crash never leaves a truncated file behind.
+fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, data)?;
+    fs::rename(tmp_path, path)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_store_survives_restart() {
+        let dir = std::env::temp_dir().join(format!(
+            "prover_daemon_store_{}_{}",
+            std::process::id(),
+            Utc::now().timestamp_subsec_nanos()
+        ));
+
+        let mut store = JobStore::open(&dir).unwrap();
+        let first = store
+            .submit(
+                None,
+                JobInput::Chunk {
+                    block_traces: vec![],
+                },
+            )
+            .unwrap();
+        let second = store
+            .submit(
+                Some("batch".to_string()),
+                JobInput::Batch {
+                    chunk_proofs: vec![],
+                },
+            )
+            .unwrap();
+        store
+            .update(first.id, |job| job.status = JobStatus::Running)
+            .unwrap();
+        store
+            .update(second.id, |job| job.status = JobStatus::Cancelled)
+            .unwrap();
+        drop(store);
+
+        let store = JobStore::open(&dir).unwrap();
+        assert_eq!(store.jobs().count(), 2);
+        assert_eq!(store.get(first.id).unwrap().status, JobStatus::Queued);
+        assert_eq!(store.get(second.id).unwrap().status, JobStatus::Cancelled);
+        assert_eq!(store.get(second.id).unwrap().name, "batch");
+        assert_eq!(store.next_queued(), Some(first.id));
+        assert!(matches!(
+            store.load_input(second.id).unwrap(),
+            JobInput::Batch { .. }
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,337 @@
+//! Export of the EVM verifier contracts of the batch and bundle proofs.
+//!
+//! The verifier is a Yul contract without any function: its calldata is the instances (each as
+//! a 32-byte big-endian word) followed by the proof bytes, and the call reverts if the proof is
+//! invalid. This is what [`BatchProof::calldata`] and [`BundleProof::calldata`] return. A
+//! Solidity contract gives it a typed `verify` function.
+//!
+//! The chunk proofs have no EVM verifier, since they are generated with a Poseidon transcript
+//! to be aggregated by the batch circuit.
+//!
+//! [`BatchProof::calldata`]: crate::BatchProof::calldata
+//! [`BundleProof::calldata`]: crate::BundleProof::calldata
+
+use crate::{config::LayerId, Proof};
+use aggregator::CompressionCircuit;
+use anyhow::{bail, Result};
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::VerifyingKey,
+    poly::kzg::commitment::ParamsKZG,
+};
+use snark_verifier::pcs::kzg::{Bdfg21, Kzg};
+use snark_verifier_sdk::{encode_calldata, gen_evm_verifier, verify_evm_calldata, CircuitExt};
+use std::{fs, path::Path};
+
+/// Write the verifier of the proofs of `layer` to `dir`, return its deployment code:
+/// - `<name>_verifier.yul`: the Yul source
+/// - `<name>_verifier.bin`: the deployment bytecode
+/// - `<name>_verifier.abi.json`: the ABI, a single fallback function
+/// - `<name>_verifier.sol`: the Solidity source of `<Name>Verifier`, whose `verify` function calls
+///   the deployed Yul verifier
+/// - `<name>_verifier.sol.abi.json`: the ABI of `<Name>Verifier`
+/// - `<name>_calldata.bin`: the calldata to verify `sample_proof`
+///
+/// The name is `batch` or `bundle` for [`LayerId::Layer4`] or [`LayerId::Layer6`], the other
+/// layers have no verifier contract. `sample_proof` is the one returned by `proof_to_verify`.
+pub fn export_verifier(
+    layer: LayerId,
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    sample_proof: &Proof,
+    dir: &str,
+) -> Result<Vec<u8>> {
+    let name = match layer {
+        LayerId::Layer4 => "batch",
+        LayerId::Layer6 => "bundle",
+        _ => bail!("No EVM verifier for {layer}"),
+    };
+
+    export_circuit_verifier::<CompressionCircuit>(name, params, vk, sample_proof, dir)
+}
+
+/// Write the verifier of the proofs of circuit `C` to `dir`, with the file names prefixed by
+/// `name`, see [`export_verifier`].
+pub fn export_circuit_verifier<C: CircuitExt<Fr>>(
+    name: &str,
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    sample_proof: &Proof,
+    dir: &str,
+) -> Result<Vec<u8>> {
+    let dir = Path::new(dir);
+    fs::create_dir_all(dir)?;
+
+    let instances = sample_proof.instances();
+    let num_instance = instances.iter().map(Vec::len).collect();
+
+    log::info!("Before generate {name} EVM verifier");
+    let deployment_code = gen_evm_verifier::<C, Kzg<Bn256, Bdfg21>>(
+        params,
+        vk,
+        num_instance,
+        Some(dir.join(format!("{name}_verifier.yul")).as_path()),
+    );
+    log::info!("After generate {name} EVM verifier");
+    fs::write(dir.join(format!("{name}_verifier.bin")), &deployment_code)?;
+
+    let abi = serde_json::json!([{
+        "type": "fallback",
+        "stateMutability": "nonpayable",
+    }]);
+    fs::write(
+        dir.join(format!("{name}_verifier.abi.json")),
+        serde_json::to_vec_pretty(&abi)?,
+    )?;
+
+    let num_instance = instances.iter().map(Vec::len).sum();
+    fs::write(
+        dir.join(format!("{name}_verifier.sol")),
+        solidity_source(name, num_instance),
+    )?;
+    fs::write(
+        dir.join(format!("{name}_verifier.sol.abi.json")),
+        serde_json::to_vec_pretty(&solidity_abi(num_instance))?,
+    )?;
+
+    let calldata = encode_calldata(&instances, sample_proof.proof());
+    if !verify_evm_calldata(deployment_code.clone(), calldata.clone()) {
+        bail!("Sample proof is rejected by the {name} EVM verifier");
+    }
+    fs::write(dir.join(format!("{name}_calldata.bin")), calldata)?;
+
+    Ok(deployment_code)
+}
+
+/// Name of the Solidity contract, e.g. `BatchVerifier` for `batch`.
+fn solidity_contract_name(name: &str) -> String {
+    let mut contract_name: String = name
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_ascii_uppercase().to_string() + chars.as_str()
+            })
+        })
+        .collect();
+    contract_name.push_str("Verifier");
+    contract_name
+}
+
+/// Solidity contract with a typed `verify` function, calling the Yul verifier with the packed
+/// instances and proof, i.e. the calldata of [`export_circuit_verifier`].
+fn solidity_source(name: &str, num_instance: usize) -> String {
+    let contract_name = solidity_contract_name(name);
+    format!(
+        r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.16;
+
+/// @title {contract_name}
+/// @notice Verifies the {name} proofs with the Yul verifier `{name}_verifier.bin`.
+contract {contract_name} {{
+    /// @notice The deployed Yul verifier, it reverts on an invalid proof.
+    address public immutable plonkVerifier;
+
+    constructor(address _plonkVerifier) {{
+        plonkVerifier = _plonkVerifier;
+    }}
+
+    /// @notice Verify a proof with its instances, each of them a field element.
+    /// @return Whether the proof is valid.
+    function verify(uint256[{num_instance}] calldata instances, bytes calldata proof)
+        external
+        view
+        returns (bool)
+    {{
+        (bool success, ) = plonkVerifier.staticcall(abi.encodePacked(instances, proof));
+        return success;
+    }}
+}}
+"#
+    )
+}
+
+/// ABI of the contract of [`solidity_source`].
+fn solidity_abi(num_instance: usize) -> serde_json::Value {
+    serde_json::json!([
+        {
+            "type": "constructor",
+            "inputs": [{"name": "_plonkVerifier", "type": "address", "internalType": "address"}],
+            "stateMutability": "nonpayable",
+        },
+        {
+            "type": "function",
+            "name": "plonkVerifier",
+            "inputs": [],
+            "outputs": [{"name": "", "type": "address", "internalType": "address"}],
+            "stateMutability": "view",
+        },
+        {
+            "type": "function",
+            "name": "verify",
+            "inputs": [
+                {
+                    "name": "instances",
+                    "type": format!("uint256[{num_instance}]"),
+                    "internalType": format!("uint256[{num_instance}]"),
+                },
+                {"name": "proof", "type": "bytes", "internalType": "bytes"},
+            ],
+            "outputs": [{"name": "", "type": "bool", "internalType": "bool"}],
+            "stateMutability": "view",
+        },
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{io::serialize_fr, utils::gen_rng, BatchProof};
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        plonk::{keygen_vk, Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+        poly::commitment::ParamsProver,
+    };
+    use snark_verifier_sdk::{gen_evm_proof_shplonk, gen_pk};
+
+    /// Circuit exposing the instances of a batch proof (the accumulator and the pi hash).
+    #[derive(Clone)]
+    struct InstanceCircuit(Vec<Fr>);
+
+    impl Circuit<Fr> for InstanceCircuit {
+        type Config = (Column<Advice>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            let advice = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(advice);
+            meta.enable_equality(instance);
+
+            (advice, instance)
+        }
+
+        fn synthesize(
+            &self,
+            (advice, instance): Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let cells = layouter.assign_region(
+                || "instances",
+                |mut region| {
+                    self.0
+                        .iter()
+                        .enumerate()
+                        .map(|(offset, value)| {
+                            region.assign_advice(
+                                || "instance",
+                                advice,
+                                offset,
+                                || Value::known(*value),
+                            )
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                },
+            )?;
+
+            for (row, cell) in cells.iter().enumerate() {
+                layouter.constrain_instance(cell.cell(), instance, row)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    impl CircuitExt<Fr> for InstanceCircuit {
+        fn num_instance(&self) -> Vec<usize> {
+            vec![self.0.len()]
+        }
+
+        fn instances(&self) -> Vec<Vec<Fr>> {
+            vec![self.0.clone()]
+        }
+    }
+
+    #[test]
+    fn test_export_verifier_rejects_chunk_layer() {
+        let params = ParamsKZG::<Bn256>::setup(4, gen_rng());
+        let circuit = InstanceCircuit(vec![Fr::from(1)]);
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let dir = std::env::temp_dir().join(format!("evm_verifier_chunk_{}", std::process::id()));
+
+        for layer in [LayerId::Layer1, LayerId::Layer2, LayerId::Layer3] {
+            let err = export_verifier(
+                layer,
+                &params,
+                &vk,
+                &Proof::default(),
+                &dir.to_string_lossy(),
+            )
+            .unwrap_err();
+            assert_eq!(err.to_string(), format!("No EVM verifier for {layer}"));
+        }
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_solidity_verifier_calldata() {
+        // 12 accumulator elements and 32 bytes of pi hash.
+        let instances: Vec<Fr> = (0..44u64).map(Fr::from).collect();
+        let batch_proof = BatchProof::from(Proof::new(vec![7; 64], &[instances.clone()], None));
+        let proof = batch_proof.clone().proof_to_verify();
+
+        // abi.encodePacked(instances, proof) of the Solidity verifier
+        let packed: Vec<u8> = instances
+            .iter()
+            .flat_map(|value| serialize_fr(value).into_iter().rev())
+            .chain(proof.proof().iter().copied())
+            .collect();
+        assert_eq!(encode_calldata(&proof.instances(), proof.proof()), packed);
+        assert_eq!(batch_proof.calldata(), packed);
+
+        let source = solidity_source("batch", 44);
+        assert!(source.contains("contract BatchVerifier {"));
+        assert!(source
+            .contains("function verify(uint256[44] calldata instances, bytes calldata proof)"));
+        let abi = solidity_abi(44);
+        assert_eq!(abi[2]["name"], "verify");
+        assert_eq!(abi[2]["inputs"][0]["type"], "uint256[44]");
+        assert_eq!(solidity_contract_name("bundle"), "BundleVerifier");
+    }
+
+    #[ignore = "it requires solc"]
+    #[test]
+    fn test_export_verifier_batch_calldata() {
+        let mut rng = gen_rng();
+        let params = ParamsKZG::<Bn256>::setup(8, &mut rng);
+
+        // 12 accumulator elements and 32 bytes of pi hash.
+        let circuit = InstanceCircuit((0..44u64).map(Fr::from).collect());
+        let instances = circuit.instances();
+        let pk = gen_pk(&params, &circuit, None);
+        let proof = gen_evm_proof_shplonk(&params, &pk, circuit, instances.clone(), &mut rng);
+        let batch_proof = BatchProof::from(Proof::new(proof, &instances, Some(&pk)));
+
+        let dir = std::env::temp_dir().join(format!("evm_verifier_{}", std::process::id()));
+        let dir = dir.to_string_lossy();
+        let deployment_code = export_circuit_verifier::<InstanceCircuit>(
+            "batch",
+            &params,
+            pk.get_vk(),
+            &batch_proof.clone().proof_to_verify(),
+            &dir,
+        )
+        .unwrap();
+
+        let calldata = batch_proof.calldata();
+        assert_eq!(
+            fs::read(Path::new(&*dir).join("batch_calldata.bin")).unwrap(),
+            calldata
+        );
+        assert!(verify_evm_calldata(deployment_code, calldata));
+    }
+}
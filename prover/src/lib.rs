@@ -1,9 +1,12 @@
 #![feature(lazy_cell)]
 
 pub mod aggregator;
+pub mod bundle;
 pub mod common;
 pub mod config;
 pub mod consts;
+pub mod daemon;
+pub mod evm;
 pub mod inner;
 pub mod io;
 pub mod proof;
@@ -15,6 +18,6 @@ pub mod zkevm;
 
 pub use common::{ChunkHash, CompressionCircuit};
 pub use eth_types::l2_types::BlockTrace;
-pub use proof::{BatchProof, ChunkProof, EvmProof, Proof};
+pub use proof::{BatchProof, BundleProof, ChunkProof, EvmProof, Proof, VersionedProof};
 pub use snark_verifier_sdk::{CircuitExt, Snark};
 pub use types::WitnessBlock;
@@ -1,4 +1,5 @@
 use crate::{
+    config::LayerId,
     io::{deserialize_fr, deserialize_vk, serialize_fr, serialize_vk, write_file},
     types::base64,
     utils::short_git_version,
@@ -18,16 +19,20 @@ use snark_verifier::{
 };
 use snark_verifier_sdk::{verify_evm_proof, Snark};
 use std::{
-    fs::File,
+    fs::{self, File},
     path::{Path, PathBuf},
 };
 
 mod batch;
+mod bundle;
 mod chunk;
+mod envelope;
 mod evm;
 
 pub use batch::BatchProof;
+pub use bundle::BundleProof;
 pub use chunk::ChunkProof;
+pub use envelope::{Decoder, Encoder, ProofEnvelope, VersionedProof, PROOF_FORMAT_VERSION};
 pub use evm::EvmProof;
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -59,6 +64,10 @@ impl Proof {
         from_json_file(dir, filename)
     }
 
+    pub fn from_bin_file(dir: &str, filename: &str) -> Result<Self> {
+        from_bin_file(dir, filename)
+    }
+
     pub fn from_snark(snark: Snark, vk: Vec<u8>) -> Self {
         let proof = snark.proof;
         let instances = serialize_instances(&snark.instances);
@@ -75,7 +84,8 @@ impl Proof {
     pub fn dump(&self, dir: &str, filename: &str) -> Result<()> {
         dump_vk(dir, filename, &self.vk);
 
-        dump_as_json(dir, filename, &self)
+        dump_as_json(dir, filename, self)?;
+        dump_as_bin(dir, filename, self)
     }
 
     pub fn evm_verify(&self, deployment_code: Vec<u8>) -> bool {
@@ -115,10 +125,40 @@ impl Proof {
     }
 }
 
-pub fn dump_as_json<P: serde::Serialize>(dir: &str, filename: &str, proof: &P) -> Result<()> {
-    // Write full proof as json.
+impl VersionedProof for Proof {
+    const LAYER: Option<LayerId> = None;
+
+    fn raw_vk(&self) -> &[u8] {
+        &self.vk
+    }
+
+    fn encode_fields(&self, encoder: &mut Encoder) {
+        encoder.put_bytes(&self.proof);
+        encoder.put_bytes(&self.instances);
+        encoder.put_bytes(&self.vk);
+        encoder.put_opt_str(self.git_version.as_deref());
+    }
+
+    fn decode_fields(decoder: &mut Decoder) -> Result<Self> {
+        Ok(Self {
+            proof: decoder.get_bytes()?,
+            instances: decoder.get_bytes()?,
+            vk: decoder.get_bytes()?,
+            git_version: decoder.get_opt_str()?,
+        })
+    }
+}
+
+pub fn dump_as_json<P: VersionedProof>(dir: &str, filename: &str, proof: &P) -> Result<()> {
+    // Write full proof as json, in an envelope of the current format version.
     let mut fd = File::create(dump_proof_path(dir, filename))?;
-    serde_json::to_writer(&mut fd, proof)?;
+    serde_json::to_writer(&mut fd, &envelope::to_json_value(proof)?)?;
+
+    Ok(())
+}
+
+pub fn dump_as_bin<P: VersionedProof>(dir: &str, filename: &str, proof: &P) -> Result<()> {
+    fs::write(dump_bin_proof_path(dir, filename), proof.to_bytes())?;
 
     Ok(())
 }
@@ -131,7 +171,8 @@ pub fn dump_vk(dir: &str, filename: &str, raw_vk: &[u8]) {
     dump_data(dir, &format!("vk_{filename}.vkey"), raw_vk);
 }
 
-pub fn from_json_file<'de, P: serde::Deserialize<'de>>(dir: &str, filename: &str) -> Result<P> {
+/// Read a proof dumped by [`dump_as_json`], or an unversioned one dumped by the older provers.
+pub fn from_json_file<P: VersionedProof>(dir: &str, filename: &str) -> Result<P> {
     let file_path = dump_proof_path(dir, filename);
     if !Path::new(&file_path).exists() {
         bail!("File {file_path} doesn't exist");
@@ -141,14 +182,29 @@ pub fn from_json_file<'de, P: serde::Deserialize<'de>>(dir: &str, filename: &str
     let mut deserializer = serde_json::Deserializer::from_reader(fd);
     deserializer.disable_recursion_limit();
     let deserializer = serde_stacker::Deserializer::new(&mut deserializer);
+    let value = serde::Deserialize::deserialize(deserializer)?;
 
-    Ok(serde::Deserialize::deserialize(deserializer)?)
+    envelope::from_json_value(value)
+}
+
+/// Read a proof dumped by [`dump_as_bin`].
+pub fn from_bin_file<P: VersionedProof>(dir: &str, filename: &str) -> Result<P> {
+    let file_path = dump_bin_proof_path(dir, filename);
+    if !Path::new(&file_path).exists() {
+        bail!("File {file_path} doesn't exist");
+    }
+
+    P::from_bytes(&fs::read(file_path)?)
 }
 
 fn dump_proof_path(dir: &str, filename: &str) -> String {
     format!("{dir}/full_proof_{filename}.json")
 }
 
+fn dump_bin_proof_path(dir: &str, filename: &str) -> String {
+    format!("{dir}/full_proof_{filename}.bin")
+}
+
 fn dummy_protocol() -> Protocol<G1Affine> {
     Protocol {
         domain: Domain {
@@ -1,5 +1,8 @@
-use super::{dump_as_json, dump_data, dump_vk, from_json_file, serialize_instance, Proof};
-use crate::utils::short_git_version;
+use super::{
+    dump_as_bin, dump_as_json, dump_data, dump_vk, from_bin_file, from_json_file,
+    serialize_instance, Decoder, Encoder, Proof, VersionedProof,
+};
+use crate::{config::LayerId, utils::short_git_version};
 use anyhow::Result;
 use serde_derive::{Deserialize, Serialize};
 use snark_verifier_sdk::encode_calldata;
@@ -18,29 +21,8 @@ pub struct BatchProof {
 
 impl From<Proof> for BatchProof {
     fn from(proof: Proof) -> Self {
-        let instances = proof.instances();
-        assert_eq!(instances.len(), 1);
-        assert_eq!(instances[0].len(), ACC_LEN + PI_LEN);
-
-        let vk = proof.vk;
-        let git_version = proof.git_version;
-
-        // raw_proof = acc + proof
-        let proof = serialize_instance(&instances[0][..ACC_LEN])
-            .into_iter()
-            .chain(proof.proof)
-            .collect();
-
-        // raw_instances = pi_data
-        let instances = serialize_instance(&instances[0][ACC_LEN..]);
-
         Self {
-            raw: Proof {
-                proof,
-                instances,
-                vk,
-                git_version,
-            },
+            raw: move_acc_to_proof(proof),
         }
     }
 }
@@ -50,6 +32,10 @@ impl BatchProof {
         from_json_file(dir, &dump_filename(name))
     }
 
+    pub fn from_bin_file(dir: &str, name: &str) -> Result<Self> {
+        from_bin_file(dir, &dump_filename(name))
+    }
+
     pub fn calldata(self) -> Vec<u8> {
         let proof = self.proof_to_verify();
 
@@ -68,28 +54,12 @@ impl BatchProof {
 
         dump_vk(dir, &filename, &self.raw.vk);
 
-        dump_as_json(dir, &filename, &self)
+        dump_as_json(dir, &filename, self)?;
+        dump_as_bin(dir, &filename, self)
     }
 
     pub fn proof_to_verify(self) -> Proof {
-        assert!(self.raw.proof.len() > ACC_BYTES);
-        assert_eq!(self.raw.instances.len(), PI_BYTES);
-
-        // instances = raw_proof[..12] (acc) + raw_instances (pi_data)
-        // proof = raw_proof[12..]
-        let mut instances = self.raw.proof;
-        let proof = instances.split_off(ACC_BYTES);
-        instances.extend(self.raw.instances);
-
-        let vk = self.raw.vk;
-        let git_version = Some(short_git_version());
-
-        Proof {
-            proof,
-            instances,
-            vk,
-            git_version,
-        }
+        move_acc_to_instances(self.raw)
     }
 
     pub fn assert_calldata(self) {
@@ -102,6 +72,74 @@ impl BatchProof {
     }
 }
 
+impl VersionedProof for BatchProof {
+    const LAYER: Option<LayerId> = Some(LayerId::Layer4);
+
+    fn raw_vk(&self) -> &[u8] {
+        &self.raw.vk
+    }
+
+    fn encode_fields(&self, encoder: &mut Encoder) {
+        self.raw.encode_fields(encoder);
+    }
+
+    fn decode_fields(decoder: &mut Decoder) -> Result<Self> {
+        // The raw proof is already in the thin format, don't move the accumulator again.
+        Ok(Self {
+            raw: Proof::decode_fields(decoder)?,
+        })
+    }
+}
+
 fn dump_filename(name: &str) -> String {
     format!("batch_{name}")
 }
+
+/// Split the accumulator from the instances of a thin EVM proof (with an accumulator), then
+/// raw_proof = acc + proof, and raw_instances = pi_data.
+pub(super) fn move_acc_to_proof(proof: Proof) -> Proof {
+    let instances = proof.instances();
+    assert_eq!(instances.len(), 1);
+    assert_eq!(instances[0].len(), ACC_LEN + PI_LEN);
+
+    let vk = proof.vk;
+    let git_version = proof.git_version;
+
+    // raw_proof = acc + proof
+    let proof = serialize_instance(&instances[0][..ACC_LEN])
+        .into_iter()
+        .chain(proof.proof)
+        .collect();
+
+    // raw_instances = pi_data
+    let instances = serialize_instance(&instances[0][ACC_LEN..]);
+
+    Proof {
+        proof,
+        instances,
+        vk,
+        git_version,
+    }
+}
+
+/// Inverse of [`move_acc_to_proof`], return the proof to verify by the EVM verifier.
+pub(super) fn move_acc_to_instances(raw: Proof) -> Proof {
+    assert!(raw.proof.len() > ACC_BYTES);
+    assert_eq!(raw.instances.len(), PI_BYTES);
+
+    // instances = raw_proof[..12] (acc) + raw_instances (pi_data)
+    // proof = raw_proof[12..]
+    let mut instances = raw.proof;
+    let proof = instances.split_off(ACC_BYTES);
+    instances.extend(raw.instances);
+
+    let vk = raw.vk;
+    let git_version = Some(short_git_version());
+
+    Proof {
+        proof,
+        instances,
+        vk,
+        git_version,
+    }
+}
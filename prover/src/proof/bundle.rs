@@ -0,0 +1,84 @@
+use super::{
+    batch::{move_acc_to_instances, move_acc_to_proof},
+    dump_as_bin, dump_as_json, dump_data, dump_vk, from_bin_file, from_json_file, Decoder, Encoder,
+    Proof, VersionedProof,
+};
+use crate::config::LayerId;
+use anyhow::Result;
+use serde_derive::{Deserialize, Serialize};
+
+/// The thin EVM proof of a bundle (layer-6), in the same format as [`BatchProof`].
+///
+/// [`BatchProof`]: super::BatchProof
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BundleProof {
+    #[serde(flatten)]
+    raw: Proof,
+}
+
+impl From<Proof> for BundleProof {
+    fn from(proof: Proof) -> Self {
+        Self {
+            raw: move_acc_to_proof(proof),
+        }
+    }
+}
+
+impl BundleProof {
+    pub fn from_json_file(dir: &str, name: &str) -> Result<Self> {
+        from_json_file(dir, &dump_filename(name))
+    }
+
+    pub fn from_bin_file(dir: &str, name: &str) -> Result<Self> {
+        from_bin_file(dir, &dump_filename(name))
+    }
+
+    pub fn calldata(self) -> Vec<u8> {
+        let proof = self.proof_to_verify();
+
+        // calldata = instances + proof
+        let mut calldata = proof.instances;
+        calldata.extend(proof.proof);
+
+        calldata
+    }
+
+    pub fn dump(&self, dir: &str, name: &str) -> Result<()> {
+        let filename = dump_filename(name);
+
+        dump_data(dir, &format!("pi_{filename}.data"), &self.raw.instances);
+        dump_data(dir, &format!("proof_{filename}.data"), &self.raw.proof);
+
+        dump_vk(dir, &filename, &self.raw.vk);
+
+        dump_as_json(dir, &filename, self)?;
+        dump_as_bin(dir, &filename, self)
+    }
+
+    pub fn proof_to_verify(self) -> Proof {
+        move_acc_to_instances(self.raw)
+    }
+}
+
+impl VersionedProof for BundleProof {
+    const LAYER: Option<LayerId> = Some(LayerId::Layer6);
+
+    fn raw_vk(&self) -> &[u8] {
+        &self.raw.vk
+    }
+
+    fn encode_fields(&self, encoder: &mut Encoder) {
+        self.raw.encode_fields(encoder);
+    }
+
+    fn decode_fields(decoder: &mut Decoder) -> Result<Self> {
+        // The raw proof is already in the thin format, don't move the accumulator again.
+        Ok(Self {
+            raw: Proof::decode_fields(decoder)?,
+        })
+    }
+}
+
+fn dump_filename(name: &str) -> String {
+    format!("bundle_{name}")
+}
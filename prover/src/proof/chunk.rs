@@ -1,5 +1,8 @@
-use super::{dump_as_json, dump_data, dump_vk, from_json_file, Proof};
-use crate::types::base64;
+use super::{
+    dump_as_bin, dump_as_json, dump_data, dump_vk, from_bin_file, from_json_file, Decoder, Encoder,
+    Proof, VersionedProof,
+};
+use crate::{config::LayerId, types::base64};
 use aggregator::ChunkHash;
 use anyhow::Result;
 use halo2_proofs::{halo2curves::bn256::G1Affine, plonk::ProvingKey};
@@ -37,6 +40,10 @@ impl ChunkProof {
         from_json_file(dir, &dump_filename(name))
     }
 
+    pub fn from_bin_file(dir: &str, name: &str) -> Result<Self> {
+        from_bin_file(dir, &dump_filename(name))
+    }
+
     pub fn dump(&self, dir: &str, name: &str) -> Result<()> {
         let filename = dump_filename(name);
 
@@ -44,7 +51,8 @@ impl ChunkProof {
         dump_vk(dir, &filename, &self.proof.vk);
         dump_data(dir, &format!("chunk_{filename}.protocol"), &self.protocol);
 
-        dump_as_json(dir, &filename, &self)
+        dump_as_json(dir, &filename, self)?;
+        dump_as_bin(dir, &filename, self)
     }
 
     pub fn to_snark(self) -> Snark {
@@ -59,6 +67,53 @@ impl ChunkProof {
     }
 }
 
+impl VersionedProof for ChunkProof {
+    const LAYER: Option<LayerId> = Some(LayerId::Layer2);
+
+    fn raw_vk(&self) -> &[u8] {
+        &self.proof.vk
+    }
+
+    fn encode_fields(&self, encoder: &mut Encoder) {
+        encoder.put_bytes(&self.protocol);
+        self.proof.encode_fields(encoder);
+        match &self.chunk_hash {
+            Some(chunk_hash) => {
+                encoder.put_u8(1);
+                encoder.put_u64(chunk_hash.chain_id);
+                encoder.put_h256(&chunk_hash.prev_state_root);
+                encoder.put_h256(&chunk_hash.post_state_root);
+                encoder.put_h256(&chunk_hash.withdraw_root);
+                encoder.put_h256(&chunk_hash.data_hash);
+                encoder.put_u8(chunk_hash.is_padding.into());
+            }
+            None => encoder.put_u8(0),
+        }
+    }
+
+    fn decode_fields(decoder: &mut Decoder) -> Result<Self> {
+        let protocol = decoder.get_bytes()?;
+        let proof = Proof::decode_fields(decoder)?;
+        let chunk_hash = match decoder.get_u8()? {
+            0 => None,
+            _ => Some(ChunkHash {
+                chain_id: decoder.get_u64()?,
+                prev_state_root: decoder.get_h256()?,
+                post_state_root: decoder.get_h256()?,
+                withdraw_root: decoder.get_h256()?,
+                data_hash: decoder.get_h256()?,
+                is_padding: decoder.get_u8()? != 0,
+            }),
+        };
+
+        Ok(Self {
+            protocol,
+            proof,
+            chunk_hash,
+        })
+    }
+}
+
 fn dump_filename(name: &str) -> String {
     format!("chunk_{name}")
 }
@@ -0,0 +1,338 @@
+use crate::{config::LayerId, utils::short_git_version};
+use anyhow::{bail, Result};
+use eth_types::H256;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// Version of the proof format written by this prover.
+/// - 0: the unversioned JSON of the proof, without an envelope
+/// - 1: the JSON of the proof in a [`ProofEnvelope`]
+pub const PROOF_FORMAT_VERSION: u32 = 1;
+
+const BIN_MAGIC: &[u8; 8] = b"SCROLLPF";
+
+/// A proof with the metadata to check it's readable by this prover.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ProofEnvelope<P> {
+    pub version: u32,
+    /// Git version of the prover which dumped the proof.
+    pub git_version: String,
+    /// Id of the layer of the proof, none for the proofs of any layer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer: Option<String>,
+    /// Hex of the SHA256 of the vk.
+    pub vk_hash: String,
+    pub proof: P,
+}
+
+impl<'a, P: VersionedProof> ProofEnvelope<&'a P> {
+    pub fn new(proof: &'a P) -> Self {
+        Self {
+            version: PROOF_FORMAT_VERSION,
+            git_version: short_git_version(),
+            layer: P::LAYER.map(|layer| layer.id().to_string()),
+            vk_hash: vk_hash(proof.raw_vk()),
+            proof,
+        }
+    }
+}
+
+impl<P: VersionedProof> ProofEnvelope<P> {
+    fn check(&self) -> Result<()> {
+        let layer = P::LAYER.map(|layer| layer.id().to_string());
+        if self.layer != layer {
+            bail!(
+                "proof of layer {:?} is read as a proof of layer {:?}",
+                self.layer,
+                layer
+            );
+        }
+        if self.vk_hash != vk_hash(self.proof.raw_vk()) {
+            bail!("vk doesn't match vk_hash {}", self.vk_hash);
+        }
+
+        Ok(())
+    }
+}
+
+/// A proof type with a versioned JSON and binary encoding.
+pub trait VersionedProof: Serialize + DeserializeOwned {
+    /// Layer of the proofs of this type, none if it's used by any layer.
+    const LAYER: Option<LayerId>;
+
+    fn raw_vk(&self) -> &[u8];
+
+    /// Encode the fields of the proof in the binary format.
+    fn encode_fields(&self, encoder: &mut Encoder);
+
+    /// Decode the fields encoded by [`Self::encode_fields`].
+    fn decode_fields(decoder: &mut Decoder) -> Result<Self>;
+
+    /// Compact binary encoding of the proof in an envelope:
+    /// magic | version | git_version | layer | vk_hash | fields
+    fn to_bytes(&self) -> Vec<u8> {
+        let envelope = ProofEnvelope::new(self);
+
+        let mut encoder = Encoder(BIN_MAGIC.to_vec());
+        encoder.put_u32(envelope.version);
+        encoder.put_str(&envelope.git_version);
+        encoder.put_str(envelope.layer.as_deref().unwrap_or_default());
+        encoder.put_str(&envelope.vk_hash);
+        self.encode_fields(&mut encoder);
+
+        encoder.0
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut decoder = Decoder(bytes);
+        if decoder.take(BIN_MAGIC.len())? != BIN_MAGIC {
+            bail!("not a binary proof");
+        }
+        let version = decoder.get_u32()?;
+        if version != PROOF_FORMAT_VERSION {
+            bail!("unsupported binary proof version {version}");
+        }
+        let git_version = decoder.get_str()?;
+        let layer = Some(decoder.get_str()?).filter(|layer| !layer.is_empty());
+        let vk_hash = decoder.get_str()?;
+        let proof = Self::decode_fields(&mut decoder)?;
+        if !decoder.0.is_empty() {
+            bail!("{} trailing bytes after the proof", decoder.0.len());
+        }
+
+        let envelope = ProofEnvelope {
+            version,
+            git_version,
+            layer,
+            vk_hash,
+            proof,
+        };
+        envelope.check()?;
+
+        Ok(envelope.proof)
+    }
+}
+
+pub(super) fn to_json_value<P: VersionedProof>(proof: &P) -> Result<Value> {
+    Ok(serde_json::to_value(ProofEnvelope::new(proof))?)
+}
+
+/// Read a proof of any format version, the unversioned ones are read as is.
+pub(super) fn from_json_value<P: VersionedProof>(value: Value) -> Result<P> {
+    if value.get("version").is_none() || value.get("proof").is_none() {
+        return Ok(serde_json::from_value(value)?);
+    }
+
+    let envelope: ProofEnvelope<Value> = serde_json::from_value(value)?;
+    if envelope.version > PROOF_FORMAT_VERSION {
+        bail!(
+            "unsupported proof format version {} (dumped by {}), the latest is {}",
+            envelope.version,
+            envelope.git_version,
+            PROOF_FORMAT_VERSION
+        );
+    }
+
+    let envelope = ProofEnvelope {
+        proof: serde_json::from_value(migrate(envelope.version, envelope.proof)?)?,
+        version: envelope.version,
+        git_version: envelope.git_version,
+        layer: envelope.layer,
+        vk_hash: envelope.vk_hash,
+    };
+    envelope.check()?;
+
+    Ok(envelope.proof)
+}
+
+/// Migrate the JSON of a proof in an envelope of `version` to the layout of the latest version,
+/// each format change should add its migration step here.
+fn migrate(version: u32, proof: Value) -> Result<Value> {
+    match version {
+        1 => Ok(proof),
+        _ => bail!("no migration from proof format version {version}"),
+    }
+}
+
+fn vk_hash(raw_vk: &[u8]) -> String {
+    hex::encode(Sha256::digest(raw_vk))
+}
+
+/// Writer of the little-endian and length-prefixed fields of a binary proof.
+#[derive(Debug, Default)]
+pub struct Encoder(Vec<u8>);
+
+impl Encoder {
+    pub fn put_u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    pub fn put_u32(&mut self, value: u32) {
+        self.0.extend(value.to_le_bytes());
+    }
+
+    pub fn put_u64(&mut self, value: u64) {
+        self.0.extend(value.to_le_bytes());
+    }
+
+    pub fn put_bytes(&mut self, bytes: &[u8]) {
+        self.put_u64(bytes.len() as u64);
+        self.0.extend(bytes);
+    }
+
+    pub fn put_str(&mut self, s: &str) {
+        self.put_bytes(s.as_bytes());
+    }
+
+    pub fn put_opt_str(&mut self, s: Option<&str>) {
+        match s {
+            Some(s) => {
+                self.put_u8(1);
+                self.put_str(s);
+            }
+            None => self.put_u8(0),
+        }
+    }
+
+    pub fn put_h256(&mut self, hash: &H256) {
+        self.0.extend(hash.as_bytes());
+    }
+}
+
+/// Reader of the fields written by [`Encoder`].
+#[derive(Debug)]
+pub struct Decoder<'a>(&'a [u8]);
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.0.len() < len {
+            bail!("truncated binary proof");
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+
+        Ok(bytes)
+    }
+
+    pub fn get_u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn get_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    pub fn get_u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+
+    pub fn get_bytes(&mut self) -> Result<Vec<u8>> {
+        let len = self.get_u64()?;
+        Ok(self.take(usize::try_from(len)?)?.to_vec())
+    }
+
+    pub fn get_str(&mut self) -> Result<String> {
+        Ok(String::from_utf8(self.get_bytes()?)?)
+    }
+
+    pub fn get_opt_str(&mut self) -> Result<Option<String>> {
+        match self.get_u8()? {
+            0 => Ok(None),
+            _ => Ok(Some(self.get_str()?)),
+        }
+    }
+
+    pub fn get_h256(&mut self) -> Result<H256> {
+        Ok(H256::from_slice(self.take(H256::len_bytes())?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChunkProof, EvmProof, Proof};
+    use aggregator::ChunkHash;
+
+    fn mock_proof() -> Proof {
+        Proof {
+            proof: vec![1, 2, 3],
+            instances: vec![4; 32],
+            vk: vec![5, 6],
+            git_version: Some("abcdef".to_string()),
+        }
+    }
+
+    fn mock_chunk_proof() -> ChunkProof {
+        ChunkProof {
+            protocol: vec![7; 10],
+            proof: mock_proof(),
+            chunk_hash: Some(ChunkHash {
+                chain_id: 534352,
+                data_hash: [8; 32].into(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    #[test]
+    fn test_json_envelope_roundtrip() {
+        let proof = mock_chunk_proof();
+        let value = to_json_value(&proof).unwrap();
+        assert_eq!(value["version"], PROOF_FORMAT_VERSION);
+        assert_eq!(value["layer"], "layer2");
+
+        let read: ChunkProof = from_json_value(value).unwrap();
+        assert_eq!(
+            serde_json::to_value(read).unwrap(),
+            serde_json::to_value(proof).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_json_unversioned() {
+        let proof = mock_chunk_proof();
+        let read: ChunkProof = from_json_value(serde_json::to_value(&proof).unwrap()).unwrap();
+        assert_eq!(read.protocol, proof.protocol);
+        assert_eq!(read.chunk_hash.unwrap().data_hash, H256::repeat_byte(8));
+    }
+
+    #[test]
+    fn test_json_envelope_rejected() {
+        let proof = mock_chunk_proof();
+
+        let mut value = to_json_value(&proof).unwrap();
+        value["version"] = (PROOF_FORMAT_VERSION + 1).into();
+        assert!(from_json_value::<ChunkProof>(value).is_err());
+
+        let mut value = to_json_value(&proof).unwrap();
+        value["vk_hash"] = vk_hash(b"another vk").into();
+        assert!(from_json_value::<ChunkProof>(value).is_err());
+
+        // A chunk proof is not an EVM proof of any layer.
+        let value = to_json_value(&proof).unwrap();
+        assert!(from_json_value::<EvmProof>(value).is_err());
+    }
+
+    #[test]
+    fn test_binary_roundtrip() {
+        let proof = mock_chunk_proof();
+        let bytes = proof.to_bytes();
+        let read = ChunkProof::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            serde_json::to_value(read).unwrap(),
+            serde_json::to_value(&proof).unwrap()
+        );
+
+        assert!(ChunkProof::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        let evm_proof = EvmProof {
+            proof: mock_proof(),
+            num_instance: vec![44],
+        };
+        let read = EvmProof::from_bytes(&evm_proof.to_bytes()).unwrap();
+        assert_eq!(read.num_instance, evm_proof.num_instance);
+        assert_eq!(read.proof.proof, evm_proof.proof.proof);
+    }
+}
@@ -1,4 +1,8 @@
-use super::{dump_as_json, dump_vk, from_json_file, Proof};
+use super::{
+    dump_as_bin, dump_as_json, dump_vk, from_bin_file, from_json_file, Decoder, Encoder, Proof,
+    VersionedProof,
+};
+use crate::config::LayerId;
 use anyhow::Result;
 use halo2_proofs::{
     halo2curves::bn256::{Fr, G1Affine},
@@ -31,11 +35,45 @@ impl EvmProof {
         from_json_file(dir, &dump_filename(name))
     }
 
+    pub fn from_bin_file(dir: &str, name: &str) -> Result<Self> {
+        from_bin_file(dir, &dump_filename(name))
+    }
+
     pub fn dump(&self, dir: &str, name: &str) -> Result<()> {
         let filename = dump_filename(name);
 
         dump_vk(dir, &filename, &self.proof.vk);
-        dump_as_json(dir, &filename, &self)
+        dump_as_json(dir, &filename, self)?;
+        dump_as_bin(dir, &filename, self)
+    }
+}
+
+impl VersionedProof for EvmProof {
+    // EVM proofs of any layer (layer-2, layer-4 and layer-6).
+    const LAYER: Option<LayerId> = None;
+
+    fn raw_vk(&self) -> &[u8] {
+        &self.proof.vk
+    }
+
+    fn encode_fields(&self, encoder: &mut Encoder) {
+        self.proof.encode_fields(encoder);
+        encoder.put_u32(self.num_instance.len() as u32);
+        for num in &self.num_instance {
+            encoder.put_u64(*num as u64);
+        }
+    }
+
+    fn decode_fields(decoder: &mut Decoder) -> Result<Self> {
+        let proof = Proof::decode_fields(decoder)?;
+        let num_instance = (0..decoder.get_u32()?)
+            .map(|_| decoder.get_u64().map(|num| num as usize))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            proof,
+            num_instance,
+        })
     }
 }
 
@@ -1,7 +1,9 @@
 mod batch;
+mod bundle;
 mod chunk;
 mod inner;
 
 pub use batch::batch_prove;
+pub use bundle::bundle_prove;
 pub use chunk::chunk_prove;
 pub use inner::inner_prove;
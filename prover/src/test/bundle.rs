@@ -0,0 +1,59 @@
+use crate::{
+    bundle::{Prover, Verifier},
+    config::LayerId,
+    consts::BUNDLE_DEPLOYMENT_CODE_FILENAME,
+    io::force_to_read,
+    utils::read_env_var,
+};
+use aggregator::BatchInfo;
+use snark_verifier_sdk::Snark;
+use std::sync::{LazyLock, Mutex};
+
+static BUNDLE_PROVER: LazyLock<Mutex<Prover>> = LazyLock::new(|| {
+    let assets_dir = read_env_var("SCROLL_PROVER_ASSETS_DIR", "./test_assets".to_string());
+    let params_dir = read_env_var("SCROLL_PROVER_PARAMS_DIR", "./test_params".to_string());
+
+    let prover = Prover::from_dirs(&params_dir, &assets_dir);
+    log::info!("Constructed bundle-prover");
+
+    Mutex::new(prover)
+});
+
+static BUNDLE_VERIFIER: LazyLock<Mutex<Verifier>> = LazyLock::new(|| {
+    let assets_dir = read_env_var("SCROLL_PROVER_ASSETS_DIR", "./test_assets".to_string());
+
+    let mut prover = BUNDLE_PROVER.lock().expect("poisoned bundle-prover");
+    let params = prover.inner.params(LayerId::Layer6.degree()).clone();
+
+    let pk = prover
+        .inner
+        .pk(LayerId::Layer6.id())
+        .expect("Failed to get bundle-prove PK");
+    let vk = pk.get_vk().clone();
+
+    let deployment_code = force_to_read(&assets_dir, &BUNDLE_DEPLOYMENT_CODE_FILENAME);
+
+    let verifier = Verifier::new(params, vk, deployment_code);
+    log::info!("Constructed bundle-verifier");
+
+    Mutex::new(verifier)
+});
+
+pub fn bundle_prove(test: &str, batch_infos_snarks: Vec<(BatchInfo, Snark)>) {
+    log::info!("{test}: bundle-prove BEGIN");
+
+    let proof = BUNDLE_PROVER
+        .lock()
+        .expect("poisoned bundle-prover")
+        .gen_bundle_evm_proof(batch_infos_snarks, None, None)
+        .unwrap_or_else(|err| panic!("{test}: failed to generate bundle proof: {err}"));
+    log::info!("{test}: generated bundle proof");
+
+    let verified = BUNDLE_VERIFIER
+        .lock()
+        .expect("poisoned bundle-verifier")
+        .verify_bundle_evm_proof(proof);
+    assert!(verified, "{test}: failed to verify bundle proof");
+
+    log::info!("{test}: bundle-prove END");
+}
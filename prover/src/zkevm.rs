@@ -6,5 +6,5 @@ mod verifier;
 
 pub use self::prover::Prover;
 #[cfg(feature = "scroll")]
-pub use capacity_checker::{CircuitCapacityChecker, RowUsage, SubCircuitRowUsage};
+pub use capacity_checker::{CircuitCapacityChecker, RowAttribution, RowUsage, SubCircuitRowUsage};
 pub use verifier::Verifier;
@@ -4,22 +4,81 @@ use super::circuit::{
 };
 
 use super::circuit::{
-    block_traces_to_witness_block_with_updated_state, calculate_row_usage_of_witness_block,
-    get_super_circuit_params,
+    attribute_row_usage_of_builder, block_traces_to_witness_block_with_updated_state,
+    calculate_row_usage_of_witness_block, estimate_row_usage_of_builder, get_super_circuit_params,
 };
+use anyhow::bail;
 use bus_mapping::{
     circuit_input_builder::{self, CircuitInputBuilder},
-    state_db::{CodeDB, StateDB},
+    state_db::{Account, CodeDB, StateDB},
 };
-use eth_types::{l2_types::BlockTrace, ToWord, H256};
+use eth_types::{l2_types::BlockTrace, Address, ToWord, Word, H256};
 use itertools::Itertools;
-use mpt_zktrie::state::ZktrieState;
+use mpt_zktrie::state::{ZkTrieHash, ZktrieState};
 use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use zkevm_circuits::super_circuit::{
+    RowSource, Subcircuit, SubcircuitRowAttribution, SubcircuitRowUsage,
+};
+
+/// Rows of a subcircuit used by one source, e.g. a tx and its execution state.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RowAttribution {
+    pub source: RowSource,
+    pub row_number: usize,
+}
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SubCircuitRowUsage {
     pub name: String,
     pub row_number: usize,
+    /// Sources of the rows, empty for the subcircuits without attribution.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attribution: Vec<RowAttribution>,
+}
+
+/// Max number of sources listed by [`SubCircuitRowUsage::explain`].
+const MAX_EXPLAINED_SOURCES: usize = 3;
+
+impl SubCircuitRowUsage {
+    /// Describe the main sources of the rows, e.g.
+    /// "keccak: 62% from tx 0x.. Memory hash, 20% from txs".
+    pub fn explain(&self) -> String {
+        let attributed_rows: usize = self.attribution.iter().map(|x| x.row_number).sum();
+        if attributed_rows == 0 {
+            return format!("{}: no attribution", self.name);
+        }
+        let sources = self
+            .attribution
+            .iter()
+            .sorted_by_key(|x| std::cmp::Reverse(x.row_number))
+            .take(MAX_EXPLAINED_SOURCES)
+            .map(|x| {
+                format!(
+                    "{}% from {}",
+                    x.row_number * 100 / attributed_rows,
+                    x.source
+                )
+            })
+            .join(", ");
+        format!("{}: {sources}", self.name)
+    }
+
+    fn add_attribution(&mut self, other: &[RowAttribution]) {
+        let mut rows: HashMap<_, _> = self
+            .attribution
+            .drain(..)
+            .map(|x| (x.source, x.row_number))
+            .collect();
+        for x in other {
+            *rows.entry(x.source).or_default() += x.row_number;
+        }
+        self.attribution = rows
+            .into_iter()
+            .map(|(source, row_number)| RowAttribution { source, row_number })
+            .sorted_by_key(|x| std::cmp::Reverse(x.row_number))
+            .collect();
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -62,15 +121,31 @@ impl RowUsage {
             (MAX_VERTICAL_ROWS, 0.95), // sig
             (MAX_VERTICAL_ROWS, 1.0),  // ecc
             (MAX_MPT_ROWS, 0.95),      // mpt
+            (MAX_KECCAK_ROWS, 0.95),   // sha256
+            (MAX_KECCAK_ROWS, 0.95),   // ripemd160
+            (MAX_KECCAK_ROWS, 0.95),   // blake2f
         ]
         .map(|(limit, confidence)| (limit as f32 * confidence) as usize);
         let details = self
             .row_usage_details
             .iter()
             .zip_eq(real_available_rows.iter())
-            .map(|(x, limit)| SubCircuitRowUsage {
-                name: x.name.clone(),
-                row_number: (1_000_000u64 * (x.row_number as u64) / (*limit as u64)) as usize,
+            .map(|(x, limit)| {
+                let normalize = |row_number: usize| {
+                    (1_000_000u64 * (row_number as u64) / (*limit as u64)) as usize
+                };
+                SubCircuitRowUsage {
+                    name: x.name.clone(),
+                    row_number: normalize(x.row_number),
+                    attribution: x
+                        .attribution
+                        .iter()
+                        .map(|a| RowAttribution {
+                            source: a.source,
+                            row_number: normalize(a.row_number),
+                        })
+                        .collect(),
+                }
             })
             .collect_vec();
         log::debug!(
@@ -99,6 +174,7 @@ impl RowUsage {
             assert_eq!(self.row_usage_details.len(), other.row_usage_details.len());
             for i in 0..self.row_usage_details.len() {
                 self.row_usage_details[i].row_number += other.row_usage_details[i].row_number;
+                self.row_usage_details[i].add_attribution(&other.row_usage_details[i].attribution);
             }
         }
 
@@ -110,6 +186,107 @@ impl RowUsage {
             .unwrap();
         self.is_ok = self.row_number <= NORMALIZED_ROW_LIMIT;
     }
+    /// Explain the subcircuits over the limit of a normalized row usage.
+    pub fn explain(&self) -> Vec<String> {
+        self.row_usage_details
+            .iter()
+            .filter(|x| x.row_number > NORMALIZED_ROW_LIMIT)
+            .map(SubCircuitRowUsage::explain)
+            .collect()
+    }
+}
+
+/// State of the builder context touched by a tx, recorded before the tx is estimated and
+/// restored when the tx is rolled back.
+///
+/// Only the accounts and storage slots in the storage trace of the tx are recorded, since the
+/// trace covers every account and slot the tx reads or writes.
+#[derive(Debug, Clone, Default)]
+struct Checkpoint {
+    /// Accounts without their storage, `None` for the accounts not in the StateDB yet.
+    accounts: Vec<(Address, Option<Account>)>,
+    /// Storage slots, `None` for the slots not in the StateDB yet.
+    storage: Vec<((Address, Word), Option<Word>)>,
+    /// Codes first seen in the tx.
+    new_codes: Vec<H256>,
+    /// Root of the zktrie state.
+    mpt_root: Option<ZkTrieHash>,
+}
+
+impl Checkpoint {
+    fn new(
+        (_, sdb, mpt_state): &(CodeDB, StateDB, Option<ZktrieState>),
+        trace: &BlockTrace,
+    ) -> Self {
+        let storage_trace = &trace.storage_trace;
+        let accounts = storage_trace
+            .proofs
+            .iter()
+            .flat_map(|proofs| proofs.keys())
+            .map(|addr| {
+                let (existed, acc) = sdb.get_account(addr);
+                let acc = existed.then(|| Account {
+                    nonce: acc.nonce,
+                    balance: acc.balance,
+                    storage: Default::default(),
+                    code_hash: acc.code_hash,
+                    keccak_code_hash: acc.keccak_code_hash,
+                    code_size: acc.code_size,
+                });
+                (*addr, acc)
+            })
+            .collect();
+        let storage = storage_trace
+            .storage_proofs
+            .iter()
+            .flat_map(|(addr, proofs)| proofs.keys().map(move |key| (*addr, *key)))
+            .map(|(addr, key)| {
+                let (existed, value) = sdb.get_committed_storage(&addr, &key);
+                ((addr, key), existed.then_some(*value))
+            })
+            .collect();
+
+        Self {
+            accounts,
+            storage,
+            new_codes: Vec::new(),
+            mpt_root: mpt_state.as_ref().map(|state| *state.root()),
+        }
+    }
+
+    /// Undo the changes of the tx.
+    fn restore(self, (code_db, sdb, mpt_state): &mut (CodeDB, StateDB, Option<ZktrieState>)) {
+        for ((addr, key), value) in self.storage {
+            match value {
+                Some(value) => *sdb.get_storage_mut(&addr, &key).1 = value,
+                None => {
+                    sdb.get_account_mut(&addr).1.storage.remove(&key);
+                }
+            }
+        }
+        for (addr, prev) in self.accounts {
+            match prev {
+                Some(prev) => {
+                    let (_, acc) = sdb.get_account_mut(&addr);
+                    acc.nonce = prev.nonce;
+                    acc.balance = prev.balance;
+                    acc.code_hash = prev.code_hash;
+                    acc.keccak_code_hash = prev.keccak_code_hash;
+                    acc.code_size = prev.code_size;
+                }
+                None => {
+                    sdb.remove_account(&addr);
+                }
+            }
+        }
+        for hash in self.new_codes {
+            code_db.0.remove(&hash);
+        }
+        if let (Some(state), Some(root)) = (mpt_state, self.mpt_root) {
+            // the trie nodes are kept in the db, the caches are refilled by the next trace
+            state.prepare_switch_to(root);
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -117,9 +294,16 @@ pub struct CircuitCapacityChecker {
     /// When "light_mode" enabled, we skip zktrie subcircuit in row estimation to avoid the heavy
     /// poseidon cost.
     pub light_mode: bool,
+    /// When "attribution" enabled, the rows used by each tx are attributed to their sources,
+    /// which [`RowUsage::explain`] lists. It costs more than the estimation itself, so it is
+    /// disabled by default.
+    pub attribution: bool,
     pub acc_row_usage: RowUsage,
     pub row_usages: Vec<RowUsage>,
     pub builder_ctx: Option<(CodeDB, StateDB, Option<ZktrieState>)>,
+    /// One checkpoint per estimated tx, aligned with `row_usages`. `None` for the tx which
+    /// created the builder context.
+    checkpoints: Vec<Option<Checkpoint>>,
 }
 
 impl Default for CircuitCapacityChecker {
@@ -135,17 +319,46 @@ impl CircuitCapacityChecker {
             acc_row_usage: RowUsage::new(),
             row_usages: Vec::new(),
             light_mode: true,
+            attribution: false,
             builder_ctx: None,
+            checkpoints: Vec::new(),
         }
     }
     pub fn reset(&mut self) {
         self.builder_ctx = None;
         self.acc_row_usage = RowUsage::new();
         self.row_usages = Vec::new();
+        self.checkpoints = Vec::new();
+    }
+    /// Pop the last `n` estimated txs, restoring the builder context and the accumulated row
+    /// usage to the state before the first popped one.
+    pub fn rollback(&mut self, n: usize) -> Result<(), anyhow::Error> {
+        if n > self.checkpoints.len() {
+            bail!(
+                "cannot rollback {n} txs, only {} txs estimated",
+                self.checkpoints.len()
+            );
+        }
+        let remaining = self.checkpoints.len() - n;
+        for checkpoint in self.checkpoints.drain(remaining..).rev() {
+            match (checkpoint, self.builder_ctx.as_mut()) {
+                (Some(checkpoint), Some(builder_ctx)) => checkpoint.restore(builder_ctx),
+                _ => self.builder_ctx = None,
+            }
+        }
+        self.row_usages.truncate(remaining);
+        self.acc_row_usage = RowUsage::new();
+        for row_usage in &self.row_usages {
+            self.acc_row_usage.add(row_usage);
+        }
+        Ok(())
     }
     pub fn set_light_mode(&mut self, light_mode: bool) {
         self.light_mode = light_mode;
     }
+    pub fn set_attribution(&mut self, attribution: bool) {
+        self.attribution = attribution;
+    }
     pub fn get_tx_num(&self) -> usize {
         self.row_usages.len()
     }
@@ -160,7 +373,36 @@ impl CircuitCapacityChecker {
         &mut self,
         trace: BlockTrace,
     ) -> Result<RowUsage, anyhow::Error> {
-        let (mut estimate_builder, codedb_prev) =
+        let mut checkpoint = self
+            .builder_ctx
+            .as_ref()
+            .map(|builder_ctx| Checkpoint::new(builder_ctx, &trace));
+        match self.estimate_circuit_capacity_inner(trace) {
+            Ok((row_usage, new_codes)) => {
+                if let Some(checkpoint) = checkpoint.as_mut() {
+                    checkpoint.new_codes = new_codes;
+                }
+                self.checkpoints.push(checkpoint);
+                Ok(row_usage)
+            }
+            Err(e) => {
+                // keep the checker usable for the following txs
+                match (checkpoint, self.builder_ctx.as_mut()) {
+                    (Some(checkpoint), Some(builder_ctx)) => checkpoint.restore(builder_ctx),
+                    _ => self.builder_ctx = None,
+                }
+                Err(e)
+            }
+        }
+    }
+    /// Estimate the row usage of a tx, returning the normalized accumulated row usage and the
+    /// codes first seen in the tx. On failure, the builder context is kept for the caller to
+    /// restore it.
+    fn estimate_circuit_capacity_inner(
+        &mut self,
+        trace: BlockTrace,
+    ) -> Result<(RowUsage, Vec<H256>), anyhow::Error> {
+        let (mut estimate_builder, codedb_prev, trace) =
             if let Some((code_db, sdb, mpt_state)) = self.builder_ctx.take() {
                 // here we create a new builder for another (sealed) witness block
                 // this builder inherit the current execution state (sdb) of
@@ -180,7 +422,7 @@ impl CircuitCapacityChecker {
                 // notice the trace has included all code required for builidng witness block,
                 // so we do not need to pick them from previous one, but we still keep the
                 // old codedb in previous run for some dedup work
-                let mut builder = if let Some(mpt_state) = mpt_state {
+                let builder = if let Some(mpt_state) = mpt_state {
                     CircuitInputBuilder::new_with_trie_state(
                         sdb,
                         CodeDB::new(),
@@ -190,8 +432,7 @@ impl CircuitCapacityChecker {
                 } else {
                     CircuitInputBuilder::new(sdb, CodeDB::new(), &builder_block)
                 };
-                builder.add_more_l2_trace(trace, false)?;
-                (builder, Some(code_db))
+                (builder, Some(code_db), Some(trace))
             } else {
                 (
                     CircuitInputBuilder::new_from_l2_trace(
@@ -201,29 +442,50 @@ impl CircuitCapacityChecker {
                         self.light_mode,
                     )?,
                     None,
+                    None,
                 )
             };
-        let witness_block =
-            block_traces_to_witness_block_with_updated_state(vec![], &mut estimate_builder)?;
-        let mut rows = calculate_row_usage_of_witness_block(&witness_block)?;
+        let (mut rows, attributions) =
+            match Self::estimate_row_usage(&mut estimate_builder, trace, self.attribution) {
+                Ok(rows) => rows,
+                Err(e) => {
+                    if let Some(code_db) = codedb_prev {
+                        self.builder_ctx.replace((
+                            code_db,
+                            estimate_builder.sdb,
+                            estimate_builder.mpt_init_state,
+                        ));
+                    }
+                    return Err(e);
+                }
+            };
 
         let mut code_db = codedb_prev.unwrap_or_else(CodeDB::new);
+        let mut new_codes = Vec::new();
         // merge current codes with previous , and dedup bytecode row usage
         // for bytecode circuit / poseidon circuit
         for (hash, bytes) in estimate_builder.code_db.0 {
             let bytes_len = bytes.len();
             // code for current run has been evaluated in previous
             if code_db.0.insert(hash, bytes).is_some() {
-                assert_eq!(rows[2].name, "bytecode");
-                rows[2].row_num_real -= bytes_len + 1;
-                assert_eq!(rows[10].name, "poseidon");
-                rows[10].row_num_real -= bytes_len / (31 * 2) * 9;
+                rows[Subcircuit::Bytecode.index()].row_num_real -= bytes_len + 1;
+                rows[Subcircuit::Poseidon.index()].row_num_real -= bytes_len / (31 * 2) * 9;
+            } else {
+                new_codes.push(hash);
             }
         }
 
         let row_usage_details: Vec<SubCircuitRowUsage> = rows
             .into_iter()
             .map(|x| SubCircuitRowUsage {
+                attribution: attributions
+                    .iter()
+                    .filter(|a| a.subcircuit.name() == x.name)
+                    .map(|a| RowAttribution {
+                        source: a.source,
+                        row_number: a.row_num_real,
+                    })
+                    .collect(),
                 name: x.name,
                 row_number: x.row_num_real,
             })
@@ -237,6 +499,189 @@ impl CircuitCapacityChecker {
             estimate_builder.sdb,
             estimate_builder.mpt_init_state,
         ));
-        Ok(self.acc_row_usage.normalize())
+        let acc_row_usage = self.acc_row_usage.normalize();
+        if !acc_row_usage.is_ok {
+            log::warn!(
+                "row usage over the limit: {}",
+                acc_row_usage.explain().join("; ")
+            );
+        }
+        Ok((acc_row_usage, new_codes))
+    }
+
+    /// Row usage of the builder, after adding the `trace` of the tx if the builder was not
+    /// created from it, and its attribution if `attribute` is set.
+    fn estimate_row_usage(
+        builder: &mut CircuitInputBuilder,
+        trace: Option<BlockTrace>,
+        attribute: bool,
+    ) -> Result<(Vec<SubcircuitRowUsage>, Vec<SubcircuitRowAttribution>), anyhow::Error> {
+        if let Some(trace) = trace {
+            builder.add_more_l2_trace(trace, false)?;
+        }
+        // without the mpt state, the witness block is not needed for the estimation
+        let rows = if builder.mpt_init_state.is_none() {
+            estimate_row_usage_of_builder(builder)?
+        } else {
+            let witness_block = block_traces_to_witness_block_with_updated_state(vec![], builder)?;
+            calculate_row_usage_of_witness_block(&witness_block)?
+        };
+        let attributions = if attribute {
+            attribute_row_usage_of_builder(builder)?
+        } else {
+            Vec::new()
+        };
+        Ok((rows, attributions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row_usage(row_number: usize) -> RowUsage {
+        RowUsage::from_row_usage_details(
+            ["evm", "state"]
+                .map(|name| SubCircuitRowUsage {
+                    name: name.to_string(),
+                    row_number,
+                    attribution: vec![],
+                })
+                .to_vec(),
+        )
+    }
+
+    fn row_numbers(row_usage: &RowUsage) -> Vec<usize> {
+        row_usage
+            .row_usage_details
+            .iter()
+            .map(|x| x.row_number)
+            .collect()
+    }
+
+    /// Trace touching the accounts `addrs` and the slots `keys` of the first one.
+    fn trace_touching(addrs: &[Address], keys: &[u64]) -> BlockTrace {
+        let mut trace = BlockTrace::default();
+        trace.storage_trace.proofs = Some(addrs.iter().map(|addr| (*addr, vec![])).collect());
+        trace.storage_trace.storage_proofs.insert(
+            addrs[0],
+            keys.iter().map(|key| (Word::from(*key), vec![])).collect(),
+        );
+        trace
+    }
+
+    /// Apply the changes of a tx to the builder context, as the builder would.
+    fn execute_tx(
+        (code_db, sdb, _): &mut (CodeDB, StateDB, Option<ZktrieState>),
+        contract: Address,
+        created: Address,
+        code: &[u8],
+    ) -> Vec<H256> {
+        sdb.get_account_mut(&contract).1.balance += Word::from(7);
+        *sdb.get_storage_mut(&contract, &Word::from(1)).1 += Word::from(1);
+        *sdb.get_storage_mut(&contract, &Word::from(2)).1 = Word::from(9);
+        sdb.get_account_mut(&created).1.nonce = Word::one();
+        vec![code_db.insert(code.to_vec())]
+    }
+
+    #[test]
+    fn checkpoint_restores_touched_state() {
+        let contract = Address::repeat_byte(1);
+        let created = Address::repeat_byte(2);
+        let mut sdb = StateDB::new();
+        sdb.set_account(&contract, Account::zero());
+        *sdb.get_storage_mut(&contract, &Word::from(1)).1 = Word::from(5);
+        let mut builder_ctx = (CodeDB::new(), sdb, None);
+        let contract_before = builder_ctx.1.get_account(&contract).1.clone();
+        let codes_before = builder_ctx.0 .0.clone();
+
+        let trace = trace_touching(&[contract, created], &[1, 2]);
+        let mut checkpoint = Checkpoint::new(&builder_ctx, &trace);
+        checkpoint.new_codes = execute_tx(&mut builder_ctx, contract, created, &[0x00]);
+        checkpoint.restore(&mut builder_ctx);
+
+        let (code_db, sdb, _) = &builder_ctx;
+        assert_eq!(sdb.get_account(&contract), (true, &contract_before));
+        assert!(!sdb.get_account(&created).0);
+        assert_eq!(code_db.0, codes_before);
+    }
+
+    #[test]
+    fn rollback_restores_row_usage_and_state() {
+        let contract = Address::repeat_byte(1);
+        let mut checker = CircuitCapacityChecker::new();
+        let mut sdb = StateDB::new();
+        sdb.set_account(&contract, Account::zero());
+        checker.builder_ctx = Some((CodeDB::new(), sdb, None));
+
+        // estimate 3 txs, each creating an account and deploying a code
+        let mut snapshots = vec![];
+        for i in 0..3u8 {
+            let builder_ctx = checker.builder_ctx.as_mut().unwrap();
+            snapshots.push((
+                checker.acc_row_usage.clone(),
+                builder_ctx.1.get_account(&contract).1.clone(),
+                builder_ctx.0 .0.len(),
+            ));
+            let created = Address::repeat_byte(0x10 + i);
+            let trace = trace_touching(&[contract, created], &[1, 2]);
+            let mut checkpoint = Checkpoint::new(builder_ctx, &trace);
+            checkpoint.new_codes = execute_tx(builder_ctx, contract, created, &[i]);
+            checker.checkpoints.push(Some(checkpoint));
+            let tx_row_usage = row_usage(100 * (i as usize + 1));
+            checker.row_usages.push(tx_row_usage.clone());
+            checker.acc_row_usage.add(&tx_row_usage);
+        }
+        assert!(checker.rollback(4).is_err());
+
+        for n in [1, 2] {
+            checker.rollback(1).unwrap();
+            let (acc_row_usage, contract_account, num_codes) = &snapshots[3 - n];
+            assert_eq!(checker.get_tx_num(), 3 - n);
+            assert_eq!(
+                row_numbers(&checker.acc_row_usage),
+                row_numbers(acc_row_usage)
+            );
+            assert_eq!(checker.acc_row_usage.row_number, acc_row_usage.row_number);
+            let (code_db, sdb, _) = checker.builder_ctx.as_ref().unwrap();
+            assert_eq!(sdb.get_account(&contract), (true, contract_account));
+            assert!(!sdb.get_account(&Address::repeat_byte(0x10 + 3 - n as u8)).0);
+            assert_eq!(code_db.0.len(), *num_codes);
+        }
+
+        // rolling back the tx which created the builder context drops it
+        checker.checkpoints[0] = None;
+        checker.rollback(1).unwrap();
+        assert!(checker.builder_ctx.is_none());
+        assert_eq!(checker.get_tx_num(), 0);
+        assert_eq!(checker.acc_row_usage.row_number, 0);
+    }
+
+    #[test]
+    fn row_usage_merges_attributions_by_source() {
+        let tx = H256::repeat_byte(1);
+        let with_attribution = |sources: &[(RowSource, usize)]| {
+            let mut row_usage = row_usage(sources.iter().map(|(_, rows)| rows).sum());
+            row_usage.row_usage_details[0].attribution = sources
+                .iter()
+                .map(|(source, row_number)| RowAttribution {
+                    source: *source,
+                    row_number: *row_number,
+                })
+                .collect();
+            row_usage
+        };
+
+        let mut acc = with_attribution(&[(RowSource::Tx(tx), 30), (RowSource::Block, 10)]);
+        acc.add(&with_attribution(&[(RowSource::Tx(tx), 50)]));
+        let attribution = &acc.row_usage_details[0].attribution;
+        assert_eq!(attribution.len(), 2);
+        assert_eq!(attribution[0].source, RowSource::Tx(tx));
+        assert_eq!(attribution[0].row_number, 80);
+        assert_eq!(
+            acc.row_usage_details[0].explain(),
+            format!("evm: 88% from tx {tx:?}, 11% from block")
+        );
+        assert_eq!(acc.row_usage_details[1].explain(), "state: no attribution");
     }
 }
@@ -13,10 +13,10 @@ mod l1_builder;
 use l1_builder as builder;
 mod super_circuit;
 pub use self::builder::{
-    block_trace_to_witness_block, block_traces_to_witness_block,
+    attribute_row_usage_of_builder, block_trace_to_witness_block, block_traces_to_witness_block,
     block_traces_to_witness_block_with_updated_state, calculate_row_usage_of_trace,
-    calculate_row_usage_of_witness_block, check_batch_capacity, get_super_circuit_params,
-    validite_block_traces,
+    calculate_row_usage_of_witness_block, check_batch_capacity, estimate_row_usage_of_builder,
+    get_super_circuit_params, validite_block_traces,
 };
 pub use super_circuit::SuperCircuit;
 
@@ -24,6 +24,18 @@ pub fn calculate_row_usage_of_witness_block(
     unimplemented!("Must build with feature scroll")
 }
 
+pub fn estimate_row_usage_of_builder(
+    _builder: &mut CircuitInputBuilder,
+) -> Result<Vec<zkevm_circuits::super_circuit::SubcircuitRowUsage>> {
+    unimplemented!("Must build with feature scroll")
+}
+
+pub fn attribute_row_usage_of_builder(
+    _builder: &CircuitInputBuilder,
+) -> Result<Vec<zkevm_circuits::super_circuit::SubcircuitRowAttribution>> {
+    unimplemented!("Must build with feature scroll")
+}
+
 #[allow(clippy::ptr_arg)]
 pub fn check_batch_capacity(_block_traces: &mut Vec<BlockTrace>) -> Result<()> {
     unimplemented!("Must build with feature scroll")
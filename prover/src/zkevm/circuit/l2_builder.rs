@@ -12,6 +12,7 @@ use mpt_zktrie::state::{ZkTrieHash, ZktrieState};
 use std::{sync::LazyLock, time::Instant};
 use zkevm_circuits::{
     evm_circuit::witness::{block_apply_mpt_state, Block},
+    super_circuit::Subcircuit,
     util::SubCircuit,
     witness::block_convert,
 };
@@ -73,16 +74,12 @@ pub fn calculate_row_usage_of_witness_block(
     let mut rows = <super::SuperCircuit as TargetCircuit>::Inner::min_num_rows_block_subcircuits(
         witness_block,
     );
-    assert_eq!(rows[10].name, "poseidon");
-    assert_eq!(rows[13].name, "mpt");
-    // empirical estimation is each row in mpt cost 1.5 hash (aka 12 rows)
-    let mpt_poseidon_rows = rows[13].row_num_real * 12;
     if witness_block.mpt_updates.smt_traces.is_empty() {
-        rows[10].row_num_real += mpt_poseidon_rows;
-        log::debug!("calculate_row_usage_of_witness_block light mode, adding {mpt_poseidon_rows} poseidon rows");
+        add_mpt_poseidon_rows(&mut rows);
     } else {
-        //rows[10].row_num_real += mpt_poseidon_rows;
-        log::debug!("calculate_row_usage_of_witness_block normal mode, skip adding {mpt_poseidon_rows} poseidon rows");
+        log::debug!(
+            "calculate_row_usage_of_witness_block normal mode, skip adding mpt poseidon rows"
+        );
     }
 
     log::debug!(
@@ -103,6 +100,46 @@ pub fn calculate_row_usage_of_witness_block(
     Ok(rows)
 }
 
+/// Estimate the row usage of the block being built by `builder` without building the witness
+/// block. The mpt witness is not generated, so it is only valid in light mode.
+pub fn estimate_row_usage_of_builder(
+    builder: &mut CircuitInputBuilder,
+) -> Result<Vec<zkevm_circuits::super_circuit::SubcircuitRowUsage>> {
+    builder.finalize_building()?;
+    let mut rows = <super::SuperCircuit as TargetCircuit>::Inner::estimate_num_rows_subcircuits(
+        &builder.block,
+        &builder.code_db,
+    )?;
+    add_mpt_poseidon_rows(&mut rows);
+    log::debug!(
+        "estimated row usage of builder, tx num {:?}, rows needed {:?}",
+        builder.block.txs().len(),
+        rows,
+    );
+    Ok(rows)
+}
+
+/// Attribute the rows used by the block of the finalized `builder` to their sources.
+pub fn attribute_row_usage_of_builder(
+    builder: &CircuitInputBuilder,
+) -> Result<Vec<zkevm_circuits::super_circuit::SubcircuitRowAttribution>> {
+    Ok(
+        <super::SuperCircuit as TargetCircuit>::Inner::row_attributions_subcircuits(
+            &builder.block,
+            &builder.code_db,
+        )?,
+    )
+}
+
+/// Without the mpt witness, the poseidon rows used by the mpt circuit are estimated from the
+/// mpt rows.
+fn add_mpt_poseidon_rows(rows: &mut [zkevm_circuits::super_circuit::SubcircuitRowUsage]) {
+    // empirical estimation is each row in mpt cost 1.5 hash (aka 12 rows)
+    let mpt_poseidon_rows = rows[Subcircuit::Mpt.index()].row_num_real * 12;
+    rows[Subcircuit::Poseidon.index()].row_num_real += mpt_poseidon_rows;
+    log::debug!("light mode, adding {mpt_poseidon_rows} poseidon rows");
+}
+
 // FIXME: we need better API name for this.
 // This function also mutates the block trace.
 pub fn check_batch_capacity(block_traces: &mut Vec<BlockTrace>) -> Result<()> {
@@ -141,6 +178,7 @@ pub fn check_batch_capacity(block_traces: &mut Vec<BlockTrace>) -> Result<()> {
             .map(|x| crate::zkevm::SubCircuitRowUsage {
                 name: x.name,
                 row_number: x.row_num_real,
+                attribution: Vec::new(),
             })
             .collect_vec();
         if acc.is_empty() {
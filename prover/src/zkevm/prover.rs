@@ -19,7 +19,8 @@ pub struct Prover {
 
 impl Prover {
     pub fn from_dirs(params_dir: &str, assets_dir: &str) -> Self {
-        let inner = common::Prover::from_params_dir(params_dir, &ZKEVM_DEGREES);
+        let mut inner = common::Prover::from_params_dir(params_dir, &ZKEVM_DEGREES);
+        inner.set_pk_dir(assets_dir, *common::SAVE_PKS);
 
         let raw_vk = try_to_read(assets_dir, &CHUNK_VK_FILENAME);
         if raw_vk.is_none() {
@@ -76,7 +77,7 @@ impl Prover {
         match output_dir.and_then(|output_dir| ChunkProof::from_json_file(output_dir, &name).ok()) {
             Some(proof) => Ok(proof),
             None => {
-                let chunk_hash = ChunkHash::from_witness_block(&witness_block, false);
+                let chunk_hash = ChunkHash::from_witness_block(&witness_block, false)?;
 
                 let result =
                     ChunkProof::new(snark, self.inner.pk(LayerId::Layer2.id()), Some(chunk_hash));
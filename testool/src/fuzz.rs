@@ -0,0 +1,332 @@
+//! Differential fuzzer: random bytecode is traced by the external tracer, re-executed by
+//! bus-mapping and checked with the MockProver on the SuperCircuit. Failing programs are
+//! shrunk to a minimal reproducer which can be replayed with `--oneliner`.
+
+use crate::{
+    config::TestSuite,
+    statetest::{run_test, CircuitsConfig, StateTest},
+};
+use anyhow::Result;
+use eth_types::{bytecode::OpcodeWithData, evm_types::OpcodeId, Bytecode, Word};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use std::panic::AssertUnwindSafe;
+
+/// Address of the contract holding the fuzzed program.
+const CONTRACT_ADDRESS: &str = "0x0000000000000000000000000000000000000fff";
+/// Gas limit of the fuzzing transaction.
+const TX_GAS_LIMIT: u64 = 1_000_000;
+/// Max number of steps per program, keep it small to fit the mock super circuit params.
+const MAX_STEPS: u64 = 64;
+/// Max number of opcodes of a generated program.
+const MAX_PROGRAM_LEN: usize = 32;
+
+#[derive(Debug, Clone)]
+pub struct FuzzConfig {
+    /// Number of random programs to run.
+    pub iterations: usize,
+    /// Seed of the program generator.
+    pub seed: u64,
+}
+
+/// Outcome of running one program through tracer, bus-mapping and circuits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Outcome {
+    Pass,
+    Skip,
+    Fail(String),
+}
+
+impl Outcome {
+    /// The signature of a failure, i.e. the first line of its error with the words holding
+    /// numbers masked, so that rows, offsets and values don't tell two failures apart.
+    fn failure_signature(&self) -> Option<String> {
+        let Outcome::Fail(err) = self else {
+            return None;
+        };
+        let first_line = err.lines().next().unwrap_or_default();
+        Some(
+            first_line
+                .split_whitespace()
+                .map(|word| {
+                    if word.contains(|c: char| c.is_ascii_digit()) {
+                        "#"
+                    } else {
+                        word
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+    }
+}
+
+/// Opcodes picked by the generator. Jumps, calls, creates and selfdestruct are excluded
+/// since random operands almost never hit a meaningful target for them. TLOAD, TSTORE,
+/// MCOPY and BLOBHASH are only valid, and so only picked, with the `cancun` feature.
+fn candidate_opcodes() -> Vec<OpcodeId> {
+    OpcodeId::valid_opcodes()
+        .into_iter()
+        .filter(|op| {
+            !op.is_push_with_data()
+                && !op.is_call_or_create()
+                && !matches!(
+                    op,
+                    OpcodeId::JUMP | OpcodeId::JUMPI | OpcodeId::SELFDESTRUCT
+                )
+        })
+        .collect()
+}
+
+fn random_push(rng: &mut ChaCha20Rng) -> OpcodeWithData {
+    // most of the operands are used as offsets or lengths, so prefer small values.
+    if rng.gen_bool(0.8) {
+        OpcodeWithData::PushWithData(1, Word::from(rng.gen::<u8>()))
+    } else {
+        let n = rng.gen_range(1..=32u8);
+        let mut bytes = [0u8; 32];
+        rng.fill(&mut bytes[32 - n as usize..]);
+        OpcodeWithData::PushWithData(n, Word::from_big_endian(&bytes))
+    }
+}
+
+fn random_program(rng: &mut ChaCha20Rng, opcodes: &[OpcodeId]) -> Vec<OpcodeWithData> {
+    let len = rng.gen_range(1..=MAX_PROGRAM_LEN);
+    (0..len)
+        .map(|_| {
+            if rng.gen_bool(0.4) {
+                random_push(rng)
+            } else {
+                OpcodeWithData::Opcode(opcodes[rng.gen_range(0..opcodes.len())])
+            }
+        })
+        .collect()
+}
+
+fn to_bytecode(program: &[OpcodeWithData]) -> Bytecode {
+    program
+        .iter()
+        .cloned()
+        .fold(Bytecode::default(), |mut code, op| {
+            code.append_op(op);
+            code
+        })
+}
+
+/// The `--oneliner` spec calling a contract which holds `program`.
+fn oneliner_spec(program: &[OpcodeWithData]) -> String {
+    format!(
+        "call;;0;{TX_GAS_LIMIT} {CONTRACT_ADDRESS};{};0",
+        hex::encode(to_bytecode(program).code())
+    )
+}
+
+fn run_program(program: &[OpcodeWithData]) -> Outcome {
+    let test = match StateTest::parse_oneline_spec(&oneliner_spec(program)) {
+        Ok(test) => test,
+        Err(err) => return Outcome::Fail(err.to_string()),
+    };
+    let mut suite = TestSuite::default();
+    suite.max_steps = MAX_STEPS;
+    let circuits_config = CircuitsConfig {
+        super_circuit: true,
+        verbose: false,
+    };
+
+    let result =
+        std::panic::catch_unwind(AssertUnwindSafe(|| run_test(test, suite, circuits_config)));
+    match result {
+        Ok(Ok(())) => Outcome::Pass,
+        Ok(Err(err)) if err.is_skip() => Outcome::Skip,
+        Ok(Err(err)) => Outcome::Fail(err.to_string()),
+        Err(err) => {
+            let panic_err = if let Some(s) = err.downcast_ref::<String>() {
+                s.to_string()
+            } else if let Some(s) = err.downcast_ref::<&str>() {
+                s.to_string()
+            } else {
+                "unable to get panic info".into()
+            };
+            if panic_err.contains("evm_unimplemented") {
+                Outcome::Skip
+            } else {
+                Outcome::Fail(panic_err)
+            }
+        }
+    }
+}
+
+/// Greedy delta debugging: remove chunks of decreasing size as long as the program keeps
+/// failing, then try to simplify the remaining push operands. A candidate is only kept if it
+/// fails with the same signature as the original program, so the shrinking can't drift to an
+/// unrelated bug.
+fn shrink(
+    mut program: Vec<OpcodeWithData>,
+    signature: &str,
+    run: impl Fn(&[OpcodeWithData]) -> Outcome,
+) -> Vec<OpcodeWithData> {
+    let same_failure = |candidate: &[OpcodeWithData]| {
+        run(candidate).failure_signature().as_deref() == Some(signature)
+    };
+
+    let mut chunk = program.len() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        while start < program.len() {
+            let end = (start + chunk).min(program.len());
+            let candidate = [&program[..start], &program[end..]].concat();
+            if !candidate.is_empty() && same_failure(&candidate) {
+                log::info!("shrink: {} -> {} ops", program.len(), candidate.len());
+                program = candidate;
+            } else {
+                start += chunk;
+            }
+        }
+        chunk /= 2;
+    }
+
+    for i in 0..program.len() {
+        if let OpcodeWithData::PushWithData(n, value) = program[i] {
+            if n == 1 && value.is_zero() {
+                continue;
+            }
+            let mut candidate = program.clone();
+            candidate[i] = OpcodeWithData::PushWithData(1, Word::zero());
+            if same_failure(&candidate) {
+                program = candidate;
+            }
+        }
+    }
+
+    program
+}
+
+/// Run the fuzzer, returns the minimal reproducers of all the failures found.
+pub fn run_fuzzer(config: FuzzConfig) -> Result<Vec<String>> {
+    let opcodes = candidate_opcodes();
+    let mut rng = ChaCha20Rng::seed_from_u64(config.seed);
+    let mut reproducers = Vec::new();
+    let (mut passed, mut skipped) = (0, 0);
+
+    // panics are expected while fuzzing and handled as failures, keep the output clean.
+    let panic_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_info| {}));
+
+    for iteration in 0..config.iterations {
+        let program = random_program(&mut rng, &opcodes);
+        let outcome = run_program(&program);
+        match &outcome {
+            Outcome::Pass => passed += 1,
+            Outcome::Skip => skipped += 1,
+            Outcome::Fail(err) => {
+                log::warn!(
+                    "fuzz #{iteration}: failure found ({err}), program {}",
+                    to_bytecode(&program).disasm()
+                );
+                let signature = outcome.failure_signature().expect("outcome is a failure");
+                let program = shrink(program, &signature, run_program);
+                let spec = oneliner_spec(&program);
+                log::warn!(
+                    "fuzz #{iteration}: minimal reproducer {}\n  --oneliner \"{spec}\"",
+                    to_bytecode(&program).disasm()
+                );
+                reproducers.push(spec);
+            }
+        }
+    }
+
+    std::panic::set_hook(panic_hook);
+    log::info!(
+        "fuzz done (seed {}): {passed} passed, {skipped} skipped, {} failed",
+        config.seed,
+        reproducers.len()
+    );
+    Ok(reproducers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fake run failing on ADD, and with another bug on MUL.
+    fn run(program: &[OpcodeWithData]) -> Outcome {
+        let has = |op| program.contains(&OpcodeWithData::Opcode(op));
+        if has(OpcodeId::ADD) {
+            Outcome::Fail(format!("add failed at step {}", program.len()))
+        } else if has(OpcodeId::MUL) {
+            Outcome::Fail("mul failed".to_string())
+        } else {
+            Outcome::Pass
+        }
+    }
+
+    fn asm(program: &[OpcodeWithData]) -> Vec<String> {
+        program.iter().map(ToString::to_string).collect()
+    }
+
+    #[cfg(feature = "cancun")]
+    #[test]
+    fn candidate_opcodes_include_cancun() {
+        let opcodes = candidate_opcodes();
+        for op in [
+            OpcodeId::TLOAD,
+            OpcodeId::TSTORE,
+            OpcodeId::MCOPY,
+            OpcodeId::BLOBHASH,
+        ] {
+            assert!(opcodes.contains(&op), "{op:?} is not a candidate");
+        }
+    }
+
+    #[test]
+    fn failure_signature_masks_numbers() {
+        assert_eq!(
+            Outcome::Fail("add failed at step 12\nbacktrace".to_string()).failure_signature(),
+            Some("add failed at step #".to_string())
+        );
+        assert_eq!(Outcome::Pass.failure_signature(), None);
+    }
+
+    #[test]
+    fn shrink_keeps_the_original_failure() {
+        let program = vec![
+            OpcodeWithData::PushWithData(1, Word::from(3)),
+            OpcodeWithData::Opcode(OpcodeId::ADD),
+            OpcodeWithData::Opcode(OpcodeId::POP),
+            OpcodeWithData::PushWithData(2, Word::from(0x1234)),
+            OpcodeWithData::PushWithData(1, Word::from(7)),
+            OpcodeWithData::Opcode(OpcodeId::MUL),
+        ];
+        let signature = run(&program).failure_signature().unwrap();
+
+        // Dropping the first half still fails, but on MUL, which is another bug.
+        assert_eq!(
+            asm(&shrink(program, &signature, run)),
+            asm(&[OpcodeWithData::Opcode(OpcodeId::ADD)])
+        );
+    }
+
+    #[test]
+    fn shrink_simplifies_push_operands() {
+        // Fails on ADD, but only when it's preceded by a push.
+        let run = |program: &[OpcodeWithData]| match program {
+            [OpcodeWithData::PushWithData(..), OpcodeWithData::Opcode(OpcodeId::ADD)] => {
+                Outcome::Fail("add failed".to_string())
+            }
+            _ => Outcome::Pass,
+        };
+        let program = vec![
+            OpcodeWithData::PushWithData(4, Word::from(0xdeadbeefu64)),
+            OpcodeWithData::Opcode(OpcodeId::ADD),
+        ];
+        let signature = run(&program).failure_signature().unwrap();
+
+        assert_eq!(
+            asm(&shrink(program, &signature, run)),
+            asm(&[
+                OpcodeWithData::PushWithData(1, Word::zero()),
+                OpcodeWithData::Opcode(OpcodeId::ADD),
+            ])
+        );
+    }
+}
@@ -4,6 +4,7 @@
 mod abi;
 mod compiler;
 mod config;
+mod fuzz;
 mod statetest;
 mod utils;
 
@@ -12,6 +13,7 @@ use anyhow::{bail, Result};
 use clap::Parser;
 use compiler::Compiler;
 use config::Config;
+use fuzz::{run_fuzzer, FuzzConfig};
 use log::info;
 use statetest::{
     load_statetests_suite, run_statetests_suite, run_test, CircuitsConfig, Results, StateTest,
@@ -80,6 +82,14 @@ struct Args {
     #[clap(long)]
     circuits: Option<Circuits>,
 
+    /// Fuzz the circuits with the given number of random programs
+    #[clap(long)]
+    fuzz: Option<usize>,
+
+    /// Seed used by the fuzzer to generate programs
+    #[clap(long, default_value = "0")]
+    fuzz_seed: u64,
+
     /// Specify a file including test IDs to run these tests
     #[clap(long)]
     test_ids: Option<String>,
@@ -163,10 +173,21 @@ fn go() -> Result<()> {
         return Ok(());
     }
 
-    let config = Config::load()?;
-
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
+    if let Some(iterations) = args.fuzz {
+        let reproducers = run_fuzzer(FuzzConfig {
+            iterations,
+            seed: args.fuzz_seed,
+        })?;
+        if !reproducers.is_empty() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let config = Config::load()?;
+
     log::info!("Using suite '{}'", args.suite);
     log::info!("Parsing and compliling tests...");
     let compiler = Compiler::new(true, Some(PathBuf::from(CODEHASH_FILE)))?;
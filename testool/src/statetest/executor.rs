@@ -13,9 +13,15 @@ use itertools::Itertools;
 use std::{collections::HashMap, env, str::FromStr, sync::LazyLock};
 use thiserror::Error;
 use zkevm_circuits::{
-    bytecode_circuit::circuit::BytecodeCircuit, ecc_circuit::EccCircuit,
-    modexp_circuit::ModExpCircuit, sig_circuit::SigCircuit, super_circuit::SuperCircuit,
-    test_util::CircuitTestBuilder, util::SubCircuit, witness::Block,
+    bytecode_circuit::circuit::BytecodeCircuit,
+    ecc_circuit::EccCircuit,
+    modexp_circuit::ModExpCircuit,
+    sig_circuit::SigCircuit,
+    state_circuit::StateCircuit,
+    super_circuit::SuperCircuit,
+    test_util::{CircuitTestBuilder, DiagnosticReport, WitnessLayout},
+    util::SubCircuit,
+    witness::Block,
 };
 
 /// Read env var with default value
@@ -178,6 +184,8 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                 gas_tip_cap: st.max_priority_fee_per_gas,
                 call_data: st.data,
                 access_list: st.access_list,
+                max_fee_per_blob_gas: Default::default(),
+                blob_versioned_hashes: vec![],
                 v,
                 r: sig.r,
                 s: sig.s,
@@ -749,7 +757,18 @@ fn mock_prove(test_id: &str, witness_block: &Block<Fr>) {
     let circuit = ScrollSuperCircuit::new_from_block(witness_block);
     let instance = circuit.instance();
     let prover = MockProver::run(k, &circuit, instance).unwrap();
-    prover.assert_satisfied_par();
+    if prover.verify_par().is_err() {
+        let state_circuit = StateCircuit::new_from_block(witness_block);
+        let layout = WitnessLayout::evm(witness_block).with_state(&state_circuit);
+        let usable_rows = (1 << k) - ScrollSuperCircuit::unusable_rows();
+        let report = DiagnosticReport::new(&prover, &layout, 0..usable_rows);
+        if let Ok(path) = env::var("DIAGNOSTICS_JSON") {
+            if let Err(e) = std::fs::write(&path, report.to_json()) {
+                log::error!("{test_id}: fail to write diagnostics to {path}: {e:?}");
+            }
+        }
+        panic!("circuit was not satisfied\n{report}");
+    }
 
     log::info!("{test_id}: mock-prove END");
 }
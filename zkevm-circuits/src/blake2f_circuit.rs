@@ -0,0 +1,145 @@
+//! The BLAKE2F circuit serve for precompile BLAKE2F calls
+
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    halo2curves::bn256::Fr,
+    plonk::{Any, Column, ConstraintSystem, Error, Expression},
+};
+
+mod circuit;
+#[cfg(test)]
+mod test;
+
+use circuit::{Blake2fTable as TableTrait, Compressor};
+pub use circuit::{CircuitConfig, INPUT_SIZE_IN_BYTES, UNIT_ROWS};
+
+use crate::{
+    table::{Blake2fTable, LookupTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::circuit_input_builder::Blake2fOp;
+use eth_types::Field;
+
+impl TableTrait for Blake2fTable {
+    fn cols(&self) -> [Column<Any>; 4] {
+        let tbl_cols = <Self as LookupTable<Fr>>::columns(self);
+        [tbl_cols[0], tbl_cols[2], tbl_cols[3], tbl_cols[1]]
+    }
+}
+
+/// Config args for BLAKE2F circuit
+#[derive(Debug, Clone)]
+pub struct CircuitConfigArgs<F: Field> {
+    /// BLAKE2F Table
+    pub blake2f_table: Blake2fTable,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl SubCircuitConfig<Fr> for CircuitConfig {
+    type ConfigArgs = CircuitConfigArgs<Fr>;
+
+    /// Return a new Blake2fCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<Fr>,
+        Self::ConfigArgs {
+            blake2f_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        Self::configure(meta, blake2f_table, challenges.keccak_input())
+    }
+}
+
+/// BLAKE2F circuit for precompile blake2f
+#[derive(Clone, Debug, Default)]
+pub struct Blake2fCircuit<F: Field>(Vec<Blake2fOp>, usize, std::marker::PhantomData<F>);
+
+impl<F: Field> Blake2fCircuit<F> {
+    fn expected_rows(&self) -> usize {
+        self.0
+            .iter()
+            // each round takes an unit, plus the one for output
+            .map(|evnt| evnt.rounds as usize + 1)
+            .reduce(|acc, v| acc + v)
+            .unwrap_or_default()
+            * UNIT_ROWS
+    }
+
+    /// Minimum and total number of rows of the circuit for the blake2f events.
+    pub(crate) fn min_num_rows(events: Vec<Blake2fOp>, max_keccak_rows: usize) -> (usize, usize) {
+        let real_row = Self(events, 0, Default::default()).expected_rows();
+
+        (real_row, real_row.max(max_keccak_rows).max(4096))
+    }
+
+    fn with_row_limit(self, row_limit: usize) -> Self {
+        if row_limit != 0 {
+            let expected_rows = self.expected_rows();
+            assert!(
+                expected_rows <= row_limit,
+                "no enough rows for blake2f circuit, expected {expected_rows}, limit {row_limit}",
+            );
+            log::info!("blake2f circuit work with maxium {} rows", row_limit);
+        }
+        let inp = self.0;
+        let unit_limit = row_limit / UNIT_ROWS;
+
+        Self(inp, unit_limit, Default::default())
+    }
+}
+
+impl SubCircuit<Fr> for Blake2fCircuit<Fr> {
+    type Config = CircuitConfig;
+
+    fn unusable_rows() -> usize {
+        2
+    }
+
+    fn new_from_block(block: &witness::Block<Fr>) -> Self {
+        Self(block.get_blake2f(), 0, Default::default())
+            .with_row_limit(block.circuits_params.max_keccak_rows)
+    }
+
+    fn min_num_rows_block(block: &witness::Block<Fr>) -> (usize, usize) {
+        Self::min_num_rows(block.get_blake2f(), block.circuits_params.max_keccak_rows)
+    }
+
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<Fr>>,
+        layouter: &mut impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let chng = challenges.keccak_input();
+        let mut compressor = Compressor::new(config.clone(), layouter)?;
+
+        for blake2f_event in &self.0 {
+            let output = compressor.compress(layouter, chng, &blake2f_event.input_bytes())?;
+            // output words of blake2f are little-endian
+            let ref_output = blake2f_event
+                .output
+                .chunks_exact(8)
+                .map(|bt| u64::from_le_bytes(bt.try_into().unwrap()))
+                .collect::<Vec<_>>();
+            for (w, check) in output.into_iter().zip(ref_output) {
+                w.value()
+                    .assert_if_known(|output_word| **output_word == Fr::from(check));
+            }
+
+            if compressor.units() > self.1 {
+                log::error!("handled units exceed limit ({})", self.1);
+                return Err(Error::Synthesis);
+            }
+        }
+
+        // paddings, each takes an unit for 0 rounds
+        let padding_input = [0u8; INPUT_SIZE_IN_BYTES];
+        for _i in compressor.units()..self.1 {
+            compressor.compress(layouter, chng, &padding_input)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,1143 @@
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{AssignedCell, Layouter, Region, Value},
+    halo2curves::bn256::Fr,
+    plonk::{
+        Advice, Any, Column, ConstraintSystem, Constraints, Error, Expression, Fixed, Selector,
+    },
+    poly::Rotation,
+};
+use std::convert::TryInto;
+
+/// u64 words of the output state
+pub const OUTPUT_WORDS: usize = 8;
+/// bytes of the input for BLAKE2F precompile
+pub const INPUT_SIZE_IN_BYTES: usize = 213;
+
+/// u64 words of the working vector
+const STATE_WORDS: usize = 16;
+/// u64 words of the message block
+const MSG_WORDS: usize = 16;
+/// the message schedule repeats every 10 rounds
+const SCHEDULE_PERIOD: usize = 10;
+/// G functions in a round
+const G_FUNCS: usize = 8;
+
+/// the header words are (rounds, h[8], m[16], t[2], f), in the order of input bytes
+const HEADER_WORDS: usize = 28;
+const H_IDX: usize = 1;
+const M_IDX: usize = H_IDX + OUTPUT_WORDS;
+const T_IDX: usize = M_IDX + MSG_WORDS;
+const FLAG_IDX: usize = T_IDX + 2;
+
+/// rows for the header of a unit, the first row holds the inherited cells
+const HEADER_OFFSET: usize = 2;
+const REMAINING_ROW: usize = HEADER_OFFSET + HEADER_WORDS;
+/// rows for v[12], v[13] and v[14] mixed with the counter and the final block flag
+const FRESH_OFFSET: usize = REMAINING_ROW + 1;
+const SELECT_OFFSET: usize = FRESH_OFFSET + 7;
+const OUTPUT_OFFSET: usize = SELECT_OFFSET + STATE_WORDS;
+const ROUND_OFFSET: usize = OUTPUT_OFFSET + 5 * OUTPUT_WORDS;
+/// rows used by a unit, which outputs the current state and runs one round
+pub const UNIT_ROWS: usize = ROUND_OFFSET + 12 * G_FUNCS;
+
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// all the rotation amounts used in the G function, 0 for the plain xor
+const ROTATIONS: [u32; 5] = [0, 16, 24, 32, 63];
+
+#[rustfmt::skip]
+const SIGMA: [[usize; MSG_WORDS]; SCHEDULE_PERIOD] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// the (a, b, c, d) indexs of working vector for each G function
+const G_IDX: [(usize, usize, usize, usize); G_FUNCS] = [
+    (0, 4, 8, 12),
+    (1, 5, 9, 13),
+    (2, 6, 10, 14),
+    (3, 7, 11, 15),
+    (0, 5, 10, 15),
+    (1, 6, 11, 12),
+    (2, 7, 8, 13),
+    (3, 4, 9, 14),
+];
+
+/// the defination for a blake2f table
+pub trait Blake2fTable {
+    /// the cols has layout [s_enable, input_rlc, output_rlc, is_final]
+    fn cols(&self) -> [Column<Any>; 4];
+
+    /// s_enable col with cell *EQUAL TO 1* mark the row is an output entry for
+    /// *ANY* unit of BLAKE2F
+    fn s_enable(&self) -> Column<Fixed> {
+        self.cols()[0]
+            .try_into()
+            .expect("must provide cols as expected layout")
+    }
+    /// input_rlc show the RLC for the 213 input bytes, the first byte is multipled
+    /// with R^212 and R is random
+    fn input_rlc(&self) -> Column<Advice> {
+        self.cols()[1]
+            .try_into()
+            .expect("must provide cols as expected layout")
+    }
+    /// output_rlc show the RLC for the 64 bytes of output state, the first byte is
+    /// multipled with R^63
+    fn output_rlc(&self) -> Column<Advice> {
+        self.cols()[2]
+            .try_into()
+            .expect("must provide cols as expected layout")
+    }
+    /// is_final col is a phase 0 col, when the cell is equal to 1 indicate all the
+    /// rounds have been done and the output is the result of compression
+    fn is_final(&self) -> Column<Advice> {
+        self.cols()[3]
+            .try_into()
+            .expect("must provide cols as expected layout")
+    }
+}
+
+/// CircuitConfig is the configure for BLAKE2F circuit
+#[derive(Clone, Debug)]
+pub struct CircuitConfig {
+    input_rlc: Column<Advice>,  // phase 2 col obtained from BLAKE2F table
+    output_rlc: Column<Advice>, // phase 2 col obtained from BLAKE2F table
+    s_final: Column<Advice>,    // indicate no rounds remain after current unit
+    s_first: Column<Advice>,    // indicate current unit is the first one of a compression
+    round_sel: [Column<Advice>; SCHEDULE_PERIOD], // one-hot index of the message schedule
+
+    bits: [Column<Advice>; 64], // bits of the word produced in the row
+    word: Column<Advice>,
+    addends: [Column<Advice>; 2],
+    carry: Column<Advice>,
+    inherit: Column<Advice>, // the cell copied from previous unit
+    inv: Column<Advice>,     // the inverse of the remaining rounds
+    msg: [Column<Advice>; MSG_WORDS],
+
+    s_output: Column<Fixed>, // indicate the row is used for output to blake2f table
+
+    s_rot: [Column<Fixed>; 5], // one-hot selector for the rotation amount
+    s_msg: [Column<Fixed>; MSG_WORDS], // one-hot selector for the message slot of a round
+
+    s_begin: Selector,     // indicate the header row of a unit
+    s_header: Selector,    // indicate the rows of header words
+    s_in_rounds: Selector, // indicate the word of rounds
+    s_in_word: Selector,   // indicate the words of h, m and t
+    s_in_flag: Selector,   // indicate the final block flag
+    s_remaining: Selector, // indicate the row of remaining rounds
+    s_word: Selector,      // indicate the bits of word is assigned
+    s_add: Selector,       // indicate the additions in G function
+    s_xor: Selector,       // indicate the xor of previous two words, with rotation
+    s_flag: Selector,      // indicate the row for v[14]
+    s_select: Selector,    // indicate the rows selecting the working vector
+    s_out: Selector,       // indicate the output words
+}
+
+type Word = (AssignedCell<Fr, Fr>, u64);
+
+#[derive(Clone, Debug)]
+struct UnitInheritments {
+    s_final: AssignedCell<Fr, Fr>,
+    round_sel: [AssignedCell<Fr, Fr>; SCHEDULE_PERIOD],
+    header: [Word; HEADER_WORDS],
+    remaining: Word,
+    state: [Word; STATE_WORDS],
+    is_final: bool,
+    round: usize,
+}
+
+/// bit i of the word goes to bit (i - s) when being rotated right by s
+fn compose_rotr(bits: &[Expression<Fr>], s: usize) -> Expression<Fr> {
+    bits.iter()
+        .enumerate()
+        .fold(Expression::Constant(Fr::zero()), |acc, (i, bit)| {
+            acc + bit.clone() * Expression::Constant(Fr::from(1u64 << ((i + 64 - s) % 64)))
+        })
+}
+
+/// the little-endian bytes composed from bits
+fn compose_bytes(bits: &[Expression<Fr>]) -> Vec<Expression<Fr>> {
+    bits.chunks_exact(8)
+        .map(|byte_bits| compose_rotr(byte_bits, 0))
+        .collect()
+}
+
+/// parse the header words from the input of precompile
+fn parse_header(input: &[u8]) -> [u64; HEADER_WORDS] {
+    assert_eq!(input.len(), INPUT_SIZE_IN_BYTES);
+    assert!(input[212] <= 1, "final block flag must be 0 or 1");
+
+    let mut header = [0u64; HEADER_WORDS];
+    header[0] = u32::from_be_bytes(input[..4].try_into().unwrap()) as u64;
+    for (w, bytes) in header[H_IDX..FLAG_IDX]
+        .iter_mut()
+        .zip(input[4..212].chunks_exact(8))
+    {
+        *w = u64::from_le_bytes(bytes.try_into().unwrap());
+    }
+    header[FLAG_IDX] = input[212] as u64;
+    header
+}
+
+impl CircuitConfig {
+    fn setup_gates(&self, meta: &mut ConstraintSystem<Fr>, rnd: Expression<Fr>) {
+        let zero = Expression::Constant(Fr::zero());
+        let one = Expression::Constant(Fr::one());
+        let two = Expression::Constant(Fr::from(2u64));
+        let two_pow_64 = Expression::Constant(Fr::from(1u64 << 32).square());
+
+        meta.create_gate("blake2f unit beginning", |meta| {
+            // a new compression begins if last unit is final
+            let is_final_prev = meta.query_advice(self.s_final, Rotation::prev());
+            let is_first = meta.query_advice(self.s_first, Rotation::cur());
+
+            let mut constraints = vec![is_first.clone() - is_final_prev];
+            // the round index restarts from 0, or moves to the next of last unit
+            for (i, col) in self.round_sel.into_iter().enumerate() {
+                let next_of_prev = meta.query_advice(
+                    self.round_sel[(i + SCHEDULE_PERIOD - 1) % SCHEDULE_PERIOD],
+                    Rotation::prev(),
+                );
+                let init = if i == 0 { one.clone() } else { zero.clone() };
+                constraints.push(
+                    meta.query_advice(col, Rotation::cur())
+                        - is_first.clone() * init
+                        - (one.clone() - is_first.clone()) * next_of_prev,
+                );
+            }
+
+            Constraints::with_selector(meta.query_selector(self.s_begin), constraints)
+        });
+
+        meta.create_gate("blake2f header", |meta| {
+            let is_first = meta.query_advice(self.s_first, Rotation::cur());
+            let word = meta.query_advice(self.word, Rotation::cur());
+            let inherit = meta.query_advice(self.inherit, Rotation::cur());
+
+            // the header must be kept in the units of the same compression
+            Constraints::with_selector(
+                meta.query_selector(self.s_header),
+                vec![(one.clone() - is_first) * (word - inherit)],
+            )
+        });
+
+        meta.create_gate("blake2f input rounds", |meta| {
+            let bits = self.bits.map(|col| meta.query_advice(col, Rotation::cur()));
+            let input_rlc = meta.query_advice(self.input_rlc, Rotation::cur());
+            // rounds is the first 4 bytes of input in big-endian
+            let rlc = compose_bytes(&bits)[..4]
+                .iter()
+                .rev()
+                .fold(zero.clone(), |acc, byte| acc * rnd.clone() + byte.clone());
+
+            Constraints::with_selector(
+                meta.query_selector(self.s_in_rounds),
+                vec![
+                    bits[32..]
+                        .iter()
+                        .fold(zero.clone(), |acc, bit| acc + bit.clone()),
+                    input_rlc - rlc,
+                ],
+            )
+        });
+
+        meta.create_gate("blake2f input words", |meta| {
+            let bits = self.bits.map(|col| meta.query_advice(col, Rotation::cur()));
+            let input_rlc = meta.query_advice(self.input_rlc, Rotation::cur());
+            let input_rlc_prev = meta.query_advice(self.input_rlc, Rotation::prev());
+            // h, m and t are put in little-endian
+            let rlc = compose_bytes(&bits)
+                .into_iter()
+                .fold(input_rlc_prev, |acc, byte| acc * rnd.clone() + byte);
+
+            Constraints::with_selector(meta.query_selector(self.s_in_word), vec![input_rlc - rlc])
+        });
+
+        meta.create_gate("blake2f input flag", |meta| {
+            let bits = self.bits.map(|col| meta.query_advice(col, Rotation::cur()));
+            let word = meta.query_advice(self.word, Rotation::cur());
+            let input_rlc = meta.query_advice(self.input_rlc, Rotation::cur());
+            let input_rlc_prev = meta.query_advice(self.input_rlc, Rotation::prev());
+
+            Constraints::with_selector(
+                meta.query_selector(self.s_in_flag),
+                vec![
+                    bits[1..]
+                        .iter()
+                        .fold(zero.clone(), |acc, bit| acc + bit.clone()),
+                    input_rlc - input_rlc_prev * rnd.clone() - word,
+                ],
+            )
+        });
+
+        meta.create_gate("blake2f remaining rounds", |meta| {
+            let is_first = meta.query_advice(self.s_first, Rotation::cur());
+            let is_final = meta.query_advice(self.s_final, Rotation::cur());
+            let word = meta.query_advice(self.word, Rotation::cur());
+            let rounds = meta.query_advice(self.addends[0], Rotation::cur());
+            let remaining_prev = meta.query_advice(self.inherit, Rotation::cur());
+            let inv = meta.query_advice(self.inv, Rotation::cur());
+
+            Constraints::with_selector(
+                meta.query_selector(self.s_remaining),
+                vec![
+                    word.clone()
+                        - is_first.clone() * rounds
+                        - (one.clone() - is_first) * (remaining_prev - one.clone()),
+                    // final iff no rounds remain
+                    is_final.clone() - one.clone() + word.clone() * inv,
+                    word * is_final,
+                ],
+            )
+        });
+
+        meta.create_gate("blake2f word bits", |meta| {
+            let bits = self.bits.map(|col| meta.query_advice(col, Rotation::cur()));
+            let word = meta.query_advice(self.word, Rotation::cur());
+
+            let mut constraints = bits
+                .iter()
+                .map(|bit| bit.clone() * (one.clone() - bit.clone()))
+                .collect::<Vec<_>>();
+            constraints.push(word - compose_rotr(&bits, 0));
+
+            Constraints::with_selector(meta.query_selector(self.s_word), constraints)
+        });
+
+        meta.create_gate("blake2f addition", |meta| {
+            let addends = self
+                .addends
+                .map(|col| meta.query_advice(col, Rotation::cur()));
+            let word = meta.query_advice(self.word, Rotation::cur());
+            let carry = meta.query_advice(self.carry, Rotation::cur());
+            let round_sel = self
+                .round_sel
+                .map(|col| meta.query_advice(col, Rotation::cur()));
+            let msg = self.msg.map(|col| meta.query_advice(col, Rotation::cur()));
+
+            // the message word is picked by the slot and the index of message schedule
+            let msg_word = self
+                .s_msg
+                .iter()
+                .enumerate()
+                .fold(zero.clone(), |acc, (slot, col)| {
+                    let picked = round_sel
+                        .iter()
+                        .zip(SIGMA)
+                        .fold(zero.clone(), |acc, (sel, sigma)| {
+                            acc + sel.clone() * msg[sigma[slot]].clone()
+                        });
+                    acc + meta.query_fixed(*col, Rotation::cur()) * picked
+                });
+
+            Constraints::with_selector(
+                meta.query_selector(self.s_add),
+                vec![
+                    addends.into_iter().fold(word, |acc, v| acc - v) - msg_word
+                        + carry.clone() * two_pow_64.clone(),
+                    (0..3).fold(one.clone(), |acc, i| {
+                        acc * (carry.clone() - Expression::Constant(Fr::from(i)))
+                    }),
+                ],
+            )
+        });
+
+        // the word is the xor of the words produced by the previous 2 rows, rotated
+        // right by the amount selected by the one-hot `s_rot` cols
+        meta.create_gate("blake2f xor", |meta| {
+            let x = self.bits.map(|col| meta.query_advice(col, Rotation(-1)));
+            let y = self.bits.map(|col| meta.query_advice(col, Rotation(-2)));
+            let word = meta.query_advice(self.word, Rotation::cur());
+
+            let xor_bits = x
+                .into_iter()
+                .zip(y)
+                .map(|(x, y)| x.clone() + y.clone() - two.clone() * x * y)
+                .collect::<Vec<_>>();
+            let rotated = self
+                .s_rot
+                .iter()
+                .zip(ROTATIONS)
+                .fold(zero.clone(), |acc, (col, s)| {
+                    acc + meta.query_fixed(*col, Rotation::cur())
+                        * compose_rotr(&xor_bits, s as usize)
+                });
+
+            Constraints::with_selector(meta.query_selector(self.s_xor), vec![word - rotated])
+        });
+
+        meta.create_gate("blake2f final block flag", |meta| {
+            let word = meta.query_advice(self.word, Rotation::cur());
+            let flag = meta.query_advice(self.addends[0], Rotation::cur());
+            let iv = Fr::from(IV[6]);
+
+            // v[14] = IV[6] ^ 0xff..ff = 0xff..ff - IV[6] for the final block
+            Constraints::with_selector(
+                meta.query_selector(self.s_flag),
+                vec![
+                    word - Expression::Constant(iv)
+                        - flag * Expression::Constant(Fr::from(u64::MAX) - iv - iv),
+                ],
+            )
+        });
+
+        meta.create_gate("blake2f state selection", |meta| {
+            let is_first = meta.query_advice(self.s_first, Rotation::cur());
+            let word = meta.query_advice(self.word, Rotation::cur());
+            let init = meta.query_advice(self.addends[0], Rotation::cur());
+            let inherit = meta.query_advice(self.inherit, Rotation::cur());
+
+            // start from the initial working vector, or continue from last unit
+            Constraints::with_selector(
+                meta.query_selector(self.s_select),
+                vec![word - inherit.clone() - is_first * (init - inherit)],
+            )
+        });
+
+        meta.create_gate("blake2f output", |meta| {
+            let bits = self.bits.map(|col| meta.query_advice(col, Rotation::cur()));
+            let output_rlc = meta.query_advice(self.output_rlc, Rotation::cur());
+            let output_rlc_prev = meta.query_advice(self.output_rlc, Rotation(-5));
+
+            // the output words are put in little-endian
+            let rlc = compose_bytes(&bits)
+                .into_iter()
+                .fold(output_rlc_prev, |acc, byte| acc * rnd.clone() + byte);
+
+            Constraints::with_selector(meta.query_selector(self.s_out), vec![output_rlc - rlc])
+        });
+    }
+
+    /// Configures a circuit to include this chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<Fr>,
+        blake2f_table: impl Blake2fTable,
+        spec_challenge: Expression<Fr>,
+    ) -> Self {
+        let input_rlc = blake2f_table.input_rlc();
+        let output_rlc = blake2f_table.output_rlc();
+        let s_output = blake2f_table.s_enable();
+        let s_final = blake2f_table.is_final();
+
+        let s_first = meta.advice_column();
+        let round_sel = [(); SCHEDULE_PERIOD].map(|_| meta.advice_column());
+        let bits = [(); 64].map(|_| meta.advice_column());
+        let word = meta.advice_column();
+        let addends = [(); 2].map(|_| meta.advice_column());
+        let carry = meta.advice_column();
+        let inherit = meta.advice_column();
+        let inv = meta.advice_column();
+        let msg = [(); MSG_WORDS].map(|_| meta.advice_column());
+
+        let s_rot = [(); 5].map(|_| meta.fixed_column());
+        let s_msg = [(); MSG_WORDS].map(|_| meta.fixed_column());
+
+        for col in [input_rlc, output_rlc, s_final, s_first, word, inherit]
+            .into_iter()
+            .chain(round_sel)
+            .chain(addends)
+            .chain(msg)
+        {
+            meta.enable_equality(col);
+        }
+
+        let ret = Self {
+            input_rlc,
+            output_rlc,
+            s_final,
+            s_first,
+            round_sel,
+
+            bits,
+            word,
+            addends,
+            carry,
+            inherit,
+            inv,
+            msg,
+
+            s_output,
+
+            s_rot,
+            s_msg,
+
+            s_begin: meta.selector(),
+            s_header: meta.selector(),
+            s_in_rounds: meta.selector(),
+            s_in_word: meta.selector(),
+            s_in_flag: meta.selector(),
+            s_remaining: meta.selector(),
+            s_word: meta.selector(),
+            s_add: meta.selector(),
+            s_xor: meta.selector(),
+            s_flag: meta.selector(),
+            s_select: meta.selector(),
+            s_out: meta.selector(),
+        };
+
+        ret.setup_gates(meta, spec_challenge);
+
+        ret
+    }
+
+    /// assign a word with its bits
+    fn assign_word(
+        &self,
+        region: &mut Region<'_, Fr>,
+        offset: usize,
+        value: u64,
+    ) -> Result<Word, Error> {
+        self.s_word.enable(region, offset)?;
+        for (i, col) in self.bits.into_iter().enumerate() {
+            region.assign_advice(
+                || "word bit",
+                col,
+                offset,
+                || Value::known(Fr::from((value >> i) & 1)),
+            )?;
+        }
+        let word = region.assign_advice(
+            || "word",
+            self.word,
+            offset,
+            || Value::known(Fr::from(value)),
+        )?;
+
+        Ok((word, value))
+    }
+
+    /// assign a word which is equal to another cell
+    fn assign_load(
+        &self,
+        region: &mut Region<'_, Fr>,
+        offset: usize,
+        src: &Word,
+    ) -> Result<Word, Error> {
+        let word = self.assign_word(region, offset, src.1)?;
+        region.constrain_equal(word.0.cell(), src.0.cell())?;
+        Ok(word)
+    }
+
+    /// assign the xor of the words in the previous 2 rows, rotated right by `rot`
+    fn assign_xor(
+        &self,
+        region: &mut Region<'_, Fr>,
+        offset: usize,
+        x: u64,
+        y: u64,
+        rot: u32,
+    ) -> Result<Word, Error> {
+        let rot_idx = ROTATIONS
+            .iter()
+            .position(|&s| s == rot)
+            .expect("rotation should be used by blake2f");
+
+        self.s_xor.enable(region, offset)?;
+        region.assign_fixed(
+            || "rotation",
+            self.s_rot[rot_idx],
+            offset,
+            || Value::known(Fr::one()),
+        )?;
+        self.assign_word(region, offset, (x ^ y).rotate_right(rot))
+    }
+
+    /// assign x + y + msg (mod 2^64), the message word is picked by `s_msg`
+    fn assign_add(
+        &self,
+        region: &mut Region<'_, Fr>,
+        offset: usize,
+        x: &Word,
+        y: &Word,
+        msg: u64,
+    ) -> Result<Word, Error> {
+        let sum = x.1 as u128 + y.1 as u128 + msg as u128;
+
+        self.s_add.enable(region, offset)?;
+        x.0.copy_advice(|| "addend", region, self.addends[0], offset)?;
+        y.0.copy_advice(|| "addend", region, self.addends[1], offset)?;
+        region.assign_advice(
+            || "carry",
+            self.carry,
+            offset,
+            || Value::known(Fr::from((sum >> 64) as u64)),
+        )?;
+        self.assign_word(region, offset, sum as u64)
+    }
+
+    /// assign the cells for picking the message word in slot
+    fn assign_message(
+        &self,
+        region: &mut Region<'_, Fr>,
+        offset: usize,
+        slot: usize,
+        round_sel: &[AssignedCell<Fr, Fr>; SCHEDULE_PERIOD],
+        msg: &[Word],
+    ) -> Result<(), Error> {
+        region.assign_fixed(
+            || "message slot",
+            self.s_msg[slot],
+            offset,
+            || Value::known(Fr::one()),
+        )?;
+        for (cell, col) in round_sel.iter().zip(self.round_sel) {
+            cell.copy_advice(|| "round selector", region, col, offset)?;
+        }
+        for ((cell, _), col) in msg.iter().zip(self.msg) {
+            cell.copy_advice(|| "message word", region, col, offset)?;
+        }
+        Ok(())
+    }
+
+    /// assign the 8 G functions of a round, return the working vector after it
+    fn assign_round(
+        &self,
+        region: &mut Region<'_, Fr>,
+        round: usize,
+        round_sel: &[AssignedCell<Fr, Fr>; SCHEDULE_PERIOD],
+        msg: &[Word],
+        state: &[Word; STATE_WORDS],
+    ) -> Result<[Word; STATE_WORDS], Error> {
+        let sigma = &SIGMA[round % SCHEDULE_PERIOD];
+        let mut v = state.clone();
+        let mut row = ROUND_OFFSET;
+
+        for (i, &(a, b, c, d)) in G_IDX.iter().enumerate() {
+            for (slot, (rot_d, rot_b)) in [(2 * i, (32, 24)), (2 * i + 1, (16, 63))] {
+                // a = a + b + m; d = (d ^ a) >>> rot_d
+                self.assign_message(region, row, slot, round_sel, msg)?;
+                v[a] = self.assign_add(region, row, &v[a], &v[b], msg[sigma[slot]].1)?;
+                let d_prev = self.assign_load(region, row + 1, &v[d])?;
+                v[d] = self.assign_xor(region, row + 2, d_prev.1, v[a].1, rot_d)?;
+                // c = c + d; b = (b ^ c) >>> rot_b
+                v[c] = self.assign_add(region, row + 3, &v[c], &v[d], 0)?;
+                let b_prev = self.assign_load(region, row + 4, &v[b])?;
+                v[b] = self.assign_xor(region, row + 5, b_prev.1, v[c].1, rot_b)?;
+                row += 6;
+            }
+        }
+        assert_eq!(row, UNIT_ROWS);
+
+        Ok(v)
+    }
+
+    fn initialize_unit_head(
+        &self,
+        layouter: &mut impl Layouter<Fr>,
+    ) -> Result<UnitInheritments, Error> {
+        layouter.assign_region(
+            || "initialize compressor",
+            |mut region| {
+                // start as if a compression has just been finalized
+                let s_final = region.assign_advice_from_constant(
+                    || "init s_final",
+                    self.s_final,
+                    0,
+                    Fr::one(),
+                )?;
+                let zero =
+                    region.assign_advice_from_constant(|| "init zero", self.word, 0, Fr::zero())?;
+
+                Ok(UnitInheritments {
+                    s_final,
+                    round_sel: [(); SCHEDULE_PERIOD].map(|_| zero.clone()),
+                    header: [(); HEADER_WORDS].map(|_| (zero.clone(), 0)),
+                    remaining: (zero.clone(), 0),
+                    state: [(); STATE_WORDS].map(|_| (zero.clone(), 0)),
+                    is_final: true,
+                    round: 0,
+                })
+            },
+        )
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn assign_unit(
+        &self,
+        layouter: &mut impl Layouter<Fr>,
+        chng: Value<Fr>,
+        prev_unit: &UnitInheritments,
+        header: &[u64; HEADER_WORDS],
+    ) -> Result<(UnitInheritments, [AssignedCell<Fr, Fr>; OUTPUT_WORDS]), Error> {
+        let is_first = prev_unit.is_final;
+        let round = if is_first { 0 } else { prev_unit.round + 1 };
+        let remaining = if is_first {
+            header[0]
+        } else {
+            prev_unit.remaining.1 - 1
+        };
+        let is_final = remaining == 0;
+
+        layouter.assign_region(
+            || "blake2f unit",
+            |mut region| {
+                prev_unit.s_final.copy_advice(
+                    || "inherit s_final",
+                    &mut region,
+                    self.s_final,
+                    0,
+                )?;
+                for (cell, col) in prev_unit.round_sel.iter().zip(self.round_sel) {
+                    cell.copy_advice(|| "inherit round selector", &mut region, col, 0)?;
+                }
+
+                self.s_begin.enable(&mut region, 1)?;
+                let s_first_cell = region.assign_advice(
+                    || "header first",
+                    self.s_first,
+                    1,
+                    || Value::known(Fr::from(is_first as u64)),
+                )?;
+                let round_sel = self
+                    .round_sel
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, col)| {
+                        region.assign_advice(
+                            || "header round selector",
+                            col,
+                            1,
+                            || Value::known(Fr::from((round % SCHEDULE_PERIOD == i) as u64)),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                let round_sel: [_; SCHEDULE_PERIOD] = round_sel.try_into().unwrap();
+
+                let mut input_rlc = Value::known(Fr::zero());
+                let mut input_rlc_cell = None;
+                let mut header_cells = Vec::with_capacity(HEADER_WORDS);
+                for (i, &w) in header.iter().enumerate() {
+                    let row = HEADER_OFFSET + i;
+                    let bytes = w.to_le_bytes();
+
+                    self.s_header.enable(&mut region, row)?;
+                    prev_unit.header[i].0.copy_advice(
+                        || "inherit header",
+                        &mut region,
+                        self.inherit,
+                        row,
+                    )?;
+                    s_first_cell.copy_advice(|| "first", &mut region, self.s_first, row)?;
+                    input_rlc = match i {
+                        0 => {
+                            self.s_in_rounds.enable(&mut region, row)?;
+                            bytes[..4]
+                                .iter()
+                                .rev()
+                                .fold(Value::known(Fr::zero()), |acc, byte| {
+                                    acc * chng + Value::known(Fr::from(*byte as u64))
+                                })
+                        }
+                        FLAG_IDX => {
+                            self.s_in_flag.enable(&mut region, row)?;
+                            input_rlc * chng + Value::known(Fr::from(w))
+                        }
+                        _ => {
+                            self.s_in_word.enable(&mut region, row)?;
+                            bytes.iter().fold(input_rlc, |acc, byte| {
+                                acc * chng + Value::known(Fr::from(*byte as u64))
+                            })
+                        }
+                    };
+                    input_rlc_cell = Some(region.assign_advice(
+                        || "input rlc",
+                        self.input_rlc,
+                        row,
+                        || input_rlc,
+                    )?);
+                    header_cells.push(self.assign_word(&mut region, row, w)?);
+                }
+                let input_rlc_cell = input_rlc_cell.expect("header is not empty");
+
+                self.s_remaining.enable(&mut region, REMAINING_ROW)?;
+                header_cells[0].0.copy_advice(
+                    || "rounds",
+                    &mut region,
+                    self.addends[0],
+                    REMAINING_ROW,
+                )?;
+                prev_unit.remaining.0.copy_advice(
+                    || "inherit remaining rounds",
+                    &mut region,
+                    self.inherit,
+                    REMAINING_ROW,
+                )?;
+                s_first_cell.copy_advice(|| "first", &mut region, self.s_first, REMAINING_ROW)?;
+                let s_final_cell = region.assign_advice(
+                    || "final",
+                    self.s_final,
+                    REMAINING_ROW,
+                    || Value::known(Fr::from(is_final as u64)),
+                )?;
+                region.assign_advice(
+                    || "inverse of remaining rounds",
+                    self.inv,
+                    REMAINING_ROW,
+                    || Value::known(Fr::from(remaining).invert().unwrap_or(Fr::zero())),
+                )?;
+                let remaining_cell = self.assign_word(&mut region, REMAINING_ROW, remaining)?;
+
+                // v[12] = t0 ^ IV[4], v[13] = t1 ^ IV[5]
+                let mut fresh = Vec::with_capacity(3);
+                for (i, t) in header_cells[T_IDX..FLAG_IDX].iter().enumerate() {
+                    let row = FRESH_OFFSET + 3 * i;
+                    self.assign_load(&mut region, row, t)?;
+                    let (iv_cell, iv) = self.assign_word(&mut region, row + 1, IV[4 + i])?;
+                    region.constrain_constant(iv_cell.cell(), Fr::from(iv))?;
+                    fresh.push(self.assign_xor(&mut region, row + 2, iv, t.1, 0)?);
+                }
+                // v[14] = IV[6] ^ 0xff..ff for the final block
+                let flag_row = FRESH_OFFSET + 6;
+                self.s_flag.enable(&mut region, flag_row)?;
+                header_cells[FLAG_IDX].0.copy_advice(
+                    || "final block flag",
+                    &mut region,
+                    self.addends[0],
+                    flag_row,
+                )?;
+                let v14 = if header[FLAG_IDX] == 0 { IV[6] } else { !IV[6] };
+                fresh.push(self.assign_word(&mut region, flag_row, v14)?);
+
+                let mut state = Vec::with_capacity(STATE_WORDS);
+                for (i, (inherit_cell, inherit)) in prev_unit.state.iter().enumerate() {
+                    let row = SELECT_OFFSET + i;
+                    let init = match i {
+                        0..=7 => {
+                            let (cell, v) = &header_cells[H_IDX + i];
+                            cell.copy_advice(|| "init state", &mut region, self.addends[0], row)?;
+                            *v
+                        }
+                        12..=14 => {
+                            let (cell, v) = &fresh[i - 12];
+                            cell.copy_advice(|| "init state", &mut region, self.addends[0], row)?;
+                            *v
+                        }
+                        _ => {
+                            region.assign_advice_from_constant(
+                                || "init state",
+                                self.addends[0],
+                                row,
+                                Fr::from(IV[i - 8]),
+                            )?;
+                            IV[i - 8]
+                        }
+                    };
+
+                    self.s_select.enable(&mut region, row)?;
+                    inherit_cell.copy_advice(|| "inherit state", &mut region, self.inherit, row)?;
+                    s_first_cell.copy_advice(|| "first", &mut region, self.s_first, row)?;
+                    let v = if is_first { init } else { *inherit };
+                    state.push(self.assign_word(&mut region, row, v)?);
+                }
+                let state: [_; STATE_WORDS] = state.try_into().unwrap();
+
+                // output h[i] ^ v[i] ^ v[i + 8]
+                region.assign_advice_from_constant(
+                    || "init output rlc",
+                    self.output_rlc,
+                    OUTPUT_OFFSET - 1,
+                    Fr::zero(),
+                )?;
+                let mut output_rlc = Value::known(Fr::zero());
+                let mut output_cells = Vec::with_capacity(OUTPUT_WORDS);
+                for (i, (v_lo, v_hi)) in state[..8].iter().zip(&state[8..]).enumerate() {
+                    let row = OUTPUT_OFFSET + 5 * i;
+                    let lo = self.assign_load(&mut region, row, v_lo)?;
+                    let hi = self.assign_load(&mut region, row + 1, v_hi)?;
+                    let mixed = self.assign_xor(&mut region, row + 2, lo.1, hi.1, 0)?;
+                    let h = self.assign_load(&mut region, row + 3, &header_cells[H_IDX + i])?;
+                    let (out_cell, out) = self.assign_xor(&mut region, row + 4, mixed.1, h.1, 0)?;
+
+                    self.s_out.enable(&mut region, row + 4)?;
+                    output_rlc = out.to_le_bytes().iter().fold(output_rlc, |acc, byte| {
+                        acc * chng + Value::known(Fr::from(*byte as u64))
+                    });
+                    region.assign_advice(
+                        || "output rlc",
+                        self.output_rlc,
+                        row + 4,
+                        || output_rlc,
+                    )?;
+                    output_cells.push(out_cell);
+                }
+
+                // build output row
+                let output_row = ROUND_OFFSET - 1;
+                region.assign_fixed(
+                    || "mark s_output",
+                    self.s_output,
+                    output_row,
+                    || Value::known(Fr::one()),
+                )?;
+                s_final_cell.copy_advice(|| "copy final", &mut region, self.s_final, output_row)?;
+                input_rlc_cell.copy_advice(
+                    || "copy input rlc",
+                    &mut region,
+                    self.input_rlc,
+                    output_row,
+                )?;
+
+                let state = self.assign_round(
+                    &mut region,
+                    round,
+                    &round_sel,
+                    &header_cells[M_IDX..T_IDX],
+                    &state,
+                )?;
+
+                Ok((
+                    UnitInheritments {
+                        s_final: s_final_cell,
+                        round_sel: round_sel.clone(),
+                        header: header_cells.clone().try_into().unwrap(),
+                        remaining: remaining_cell,
+                        state,
+                        is_final,
+                        round,
+                    },
+                    output_cells.try_into().unwrap(),
+                ))
+            },
+        )
+    }
+}
+
+/// blake2f compressor for the inputs of precompile
+#[derive(Debug)]
+pub struct Compressor {
+    chip: CircuitConfig,
+    unit_state: UnitInheritments,
+    unit_usage: usize,
+}
+
+impl Compressor {
+    /// return the number of units which has been assigned
+    pub fn units(&self) -> usize {
+        self.unit_usage
+    }
+
+    /// create a compressor, the circuit would be identify when unit_usage is the same
+    pub fn new(chip: CircuitConfig, layouter: &mut impl Layouter<Fr>) -> Result<Self, Error> {
+        let unit_state = chip.initialize_unit_head(layouter)?;
+        Ok(Self {
+            chip,
+            unit_state,
+            unit_usage: 0,
+        })
+    }
+
+    /// compress the 213 bytes input of precompile, which takes rounds + 1 units, and
+    /// return the cells of output words
+    pub fn compress(
+        &mut self,
+        layouter: &mut impl Layouter<Fr>,
+        chng: Value<Fr>,
+        input: &[u8],
+    ) -> Result<[AssignedCell<Fr, Fr>; OUTPUT_WORDS], Error> {
+        let header = parse_header(input);
+
+        loop {
+            let (unit_state, output) =
+                self.chip
+                    .assign_unit(layouter, chng, &self.unit_state, &header)?;
+            self.unit_state = unit_state;
+            self.unit_usage += 1;
+            if self.unit_state.is_final {
+                return Ok(output);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, plonk::Circuit};
+
+    struct MyCircuit(Vec<(Vec<u8>, Option<[u64; OUTPUT_WORDS]>)>);
+
+    impl Circuit<Fr> for MyCircuit {
+        type Config = CircuitConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            unimplemented!()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            struct DevTable {
+                s_enable: Column<Fixed>,
+                input_rlc: Column<Advice>,
+                output_rlc: Column<Advice>,
+                is_final: Column<Advice>,
+            }
+
+            impl Blake2fTable for DevTable {
+                fn cols(&self) -> [Column<Any>; 4] {
+                    [
+                        self.s_enable.into(),
+                        self.input_rlc.into(),
+                        self.output_rlc.into(),
+                        self.is_final.into(),
+                    ]
+                }
+            }
+
+            let dev_table = DevTable {
+                s_enable: meta.fixed_column(),
+                input_rlc: meta.advice_column(),
+                output_rlc: meta.advice_column(),
+                is_final: meta.advice_column(),
+            };
+            meta.enable_constant(dev_table.s_enable);
+
+            let chng = Expression::Constant(Fr::from(0x1000u64));
+            Self::Config::configure(meta, dev_table, chng)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let chng_v = Value::known(Fr::from(0x1000u64));
+            let mut compressor = Compressor::new(config, &mut layouter)?;
+
+            for (input, output) in &self.0 {
+                let ret_output = compressor.compress(&mut layouter, chng_v, input)?;
+                if let Some(check_output) = output {
+                    for (w, check) in ret_output.into_iter().zip(*check_output) {
+                        w.value()
+                            .assert_if_known(|output_word| **output_word == Fr::from(check));
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// the input of EIP-152 test vectors, with the state of blake2b for "abc"
+    fn input(rounds: u32, is_final: bool) -> Vec<u8> {
+        let mut h = IV;
+        h[0] ^= 0x01010040;
+        let mut ret = rounds.to_be_bytes().to_vec();
+        ret.extend(h.iter().flat_map(|w| w.to_le_bytes()));
+        ret.extend(b"abc");
+        ret.resize(4 + 64 + 128, 0);
+        ret.extend(3u64.to_le_bytes());
+        ret.extend(0u64.to_le_bytes());
+        ret.push(is_final as u8);
+        ret
+    }
+
+    // output words are little-endian
+    const OUTPUT_12: [u64; OUTPUT_WORDS] = [
+        0x0d4d1c983fa580ba,
+        0xe9f6129fb697276a,
+        0xb7c45a68142f214c,
+        0xd1a2ffdb6fbb124b,
+        0x2d79ab2a39c5877d,
+        0x95cc3345ded552c2,
+        0x5a92f1dba88ad318,
+        0x239900d4ed8623b9,
+    ];
+
+    const OUTPUT_0: [u64; OUTPUT_WORDS] = [
+        0x6a09e667f3bcc908,
+        0xbb67ae8584caa73b,
+        0x3c6ef372fe94f82b,
+        0xa54ff53a5f1d36f1,
+        0x510e527fade682d2,
+        0x9b05688c2b3e6c1f,
+        0xe07c265404be4294,
+        0x5be0cd19137e2179,
+    ];
+
+    const OUTPUT_12_NOT_FINAL: [u64; OUTPUT_WORDS] = [
+        0x2c560a19d369ab75,
+        0x75271c8fd8f8ae51,
+        0x2cc4707240446987,
+        0x5287d2262c254498,
+        0xf2a25e6d7f3e7498,
+        0x1bd39c0326d2e8d3,
+        0x66d6d3f2c46a424e,
+        0x3547de6f11c210a6,
+    ];
+
+    const OUTPUT_1: [u64; OUTPUT_WORDS] = [
+        0x527d89b20c383ab6,
+        0x182cee3452a89419,
+        0x004c622c4d845f1b,
+        0xfbd2493470e97726,
+        0xf5cd3b33a8b351a5,
+        0x2339d59389e0f7f2,
+        0x4e038cc6fc643dde,
+        0x21a4d7fe93927b71,
+    ];
+
+    const OUTPUT_23: [u64; OUTPUT_WORDS] = [
+        0xec293b08b2797f30,
+        0xf557146ec36b9266,
+        0xac2f57c07c0bf971,
+        0x4f705da39c22d9a1,
+        0x904860f965eeef73,
+        0x64b3c9d9678f1617,
+        0x7d0ab7c657de5237,
+        0x50512f2882dabd6e,
+    ];
+
+    fn run(circuit: &MyCircuit) {
+        let prover = match MockProver::<Fr>::run(14, circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{e:#?}"),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn blake2f_simple() {
+        run(&MyCircuit(vec![(input(12, true), Some(OUTPUT_12))]));
+    }
+
+    #[test]
+    fn blake2f_zero_rounds() {
+        run(&MyCircuit(vec![
+            (input(0, true), Some(OUTPUT_0)),
+            (input(0, true), Some(OUTPUT_0)),
+        ]));
+    }
+
+    #[test]
+    fn blake2f_multiple() {
+        run(&MyCircuit(vec![
+            (input(12, true), Some(OUTPUT_12)),
+            (input(12, false), Some(OUTPUT_12_NOT_FINAL)),
+            (input(1, true), Some(OUTPUT_1)),
+            (input(0, true), Some(OUTPUT_0)),
+        ]));
+    }
+
+    #[test]
+    fn blake2f_schedule_wrapping() {
+        run(&MyCircuit(vec![(input(23, true), Some(OUTPUT_23))]));
+    }
+}
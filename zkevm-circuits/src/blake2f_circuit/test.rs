@@ -0,0 +1,136 @@
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, Circuit, ConstraintSystem, Error},
+    transcript::{Blake2bRead, Blake2bWrite, Challenge255},
+};
+use rand::rngs::OsRng;
+
+use super::circuit::*;
+
+use crate::util::Challenges;
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr},
+    plonk::{Advice, Any, Column, Fixed, SecondPhase},
+    poly::{
+        commitment::ParamsProver,
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::{ProverSHPLONK, VerifierSHPLONK},
+            strategy::SingleStrategy,
+        },
+    },
+    transcript::{TranscriptReadBuffer, TranscriptWriterBuffer},
+};
+
+const CAP_UNIT: usize = 32;
+
+#[derive(Default, Clone, Copy)]
+struct MyCircuit {
+    compressions: usize,
+}
+
+impl Circuit<Fr> for MyCircuit {
+    type Config = (CircuitConfig, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        unimplemented!()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        struct DevTable {
+            s_enable: Column<Fixed>,
+            input_rlc: Column<Advice>,
+            output_rlc: Column<Advice>,
+            is_final: Column<Advice>,
+        }
+
+        impl Blake2fTable for DevTable {
+            fn cols(&self) -> [Column<Any>; 4] {
+                [
+                    self.s_enable.into(),
+                    self.input_rlc.into(),
+                    self.output_rlc.into(),
+                    self.is_final.into(),
+                ]
+            }
+        }
+
+        let dev_table = DevTable {
+            s_enable: meta.fixed_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            output_rlc: meta.advice_column_in(SecondPhase),
+            is_final: meta.advice_column(),
+        };
+        meta.enable_constant(dev_table.s_enable);
+
+        let challenges = Challenges::construct(meta);
+        let chng = challenges.exprs(meta).keccak_input();
+        (CircuitConfig::configure(meta, dev_table, chng), challenges)
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges): Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&layouter);
+        let chng_v = challenges.keccak_input();
+        let mut compressor = Compressor::new(config, &mut layouter)?;
+
+        let mut input = [0u8; INPUT_SIZE_IN_BYTES];
+        // 2 rounds for each compression
+        input[3] = 2;
+        input[4..].fill(b'a');
+        input[INPUT_SIZE_IN_BYTES - 1] = 1;
+        for _ in 0..self.compressions {
+            compressor.compress(&mut layouter, chng_v, &input)?;
+        }
+
+        let padding_input = [0u8; INPUT_SIZE_IN_BYTES];
+        for _ in compressor.units()..CAP_UNIT {
+            compressor.compress(&mut layouter, chng_v, &padding_input)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn vk_stable() {
+    let k = 14;
+
+    let params: ParamsKZG<Bn256> = ParamsKZG::new(k);
+    let empty_circuit: MyCircuit = MyCircuit { compressions: 0 };
+
+    // Initialize the proving key
+    let vk_from_empty = keygen_vk(&params, &empty_circuit).expect("keygen_vk should not fail");
+
+    let circuit = MyCircuit { compressions: 8 };
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk should not fail");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk should not fail");
+
+    // Create a proof
+    let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+    create_proof::<KZGCommitmentScheme<Bn256>, ProverSHPLONK<_>, _, _, _, _>(
+        &params,
+        &pk,
+        &[circuit],
+        &[&[]],
+        OsRng,
+        &mut transcript,
+    )
+    .expect("proof generation should not fail");
+    let proof: Vec<u8> = transcript.finalize();
+
+    let strategy = SingleStrategy::new(&params);
+    let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+    verify_proof::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<_>, _, _, _>(
+        &params,
+        &vk_from_empty,
+        strategy,
+        &[&[]],
+        &mut transcript,
+    )
+    .unwrap();
+}
@@ -1014,6 +1014,14 @@ impl<F: Field> BytecodeCircuit<F> {
             .collect();
         Self::new(bytecodes, bytecode_size)
     }
+
+    /// Minimum and total number of rows of the circuit for bytecodes of `bytecode_lens` bytes.
+    pub(crate) fn min_num_rows(
+        bytecode_lens: impl Iterator<Item = usize>,
+        max_bytecode: usize,
+    ) -> (usize, usize) {
+        (bytecode_lens.map(|len| len + 1).sum(), max_bytecode)
+    }
 }
 
 impl<F: Field> SubCircuit<F> for BytecodeCircuit<F> {
@@ -1038,12 +1046,11 @@ impl<F: Field> SubCircuit<F> for BytecodeCircuit<F> {
 
     /// Return the minimum number of rows required to prove the block
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
-        (
+        Self::min_num_rows(
             block
                 .bytecodes
                 .values()
-                .map(|bytecode| bytecode.bytes.len() + 1)
-                .sum(),
+                .map(|bytecode| bytecode.bytes.len()),
             block.circuits_params.max_bytecode,
         )
     }
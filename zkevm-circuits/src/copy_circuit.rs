@@ -115,6 +115,13 @@ pub struct CopyCircuitConfig<F> {
     pub is_word_end: IsEqualConfig<F>,
     /// non pad and non mask witness to reduce the degree of lookups.
     pub non_pad_non_mask: Column<Advice>,
+    /// Whether the reader and the writer of a step have the same id.
+    pub is_same_id: IsEqualConfig<F>,
+    /// Whether the event copies within the memory of a single call (MCOPY).
+    pub is_memory_copy: Column<Advice>,
+    /// The distance between the RW counters of the reads and the writes of a copy within the
+    /// memory of a call, where all the reads come first. Zero for other copies.
+    pub rwc_gap: Column<Advice>,
     // External tables
     /// TxTable
     pub tx_table: TxTable,
@@ -202,7 +209,16 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             |_meta| 31.expr(),
         );
 
+        let is_same_id = IsEqualChip::configure(
+            meta,
+            |meta| meta.query_selector(q_step),
+            |meta| meta.query_advice(id, CURRENT),
+            |meta| meta.query_advice(id, NEXT_ROW),
+        );
+
         let non_pad_non_mask = meta.advice_column();
+        let is_memory_copy = meta.advice_column();
+        let rwc_gap = meta.advice_column();
 
         constrain_tag(
             meta,
@@ -336,22 +352,31 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             {
                 let is_rw_word_type = meta.query_advice(is_memory, CURRENT) + is_tx_log.expr();
                 let is_rw_type = is_rw_word_type.expr() + is_access_list.expr();
+                // Only a copy within the memory of one call reads all words before writing.
+                let is_memory_to_memory = meta.query_advice(is_memory, CURRENT)
+                    * meta.query_advice(is_memory, NEXT_ROW)
+                    * is_same_id.expr();
 
                 // No word align for access list address and storage key.
                 let is_row_end = select::expr(
                     is_access_list.expr(),
-                    not::expr(is_reader),
+                    not::expr(is_reader.expr()),
                     is_word_end.expr(),
                 );
 
                 constrain_rw_counter(
                     cb,
                     meta,
+                    is_reader.expr(),
+                    is_first.expr(),
                     is_last.expr(),
+                    is_memory_to_memory,
                     is_rw_type.expr(),
                     is_row_end.expr(),
                     rw_counter,
                     rwc_inc_left,
+                    is_memory_copy,
+                    rwc_gap,
                 );
 
                 constrain_rw_word_complete(cb, is_last_step, is_rw_word_type.expr(), is_word_end);
@@ -572,6 +597,9 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             is_src_end,
             is_word_end,
             non_pad_non_mask,
+            is_same_id,
+            is_memory_copy,
+            rwc_gap,
             copy_table,
             tx_table,
             rw_table,
@@ -590,14 +618,12 @@ impl<F: Field> CopyCircuitConfig<F> {
         tag_chip: &BinaryNumberChip<F, CopyDataType, { CopyDataType::N_BITS }>,
         is_src_end_chip: &IsEqualChip<F>,
         lt_word_end_chip: &IsEqualChip<F>,
+        is_same_id_chip: &IsEqualChip<F>,
         challenges: Challenges<Value<F>>,
         copy_event: &CopyEvent,
     ) -> Result<(), Error> {
-        for (step_idx, (tag, table_row, circuit_row)) in
-            CopyTable::assignments(copy_event, challenges)
-                .iter()
-                .enumerate()
-        {
+        let assignments = CopyTable::assignments(copy_event, challenges);
+        for (step_idx, (tag, table_row, circuit_row)) in assignments.iter().enumerate() {
             let is_read = step_idx % 2 == 0;
 
             // Copy table assignments
@@ -638,6 +664,7 @@ impl<F: Field> CopyCircuitConfig<F> {
                 self.mask,
                 self.front_mask,
                 self.word_index,
+                self.rwc_gap,
             ]
             .iter()
             .zip_eq(circuit_row)
@@ -662,6 +689,13 @@ impl<F: Field> CopyCircuitConfig<F> {
                     addr,
                     Value::known(F::from(copy_event.src_addr_end)),
                 )?;
+                // The id of the writer at the next row.
+                is_same_id_chip.assign(
+                    region,
+                    *offset,
+                    table_row[1].0,
+                    assignments[step_idx + 1].1[1].0,
+                )?;
             }
 
             lt_word_end_chip.assign(
@@ -680,6 +714,12 @@ impl<F: Field> CopyCircuitConfig<F> {
                 *offset,
                 || Value::known(F::from(non_pad_non_mask)),
             )?;
+            region.assign_advice(
+                || format!("is_memory_copy at row: {offset}"),
+                self.is_memory_copy,
+                *offset,
+                || Value::known(F::from(copy_event.is_memory_copy())),
+            )?;
 
             region.assign_advice(
                 || format!("is_tx_calldata at row: {}", *offset),
@@ -751,6 +791,7 @@ impl<F: Field> CopyCircuitConfig<F> {
         let tag_chip = BinaryNumberChip::construct(self.copy_table.tag);
         let is_src_end_chip = IsEqualChip::construct(self.is_src_end.clone());
         let lt_word_end_chip = IsEqualChip::construct(self.is_word_end.clone());
+        let is_same_id_chip = IsEqualChip::construct(self.is_same_id.clone());
 
         layouter.assign_region(
             || "assign copy table",
@@ -765,6 +806,8 @@ impl<F: Field> CopyCircuitConfig<F> {
                 region.name_column(|| "front_mask", self.front_mask);
                 region.name_column(|| "is_pad", self.is_pad);
                 region.name_column(|| "non_pad_non_mask", self.non_pad_non_mask);
+                region.name_column(|| "is_memory_copy", self.is_memory_copy);
+                region.name_column(|| "rwc_gap", self.rwc_gap);
 
                 let mut offset = 0;
                 for (ev_idx, copy_event) in copy_events.iter().enumerate() {
@@ -786,6 +829,7 @@ impl<F: Field> CopyCircuitConfig<F> {
                         &tag_chip,
                         &is_src_end_chip,
                         &lt_word_end_chip,
+                        &is_same_id_chip,
                         challenges,
                         copy_event,
                     )?;
@@ -800,6 +844,7 @@ impl<F: Field> CopyCircuitConfig<F> {
                         &tag_chip,
                         &is_src_end_chip,
                         &lt_word_end_chip,
+                        &is_same_id_chip,
                     )?;
                 }
                 assert_eq!(offset % 2, 0, "enabled rows must come in pairs");
@@ -812,6 +857,7 @@ impl<F: Field> CopyCircuitConfig<F> {
                         &tag_chip,
                         &is_src_end_chip,
                         &lt_word_end_chip,
+                        &is_same_id_chip,
                     )?;
                 }
 
@@ -829,6 +875,7 @@ impl<F: Field> CopyCircuitConfig<F> {
         tag_chip: &BinaryNumberChip<F, CopyDataType, { CopyDataType::N_BITS }>,
         is_src_end_chip: &IsEqualChip<F>,
         lt_word_end_chip: &IsEqualChip<F>,
+        is_same_id_chip: &IsEqualChip<F>,
     ) -> Result<(), Error> {
         // q_enable
         region.assign_fixed(
@@ -970,6 +1017,13 @@ impl<F: Field> CopyCircuitConfig<F> {
             *offset,
             || Value::known(F::zero()),
         )?;
+        // rwc_gap
+        region.assign_advice(
+            || format!("assign rwc_gap {}", *offset),
+            self.rwc_gap,
+            *offset,
+            || Value::known(F::zero()),
+        )?;
         // tag
         tag_chip.assign(region, *offset, &CopyDataType::Padding)?;
         // Assign IsEqual gadgets
@@ -985,12 +1039,24 @@ impl<F: Field> CopyCircuitConfig<F> {
             Value::known(F::zero()),
             Value::known(F::from(31u64)),
         )?;
+        is_same_id_chip.assign(
+            region,
+            *offset,
+            Value::known(F::zero()),
+            Value::known(F::zero()),
+        )?;
         region.assign_advice(
             || format!("non_pad_non_mask at row: {offset}"),
             self.non_pad_non_mask,
             *offset,
             || Value::known(F::zero()),
         )?;
+        region.assign_advice(
+            || format!("is_memory_copy at row: {offset}"),
+            self.is_memory_copy,
+            *offset,
+            || Value::known(F::zero()),
+        )?;
 
         for column in [
             self.is_tx_calldata,
@@ -1054,6 +1120,17 @@ impl<F: Field> CopyCircuit<F> {
         }
     }
 
+    /// Minimum and total number of rows of the circuit for the copy events.
+    pub(crate) fn min_num_rows(copy_events: &[CopyEvent], max_copy_rows: usize) -> (usize, usize) {
+        let row_num = copy_events
+            .iter()
+            .map(|c| c.full_length() as usize * 2)
+            .sum::<usize>()
+            + UNUSED_ROWS
+            + DISABLED_ROWS;
+        (row_num, row_num.max(max_copy_rows))
+    }
+
     /// Return a new CopyCircuit with external data
     pub fn new_with_external_data(
         copy_events: Vec<CopyEvent>,
@@ -1106,14 +1183,7 @@ impl<F: Field> SubCircuit<F> for CopyCircuit<F> {
 
     /// Return the minimum number of rows required to prove the block
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
-        let row_num = block
-            .copy_events
-            .iter()
-            .map(|c| c.full_length() as usize * 2)
-            .sum::<usize>()
-            + UNUSED_ROWS
-            + DISABLED_ROWS;
-        (row_num, row_num.max(block.circuits_params.max_copy_rows))
+        Self::min_num_rows(&block.copy_events, block.circuits_params.max_copy_rows)
     }
 
     /// Make the assignments to the CopyCircuit
@@ -490,18 +490,65 @@ pub fn constrain_address<F: Field>(
 }
 
 /// Update the RW counter and verify that all RWs requested by the event are consumed.
+///
+/// For a copy within the memory of one call (MCOPY), all the word reads happen before all the
+/// word writes, so that overlapping ranges are read before being overwritten. The reader rows then
+/// use the RW counters of the first half and the writer rows use those of the second half, which
+/// are `rwc_gap` apart. Other copies, including Memory-to-Memory copies between two calls
+/// (RETURN, REVERT, CALL and RETURNDATACOPY), interleave reads and writes.
 #[allow(clippy::too_many_arguments)]
 pub fn constrain_rw_counter<F: Field>(
     cb: &mut BaseConstraintBuilder<F>,
     meta: &mut VirtualCells<'_, F>,
-    is_last: Expression<F>, // The last row.
+    is_reader: Expression<F>,
+    is_first: Expression<F>,
+    is_last: Expression<F>,             // The last row.
+    is_memory_to_memory: Expression<F>, // Same call on both sides, valid on reader rows.
     is_rw_type: Expression<F>,
     is_row_end: Expression<F>,
     rw_counter: Column<Advice>,
     rwc_inc_left: Column<Advice>,
+    is_memory_copy: Column<Advice>,
+    rwc_gap: Column<Advice>,
 ) {
+    let is_memory_copy_cur = meta.query_advice(is_memory_copy, CURRENT);
+    let rwc_gap_cur = meta.query_advice(rwc_gap, CURRENT);
+
+    // The gap is half of the RW operations for copies within the memory of a call, and 0
+    // otherwise.
+    cb.condition(is_first, |cb| {
+        cb.require_equal(
+            "is_memory_copy == Memory-to-Memory within the same call",
+            is_memory_copy_cur.expr(),
+            is_memory_to_memory,
+        );
+        cb.require_equal(
+            "2 * rwc_gap == rwc_inc_left for a memory copy, or 0",
+            2.expr() * rwc_gap_cur.expr(),
+            is_memory_copy_cur.expr() * meta.query_advice(rwc_inc_left, CURRENT),
+        );
+    });
+    cb.condition(not::expr(is_last.expr()), |cb| {
+        cb.require_equal(
+            "rows[0].is_memory_copy == rows[1].is_memory_copy",
+            is_memory_copy_cur.expr(),
+            meta.query_advice(is_memory_copy, NEXT_ROW),
+        );
+        cb.require_equal(
+            "rows[0].rwc_gap == rows[1].rwc_gap",
+            rwc_gap_cur.expr(),
+            meta.query_advice(rwc_gap, NEXT_ROW),
+        );
+    });
+
     // Decrement rwc_inc_left for the next row, when an RW operation happens.
-    let rwc_diff = is_rw_type.expr() * is_row_end.expr();
+    // With a gap, the reader jumps forward to the writes and the writer jumps back to the reads.
+    let rw_op = is_rw_type.expr() * is_row_end.expr();
+    let rwc_diff = select::expr(
+        is_reader,
+        rw_op.expr() * not::expr(is_memory_copy_cur) + rwc_gap_cur.expr(),
+        rw_op - rwc_gap_cur * not::expr(is_last.expr()),
+    );
     let new_value = meta.query_advice(rwc_inc_left, CURRENT) - rwc_diff;
     // At the end, it must reach 0.
     let update_or_finish = select::expr(
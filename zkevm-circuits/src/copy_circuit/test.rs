@@ -7,7 +7,7 @@ use crate::{
     witness::Block,
 };
 use bus_mapping::{
-    circuit_input_builder::{CircuitInputBuilder, CircuitsParams},
+    circuit_input_builder::{CircuitInputBuilder, CircuitsParams, CopyDataType},
     evm::{gen_sha3_code, MemoryKind, OpcodeId, PrecompileCallArgs},
     mock::BlockData,
     precompile::PrecompileCalls,
@@ -348,6 +348,89 @@ fn gen_return_data() -> CircuitInputBuilder {
     builder
 }
 
+fn gen_call_return_data() -> CircuitInputBuilder {
+    let (addr_a, addr_b) = (mock::MOCK_ACCOUNTS[0], mock::MOCK_ACCOUNTS[1]);
+
+    // The callee returns 3 words, which are copied from its memory into the memory of the
+    // caller, and then again by RETURNDATACOPY.
+    let mut code_b = bytecode! {};
+    for i in 0..3u64 {
+        code_b.push(32, Word::from_big_endian(&rand_bytes(32)));
+        code_b.push(1, Word::from(32 * i));
+        code_b.write_op(OpcodeId::MSTORE);
+    }
+    code_b.append(&bytecode! {
+        PUSH32(0x5a) // length
+        PUSH32(0x03) // offset
+        RETURN
+    });
+
+    let code_a = bytecode! {
+        PUSH32(0x5a) // retLength
+        PUSH32(0x07) // retOffset
+        PUSH1(0x00) // argsLength
+        PUSH1(0x00) // argsOffset
+        PUSH1(0x00) // value
+        PUSH32(addr_b.to_word()) // addr
+        PUSH32(0x1_0000) // gas
+        CALL
+        PUSH32(0x5a) // size
+        PUSH32(0x0) // offset
+        PUSH32(0x71) // dest_offset
+        RETURNDATACOPY
+        STOP
+    };
+
+    let test_ctx = TestContext::<3, 1>::new(
+        None,
+        |accs| {
+            accs[0].address(addr_a).code(code_a);
+            accs[1].address(addr_b).code(code_b);
+            accs[2]
+                .address(mock::MOCK_ACCOUNTS[2])
+                .balance(Word::from(1u64 << 30));
+        },
+        |mut txs, accs| {
+            txs[0].to(accs[0].address).from(accs[2].address);
+        },
+        |block, _tx| block,
+    )
+    .unwrap();
+
+    let block: GethData = test_ctx.into();
+    let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    builder
+}
+
+#[cfg(feature = "cancun")]
+fn gen_mcopy_data(dst_offset: usize, src_offset: usize, length: usize) -> CircuitInputBuilder {
+    // Fill the first 0x80 bytes of memory before the copy.
+    let mut code = bytecode! {};
+    for i in 0..4u64 {
+        code.push(32, Word::from_big_endian(&rand_bytes(32)));
+        code.push(1, Word::from(32 * i));
+        code.write_op(OpcodeId::MSTORE);
+    }
+    code.append(&bytecode! {
+        PUSH32(length)
+        PUSH32(src_offset)
+        PUSH32(dst_offset)
+        MCOPY
+        STOP
+    });
+
+    let test_ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap();
+    let block: GethData = test_ctx.into();
+    let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    builder
+}
+
 #[test]
 fn copy_circuit_valid_calldatacopy() {
     let builder = gen_calldatacopy_data();
@@ -411,6 +494,63 @@ fn copy_circuit_valid_return() {
     assert_eq!(test_copy_circuit_from_block(block), Ok(()));
 }
 
+#[test]
+fn copy_circuit_valid_call_return() {
+    let builder = gen_call_return_data();
+
+    // Copies between the memories of two calls keep reading and writing in turns.
+    let memory_copies = builder
+        .block
+        .copy_events
+        .iter()
+        .filter(|event| {
+            event.src_type == CopyDataType::Memory && event.dst_type == CopyDataType::Memory
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(memory_copies.len(), 2, "RETURN and RETURNDATACOPY");
+    for event in memory_copies {
+        assert!(!event.is_memory_copy());
+        let rw_counters =
+            event.rw_counter_start()..event.rw_counter_start() + event.rw_counter_delta();
+        let mut ops = builder
+            .block
+            .container
+            .memory
+            .iter()
+            .filter(|op| rw_counters.contains(&(op.rwc().0 as u64)))
+            .collect::<Vec<_>>();
+        ops.sort_by_key(|op| op.rwc());
+        assert!(ops.len() > 2);
+        for (idx, op) in ops.iter().enumerate() {
+            assert_eq!(op.rw().is_write(), idx % 2 == 1);
+        }
+    }
+
+    let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+    assert_eq!(test_copy_circuit_from_block(block), Ok(()));
+}
+
+#[cfg(feature = "cancun")]
+#[test]
+fn copy_circuit_valid_mcopy() {
+    for (dst_offset, src_offset, length) in [
+        // non-overlapping
+        (0x80, 0x00, 0x40),
+        // overlapping, destination after source
+        (0x20, 0x00, 0x60),
+        (0x11, 0x03, 0x45),
+        // overlapping, destination before source
+        (0x00, 0x20, 0x60),
+        (0x05, 0x17, 0x52),
+        // same range
+        (0x10, 0x10, 0x40),
+    ] {
+        let builder = gen_mcopy_data(dst_offset, src_offset, length);
+        let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+        assert_eq!(test_copy_circuit_from_block(block), Ok(()));
+    }
+}
+
 #[test]
 fn copy_circuit_invalid_calldatacopy() {
     let mut builder = gen_calldatacopy_data();
@@ -480,6 +620,29 @@ fn copy_circuit_invalid_sha3() {
     assert_error_matches(test_copy_circuit_from_block(block), vec!["rw lookup"]);
 }
 
+#[cfg(feature = "cancun")]
+#[test]
+fn copy_circuit_invalid_mcopy() {
+    // Overlapping copy, the destination word 1 is also the source word 1.
+    let mut builder = gen_mcopy_data(0x20, 0x00, 0x40);
+
+    // Pretend that the source word 1 is read after the destination word 0 is written, which
+    // "smears" the first copied word over the rest of the destination.
+    let copy_bytes = &mut builder.block.copy_events[0].copy_bytes;
+    let smeared: Vec<u8> = copy_bytes.bytes[..32].iter().map(|b| b.0).collect();
+    for (i, value) in smeared.into_iter().enumerate() {
+        copy_bytes.bytes[32 + i].0 = value;
+        copy_bytes.aux_bytes.as_mut().unwrap()[32 + i].0 = value;
+    }
+
+    let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+    assert_error_matches(
+        test_copy_circuit_from_block(block),
+        vec!["rw lookup", "rw lookup"],
+    );
+}
+
 #[test]
 fn copy_circuit_invalid_tx_log() {
     let mut builder = gen_tx_log_data();
@@ -4,7 +4,9 @@
 use std::{iter, marker::PhantomData};
 
 use bus_mapping::{
-    circuit_input_builder::{EcAddOp, EcMulOp, EcPairingOp, N_BYTES_PER_PAIR, N_PAIRING_PER_OP},
+    circuit_input_builder::{
+        CircuitsParams, EcAddOp, EcMulOp, EcPairingOp, N_BYTES_PER_PAIR, N_PAIRING_PER_OP,
+    },
     precompile::PrecompileCalls,
 };
 use eth_types::{Field, ToLittleEndian, ToScalar, U256};
@@ -174,6 +176,39 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         (1 << LOG_TOTAL_NUM_ROWS) - (max_blinding_factor + 3)
     }
 
+    /// Minimum and total number of rows of the circuit for the given numbers of EcAdd, EcMul
+    /// and EcPairing ops.
+    pub(crate) fn min_num_rows_of(
+        ec_adds: usize,
+        ec_muls: usize,
+        ec_pairings: usize,
+        circuits_params: &CircuitsParams,
+    ) -> (usize, usize) {
+        let row_num = if circuits_params.max_vertical_circuit_rows == 0 {
+            Self::min_num_rows()
+        } else {
+            circuits_params.max_vertical_circuit_rows
+        };
+
+        let max_ec_ops = &circuits_params.max_ec_ops;
+        log::debug!("ecc circuit row usage: ecadd {ec_adds}/{}, ecmul {ec_muls}/{}, ecpairing {ec_pairings}/{}",
+        max_ec_ops.ec_add, max_ec_ops.ec_mul, max_ec_ops.ec_pairing);
+
+        // Instead of showing actual minimum row usage,
+        // halo2-lib based circuits use min_row_num to represent a percentage of total-used capacity
+        // This functionality allows l2geth to decide if additional ops can be added.
+        let min_row_num = [
+            (row_num / max_ec_ops.ec_add) * ec_adds,
+            (row_num / max_ec_ops.ec_mul) * ec_muls,
+            (row_num / max_ec_ops.ec_pairing) * ec_pairings,
+        ]
+        .into_iter()
+        .max()
+        .unwrap();
+
+        (min_row_num, row_num)
+    }
+
     /// Assign witness from the ecXX ops to the circuit.
     pub(crate) fn assign(
         &self,
@@ -197,11 +232,12 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
             return Err(Error::Synthesis);
         }
 
-        // keccak powers of randomness.
+        // keccak powers of randomness, the last one being used to chain the input RLC of a pairing
+        // op to that of the previous op.
         let keccak_powers = std::iter::successors(Some(Value::known(F::one())), |coeff| {
             Some(challenges.keccak_input() * coeff)
         })
-        .take(N_PAIRING_PER_OP * N_BYTES_PER_PAIR)
+        .take(N_PAIRING_PER_OP * N_BYTES_PER_PAIR + 1)
         .map(|x| QuantumCell::Witness(x))
         .collect_vec();
 
@@ -273,13 +309,28 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
                 let ec_muls_decomposed =
                     decompose_ec_op!(EcMulOp, self.mul_ops, self.max_mul_ops, decompose_ec_mul_op);
 
-                // e(G1 . G2) * ... * e(G1 . G2) -> Gt
-                let ec_pairings_decomposed = decompose_ec_op!(
-                    EcPairingOp,
-                    self.pairing_ops,
-                    self.max_pairing_ops,
-                    decompose_ec_pairing_op
-                );
+                // e(G1 . G2) * ... * e(G1 . G2) -> Gt, where a chained op continues the pairing
+                // check of the previous op.
+                let mut ec_pairings_decomposed: Vec<EcPairingDecomposed<F>> =
+                    Vec::with_capacity(self.max_pairing_ops);
+                for op in self
+                    .pairing_ops
+                    .iter()
+                    .filter(|op| !op.skip_by_ecc_circuit())
+                    .chain(std::iter::repeat(&EcPairingOp::default()))
+                    .take(self.max_pairing_ops)
+                {
+                    let decomposed = self.decompose_ec_pairing_op(
+                        &mut ctx,
+                        &ecc_chip,
+                        &pairing_chip,
+                        &fp12_chip,
+                        &powers_of_256,
+                        op,
+                        ec_pairings_decomposed.last(),
+                    );
+                    ec_pairings_decomposed.push(decomposed);
+                }
 
                 #[cfg(not(feature = "onephase"))]
                 {
@@ -290,7 +341,18 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
 
                 let ec_adds_assigned = assign_ec_op!(ec_adds_decomposed, assign_ec_add);
                 let ec_muls_assigned = assign_ec_op!(ec_muls_decomposed, assign_ec_mul);
-                let ec_pairings_assigned = assign_ec_op!(ec_pairings_decomposed, assign_ec_pairing);
+                let mut ec_pairings_assigned: Vec<EcPairingAssigned<F>> =
+                    Vec::with_capacity(ec_pairings_decomposed.len());
+                for decomposed in ec_pairings_decomposed.iter() {
+                    let assigned = self.assign_ec_pairing(
+                        &mut ctx,
+                        decomposed,
+                        &ecc_chip,
+                        &keccak_powers,
+                        ec_pairings_assigned.last(),
+                    );
+                    ec_pairings_assigned.push(assigned);
+                }
 
                 // Finalize the Fp config always at the end of assignment.
                 let lookup_cells = config.fp_config.finalize(&mut ctx);
@@ -675,16 +737,19 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
 
     /// Decomposes an EcPairing operation and returns cells that represent the LE-bytes of all
     /// (G1, G2) pairs. In phase2 they will be RLC'd with the keccak randomness.
+    ///
+    /// If the op is chained, its Miller loop result is multiplied into the partial product of the
+    /// previous op, so that the pairing check is done over all pairs of the chain so far.
     #[allow(clippy::too_many_arguments)]
     fn decompose_ec_pairing_op(
         &self,
         ctx: &mut Context<F>,
         ecc_chip: &EccChip<F, FpConfig<F, Fq>>,
-        _fr_chip: &FpConfig<F, Fr>,
         pairing_chip: &PairingChip<F>,
         fp12_chip: &Fp12Chip<F, FpConfig<F, Fq>, Fq12, XI_0>,
         powers_of_256: &[QuantumCell<F>],
         op: &EcPairingOp,
+        prev: Option<&EcPairingDecomposed<F>>,
     ) -> EcPairingDecomposed<F> {
         log::trace!("[ECC] ==> EcPairing Assignment START:");
         log_context_cursor!(ctx);
@@ -950,23 +1015,76 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
             .collect_vec();
         let pairs = pairs.iter().map(|(g1, g2)| (g1, g2)).collect_vec();
 
-        // if the entire input to ecPairing is valid.
-        let is_valid = ecc_chip.field_chip().range().gate().and_many(
-            ctx,
-            decomposed_pairs
-                .iter()
-                .map(|&(_, is_pair_valid, _, _)| QuantumCell::Existing(is_pair_valid))
-                .collect_vec(),
-        );
+        // whether this op continues the pairing check of the previous op. The first op can not be
+        // chained.
+        let is_chained = ecc_chip
+            .field_chip()
+            .range()
+            .gate()
+            .load_witness(ctx, Value::known(F::from(op.chained as u64)));
+        ecc_chip
+            .field_chip()
+            .range()
+            .gate()
+            .assert_bit(ctx, is_chained);
+        if prev.is_none() {
+            ecc_chip.field_chip().range().gate().assert_equal(
+                ctx,
+                QuantumCell::Existing(is_chained),
+                QuantumCell::Constant(F::zero()),
+            );
+        }
+        // the previous op's flag if chained, or else true.
+        let chained_flag = |ctx: &mut Context<F>, prev_flag: Option<AssignedValue<F>>| {
+            prev_flag.map_or(QuantumCell::Constant(F::one()), |prev_flag| {
+                QuantumCell::Existing(ecc_chip.field_chip().range().gate().select(
+                    ctx,
+                    QuantumCell::Existing(prev_flag),
+                    QuantumCell::Constant(F::one()),
+                    QuantumCell::Existing(is_chained),
+                ))
+            })
+        };
 
-        // multi-miller loop and final exponentiation to do pairing check.
+        // if the entire input to ecPairing (of the chain so far) is valid.
+        let is_valid = {
+            let prev_is_valid = chained_flag(ctx, prev.map(|prev| prev.is_valid));
+            ecc_chip.field_chip().range().gate().and_many(
+                ctx,
+                decomposed_pairs
+                    .iter()
+                    .map(|&(_, is_pair_valid, _, _)| QuantumCell::Existing(is_pair_valid))
+                    .chain(std::iter::once(prev_is_valid))
+                    .collect_vec(),
+            )
+        };
+        // if all pairs of the chain so far are zero pairs.
+        let all_pairs_zero = {
+            let prev_all_pairs_zero = chained_flag(ctx, prev.map(|prev| prev.all_pairs_zero));
+            ecc_chip.field_chip().range().gate().and(
+                ctx,
+                QuantumCell::Existing(all_pairs_zero),
+                prev_all_pairs_zero,
+            )
+        };
+
+        // multi-miller loop, chained to the previous op's product, and final exponentiation to do
+        // pairing check. As the final exponentiation is a homomorphism, the pairing check of the
+        // chain is done on the product of the Miller loop results of all its ops.
+        let one = fp12_chip.load_constant(ctx, Fq12::one());
+        let miller_loop_acc = {
+            let miller_loop = pairing_chip.multi_miller_loop(ctx, pairs);
+            match prev {
+                Some(prev) => {
+                    let prev_acc = fp12_chip.select(ctx, &prev.miller_loop_acc, &one, &is_chained);
+                    fp12_chip.mul(ctx, &prev_acc, &miller_loop)
+                }
+                None => miller_loop,
+            }
+        };
         let success = {
-            let gt = {
-                let gt = pairing_chip.multi_miller_loop(ctx, pairs);
-                pairing_chip.final_exp(ctx, &gt)
-            };
+            let gt = pairing_chip.final_exp(ctx, &miller_loop_acc);
             // whether pairing check was successful.
-            let one = fp12_chip.load_constant(ctx, Fq12::one());
             fp12_chip.is_equal(ctx, &gt, &one)
         };
         // success == true only if pairing check and validity are both satisfied.
@@ -1005,7 +1123,10 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         log_context_cursor!(ctx);
 
         EcPairingDecomposed {
+            is_chained,
             is_valid,
+            all_pairs_zero,
+            miller_loop_acc,
             input_cells,
             success,
         }
@@ -1100,21 +1221,43 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         }
     }
 
-    /// Handles Phase2 for EcPairing operation and returns the RLC'd input bytes.
+    /// Handles Phase2 for EcPairing operation and returns the RLC'd input bytes. For a chained op
+    /// the RLC continues that of the previous op.
     fn assign_ec_pairing(
         &self,
         ctx: &mut Context<F>,
         ec_pairing_decomposed: &EcPairingDecomposed<F>,
         ecc_chip: &EccChip<F, FpConfig<F, Fq>>,
         keccak_powers: &[QuantumCell<F>],
+        prev: Option<&EcPairingAssigned<F>>,
     ) -> EcPairingAssigned<F> {
+        let gate = ecc_chip.field_chip().range().gate();
+        let input_rlc = gate.inner_product(
+            ctx,
+            ec_pairing_decomposed.input_cells.clone().into_iter().rev(),
+            keccak_powers.iter().cloned(),
+        );
+        let input_rlc = match prev {
+            Some(prev) => {
+                // prev_input_rlc * r^(N_PAIRING_PER_OP * N_BYTES_PER_PAIR) + input_rlc
+                let chained_input_rlc = gate.mul_add(
+                    ctx,
+                    QuantumCell::Existing(prev.input_rlc),
+                    keccak_powers[N_PAIRING_PER_OP * N_BYTES_PER_PAIR].clone(),
+                    QuantumCell::Existing(input_rlc),
+                );
+                gate.select(
+                    ctx,
+                    QuantumCell::Existing(chained_input_rlc),
+                    QuantumCell::Existing(input_rlc),
+                    QuantumCell::Existing(ec_pairing_decomposed.is_chained),
+                )
+            }
+            None => input_rlc,
+        };
         EcPairingAssigned {
             is_valid: ec_pairing_decomposed.is_valid,
-            input_rlc: ecc_chip.field_chip().range().gate().inner_product(
-                ctx,
-                ec_pairing_decomposed.input_cells.clone().into_iter().rev(),
-                keccak_powers.iter().cloned(),
-            ),
+            input_rlc,
             success: ec_pairing_decomposed.success,
         }
     }
@@ -1352,31 +1495,11 @@ impl<F: Field, const XI_0: i64> SubCircuit<F> for EccCircuit<F, XI_0> {
     }
 
     fn min_num_rows_block(block: &Block<F>) -> (usize, usize) {
-        let row_num = if block.circuits_params.max_vertical_circuit_rows == 0 {
-            Self::min_num_rows()
-        } else {
-            block.circuits_params.max_vertical_circuit_rows
-        };
-
-        let ec_adds = block.get_ec_add_ops().len();
-        let ec_muls = block.get_ec_mul_ops().len();
-        let ec_pairings = block.get_ec_pairing_ops().len();
-        let max_ec_ops = &block.circuits_params.max_ec_ops;
-        log::debug!("ecc circuit row usage: ecadd {ec_adds}/{}, ecmul {ec_muls}/{}, ecpairing {ec_pairings}/{}",
-        max_ec_ops.ec_add, max_ec_ops.ec_mul, max_ec_ops.ec_pairing);
-
-        // Instead of showing actual minimum row usage,
-        // halo2-lib based circuits use min_row_num to represent a percentage of total-used capacity
-        // This functionality allows l2geth to decide if additional ops can be added.
-        let min_row_num = [
-            (row_num / max_ec_ops.ec_add) * ec_adds,
-            (row_num / max_ec_ops.ec_mul) * ec_muls,
-            (row_num / max_ec_ops.ec_pairing) * ec_pairings,
-        ]
-        .into_iter()
-        .max()
-        .unwrap();
-
-        (min_row_num, row_num)
+        Self::min_num_rows_of(
+            block.get_ec_add_ops().len(),
+            block.get_ec_mul_ops().len(),
+            block.get_ec_pairing_ops().len(),
+            &block.circuits_params,
+        )
     }
 }
@@ -1,20 +1,31 @@
 use std::{
+    cell::RefCell,
     marker::PhantomData,
     ops::{Add, Mul, Neg},
 };
 
 use bus_mapping::circuit_input_builder::{
-    EcAddOp, EcMulOp, EcPairingOp, EcPairingPair, PrecompileEcParams,
+    EcAddOp, EcMulOp, EcPairingOp, EcPairingPair, PrecompileEcParams, N_BYTES_PER_PAIR,
+    N_PAIRING_PER_OP,
 };
 use eth_types::{Field, U256};
+use halo2_base::{QuantumCell, SKIP_FIRST_PASS};
 use halo2_proofs::{
     arithmetic::Field as ArithmeticField,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
     dev::MockProver,
-    halo2curves::bn256::{Fr, G1Affine, G2Affine},
+    halo2curves::bn256::{Fq, Fq12, Fr, G1Affine, G2Affine},
+    plonk::{Circuit, ConstraintSystem, Error},
 };
 use rand::{CryptoRng, Rng, RngCore};
 
-use crate::ecc_circuit::EccCircuit;
+use crate::{
+    ecc_circuit::{
+        util::{EcPairingAssigned, EcPairingDecomposed},
+        EccChip, EccCircuit, Fp12Chip, FpConfig, PairingChip,
+    },
+    evm_circuit::param::N_BYTES_WORD,
+};
 
 fn run<F: Field, const MUST_FAIL: bool>(
     k: u32,
@@ -390,6 +401,35 @@ mod valid_invalid_cases {
             },
         ]
     });
+    pub(crate) static EC_PAIRING_OPS5: LazyLock<Vec<EcPairingOp>> = LazyLock::new(|| {
+        let point_p = G1Affine::from(G1Affine::generator() * Fr::from(0x102030));
+        let point_q = G2Affine::from(G2Affine::generator() * Fr::from(0x413121));
+        vec![
+            // 9. valid: [(G1::gen, G2::gen), (-G1::gen, G2::gen), (P, Q)], pairing_check == 0
+            EcPairingOp {
+                pairs: [
+                    EcPairingPair::new(G1Affine::generator(), G2Affine::generator()),
+                    EcPairingPair::new(G1Affine::generator().neg(), G2Affine::generator()),
+                    EcPairingPair::new(point_p, point_q),
+                    EcPairingPair::padding_pair(),
+                ],
+                output: 0.into(),
+                ..Default::default()
+            },
+            // 10. valid: chained with [(-P, Q)], pairing_check == 1
+            EcPairingOp {
+                pairs: [
+                    EcPairingPair::new(point_p.neg(), point_q),
+                    EcPairingPair::padding_pair(),
+                    EcPairingPair::padding_pair(),
+                    EcPairingPair::padding_pair(),
+                ],
+                output: 1.into(),
+                chained: true,
+                ..Default::default()
+            },
+        ]
+    });
 }
 
 #[test]
@@ -398,6 +438,7 @@ fn test_ecc_circuit_valid_invalid() {
     use halo2_proofs::halo2curves::bn256::Fr;
     use valid_invalid_cases::{
         EC_ADD_OPS, EC_MUL_OPS, EC_PAIRING_OPS1, EC_PAIRING_OPS2, EC_PAIRING_OPS3, EC_PAIRING_OPS4,
+        EC_PAIRING_OPS5,
     };
 
     run::<Fr, false>(
@@ -443,6 +484,18 @@ fn test_ecc_circuit_valid_invalid() {
         vec![],
         EC_PAIRING_OPS4.clone(),
     );
+
+    run::<Fr, false>(
+        LOG_TOTAL_NUM_ROWS,
+        PrecompileEcParams {
+            ec_add: 0,
+            ec_mul: 0,
+            ec_pairing: 2,
+        },
+        vec![],
+        vec![],
+        EC_PAIRING_OPS5.clone(),
+    );
 }
 
 #[ignore = "generate a lot of random invalid inputs for bn254 add"]
@@ -618,3 +671,128 @@ fn variadic_size_check() {
     assert_eq!(prover1.fixed(), prover2.fixed());
     assert_eq!(prover1.permutation(), prover2.permutation());
 }
+
+/// Decomposes and assigns the given pairing ops, the same way as the ECC circuit does, and
+/// counts the advice cells used by each of them.
+struct EcPairingCellsCircuit {
+    circuit: EccCircuit<Fr, 9>,
+    cells: RefCell<Vec<usize>>,
+}
+
+impl Circuit<Fr> for EcPairingCellsCircuit {
+    type Config = <EccCircuit<Fr, 9> as Circuit<Fr>>::Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            circuit: EccCircuit::default(),
+            cells: Default::default(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        EccCircuit::<Fr, 9>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges): Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&layouter);
+        let keccak_powers = std::iter::successors(Some(Value::known(Fr::one())), |coeff| {
+            Some(challenges.keccak_input() * coeff)
+        })
+        .take(N_PAIRING_PER_OP * N_BYTES_PER_PAIR + 1)
+        .map(QuantumCell::Witness)
+        .collect::<Vec<_>>();
+        let powers_of_256 =
+            std::iter::successors(Some(Fr::one()), |coeff| Some(Fr::from(256) * coeff))
+                .take(N_BYTES_WORD)
+                .map(QuantumCell::Constant)
+                .collect::<Vec<_>>();
+
+        let ecc_chip = EccChip::<Fr, FpConfig<Fr, Fq>>::construct(config.fp_config.clone());
+        let pairing_chip = PairingChip::construct(config.fp_config.clone());
+        let fp12_chip =
+            Fp12Chip::<Fr, FpConfig<Fr, Fq>, Fq12, 9>::construct(config.fp_config.clone());
+
+        let mut first_pass = SKIP_FIRST_PASS;
+        layouter.assign_region(
+            || "ec pairing cells",
+            |region| {
+                if first_pass {
+                    first_pass = false;
+                    return Ok(());
+                }
+
+                let mut ctx = config.fp_config.new_context(region);
+                let mut cells = Vec::with_capacity(self.circuit.pairing_ops.len());
+                let mut decomposed_ops: Vec<EcPairingDecomposed<Fr>> = Vec::new();
+                for op in self.circuit.pairing_ops.iter() {
+                    let total_advice = ctx.total_advice;
+                    let decomposed = self.circuit.decompose_ec_pairing_op(
+                        &mut ctx,
+                        &ecc_chip,
+                        &pairing_chip,
+                        &fp12_chip,
+                        &powers_of_256,
+                        op,
+                        decomposed_ops.last(),
+                    );
+                    cells.push(ctx.total_advice - total_advice);
+                    decomposed_ops.push(decomposed);
+                }
+
+                config.fp_config.finalize(&mut ctx);
+                ctx.next_phase();
+
+                let mut assigned_ops: Vec<EcPairingAssigned<Fr>> = Vec::new();
+                for (decomposed, cells) in decomposed_ops.iter().zip(cells.iter_mut()) {
+                    let total_advice = ctx.total_advice;
+                    let assigned = self.circuit.assign_ec_pairing(
+                        &mut ctx,
+                        decomposed,
+                        &ecc_chip,
+                        &keccak_powers,
+                        assigned_ops.last(),
+                    );
+                    *cells += ctx.total_advice - total_advice;
+                    assigned_ops.push(assigned);
+                }
+
+                *self.cells.borrow_mut() = cells;
+                Ok(())
+            },
+        )
+    }
+}
+
+/// [`EC_PAIRING_CELLS`] must cover the advice cells of a pairing op, chained or not.
+///
+/// [`EC_PAIRING_CELLS`]: crate::ecc_circuit::util::EC_PAIRING_CELLS
+#[test]
+fn test_ec_pairing_op_cells() {
+    use crate::ecc_circuit::util::{EC_PAIRING_CELLS, LOG_TOTAL_NUM_ROWS};
+    use valid_invalid_cases::EC_PAIRING_OPS5;
+
+    let circuit = EcPairingCellsCircuit {
+        circuit: EccCircuit::<Fr, 9> {
+            max_pairing_ops: EC_PAIRING_OPS5.len(),
+            pairing_ops: EC_PAIRING_OPS5.clone(),
+            ..Default::default()
+        },
+        cells: Default::default(),
+    };
+    MockProver::<Fr>::run(LOG_TOTAL_NUM_ROWS, &circuit, vec![]).unwrap();
+
+    let cells = circuit.cells.into_inner();
+    log::info!("advice cells of the pairing ops (not chained, chained): {cells:?}");
+    assert_eq!(cells.len(), 2);
+    for cells in cells {
+        assert!(
+            cells <= EC_PAIRING_CELLS,
+            "a pairing op uses {cells} advice cells > EC_PAIRING_CELLS = {EC_PAIRING_CELLS}"
+        );
+    }
+}
@@ -9,7 +9,8 @@ pub const LOG_TOTAL_NUM_ROWS: u32 = 20;
 // Roud up to nearest 100
 pub(super) const EC_ADD_CELLS: usize = 6_900; // actual: 6_851
 pub(super) const EC_MUL_CELLS: usize = 405_500; // actual: 405_476
-pub(super) const EC_PAIRING_CELLS: usize = 6_627_500; // actual: 6_627_442
+// Covers a chained EcPairing op, see `test_ec_pairing_op_cells` for the actual cells.
+pub(super) const EC_PAIRING_CELLS: usize = 6_640_000;
 pub(super) const COLUMN_NUM_LIMIT: usize = 150; // Max number of columns allowed
 
 /// Decomposed state of a G1 curve point.
@@ -85,7 +86,14 @@ pub(super) struct EcMulAssigned<F: Field> {
 
 /// State of EcPairing operation post first phase.
 pub(super) struct EcPairingDecomposed<F: Field> {
+    /// Whether the op continues the pairing check of the previous op.
+    pub is_chained: AssignedValue<F>,
+    /// Whether all pairs of the chain so far are valid.
     pub is_valid: AssignedValue<F>,
+    /// Whether all pairs of the chain so far are zero pairs.
+    pub all_pairs_zero: AssignedValue<F>,
+    /// Product of the Miller loop results of the chain so far.
+    pub miller_loop_acc: FieldExtPoint<CRTInteger<F>>,
     pub input_cells: Vec<QuantumCell<F>>,
     pub success: AssignedValue<F>,
 }
@@ -93,7 +101,7 @@ pub(super) struct EcPairingDecomposed<F: Field> {
 /// State of EcPairing operation post second phase.
 pub(super) struct EcPairingAssigned<F: Field> {
     pub is_valid: AssignedValue<F>,
-    /// RLC of (G1, G2) pairs.
+    /// RLC of (G1, G2) pairs of the chain so far.
     pub input_rlc: AssignedValue<F>,
     pub success: AssignedValue<F>,
 }
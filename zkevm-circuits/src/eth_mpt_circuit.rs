@@ -0,0 +1,3202 @@
+//! The keccak MPT circuit proves the updates of the MPT table against the
+//! hexary Merkle-Patricia tries of Ethereum, as an alternative to the zktrie
+//! circuit for L1 blocks.
+//!
+//! Each update is proven in a unit of [`ROWS_PER_UPDATE`] rows. The first half
+//! of the unit walks the path of the account in the state trie and the second
+//! half the path of the storage slot in the storage trie of the account, one
+//! row per nibble of the key, in the old and the new trie side by side. The
+//! trie nodes along the paths and the accounts are decoded by the RLP circuit,
+//! and hashed by the keccak circuit. A node shorter than 32 bytes is embedded
+//! in its parent instead of being referenced by its hash.
+//!
+//! The old and new paths share their nodes down to the fork, where the key is
+//! inserted or deleted. There, the path in the trie without the key ends with
+//! an empty child of a branch, or with a leaf or an extension whose path
+//! diverges from the key. In the trie with the key, the empty child holds the
+//! leaf of the key, or the diverging node is split around a new branch holding
+//! the leaf of the key and the rest of the node, its sibling. Accounts are
+//! created by the update of one of their fields, and never deleted.
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::EthMptCircuit as TestEthMptCircuit;
+
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    rlp_circuit_fsm::RlpFsmDataTable,
+    table::{
+        KeccakTable, LookupTable, MPTProofType, MptTable, PowOfRandTable, RlpFsmRlpTable, U8Table,
+    },
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness::{
+        self, empty_trie_root,
+        eth_mpt::{common_prefix_len, hp_encode, item_offsets, key_nibbles, TrieNode},
+        EthAccount, EthMptTrace, Format, MptUpdate, MptUpdateRow, MptUpdates, RlpTag, Tag,
+        TrieNodeRlp, TriePath,
+    },
+};
+use eth_types::{Field, ToBigEndian, ToLittleEndian, ToWord};
+use ethers_core::utils::{keccak256, rlp::Rlp};
+use gadgets::util::{not, sum, Expr};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, VirtualCells,
+    },
+    poly::Rotation,
+};
+use itertools::Itertools;
+use std::marker::PhantomData;
+
+/// Rows of the path of a key in a trie, one per nibble of the key.
+const ROWS_PER_SEGMENT: usize = 64;
+
+/// Rows used to prove an MPT update: the path of the account in the state trie
+/// followed by the path of the storage slot in the storage trie.
+pub(crate) const ROWS_PER_UPDATE: usize = 2 * ROWS_PER_SEGMENT;
+
+/// The proof types of the MPT table proven by the circuit, in the order of the
+/// flags of a unit. Outside of scroll, the keccak code hash of an account is
+/// its `CodeHash` field.
+const PROOF_TYPES: [MPTProofType; 6] = [
+    MPTProofType::NonceChanged,
+    MPTProofType::BalanceChanged,
+    MPTProofType::PoseidonCodeHashExists,
+    MPTProofType::AccountDoesNotExist,
+    MPTProofType::StorageChanged,
+    MPTProofType::StorageDoesNotExist,
+];
+const IS_NONCE: usize = 0;
+const IS_BALANCE: usize = 1;
+const IS_CODE_HASH: usize = 2;
+const IS_ACCOUNT_ABSENT: usize = 3;
+const IS_STORAGE_CHANGED: usize = 4;
+const IS_STORAGE_ABSENT: usize = 5;
+
+/// The outputs of the RLP circuit for an account, looked up in the first rows
+/// of the account segment. The fields of an account follow its RLC and length.
+const ACCOUNT_FIELDS: [RlpTag; 6] = [
+    RlpTag::RLC,
+    RlpTag::Len,
+    RlpTag::Tag(Tag::Nonce),
+    RlpTag::Tag(Tag::Value),
+    RlpTag::Tag(Tag::StorageRoot),
+    RlpTag::Tag(Tag::CodeHash),
+];
+const FIELD_RLC: i32 = 0;
+const FIELD_LEN: i32 = 1;
+const FIELD_NONCE: i32 = 2;
+const FIELD_BALANCE: i32 = 3;
+const FIELD_STORAGE_ROOT: i32 = 4;
+const FIELD_CODE_HASH: i32 = 5;
+
+/// Columns holding the MPT update of a unit, constant over its rows.
+#[derive(Clone, Copy, Debug)]
+struct UnitColumns {
+    /// Whether the unit proves an update or is padding
+    active: Column<Advice>,
+    address: Column<Advice>,
+    storage_key: Column<Advice>,
+    proof_type: Column<Advice>,
+    /// Old and new state roots
+    roots: [Column<Advice>; 2],
+    /// Old and new values
+    values: [Column<Advice>; 2],
+    /// Inverse of the new value, which is not 0 when an account is created
+    value_inv: Column<Advice>,
+    /// Old and new storage roots of the account
+    storage_roots: [Column<Advice>; 2],
+    /// Whether the account exists in the old and new state tries
+    account_exists: [Column<Advice>; 2],
+    /// Whether the storage slot exists in the old and new storage tries
+    storage_exists: [Column<Advice>; 2],
+    /// One flag per proof type of `PROOF_TYPES`
+    flags: [Column<Advice>; 6],
+}
+
+/// Columns of a trie node along the path, constant over the rows of the
+/// nibbles it consumes.
+#[derive(Clone, Copy, Debug)]
+struct NodeColumns {
+    /// Id of the node in the RLP table
+    id: Column<Advice>,
+    /// Word RLC of the hash of the node
+    hash: Column<Advice>,
+    /// RLC of the RLP encoding of the node
+    rlc: Column<Advice>,
+    /// Length of the RLP encoding of the node
+    len: Column<Advice>,
+    /// Number of bytes of the length in the list header of the node
+    len_bytes: Column<Advice>,
+    /// The item of the node on the path: the child of a branch or an
+    /// extension, or the value of a leaf
+    item: Column<Advice>,
+    item_bytes_rlc: Column<Advice>,
+    item_len: Column<Advice>,
+    item_none: Column<Advice>,
+    /// Whether the child is referenced by its hash, and not embedded
+    item_is_hash: Column<Advice>,
+}
+
+/// Columns of the bytes of a branch around its item on the path, read from
+/// the RLP data table: the list header, the children before the item, the
+/// item and the children after it.
+#[derive(Clone, Copy, Debug)]
+struct BranchColumns {
+    /// First byte of the list header, and whether the header is long
+    hdr_byte: Column<Advice>,
+    is_long: Column<Advice>,
+    /// RLC of the list header
+    hdr_rlc: Column<Advice>,
+    /// Number of bytes of the children before the item, the power of the
+    /// randomness to it, and the RLC of the bytes of the branch up to them
+    pre_len: Column<Advice>,
+    pre_pow: Column<Advice>,
+    pre_acc: Column<Advice>,
+    /// Power of the randomness to the length of the item, and the RLC of the
+    /// bytes of the branch up to the item
+    item_pow: Column<Advice>,
+    item_acc: Column<Advice>,
+    /// Number of bytes following the item, and the power of the randomness
+    /// to it
+    suffix_len: Column<Advice>,
+    suffix_pow: Column<Advice>,
+}
+
+/// Columns of the path of the key in a trie. There is one set of them for the
+/// old trie and one for the new trie.
+#[derive(Clone, Copy, Debug)]
+struct PathColumns {
+    /// Whether the key exists in the trie
+    exists: Column<Advice>,
+    /// Whether a node starts at the row, and its type
+    is_start: Column<Advice>,
+    is_branch: Column<Advice>,
+    is_extension: Column<Advice>,
+    is_leaf: Column<Advice>,
+    /// Rows after an empty child or a diverging extension, in proofs of
+    /// absence
+    is_tail: Column<Advice>,
+
+    /// Nibble of the path of the node at the row, which is the nibble of the
+    /// key until the path of a leaf or an extension diverges from it
+    nibble: Column<Advice>,
+    is_diverged: Column<Advice>,
+    /// Whether the path diverges from the key at the row, and the inverse of
+    /// the difference of their nibbles
+    diverge_start: Column<Advice>,
+    diverge_inv: Column<Advice>,
+    /// Whether the path diverges from the key below the fork, where the
+    /// sibling of the leaf of the key is checked
+    has_sibling: Column<Advice>,
+
+    /// The hex-prefix encoding of the nibbles of a node, accumulated from the
+    /// last one: a nibble is either the low or the high half of a byte.
+    is_low: Column<Advice>,
+    path_acc: Column<Advice>,
+    path_pow: Column<Advice>,
+    path_len_acc: Column<Advice>,
+    /// The path of the extension or leaf starting at the row
+    path_rlc: Column<Advice>,
+    path_len: Column<Advice>,
+
+    node: NodeColumns,
+    branch: BranchColumns,
+}
+
+/// Columns of the other child of the branch created by an insertion or
+/// removed by a deletion, next to the leaf of the key.
+#[derive(Clone, Copy, Debug)]
+struct SiblingColumns {
+    /// The child in the branch, and whether it is the hash of the sibling
+    item: Column<Advice>,
+    item_bytes_rlc: Column<Advice>,
+    item_len: Column<Advice>,
+    item_is_hash: Column<Advice>,
+    /// Whether the sibling is the diverging node without the nibbles down to
+    /// the branch, or else the child of the diverging extension
+    is_derived: Column<Advice>,
+    /// The sibling decoded by the RLP circuit
+    id: Column<Advice>,
+    hash: Column<Advice>,
+    rlc: Column<Advice>,
+    len: Column<Advice>,
+    len_bytes: Column<Advice>,
+    /// The path of a derived sibling
+    path_rlc: Column<Advice>,
+    path_len: Column<Advice>,
+}
+
+/// Columns of the account in the leaf of the state trie, whose fields are
+/// looked up in the first rows of the account segment.
+#[derive(Clone, Copy, Debug)]
+struct AccountColumns {
+    /// Id of the account in the RLP table, constant over the account segment
+    id: Column<Advice>,
+    field_value: Column<Advice>,
+    field_bytes_rlc: Column<Advice>,
+    field_len: Column<Advice>,
+    field_none: Column<Advice>,
+}
+
+/// Columns decomposing a storage value into its 32 big endian bytes, in the
+/// first rows of the storage segment, to rebuild its RLP encoding.
+#[derive(Clone, Copy, Debug)]
+struct StorageValueColumns {
+    byte: Column<Advice>,
+    /// Whether the byte is after the leading zeros
+    is_significant: Column<Advice>,
+    /// Inverse of the first significant byte
+    byte_inv: Column<Advice>,
+    /// Word RLC of the value
+    value_acc: Column<Advice>,
+    /// RLC of the significant bytes
+    bytes_rlc: Column<Advice>,
+    /// Power of the randomness to the number of significant bytes
+    bytes_pow: Column<Advice>,
+    /// Number of significant bytes
+    bytes_len: Column<Advice>,
+    /// Whether the value is a single byte below 0x80, encoded as itself
+    is_short: Column<Advice>,
+}
+
+/// Config for the keccak MPT circuit
+#[derive(Clone, Debug)]
+pub struct EthMptCircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_unit_first: Column<Fixed>,
+    q_seg_first: Column<Fixed>,
+    q_seg_last: Column<Fixed>,
+    q_storage: Column<Fixed>,
+    q_preimage: Column<Fixed>,
+    q_odd: Column<Fixed>,
+    q_value_last: Column<Fixed>,
+    q_account_field: Column<Fixed>,
+    account_field_tag: Column<Fixed>,
+
+    unit: UnitColumns,
+
+    /// Nibble of the key at the row
+    nibble: Column<Advice>,
+    /// Word RLC of the complete bytes of the key up to the row
+    key_rlc: Column<Advice>,
+    /// Address or storage slot hashed into the key
+    preimage_byte: Column<Advice>,
+    preimage_rlc: Column<Advice>,
+    preimage_acc: Column<Advice>,
+
+    /// Whether the old and new paths share the node at the row, and whether
+    /// the row is the first one they do not share
+    is_common: Column<Advice>,
+    is_fork: Column<Advice>,
+    /// Whether the fork is a tail following a branch, whose two other
+    /// children are given with the inverse of the product of the differences
+    /// of the three nibbles
+    is_tail_fork: Column<Advice>,
+    other_nibbles: [Column<Advice>; 2],
+    other_inv: Column<Advice>,
+
+    paths: [PathColumns; 2],
+    sibling: SiblingColumns,
+    accounts: [AccountColumns; 2],
+    storage_values: [StorageValueColumns; 2],
+
+    /// MPT table
+    pub mpt_table: MptTable,
+    /// Keccak table
+    pub keccak_table: KeccakTable,
+    /// RLP table
+    pub rlp_table: RlpFsmRlpTable,
+    /// RLP data table, holding the bytes of the trie nodes
+    pub rlp_data_table: RlpFsmDataTable,
+    /// Powers of the keccak randomness
+    pub pow_of_rand_table: PowOfRandTable,
+    /// u8 table
+    pub u8_table: U8Table,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct EthMptCircuitConfigArgs<F: Field> {
+    /// MPT table
+    pub mpt_table: MptTable,
+    /// Keccak table
+    pub keccak_table: KeccakTable,
+    /// RLP table
+    pub rlp_table: RlpFsmRlpTable,
+    /// RLP data table
+    pub rlp_data_table: RlpFsmDataTable,
+    /// Powers of the keccak randomness
+    pub pow_of_rand_table: PowOfRandTable,
+    /// u8 table
+    pub u8_table: U8Table,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for EthMptCircuitConfig<F> {
+    type ConfigArgs = EthMptCircuitConfigArgs<F>;
+
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            mpt_table,
+            keccak_table,
+            rlp_table,
+            rlp_data_table,
+            pow_of_rand_table,
+            u8_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_unit_first = meta.fixed_column();
+        let q_seg_first = meta.fixed_column();
+        let q_seg_last = meta.fixed_column();
+        let q_storage = meta.fixed_column();
+        let q_preimage = meta.fixed_column();
+        let q_odd = meta.fixed_column();
+        let q_value_last = meta.fixed_column();
+        let q_account_field = meta.fixed_column();
+        let account_field_tag = meta.fixed_column();
+
+        let unit = UnitColumns {
+            active: meta.advice_column(),
+            address: meta.advice_column(),
+            storage_key: meta.advice_column_in(SecondPhase),
+            proof_type: meta.advice_column(),
+            roots: [(); 2].map(|_| meta.advice_column_in(SecondPhase)),
+            values: [(); 2].map(|_| meta.advice_column_in(SecondPhase)),
+            value_inv: meta.advice_column_in(SecondPhase),
+            storage_roots: [(); 2].map(|_| meta.advice_column_in(SecondPhase)),
+            account_exists: [(); 2].map(|_| meta.advice_column()),
+            storage_exists: [(); 2].map(|_| meta.advice_column()),
+            flags: [(); 6].map(|_| meta.advice_column()),
+        };
+
+        let nibble = meta.advice_column();
+        let key_rlc = meta.advice_column_in(SecondPhase);
+        let preimage_byte = meta.advice_column();
+        let preimage_rlc = meta.advice_column_in(SecondPhase);
+        let preimage_acc = meta.advice_column_in(SecondPhase);
+
+        let is_common = meta.advice_column();
+        let is_fork = meta.advice_column();
+        let is_tail_fork = meta.advice_column();
+        let other_nibbles = [(); 2].map(|_| meta.advice_column());
+        let other_inv = meta.advice_column();
+
+        let paths = [(); 2].map(|_| PathColumns {
+            exists: meta.advice_column(),
+            is_start: meta.advice_column(),
+            is_branch: meta.advice_column(),
+            is_extension: meta.advice_column(),
+            is_leaf: meta.advice_column(),
+            is_tail: meta.advice_column(),
+            nibble: meta.advice_column(),
+            is_diverged: meta.advice_column(),
+            diverge_start: meta.advice_column(),
+            diverge_inv: meta.advice_column(),
+            has_sibling: meta.advice_column(),
+            is_low: meta.advice_column(),
+            path_acc: meta.advice_column_in(SecondPhase),
+            path_pow: meta.advice_column_in(SecondPhase),
+            path_len_acc: meta.advice_column(),
+            path_rlc: meta.advice_column_in(SecondPhase),
+            path_len: meta.advice_column(),
+            node: NodeColumns {
+                id: meta.advice_column(),
+                hash: meta.advice_column_in(SecondPhase),
+                rlc: meta.advice_column_in(SecondPhase),
+                len: meta.advice_column(),
+                len_bytes: meta.advice_column(),
+                item: meta.advice_column_in(SecondPhase),
+                item_bytes_rlc: meta.advice_column_in(SecondPhase),
+                item_len: meta.advice_column(),
+                item_none: meta.advice_column(),
+                item_is_hash: meta.advice_column(),
+            },
+            branch: BranchColumns {
+                hdr_byte: meta.advice_column(),
+                is_long: meta.advice_column(),
+                hdr_rlc: meta.advice_column_in(SecondPhase),
+                pre_len: meta.advice_column(),
+                pre_pow: meta.advice_column_in(SecondPhase),
+                pre_acc: meta.advice_column_in(SecondPhase),
+                item_pow: meta.advice_column_in(SecondPhase),
+                item_acc: meta.advice_column_in(SecondPhase),
+                suffix_len: meta.advice_column(),
+                suffix_pow: meta.advice_column_in(SecondPhase),
+            },
+        });
+        let sibling = SiblingColumns {
+            item: meta.advice_column_in(SecondPhase),
+            item_bytes_rlc: meta.advice_column_in(SecondPhase),
+            item_len: meta.advice_column(),
+            item_is_hash: meta.advice_column(),
+            is_derived: meta.advice_column(),
+            id: meta.advice_column(),
+            hash: meta.advice_column_in(SecondPhase),
+            rlc: meta.advice_column_in(SecondPhase),
+            len: meta.advice_column(),
+            len_bytes: meta.advice_column(),
+            path_rlc: meta.advice_column_in(SecondPhase),
+            path_len: meta.advice_column(),
+        };
+        let accounts = [(); 2].map(|_| AccountColumns {
+            id: meta.advice_column(),
+            field_value: meta.advice_column_in(SecondPhase),
+            field_bytes_rlc: meta.advice_column_in(SecondPhase),
+            field_len: meta.advice_column(),
+            field_none: meta.advice_column(),
+        });
+        let storage_values = [(); 2].map(|_| StorageValueColumns {
+            byte: meta.advice_column(),
+            is_significant: meta.advice_column(),
+            byte_inv: meta.advice_column(),
+            value_acc: meta.advice_column_in(SecondPhase),
+            bytes_rlc: meta.advice_column_in(SecondPhase),
+            bytes_pow: meta.advice_column_in(SecondPhase),
+            bytes_len: meta.advice_column(),
+            is_short: meta.advice_column(),
+        });
+
+        let keccak_rand = challenges.keccak_input();
+        let word_rand = challenges.evm_word();
+
+        let flag = |meta: &mut VirtualCells<F>, i: usize| {
+            meta.query_advice(unit.flags[i], Rotation::cur())
+        };
+        // Whether the segment of the row walks a path.
+        let seg_active = |meta: &mut VirtualCells<F>| {
+            let q_storage = meta.query_fixed(q_storage, Rotation::cur());
+            not::expr(q_storage.expr()) * meta.query_advice(unit.active, Rotation::cur())
+                + q_storage * (flag(meta, IS_STORAGE_CHANGED) + flag(meta, IS_STORAGE_ABSENT))
+        };
+        // The root of the trie of the segment, the state root or the storage
+        // root of the account.
+        let seg_root = |meta: &mut VirtualCells<F>, side: usize| {
+            let q_storage = meta.query_fixed(q_storage, Rotation::cur());
+            not::expr(q_storage.expr()) * meta.query_advice(unit.roots[side], Rotation::cur())
+                + q_storage * meta.query_advice(unit.storage_roots[side], Rotation::cur())
+        };
+        let node_format = |meta: &mut VirtualCells<F>, path: &PathColumns| {
+            meta.query_advice(path.is_branch, Rotation::cur()) * Format::MptBranch.expr()
+                + meta.query_advice(path.is_extension, Rotation::cur())
+                    * Format::MptExtension.expr()
+                + meta.query_advice(path.is_leaf, Rotation::cur()) * Format::MptLeaf.expr()
+        };
+        // The number of bytes of the list header of a branch.
+        let hdr_len = |meta: &mut VirtualCells<F>, path: &PathColumns| {
+            1.expr()
+                + meta.query_advice(path.branch.is_long, Rotation::cur())
+                    * (meta.query_advice(path.branch.hdr_byte, Rotation::cur()) - 0xf7.expr())
+        };
+        // The number of bytes of the item of a branch: an empty string, a
+        // string of 32 bytes or an embedded list.
+        let item_encoding_len = |meta: &mut VirtualCells<F>, path: &PathColumns| {
+            meta.query_advice(path.node.item_none, Rotation::cur())
+                + meta.query_advice(path.node.item_is_hash, Rotation::cur())
+                + meta.query_advice(path.node.item_len, Rotation::cur())
+        };
+        let empty_root = rlc::expr(
+            &empty_trie_root()
+                .to_word()
+                .to_le_bytes()
+                .map(|byte| byte.expr()),
+            word_rand.expr(),
+        );
+
+        meta.create_gate("eth mpt: update of a unit", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let active = meta.query_advice(unit.active, Rotation::cur());
+            let flags = (0..PROOF_TYPES.len())
+                .map(|i| flag(meta, i))
+                .collect::<Vec<_>>();
+            cb.require_boolean("active is boolean", active.expr());
+            for flag in flags.iter() {
+                cb.require_boolean("proof type flag is boolean", flag.expr());
+            }
+            cb.require_equal(
+                "an active unit proves one proof type",
+                sum::expr(flags.iter()),
+                active.expr(),
+            );
+            cb.require_equal(
+                "proof type matches the flags",
+                meta.query_advice(unit.proof_type, Rotation::cur()),
+                flags
+                    .iter()
+                    .zip_eq(PROOF_TYPES)
+                    .map(|(flag, proof_type)| flag.expr() * (proof_type as u64).expr())
+                    .fold(
+                        not::expr(active.expr())
+                            * (MPTProofType::AccountDoesNotExist as u64).expr(),
+                        |acc, term| acc + term,
+                    ),
+            );
+
+            let address = meta.query_advice(unit.address, Rotation::cur());
+            let storage_key = meta.query_advice(unit.storage_key, Rotation::cur());
+            let [old_root, new_root] = unit
+                .roots
+                .map(|root| meta.query_advice(root, Rotation::cur()));
+            let [old_value, new_value] = unit
+                .values
+                .map(|value| meta.query_advice(value, Rotation::cur()));
+            let [old_storage_root, new_storage_root] = unit
+                .storage_roots
+                .map(|root| meta.query_advice(root, Rotation::cur()));
+            let [old_account_exists, new_account_exists] = unit
+                .account_exists
+                .map(|exists| meta.query_advice(exists, Rotation::cur()));
+            let [old_storage_exists, new_storage_exists] = unit
+                .storage_exists
+                .map(|exists| meta.query_advice(exists, Rotation::cur()));
+
+            cb.condition(not::expr(active.expr()), |cb| {
+                for value in [
+                    &address,
+                    &storage_key,
+                    &old_root,
+                    &new_root,
+                    &old_value,
+                    &new_value,
+                    &old_account_exists,
+                    &new_account_exists,
+                ] {
+                    cb.require_zero("padding unit is the padding row", value.expr());
+                }
+            });
+            cb.condition(
+                flags[IS_ACCOUNT_ABSENT].expr() + flags[IS_STORAGE_ABSENT].expr(),
+                |cb| {
+                    cb.require_zero("absent key has old value 0", old_value.expr());
+                    cb.require_zero("absent key has new value 0", new_value.expr());
+                    cb.require_equal(
+                        "reading an absent key leaves the root unchanged",
+                        old_root.expr(),
+                        new_root.expr(),
+                    );
+                },
+            );
+            cb.condition(not::expr(flags[IS_STORAGE_CHANGED].expr()), |cb| {
+                cb.require_equal(
+                    "storage root is unchanged",
+                    old_storage_root.expr(),
+                    new_storage_root.expr(),
+                );
+            });
+            cb.condition(
+                not::expr(flags[IS_STORAGE_CHANGED].expr() + flags[IS_STORAGE_ABSENT].expr()),
+                |cb| {
+                    cb.require_zero("account update has no storage key", storage_key.expr());
+                },
+            );
+
+            for exists in [
+                &old_account_exists,
+                &new_account_exists,
+                &old_storage_exists,
+                &new_storage_exists,
+            ] {
+                cb.require_boolean("exists is boolean", exists.expr());
+            }
+            cb.require_zero(
+                "an account is never deleted",
+                old_account_exists.expr() * not::expr(new_account_exists.expr()),
+            );
+            cb.require_zero(
+                "an absent account is not created",
+                flags[IS_ACCOUNT_ABSENT].expr() * new_account_exists.expr(),
+            );
+            let is_created = new_account_exists.expr() - old_account_exists.expr();
+            cb.condition(is_created.expr(), |cb| {
+                cb.require_equal(
+                    "an account is created by the update of one of its fields",
+                    flags[IS_NONCE].expr() + flags[IS_BALANCE].expr() + flags[IS_CODE_HASH].expr(),
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "an account is created by a non zero value",
+                    new_value.expr() * meta.query_advice(unit.value_inv, Rotation::cur()),
+                    1.expr(),
+                );
+            });
+            cb.require_zero(
+                "storage is changed in an existing account",
+                flags[IS_STORAGE_CHANGED].expr() * not::expr(old_account_exists.expr()),
+            );
+            cb.condition(
+                active * not::expr(old_account_exists) * not::expr(new_account_exists),
+                |cb| {
+                    cb.require_equal(
+                        "a missing account leaves the root unchanged",
+                        old_root.expr(),
+                        new_root.expr(),
+                    );
+                },
+            );
+            cb.require_zero(
+                "only the storage of a storage update exists",
+                not::expr(flags[IS_STORAGE_CHANGED].expr())
+                    * (old_storage_exists.expr() + new_storage_exists.expr()),
+            );
+            cb.condition(
+                flags[IS_STORAGE_CHANGED].expr()
+                    * not::expr(old_storage_exists)
+                    * not::expr(new_storage_exists),
+                |cb| {
+                    cb.require_equal(
+                        "a missing slot leaves the storage root unchanged",
+                        old_storage_root,
+                        new_storage_root,
+                    );
+                },
+            );
+
+            cb.gate(meta.query_fixed(q_unit_first, Rotation::cur()))
+        });
+
+        meta.create_gate("eth mpt: update is constant over the unit", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let columns = [
+                unit.active,
+                unit.address,
+                unit.storage_key,
+                unit.proof_type,
+                unit.value_inv,
+            ]
+            .into_iter()
+            .chain(unit.roots)
+            .chain(unit.values)
+            .chain(unit.storage_roots)
+            .chain(unit.account_exists)
+            .chain(unit.storage_exists)
+            .chain(unit.flags);
+            for column in columns {
+                cb.require_equal(
+                    "unit column is constant",
+                    meta.query_advice(column, Rotation::next()),
+                    meta.query_advice(column, Rotation::cur()),
+                );
+            }
+
+            let q_unit_last = meta.query_fixed(q_storage, Rotation::cur())
+                * meta.query_fixed(q_seg_last, Rotation::cur());
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()) * not::expr(q_unit_last))
+        });
+
+        meta.create_gate("eth mpt: key of the path and its preimage", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let q_seg_first = meta.query_fixed(q_seg_first, Rotation::cur());
+            let q_odd = meta.query_fixed(q_odd, Rotation::cur());
+            let q_preimage = meta.query_fixed(q_preimage, Rotation::cur());
+            let q_storage = meta.query_fixed(q_storage, Rotation::cur());
+            let nibble_prev = meta.query_advice(nibble, Rotation::prev());
+            let nibble = meta.query_advice(nibble, Rotation::cur());
+            let key_rlc_prev = meta.query_advice(key_rlc, Rotation::prev());
+            let key_rlc = meta.query_advice(key_rlc, Rotation::cur());
+            let byte = meta.query_advice(preimage_byte, Rotation::cur());
+            let preimage_rlc_prev = meta.query_advice(preimage_rlc, Rotation::prev());
+            let preimage_rlc = meta.query_advice(preimage_rlc, Rotation::cur());
+            let preimage_acc_prev = meta.query_advice(preimage_acc, Rotation::prev());
+            let preimage_acc = meta.query_advice(preimage_acc, Rotation::cur());
+
+            cb.condition(q_seg_first.expr(), |cb| {
+                cb.require_zero("key rlc starts at 0", key_rlc.expr());
+                cb.require_equal("preimage rlc starts", preimage_rlc.expr(), byte.expr());
+                cb.require_equal("preimage acc starts", preimage_acc.expr(), byte.expr());
+            });
+            cb.condition(not::expr(q_seg_first), |cb| {
+                cb.require_equal(
+                    "a byte of the key is complete at its low nibble",
+                    key_rlc.expr(),
+                    key_rlc_prev.expr()
+                        + q_odd.expr()
+                            * (key_rlc_prev.expr() * (word_rand.expr() - 1.expr())
+                                + nibble_prev * 16.expr()
+                                + nibble),
+                );
+                cb.require_equal(
+                    "preimage rlc",
+                    preimage_rlc.expr(),
+                    preimage_rlc_prev.expr()
+                        + q_preimage.expr()
+                            * (preimage_rlc_prev * (keccak_rand.expr() - 1.expr()) + byte.expr()),
+                );
+                // an address is a scalar, a storage key a word
+                let base = not::expr(q_storage.expr()) * 256.expr() + q_storage * word_rand.expr();
+                cb.require_equal(
+                    "preimage acc",
+                    preimage_acc.expr(),
+                    preimage_acc_prev.expr()
+                        + q_preimage.expr() * (preimage_acc_prev * (base - 1.expr()) + byte.expr()),
+                );
+            });
+            cb.condition(not::expr(q_preimage), |cb| {
+                cb.require_zero("no preimage byte after the preimage", byte);
+            });
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("eth mpt: preimage of the key", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let q_storage = meta.query_fixed(q_storage, Rotation::cur());
+            cb.require_equal(
+                "key is the hash of the address or of the storage key",
+                meta.query_advice(preimage_acc, Rotation::cur()),
+                not::expr(q_storage.expr()) * meta.query_advice(unit.address, Rotation::cur())
+                    + q_storage * meta.query_advice(unit.storage_key, Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(q_seg_last, Rotation::cur()) * seg_active(meta))
+        });
+
+        meta.lookup("eth mpt: nibble range", |meta| {
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            vec![(
+                q_enable * meta.query_advice(nibble, Rotation::cur()),
+                u8_table.into(),
+            )]
+        });
+        meta.lookup("eth mpt: nibble is below 16", |meta| {
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            vec![(
+                q_enable * meta.query_advice(nibble, Rotation::cur()) * 16.expr(),
+                u8_table.into(),
+            )]
+        });
+        meta.lookup("eth mpt: preimage byte range", |meta| {
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            vec![(
+                q_enable * meta.query_advice(preimage_byte, Rotation::cur()),
+                u8_table.into(),
+            )]
+        });
+        meta.lookup_any("eth mpt: key is the keccak hash of its preimage", |meta| {
+            let enable = meta.query_fixed(q_seg_last, Rotation::cur()) * seg_active(meta);
+            let q_storage = meta.query_fixed(q_storage, Rotation::cur());
+            let preimage_len = 20.expr() + q_storage * 12.expr();
+
+            vec![
+                1.expr(), // q_enable
+                1.expr(), // is_final
+                meta.query_advice(preimage_rlc, Rotation::cur()),
+                preimage_len,
+                meta.query_advice(key_rlc, Rotation::cur()),
+            ]
+            .into_iter()
+            .zip_eq(keccak_table.table_exprs(meta))
+            .map(|(arg, table)| (enable.expr() * arg, table))
+            .collect()
+        });
+
+        meta.create_gate("eth mpt: fork of the old and new paths", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let q_seg_first = meta.query_fixed(q_seg_first, Rotation::cur());
+            let q_seg_last = meta.query_fixed(q_seg_last, Rotation::cur());
+            let is_common_prev = meta.query_advice(is_common, Rotation::prev());
+            let is_common = meta.query_advice(is_common, Rotation::cur());
+            let is_fork = meta.query_advice(is_fork, Rotation::cur());
+            let [old_exists, new_exists] =
+                paths.map(|path| meta.query_advice(path.exists, Rotation::cur()));
+            // the key is inserted or deleted
+            let is_changed =
+                old_exists.expr() + new_exists.expr() - 2.expr() * old_exists * new_exists;
+
+            cb.require_boolean("is_common is boolean", is_common.expr());
+            cb.condition(not::expr(q_seg_first.expr()), |cb| {
+                cb.require_zero(
+                    "the paths share their nodes down to the fork",
+                    not::expr(is_common_prev.expr()) * is_common.expr(),
+                );
+            });
+            cb.condition(q_seg_last, |cb| {
+                cb.require_equal(
+                    "the paths fork iff the key is inserted or deleted",
+                    is_common.expr(),
+                    not::expr(seg_active(meta) * is_changed),
+                );
+            });
+            cb.require_equal(
+                "the fork is the first row the paths do not share",
+                is_fork.expr(),
+                not::expr(q_seg_first.expr()) * (is_common_prev - is_common.expr())
+                    + q_seg_first.expr() * not::expr(is_common.expr()),
+            );
+            cb.condition(is_fork.expr(), |cb| {
+                for path in paths.iter() {
+                    cb.require_equal(
+                        "a node starts at the fork",
+                        meta.query_advice(path.is_start, Rotation::cur()),
+                        1.expr(),
+                    );
+                }
+            });
+            cb.condition(is_common, |cb| {
+                let [old, new] = paths;
+                for (old, new) in [
+                    (old.is_start, new.is_start),
+                    (old.is_branch, new.is_branch),
+                    (old.is_extension, new.is_extension),
+                    (old.is_leaf, new.is_leaf),
+                    (old.is_tail, new.is_tail),
+                    (old.nibble, new.nibble),
+                    (old.is_diverged, new.is_diverged),
+                    (old.branch.pre_len, new.branch.pre_len),
+                    (old.branch.suffix_len, new.branch.suffix_len),
+                ] {
+                    cb.require_equal(
+                        "shared nodes have the same path",
+                        meta.query_advice(old, Rotation::cur()),
+                        meta.query_advice(new, Rotation::cur()),
+                    );
+                }
+            });
+            cb.require_equal(
+                "a tail at the fork follows a branch",
+                meta.query_advice(is_tail_fork, Rotation::cur()),
+                is_fork
+                    * not::expr(q_seg_first)
+                    * sum::expr(paths.map(|path| meta.query_advice(path.is_tail, Rotation::cur()))),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate(
+            "eth mpt: old and new branches only differ by their item",
+            |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                // The bytes of a branch before its item are its header followed by
+                // the children before the item, and the bytes after its item the
+                // children after it.
+                let [old_children_before, new_children_before] = paths.map(|path| {
+                    meta.query_advice(path.branch.pre_acc, Rotation::cur())
+                        - meta.query_advice(path.branch.hdr_rlc, Rotation::cur())
+                            * meta.query_advice(path.branch.pre_pow, Rotation::cur())
+                });
+                let [old_children_after, new_children_after] = paths.map(|path| {
+                    meta.query_advice(path.node.rlc, Rotation::cur())
+                        - meta.query_advice(path.branch.item_acc, Rotation::cur())
+                            * meta.query_advice(path.branch.suffix_pow, Rotation::cur())
+                });
+                cb.require_equal(
+                    "children before the item are unchanged",
+                    old_children_before,
+                    new_children_before,
+                );
+                cb.require_equal(
+                    "children after the item are unchanged",
+                    old_children_after,
+                    new_children_after,
+                );
+
+                cb.gate(
+                    meta.query_fixed(q_enable, Rotation::cur())
+                        * meta.query_advice(is_common, Rotation::cur())
+                        * meta.query_advice(paths[0].is_start, Rotation::cur())
+                        * meta.query_advice(paths[0].is_branch, Rotation::cur()),
+                )
+            },
+        );
+
+        meta.create_gate(
+            "eth mpt: other children of the branch at a tail fork",
+            |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                let nibble = meta.query_advice(nibble, Rotation::prev());
+                let [first, second] =
+                    other_nibbles.map(|other| meta.query_advice(other, Rotation::cur()));
+                cb.require_equal(
+                    "the branch has two other children",
+                    (first.expr() - nibble.expr())
+                        * (second.expr() - nibble)
+                        * (second - first)
+                        * meta.query_advice(other_inv, Rotation::cur()),
+                    1.expr(),
+                );
+
+                cb.gate(
+                    meta.query_fixed(q_enable, Rotation::cur())
+                        * meta.query_advice(is_tail_fork, Rotation::cur()),
+                )
+            },
+        );
+        for other in other_nibbles {
+            meta.lookup("eth mpt: other nibble is below 16", |meta| {
+                let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+                vec![(
+                    q_enable * meta.query_advice(other, Rotation::cur()) * 16.expr(),
+                    u8_table.into(),
+                )]
+            });
+        }
+
+        for (side, path) in paths.iter().enumerate() {
+            let path = *path;
+            let other = paths[1 - side];
+            let node = path.node;
+            let branch = path.branch;
+
+            meta.create_gate("eth mpt: node spans", |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                let q_seg_first = meta.query_fixed(q_seg_first, Rotation::cur());
+                let q_seg_last = meta.query_fixed(q_seg_last, Rotation::cur());
+                let q_storage = meta.query_fixed(q_storage, Rotation::cur());
+                let is_start = meta.query_advice(path.is_start, Rotation::cur());
+                let is_start_next = meta.query_advice(path.is_start, Rotation::next());
+                let types = [
+                    path.is_branch,
+                    path.is_extension,
+                    path.is_leaf,
+                    path.is_tail,
+                ];
+                let [is_branch, is_extension, is_leaf, is_tail] =
+                    types.map(|column| meta.query_advice(column, Rotation::cur()));
+                let [is_branch_next, _, _, is_tail_next] =
+                    types.map(|column| meta.query_advice(column, Rotation::next()));
+                let is_diverged = meta.query_advice(path.is_diverged, Rotation::cur());
+                let exists = meta.query_advice(path.exists, Rotation::cur());
+
+                cb.require_equal(
+                    "the key exists in the trie of the segment",
+                    exists.expr(),
+                    not::expr(q_storage.expr())
+                        * meta.query_advice(unit.account_exists[side], Rotation::cur())
+                        + q_storage * meta.query_advice(unit.storage_exists[side], Rotation::cur()),
+                );
+                cb.require_boolean("is_start is boolean", is_start.expr());
+                for is_type in [&is_branch, &is_extension, &is_leaf, &is_tail] {
+                    cb.require_boolean("node type is boolean", is_type.expr());
+                }
+                cb.require_equal(
+                    "each row of an active segment has one node type",
+                    sum::expr([&is_branch, &is_extension, &is_leaf, &is_tail]),
+                    seg_active(meta),
+                );
+                cb.require_zero(
+                    "only the path of an absent key has a tail",
+                    is_tail.expr() * exists.expr(),
+                );
+                cb.condition(q_seg_first.expr(), |cb| {
+                    cb.require_equal(
+                        "a node starts at the root",
+                        is_start.expr(),
+                        seg_active(meta),
+                    );
+                });
+                cb.condition(not::expr(q_seg_first) * is_tail.expr() * is_start, |cb| {
+                    cb.require_zero(
+                        "a tail follows an empty child of a branch",
+                        meta.query_advice(path.is_branch, Rotation::prev())
+                            * not::expr(meta.query_advice(node.item_none, Rotation::prev())),
+                    );
+                });
+                cb.condition(q_seg_last.expr(), |cb| {
+                    cb.require_zero(
+                        "the path ends with a leaf or a tail",
+                        is_branch.expr() + is_extension.expr(),
+                    );
+                });
+                cb.condition(q_seg_last.expr() * seg_active(meta), |cb| {
+                    cb.require_zero(
+                        "the path of an existing key ends with its leaf",
+                        exists.expr() * (not::expr(is_leaf.expr()) + is_diverged.expr()),
+                    );
+                    cb.require_zero(
+                        "the path of an absent key ends with a tail or a diverging node",
+                        not::expr(exists) * (1.expr() - is_tail.expr() - is_diverged.expr()),
+                    );
+                });
+                let is_last = q_seg_last;
+                cb.require_zero(
+                    "a branch consumes one nibble",
+                    not::expr(is_last.expr()) * is_branch * not::expr(is_start_next.expr()),
+                );
+                cb.condition(
+                    not::expr(is_last.expr()) * is_extension * is_start_next.expr(),
+                    |cb| {
+                        cb.require_equal(
+                            "an extension is followed by a branch or a tail",
+                            is_branch_next + is_tail_next.expr(),
+                            1.expr(),
+                        );
+                        cb.require_equal(
+                            "a tail follows an extension iff it diverges from the key",
+                            is_tail_next,
+                            is_diverged,
+                        );
+                    },
+                );
+                cb.require_zero(
+                    "a leaf or a tail runs to the end of the path",
+                    not::expr(is_last) * (is_leaf + is_tail) * is_start_next,
+                );
+
+                cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+            });
+
+            meta.create_gate("eth mpt: node is constant over its span", |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                let columns = [
+                    path.is_branch,
+                    path.is_extension,
+                    path.is_leaf,
+                    path.is_tail,
+                    node.id,
+                    node.item,
+                    node.item_bytes_rlc,
+                    node.item_len,
+                    node.item_none,
+                    node.item_is_hash,
+                ];
+                for column in columns {
+                    cb.require_equal(
+                        "node column is constant over its span",
+                        meta.query_advice(column, Rotation::next()),
+                        meta.query_advice(column, Rotation::cur()),
+                    );
+                }
+
+                cb.gate(
+                    meta.query_fixed(q_enable, Rotation::cur())
+                        * not::expr(meta.query_fixed(q_seg_last, Rotation::cur()))
+                        * not::expr(meta.query_advice(path.is_start, Rotation::next())),
+                )
+            });
+
+            meta.create_gate("eth mpt: divergence of the path from the key", |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                let q_seg_first = meta.query_fixed(q_seg_first, Rotation::cur());
+                let is_start = meta.query_advice(path.is_start, Rotation::cur());
+                let is_diverged_prev = meta.query_advice(path.is_diverged, Rotation::prev());
+                let is_diverged = meta.query_advice(path.is_diverged, Rotation::cur());
+                let diverge_start = meta.query_advice(path.diverge_start, Rotation::cur());
+                let nibble_diff = meta.query_advice(path.nibble, Rotation::cur())
+                    - meta.query_advice(nibble, Rotation::cur());
+
+                cb.require_boolean("is_diverged is boolean", is_diverged.expr());
+                cb.require_zero(
+                    "only the path of a leaf or an extension diverges from the key",
+                    is_diverged.expr()
+                        * not::expr(
+                            meta.query_advice(path.is_leaf, Rotation::cur())
+                                + meta.query_advice(path.is_extension, Rotation::cur()),
+                        ),
+                );
+                cb.condition(not::expr(q_seg_first) * not::expr(is_start.expr()), |cb| {
+                    cb.require_zero(
+                        "a diverging path stays diverged",
+                        is_diverged_prev.expr() * not::expr(is_diverged.expr()),
+                    );
+                });
+                cb.condition(not::expr(is_diverged.expr()), |cb| {
+                    cb.require_zero("the path follows the key", nibble_diff.expr());
+                });
+                cb.require_equal(
+                    "the path diverges at the first diverged row of a node",
+                    diverge_start.expr(),
+                    is_diverged
+                        * (is_start.expr() + not::expr(is_start) * not::expr(is_diverged_prev)),
+                );
+                cb.condition(diverge_start.expr(), |cb| {
+                    cb.require_equal(
+                        "the path differs from the key where it diverges",
+                        nibble_diff * meta.query_advice(path.diverge_inv, Rotation::cur()),
+                        1.expr(),
+                    );
+                });
+                cb.require_equal(
+                    "the sibling is checked where the path diverges below the fork",
+                    meta.query_advice(path.has_sibling, Rotation::cur()),
+                    not::expr(meta.query_advice(is_common, Rotation::cur())) * diverge_start,
+                );
+
+                cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+            });
+            meta.lookup("eth mpt: path nibble range", |meta| {
+                let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+                vec![(
+                    q_enable * meta.query_advice(path.nibble, Rotation::cur()),
+                    u8_table.into(),
+                )]
+            });
+            meta.lookup("eth mpt: path nibble is below 16", |meta| {
+                let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+                vec![(
+                    q_enable * meta.query_advice(path.nibble, Rotation::cur()) * 16.expr(),
+                    u8_table.into(),
+                )]
+            });
+
+            meta.create_gate("eth mpt: nodes are linked by their hashes", |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                let q_seg_first = meta.query_fixed(q_seg_first, Rotation::cur());
+                let is_leaf = meta.query_advice(path.is_leaf, Rotation::cur());
+                let is_tail = meta.query_advice(path.is_tail, Rotation::cur());
+                let is_node = meta.query_advice(path.is_branch, Rotation::cur())
+                    + meta.query_advice(path.is_extension, Rotation::cur())
+                    + is_leaf.expr();
+                let hash = meta.query_advice(node.hash, Rotation::cur());
+                let root = seg_root(meta, side);
+                let item_is_hash_prev = meta.query_advice(node.item_is_hash, Rotation::prev());
+
+                cb.condition(q_seg_first.expr() * is_node.expr(), |cb| {
+                    cb.require_equal("first node is the root", hash.expr(), root.expr());
+                });
+                cb.condition(q_seg_first.expr() * is_tail, |cb| {
+                    cb.require_equal(
+                        "a tail at the root is an empty trie",
+                        root.expr(),
+                        empty_root.expr(),
+                    );
+                });
+                let is_child = not::expr(q_seg_first) * is_node;
+                cb.require_zero(
+                    "node is not an empty child",
+                    is_child.expr() * meta.query_advice(node.item_none, Rotation::prev()),
+                );
+                cb.condition(is_child.expr() * item_is_hash_prev.expr(), |cb| {
+                    cb.require_equal(
+                        "node is the child of its parent",
+                        meta.query_advice(node.item, Rotation::prev()),
+                        hash,
+                    );
+                });
+                cb.condition(is_child * not::expr(item_is_hash_prev), |cb| {
+                    cb.require_equal(
+                        "an embedded node is the child of a branch",
+                        meta.query_advice(path.is_branch, Rotation::prev()),
+                        1.expr(),
+                    );
+                    cb.require_equal("an embedded node is a leaf", is_leaf, 1.expr());
+                    cb.require_equal(
+                        "an embedded node is its child in the parent",
+                        meta.query_advice(node.item_bytes_rlc, Rotation::prev()),
+                        meta.query_advice(node.rlc, Rotation::cur()),
+                    );
+                    cb.require_equal(
+                        "length of an embedded node",
+                        meta.query_advice(node.item_len, Rotation::prev()),
+                        meta.query_advice(node.len, Rotation::cur()),
+                    );
+                });
+
+                cb.gate(
+                    meta.query_fixed(q_enable, Rotation::cur())
+                        * meta.query_advice(path.is_start, Rotation::cur()),
+                )
+            });
+            meta.lookup(
+                "eth mpt: hashed node is not shorter than 32 bytes",
+                |meta| {
+                    let enable = meta.query_fixed(q_enable, Rotation::cur())
+                        * not::expr(meta.query_fixed(q_seg_first, Rotation::cur()))
+                        * meta.query_advice(path.is_start, Rotation::cur())
+                        * (meta.query_advice(path.is_branch, Rotation::cur())
+                            + meta.query_advice(path.is_extension, Rotation::cur())
+                            + meta.query_advice(path.is_leaf, Rotation::cur()))
+                        * meta.query_advice(node.item_is_hash, Rotation::prev());
+                    vec![(
+                        enable * (meta.query_advice(node.len, Rotation::cur()) - 32.expr()),
+                        u8_table.into(),
+                    )]
+                },
+            );
+
+            // The child of a branch or an extension is empty, the hash of the
+            // child, or the child itself if it is shorter than 32 bytes.
+            let child_enable = |meta: &mut VirtualCells<F>| {
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * meta.query_advice(path.is_start, Rotation::cur())
+                    * (meta.query_advice(path.is_branch, Rotation::cur())
+                        + meta.query_advice(path.is_extension, Rotation::cur()))
+            };
+            meta.create_gate("eth mpt: reference to the child", |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                let item_none = meta.query_advice(node.item_none, Rotation::cur());
+                let item_is_hash = meta.query_advice(node.item_is_hash, Rotation::cur());
+                let item_len = meta.query_advice(node.item_len, Rotation::cur());
+
+                cb.require_boolean("item_is_hash is boolean", item_is_hash.expr());
+                cb.require_zero(
+                    "an empty child has no hash",
+                    item_none.expr() * item_is_hash.expr(),
+                );
+                cb.require_zero("an empty child has no bytes", item_none * item_len.expr());
+                cb.condition(item_is_hash, |cb| {
+                    cb.require_equal("a hash is 32 bytes", item_len, 32.expr());
+                });
+
+                cb.gate(child_enable(meta))
+            });
+            meta.lookup("eth mpt: embedded child is shorter than 32 bytes", |meta| {
+                let is_embedded = not::expr(
+                    meta.query_advice(node.item_none, Rotation::cur())
+                        + meta.query_advice(node.item_is_hash, Rotation::cur()),
+                );
+                vec![(
+                    child_enable(meta)
+                        * is_embedded
+                        * (31.expr() - meta.query_advice(node.item_len, Rotation::cur())),
+                    u8_table.into(),
+                )]
+            });
+
+            meta.create_gate("eth mpt: hex-prefix encoding of the nibbles", |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                let q_seg_last = meta.query_fixed(q_seg_last, Rotation::cur());
+                let span_continues = not::expr(q_seg_last)
+                    * not::expr(meta.query_advice(path.is_start, Rotation::next()));
+                let is_low_next = meta.query_advice(path.is_low, Rotation::next());
+                let is_low = meta.query_advice(path.is_low, Rotation::cur());
+                let acc = meta.query_advice(path.path_acc, Rotation::cur());
+                let pow = meta.query_advice(path.path_pow, Rotation::cur());
+                let len = meta.query_advice(path.path_len_acc, Rotation::cur());
+
+                cb.condition(not::expr(span_continues.expr()), |cb| {
+                    cb.require_equal("last nibble of a span is low", is_low.expr(), 1.expr());
+                    cb.require_zero("path acc starts at 0", acc.expr());
+                    cb.require_equal("path pow starts at 1", pow.expr(), 1.expr());
+                    cb.require_zero("path len starts at 0", len.expr());
+                });
+                cb.condition(span_continues, |cb| {
+                    let is_high = not::expr(is_low.expr());
+                    let byte = meta.query_advice(path.nibble, Rotation::cur()) * 16.expr()
+                        + meta.query_advice(path.nibble, Rotation::next());
+                    let pow_next = meta.query_advice(path.path_pow, Rotation::next());
+
+                    cb.require_equal("nibbles alternate", is_low, not::expr(is_low_next));
+                    cb.require_equal(
+                        "a high nibble completes a byte",
+                        acc,
+                        meta.query_advice(path.path_acc, Rotation::next())
+                            + is_high.expr() * byte * pow_next.expr(),
+                    );
+                    cb.require_equal(
+                        "path pow",
+                        pow,
+                        pow_next.expr()
+                            + is_high.expr() * pow_next * (keccak_rand.expr() - 1.expr()),
+                    );
+                    cb.require_equal(
+                        "path len",
+                        len,
+                        meta.query_advice(path.path_len_acc, Rotation::next()) + is_high,
+                    );
+                });
+
+                cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+            });
+
+            // The hex-prefix encoding of the path from the row to the end of
+            // its span: the first byte holds the flags of the path, and its
+            // first nibble if the number of nibbles is odd.
+            let hp_path = |meta: &mut VirtualCells<F>, rotation: Rotation| {
+                let is_low = meta.query_advice(path.is_low, rotation);
+                let first_byte = meta.query_advice(path.is_leaf, rotation) * 32.expr()
+                    + is_low.expr() * 16.expr()
+                    + is_low * meta.query_advice(path.nibble, rotation);
+                (
+                    first_byte * meta.query_advice(path.path_pow, rotation)
+                        + meta.query_advice(path.path_acc, rotation),
+                    meta.query_advice(path.path_len_acc, rotation) + 1.expr(),
+                )
+            };
+            meta.create_gate("eth mpt: path of extensions and leaves", |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                let (path_rlc, path_len) = hp_path(meta, Rotation::cur());
+                cb.condition(
+                    meta.query_advice(path.is_extension, Rotation::cur())
+                        + meta.query_advice(path.is_leaf, Rotation::cur()),
+                    |cb| {
+                        cb.require_equal(
+                            "path of the node",
+                            meta.query_advice(path.path_rlc, Rotation::cur()),
+                            path_rlc,
+                        );
+                        cb.require_equal(
+                            "path length of the node",
+                            meta.query_advice(path.path_len, Rotation::cur()),
+                            path_len,
+                        );
+                    },
+                );
+
+                cb.gate(
+                    meta.query_fixed(q_enable, Rotation::cur())
+                        * meta.query_advice(path.is_start, Rotation::cur()),
+                )
+            });
+
+            let node_enable = |meta: &mut VirtualCells<F>| {
+                meta.query_advice(path.is_start, Rotation::cur())
+                    * (meta.query_advice(path.is_branch, Rotation::cur())
+                        + meta.query_advice(path.is_extension, Rotation::cur())
+                        + meta.query_advice(path.is_leaf, Rotation::cur()))
+            };
+
+            meta.lookup_any("eth mpt: length of the node in RLP table", |meta| {
+                let enable = node_enable(meta);
+                vec![
+                    1.expr(), // q_enable
+                    meta.query_advice(node.id, Rotation::cur()),
+                    node_format(meta, &path),
+                    RlpTag::Len.expr(),
+                    meta.query_advice(node.len, Rotation::cur()),
+                    0.expr(), // tag_bytes_rlc
+                    meta.query_advice(node.len_bytes, Rotation::cur()),
+                    1.expr(), // is_output
+                    0.expr(), // is_none
+                    0.expr(), // access_list_idx
+                    0.expr(), // storage_key_idx
+                ]
+                .into_iter()
+                .zip_eq(rlp_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.expr() * arg, table))
+                .collect()
+            });
+
+            meta.lookup_any("eth mpt: rlc of the node in RLP table", |meta| {
+                let enable = node_enable(meta);
+                vec![
+                    1.expr(), // q_enable
+                    meta.query_advice(node.id, Rotation::cur()),
+                    node_format(meta, &path),
+                    RlpTag::RLC.expr(),
+                    meta.query_advice(node.rlc, Rotation::cur()),
+                    0.expr(), // tag_bytes_rlc
+                    0.expr(), // tag_length
+                    1.expr(), // is_output
+                    0.expr(), // is_none
+                    0.expr(), // access_list_idx
+                    0.expr(), // storage_key_idx
+                ]
+                .into_iter()
+                .zip_eq(rlp_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.expr() * arg, table))
+                .collect()
+            });
+
+            meta.lookup_any("eth mpt: hash of the node in keccak table", |meta| {
+                let enable = node_enable(meta);
+                vec![
+                    1.expr(), // q_enable
+                    1.expr(), // is_final
+                    meta.query_advice(node.rlc, Rotation::cur()),
+                    meta.query_advice(node.len, Rotation::cur()),
+                    meta.query_advice(node.hash, Rotation::cur()),
+                ]
+                .into_iter()
+                .zip_eq(keccak_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.expr() * arg, table))
+                .collect()
+            });
+
+            meta.lookup_any("eth mpt: item of the node in RLP table", |meta| {
+                let enable = node_enable(meta);
+                let is_branch = meta.query_advice(path.is_branch, Rotation::cur());
+                let is_extension = meta.query_advice(path.is_extension, Rotation::cur());
+                let is_leaf = meta.query_advice(path.is_leaf, Rotation::cur());
+                let tag = (is_branch.expr() + is_extension.expr()) * Tag::MptChild.expr()
+                    + is_leaf * Tag::Data.expr();
+                // the children of a node are indexed from 1
+                let child_idx = is_branch
+                    * (meta.query_advice(path.nibble, Rotation::cur()) + 1.expr())
+                    + is_extension;
+
+                vec![
+                    1.expr(), // q_enable
+                    meta.query_advice(node.id, Rotation::cur()),
+                    node_format(meta, &path),
+                    tag,
+                    meta.query_advice(node.item, Rotation::cur()),
+                    meta.query_advice(node.item_bytes_rlc, Rotation::cur()),
+                    meta.query_advice(node.item_len, Rotation::cur()),
+                    1.expr(), // is_output
+                    meta.query_advice(node.item_none, Rotation::cur()),
+                    child_idx,
+                    0.expr(), // storage_key_idx
+                ]
+                .into_iter()
+                .zip_eq(rlp_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.expr() * arg, table))
+                .collect()
+            });
+
+            meta.lookup_any("eth mpt: path of the node in RLP table", |meta| {
+                let enable = meta.query_advice(path.is_start, Rotation::cur())
+                    * (meta.query_advice(path.is_extension, Rotation::cur())
+                        + meta.query_advice(path.is_leaf, Rotation::cur()));
+                let path_rlc = meta.query_advice(path.path_rlc, Rotation::cur());
+                vec![
+                    1.expr(), // q_enable
+                    meta.query_advice(node.id, Rotation::cur()),
+                    node_format(meta, &path),
+                    Tag::MptPath.expr(),
+                    path_rlc.expr(),
+                    path_rlc,
+                    meta.query_advice(path.path_len, Rotation::cur()),
+                    1.expr(), // is_output
+                    0.expr(), // is_none
+                    0.expr(), // access_list_idx
+                    0.expr(), // storage_key_idx
+                ]
+                .into_iter()
+                .zip_eq(rlp_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.expr() * arg, table))
+                .collect()
+            });
+
+            let account = accounts[side];
+            meta.lookup_any("eth mpt: fields of the account in RLP table", |meta| {
+                let enable = meta.query_fixed(q_account_field, Rotation::cur())
+                    * meta.query_advice(path.exists, Rotation::cur());
+                vec![
+                    1.expr(), // q_enable
+                    meta.query_advice(account.id, Rotation::cur()),
+                    Format::MptAccount.expr(),
+                    meta.query_fixed(account_field_tag, Rotation::cur()),
+                    meta.query_advice(account.field_value, Rotation::cur()),
+                    meta.query_advice(account.field_bytes_rlc, Rotation::cur()),
+                    meta.query_advice(account.field_len, Rotation::cur()),
+                    1.expr(), // is_output
+                    meta.query_advice(account.field_none, Rotation::cur()),
+                    0.expr(), // access_list_idx
+                    0.expr(), // storage_key_idx
+                ]
+                .into_iter()
+                .zip_eq(rlp_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.expr() * arg, table))
+                .collect()
+            });
+
+            // The bytes of a branch are read from the RLP data table at the
+            // ends of its header, of the children before the item, and of the
+            // item.
+            let branch_enable = |meta: &mut VirtualCells<F>| {
+                meta.query_advice(path.is_start, Rotation::cur())
+                    * meta.query_advice(path.is_branch, Rotation::cur())
+            };
+            meta.lookup_any(
+                "eth mpt: first byte of the branch in RLP data table",
+                |meta| {
+                    let enable = branch_enable(meta);
+                    vec![
+                        (
+                            meta.query_advice(node.id, Rotation::cur()),
+                            rlp_data_table.tx_id,
+                        ),
+                        (Format::MptBranch.expr(), rlp_data_table.format),
+                        (1.expr(), rlp_data_table.byte_idx),
+                        (
+                            meta.query_advice(branch.hdr_byte, Rotation::cur()),
+                            rlp_data_table.byte_value,
+                        ),
+                    ]
+                    .into_iter()
+                    .map(|(arg, column)| {
+                        (
+                            enable.expr() * arg,
+                            meta.query_advice(column, Rotation::cur()),
+                        )
+                    })
+                    .collect()
+                },
+            );
+            for (name, end, acc) in [
+                (
+                    "eth mpt: header of the branch in RLP data table",
+                    0,
+                    branch.hdr_rlc,
+                ),
+                (
+                    "eth mpt: children before the item in RLP data table",
+                    1,
+                    branch.pre_acc,
+                ),
+                (
+                    "eth mpt: item of the branch in RLP data table",
+                    2,
+                    branch.item_acc,
+                ),
+            ] {
+                meta.lookup_any(name, |meta| {
+                    let enable = branch_enable(meta);
+                    let byte_idx = [
+                        hdr_len(meta, &path),
+                        meta.query_advice(branch.pre_len, Rotation::cur()),
+                        item_encoding_len(meta, &path),
+                    ]
+                    .into_iter()
+                    .take(end + 1)
+                    .fold(0.expr(), |acc, len| acc + len);
+                    vec![
+                        (
+                            meta.query_advice(node.id, Rotation::cur()),
+                            rlp_data_table.tx_id,
+                        ),
+                        (Format::MptBranch.expr(), rlp_data_table.format),
+                        (byte_idx, rlp_data_table.byte_idx),
+                        (
+                            meta.query_advice(acc, Rotation::cur()),
+                            rlp_data_table.bytes_rlc,
+                        ),
+                    ]
+                    .into_iter()
+                    .map(|(arg, column)| {
+                        (
+                            enable.expr() * arg,
+                            meta.query_advice(column, Rotation::cur()),
+                        )
+                    })
+                    .collect()
+                });
+            }
+            meta.lookup("eth mpt: list header of the branch", |meta| {
+                let is_long = meta.query_advice(branch.is_long, Rotation::cur());
+                let hdr_byte = meta.query_advice(branch.hdr_byte, Rotation::cur());
+                vec![(
+                    branch_enable(meta)
+                        * (is_long.expr() * (hdr_byte.expr() - 0xf8.expr())
+                            + not::expr(is_long) * (0xf7.expr() - hdr_byte)),
+                    u8_table.into(),
+                )]
+            });
+            for (len, pow) in [
+                (node.item_len, branch.item_pow),
+                (branch.pre_len, branch.pre_pow),
+                (branch.suffix_len, branch.suffix_pow),
+            ] {
+                meta.lookup_any(
+                    "eth mpt: bytes of the branch in pow of rand table",
+                    |meta| {
+                        let enable = branch_enable(meta);
+                        vec![
+                            1.expr(), // q_enable
+                            meta.query_advice(len, Rotation::cur()),
+                            meta.query_advice(pow, Rotation::cur()),
+                        ]
+                        .into_iter()
+                        .zip_eq(pow_of_rand_table.table_exprs(meta))
+                        .map(|(arg, table)| (enable.expr() * arg, table))
+                        .collect()
+                    },
+                );
+            }
+            meta.create_gate("eth mpt: bytes of the branch around its item", |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                let is_long = meta.query_advice(branch.is_long, Rotation::cur());
+                let item_none = meta.query_advice(node.item_none, Rotation::cur());
+                let item_is_hash = meta.query_advice(node.item_is_hash, Rotation::cur());
+                let is_embedded = not::expr(item_none.expr() + item_is_hash.expr());
+                let pre_acc = meta.query_advice(branch.pre_acc, Rotation::cur());
+
+                cb.require_boolean("is_long is boolean", is_long);
+                // an embedded child is its own encoding, a string has a header
+                cb.require_equal(
+                    "item of the branch",
+                    meta.query_advice(branch.item_acc, Rotation::cur()),
+                    meta.query_advice(branch.item_pow, Rotation::cur())
+                        * (pre_acc
+                            * (is_embedded.expr() + not::expr(is_embedded) * keccak_rand.expr())
+                            + item_none * 0x80.expr()
+                            + item_is_hash * 0xa0.expr())
+                        + meta.query_advice(node.item_bytes_rlc, Rotation::cur()),
+                );
+                cb.require_equal(
+                    "length of the branch",
+                    meta.query_advice(node.len, Rotation::cur()),
+                    hdr_len(meta, &path)
+                        + meta.query_advice(branch.pre_len, Rotation::cur())
+                        + item_encoding_len(meta, &path)
+                        + meta.query_advice(branch.suffix_len, Rotation::cur()),
+                );
+
+                cb.gate(meta.query_fixed(q_enable, Rotation::cur()) * branch_enable(meta))
+            });
+
+            // Below the fork, the path without the key is the path with the key
+            // until it diverges, or ends with an empty child.
+            meta.create_gate("eth mpt: path without the key below the fork", |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                let is_absent = not::expr(meta.query_advice(path.exists, Rotation::cur()))
+                    * not::expr(meta.query_advice(is_common, Rotation::cur()));
+                let is_start = meta.query_advice(path.is_start, Rotation::cur());
+                let is_extension = meta.query_advice(path.is_extension, Rotation::cur());
+                let is_leaf = meta.query_advice(path.is_leaf, Rotation::cur());
+                let is_diverged = meta.query_advice(path.is_diverged, Rotation::cur());
+
+                cb.require_zero(
+                    "no branch below the fork",
+                    is_absent.expr() * meta.query_advice(path.is_branch, Rotation::cur()),
+                );
+                cb.require_zero(
+                    "an empty child is replaced by the leaf of the key",
+                    is_absent.expr()
+                        * meta.query_advice(is_fork, Rotation::cur())
+                        * meta.query_advice(path.is_tail, Rotation::cur())
+                        * not::expr(meta.query_advice(other.is_leaf, Rotation::cur())),
+                );
+                cb.condition(
+                    is_absent * (is_extension + is_leaf) * not::expr(is_diverged),
+                    |cb| {
+                        cb.require_equal(
+                            "the nibbles above the divergence are an extension",
+                            meta.query_advice(other.is_extension, Rotation::cur()),
+                            1.expr(),
+                        );
+                        cb.require_equal(
+                            "the extension starts with the diverging node",
+                            meta.query_advice(other.is_start, Rotation::cur()),
+                            is_start,
+                        );
+                    },
+                );
+                cb.condition(meta.query_advice(path.has_sibling, Rotation::cur()), |cb| {
+                    cb.require_zero(
+                        "the path diverges above the last nibble",
+                        meta.query_fixed(q_seg_last, Rotation::cur()),
+                    );
+                    for (column, rotation, msg) in [
+                        (
+                            other.is_start,
+                            Rotation::cur(),
+                            "a branch starts at the divergence",
+                        ),
+                        (
+                            other.is_branch,
+                            Rotation::cur(),
+                            "a branch starts at the divergence",
+                        ),
+                        (
+                            other.is_start,
+                            Rotation::next(),
+                            "the leaf of the key follows",
+                        ),
+                        (
+                            other.is_leaf,
+                            Rotation::next(),
+                            "the leaf of the key follows",
+                        ),
+                    ] {
+                        cb.require_equal(msg, meta.query_advice(column, rotation), 1.expr());
+                    }
+                });
+
+                cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+            });
+
+            // The node where the path without the key diverges from it is the
+            // sibling of the leaf of the key in the new branch, without the
+            // nibbles down to the branch, or its child when the node is an
+            // extension diverging at its last nibble. The sibling is decoded
+            // for deletions, to prove that the branch removed had no other
+            // child, and for insertions when it is derived.
+            let is_deletion = side == 1;
+            let sibling_decoded = |meta: &mut VirtualCells<F>| {
+                if is_deletion {
+                    1.expr()
+                } else {
+                    meta.query_advice(sibling.is_derived, Rotation::cur())
+                }
+            };
+            let sibling_format = |meta: &mut VirtualCells<F>| {
+                let is_derived = meta.query_advice(sibling.is_derived, Rotation::cur());
+                is_derived.expr()
+                    * (meta.query_advice(path.is_leaf, Rotation::cur()) * Format::MptLeaf.expr()
+                        + meta.query_advice(path.is_extension, Rotation::cur())
+                            * Format::MptExtension.expr())
+                    + not::expr(is_derived) * Format::MptBranch.expr()
+            };
+            meta.create_gate("eth mpt: sibling of the leaf of the key", |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                let is_derived = meta.query_advice(sibling.is_derived, Rotation::cur());
+                let is_decoded = sibling_decoded(meta);
+                let item_is_hash = meta.query_advice(sibling.item_is_hash, Rotation::cur());
+                let item_len = meta.query_advice(sibling.item_len, Rotation::cur());
+                let sibling_item = meta.query_advice(sibling.item, Rotation::cur());
+
+                cb.require_equal(
+                    "the sibling is derived unless the extension diverges at its last nibble",
+                    is_derived.expr(),
+                    not::expr(
+                        meta.query_advice(path.is_extension, Rotation::cur())
+                            * meta.query_advice(path.is_start, Rotation::next()),
+                    ),
+                );
+                cb.require_boolean("item_is_hash is boolean", item_is_hash.expr());
+                cb.condition(item_is_hash.expr(), |cb| {
+                    cb.require_equal("a hash is 32 bytes", item_len.expr(), 32.expr());
+                });
+                // the 14 other children and the value of the branch are empty
+                cb.require_equal(
+                    "the branch has two children",
+                    meta.query_advice(other.node.len, Rotation::cur()) - hdr_len(meta, &other),
+                    15.expr()
+                        + item_encoding_len(meta, &other)
+                        + item_is_hash.expr()
+                        + item_len.expr(),
+                );
+                cb.condition(not::expr(is_derived.expr()), |cb| {
+                    cb.require_equal(
+                        "the sibling is the child of the extension",
+                        sibling_item.expr(),
+                        meta.query_advice(node.item, Rotation::cur()),
+                    );
+                    cb.require_equal(
+                        "length of the child of the extension",
+                        item_len.expr(),
+                        meta.query_advice(node.item_len, Rotation::cur()),
+                    );
+                });
+                cb.condition(is_decoded.expr() * item_is_hash.expr(), |cb| {
+                    cb.require_equal(
+                        "the sibling is referenced by its hash",
+                        sibling_item,
+                        meta.query_advice(sibling.hash, Rotation::cur()),
+                    );
+                });
+                cb.condition(is_decoded * not::expr(item_is_hash), |cb| {
+                    cb.require_equal(
+                        "the sibling is embedded",
+                        meta.query_advice(sibling.item_bytes_rlc, Rotation::cur()),
+                        meta.query_advice(sibling.rlc, Rotation::cur()),
+                    );
+                    cb.require_equal(
+                        "length of the embedded sibling",
+                        item_len,
+                        meta.query_advice(sibling.len, Rotation::cur()),
+                    );
+                    cb.require_equal(
+                        "an embedded sibling is derived",
+                        is_derived.expr(),
+                        1.expr(),
+                    );
+                    cb.require_equal(
+                        "an embedded sibling is a leaf",
+                        meta.query_advice(path.is_leaf, Rotation::cur()),
+                        1.expr(),
+                    );
+                });
+                cb.condition(is_derived, |cb| {
+                    let (path_rlc, path_len) = hp_path(meta, Rotation::next());
+                    cb.require_equal(
+                        "the path of the sibling is the rest of the path of the node",
+                        meta.query_advice(sibling.path_rlc, Rotation::cur()),
+                        path_rlc,
+                    );
+                    cb.require_equal(
+                        "path length of the sibling",
+                        meta.query_advice(sibling.path_len, Rotation::cur()),
+                        path_len,
+                    );
+                });
+
+                cb.gate(
+                    meta.query_fixed(q_enable, Rotation::cur())
+                        * meta.query_advice(path.has_sibling, Rotation::cur()),
+                )
+            });
+            meta.lookup_any("eth mpt: sibling in the branch in RLP table", |meta| {
+                let enable = meta.query_advice(path.has_sibling, Rotation::cur());
+                vec![
+                    1.expr(), // q_enable
+                    meta.query_advice(other.node.id, Rotation::cur()),
+                    Format::MptBranch.expr(),
+                    Tag::MptChild.expr(),
+                    meta.query_advice(sibling.item, Rotation::cur()),
+                    meta.query_advice(sibling.item_bytes_rlc, Rotation::cur()),
+                    meta.query_advice(sibling.item_len, Rotation::cur()),
+                    1.expr(), // is_output
+                    0.expr(), // is_none
+                    meta.query_advice(path.nibble, Rotation::cur()) + 1.expr(),
+                    0.expr(), // storage_key_idx
+                ]
+                .into_iter()
+                .zip_eq(rlp_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.expr() * arg, table))
+                .collect()
+            });
+            meta.lookup(
+                "eth mpt: embedded sibling is shorter than 32 bytes",
+                |meta| {
+                    vec![(
+                        meta.query_advice(path.has_sibling, Rotation::cur())
+                            * not::expr(meta.query_advice(sibling.item_is_hash, Rotation::cur()))
+                            * (31.expr() - meta.query_advice(sibling.item_len, Rotation::cur())),
+                        u8_table.into(),
+                    )]
+                },
+            );
+            meta.lookup(
+                "eth mpt: hashed sibling is not shorter than 32 bytes",
+                |meta| {
+                    vec![(
+                        meta.query_advice(path.has_sibling, Rotation::cur())
+                            * sibling_decoded(meta)
+                            * meta.query_advice(sibling.item_is_hash, Rotation::cur())
+                            * (meta.query_advice(sibling.len, Rotation::cur()) - 32.expr()),
+                        u8_table.into(),
+                    )]
+                },
+            );
+            meta.lookup_any("eth mpt: length of the sibling in RLP table", |meta| {
+                let enable =
+                    meta.query_advice(path.has_sibling, Rotation::cur()) * sibling_decoded(meta);
+                vec![
+                    1.expr(), // q_enable
+                    meta.query_advice(sibling.id, Rotation::cur()),
+                    sibling_format(meta),
+                    RlpTag::Len.expr(),
+                    meta.query_advice(sibling.len, Rotation::cur()),
+                    0.expr(), // tag_bytes_rlc
+                    meta.query_advice(sibling.len_bytes, Rotation::cur()),
+                    1.expr(), // is_output
+                    0.expr(), // is_none
+                    0.expr(), // access_list_idx
+                    0.expr(), // storage_key_idx
+                ]
+                .into_iter()
+                .zip_eq(rlp_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.expr() * arg, table))
+                .collect()
+            });
+            meta.lookup_any("eth mpt: rlc of the sibling in RLP table", |meta| {
+                let enable =
+                    meta.query_advice(path.has_sibling, Rotation::cur()) * sibling_decoded(meta);
+                vec![
+                    1.expr(), // q_enable
+                    meta.query_advice(sibling.id, Rotation::cur()),
+                    sibling_format(meta),
+                    RlpTag::RLC.expr(),
+                    meta.query_advice(sibling.rlc, Rotation::cur()),
+                    0.expr(), // tag_bytes_rlc
+                    0.expr(), // tag_length
+                    1.expr(), // is_output
+                    0.expr(), // is_none
+                    0.expr(), // access_list_idx
+                    0.expr(), // storage_key_idx
+                ]
+                .into_iter()
+                .zip_eq(rlp_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.expr() * arg, table))
+                .collect()
+            });
+            meta.lookup_any("eth mpt: hash of the sibling in keccak table", |meta| {
+                let enable = meta.query_advice(path.has_sibling, Rotation::cur())
+                    * sibling_decoded(meta)
+                    * meta.query_advice(sibling.item_is_hash, Rotation::cur());
+                vec![
+                    1.expr(), // q_enable
+                    1.expr(), // is_final
+                    meta.query_advice(sibling.rlc, Rotation::cur()),
+                    meta.query_advice(sibling.len, Rotation::cur()),
+                    meta.query_advice(sibling.hash, Rotation::cur()),
+                ]
+                .into_iter()
+                .zip_eq(keccak_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.expr() * arg, table))
+                .collect()
+            });
+            meta.lookup_any("eth mpt: path of the sibling in RLP table", |meta| {
+                let enable = meta.query_advice(path.has_sibling, Rotation::cur())
+                    * meta.query_advice(sibling.is_derived, Rotation::cur());
+                let path_rlc = meta.query_advice(sibling.path_rlc, Rotation::cur());
+                vec![
+                    1.expr(), // q_enable
+                    meta.query_advice(sibling.id, Rotation::cur()),
+                    node_format(meta, &path),
+                    Tag::MptPath.expr(),
+                    path_rlc.expr(),
+                    path_rlc,
+                    meta.query_advice(sibling.path_len, Rotation::cur()),
+                    1.expr(), // is_output
+                    0.expr(), // is_none
+                    0.expr(), // access_list_idx
+                    0.expr(), // storage_key_idx
+                ]
+                .into_iter()
+                .zip_eq(rlp_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.expr() * arg, table))
+                .collect()
+            });
+            meta.lookup_any("eth mpt: item of the sibling in RLP table", |meta| {
+                let enable = meta.query_advice(path.has_sibling, Rotation::cur())
+                    * meta.query_advice(sibling.is_derived, Rotation::cur());
+                let is_extension = meta.query_advice(path.is_extension, Rotation::cur());
+                let tag = is_extension.expr() * Tag::MptChild.expr()
+                    + meta.query_advice(path.is_leaf, Rotation::cur()) * Tag::Data.expr();
+                vec![
+                    1.expr(), // q_enable
+                    meta.query_advice(sibling.id, Rotation::cur()),
+                    node_format(meta, &path),
+                    tag,
+                    meta.query_advice(node.item, Rotation::cur()),
+                    meta.query_advice(node.item_bytes_rlc, Rotation::cur()),
+                    meta.query_advice(node.item_len, Rotation::cur()),
+                    1.expr(), // is_output
+                    meta.query_advice(node.item_none, Rotation::cur()),
+                    is_extension, // access_list_idx
+                    0.expr(),     // storage_key_idx
+                ]
+                .into_iter()
+                .zip_eq(rlp_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.expr() * arg, table))
+                .collect()
+            });
+
+            // The branch above a tail at the fork keeps two other children.
+            for other_nibble in other_nibbles {
+                meta.lookup_any("eth mpt: other child of the branch in RLP table", |meta| {
+                    let enable = meta.query_advice(is_tail_fork, Rotation::cur())
+                        * meta.query_advice(path.is_tail, Rotation::cur());
+                    vec![
+                        (
+                            1.expr(),
+                            meta.query_fixed(rlp_table.q_enable, Rotation::cur()),
+                        ),
+                        (
+                            meta.query_advice(node.id, Rotation::prev()),
+                            meta.query_advice(rlp_table.tx_id, Rotation::cur()),
+                        ),
+                        (
+                            Format::MptBranch.expr(),
+                            meta.query_advice(rlp_table.format, Rotation::cur()),
+                        ),
+                        (
+                            Tag::MptChild.expr(),
+                            meta.query_advice(rlp_table.rlp_tag, Rotation::cur()),
+                        ),
+                        (
+                            1.expr(),
+                            meta.query_advice(rlp_table.is_output, Rotation::cur()),
+                        ),
+                        (
+                            0.expr(),
+                            meta.query_advice(rlp_table.is_none, Rotation::cur()),
+                        ),
+                        (
+                            meta.query_advice(other_nibble, Rotation::cur()) + 1.expr(),
+                            meta.query_advice(rlp_table.access_list_idx, Rotation::cur()),
+                        ),
+                    ]
+                    .into_iter()
+                    .map(|(arg, table)| (enable.expr() * arg, table))
+                    .collect()
+                });
+            }
+        }
+
+        meta.create_gate("eth mpt: account id is constant", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            for account in accounts.iter() {
+                cb.require_equal(
+                    "account id is constant over the account segment",
+                    meta.query_advice(account.id, Rotation::next()),
+                    meta.query_advice(account.id, Rotation::cur()),
+                );
+            }
+
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * not::expr(meta.query_fixed(q_storage, Rotation::cur()))
+                    * not::expr(meta.query_fixed(q_seg_last, Rotation::cur())),
+            )
+        });
+
+        meta.create_gate("eth mpt: account in the leaf", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let last = Rotation(ROWS_PER_SEGMENT as i32 - 1);
+            let field = |meta: &mut VirtualCells<F>, side: usize, field: i32| {
+                meta.query_advice(accounts[side].field_value, Rotation(field))
+            };
+            let exists = unit
+                .account_exists
+                .map(|exists| meta.query_advice(exists, Rotation::cur()));
+            for (side, path) in paths.iter().enumerate() {
+                let storage_root = meta.query_advice(unit.storage_roots[side], Rotation::cur());
+                cb.condition(exists[side].expr(), |cb| {
+                    cb.require_equal(
+                        "leaf value is the account",
+                        field(meta, side, FIELD_RLC),
+                        meta.query_advice(path.node.item, last),
+                    );
+                    cb.require_equal(
+                        "leaf value length is the account length",
+                        field(meta, side, FIELD_LEN),
+                        meta.query_advice(path.node.item_len, last),
+                    );
+                    cb.require_equal(
+                        "storage root of the account",
+                        field(meta, side, FIELD_STORAGE_ROOT),
+                        storage_root.expr(),
+                    );
+                });
+                cb.condition(not::expr(exists[side].expr()), |cb| {
+                    cb.require_equal(
+                        "the storage of a missing account is empty",
+                        storage_root,
+                        empty_root.expr(),
+                    );
+                });
+            }
+
+            // the fields of a missing account are 0
+            for (field_idx, flag_idx) in [
+                (FIELD_NONCE, IS_NONCE),
+                (FIELD_BALANCE, IS_BALANCE),
+                (FIELD_CODE_HASH, IS_CODE_HASH),
+            ] {
+                let flag = flag(meta, flag_idx);
+                let [old_field, new_field] =
+                    [0, 1].map(|side| exists[side].expr() * field(meta, side, field_idx));
+                cb.condition(flag.expr(), |cb| {
+                    cb.require_equal(
+                        "old value of the updated field",
+                        old_field.expr(),
+                        meta.query_advice(unit.values[0], Rotation::cur()),
+                    );
+                    cb.require_equal(
+                        "new value of the updated field",
+                        new_field.expr(),
+                        meta.query_advice(unit.values[1], Rotation::cur()),
+                    );
+                });
+                cb.condition(not::expr(flag), |cb| {
+                    cb.require_equal("other fields are unchanged", old_field, new_field);
+                });
+            }
+
+            cb.gate(
+                meta.query_fixed(q_seg_first, Rotation::cur())
+                    * not::expr(meta.query_fixed(q_storage, Rotation::cur()))
+                    * meta.query_advice(unit.active, Rotation::cur()),
+            )
+        });
+
+        for (side, value) in storage_values.iter().enumerate() {
+            meta.create_gate("eth mpt: bytes of the storage value", |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                let q_seg_first = meta.query_fixed(q_seg_first, Rotation::cur());
+                // the accumulators are 0 before the first byte, and the power 1
+                let prev = |meta: &mut VirtualCells<F>, column: Column<Advice>| {
+                    not::expr(q_seg_first.expr()) * meta.query_advice(column, Rotation::prev())
+                };
+                let byte = meta.query_advice(value.byte, Rotation::cur());
+                let is_significant = meta.query_advice(value.is_significant, Rotation::cur());
+                let is_significant_prev = prev(meta, value.is_significant);
+                let step = 1.expr() + is_significant.expr() * (keccak_rand.expr() - 1.expr());
+
+                cb.require_boolean("is_significant is boolean", is_significant.expr());
+                cb.require_zero(
+                    "significant bytes are contiguous",
+                    is_significant_prev.expr() * not::expr(is_significant.expr()),
+                );
+                cb.require_zero(
+                    "leading bytes are 0",
+                    not::expr(is_significant.expr()) * byte.expr(),
+                );
+                cb.require_zero(
+                    "first significant byte is not 0",
+                    (is_significant.expr() - is_significant_prev)
+                        * (byte.expr() * meta.query_advice(value.byte_inv, Rotation::cur())
+                            - 1.expr()),
+                );
+                cb.require_equal(
+                    "value acc",
+                    meta.query_advice(value.value_acc, Rotation::cur()),
+                    prev(meta, value.value_acc) * word_rand.expr() + byte.expr(),
+                );
+                cb.require_equal(
+                    "bytes rlc",
+                    meta.query_advice(value.bytes_rlc, Rotation::cur()),
+                    prev(meta, value.bytes_rlc) * step.expr() + byte,
+                );
+                cb.require_equal(
+                    "bytes pow",
+                    meta.query_advice(value.bytes_pow, Rotation::cur()),
+                    (prev(meta, value.bytes_pow) + q_seg_first.expr()) * step,
+                );
+                cb.require_equal(
+                    "bytes len",
+                    meta.query_advice(value.bytes_len, Rotation::cur()),
+                    prev(meta, value.bytes_len) + is_significant,
+                );
+
+                cb.gate(
+                    meta.query_fixed(q_preimage, Rotation::cur())
+                        * meta.query_fixed(q_storage, Rotation::cur()),
+                )
+            });
+
+            meta.create_gate("eth mpt: storage value in the leaf", |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                let byte = meta.query_advice(value.byte, Rotation::cur());
+                let is_short = meta.query_advice(value.is_short, Rotation::cur());
+                let bytes_len = meta.query_advice(value.bytes_len, Rotation::cur());
+                let exists = meta.query_advice(unit.storage_exists[side], Rotation::cur());
+                // the leaf is at the end of the storage segment
+                let leaf = Rotation(ROWS_PER_SEGMENT as i32 - 32);
+
+                cb.require_equal(
+                    "a storage slot exists iff its value is not 0",
+                    exists.expr(),
+                    meta.query_advice(value.is_significant, Rotation::cur()),
+                );
+                cb.require_equal(
+                    "storage value",
+                    meta.query_advice(value.value_acc, Rotation::cur()),
+                    meta.query_advice(unit.values[side], Rotation::cur()),
+                );
+                cb.require_boolean("is_short is boolean", is_short.expr());
+                cb.require_zero(
+                    "a short value is a single byte",
+                    is_short.expr() * meta.query_advice(value.is_significant, Rotation::prev()),
+                );
+                cb.condition(exists, |cb| {
+                    cb.require_equal(
+                        "leaf value is the RLP encoding of the storage value",
+                        meta.query_advice(paths[side].node.item, leaf),
+                        is_short.expr() * byte.expr()
+                            + not::expr(is_short.expr())
+                                * ((bytes_len.expr() + 0x80.expr())
+                                    * meta.query_advice(value.bytes_pow, Rotation::cur())
+                                    + meta.query_advice(value.bytes_rlc, Rotation::cur())),
+                    );
+                    cb.require_equal(
+                        "leaf value length",
+                        meta.query_advice(paths[side].node.item_len, leaf),
+                        is_short.expr() + not::expr(is_short) * (bytes_len + 1.expr()),
+                    );
+                });
+
+                cb.gate(
+                    meta.query_fixed(q_value_last, Rotation::cur())
+                        * flag(meta, IS_STORAGE_CHANGED),
+                )
+            });
+
+            meta.lookup("eth mpt: storage value byte range", |meta| {
+                let enable = meta.query_fixed(q_preimage, Rotation::cur())
+                    * meta.query_fixed(q_storage, Rotation::cur());
+                vec![(
+                    enable * meta.query_advice(value.byte, Rotation::cur()),
+                    u8_table.into(),
+                )]
+            });
+            meta.lookup("eth mpt: short storage value is below 0x80", |meta| {
+                let enable = meta.query_fixed(q_value_last, Rotation::cur())
+                    * meta.query_advice(unit.storage_exists[side], Rotation::cur());
+                let byte = meta.query_advice(value.byte, Rotation::cur());
+                let is_short = meta.query_advice(value.is_short, Rotation::cur());
+                let is_single_byte =
+                    not::expr(meta.query_advice(value.is_significant, Rotation::prev()));
+                // a single byte value is short iff it is below 0x80
+                vec![(
+                    enable
+                        * (is_short.expr() * (0x7f.expr() - byte.expr())
+                            + not::expr(is_short) * is_single_byte * (byte - 0x80.expr())),
+                    u8_table.into(),
+                )]
+            });
+        }
+
+        meta.lookup_any("eth mpt: updates in mpt table proven", |meta| {
+            let q_unit_first = meta.query_fixed(q_unit_first, Rotation::cur());
+            let [old_root, new_root] = unit
+                .roots
+                .map(|root| meta.query_advice(root, Rotation::cur()));
+            let [old_value, new_value] = unit
+                .values
+                .map(|value| meta.query_advice(value, Rotation::cur()));
+
+            mpt_table
+                .table_exprs(meta)
+                .into_iter()
+                .zip_eq([
+                    1.expr(), // q_enable
+                    meta.query_advice(unit.address, Rotation::cur()),
+                    meta.query_advice(unit.storage_key, Rotation::cur()),
+                    meta.query_advice(unit.proof_type, Rotation::cur()),
+                    new_root,
+                    old_root,
+                    new_value,
+                    old_value,
+                ])
+                .map(|(arg, table)| (arg, q_unit_first.expr() * table))
+                .collect()
+        });
+
+        log::debug!("eth mpt circuit degree: {}", meta.degree());
+
+        Self {
+            q_enable,
+            q_unit_first,
+            q_seg_first,
+            q_seg_last,
+            q_storage,
+            q_preimage,
+            q_odd,
+            q_value_last,
+            q_account_field,
+            account_field_tag,
+            unit,
+            nibble,
+            key_rlc,
+            preimage_byte,
+            preimage_rlc,
+            preimage_acc,
+            is_common,
+            is_fork,
+            is_tail_fork,
+            other_nibbles,
+            other_inv,
+            paths,
+            sibling,
+            accounts,
+            storage_values,
+            mpt_table,
+            keccak_table,
+            rlp_table,
+            rlp_data_table,
+            pow_of_rand_table,
+            u8_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Keccak input RLC, or word RLC, of big endian bytes.
+fn bytes_rlc<F: Field>(bytes: &[u8], randomness: Value<F>) -> Value<F> {
+    randomness.map(|randomness| rlc::value(bytes.iter().rev(), randomness))
+}
+
+fn pow<F: Field>(randomness: Value<F>, exponent: usize) -> Value<F> {
+    randomness.map(|randomness| randomness.pow([exponent as u64, 0, 0, 0]))
+}
+
+fn known<F: Field>(value: u64) -> Value<F> {
+    Value::known(F::from(value))
+}
+
+fn inverse<F: Field>(value: Value<F>) -> Value<F> {
+    value.map(|value| value.invert().unwrap_or(F::zero()))
+}
+
+/// Number of bytes of the length in the list header of an RLP encoded list.
+fn len_bytes(bytes: &[u8]) -> u64 {
+    if bytes[0] < 0xf8 {
+        1
+    } else {
+        (bytes[0] - 0xf7) as u64
+    }
+}
+
+/// The type of the node consuming a nibble of a key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SpanType {
+    Branch,
+    Extension,
+    Leaf,
+    Tail,
+}
+
+/// A trie node along a path with its id in the RLP table, or the tail of a
+/// proof of absence, consuming `len` nibbles of the key from `start`.
+struct Span<'a> {
+    start: usize,
+    len: usize,
+    span_type: SpanType,
+    node: Option<(&'a TrieNodeRlp, TrieNode)>,
+}
+
+impl Span<'_> {
+    /// The nibbles of the path of an extension or a leaf.
+    fn path(&self) -> &[u8] {
+        match &self.node {
+            Some((_, TrieNode::Extension(path, _) | TrieNode::Leaf(path, _))) => path,
+            _ => &[],
+        }
+    }
+
+    /// The row where the path of the node diverges from `nibbles`, past the
+    /// span if it does not.
+    fn diverge_row(&self, nibbles: &[u8]) -> usize {
+        self.start + common_prefix_len(self.path(), &nibbles[self.start..])
+    }
+}
+
+/// The old and new nodes along the path of a key, and the sibling of the leaf
+/// of the key, with their ids in the RLP table.
+#[derive(Clone, Copy, Default)]
+struct PathNodes<'a> {
+    sides: [&'a [TrieNodeRlp]; 2],
+    sibling: Option<&'a TrieNodeRlp>,
+}
+
+impl<'a> PathNodes<'a> {
+    /// Split the nodes of `path` from the front of `nodes`, which are in the
+    /// order of `EthMptTrace::rlp_instances`.
+    fn split(path: &TriePath, nodes: &'a [TrieNodeRlp]) -> (Self, &'a [TrieNodeRlp]) {
+        let (old, nodes) = nodes.split_at(path.old_nodes.len());
+        let (new, nodes) = nodes.split_at(path.new_nodes.len());
+        let (sibling, nodes) = nodes.split_at(path.sibling.iter().count());
+        let path_nodes = Self {
+            sides: [old, new],
+            sibling: sibling.first(),
+        };
+        (path_nodes, nodes)
+    }
+}
+
+impl<F: Field> EthMptCircuitConfig<F> {
+    /// Assign the units proving the MPT updates, and load the MPT table. The
+    /// trie nodes are numbered in the RLP table from `first_node_id`.
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        mpt_updates: &MptUpdates,
+        first_node_id: u64,
+        n_units: usize,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let node_rlps = mpt_updates.eth_trie_node_rlps(first_node_id);
+        debug_assert!(mpt_updates.len() < n_units);
+
+        self.mpt_table
+            .load(layouter, mpt_updates, n_units, challenges.evm_word())?;
+
+        layouter.assign_region(
+            || "eth mpt circuit",
+            |mut region| {
+                for (i, unit) in mpt_updates
+                    .eth_updates()
+                    .zip_eq(node_rlps.iter())
+                    .map(|((update, trace), nodes)| Some((update, trace, nodes.as_slice())))
+                    .chain(std::iter::repeat(None))
+                    .take(n_units)
+                    .enumerate()
+                {
+                    self.assign_unit(&mut region, i * ROWS_PER_UPDATE, unit, challenges)?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    fn assign_unit(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        unit: Option<(&MptUpdate, &EthMptTrace, &[TrieNodeRlp])>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        self.assign_fixed_columns(region, offset)?;
+
+        let row = unit
+            .map(|(update, _, _)| update.table_assignments(challenges.evm_word()))
+            .unwrap_or_else(MptUpdateRow::padding);
+        let [address, storage_key, proof_type, new_root, old_root, new_value, old_value] = row.0;
+        let flags = PROOF_TYPES.map(|proof_type| {
+            unit.map_or(false, |(update, _, _)| {
+                update.proof_type() as u64 == proof_type as u64
+            })
+        });
+
+        // The nodes are decoded in the order of `EthMptTrace::rlp_instances`.
+        let (account_nodes, accounts, storage_nodes) = match unit {
+            Some((_, trace, nodes)) => {
+                let (account_nodes, nodes) = PathNodes::split(&trace.account, nodes);
+                let values = trace
+                    .account
+                    .values()
+                    .expect("accounts are decoded when building the traces");
+                let mut nodes = nodes.iter();
+                let accounts = values.map(|value| value.map(|_| nodes.next().unwrap()));
+                let storage_nodes = trace
+                    .storage
+                    .as_ref()
+                    .map_or_else(PathNodes::default, |path| {
+                        PathNodes::split(path, nodes.as_slice()).0
+                    });
+                (account_nodes, accounts, storage_nodes)
+            }
+            None => Default::default(),
+        };
+        let account_exists = accounts.map(|account| account.is_some());
+        let storage_roots = accounts.map(|account| match account {
+            Some(account) => {
+                let account = EthAccount::decode(&account.bytes)
+                    .expect("accounts are decoded when building the traces");
+                bytes_rlc(account.storage_root.as_bytes(), challenges.evm_word())
+            }
+            // the storage of a missing account is empty
+            None if unit.is_some() => {
+                bytes_rlc(empty_trie_root().as_bytes(), challenges.evm_word())
+            }
+            None => Value::known(F::zero()),
+        });
+
+        let update = unit.map(|(update, _, _)| update);
+        let storage_values = if flags[IS_STORAGE_CHANGED] {
+            let (new_value, old_value) = update.expect("active unit").values();
+            [old_value, new_value]
+        } else {
+            Default::default()
+        };
+        // a storage slot is deleted when set to 0
+        let storage_exists = storage_values.map(|value| !value.is_zero());
+
+        for row in offset..offset + ROWS_PER_UPDATE {
+            let unit_columns = [
+                (self.unit.active, known(unit.is_some() as u64)),
+                (self.unit.address, address),
+                (self.unit.storage_key, storage_key),
+                (self.unit.proof_type, proof_type),
+                (self.unit.roots[0], old_root),
+                (self.unit.roots[1], new_root),
+                (self.unit.values[0], old_value),
+                (self.unit.values[1], new_value),
+                (self.unit.value_inv, inverse(new_value)),
+                (self.unit.storage_roots[0], storage_roots[0]),
+                (self.unit.storage_roots[1], storage_roots[1]),
+            ]
+            .into_iter()
+            .chain(
+                self.unit
+                    .account_exists
+                    .into_iter()
+                    .zip_eq(account_exists.map(|exists| known(exists as u64))),
+            )
+            .chain(
+                self.unit
+                    .storage_exists
+                    .into_iter()
+                    .zip_eq(storage_exists.map(|exists| known(exists as u64))),
+            )
+            .chain(
+                self.unit
+                    .flags
+                    .into_iter()
+                    .zip_eq(flags.map(|flag| known(flag as u64))),
+            );
+            for (column, value) in unit_columns {
+                region.assign_advice(|| "eth mpt unit", column, row, || value)?;
+            }
+        }
+
+        let trace = unit.map(|(_, trace, _)| trace);
+        self.assign_segment(
+            region,
+            offset,
+            trace.map(|trace| &trace.account),
+            update.map(|update| update.address().as_bytes().to_vec()),
+            account_exists,
+            account_nodes,
+            false,
+            challenges,
+        )?;
+        self.assign_account(region, offset, accounts, challenges)?;
+        self.assign_segment(
+            region,
+            offset + ROWS_PER_SEGMENT,
+            trace.and_then(|trace| trace.storage.as_ref()),
+            update
+                .and_then(MptUpdate::storage_key)
+                .map(|storage_key| storage_key.to_be_bytes().to_vec()),
+            storage_exists,
+            storage_nodes,
+            true,
+            challenges,
+        )?;
+        for (columns, value) in self.storage_values.iter().zip_eq(storage_values) {
+            self.assign_storage_value(
+                region,
+                offset + ROWS_PER_SEGMENT,
+                columns,
+                &value.to_be_bytes(),
+                challenges,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn assign_fixed_columns(&self, region: &mut Region<'_, F>, offset: usize) -> Result<(), Error> {
+        for i in 0..ROWS_PER_UPDATE {
+            let is_storage = i >= ROWS_PER_SEGMENT;
+            let seg_row = i % ROWS_PER_SEGMENT;
+            let preimage_len = if is_storage { 32 } else { 20 };
+            let account_field_tag = (!is_storage).then(|| ACCOUNT_FIELDS.get(seg_row)).flatten();
+            let columns = [
+                (self.q_enable, true),
+                (self.q_unit_first, i == 0),
+                (self.q_seg_first, seg_row == 0),
+                (self.q_seg_last, seg_row == ROWS_PER_SEGMENT - 1),
+                (self.q_storage, is_storage),
+                (self.q_preimage, seg_row < preimage_len),
+                (self.q_odd, seg_row % 2 == 1),
+                (self.q_value_last, is_storage && seg_row == 31),
+                (self.q_account_field, account_field_tag.is_some()),
+            ];
+            for (column, value) in columns {
+                region.assign_fixed(
+                    || "eth mpt fixed",
+                    column,
+                    offset + i,
+                    || known::<F>(value as u64),
+                )?;
+            }
+            region.assign_fixed(
+                || "eth mpt account field tag",
+                self.account_field_tag,
+                offset + i,
+                || known::<F>(account_field_tag.map_or(0, |tag| usize::from(*tag) as u64)),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Assign the old and new paths of a key in a trie, where the key exists
+    /// or not on each side.
+    #[allow(clippy::too_many_arguments)]
+    fn assign_segment(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        path: Option<&TriePath>,
+        preimage: Option<Vec<u8>>,
+        exists: [bool; 2],
+        nodes: PathNodes,
+        is_storage: bool,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let keccak_rand = challenges.keccak_input();
+        let word_rand = challenges.evm_word();
+        let nibbles = path.map_or(vec![0; ROWS_PER_SEGMENT], |path| key_nibbles(path.key));
+        let preimage = preimage.unwrap_or_default();
+
+        // key and preimage
+        let mut key_rlc = Value::known(F::zero());
+        let mut preimage_rlc = Value::known(F::zero());
+        let mut preimage_acc = Value::known(F::zero());
+        let preimage_base = if is_storage {
+            word_rand
+        } else {
+            Value::known(F::from(256))
+        };
+        for (i, nibble) in nibbles.iter().enumerate() {
+            if i % 2 == 1 {
+                let byte = Value::known(F::from((nibbles[i - 1] * 16 + nibble) as u64));
+                key_rlc = key_rlc * word_rand + byte;
+            }
+            let byte = preimage.get(i).copied().unwrap_or_default();
+            if i < preimage.len() {
+                preimage_rlc = preimage_rlc * keccak_rand + known(byte as u64);
+                preimage_acc = preimage_acc * preimage_base + known(byte as u64);
+            }
+            for (column, value) in [
+                (self.nibble, known(*nibble as u64)),
+                (self.key_rlc, key_rlc),
+                (self.preimage_byte, known(byte as u64)),
+                (self.preimage_rlc, preimage_rlc),
+                (self.preimage_acc, preimage_acc),
+            ] {
+                region.assign_advice(|| "eth mpt key", column, offset + i, || value)?;
+            }
+        }
+
+        let spans = nodes.sides.map(|node_rlps| {
+            let mut spans = vec![];
+            let mut depth = 0;
+            for node_rlp in node_rlps {
+                let node = TrieNode::decode(&node_rlp.bytes)
+                    .expect("trie nodes are decoded when building the traces");
+                let (span_type, len) = match node {
+                    TrieNode::Branch(_) => (SpanType::Branch, 1),
+                    TrieNode::Extension(ref path, _) => (SpanType::Extension, path.len()),
+                    TrieNode::Leaf(..) => (SpanType::Leaf, ROWS_PER_SEGMENT - depth),
+                };
+                spans.push(Span {
+                    start: depth,
+                    len,
+                    span_type,
+                    node: Some((node_rlp, node)),
+                });
+                depth += len;
+            }
+            if path.is_some() && depth < ROWS_PER_SEGMENT {
+                spans.push(Span {
+                    start: depth,
+                    len: ROWS_PER_SEGMENT - depth,
+                    span_type: SpanType::Tail,
+                    node: None,
+                });
+            }
+            spans
+        });
+        let span_rows = [0, 1].map(|side| {
+            let mut span_rows = vec![None; ROWS_PER_SEGMENT];
+            for span in spans[side].iter() {
+                for row in span_rows.iter_mut().skip(span.start).take(span.len) {
+                    *row = Some(span);
+                }
+            }
+            span_rows
+        });
+        // the nibbles of the paths, which follow the key until they diverge
+        let path_nibbles = [0, 1].map(|side| {
+            span_rows[side]
+                .iter()
+                .enumerate()
+                .map(|(i, span)| {
+                    span.and_then(|span| span.path().get(i - span.start).copied())
+                        .unwrap_or(nibbles[i])
+                })
+                .collect::<Vec<_>>()
+        });
+
+        // The paths share their nodes down to the first one which differs, when
+        // the key is inserted or deleted.
+        let fork = if exists[0] != exists[1] {
+            let shape = |span: &Span| (span.start, span.len, span.span_type, span.path().to_vec());
+            spans[0]
+                .iter()
+                .zip(spans[1].iter())
+                .find(|&(old, new)| shape(old) != shape(new))
+                .map_or(ROWS_PER_SEGMENT, |(old, _)| old.start)
+        } else {
+            ROWS_PER_SEGMENT
+        };
+        // The branch above a tail at the fork keeps two other children.
+        let tail_side = (0..2).find(|side| {
+            span_rows[*side]
+                .get(fork)
+                .copied()
+                .flatten()
+                .map_or(false, |span| span.span_type == SpanType::Tail)
+        });
+        let other_nibbles = match tail_side {
+            Some(side) if fork > 0 => {
+                match span_rows[side][fork - 1].and_then(|span| span.node.as_ref()) {
+                    Some((_, TrieNode::Branch(children))) => {
+                        let others = children
+                            .iter()
+                            .positions(|child| !child.is_empty())
+                            .filter(|nibble| *nibble != nibbles[fork - 1] as usize)
+                            .take(2)
+                            .collect::<Vec<_>>();
+                        Some([others[0] as u64, others[1] as u64])
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        for i in 0..ROWS_PER_SEGMENT {
+            let is_tail_fork = i == fork && other_nibbles.is_some();
+            let [first, second] = other_nibbles
+                .filter(|_| is_tail_fork)
+                .unwrap_or_default()
+                .map(|nibble| F::from(nibble));
+            let nibble = F::from(nibbles[i.saturating_sub(1)] as u64);
+            let other_inv = (first - nibble) * (second - nibble) * (second - first);
+            for (column, value) in [
+                (self.is_common, known((i < fork) as u64)),
+                (self.is_fork, known((i == fork) as u64)),
+                (self.is_tail_fork, known(is_tail_fork as u64)),
+                (self.other_nibbles[0], Value::known(first)),
+                (self.other_nibbles[1], Value::known(second)),
+                (
+                    self.other_inv,
+                    Value::known(other_inv.invert().unwrap_or(F::zero())),
+                ),
+            ] {
+                region.assign_advice(|| "eth mpt fork", column, offset + i, || value)?;
+            }
+        }
+
+        let mut sibling_rows = vec![];
+        for (side, columns) in self.paths.iter().enumerate() {
+            let span_rows = &span_rows[side];
+            let path_nibbles = &path_nibbles[side];
+
+            // The hex-prefix encoding of the nibbles is accumulated from the
+            // end of each span, the rows of an inactive segment making up a
+            // single span.
+            let span_bounds = if spans[side].is_empty() {
+                vec![(0, ROWS_PER_SEGMENT)]
+            } else {
+                spans[side]
+                    .iter()
+                    .map(|span| (span.start, span.len))
+                    .collect()
+            };
+            for (start, len) in span_bounds {
+                let mut is_low = true;
+                let mut acc = Value::known(F::zero());
+                let mut pow = Value::known(F::one());
+                let mut path_len = 0;
+                for i in (start..start + len).rev() {
+                    if i + 1 < start + len {
+                        is_low = !is_low;
+                        if !is_low {
+                            let byte = known((path_nibbles[i] * 16 + path_nibbles[i + 1]) as u64);
+                            acc = acc + byte * pow;
+                            pow = pow * keccak_rand;
+                            path_len += 1;
+                        }
+                    }
+                    for (column, value) in [
+                        (columns.is_low, known(is_low as u64)),
+                        (columns.path_acc, acc),
+                        (columns.path_pow, pow),
+                        (columns.path_len_acc, known(path_len)),
+                    ] {
+                        region.assign_advice(|| "eth mpt path", column, offset + i, || value)?;
+                    }
+                }
+            }
+
+            for (i, span) in span_rows.iter().enumerate() {
+                let is_start = span.map_or(false, |span| span.start == i);
+                let span_type = span.map(|span| span.span_type);
+                let diverge_row = span.map_or(ROWS_PER_SEGMENT, |span| span.diverge_row(&nibbles));
+                let is_diverged = i >= diverge_row;
+                let diverge_start = i == diverge_row;
+                let has_sibling = diverge_start && i >= fork;
+                if has_sibling {
+                    sibling_rows.push((side, i));
+                }
+                let nibble_diff = F::from(path_nibbles[i] as u64) - F::from(nibbles[i] as u64);
+                let diverge_inv = if diverge_start {
+                    nibble_diff.invert().unwrap_or(F::zero())
+                } else {
+                    F::zero()
+                };
+                for (column, value) in [
+                    (columns.exists, exists[side]),
+                    (columns.is_start, is_start),
+                    (columns.is_branch, span_type == Some(SpanType::Branch)),
+                    (columns.is_extension, span_type == Some(SpanType::Extension)),
+                    (columns.is_leaf, span_type == Some(SpanType::Leaf)),
+                    (columns.is_tail, span_type == Some(SpanType::Tail)),
+                    (columns.is_diverged, is_diverged),
+                    (columns.diverge_start, diverge_start),
+                    (columns.has_sibling, has_sibling),
+                ] {
+                    region.assign_advice(
+                        || "eth mpt span",
+                        column,
+                        offset + i,
+                        || known(value as u64),
+                    )?;
+                }
+                for (column, value) in [
+                    (columns.nibble, known(path_nibbles[i] as u64)),
+                    (columns.diverge_inv, Value::known(diverge_inv)),
+                ] {
+                    region.assign_advice(|| "eth mpt path nibble", column, offset + i, || value)?;
+                }
+
+                let node = span.and_then(|span| span.node.as_ref());
+                let (path_rlc, path_len) = match node {
+                    Some((_, node @ (TrieNode::Extension(path, _) | TrieNode::Leaf(path, _))))
+                        if is_start =>
+                    {
+                        let path = hp_encode(path, matches!(node, TrieNode::Leaf(..)));
+                        (bytes_rlc(&path, keccak_rand), path.len() as u64)
+                    }
+                    _ => (Value::known(F::zero()), 0),
+                };
+                for (column, value) in [
+                    (columns.path_rlc, path_rlc),
+                    (columns.path_len, known(path_len)),
+                ] {
+                    region.assign_advice(|| "eth mpt node path", column, offset + i, || value)?;
+                }
+
+                let node_values = match node {
+                    Some((node_rlp, node)) => {
+                        let bytes = &node_rlp.bytes;
+                        let (_, item) = node.item(path_nibbles[span.unwrap().start]);
+                        let is_leaf = matches!(node, TrieNode::Leaf(..));
+                        // the leaf values are longer than words
+                        let item_rand = if is_leaf { keccak_rand } else { word_rand };
+                        [
+                            known(node_rlp.id),
+                            bytes_rlc(&keccak256(bytes), word_rand),
+                            bytes_rlc(bytes, keccak_rand),
+                            known(bytes.len() as u64),
+                            known(len_bytes(bytes)),
+                            bytes_rlc(item, item_rand),
+                            bytes_rlc(item, keccak_rand),
+                            known(item.len() as u64),
+                            known(item.is_empty() as u64),
+                            known((!is_leaf && item.len() == 32) as u64),
+                        ]
+                    }
+                    None => [Value::known(F::zero()); 10],
+                };
+                let node_columns = [
+                    columns.node.id,
+                    columns.node.hash,
+                    columns.node.rlc,
+                    columns.node.len,
+                    columns.node.len_bytes,
+                    columns.node.item,
+                    columns.node.item_bytes_rlc,
+                    columns.node.item_len,
+                    columns.node.item_none,
+                    columns.node.item_is_hash,
+                ];
+                for (column, value) in node_columns.into_iter().zip_eq(node_values) {
+                    region.assign_advice(|| "eth mpt node", column, offset + i, || value)?;
+                }
+
+                let branch_values = match node {
+                    Some((node_rlp, node @ TrieNode::Branch(_))) => {
+                        let bytes = &node_rlp.bytes;
+                        let nibble = path_nibbles[i];
+                        let [hdr_len, pre_len, item_len, suffix_len] =
+                            item_offsets(bytes, nibble as usize);
+                        let (_, item) = node.item(nibble);
+                        [
+                            known(bytes[0] as u64),
+                            known((bytes[0] >= 0xf8) as u64),
+                            bytes_rlc(&bytes[..hdr_len], keccak_rand),
+                            known(pre_len as u64),
+                            pow(keccak_rand, pre_len),
+                            bytes_rlc(&bytes[..hdr_len + pre_len], keccak_rand),
+                            pow(keccak_rand, item.len()),
+                            bytes_rlc(&bytes[..hdr_len + pre_len + item_len], keccak_rand),
+                            known(suffix_len as u64),
+                            pow(keccak_rand, suffix_len),
+                        ]
+                    }
+                    _ => [Value::known(F::zero()); 10],
+                };
+                let branch_columns = [
+                    columns.branch.hdr_byte,
+                    columns.branch.is_long,
+                    columns.branch.hdr_rlc,
+                    columns.branch.pre_len,
+                    columns.branch.pre_pow,
+                    columns.branch.pre_acc,
+                    columns.branch.item_pow,
+                    columns.branch.item_acc,
+                    columns.branch.suffix_len,
+                    columns.branch.suffix_pow,
+                ];
+                for (column, value) in branch_columns.into_iter().zip_eq(branch_values) {
+                    region.assign_advice(|| "eth mpt branch", column, offset + i, || value)?;
+                }
+            }
+        }
+
+        // The sibling of the leaf of the key is the child of the new branch
+        // next to it, at the nibble of the path without the key.
+        let sibling_columns = [
+            self.sibling.item,
+            self.sibling.item_bytes_rlc,
+            self.sibling.item_len,
+            self.sibling.item_is_hash,
+            self.sibling.is_derived,
+            self.sibling.id,
+            self.sibling.hash,
+            self.sibling.rlc,
+            self.sibling.len,
+            self.sibling.len_bytes,
+            self.sibling.path_rlc,
+            self.sibling.path_len,
+        ];
+        for i in 0..ROWS_PER_SEGMENT {
+            let sibling_values = match sibling_rows.iter().find(|(_, row)| *row == i) {
+                Some(&(side, _)) => {
+                    let span = span_rows[side][i].expect("the path diverges in a node");
+                    let nibble = path_nibbles[side][i];
+                    let child = match span_rows[1 - side][i].and_then(|span| span.node.as_ref()) {
+                        Some((_, branch @ TrieNode::Branch(_))) => branch.item(nibble).1,
+                        _ => unreachable!("the sibling is the child of a branch"),
+                    };
+                    let is_derived =
+                        !(span.span_type == SpanType::Extension && i + 1 == span.start + span.len);
+                    let (id, hash, rlc, len, len_bytes) = match nodes.sibling {
+                        Some(sibling) => (
+                            known(sibling.id),
+                            bytes_rlc(&keccak256(&sibling.bytes), word_rand),
+                            bytes_rlc(&sibling.bytes, keccak_rand),
+                            known(sibling.bytes.len() as u64),
+                            known(len_bytes(&sibling.bytes)),
+                        ),
+                        None => {
+                            let zero = Value::known(F::zero());
+                            (zero, zero, zero, zero, zero)
+                        }
+                    };
+                    let (path_rlc, path_len) = if is_derived {
+                        let path = hp_encode(
+                            &span.path()[i + 1 - span.start..],
+                            span.span_type == SpanType::Leaf,
+                        );
+                        (bytes_rlc(&path, keccak_rand), known(path.len() as u64))
+                    } else {
+                        (Value::known(F::zero()), Value::known(F::zero()))
+                    };
+                    [
+                        bytes_rlc(child, word_rand),
+                        bytes_rlc(child, keccak_rand),
+                        known(child.len() as u64),
+                        known((child.len() == 32) as u64),
+                        known(is_derived as u64),
+                        id,
+                        hash,
+                        rlc,
+                        len,
+                        len_bytes,
+                        path_rlc,
+                        path_len,
+                    ]
+                }
+                None => [Value::known(F::zero()); 12],
+            };
+            for (column, value) in sibling_columns.into_iter().zip_eq(sibling_values) {
+                region.assign_advice(|| "eth mpt sibling", column, offset + i, || value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Assign the old and new accounts of the leaf in the first rows of the
+    /// account segment, where they exist.
+    fn assign_account(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        accounts: [Option<&TrieNodeRlp>; 2],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let keccak_rand = challenges.keccak_input();
+        let word_rand = challenges.evm_word();
+
+        for (columns, account) in self.accounts.iter().zip_eq(accounts) {
+            let id = known(account.map_or(0, |account| account.id));
+            for row in offset..offset + ROWS_PER_SEGMENT {
+                region.assign_advice(|| "eth mpt account id", columns.id, row, || id)?;
+            }
+
+            for (i, tag) in ACCOUNT_FIELDS.iter().enumerate() {
+                let values = match account {
+                    Some(account) => {
+                        let bytes = &account.bytes;
+                        match tag {
+                            RlpTag::RLC => [
+                                bytes_rlc(bytes, keccak_rand),
+                                Value::known(F::zero()),
+                                known(0),
+                                known(0),
+                            ],
+                            RlpTag::Len => [
+                                known(bytes.len() as u64),
+                                Value::known(F::zero()),
+                                known(len_bytes(bytes)),
+                                known(0),
+                            ],
+                            _ => {
+                                let field = Rlp::new(bytes)
+                                    .at(i - 2)
+                                    .and_then(|field| field.data())
+                                    .expect("accounts are valid RLP lists");
+                                // the nonce is a scalar, the other fields words
+                                let field_value = if matches!(tag, RlpTag::Tag(Tag::Nonce)) {
+                                    Value::known(field.iter().fold(F::zero(), |acc, byte| {
+                                        acc * F::from(256) + F::from(*byte as u64)
+                                    }))
+                                } else {
+                                    bytes_rlc(field, word_rand)
+                                };
+                                [
+                                    field_value,
+                                    bytes_rlc(field, keccak_rand),
+                                    known(field.len() as u64),
+                                    known(field.is_empty() as u64),
+                                ]
+                            }
+                        }
+                    }
+                    None => [Value::known(F::zero()); 4],
+                };
+                let field_columns = [
+                    columns.field_value,
+                    columns.field_bytes_rlc,
+                    columns.field_len,
+                    columns.field_none,
+                ];
+                for (column, value) in field_columns.into_iter().zip_eq(values) {
+                    region.assign_advice(
+                        || "eth mpt account field",
+                        column,
+                        offset + i,
+                        || value,
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Assign the bytes of a storage value in the first rows of the storage
+    /// segment.
+    fn assign_storage_value(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        columns: &StorageValueColumns,
+        value: &[u8; 32],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let keccak_rand = challenges.keccak_input();
+        let word_rand = challenges.evm_word();
+        let first_significant = value.iter().position(|byte| *byte != 0).unwrap_or(32);
+        let is_short = first_significant == 31 && value[31] < 0x80;
+
+        let mut value_acc = Value::known(F::zero());
+        let mut bytes_rlc = Value::known(F::zero());
+        let mut bytes_pow = Value::known(F::one());
+        let mut bytes_len = 0;
+        for (i, byte) in value.iter().enumerate() {
+            let is_significant = i >= first_significant;
+            value_acc = value_acc * word_rand + known(*byte as u64);
+            if is_significant {
+                bytes_rlc = bytes_rlc * keccak_rand + known(*byte as u64);
+                bytes_pow = bytes_pow * keccak_rand;
+                bytes_len += 1;
+            }
+            let byte_inv = if i == first_significant {
+                F::from(*byte as u64).invert().unwrap_or(F::zero())
+            } else {
+                F::zero()
+            };
+            for (column, value) in [
+                (columns.byte, known(*byte as u64)),
+                (columns.is_significant, known(is_significant as u64)),
+                (columns.byte_inv, Value::known(byte_inv)),
+                (columns.value_acc, value_acc),
+                (columns.bytes_rlc, bytes_rlc),
+                (columns.bytes_pow, bytes_pow),
+                (columns.bytes_len, known(bytes_len)),
+                (columns.is_short, known((is_short && i == 31) as u64)),
+            ] {
+                region.assign_advice(|| "eth mpt storage value", column, offset + i, || value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The keccak MPT circuit, proving the MPT updates of a block against the
+/// Ethereum state trie.
+#[derive(Clone, Debug, Default)]
+pub struct EthMptCircuit<F: Field> {
+    /// The MPT updates, with the paths proving them
+    pub mpt_updates: MptUpdates,
+    /// Id of the first trie node in the RLP table
+    pub first_node_id: u64,
+    /// Number of updates the circuit can prove, one more than the updates for
+    /// the padding row of the MPT table
+    pub n_units: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> EthMptCircuit<F> {
+    /// Return a new EthMptCircuit
+    pub fn new(mpt_updates: MptUpdates, first_node_id: u64, n_units: usize) -> Self {
+        Self {
+            mpt_updates,
+            first_node_id,
+            n_units,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Minimum and total number of rows of the circuit for `num_updates` mpt updates, and
+    /// the padding unit.
+    pub(crate) fn min_num_rows(num_updates: usize, max_mpt_rows: usize) -> (usize, usize) {
+        let num_rows = ROWS_PER_UPDATE * (num_updates + 1);
+        (num_rows, max_mpt_rows.max(num_rows))
+    }
+}
+
+impl<F: Field> SubCircuit<F> for EthMptCircuit<F> {
+    type Config = EthMptCircuitConfig<F>;
+
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        // 0 means "dynamic"
+        let n_units = if block.circuits_params.max_mpt_rows == 0 {
+            block.mpt_updates.len() + 1
+        } else {
+            block.circuits_params.max_mpt_rows / ROWS_PER_UPDATE
+        };
+        // The trie nodes are decoded after the txs in the RLP circuit.
+        Self::new(
+            block.mpt_updates.clone(),
+            block.circuits_params.max_txs as u64 + 1,
+            n_units,
+        )
+    }
+
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        Self::min_num_rows(block.mpt_updates.len(), block.circuits_params.max_mpt_rows)
+    }
+
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.assign(
+            layouter,
+            &self.mpt_updates,
+            self.first_node_id,
+            self.n_units,
+            challenges,
+        )
+    }
+}
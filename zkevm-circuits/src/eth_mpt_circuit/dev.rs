@@ -0,0 +1,78 @@
+pub use super::EthMptCircuit;
+
+use crate::{
+    eth_mpt_circuit::{EthMptCircuitConfig, EthMptCircuitConfigArgs},
+    rlp_circuit_fsm::RlpFsmDataTable,
+    table::{KeccakTable, MptTable, PowOfRandTable, RlpFsmRlpTable, U8Table},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+impl<F: Field> Circuit<F> for EthMptCircuit<F> {
+    type Config = (EthMptCircuitConfig<F>, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let challenges = Challenges::construct(meta);
+        let challenge_exprs = challenges.exprs(meta);
+        let mpt_table = MptTable::construct(meta);
+        let keccak_table = KeccakTable::construct(meta);
+        let rlp_table = RlpFsmRlpTable::construct(meta);
+        let rlp_data_table = RlpFsmDataTable::construct(meta);
+        let pow_of_rand_table = PowOfRandTable::construct(meta, &challenge_exprs);
+        let u8_table = U8Table::construct(meta);
+        (
+            EthMptCircuitConfig::new(
+                meta,
+                EthMptCircuitConfigArgs {
+                    mpt_table,
+                    keccak_table,
+                    rlp_table,
+                    rlp_data_table,
+                    pow_of_rand_table,
+                    u8_table,
+                    challenges: challenge_exprs,
+                },
+            ),
+            challenges,
+        )
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&layouter);
+        config.keccak_table.dev_load(
+            &mut layouter,
+            &self.mpt_updates.eth_keccak_inputs(),
+            &challenges,
+        )?;
+        config.rlp_table.dev_load(
+            &mut layouter,
+            self.mpt_updates.trie_node_rlps(self.first_node_id),
+            &challenges,
+        )?;
+        config.rlp_data_table.dev_load(
+            &mut layouter,
+            self.mpt_updates.trie_node_rlps(self.first_node_id),
+            &challenges,
+        )?;
+        config
+            .pow_of_rand_table
+            .assign(&mut layouter, &challenges)?;
+        config.u8_table.load(&mut layouter)?;
+        self.synthesize_sub(&config, &challenges, &mut layouter)
+    }
+}
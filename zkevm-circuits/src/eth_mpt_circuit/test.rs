@@ -0,0 +1,215 @@
+#![allow(unused_imports)]
+use crate::{
+    eth_mpt_circuit::{EthMptCircuit, ROWS_PER_UPDATE},
+    rlp_circuit_fsm::RlpCircuit,
+    table::AccountFieldTag,
+    witness::{EthAccount, EthTrieState, MptUpdates, Rw, Transaction},
+};
+use eth_types::{Address, Field, Word, H256, U256};
+use ethers_core::utils::keccak256;
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+use std::collections::BTreeMap;
+
+const FIRST_NODE_ID: u64 = 1;
+
+fn address(n: u8) -> Address {
+    Address::repeat_byte(n)
+}
+
+/// A state trie with a few accounts, the last one holding some storage.
+fn trie() -> EthTrieState {
+    let accounts = (1..=4u8)
+        .map(|n| {
+            let account = EthAccount {
+                nonce: n as u64,
+                balance: U256::from(1000 * n as u64),
+                storage_root: H256::zero(),
+                code_hash: H256(keccak256([])),
+            };
+            let storage = if n == 4 {
+                (1..=3u64)
+                    .map(|slot| (Word::from(slot), Word::from(0x100 * slot)))
+                    .chain([(Word::from(4), Word::from(0x7f))])
+                    .collect()
+            } else {
+                BTreeMap::new()
+            };
+            (address(n), (account, storage))
+        })
+        .collect();
+    EthTrieState::from_accounts(&accounts).unwrap()
+}
+
+fn account_rw(address: Address, field_tag: AccountFieldTag, value_prev: Word, value: Word) -> Rw {
+    Rw::Account {
+        rw_counter: 1,
+        is_write: true,
+        account_address: address,
+        field_tag,
+        value,
+        value_prev,
+    }
+}
+
+fn storage_rw(address: Address, storage_key: u64, value_prev: u64, value: u64) -> Rw {
+    Rw::AccountStorage {
+        rw_counter: 1,
+        is_write: true,
+        account_address: address,
+        storage_key: storage_key.into(),
+        value: value.into(),
+        value_prev: value_prev.into(),
+        tx_id: 1,
+        committed_value: value_prev.into(),
+    }
+}
+
+/// Two storage slots whose keys share their first 8 nibbles: the leaves of
+/// small values below their branch are shorter than 32 bytes, and embedded in
+/// the branch.
+const EMBEDDED_SLOTS: [u64; 2] = [40364, 105566];
+
+/// A state trie with a single account, holding the given storage.
+fn trie_with_storage(storage: &[(u64, u64)]) -> EthTrieState {
+    let account = EthAccount {
+        nonce: 1,
+        balance: U256::from(1000),
+        storage_root: H256::zero(),
+        code_hash: H256(keccak256([])),
+    };
+    let storage = storage
+        .iter()
+        .map(|(slot, value)| (Word::from(*slot), Word::from(*value)))
+        .collect();
+    EthTrieState::from_accounts(&BTreeMap::from([(address(5), (account, storage))])).unwrap()
+}
+
+fn mpt_updates_in(trie: &EthTrieState, rws: &[Rw]) -> MptUpdates {
+    let mut updates = MptUpdates::mock_from(rws);
+    updates.fill_state_roots_eth(trie).unwrap();
+    updates
+}
+
+fn mpt_updates(rws: &[Rw]) -> MptUpdates {
+    mpt_updates_in(&trie(), rws)
+}
+
+fn test_eth_mpt_circuit(updates: MptUpdates) {
+    let n_units = updates.len() + 1;
+    let circuit = EthMptCircuit::<Fr>::new(updates, FIRST_NODE_ID, n_units);
+    let prover = MockProver::<Fr>::run(14, &circuit, vec![]).unwrap();
+    prover.assert_satisfied_par();
+}
+
+#[test]
+fn eth_mpt_circuit_account_updates() {
+    test_eth_mpt_circuit(mpt_updates(&[
+        account_rw(address(1), AccountFieldTag::Nonce, 1.into(), 2.into()),
+        account_rw(address(2), AccountFieldTag::Balance, 2000.into(), 0.into()),
+        account_rw(
+            address(3),
+            AccountFieldTag::CodeHash,
+            U256::from_big_endian(&keccak256([])),
+            U256::from(0x1234),
+        ),
+    ]));
+}
+
+#[test]
+fn eth_mpt_circuit_absent_account() {
+    test_eth_mpt_circuit(mpt_updates(&[account_rw(
+        address(5),
+        AccountFieldTag::CodeHash,
+        0.into(),
+        0.into(),
+    )]));
+}
+
+#[test]
+fn eth_mpt_circuit_storage_updates() {
+    test_eth_mpt_circuit(mpt_updates(&[
+        storage_rw(address(4), 1, 0x100, 0x1234567),
+        storage_rw(address(4), 2, 0x200, 0x5),
+        storage_rw(address(4), 4, 0x7f, 0x80),
+        storage_rw(address(4), 9, 0, 0),
+    ]));
+}
+
+#[test]
+fn eth_mpt_circuit_storage_of_empty_trie() {
+    test_eth_mpt_circuit(mpt_updates(&[storage_rw(address(1), 1, 0, 0)]));
+}
+
+#[test]
+fn eth_mpt_circuit_wrong_old_value() {
+    let mut updates = MptUpdates::mock_from(&[storage_rw(address(4), 1, 0x200, 0x300)]);
+    assert!(updates.fill_state_roots_eth(&trie()).is_err());
+}
+
+#[test]
+fn eth_mpt_circuit_account_creation() {
+    test_eth_mpt_circuit(mpt_updates(&[
+        account_rw(address(5), AccountFieldTag::Balance, 0.into(), 100.into()),
+        account_rw(address(6), AccountFieldTag::Nonce, 0.into(), 1.into()),
+    ]));
+}
+
+#[test]
+fn eth_mpt_circuit_storage_insertion() {
+    test_eth_mpt_circuit(mpt_updates(&[
+        storage_rw(address(4), 9, 0, 1),
+        storage_rw(address(4), 10, 0, 0x1234),
+        storage_rw(address(1), 1, 0, 5),
+    ]));
+}
+
+#[test]
+fn eth_mpt_circuit_storage_deletion() {
+    // the last deletions collapse the branches of the storage trie, down to
+    // the empty trie
+    test_eth_mpt_circuit(mpt_updates(&[
+        storage_rw(address(4), 1, 0x100, 0),
+        storage_rw(address(4), 2, 0x200, 0),
+        storage_rw(address(4), 3, 0x300, 0),
+        storage_rw(address(4), 4, 0x7f, 0),
+    ]));
+}
+
+#[test]
+fn eth_mpt_circuit_embedded_leaf_insertion() {
+    let [slot, other_slot] = EMBEDDED_SLOTS;
+    test_eth_mpt_circuit(mpt_updates_in(
+        &trie_with_storage(&[(slot, 1)]),
+        &[storage_rw(address(5), other_slot, 0, 2)],
+    ));
+}
+
+#[test]
+fn eth_mpt_circuit_embedded_leaf_deletion() {
+    let [slot, other_slot] = EMBEDDED_SLOTS;
+    test_eth_mpt_circuit(mpt_updates_in(
+        &trie_with_storage(&[(slot, 1), (other_slot, 2)]),
+        &[
+            storage_rw(address(5), slot, 1, 3),
+            storage_rw(address(5), other_slot, 2, 0),
+        ],
+    ));
+}
+
+#[test]
+fn rlp_circuit_trie_nodes() {
+    let updates = mpt_updates(&[
+        account_rw(address(1), AccountFieldTag::Balance, 1000.into(), 1.into()),
+        storage_rw(address(4), 3, 0x300, 0x301),
+    ]);
+    let rlp_circuit = RlpCircuit::<Fr, Transaction> {
+        txs: vec![],
+        max_txs: 0,
+        trie_nodes: updates.trie_node_rlps(FIRST_NODE_ID),
+        size: 1 << 13,
+        _marker: Default::default(),
+    };
+
+    let prover = MockProver::run(14, &rlp_circuit, vec![]).unwrap();
+    prover.assert_satisfied_par();
+}
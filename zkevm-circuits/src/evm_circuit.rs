@@ -22,8 +22,9 @@ pub use crate::witness;
 use crate::{
     evm_circuit::param::{MAX_STEP_HEIGHT, STEP_STATE_HEIGHT},
     table::{
-        BlockTable, BytecodeTable, CopyTable, EccTable, ExpTable, KeccakTable, LookupTable,
-        ModExpTable, PowOfRandTable, RwTable, SHA256Table, SigTable, TxTable,
+        Blake2fTable, BlockTable, BytecodeTable, CopyTable, EccTable, ExpTable, KeccakTable,
+        LookupTable, ModExpTable, PowOfRandTable, Ripemd160Table, RwTable, SHA256Table, SigTable,
+        TxTable,
     },
     util::{SubCircuit, SubCircuitConfig},
 };
@@ -49,6 +50,8 @@ pub struct EvmCircuitConfig<F> {
     copy_table: CopyTable,
     keccak_table: KeccakTable,
     sha256_table: SHA256Table,
+    ripemd160_table: Ripemd160Table,
+    blake2f_table: Blake2fTable,
     exp_table: ExpTable,
     sig_table: SigTable,
     modexp_table: ModExpTable,
@@ -74,6 +77,10 @@ pub struct EvmCircuitConfigArgs<F: Field> {
     pub keccak_table: KeccakTable,
     /// SHA256Table
     pub sha256_table: SHA256Table,
+    /// Ripemd160Table
+    pub ripemd160_table: Ripemd160Table,
+    /// Blake2fTable
+    pub blake2f_table: Blake2fTable,
     /// ExpTable
     pub exp_table: ExpTable,
     /// SigTable
@@ -109,6 +116,8 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             copy_table,
             keccak_table,
             sha256_table,
+            ripemd160_table,
+            blake2f_table,
             exp_table,
             sig_table,
             modexp_table,
@@ -130,6 +139,8 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             &copy_table,
             &keccak_table,
             &sha256_table,
+            &ripemd160_table,
+            &blake2f_table,
             &exp_table,
             &sig_table,
             &modexp_table,
@@ -164,6 +175,8 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             copy_table,
             keccak_table,
             sha256_table,
+            ripemd160_table,
+            blake2f_table,
             exp_table,
             sig_table,
             modexp_table,
@@ -287,6 +300,26 @@ impl<F: Field> EvmCircuit<F> {
         // It must have one row for EndBlock and at least one unused one
         num_rows + 2
     }
+
+    /// Minimum and total number of rows of the circuit, given the rows of the execution steps
+    /// and whether the bitwise fixed tables are needed.
+    pub(crate) fn min_num_rows(
+        num_rows_required_for_execution_steps: usize,
+        need_bitwise_lookup: bool,
+        max_evm_rows: usize,
+    ) -> (usize, usize) {
+        let mut total_rows = num_rows_required_for_execution_steps;
+        total_rows = total_rows.max(max_evm_rows);
+
+        if total_rows <= FIXED_TABLE_ROWS {
+            // for many test cases, there is no need for bitwise table.
+            // So using `detect_fixed_table_tags` can greatly improve CI time.
+            let num_rows_required_for_fixed_table = get_fixed_table_row_num(need_bitwise_lookup);
+            total_rows = total_rows.max(num_rows_required_for_fixed_table)
+        }
+
+        (num_rows_required_for_execution_steps, total_rows)
+    }
 }
 
 const FIXED_TABLE_ROWS_NO_BITWISE: usize = 3647;
@@ -307,20 +340,11 @@ impl<F: Field> SubCircuit<F> for EvmCircuit<F> {
 
     /// Return the minimum number of rows required to prove the block
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
-        let num_rows_required_for_execution_steps: usize =
-            Self::get_num_rows_required_no_padding(block);
-        let mut total_rows = num_rows_required_for_execution_steps;
-        total_rows = total_rows.max(block.circuits_params.max_evm_rows);
-
-        if total_rows <= FIXED_TABLE_ROWS {
-            // for many test cases, there is no need for bitwise table.
-            // So using `detect_fixed_table_tags` can greatly improve CI time.
-            let num_rows_required_for_fixed_table =
-                get_fixed_table_row_num(need_bitwise_lookup(block));
-            total_rows = total_rows.max(num_rows_required_for_fixed_table)
-        }
-
-        (num_rows_required_for_execution_steps, total_rows)
+        Self::min_num_rows(
+            Self::get_num_rows_required_no_padding(block),
+            need_bitwise_lookup(block),
+            block.circuits_params.max_evm_rows,
+        )
     }
 
     /// Make the assignments to the EvmCircuit
@@ -351,17 +375,20 @@ fn get_fixed_table_row_num(need_bitwise_lookup: bool) -> usize {
 
 fn need_bitwise_lookup<F: Field>(block: &Block<F>) -> bool {
     block.txs.iter().any(|tx| {
-        tx.steps.iter().any(|step| {
-            matches!(
-                step.opcode,
-                Some(OpcodeId::AND)
-                    | Some(OpcodeId::OR)
-                    | Some(OpcodeId::XOR)
-                    | Some(OpcodeId::NOT)
-            )
-        })
+        tx.steps
+            .iter()
+            .any(|step| step.opcode.map_or(false, is_bitwise_opcode))
     })
 }
+
+/// Whether the opcode looks up the bitwise fixed tables.
+pub(crate) fn is_bitwise_opcode(opcode: OpcodeId) -> bool {
+    matches!(
+        opcode,
+        OpcodeId::AND | OpcodeId::OR | OpcodeId::XOR | OpcodeId::NOT
+    )
+}
+
 /// create fixed_table_tags needed given witness block
 pub(crate) fn detect_fixed_table_tags<F: Field>(block: &Block<F>) -> Vec<FixedTableTag> {
     if need_bitwise_lookup(block) {
@@ -463,6 +490,8 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
         let copy_table = CopyTable::construct(meta, q_copy_table);
         let keccak_table = KeccakTable::construct(meta);
         let sha256_table = SHA256Table::construct(meta);
+        let ripemd160_table = Ripemd160Table::construct(meta);
+        let blake2f_table = Blake2fTable::construct(meta);
         let exp_table = ExpTable::construct(meta);
         let sig_table = SigTable::construct(meta);
         let modexp_table = ModExpTable::construct(meta);
@@ -480,6 +509,8 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
                     copy_table,
                     keccak_table,
                     sha256_table,
+                    ripemd160_table,
+                    blake2f_table,
                     exp_table,
                     sig_table,
                     modexp_table,
@@ -536,13 +567,24 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
                 .map(|evt| (&evt.input, &evt.digest)),
             &challenges,
         )?;
+        config.ripemd160_table.dev_load(
+            &mut layouter,
+            block
+                .get_ripemd160()
+                .iter()
+                .map(|evt| (&evt.input, &evt.digest)),
+            &challenges,
+        )?;
+        config
+            .blake2f_table
+            .dev_load(&mut layouter, &block.get_blake2f(), &challenges)?;
         config.exp_table.dev_load(&mut layouter, block)?;
         config
             .sig_table
             .dev_load(&mut layouter, block, &challenges)?;
         config
             .modexp_table
-            .dev_load(&mut layouter, &block.get_big_modexp())?;
+            .dev_load(&mut layouter, &block.get_big_modexp(), &challenges)?;
         config.ecc_table.dev_load(
             &mut layouter,
             block.circuits_params.max_ec_ops,
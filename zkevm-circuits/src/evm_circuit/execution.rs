@@ -1,9 +1,10 @@
 use super::{
     param::{
-        BLOCK_TABLE_LOOKUPS, BYTECODE_TABLE_LOOKUPS, COPY_TABLE_LOOKUPS, ECC_TABLE_LOOKUPS,
-        EXP_TABLE_LOOKUPS, FIXED_TABLE_LOOKUPS, KECCAK_TABLE_LOOKUPS, MODEXP_TABLE_LOOKUPS,
-        N_BYTE_LOOKUPS, N_COPY_COLUMNS, N_PHASE1_COLUMNS, POW_OF_RAND_TABLE_LOOKUPS,
-        RW_TABLE_LOOKUPS, SHA256_TABLE_LOOKUPS, SIG_TABLE_LOOKUPS, TX_TABLE_LOOKUPS,
+        BLAKE2F_TABLE_LOOKUPS, BLOCK_TABLE_LOOKUPS, BYTECODE_TABLE_LOOKUPS, COPY_TABLE_LOOKUPS,
+        ECC_TABLE_LOOKUPS, EXP_TABLE_LOOKUPS, FIXED_TABLE_LOOKUPS, KECCAK_TABLE_LOOKUPS,
+        MODEXP_TABLE_LOOKUPS, N_BYTE_LOOKUPS, N_COPY_COLUMNS, N_PHASE1_COLUMNS,
+        POW_OF_RAND_TABLE_LOOKUPS, RIPEMD160_TABLE_LOOKUPS, RW_TABLE_LOOKUPS, SHA256_TABLE_LOOKUPS,
+        SIG_TABLE_LOOKUPS, TX_TABLE_LOOKUPS,
     },
     util::{instrumentation::Instrument, CachedRegion, CellManager, Inverter, StoredExpression},
     EvmCircuitExports,
@@ -61,6 +62,8 @@ mod address;
 mod balance;
 mod begin_tx;
 mod bitwise;
+#[cfg(feature = "cancun")]
+mod blobhash;
 mod block_ctx;
 mod blockhash;
 mod byte;
@@ -75,8 +78,7 @@ mod codecopy;
 mod codesize;
 mod comparator;
 mod create;
-#[cfg(not(feature = "scroll"))]
-mod dummy;
+mod destruct_account;
 mod dup;
 mod end_block;
 mod end_inner_block;
@@ -94,6 +96,8 @@ mod error_oog_exp;
 mod error_oog_log;
 mod error_oog_memory_copy;
 mod error_oog_precompile;
+#[cfg(not(feature = "scroll"))]
+mod error_oog_self_destruct;
 mod error_oog_sha3;
 mod error_oog_sload_sstore;
 mod error_oog_static_memory;
@@ -112,6 +116,8 @@ mod jump;
 mod jumpdest;
 mod jumpi;
 mod logs;
+#[cfg(feature = "cancun")]
+mod mcopy;
 mod memory;
 mod msize;
 mod mul_div_mod;
@@ -129,6 +135,8 @@ mod returndatasize;
 mod sar;
 mod sdiv_smod;
 mod selfbalance;
+#[cfg(not(feature = "scroll"))]
+mod selfdestruct;
 mod sha3;
 mod shl_shr;
 mod signed_comparator;
@@ -137,14 +145,20 @@ mod sload;
 mod sstore;
 mod stop;
 mod swap;
+#[cfg(feature = "cancun")]
+mod tload;
+#[cfg(feature = "cancun")]
+mod tstore;
 
-use self::{logs::LogGadget, precompiles::BasePrecompileGadget, sha3::Sha3Gadget};
+use self::{logs::LogGadget, sha3::Sha3Gadget};
 use add_sub::AddSubGadget;
 use addmod::AddModGadget;
 use address::AddressGadget;
 use balance::BalanceGadget;
 use begin_tx::BeginTxGadget;
 use bitwise::BitwiseGadget;
+#[cfg(feature = "cancun")]
+use blobhash::BlobHashGadget;
 #[cfg(feature = "scroll")]
 use block_ctx::DifficultyGadget;
 use block_ctx::{BlockCtxU160Gadget, BlockCtxU256Gadget, BlockCtxU64Gadget};
@@ -161,8 +175,7 @@ use codecopy::CodeCopyGadget;
 use codesize::CodesizeGadget;
 use comparator::ComparatorGadget;
 use create::CreateGadget;
-#[cfg(not(feature = "scroll"))]
-use dummy::DummyGadget;
+use destruct_account::DestructAccountGadget;
 use dup::DupGadget;
 use end_block::EndBlockGadget;
 use end_inner_block::EndInnerBlockGadget;
@@ -179,6 +192,8 @@ use error_oog_dynamic_memory::ErrorOOGDynamicMemoryGadget;
 use error_oog_exp::ErrorOOGExpGadget;
 use error_oog_log::ErrorOOGLogGadget;
 use error_oog_memory_copy::ErrorOOGMemoryCopyGadget;
+#[cfg(not(feature = "scroll"))]
+use error_oog_self_destruct::ErrorOOGSelfDestructGadget;
 use error_oog_sha3::ErrorOOGSha3Gadget;
 use error_oog_sload_sstore::ErrorOOGSloadSstoreGadget;
 use error_oog_static_memory::ErrorOOGStaticMemoryGadget;
@@ -198,6 +213,8 @@ use jumpdest::JumpdestGadget;
 use jumpi::JumpiGadget;
 
 use crate::evm_circuit::execution::error_oog_precompile::ErrorOOGPrecompileGadget;
+#[cfg(feature = "cancun")]
+use mcopy::McopyGadget;
 use memory::MemoryGadget;
 use msize::MsizeGadget;
 use mul_div_mod::MulDivModGadget;
@@ -207,8 +224,8 @@ use origin::OriginGadget;
 use pc::PcGadget;
 use pop::PopGadget;
 use precompiles::{
-    EcAddGadget, EcMulGadget, EcPairingGadget, EcrecoverGadget, IdentityGadget, ModExpGadget,
-    SHA256Gadget,
+    Blake2fGadget, EcAddGadget, EcMulGadget, EcPairingGadget, EcrecoverGadget, IdentityGadget,
+    ModExpGadget, Ripemd160Gadget, SHA256Gadget,
 };
 use push::PushGadget;
 use return_revert::ReturnRevertGadget;
@@ -217,6 +234,8 @@ use returndatasize::ReturnDataSizeGadget;
 use sar::SarGadget;
 use sdiv_smod::SignedDivModGadget;
 use selfbalance::SelfbalanceGadget;
+#[cfg(not(feature = "scroll"))]
+use selfdestruct::SelfDestructGadget;
 use shl_shr::ShlShrGadget;
 use signed_comparator::SignedComparatorGadget;
 use signextend::SignextendGadget;
@@ -224,6 +243,10 @@ use sload::SloadGadget;
 use sstore::SstoreGadget;
 use stop::StopGadget;
 use swap::SwapGadget;
+#[cfg(feature = "cancun")]
+use tload::TloadGadget;
+#[cfg(feature = "cancun")]
+use tstore::TstoreGadget;
 
 pub(crate) trait ExecutionGadget<F: Field> {
     const NAME: &'static str;
@@ -270,6 +293,7 @@ pub(crate) struct ExecutionConfig<F> {
     end_block_gadget: Box<EndBlockGadget<F>>,
     end_inner_block_gadget: Box<EndInnerBlockGadget<F>>,
     end_tx_gadget: Box<EndTxGadget<F>>,
+    destruct_account_gadget: Box<DestructAccountGadget<F>>,
     // opcode gadgets
     add_sub_gadget: Box<AddSubGadget<F>>,
     addmod_gadget: Box<AddModGadget<F>>,
@@ -319,13 +343,21 @@ pub(crate) struct ExecutionConfig<F> {
     create_gadget: Box<CreateGadget<F, false, { ExecutionState::CREATE }>>,
     create2_gadget: Box<CreateGadget<F, true, { ExecutionState::CREATE2 }>>,
     #[cfg(not(feature = "scroll"))]
-    selfdestruct_gadget: Box<DummyGadget<F, 1, 0, { ExecutionState::SELFDESTRUCT }>>,
+    selfdestruct_gadget: Box<SelfDestructGadget<F>>,
     signed_comparator_gadget: Box<SignedComparatorGadget<F>>,
     signextend_gadget: Box<SignextendGadget<F>>,
     sload_gadget: Box<SloadGadget<F>>,
     sstore_gadget: Box<SstoreGadget<F>>,
     stop_gadget: Box<StopGadget<F>>,
     swap_gadget: Box<SwapGadget<F>>,
+    #[cfg(feature = "cancun")]
+    tload_gadget: Box<TloadGadget<F>>,
+    #[cfg(feature = "cancun")]
+    tstore_gadget: Box<TstoreGadget<F>>,
+    #[cfg(feature = "cancun")]
+    mcopy_gadget: Box<McopyGadget<F>>,
+    #[cfg(feature = "cancun")]
+    blobhash_gadget: Box<BlobHashGadget<F>>,
     blockhash_gadget: Box<BlockHashGadget<F>>,
     block_ctx_u64_gadget: Box<BlockCtxU64Gadget<F>>,
     block_ctx_u160_gadget: Box<BlockCtxU160Gadget<F>>,
@@ -349,8 +381,7 @@ pub(crate) struct ExecutionConfig<F> {
     error_oog_create: Box<ErrorOOGCreateGadget<F>>,
     error_code_store: Box<ErrorCodeStoreGadget<F>>,
     #[cfg(not(feature = "scroll"))]
-    error_oog_self_destruct:
-        Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorOutOfGasSELFDESTRUCT }>>,
+    error_oog_self_destruct: Box<ErrorOOGSelfDestructGadget<F>>,
     error_invalid_jump: Box<ErrorInvalidJumpGadget<F>>,
     error_invalid_opcode: Box<ErrorInvalidOpcodeGadget<F>>,
     error_invalid_creation_code: Box<ErrorInvalidCreationCodeGadget<F>>,
@@ -359,13 +390,13 @@ pub(crate) struct ExecutionConfig<F> {
     // precompile calls
     precompile_ecrecover_gadget: Box<EcrecoverGadget<F>>,
     precompile_sha2_gadget: Box<SHA256Gadget<F>>,
-    precompile_ripemd_gadget: Box<BasePrecompileGadget<F, { ExecutionState::PrecompileRipemd160 }>>,
+    precompile_ripemd_gadget: Box<Ripemd160Gadget<F>>,
     precompile_identity_gadget: Box<IdentityGadget<F>>,
     precompile_modexp_gadget: Box<ModExpGadget<F>>,
     precompile_bn128add_gadget: Box<EcAddGadget<F>>,
     precompile_bn128mul_gadget: Box<EcMulGadget<F>>,
     precompile_bn128pairing_gadget: Box<EcPairingGadget<F>>,
-    precompile_blake2f_gadget: Box<BasePrecompileGadget<F, { ExecutionState::PrecompileBlake2f }>>,
+    precompile_blake2f_gadget: Box<Blake2fGadget<F>>,
 }
 
 impl<F: Field> ExecutionConfig<F> {
@@ -383,6 +414,8 @@ impl<F: Field> ExecutionConfig<F> {
         copy_table: &dyn LookupTable<F>,
         keccak_table: &dyn LookupTable<F>,
         sha256_table: &dyn LookupTable<F>,
+        ripemd160_table: &dyn LookupTable<F>,
+        blake2f_table: &dyn LookupTable<F>,
         exp_table: &dyn LookupTable<F>,
         sig_table: &dyn LookupTable<F>,
         modexp_table: &dyn LookupTable<F>,
@@ -551,6 +584,7 @@ impl<F: Field> ExecutionConfig<F> {
             end_block_gadget: configure_gadget!(),
             end_inner_block_gadget: configure_gadget!(),
             end_tx_gadget: configure_gadget!(),
+            destruct_account_gadget: configure_gadget!(),
             // opcode gadgets
             add_sub_gadget: configure_gadget!(),
             addmod_gadget: configure_gadget!(),
@@ -608,6 +642,14 @@ impl<F: Field> ExecutionConfig<F> {
             sstore_gadget: configure_gadget!(),
             stop_gadget: configure_gadget!(),
             swap_gadget: configure_gadget!(),
+            #[cfg(feature = "cancun")]
+            tload_gadget: configure_gadget!(),
+            #[cfg(feature = "cancun")]
+            tstore_gadget: configure_gadget!(),
+            #[cfg(feature = "cancun")]
+            mcopy_gadget: configure_gadget!(),
+            #[cfg(feature = "cancun")]
+            blobhash_gadget: configure_gadget!(),
             block_ctx_u64_gadget: configure_gadget!(),
             block_ctx_u160_gadget: configure_gadget!(),
             block_ctx_u256_gadget: configure_gadget!(),
@@ -664,6 +706,8 @@ impl<F: Field> ExecutionConfig<F> {
             copy_table,
             keccak_table,
             sha256_table,
+            ripemd160_table,
+            blake2f_table,
             exp_table,
             sig_table,
             modexp_table,
@@ -830,9 +874,14 @@ impl<F: Field> ExecutionConfig<F> {
                 .chain(
                     IntoIterator::into_iter([
                         (
-                            "EndTx can only transit to BeginTx or EndInnerBlock",
+                            "EndTx can only transit to BeginTx, EndInnerBlock or DestructAccount",
                             ExecutionState::EndTx,
-                            vec![ExecutionState::BeginTx, ExecutionState::EndInnerBlock],
+                            vec![ExecutionState::BeginTx, ExecutionState::EndInnerBlock, ExecutionState::DestructAccount],
+                        ),
+                        (
+                            "DestructAccount can only transit to DestructAccount, BeginTx or EndInnerBlock",
+                            ExecutionState::DestructAccount,
+                            vec![ExecutionState::DestructAccount, ExecutionState::BeginTx, ExecutionState::EndInnerBlock],
                         ),
                         (
                             "EndInnerBlock can only transition to BeginTx, EndInnerBlock or EndBlock",
@@ -851,9 +900,14 @@ impl<F: Field> ExecutionConfig<F> {
                 .chain(
                     IntoIterator::into_iter([
                         (
-                            "Only EndTx or EndInnerBlock can transit to BeginTx",
+                            "Only EndTx, DestructAccount or EndInnerBlock can transit to BeginTx",
                             ExecutionState::BeginTx,
-                            vec![ExecutionState::EndTx, ExecutionState::EndInnerBlock],
+                            vec![ExecutionState::EndTx, ExecutionState::DestructAccount, ExecutionState::EndInnerBlock],
+                        ),
+                        (
+                            "Only EndTx or DestructAccount can transit to DestructAccount",
+                            ExecutionState::DestructAccount,
+                            vec![ExecutionState::EndTx, ExecutionState::DestructAccount],
                         ),
                         (
                             "Only ExecutionState which halts / precompile or BeginTx can transit to EndTx",
@@ -871,9 +925,9 @@ impl<F: Field> ExecutionConfig<F> {
                         ),
                         (
                             // Empty block can result multiple EndInnerBlock states.
-                            "Only EndTx or EndInnerBlock can transit to EndInnerBlock",
+                            "Only EndTx, DestructAccount or EndInnerBlock can transit to EndInnerBlock",
                             ExecutionState::EndInnerBlock,
-                            vec![ExecutionState::EndTx, ExecutionState::EndInnerBlock],
+                            vec![ExecutionState::EndTx, ExecutionState::DestructAccount, ExecutionState::EndInnerBlock],
                         ),
                     ])
                     .filter(move |(_, _, from)| !from.contains(&execution_state))
@@ -933,6 +987,8 @@ impl<F: Field> ExecutionConfig<F> {
         copy_table: &dyn LookupTable<F>,
         keccak_table: &dyn LookupTable<F>,
         sha256_table: &dyn LookupTable<F>,
+        ripemd160_table: &dyn LookupTable<F>,
+        blake2f_table: &dyn LookupTable<F>,
         exp_table: &dyn LookupTable<F>,
         sig_table: &dyn LookupTable<F>,
         modexp_table: &dyn LookupTable<F>,
@@ -954,6 +1010,8 @@ impl<F: Field> ExecutionConfig<F> {
                         Table::Copy => copy_table,
                         Table::Keccak => keccak_table,
                         Table::Sha256 => sha256_table,
+                        Table::Ripemd160 => ripemd160_table,
+                        Table::Blake2f => blake2f_table,
                         Table::Exp => exp_table,
                         Table::Sig => sig_table,
                         Table::ModExp => modexp_table,
@@ -1387,6 +1445,8 @@ impl<F: Field> ExecutionConfig<F> {
             ("EVM_lookup_copy", COPY_TABLE_LOOKUPS),
             ("EVM_lookup_keccak", KECCAK_TABLE_LOOKUPS),
             ("EVM_lookup_sha256", SHA256_TABLE_LOOKUPS),
+            ("EVM_lookup_ripemd160", RIPEMD160_TABLE_LOOKUPS),
+            ("EVM_lookup_blake2f", BLAKE2F_TABLE_LOOKUPS),
             ("EVM_lookup_exp", EXP_TABLE_LOOKUPS),
             ("EVM_lookup_sig", SIG_TABLE_LOOKUPS),
             ("EVM_lookup_modexp", MODEXP_TABLE_LOOKUPS),
@@ -1536,6 +1596,7 @@ impl<F: Field> ExecutionConfig<F> {
             // internal states
             ExecutionState::BeginTx => assign_exec_step!(self.begin_tx_gadget),
             ExecutionState::EndTx => assign_exec_step!(self.end_tx_gadget),
+            ExecutionState::DestructAccount => assign_exec_step!(self.destruct_account_gadget),
             ExecutionState::EndInnerBlock => assign_exec_step!(self.end_inner_block_gadget),
             ExecutionState::EndBlock => assign_exec_step!(self.end_block_gadget),
             // opcode
@@ -1590,13 +1651,11 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::SELFBALANCE => assign_exec_step!(self.selfbalance_gadget),
             ExecutionState::CREATE => assign_exec_step!(self.create_gadget),
             ExecutionState::CREATE2 => assign_exec_step!(self.create2_gadget),
-            // dummy gadgets
             ExecutionState::EXTCODECOPY => assign_exec_step!(self.extcodecopy_gadget),
             ExecutionState::SELFDESTRUCT => {
                 #[cfg(not(feature = "scroll"))]
                 assign_exec_step!(self.selfdestruct_gadget)
             }
-            // end of dummy gadgets
             ExecutionState::SHA3 => assign_exec_step!(self.sha3_gadget),
             ExecutionState::SHL_SHR => assign_exec_step!(self.shl_shr_gadget),
             ExecutionState::SIGNEXTEND => assign_exec_step!(self.signextend_gadget),
@@ -1604,6 +1663,14 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::SSTORE => assign_exec_step!(self.sstore_gadget),
             ExecutionState::STOP => assign_exec_step!(self.stop_gadget),
             ExecutionState::SWAP => assign_exec_step!(self.swap_gadget),
+            #[cfg(feature = "cancun")]
+            ExecutionState::TLOAD => assign_exec_step!(self.tload_gadget),
+            #[cfg(feature = "cancun")]
+            ExecutionState::TSTORE => assign_exec_step!(self.tstore_gadget),
+            #[cfg(feature = "cancun")]
+            ExecutionState::MCOPY => assign_exec_step!(self.mcopy_gadget),
+            #[cfg(feature = "cancun")]
+            ExecutionState::BLOBHASH => assign_exec_step!(self.blobhash_gadget),
             // dummy errors
             ExecutionState::ErrorOutOfGasStaticMemoryExpansion => {
                 assign_exec_step!(self.error_oog_static_memory_gadget)
@@ -44,6 +44,13 @@ const SHANGHAI_RW_DELTA: u8 = 1;
 #[cfg(not(feature = "shanghai"))]
 const SHANGHAI_RW_DELTA: u8 = 0;
 
+// For Shanghai, EIP-6780 adds 1 write op to mark the callee of a creation tx as
+// created in the tx.
+#[cfg(feature = "shanghai")]
+const CREATED_ACCOUNT_RW_DELTA: u8 = 1;
+#[cfg(not(feature = "shanghai"))]
+const CREATED_ACCOUNT_RW_DELTA: u8 = 0;
+
 const PRECOMPILE_COUNT: usize = 9;
 
 #[derive(Clone, Debug)]
@@ -446,6 +453,14 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 0.expr(),
                 Some(&mut reversion_info),
             );
+            #[cfg(feature = "shanghai")]
+            cb.tx_created_account_write(
+                tx_id.expr(),
+                call_callee_address.expr(),
+                1.expr(),
+                0.expr(),
+                Some(&mut reversion_info),
+            );
             for (field_tag, value) in [
                 (CallContextFieldTag::Depth, 1.expr()),
                 (CallContextFieldTag::CallerAddress, tx_caller_address.expr()),
@@ -485,6 +500,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 //   - Read Account CodeHash
                 //   - a TransferWithGasFeeGadget
                 //   - Write Account (Callee) Nonce (Reversible)
+                //   - Write TxCreatedAccount (Callee) (Reversible) only for Shanghai
                 //   - Write CallContext Depth
                 //   - Write CallContext CallerAddress
                 //   - Write CallContext CalleeAddress
@@ -504,6 +520,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                         + transfer_with_gas_fee.rw_delta()
                         + tx_access_list.rw_delta_expr()
                         + SHANGHAI_RW_DELTA.expr()
+                        + CREATED_ACCOUNT_RW_DELTA.expr()
                         + PRECOMPILE_COUNT.expr(),
                 ),
                 call_id: To(call_id.expr()),
@@ -511,10 +528,13 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 is_create: To(tx_is_create.expr()),
                 code_hash: To(cb.curr.state.code_hash.expr()),
                 gas_left: To(gas_left.clone()),
-                // There are a + 1 reversible writes:
+                // There are a + 1 (+ 1 for Shanghai) reversible writes:
                 //  - a TransferWithGasFeeGadget
                 //  - Callee Account Nonce
-                reversible_write_counter: To(transfer_with_gas_fee.reversible_w_delta() + 1.expr()),
+                //  - Callee TxCreatedAccount only for Shanghai
+                reversible_write_counter: To(transfer_with_gas_fee.reversible_w_delta()
+                    + 1.expr()
+                    + CREATED_ACCOUNT_RW_DELTA.expr()),
                 log_id: To(0.expr()),
                 ..StepStateTransition::new_context()
             });
@@ -1011,11 +1031,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             .unwrap_or_default()
         {
             let precompile_call: PrecompileCalls = tx.callee_address.unwrap().0[19].into();
-            let input_len = if let Some(input_len) = precompile_call.input_len() {
-                std::cmp::min(input_len, tx.call_data_length)
-            } else {
-                tx.call_data_length
-            };
+            let input_len = precompile_call.n_input_bytes(tx.call_data_length, &tx.call_data);
 
             let input_bytes_rlc = region.keccak_rlc(
                 &tx.call_data
@@ -0,0 +1,143 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_U64,
+        step::ExecutionState,
+        util::{
+            common_gadget::{SameContextGadget, WordByteCapGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::Delta,
+            },
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{CallContextFieldTag, TxContextFieldTag},
+    util::Expr,
+};
+use eth_types::{evm_types::OpcodeId, Field};
+use gadgets::util::not;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+#[derive(Clone, Debug)]
+pub(crate) struct BlobHashGadget<F> {
+    same_context: SameContextGadget<F>,
+    index: WordByteCapGadget<F, N_BYTES_U64>,
+    tx_id: Cell<F>,
+    blob_hashes_len: Cell<F>,
+    blob_hash: Cell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for BlobHashGadget<F> {
+    const NAME: &'static str = "BLOBHASH";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::BLOBHASH;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+
+        let blob_hashes_len = cb.query_cell();
+        let index = WordByteCapGadget::construct(cb, blob_hashes_len.expr());
+        cb.stack_pop(index.original_word());
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        cb.tx_context_lookup(
+            tx_id.expr(),
+            TxContextFieldTag::BlobVersionedHashesLen,
+            None,
+            blob_hashes_len.expr(),
+        );
+
+        // The versioned hash is looked up from the dynamic section of the tx
+        // table if the index is in range, otherwise zero is pushed.
+        let blob_hash = cb.query_cell_phase2();
+        cb.condition(index.lt_cap(), |cb| {
+            cb.tx_context_lookup(
+                tx_id.expr(),
+                TxContextFieldTag::BlobVersionedHash,
+                Some(index.valid_value()),
+                blob_hash.expr(),
+            );
+        });
+        cb.condition(not::expr(index.lt_cap()), |cb| {
+            cb.require_zero("Out of range index for blob hash lookup", blob_hash.expr());
+        });
+
+        cb.stack_push(blob_hash.expr());
+
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(3.expr()),
+            program_counter: Delta(1.expr()),
+            gas_left: Delta(-OpcodeId::BLOBHASH.constant_gas_cost().expr()),
+            ..Default::default()
+        };
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            index,
+            tx_id,
+            blob_hashes_len,
+            blob_hash,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+
+        let index = block.rws[step.rw_indices[0]].stack_value();
+        let blob_hash = block.rws[step.rw_indices[2]].stack_value();
+
+        let blob_hashes_len = F::from(tx.blob_versioned_hashes.len() as u64);
+        self.index.assign(region, offset, index, blob_hashes_len)?;
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
+        self.blob_hashes_len
+            .assign(region, offset, Value::known(blob_hashes_len))?;
+        self.blob_hash
+            .assign(region, offset, region.word_rlc(blob_hash))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{bytecode, U256};
+    use mock::test_ctx::{helpers::*, TestContext};
+
+    fn test_ok(index: U256) {
+        let code = bytecode! {
+            PUSH32(index)
+            BLOBHASH
+            STOP
+        };
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn blobhash_gadget_out_of_range() {
+        test_ok(0.into());
+        test_ok(1.into());
+        test_ok(U256::MAX);
+    }
+}
@@ -989,11 +989,24 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             return_rws,
         ) = if is_precheck_ok && is_precompiled(&callee_address.to_address()) {
             let precompile_call: PrecompileCalls = precompile_addr.0[19].into();
-            let input_len = if let Some(input_len) = precompile_call.input_len() {
-                min(input_len, cd_length.as_usize())
-            } else {
-                cd_length.as_usize()
-            };
+            // the input of modexp depends on the sizes at the head of the call data, which are
+            // peeked from the memory words read for the input
+            let call_data_head =
+                if precompile_call == PrecompileCalls::Modexp && !cd_length.is_zero() {
+                    let head_len = min(cd_length.as_usize(), 96);
+                    let range = MemoryWordRange::align_range(cd_offset.as_usize(), head_len);
+                    let rws_offset = rws.offset();
+                    let words = (0..range.word_count())
+                        .map(|_| rws.next().memory_word_pair().0)
+                        .flat_map(|word| word.to_be_bytes())
+                        .collect::<Vec<_>>();
+                    rws.offset_set(rws_offset);
+                    let start = cd_offset.as_usize() - range.start_slot().0;
+                    words[start..start + head_len].to_vec()
+                } else {
+                    vec![]
+                };
+            let input_len = precompile_call.n_input_bytes(cd_length.as_usize(), &call_data_head);
             let [input_bytes_start_offset, input_bytes_end_offset, input_bytes_word_count] =
                 // Correspond to this check in bus-mapping.
                 // <https://github.com/scroll-tech/zkevm-circuits/blob/25dd32aa316ec842ffe79bb8efe9f05f86edc33e/bus-mapping/src/evm/opcodes/callop.rs#L349>
@@ -1835,9 +1848,6 @@ mod test_precompiles {
             address: Word::from(0x3),
             stack_value: vec![(
                 Word::from(0x20),
-                #[cfg(feature = "scroll")]
-                Word::zero(),
-                #[cfg(not(feature = "scroll"))]
                 word!("2c0c45d3ecab80fe060e5f1d7057cd2f8de5e557"),
             )],
             ..Default::default()
@@ -2051,16 +2061,10 @@ mod test_precompiles {
             stack_value: vec![
                 (
                     Word::from(0x20),
-                    #[cfg(feature = "scroll")]
-                    word!("3af54fa5d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e13"),
-                    #[cfg(not(feature = "scroll"))]
                     word!("d282e6ad7f520e511f6c3e2b8c68059b9442be0454267ce079217e1319cde05b"),
                 ),
                 (
                     Word::from(0x0),
-                    #[cfg(feature = "scroll")]
-                    word!("0000000048c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f"),
-                    #[cfg(not(feature = "scroll"))]
                     word!("8c9bcf367e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5"),
                 ),
             ],
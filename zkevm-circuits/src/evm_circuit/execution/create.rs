@@ -36,6 +36,13 @@ use halo2_proofs::{circuit::Value, plonk::Error};
 use log::trace;
 use std::iter::once;
 
+// For Shanghai, EIP-6780 adds 1 reversible write op to mark the contract as
+// created in the tx.
+#[cfg(feature = "shanghai")]
+const CREATED_ACCOUNT_RW_DELTA: u8 = 1;
+#[cfg(not(feature = "shanghai"))]
+const CREATED_ACCOUNT_RW_DELTA: u8 = 0;
+
 /// Gadget for CREATE and CREATE2 opcodes
 #[derive(Clone, Debug)]
 pub(crate) struct CreateGadget<F, const IS_CREATE2: bool, const S: ExecutionState> {
@@ -424,6 +431,18 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                     0.expr(),
                     Some(&mut callee_reversion_info),
                 );
+                // EIP-6780, mark the contract as created in this tx. It can't be
+                // marked already: a creation that succeeded earlier in the tx
+                // leaves a non-zero nonce (an address collision), and a reverted
+                // one reverts the mark as well.
+                #[cfg(feature = "shanghai")]
+                cb.tx_created_account_write(
+                    tx_id.expr(),
+                    contract_addr.clone(),
+                    1.expr(),
+                    0.expr(),
+                    Some(&mut callee_reversion_info),
+                );
 
                 cb.condition(init_code.has_length(), |cb| {
                     for (field_tag, value) in [
@@ -461,7 +480,9 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                         is_create: To(true.expr()),
                         code_hash: To(create.code_hash_word_rlc()),
                         gas_left: To(callee_gas_left),
-                        reversible_write_counter: To(1.expr() + transfer.reversible_w_delta()),
+                        reversible_write_counter: To(1.expr()
+                            + CREATED_ACCOUNT_RW_DELTA.expr()
+                            + transfer.reversible_w_delta()),
                         ..StepStateTransition::new_context()
                     });
                 });
@@ -484,7 +505,11 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                         program_counter: Delta(1.expr()),
                         stack_pointer: Delta(2.expr() + IS_CREATE2.expr()),
                         gas_left: Delta(-gas_cost.expr()),
-                        reversible_write_counter: Delta(3.expr() + transfer.reversible_w_delta()),
+                        reversible_write_counter: Delta(
+                            3.expr()
+                                + CREATED_ACCOUNT_RW_DELTA.expr()
+                                + transfer.reversible_w_delta(),
+                        ),
                         ..Default::default()
                     })
                 });
@@ -791,6 +816,8 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                 F::one()
             } else {
                 rws.next(); // callee nonce += 1
+                #[cfg(feature = "shanghai")]
+                rws.next(); // callee is created in tx
                 rws.next(); // caller id
                 let rw = rws.next();
                 debug_assert_eq!(rw.tag(), RwTableTag::CallContext);
@@ -0,0 +1,330 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            constraint_builder::{
+                EVMConstraintBuilder, StepStateTransition,
+                Transition::{Delta, Same},
+            },
+            math_gadget::IsZeroGadget,
+            not, CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag, RwTableTag},
+    util::Expr,
+};
+use eth_types::{Field, ToScalar};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for the virtual steps after EndTx, which destruct the accounts self
+/// destructed in the transaction.
+///
+/// An account marked by `SELFDESTRUCT` keeps its code, nonce and storage until
+/// the end of the transaction. Each of its storage slots is cleared by a step
+/// in storage mode, which reads the `TxDestructedAccount` mark, and a last step
+/// clears the mark and the account's code hash, nonce and balance. The State
+/// Circuit requires the mark to be cleared at its last access, so no
+/// destructed account can be skipped.
+///
+/// The storage slots cleared are the ones known from the trace, the storage
+/// trie of the account can't be deleted.
+#[derive(Clone, Debug)]
+pub(crate) struct DestructAccountGadget<F> {
+    tx_id: Cell<F>,
+    address: Cell<F>,
+    /// Whether this step clears a storage slot of the account, or the account.
+    is_storage: Cell<F>,
+    storage_key: Cell<F>,
+    storage_value_prev: Cell<F>,
+    storage_committed_value: Cell<F>,
+    code_hash_prev: Cell<F>,
+    nonce_prev: Cell<F>,
+    balance: Cell<F>,
+    balance_is_zero: IsZeroGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for DestructAccountGadget<F> {
+    const NAME: &'static str = "DestructAccount";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::DestructAccount;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // call_id is propagated from EndTx
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let address = cb.query_cell();
+        let is_storage = cb.query_bool();
+
+        let storage_key = cb.query_cell_phase2();
+        let storage_value_prev = cb.query_cell_phase2();
+        let storage_committed_value = cb.query_cell_phase2();
+        cb.condition(is_storage.expr(), |cb| {
+            cb.tx_destructed_account_read(tx_id.expr(), address.expr(), 1.expr());
+            cb.account_storage_write(
+                address.expr(),
+                storage_key.expr(),
+                0.expr(),
+                storage_value_prev.expr(),
+                tx_id.expr(),
+                storage_committed_value.expr(),
+                None,
+            );
+        });
+
+        let code_hash_prev = cb.query_cell_phase2();
+        let nonce_prev = cb.query_cell();
+        let balance = cb.query_cell_phase2();
+        let balance_is_zero = IsZeroGadget::construct(cb, balance.expr());
+        cb.condition(not::expr(is_storage.expr()), |cb| {
+            cb.tx_destructed_account_write(tx_id.expr(), address.expr(), 0.expr(), 1.expr(), None);
+            // Write code hash first, the nonce of a contract account is at least 1 so it's
+            // still non-empty at this point.
+            cb.account_write(
+                address.expr(),
+                AccountFieldTag::CodeHash,
+                0.expr(),
+                code_hash_prev.expr(),
+                None,
+            );
+            cb.account_write(
+                address.expr(),
+                AccountFieldTag::Nonce,
+                0.expr(),
+                nonce_prev.expr(),
+                None,
+            );
+            // The balance received after SELFDESTRUCT in the same tx is burnt.
+            cb.account_read(address.expr(), AccountFieldTag::Balance, balance.expr());
+            cb.condition(not::expr(balance_is_zero.expr()), |cb| {
+                cb.account_write(
+                    address.expr(),
+                    AccountFieldTag::Balance,
+                    0.expr(),
+                    balance.expr(),
+                    None,
+                );
+            });
+        });
+
+        // rwc_delta = 1 + is_storage * 2 + !is_storage * (4 + !balance_is_zero)
+        let rw_counter_offset = 1.expr()
+            + is_storage.expr() * 2.expr()
+            + not::expr(is_storage.expr()) * (4.expr() + not::expr(balance_is_zero.expr()));
+
+        // The next state of `destruct_account` can only be 'begin_tx', 'end_inner_block' or
+        // 'destruct_account'
+        cb.condition(
+            cb.next.execution_state_selector([ExecutionState::BeginTx]),
+            |cb| {
+                let next_step_rwc = cb.next.state.rw_counter.expr();
+                // lookup use next step initial rwc, thus lead to same record on rw table
+                cb.call_context_lookup_write_with_counter(
+                    next_step_rwc.clone(),
+                    Some(next_step_rwc),
+                    CallContextFieldTag::TxId,
+                    tx_id.expr() + 1.expr(),
+                );
+
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Delta(rw_counter_offset.clone()),
+                    ..StepStateTransition::any()
+                });
+            },
+        );
+
+        cb.condition(
+            cb.next.execution_state_selector([
+                ExecutionState::DestructAccount,
+                ExecutionState::EndInnerBlock,
+            ]),
+            |cb| {
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Delta(rw_counter_offset),
+                    // We propagate call_id so that the next DestructAccount or EndBlock can get
+                    // the tx_id.
+                    call_id: Same,
+                    ..StepStateTransition::any()
+                });
+            },
+        );
+
+        Self {
+            tx_id,
+            address,
+            is_storage,
+            storage_key,
+            storage_value_prev,
+            storage_committed_value,
+            code_hash_prev,
+            nonce_prev,
+            balance,
+            balance_is_zero,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
+
+        let destructed_rw = &block.rws[step.rw_indices[1]];
+        let address = destructed_rw.address().unwrap();
+        self.address
+            .assign(region, offset, Value::known(address.to_scalar().unwrap()))?;
+
+        let is_storage = step.rw_indices[2].0 == RwTableTag::AccountStorage;
+        self.is_storage
+            .assign(region, offset, Value::known(F::from(is_storage as u64)))?;
+
+        if is_storage {
+            let storage_rw = &block.rws[step.rw_indices[2]];
+            let (_, value_prev, _, committed_value) = storage_rw.storage_value_aux();
+            self.storage_key.assign(
+                region,
+                offset,
+                region.word_rlc(storage_rw.storage_key().unwrap()),
+            )?;
+            self.storage_value_prev
+                .assign(region, offset, region.word_rlc(value_prev))?;
+            self.storage_committed_value.assign(
+                region,
+                offset,
+                region.word_rlc(committed_value),
+            )?;
+        } else {
+            let (_, code_hash_prev) = block.rws[step.rw_indices[2]].account_codehash_pair();
+            self.code_hash_prev
+                .assign(region, offset, region.code_hash(code_hash_prev))?;
+            let (_, nonce_prev) = block.rws[step.rw_indices[3]].account_nonce_pair();
+            self.nonce_prev.assign(
+                region,
+                offset,
+                Value::known(nonce_prev.to_scalar().unwrap()),
+            )?;
+            let (balance, _) = block.rws[step.rw_indices[4]].account_balance_pair();
+            self.balance
+                .assign(region, offset, region.word_rlc(balance))?;
+            self.balance_is_zero
+                .assign_value(region, offset, region.word_rlc(balance))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{bytecode, Address, Bytecode, ToWord, Word};
+    use mock::TestContext;
+
+    const CALLER: Address = Address::repeat_byte(0x01);
+    const CONTRACT: Address = Address::repeat_byte(0x10);
+    const HELPER: Address = Address::repeat_byte(0x11);
+    const BENEFICIARY: Address = Address::repeat_byte(0x20);
+    const ENTRY: Address = Address::repeat_byte(0x30);
+
+    fn call(address: Address) -> Bytecode {
+        bytecode! {
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH20(address.to_word())
+            PUSH2(50000)
+            CALL
+            POP
+        }
+    }
+
+    // The contract self destructs, then `calls_after` runs and its code is read in the same tx,
+    // before it's destructed after EndTx.
+    fn test_ok(calls_after: Bytecode, storage: Vec<(Word, Word)>) {
+        let contract_code = bytecode! {
+            PUSH1(1)
+            SLOAD
+            POP
+            PUSH20(BENEFICIARY.to_word())
+            SELFDESTRUCT
+        };
+        // The helper moves its balance to the already destructed contract.
+        let helper_code = bytecode! {
+            PUSH20(CONTRACT.to_word())
+            SELFDESTRUCT
+        };
+        let mut entry_code = call(CONTRACT);
+        entry_code.append(&calls_after);
+        entry_code.append(&bytecode! {
+            PUSH20(CONTRACT.to_word())
+            EXTCODEHASH
+            PUSH20(CONTRACT.to_word())
+            EXTCODESIZE
+            STOP
+        });
+
+        let ctx = TestContext::<5, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(CALLER).balance(Word::from(1u64 << 30));
+                accs[1]
+                    .address(CONTRACT)
+                    .balance(Word::from(1000u64))
+                    .nonce(Word::one())
+                    .code(contract_code)
+                    .storage(storage.into_iter());
+                accs[2]
+                    .address(HELPER)
+                    .balance(Word::from(100u64))
+                    .nonce(Word::one())
+                    .code(helper_code);
+                accs[3].address(BENEFICIARY).balance(Word::from(1u64 << 20));
+                accs[4].address(ENTRY).code(entry_code);
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[4].address)
+                    .gas(Word::from(1_000_000));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn destruct_account_after_selfdestruct() {
+        test_ok(bytecode! {}, vec![]);
+    }
+
+    #[test]
+    fn destruct_account_clears_storage() {
+        test_ok(
+            bytecode! {},
+            vec![
+                (Word::one(), Word::from(0x1234u64)),
+                (Word::from(2), Word::one()),
+            ],
+        );
+    }
+
+    #[test]
+    fn destruct_account_called_back_after_selfdestruct() {
+        test_ok(call(CONTRACT), vec![(Word::one(), Word::from(0x1234u64))]);
+    }
+
+    #[test]
+    fn destruct_account_burns_balance_received_after_selfdestruct() {
+        test_ok(call(HELPER), vec![(Word::one(), Word::from(0x1234u64))]);
+    }
+}
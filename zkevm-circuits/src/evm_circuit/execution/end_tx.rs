@@ -226,7 +226,8 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
         );
         // rwc_delta = 9 - is_first_tx + !tx_is_l1msg * (coinbase_transfer.rw_delta + 1)
 
-        // The next state of `end_tx` can only be 'begin_tx' or 'end_inner_block'
+        // The next state of `end_tx` can only be 'begin_tx', 'end_inner_block' or
+        // 'destruct_account'
 
         let rw_counter_offset = 9.expr() - is_first_tx.expr()
             + not::expr(tx_is_l1msg.expr()) * (coinbase_transfer.rw_delta() + 1.expr());
@@ -255,7 +256,7 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
                 .execution_state_selector([ExecutionState::EndInnerBlock]),
             |cb| {
                 cb.require_step_state_transition(StepStateTransition {
-                    rw_counter: Delta(rw_counter_offset),
+                    rw_counter: Delta(rw_counter_offset.clone()),
                     // We propagate call_id so that EndBlock can get the last tx_id
                     // in order to count processed txs.
                     call_id: Same,
@@ -264,6 +265,19 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
             },
         );
 
+        cb.condition(
+            cb.next
+                .execution_state_selector([ExecutionState::DestructAccount]),
+            |cb| {
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Delta(rw_counter_offset),
+                    // We propagate call_id so that DestructAccount can read the tx_id.
+                    call_id: Same,
+                    ..StepStateTransition::any()
+                });
+            },
+        );
+
         Self {
             tx_id,
             tx_gas,
@@ -12,7 +12,7 @@ use crate::{
                 CommonMemoryAddressGadget, MemoryCopierGasGadget, MemoryExpandedAddressGadget,
                 MemoryExpansionGadget,
             },
-            or, select, CachedRegion, Cell, Word,
+            not, or, select, CachedRegion, Cell, Word,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
@@ -27,7 +27,8 @@ use halo2_proofs::{circuit::Value, plonk::Error};
 
 /// Gadget to implement the corresponding out of gas errors for
 /// [`OpcodeId::CALLDATACOPY`], [`OpcodeId::CODECOPY`],
-/// [`OpcodeId::EXTCODECOPY`] and [`OpcodeId::RETURNDATACOPY`].
+/// [`OpcodeId::EXTCODECOPY`], [`OpcodeId::RETURNDATACOPY`] and
+/// [`OpcodeId::MCOPY`].
 #[derive(Clone, Debug)]
 pub(crate) struct ErrorOOGMemoryCopyGadget<F> {
     opcode: Cell<F>,
@@ -40,10 +41,13 @@ pub(crate) struct ErrorOOGMemoryCopyGadget<F> {
     src_offset: Word<F>,
     /// Destination offset and size to copy
     dst_memory_addr: MemoryExpandedAddressGadget<F>,
-    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    /// Source offset and size to copy, only used by `MCOPY`
+    src_memory_addr: MemoryExpandedAddressGadget<F>,
+    memory_expansion: MemoryExpansionGadget<F, 2, N_BYTES_MEMORY_WORD_SIZE>,
     memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY }>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
     is_extcodecopy: IsZeroGadget<F>,
+    is_mcopy: IsZeroGadget<F>,
     common_error_gadget: CommonErrorGadget<F>,
 }
 
@@ -55,13 +59,14 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         let opcode = cb.query_cell();
         cb.require_in_set(
-            "ErrorOutOfGasMemoryCopy opcode must be CALLDATACOPY, CODECOPY, EXTCODECOPY or RETURNDATACOPY",
+            "ErrorOutOfGasMemoryCopy opcode must be CALLDATACOPY, CODECOPY, EXTCODECOPY, RETURNDATACOPY or MCOPY",
             opcode.expr(),
             vec![
                 OpcodeId::CALLDATACOPY.expr(),
                 OpcodeId::CODECOPY.expr(),
                 OpcodeId::EXTCODECOPY.expr(),
                 OpcodeId::RETURNDATACOPY.expr(),
+                OpcodeId::MCOPY.expr(),
             ],
         );
 
@@ -72,6 +77,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
 
         let is_extcodecopy =
             IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::EXTCODECOPY.expr());
+        let is_mcopy = IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::MCOPY.expr());
 
         cb.condition(is_extcodecopy.expr(), |cb| {
             cb.call_context_lookup(false.expr(), None, CallContextFieldTag::TxId, tx_id.expr());
@@ -93,7 +99,31 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
         cb.stack_pop(src_offset.expr());
         cb.stack_pop(dst_memory_addr.length_rlc());
 
-        let memory_expansion = MemoryExpansionGadget::construct(cb, [dst_memory_addr.end_offset()]);
+        // MCOPY also expands the memory for the source range, of the same size.
+        let src_memory_addr = MemoryExpandedAddressGadget::construct_self(cb);
+        cb.condition(is_mcopy.expr(), |cb| {
+            cb.require_equal(
+                "MCOPY source offset",
+                src_memory_addr.offset_rlc(),
+                src_offset.expr(),
+            );
+            cb.require_equal(
+                "MCOPY source and destination have the same size",
+                src_memory_addr.length_rlc(),
+                dst_memory_addr.length_rlc(),
+            );
+        });
+        cb.condition(not::expr(is_mcopy.expr()), |cb| {
+            cb.require_zero(
+                "No source memory expansion if not MCOPY",
+                src_memory_addr.length_rlc(),
+            );
+        });
+
+        let memory_expansion = MemoryExpansionGadget::construct(
+            cb,
+            [dst_memory_addr.end_offset(), src_memory_addr.end_offset()],
+        );
         let memory_copier_gas = MemoryCopierGasGadget::construct(
             cb,
             dst_memory_addr.length(),
@@ -109,7 +139,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
                 GasCost::WARM_ACCESS.expr(),
                 GasCost::COLD_ACCOUNT_ACCESS.expr(),
             ),
-            // Constant gas cost is same for CALLDATACOPY, CODECOPY and RETURNDATACOPY.
+            // Constant gas cost is same for CALLDATACOPY, CODECOPY, RETURNDATACOPY and MCOPY.
             OpcodeId::CALLDATACOPY.constant_gas_cost().expr(),
         );
 
@@ -121,7 +151,11 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
 
         cb.require_equal(
             "Memory address is overflow or gas left is less than cost",
-            or::expr([dst_memory_addr.overflow(), insufficient_gas.expr()]),
+            or::expr([
+                dst_memory_addr.overflow(),
+                is_mcopy.expr() * src_memory_addr.overflow(),
+                insufficient_gas.expr(),
+            ]),
             1.expr(),
         );
 
@@ -140,10 +174,12 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
             external_address,
             src_offset,
             dst_memory_addr,
+            src_memory_addr,
             memory_expansion,
             memory_copier_gas,
             insufficient_gas,
             is_extcodecopy,
+            is_mcopy,
             common_error_gadget,
         }
     }
@@ -193,9 +229,20 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
         let memory_addr = self
             .dst_memory_addr
             .assign(region, offset, dst_offset, copy_size)?;
-        let (_, memory_expansion_cost) =
-            self.memory_expansion
-                .assign(region, offset, step.memory_word_size(), [memory_addr])?;
+        let src_memory_addr = if opcode == OpcodeId::MCOPY {
+            self.src_memory_addr
+                .assign(region, offset, src_offset, copy_size)?
+        } else {
+            self.src_memory_addr
+                .assign(region, offset, U256::zero(), U256::zero())?;
+            0
+        };
+        let (_, memory_expansion_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [memory_addr, src_memory_addr],
+        )?;
         let memory_copier_gas = self.memory_copier_gas.assign(
             region,
             offset,
@@ -222,6 +269,11 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
             offset,
             F::from(opcode.as_u64()) - F::from(OpcodeId::EXTCODECOPY.as_u64()),
         )?;
+        self.is_mcopy.assign(
+            region,
+            offset,
+            F::from(opcode.as_u64()) - F::from(OpcodeId::MCOPY.as_u64()),
+        )?;
         self.common_error_gadget.assign(
             region,
             offset,
@@ -291,6 +343,21 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "cancun")]
+    #[test]
+    fn test_oog_memory_copy_for_mcopy() {
+        for (src_offset, (dst_offset, copy_size)) in [0x00, 0x4000]
+            .iter()
+            .cartesian_product(TESTING_DST_OFFSET_COPY_SIZE_PAIRS.iter())
+        {
+            let testing_data =
+                TestingData::new_for_mcopy(*dst_offset, *src_offset, *copy_size, None);
+
+            test_root(&testing_data);
+            test_internal(&testing_data);
+        }
+    }
+
     #[test]
     fn test_oog_memory_copy_max_expanded_address() {
         // 0xffffffff1 + 0xffffffff0 = 0x1fffffffe1
@@ -333,6 +400,36 @@ mod tests {
             Self { bytecode, gas_cost }
         }
 
+        #[cfg(feature = "cancun")]
+        pub fn new_for_mcopy(
+            dst_offset: u64,
+            src_offset: u64,
+            copy_size: u64,
+            gas_cost: Option<u64>,
+        ) -> Self {
+            let bytecode = bytecode! {
+                PUSH32(copy_size)
+                PUSH32(src_offset)
+                PUSH32(dst_offset)
+                MCOPY
+            };
+
+            let gas_cost = gas_cost.unwrap_or_else(|| {
+                // Both the source and destination ranges expand the memory.
+                let memory_word_size = if copy_size == 0 {
+                    0
+                } else {
+                    (dst_offset.max(src_offset) + copy_size + 31) / 32
+                };
+
+                OpcodeId::PUSH32.constant_gas_cost().0 * 3
+                    + OpcodeId::MCOPY.constant_gas_cost().0
+                    + memory_copier_gas_cost(0, memory_word_size, copy_size, GasCost::COPY.as_u64())
+            });
+
+            Self { bytecode, gas_cost }
+        }
+
         pub fn new_for_extcodecopy(
             is_warm: bool,
             dst_offset: u64,
@@ -58,13 +58,21 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
                 )
             },
         );
-        let n_words = cb.condition(addr_bits.value_equals(PrecompileCalls::Identity), |cb| {
-            ConstantDivisionGadget::construct(
-                cb,
-                call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
-                N_BYTES_WORD as u64,
-            )
-        });
+        // precompiles whose gas cost is charged by the input words
+        let n_words = cb.condition(
+            sum::expr([
+                addr_bits.value_equals(PrecompileCalls::Sha256),
+                addr_bits.value_equals(PrecompileCalls::Ripemd160),
+                addr_bits.value_equals(PrecompileCalls::Identity),
+            ]),
+            |cb| {
+                ConstantDivisionGadget::construct(
+                    cb,
+                    call_data_length.expr() + (N_BYTES_WORD - 1).expr(),
+                    N_BYTES_WORD as u64,
+                )
+            },
+        );
 
         // calculate required gas for precompile
         let precompiles_required_gas = vec![
@@ -78,8 +86,12 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
                 GasCost::PRECOMPILE_SHA256_BASE.expr()
                     + n_words.quotient() * GasCost::PRECOMPILE_SHA256_PER_WORD.expr(),
             ),
-            // addr_bits.value_equals(PrecompileCalls::Ripemd160),
-            // addr_bits.value_equals(PrecompileCalls::Blake2F),
+            (
+                addr_bits.value_equals(PrecompileCalls::Ripemd160),
+                GasCost::PRECOMPILE_RIPEMD160_BASE.expr()
+                    + n_words.quotient() * GasCost::PRECOMPILE_RIPEMD160_PER_WORD.expr(),
+            ),
+            // blake2f is handled in Blake2fGadget
             (
                 addr_bits.value_equals(PrecompileCalls::Identity),
                 GasCost::PRECOMPILE_IDENTITY_BASE.expr()
@@ -207,6 +219,11 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGPrecompileGadget<F> {
                 precompile_call.base_gas_cost().as_u64()
                     + n_words * GasCost::PRECOMPILE_SHA256_PER_WORD.as_u64()
             }
+            PrecompileCalls::Ripemd160 => {
+                let n_words = (call.call_data_length + 31) / 32;
+                precompile_call.base_gas_cost().as_u64()
+                    + n_words * GasCost::PRECOMPILE_RIPEMD160_PER_WORD.as_u64()
+            }
             PrecompileCalls::Bn128Add | PrecompileCalls::Bn128Mul | PrecompileCalls::Ecrecover => {
                 precompile_call.base_gas_cost().as_u64()
             }
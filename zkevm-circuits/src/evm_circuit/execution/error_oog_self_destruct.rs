@@ -0,0 +1,262 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS},
+        step::ExecutionState,
+        util::{
+            common_gadget::CommonErrorGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            from_bytes,
+            math_gadget::{IsZeroGadget, LtGadget},
+            not, CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag},
+    util::Expr,
+};
+use eth_types::{
+    evm_types::{GasCost, OpcodeId},
+    Field, ToLittleEndian, ToScalar,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget to implement the corresponding out of gas errors for
+/// [`OpcodeId::SELFDESTRUCT`].
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorOOGSelfDestructGadget<F> {
+    opcode: Cell<F>,
+    beneficiary_word: Word<F>,
+    tx_id: Cell<F>,
+    is_warm: Cell<F>,
+    callee_address: Cell<F>,
+    beneficiary_code_hash: Cell<F>,
+    beneficiary_not_exists: IsZeroGadget<F>,
+    balance: Cell<F>,
+    value_is_zero: IsZeroGadget<F>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    common_error_gadget: CommonErrorGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ErrorOOGSelfDestructGadget<F> {
+    const NAME: &'static str = "ErrorOutOfGasSELFDESTRUCT";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorOutOfGasSELFDESTRUCT;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.require_equal(
+            "ErrorOutOfGasSELFDESTRUCT opcode must be SELFDESTRUCT",
+            opcode.expr(),
+            OpcodeId::SELFDESTRUCT.expr(),
+        );
+
+        let beneficiary_word = cb.query_word_rlc();
+        let beneficiary = from_bytes::expr(&beneficiary_word.cells[..N_BYTES_ACCOUNT_ADDRESS]);
+        cb.stack_pop(beneficiary_word.expr());
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let is_warm = cb.query_bool();
+        // read is_warm
+        cb.account_access_list_read(tx_id.expr(), beneficiary.expr(), is_warm.expr());
+
+        // The NEW_ACCOUNT gas is charged if the beneficiary doesn't exist and the
+        // balance of current account is not zero.
+        let callee_address = cb.call_context(None, CallContextFieldTag::CalleeAddress);
+        let beneficiary_code_hash = cb.query_cell_phase2();
+        cb.account_read(
+            beneficiary.expr(),
+            AccountFieldTag::CodeHash,
+            beneficiary_code_hash.expr(),
+        );
+        let beneficiary_not_exists = IsZeroGadget::construct(cb, beneficiary_code_hash.expr());
+        let balance = cb.query_cell_phase2();
+        cb.account_read(
+            callee_address.expr(),
+            AccountFieldTag::Balance,
+            balance.expr(),
+        );
+        let value_is_zero = IsZeroGadget::construct(cb, balance.expr());
+
+        let gas_cost = GasCost::SELFDESTRUCT.expr()
+            + not::expr(is_warm.expr()) * GasCost::COLD_ACCOUNT_ACCESS.expr()
+            + beneficiary_not_exists.expr()
+                * not::expr(value_is_zero.expr())
+                * GasCost::NEW_ACCOUNT.expr();
+        let insufficient_gas = LtGadget::construct(cb, cb.curr.state.gas_left.expr(), gas_cost);
+        cb.require_equal(
+            "Gas left is less than gas cost",
+            insufficient_gas.expr(),
+            1.expr(),
+        );
+
+        let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 8.expr());
+        Self {
+            opcode,
+            beneficiary_word,
+            tx_id,
+            is_warm,
+            callee_address,
+            beneficiary_code_hash,
+            beneficiary_not_exists,
+            balance,
+            value_is_zero,
+            insufficient_gas,
+            common_error_gadget,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode.unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let beneficiary = block.rws[step.rw_indices[0]].stack_value();
+        self.beneficiary_word
+            .assign(region, offset, Some(beneficiary.to_le_bytes()))?;
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
+
+        let (_, is_warm) = block.rws[step.rw_indices[2]].tx_access_list_value_pair();
+        self.is_warm
+            .assign(region, offset, Value::known(F::from(is_warm)))?;
+
+        self.callee_address.assign(
+            region,
+            offset,
+            Value::known(
+                call.address
+                    .to_scalar()
+                    .expect("unexpected Address -> Scalar conversion failure"),
+            ),
+        )?;
+
+        let beneficiary_code_hash = block.rws[step.rw_indices[4]].account_value_pair().0;
+        self.beneficiary_code_hash.assign(
+            region,
+            offset,
+            region.code_hash(beneficiary_code_hash),
+        )?;
+        self.beneficiary_not_exists.assign_value(
+            region,
+            offset,
+            region.code_hash(beneficiary_code_hash),
+        )?;
+
+        let balance = block.rws[step.rw_indices[5]].account_value_pair().0;
+        self.balance
+            .assign(region, offset, region.word_rlc(balance))?;
+        self.value_is_zero
+            .assign_value(region, offset, region.word_rlc(balance))?;
+
+        let mut gas_cost = GasCost::SELFDESTRUCT.as_u64();
+        if !is_warm {
+            gas_cost += GasCost::COLD_ACCOUNT_ACCESS.as_u64();
+        }
+        if beneficiary_code_hash.is_zero() && !balance.is_zero() {
+            gas_cost += GasCost::NEW_ACCOUNT.as_u64();
+        }
+        self.insufficient_gas
+            .assign(region, offset, F::from(step.gas_left), F::from(gas_cost))?;
+
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, 8)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{
+        address, bytecode,
+        evm_types::{GasCost, OpcodeId},
+        ToWord, Word,
+    };
+    use mock::TestContext;
+
+    fn test_oog(beneficiary_exists: bool, is_root: bool) {
+        let beneficiary = if beneficiary_exists {
+            address!("0x0000000000000000000000000000000000cafe03")
+        } else {
+            address!("0x0000000000000000000000000000000000cafe04")
+        };
+        let code = bytecode! {
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        };
+        // Leave the callee just short of the constant gas of SELFDESTRUCT.
+        let callee_gas =
+            OpcodeId::PUSH20.constant_gas_cost().as_u64() + GasCost::SELFDESTRUCT.as_u64() - 1;
+
+        let ctx = TestContext::<4, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000000123"))
+                    .balance(Word::from(1u64 << 30));
+                accs[1]
+                    .address(address!("0x0000000000000000000000000000000000000010"))
+                    .balance(Word::from(1u64 << 20))
+                    .code(bytecode! {
+                        PUSH1(0)
+                        PUSH1(0)
+                        PUSH1(0)
+                        PUSH1(0)
+                        PUSH1(0)
+                        PUSH1(0x20)
+                        PUSH32(callee_gas)
+                        CALL
+                        STOP
+                    });
+                accs[2]
+                    .address(address!("0x0000000000000000000000000000000000000020"))
+                    .balance(Word::from(1000u64))
+                    .nonce(Word::one())
+                    .code(code);
+                accs[3]
+                    .address(address!("0x0000000000000000000000000000000000cafe03"))
+                    .balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                let to = if is_root {
+                    accs[2].address
+                } else {
+                    accs[1].address
+                };
+                let gas = if is_root {
+                    GasCost::TX.as_u64() + callee_gas
+                } else {
+                    100_000
+                };
+                txs[0].from(accs[0].address).to(to).gas(Word::from(gas));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn test_oog_self_destruct_root() {
+        test_oog(true, true);
+        test_oog(false, true);
+    }
+
+    #[test]
+    fn test_oog_self_destruct_internal() {
+        test_oog(true, false);
+        test_oog(false, false);
+    }
+}
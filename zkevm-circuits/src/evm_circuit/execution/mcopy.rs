@@ -0,0 +1,226 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_MEMORY_WORD_SIZE,
+        step::ExecutionState,
+        util::{
+            common_gadget::SameContextGadget,
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::{Delta, To},
+            },
+            memory_gadget::{
+                CommonMemoryAddressGadget, MemoryAddressGadget, MemoryCopierGasGadget,
+                MemoryExpansionGadget,
+            },
+            not, CachedRegion, Cell, MemoryAddress,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId};
+use eth_types::{evm_types::GasCost, Field, ToScalar};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for MCOPY, which copies a memory range to another one in the same
+/// call. The ranges may overlap, the copy circuit reads all the source words
+/// before writing any destination word.
+#[derive(Clone, Debug)]
+pub(crate) struct McopyGadget<F> {
+    same_context: SameContextGadget<F>,
+    /// Destination offset and length to copy
+    dst_memory_addr: MemoryAddressGadget<F>,
+    /// Source offset and the same length
+    src_memory_addr: MemoryAddressGadget<F>,
+    copy_rwc_inc: Cell<F>,
+    memory_expansion: MemoryExpansionGadget<F, 2, N_BYTES_MEMORY_WORD_SIZE>,
+    memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY }>,
+}
+
+impl<F: Field> ExecutionGadget<F> for McopyGadget<F> {
+    const NAME: &'static str = "MCOPY";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::MCOPY;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+
+        let dst_offset = cb.query_cell_phase2();
+        let src_offset = cb.query_cell_phase2();
+        let length: MemoryAddress<F> = cb.query_word_rlc();
+
+        // Pop dst_offset, src_offset, length from stack
+        cb.stack_pop(dst_offset.expr());
+        cb.stack_pop(src_offset.expr());
+        cb.stack_pop(length.expr());
+
+        // Both ranges share the same length, and both may expand the memory.
+        let dst_memory_addr = MemoryAddressGadget::construct(cb, dst_offset, length.clone());
+        let src_memory_addr = MemoryAddressGadget::construct(cb, src_offset, length);
+        let memory_expansion = MemoryExpansionGadget::construct(
+            cb,
+            [dst_memory_addr.end_offset(), src_memory_addr.end_offset()],
+        );
+        let memory_copier_gas = MemoryCopierGasGadget::construct(
+            cb,
+            dst_memory_addr.length(),
+            memory_expansion.gas_cost(),
+        );
+
+        let copy_rwc_inc = cb.query_cell();
+        cb.condition(dst_memory_addr.has_length(), |cb| {
+            cb.copy_table_lookup(
+                cb.curr.state.call_id.expr(),
+                CopyDataType::Memory.expr(),
+                cb.curr.state.call_id.expr(),
+                CopyDataType::Memory.expr(),
+                src_memory_addr.offset(),
+                src_memory_addr.end_offset(),
+                dst_memory_addr.offset(),
+                dst_memory_addr.length(),
+                0.expr(), // for MCOPY rlc_acc is 0
+                copy_rwc_inc.expr(),
+            );
+        });
+        cb.condition(not::expr(dst_memory_addr.has_length()), |cb| {
+            cb.require_zero(
+                "if no bytes to copy, copy table rwc inc == 0",
+                copy_rwc_inc.expr(),
+            );
+        });
+
+        // State transition
+        let step_state_transition = StepStateTransition {
+            // 3 stack pop + copy table rw lookups
+            rw_counter: Delta(cb.rw_counter_offset()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Delta(3.expr()),
+            gas_left: Delta(
+                -(OpcodeId::MCOPY.constant_gas_cost().expr() + memory_copier_gas.gas_cost()),
+            ),
+            memory_word_size: To(memory_expansion.next_memory_word_size()),
+            ..Default::default()
+        };
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            dst_memory_addr,
+            src_memory_addr,
+            copy_rwc_inc,
+            memory_expansion,
+            memory_copier_gas,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        _call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+
+        let [dst_offset, src_offset, length] =
+            [step.rw_indices[0], step.rw_indices[1], step.rw_indices[2]]
+                .map(|idx| block.rws[idx].stack_value());
+        let dst_memory_addr = self
+            .dst_memory_addr
+            .assign(region, offset, dst_offset, length)?;
+        let src_memory_addr = self
+            .src_memory_addr
+            .assign(region, offset, src_offset, length)?;
+
+        self.copy_rwc_inc.assign(
+            region,
+            offset,
+            Value::known(
+                step.copy_rw_counter_delta
+                    .to_scalar()
+                    .expect("unexpected U256 -> Scalar conversion failure"),
+            ),
+        )?;
+
+        // Memory expansion
+        let (_, memory_expansion_gas_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [dst_memory_addr, src_memory_addr],
+        )?;
+
+        self.memory_copier_gas.assign(
+            region,
+            offset,
+            length.as_u64(),
+            memory_expansion_gas_cost,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{evm_circuit::test::rand_bytes_array, test_util::CircuitTestBuilder};
+    use eth_types::{bytecode, evm_types::OpcodeId, Bytecode, Word};
+    use mock::{test_ctx::helpers::*, TestContext};
+
+    fn test_ok(dst_offset: usize, src_offset: usize, length: usize) {
+        // Initialize some memory before the copy.
+        let mut code = Bytecode::default();
+        for i in 0..4u64 {
+            code.push(32, Word::from_big_endian(&rand_bytes_array::<32>()));
+            code.push(2, Word::from(32 * i));
+            code.write_op(OpcodeId::MSTORE);
+        }
+        code.append(&bytecode! {
+            PUSH32(length)
+            PUSH32(src_offset)
+            PUSH32(dst_offset)
+            MCOPY
+            STOP
+        });
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _txs| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn mcopy_gadget_simple() {
+        test_ok(0x80, 0x00, 0x40);
+    }
+
+    #[test]
+    fn mcopy_gadget_unaligned() {
+        test_ok(0x45, 0x13, 0x2b);
+    }
+
+    #[test]
+    fn mcopy_gadget_overlapping() {
+        test_ok(0x10, 0x00, 0x50);
+        test_ok(0x00, 0x21, 0x50);
+        test_ok(0x20, 0x20, 0x40);
+    }
+
+    #[test]
+    fn mcopy_gadget_expand_by_source() {
+        test_ok(0x00, 0x100, 0x20);
+    }
+
+    #[test]
+    fn mcopy_gadget_zero_length() {
+        test_ok(0x10000, 0x20000, 0x00);
+    }
+}
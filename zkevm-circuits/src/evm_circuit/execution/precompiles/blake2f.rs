@@ -0,0 +1,375 @@
+use bus_mapping::{circuit_input_builder::BLAKE2F_INPUT_LEN, precompile::PrecompileAuxData};
+use eth_types::{Field, ToScalar};
+use gadgets::util::{and, not, select, Expr};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_GAS,
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            from_bytes,
+            math_gadget::{IsEqualGadget, LtGadget},
+            rlc, CachedRegion, Cell,
+        },
+    },
+    table::CallContextFieldTag,
+    witness::{Block, Call, ExecStep, Transaction},
+};
+
+#[derive(Clone, Debug)]
+pub struct Blake2fGadget<F> {
+    input_bytes_rlc: Cell<F>,
+    output_bytes_rlc: Cell<F>,
+    return_bytes_rlc: Cell<F>,
+
+    /// The 213 input bytes: rounds (4 bytes, big-endian), h (64 bytes), m (128 bytes),
+    /// t (16 bytes) and the final block indicator flag (1 byte).
+    input_bytes: [Cell<F>; BLAKE2F_INPUT_LEN],
+    is_valid_length: IsEqualGadget<F>,
+    is_valid_flag: LtGadget<F, 1>,
+    is_gas_insufficient: LtGadget<F, N_BYTES_GAS>,
+
+    is_success: Cell<F>,
+    callee_address: Cell<F>,
+    is_root: Cell<F>,
+    call_data_offset: Cell<F>,
+    call_data_length: Cell<F>,
+    return_data_offset: Cell<F>,
+    return_data_length: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for Blake2fGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileBlake2f;
+
+    const NAME: &'static str = "BLAKE2F";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let (input_bytes_rlc, output_bytes_rlc, return_bytes_rlc) = (
+            cb.query_cell_phase2(),
+            cb.query_cell_phase2(),
+            cb.query_cell_phase2(),
+        );
+        let [is_success, callee_address, is_root, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [
+                CallContextFieldTag::IsSuccess,
+                CallContextFieldTag::CalleeAddress,
+                CallContextFieldTag::IsRoot,
+                CallContextFieldTag::CallDataOffset,
+                CallContextFieldTag::CallDataLength,
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|tag| cb.call_context(None, tag));
+
+        cb.precompile_info_lookup(
+            cb.execution_state().as_u64().expr(),
+            callee_address.expr(),
+            cb.execution_state().precompile_base_gas_cost().expr(),
+        );
+
+        let input_bytes = cb.query_bytes();
+        let is_valid_length =
+            IsEqualGadget::construct(cb, call_data_length.expr(), BLAKE2F_INPUT_LEN.expr());
+        // the input bytes only make sense when the calldata has exactly 213 bytes.
+        cb.condition(is_valid_length.expr(), |cb| {
+            cb.require_equal(
+                "input bytes rlc == RLC(input bytes)",
+                input_bytes_rlc.expr(),
+                rlc::expr(
+                    &input_bytes.iter().rev().map(Expr::expr).collect::<Vec<_>>(),
+                    cb.challenges().keccak_input(),
+                ),
+            );
+        });
+
+        // rounds is a big-endian u32, and also the gas cost of the call.
+        let rounds = from_bytes::expr(
+            &input_bytes[0..4]
+                .iter()
+                .rev()
+                .map(Expr::expr)
+                .collect::<Vec<_>>(),
+        );
+        let is_valid_flag =
+            LtGadget::construct(cb, input_bytes[BLAKE2F_INPUT_LEN - 1].expr(), 2.expr());
+        let is_gas_insufficient =
+            LtGadget::construct(cb, cb.curr.state.gas_left.expr(), rounds.expr());
+
+        cb.require_equal(
+            "call success if valid input and enough gas",
+            is_success.expr(),
+            and::expr([
+                is_valid_length.expr(),
+                is_valid_flag.expr(),
+                not::expr(is_gas_insufficient.expr()),
+            ]),
+        );
+
+        cb.condition(is_success.expr(), |cb| {
+            cb.blake2f_table_lookup(input_bytes_rlc.expr(), output_bytes_rlc.expr());
+        });
+
+        // all the gas is consumed for a failed call.
+        let gas_cost = select::expr(
+            is_success.expr(),
+            rounds.expr(),
+            cb.curr.state.gas_left.expr(),
+        );
+
+        let restore_context = super::gen_restore_context(
+            cb,
+            is_root.expr(),
+            is_success.expr(),
+            gas_cost.expr(),
+            select::expr(is_success.expr(), 0x40.expr(), 0x00.expr()), // ReturnDataLength
+        );
+
+        Self {
+            input_bytes_rlc,
+            output_bytes_rlc,
+            return_bytes_rlc,
+
+            input_bytes,
+            is_valid_length,
+            is_valid_flag,
+            is_gas_insufficient,
+
+            is_success,
+            callee_address,
+            is_root,
+            call_data_offset,
+            call_data_length,
+            return_data_offset,
+            return_data_length,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        if let Some(PrecompileAuxData::Blake2f(data)) = &step.aux_data {
+            for (col, bytes) in [
+                (&self.input_bytes_rlc, &data.input_bytes),
+                (&self.output_bytes_rlc, &data.output_bytes),
+                (&self.return_bytes_rlc, &data.return_bytes),
+            ] {
+                col.assign(
+                    region,
+                    offset,
+                    region
+                        .challenges()
+                        .keccak_input()
+                        .map(|r| rlc::value(bytes.iter().rev(), r)),
+                )?;
+            }
+
+            let input_bytes = if data.input_bytes.len() == BLAKE2F_INPUT_LEN {
+                data.input_bytes.clone()
+            } else {
+                vec![0u8; BLAKE2F_INPUT_LEN]
+            };
+            for (cell, &byte) in self.input_bytes.iter().zip(input_bytes.iter()) {
+                cell.assign(region, offset, Value::known(F::from(byte as u64)))?;
+            }
+            self.is_valid_length.assign(
+                region,
+                offset,
+                F::from(call.call_data_length),
+                F::from(BLAKE2F_INPUT_LEN as u64),
+            )?;
+            self.is_valid_flag.assign(
+                region,
+                offset,
+                F::from(input_bytes[BLAKE2F_INPUT_LEN - 1] as u64),
+                F::from(2),
+            )?;
+            self.is_gas_insufficient.assign(
+                region,
+                offset,
+                F::from(step.gas_left),
+                F::from(data.rounds as u64),
+            )?;
+        } else {
+            log::error!("unexpected aux_data {:?} for blake2f", step.aux_data);
+            return Err(Error::Synthesis);
+        }
+
+        self.is_success.assign(
+            region,
+            offset,
+            Value::known(F::from(u64::from(call.is_success))),
+        )?;
+        self.callee_address.assign(
+            region,
+            offset,
+            Value::known(call.code_address.unwrap().to_scalar().unwrap()),
+        )?;
+        self.is_root
+            .assign(region, offset, Value::known(F::from(call.is_root as u64)))?;
+        self.call_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_offset)),
+        )?;
+        self.call_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_length)),
+        )?;
+        self.return_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_offset)),
+        )?;
+        self.return_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_length)),
+        )?;
+        self.restore_context
+            .assign(region, offset, block, call, step, 7)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::{
+        evm::{OpcodeId, PrecompileCallArgs},
+        precompile::PrecompileCalls,
+    };
+    use eth_types::{Bytecode, ToWord, Word};
+    use itertools::Itertools;
+    use mock::TestContext;
+    use std::sync::LazyLock;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    /// h, m and t of the EIP-152 test vectors, i.e. the compression of "abc".
+    const BODY: &str = "48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b616263000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000003000000000000000000000000000000";
+
+    fn input(rounds: u32, flag: u8) -> Vec<u8> {
+        std::iter::empty()
+            .chain(rounds.to_be_bytes())
+            .chain(hex::decode(BODY).unwrap())
+            .chain(std::iter::once(flag))
+            .collect()
+    }
+
+    /// place the input bytes in memory from offset 0.
+    fn setup_code(input: &[u8]) -> Bytecode {
+        let mut code = Bytecode::default();
+        for (i, chunk) in input.chunks(32).enumerate() {
+            let mut word = [0u8; 32];
+            word[..chunk.len()].copy_from_slice(chunk);
+            code.op_mstore(i * 32, Word::from_big_endian(&word));
+        }
+        code
+    }
+
+    fn call_args(name: &'static str, input: &[u8]) -> PrecompileCallArgs {
+        PrecompileCallArgs {
+            name,
+            setup_code: setup_code(input),
+            call_data_offset: 0x00.into(),
+            call_data_length: input.len().into(),
+            ret_offset: 0x100.into(),
+            ret_size: 0x40.into(),
+            address: PrecompileCalls::Blake2F.address().to_word(),
+            ..Default::default()
+        }
+    }
+
+    static TEST_VECTOR: LazyLock<Vec<PrecompileCallArgs>> = LazyLock::new(|| {
+        vec![
+            // EIP-152 test vector 5
+            call_args("12 rounds", &input(12, 1)),
+            // EIP-152 test vector 4
+            call_args("zero rounds", &input(0, 1)),
+            // EIP-152 test vector 6
+            call_args("non-final block", &input(12, 0)),
+            // EIP-152 test vector 7
+            call_args("one round", &input(1, 1)),
+            PrecompileCallArgs {
+                ret_size: 0x20.into(),
+                ..call_args("truncated return", &input(12, 1))
+            },
+            PrecompileCallArgs {
+                ret_offset: 0x00.into(),
+                ..call_args("overlapped return", &input(12, 1))
+            },
+        ]
+    });
+
+    static FAILURE_TEST_VECTOR: LazyLock<Vec<PrecompileCallArgs>> = LazyLock::new(|| {
+        vec![
+            // EIP-152 test vector 0
+            call_args("empty input", &[]),
+            // EIP-152 test vector 1
+            call_args("input too short", &input(12, 1)[..212]),
+            // EIP-152 test vector 2
+            call_args("input too long", &[input(12, 1), vec![0x00]].concat()),
+            // EIP-152 test vector 3
+            call_args("invalid flag", &input(12, 2)),
+            PrecompileCallArgs {
+                gas: 11.into(),
+                ..call_args("oog", &input(12, 1))
+            },
+        ]
+    });
+
+    #[test]
+    fn precompile_blake2f_common_test() {
+        let call_kinds = vec![
+            OpcodeId::CALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::CALLCODE,
+        ];
+
+        for (test_vector, &call_kind) in TEST_VECTOR.iter().cartesian_product(&call_kinds) {
+            let bytecode = test_vector.with_call_op(call_kind);
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+            )
+            .block_modifier(Box::new(|blk| {
+                let evts = blk.get_blake2f();
+                assert_eq!(evts.len(), 1);
+                assert_eq!(evts[0].output.len(), 64);
+            }))
+            .run();
+        }
+    }
+
+    #[test]
+    fn precompile_blake2f_failure_test() {
+        let call_kinds = vec![
+            OpcodeId::CALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::CALLCODE,
+        ];
+
+        for (test_vector, &call_kind) in FAILURE_TEST_VECTOR.iter().cartesian_product(&call_kinds) {
+            let bytecode = test_vector.with_call_op(call_kind);
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+            )
+            .block_modifier(Box::new(|blk| assert!(blk.get_blake2f().is_empty())))
+            .run();
+        }
+    }
+}
@@ -235,7 +235,7 @@ impl<F: Field> ExecutionGadget<F> for EcAddGadget<F> {
             self.padding.assign(
                 region,
                 offset,
-                PrecompileCalls::Bn128Add,
+                PrecompileCalls::Bn128Add.input_len(),
                 region
                     .challenges()
                     .keccak_input()
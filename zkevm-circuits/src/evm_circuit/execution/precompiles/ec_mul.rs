@@ -440,7 +440,7 @@ impl<F: Field> ExecutionGadget<F> for EcMulGadget<F> {
             self.padding.assign(
                 region,
                 offset,
-                PrecompileCalls::Bn128Mul,
+                PrecompileCalls::Bn128Mul.input_len(),
                 region
                     .challenges()
                     .keccak_input()
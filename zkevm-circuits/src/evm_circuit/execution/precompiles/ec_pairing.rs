@@ -1,9 +1,9 @@
 use bus_mapping::{
     circuit_input_builder::{N_BYTES_PER_PAIR, N_PAIRING_PER_OP},
-    precompile::{EcPairingError, PrecompileAuxData, PrecompileCalls},
+    precompile::{EcPairingError, PrecompileAuxData, PrecompileCalls, EC_PAIRING_MAX_INPUT_LEN},
 };
 use eth_types::{evm_types::GasCost, Field, ToScalar};
-use gadgets::util::{and, not, or, select, Expr};
+use gadgets::util::{and, not, select, Expr};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 use crate::{
@@ -13,7 +13,10 @@ use crate::{
         util::{
             common_gadget::RestoreContextGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-            math_gadget::{BinaryNumberGadget, IsZeroGadget, LtGadget},
+            math_gadget::{
+                BinaryNumberGadget, ConstantDivisionGadget, IsZeroGadget, LtGadget,
+                RangeCheckGadget,
+            },
             rlc, CachedRegion, Cell,
         },
     },
@@ -21,10 +24,13 @@ use crate::{
     witness::{Block, Call, ExecStep, Transaction},
 };
 
-/// Note: input_len ∈ { 0, 192, 384, 576, 768 } if valid.
+/// Note: input_len % 192 == 0 if valid, and input_len <= 768 unless the `ec-pairing-chain` feature
+/// lifts the limit of scroll evm.
 ///
-/// Note: input bytes are padded to 768 bytes within our zkEVM implementation to standardise a
-/// pairing operation, such that each pairing op has 4 pairs: [(G1, G2); 4].
+/// Note: input bytes are padded to a multiple of 768 bytes within our zkEVM implementation to
+/// standardise a pairing operation, such that each pairing op has 4 pairs: [(G1, G2); 4]. A call
+/// with more than 4 pairs is split across several chained pairing ops in the ECC circuit, the last
+/// of which holds the RLC of all (padded) input bytes and the result of the call.
 #[derive(Clone, Debug)]
 pub struct EcPairingGadget<F> {
     // Random linear combination of input bytes to the precompile ecPairing call.
@@ -39,21 +45,20 @@ pub struct EcPairingGadget<F> {
     output: Cell<F>,
 
     // Verify invalidity of input bytes. We basically check `or(1, 2)` where:
-    // 1. input_len > 4 * 192
+    // 1. input_len > 4 * 192, if EC_PAIRING_MAX_INPUT_LEN is set
     // 2. input_len % 192 != 0
     input_is_zero: IsZeroGadget<F>,
-
     // call_data_len must less than 2^32.
-    input_lt_769: LtGadget<F, 4>,
-    input_mod_192: Cell<F>,
-    input_div_192: Cell<F>,
-    input_mod_192_lt: LtGadget<F, 1>,
+    input_lt_max: LtGadget<F, 4>,
+    // (input_len // 192, input_len % 192)
+    input_div_192: ConstantDivisionGadget<F, 4>,
     input_mod_192_is_zero: IsZeroGadget<F>,
 
-    /// Number of pairs provided through EVM input. Since a maximum of 4 pairs can be supplied from
-    /// EVM, we need 3 binary bits for a max value of [1, 0, 0].
-    n_pairs: Cell<F>,
-    n_pairs_cmp: BinaryNumberGadget<F, 3>,
+    /// Number of pairs provided through EVM input is n_pairs = 4 * n_pairs_div_4 + n_pairs_mod_4,
+    /// where the latter determines the number of padding pairs in the last pairing op.
+    n_pairs_div_4: Cell<F>,
+    n_pairs_div_4_range: RangeCheckGadget<F, 4>,
+    n_pairs_mod_4: BinaryNumberGadget<F, 2>,
     rand_pow_64: Cell<F>,
 
     is_success: Cell<F>,
@@ -79,9 +84,6 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
             cb.query_bool(),
         );
 
-        let n_pairs = cb.query_cell();
-        let n_pairs_cmp = BinaryNumberGadget::construct(cb, n_pairs.expr());
-
         let [is_success, callee_address, is_root, call_data_offset, call_data_length, return_data_offset, return_data_length] =
             [
                 CallContextFieldTag::IsSuccess,
@@ -100,6 +102,34 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
             cb.execution_state().precompile_base_gas_cost().expr(),
         );
 
+        //////////////////////////////// INVALID BEGIN ////////////////////////////////
+        let input_is_zero = IsZeroGadget::construct(cb, call_data_length.expr());
+        // q == len(input) // 192, r == len(input) % 192
+        let input_div_192 =
+            ConstantDivisionGadget::construct(cb, call_data_length.expr(), N_BYTES_PER_PAIR as u64);
+        let input_mod_192_is_zero = IsZeroGadget::construct(cb, input_div_192.remainder());
+        let n_pairs = input_div_192.quotient();
+        // len(input) <= EC_PAIRING_MAX_INPUT_LEN, if any.
+        let input_lt_max = LtGadget::construct(
+            cb,
+            call_data_length.expr(),
+            (EC_PAIRING_MAX_INPUT_LEN.unwrap_or_default() + 1).expr(),
+        );
+        let input_len_valid = if EC_PAIRING_MAX_INPUT_LEN.is_some() {
+            and::expr([input_lt_max.expr(), input_mod_192_is_zero.expr()])
+        } else {
+            input_mod_192_is_zero.expr()
+        };
+        cb.condition(not::expr(input_len_valid.expr()), |cb| {
+            cb.require_equal(
+                "len(input) is invalid => is_success == false",
+                is_success.expr(),
+                false.expr(),
+            );
+            cb.require_zero("pairing check == 0", output.expr());
+        });
+        //////////////////////////////// INVALID END //////////////////////////////////
+
         // all gas sent to this call will be consumed if `is_success == false`.
         let gas_cost = select::expr(
             is_success.expr(),
@@ -115,64 +145,19 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
             is_success.expr() - output.expr(),
         );
 
-        //////////////////////////////// INVALID BEGIN ////////////////////////////////
-        let input_is_zero = IsZeroGadget::construct(cb, call_data_length.expr());
-        let input_lt_769 = LtGadget::construct(cb, call_data_length.expr(), 769.expr());
-        let (input_mod_192, input_div_192, input_mod_192_lt, input_mod_192_is_zero) = cb.condition(
-            and::expr([not::expr(input_is_zero.expr()), input_lt_769.expr()]),
-            |cb| {
-                // r == len(input) % 192
-                let input_mod_192 = cb.query_byte();
-                // r < 192
-                let input_mod_192_lt = LtGadget::construct(cb, input_mod_192.expr(), 192.expr());
-                cb.require_equal("len(input) % 192 < 192", input_mod_192_lt.expr(), 1.expr());
-                // q == len(input) // 192
-                let input_div_192 = cb.query_cell();
-                cb.require_in_set(
-                    "len(input) // 192 ∈ { 0, 1, 2, 3, 4 }",
-                    input_div_192.expr(),
-                    vec![0.expr(), 1.expr(), 2.expr(), 3.expr(), 4.expr()],
-                );
-                // q * 192 + r == call_data_length
-                cb.require_equal(
-                    "q * 192 + r == len(input)",
-                    input_div_192.expr() * 192.expr() + input_mod_192.expr(),
-                    call_data_length.expr(),
-                );
-                let input_mod_192_is_zero = IsZeroGadget::construct(cb, input_mod_192.expr());
-                (
-                    input_mod_192,
-                    input_div_192,
-                    input_mod_192_lt,
-                    input_mod_192_is_zero,
-                )
-            },
-        );
-        cb.condition(
-            // (len(input) > 768) || (len(input) % 192 != 0)
-            or::expr([
-                not::expr(input_lt_769.expr()),
-                not::expr(input_mod_192_is_zero.expr()),
-            ]),
+        ///////////////////////////////// VALID BEGIN /////////////////////////////////
+        let (n_pairs_div_4, n_pairs_div_4_range, n_pairs_mod_4, rand_pow_64) = cb.condition(
+            // (len(input) <= EC_PAIRING_MAX_INPUT_LEN) && (len(input) % 192 == 0)
+            input_len_valid,
             |cb| {
-                cb.require_equal(
-                    "len(input) is invalid => is_success == false",
-                    is_success.expr(),
-                    false.expr(),
+                // n_pairs == 4 * n_pairs_div_4 + n_pairs_mod_4, where n_pairs_mod_4 < 4.
+                let n_pairs_div_4 = cb.query_cell();
+                let n_pairs_div_4_range = RangeCheckGadget::construct(cb, n_pairs_div_4.expr());
+                let n_pairs_mod_4 = BinaryNumberGadget::construct(
+                    cb,
+                    n_pairs.expr() - n_pairs_div_4.expr() * N_PAIRING_PER_OP.expr(),
                 );
-                cb.require_zero("pairing check == 0", output.expr());
-            },
-        );
-        //////////////////////////////// INVALID END //////////////////////////////////
 
-        ///////////////////////////////// VALID BEGIN /////////////////////////////////
-        let rand_pow_64 = cb.condition(
-            // (len(input) == 0) || ((len(input) <= 768) && (len(input) % 192 == 0))
-            or::expr([
-                input_is_zero.expr(),
-                and::expr([input_lt_769.expr(), input_mod_192_is_zero.expr()]),
-            ]),
-            |cb| {
                 let rand_pow_64 = cb.query_cell_phase2();
                 let (rand_pow_192, rand_pow_384, rand_pow_576) = {
                     let rand_pow_128 = rand_pow_64.expr() * rand_pow_64.expr();
@@ -183,25 +168,22 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
                 };
                 cb.pow_of_rand_lookup(64.expr(), rand_pow_64.expr());
 
-                // RLC(inputs) that was processed in the ECC Circuit.
+                // RLC(inputs) that was processed in the ECC Circuit, i.e. padded to a multiple of
+                // 4 pairs.
                 let ecc_circuit_input_rlc = select::expr(
-                    n_pairs_cmp.value_equals(0usize),
-                    0.expr(),
+                    n_pairs_mod_4.value_equals(1usize),
+                    input_bytes_rlc.expr() * rand_pow_576.expr(), /* 576 bytes padded */
                     select::expr(
-                        n_pairs_cmp.value_equals(1usize),
-                        input_bytes_rlc.expr() * rand_pow_576.expr(), /* 576 bytes padded */
+                        n_pairs_mod_4.value_equals(2usize),
+                        input_bytes_rlc.expr() * rand_pow_384.expr(), /* 384 bytes padded */
                         select::expr(
-                            n_pairs_cmp.value_equals(2usize),
-                            input_bytes_rlc.expr() * rand_pow_384.expr(), /* 384 bytes padded */
-                            select::expr(
-                                n_pairs_cmp.value_equals(3usize),
-                                input_bytes_rlc.expr() * rand_pow_192.expr(), /* 192 bytes padded */
-                                input_bytes_rlc.expr(),                       /* 0 bytes padded */
-                            ),
+                            n_pairs_mod_4.value_equals(3usize),
+                            input_bytes_rlc.expr() * rand_pow_192.expr(), /* 192 bytes padded */
+                            input_bytes_rlc.expr(),                       /* 0 bytes padded */
                         ),
                     ),
                 );
-                cb.condition(n_pairs_cmp.value_equals(0usize), |cb| {
+                cb.condition(input_is_zero.expr(), |cb| {
                     cb.require_zero(
                         "ecPairing: n_pairs == 0 => evm input == 0",
                         input_bytes_rlc.expr(),
@@ -217,6 +199,8 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
                 //     - G1 co-ord is not in canonical form
                 //     - G2 co-ord is not in canonical form
                 //     - G1, G2 both valid
+                //
+                // For more than 4 pairs, this is the last of the chained pairing ops.
                 cb.ecc_table_lookup(
                     u64::from(PrecompileCalls::Bn128Pairing).expr(),
                     is_success.expr(),
@@ -229,27 +213,12 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
                     0.expr(),
                 );
 
-                // since len(input) was valid, we know that we are left with 3 scenarios:
-                // 1. pairing check == true
-                // 2. pairing check == false
-                // 3. invalid inputs:
-                //      - invalid field element
-                //      - point not on G1
-                //      - point not on G2
-                //
-                // In all the above, we know that len(input) % 192 == 0 and len(input) <= 768
-                cb.require_equal(
-                    "ecPairing: n_pairs * N_BYTES_PER_PAIR == call_data_length",
-                    n_pairs.expr() * N_BYTES_PER_PAIR.expr(),
-                    call_data_length.expr(),
-                );
-                cb.require_in_set(
-                    "ecPairing: input_len ∈ { 0, 192, 384, 576, 768 }",
-                    call_data_length.expr(),
-                    vec![0.expr(), 192.expr(), 384.expr(), 576.expr(), 768.expr()],
-                );
-
-                rand_pow_64
+                (
+                    n_pairs_div_4,
+                    n_pairs_div_4_range,
+                    n_pairs_mod_4,
+                    rand_pow_64,
+                )
             },
         );
         ///////////////////////////////// VALID END ///////////////////////////////////
@@ -270,14 +239,13 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
             output,
 
             input_is_zero,
-            input_lt_769,
-            input_mod_192,
+            input_lt_max,
             input_div_192,
-            input_mod_192_lt,
             input_mod_192_is_zero,
 
-            n_pairs,
-            n_pairs_cmp,
+            n_pairs_div_4,
+            n_pairs_div_4_range,
+            n_pairs_mod_4,
             rand_pow_64,
 
             is_success,
@@ -310,50 +278,48 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
                 "assign ec pairing exec step: calldata_len = {}",
                 call.call_data_length
             );
-            self.input_lt_769.assign(
+            self.input_lt_max.assign(
                 region,
                 offset,
                 F::from(call.call_data_length),
-                F::from(769),
+                F::from(EC_PAIRING_MAX_INPUT_LEN.unwrap_or_default() as u64 + 1),
             )?;
-            let (input_div_192, input_mod_192) = (
-                call.call_data_length / (N_BYTES_PER_PAIR as u64),
-                call.call_data_length % (N_BYTES_PER_PAIR as u64),
-            );
-            self.input_div_192
-                .assign(region, offset, Value::known(F::from(input_div_192)))?;
-            self.input_mod_192
-                .assign(region, offset, Value::known(F::from(input_mod_192)))?;
-            self.input_mod_192_lt
-                .assign(region, offset, F::from(input_mod_192), F::from(192))?;
+            let (n_pairs, input_mod_192) =
+                self.input_div_192
+                    .assign(region, offset, call.call_data_length as u128)?;
             self.input_mod_192_is_zero
-                .assign(region, offset, F::from(input_mod_192))?;
+                .assign(region, offset, F::from_u128(input_mod_192))?;
+            let n_pairs_div_4 = n_pairs / (N_PAIRING_PER_OP as u128);
+            self.n_pairs_div_4
+                .assign(region, offset, Value::known(F::from_u128(n_pairs_div_4)))?;
+            self.n_pairs_div_4_range
+                .assign(region, offset, F::from_u128(n_pairs_div_4))?;
+            self.n_pairs_mod_4.assign(
+                region,
+                offset,
+                (n_pairs % (N_PAIRING_PER_OP as u128)) as usize,
+            )?;
 
             match *res_aux_data {
                 Ok(aux_data) => {
                     debug_assert!(
-                        call.call_data_length <= (N_PAIRING_PER_OP * N_BYTES_PER_PAIR) as u64,
-                        "len(input) > 768"
+                        EC_PAIRING_MAX_INPUT_LEN
+                            .map_or(true, |max_len| call.call_data_length <= max_len as u64),
+                        "len(input) > EC_PAIRING_MAX_INPUT_LEN"
                     );
                     debug_assert!(
                         call.call_data_length % (N_BYTES_PER_PAIR as u64) == 0,
                         "len(input) % 192 != 0"
                     );
-                    // Consider only call_data_length bytes for EVM input.
+                    debug_assert_eq!(
+                        aux_data.0.input_bytes.len(),
+                        call.call_data_length as usize,
+                        "len(input) != call_data_length"
+                    );
                     self.input_bytes_rlc.assign(
                         region,
                         offset,
-                        keccak_rand.map(|r| {
-                            rlc::value(
-                                aux_data
-                                    .0
-                                    .to_bytes_be()
-                                    .iter()
-                                    .take(call.call_data_length as usize)
-                                    .rev(),
-                                r,
-                            )
-                        }),
+                        keccak_rand.map(|r| rlc::value(aux_data.0.input_bytes.iter().rev(), r)),
                     )?;
                     self.output_bytes_rlc.assign(
                         region,
@@ -377,11 +343,6 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
                                 .expect("ecPairing: output in {0, 1}"),
                         ),
                     )?;
-                    // Number of pairs provided in the EVM call.
-                    let n_pairs = (call.call_data_length as usize) / N_BYTES_PER_PAIR;
-                    self.n_pairs
-                        .assign(region, offset, Value::known(F::from(n_pairs as u64)))?;
-                    self.n_pairs_cmp.assign(region, offset, n_pairs)?;
                     self.rand_pow_64.assign(
                         region,
                         offset,
@@ -395,7 +356,8 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
                         "len(input) != call_data_length"
                     );
                     debug_assert!(
-                        (call.call_data_length > (N_PAIRING_PER_OP * N_BYTES_PER_PAIR) as u64)
+                        EC_PAIRING_MAX_INPUT_LEN
+                            .is_some_and(|max_len| call.call_data_length > max_len as u64)
                             || (call.call_data_length % (N_BYTES_PER_PAIR as u64) != 0),
                         "len(input) is expected to be invalid",
                     );
@@ -493,7 +455,7 @@ mod test {
                 address: PrecompileCalls::Bn128Pairing.address().to_word(),
                 ..Default::default()
             },
-            #[cfg(feature = "scroll")]
+            #[cfg(all(feature = "scroll", not(feature = "ec-pairing-chain")))]
             PrecompileCallArgs {
                 name: "ecPairing (invalid): all zero bytes, len(input) == 5 * 192",
                 setup_code: bytecode! {},
@@ -505,6 +467,18 @@ mod test {
                 address: PrecompileCalls::Bn128Pairing.address().to_word(),
                 ..Default::default()
             },
+            #[cfg(any(not(feature = "scroll"), feature = "ec-pairing-chain"))]
+            PrecompileCallArgs {
+                name: "ecPairing (valid): all zero bytes, len(input) == 5 * 192",
+                setup_code: bytecode! {},
+                call_data_offset: 0x00.into(),
+                call_data_length: 0x3C0.into(),
+                ret_offset: 0x3C0.into(),
+                ret_size: 0x20.into(),
+                value: 1.into(),
+                address: PrecompileCalls::Bn128Pairing.address().to_word(),
+                ..Default::default()
+            },
             PrecompileCallArgs {
                 name: "ecPairing (pairing true): 2 pairs",
                 setup_code: bytecode! {
@@ -726,9 +700,49 @@ mod test {
                 address: PrecompileCalls::Bn128Pairing.address().to_word(),
                 ..Default::default()
             },
-            #[cfg(feature = "scroll")]
+            #[cfg(any(not(feature = "scroll"), feature = "ec-pairing-chain"))]
+            PrecompileCallArgs {
+                name: "ecPairing (pairing true): 6 pairs",
+                setup_code: {
+                    let mut setup_code = bytecode! {};
+                    let pair_words = [
+                        // G1_x1, G1_y1
+                        word!("0x2cf44499d5d27bb186308b7af7af02ac5bc9eeb6a3d147c186b21fb1b76e18da"),
+                        word!("0x2c0f001f52110ccfe69108924926e45f0b0c868df0e7bde1fe16d3242dc715f6"),
+                        // G2_x11, G2_x12, G2_y11, G2_y12
+                        word!("0x1fb19bb476f6b9e44e2a32234da8212f61cd63919354bc06aef31e3cfaff3ebc"),
+                        word!("0x22606845ff186793914e03e21df544c34ffe2f2f3504de8a79d9159eca2d98d9"),
+                        word!("0x2bd368e28381e8eccb5fa81fc26cf3f048eea9abfdd85d7ed3ab3698d63e4f90"),
+                        word!("0x2fe02e47887507adf0ff1743cbac6ba291e66f59be6bd763950bb16041a0a85e"),
+                        // G1_x2, G1_y2
+                        word!("0x0000000000000000000000000000000000000000000000000000000000000001"),
+                        word!("0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45"),
+                        // G2_x21, G2_x22, G2_y21, G2_y22
+                        word!("0x1971ff0471b09fa93caaf13cbf443c1aede09cc4328f5a62aad45f40ec133eb4"),
+                        word!("0x091058a3141822985733cbdddfed0fd8d6c104e9e9eff40bf5abfef9ab163bc7"),
+                        word!("0x2a23af9a5ce2ba2796c1f4e453a370eb0af8c212d9dc9acd8fc02c2e907baea2"),
+                        word!("0x23a8eb0b0996252cb548a4487da97b02422ebc0e834613f954de6c7e0afdc1fc"),
+                    ];
+                    // the 2 pairs of a successful pairing check, 3 times, so that the call spans
+                    // 2 chained pairing ops.
+                    let mut memory_addr = 0x00;
+                    for word in std::iter::repeat(pair_words).take(3).flatten() {
+                        setup_code.push(32, word);
+                        setup_code.push(2, memory_addr);
+                        memory_addr += 0x20;
+                        setup_code.write_op(OpcodeId::MSTORE);
+                    }
+                    setup_code
+                },
+                call_data_offset: 0x00.into(),
+                call_data_length: 0x480.into(),
+                ret_offset: 0x480.into(),
+                ret_size: 0x20.into(),
+                address: PrecompileCalls::Bn128Pairing.address().to_word(),
+                ..Default::default()
+            },
             PrecompileCallArgs {
-                name: "ecPairing (invalid): len(input) > 768",
+                name: "ecPairing (invalid): len(input) > 768, len(input) % 192 != 0",
                 setup_code: bytecode! {},
                 call_data_offset: 0x00.into(),
                 call_data_length: 769.into(),
@@ -1079,21 +1093,18 @@ mod test {
     });
 
     static INVALID_LEN_TEST: LazyLock<Vec<PrecompileCallArgs>> = LazyLock::new(|| {
-        vec![
-            #[cfg(feature = "scroll")]
-            PrecompileCallArgs {
-                name: "ecPairing (invalid): len(input) > 768",
-                setup_code: bytecode! {},
-                call_data_offset: 0x00.into(),
-                call_data_length: 0x10340.into(),
-                ret_offset: 0xC0.into(),
-                ret_size: 0x20.into(),
-                value: 1.into(),
-                address: PrecompileCalls::Bn128Pairing.address().to_word(),
-                gas: 12_000_000.into(),
-                ..Default::default()
-            },
-        ]
+        vec![PrecompileCallArgs {
+            name: "ecPairing (invalid): len(input) % 192 != 0",
+            setup_code: bytecode! {},
+            call_data_offset: 0x00.into(),
+            call_data_length: 0x10340.into(),
+            ret_offset: 0xC0.into(),
+            ret_size: 0x20.into(),
+            value: 1.into(),
+            address: PrecompileCalls::Bn128Pairing.address().to_word(),
+            gas: 12_000_000.into(),
+            ..Default::default()
+        }]
     });
 
     #[test]
@@ -462,7 +462,7 @@ impl<F: Field> ExecutionGadget<F> for EcrecoverGadget<F> {
             self.padding.assign(
                 region,
                 offset,
-                PrecompileCalls::Ecrecover,
+                PrecompileCalls::Ecrecover.input_len(),
                 region
                     .challenges()
                     .keccak_input()
@@ -1,7 +1,7 @@
 use bus_mapping::precompile::{
-    PrecompileAuxData, PrecompileCalls, MODEXP_INPUT_LIMIT, MODEXP_SIZE_LIMIT,
+    ModExpAuxData, PrecompileAuxData, MODEXP_INPUT_LIMIT, MODEXP_SIZE_LIMIT,
 };
-use eth_types::{evm_types::GasCost, Field, ToBigEndian, ToScalar, U256};
+use eth_types::{evm_types::GasCost, Field, ToScalar, U256};
 use gadgets::util::{self, not, select, Expr};
 use halo2_proofs::{
     circuit::Value,
@@ -11,14 +11,13 @@ use halo2_proofs::{
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::{N_BITS_U8, N_BYTES_MEMORY_ADDRESS, N_BYTES_U64, N_BYTES_WORD},
+        param::{N_BITS_U8, N_BYTES_MEMORY_ADDRESS, N_BYTES_U64},
         step::ExecutionState,
         util::{
             common_gadget::RestoreContextGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::{
-                BinaryNumberGadget, BitLengthGadget, ByteOrWord, ByteSizeGadget,
-                ConstantDivisionGadget, IsZeroGadget, LtGadget, MinMaxGadget,
+                BinaryNumberGadget, ConstantDivisionGadget, IsZeroGadget, LtGadget, MinMaxGadget,
             },
             padding_gadget::PaddingGadget,
             rlc, CachedRegion, Cell,
@@ -116,12 +115,10 @@ impl<F: Field, const BIT_LIMIT: usize> RandPowRepresent<F, BIT_LIMIT> {
     }
 }
 
-const SIZE_LIMIT: usize = MODEXP_SIZE_LIMIT;
-const SIZE_REPRESENT_BITS: usize = 6;
-const SIZE_REPRESENT_BYTES: usize = SIZE_LIMIT / 256 + 1;
-const INPUT_LIMIT: usize = 32 * 6;
-const INPUT_REPRESENT_BYTES: usize = MODEXP_INPUT_LIMIT / 256 + 1;
-const INPUT_REPRESENT_BITS: usize = 8;
+// the sizes are compared in 2 bytes, and the powers of randomness of them are built from 9 bits,
+// which bound the size limit of the gadget
+const SIZE_REPRESENT_BITS: usize = 9;
+const SIZE_REPRESENT_BYTES: usize = 2;
 
 type Word<F> = [Cell<F>; 32];
 
@@ -155,6 +152,11 @@ fn rlc_word_rev<F: Field>(cells: &[Cell<F>; 32], randomness: Expression<F>) -> E
     rlc_rev(cells, randomness)
 }
 
+// rlc of big-endian bytes, which doesn't depend on the leading zero bytes
+fn rlc_bytes_rev<F: Field>(bytes: &[u8], randomness: Value<F>) -> Value<F> {
+    randomness.map(|r| rlc::value(bytes.iter().rev(), r))
+}
+
 // calc for big-endian (notice util::expr_from_bytes calc for little-endian)
 fn expr_from_bytes<F: Field, E: Expr<F>>(bytes: &[E]) -> Expression<F> {
     bytes
@@ -163,14 +165,14 @@ fn expr_from_bytes<F: Field, E: Expr<F>>(bytes: &[E]) -> Expression<F> {
 }
 
 #[derive(Clone, Debug)]
-struct SizeRepresent<F> {
+struct SizeRepresent<F, const SIZE_LIMIT: usize> {
     len_bytes: Word<F>,
     expression: Expression<F>,
     is_rest_field_zero: IsZeroGadget<F>,
     is_not_exceed_limit: LtGadget<F, SIZE_REPRESENT_BYTES>,
 }
 
-impl<F: Field> SizeRepresent<F> {
+impl<F: Field, const SIZE_LIMIT: usize> SizeRepresent<F, SIZE_LIMIT> {
     pub fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         let len_bytes = cb.query_bytes();
         let expression = rlc_word_rev(&len_bytes, cb.challenges().keccak_input());
@@ -246,30 +248,29 @@ impl<F: Field> SizeRepresent<F> {
 
 type RandPow<F> = RandPowRepresent<F, SIZE_REPRESENT_BITS>;
 
-// parse as (valid, len, value: [base, exp, modulus])
-type InputParsedResult = (bool, [U256; 3], [[u8; SIZE_LIMIT]; 3]);
-type OutputParsedResult = (usize, [u8; SIZE_LIMIT]);
-
+/// The operands are not held as bytes, which can't fit into a step for large sizes, but as the
+/// RLCs of their big-endian bytes. The RLCs are looked up in the ModExpTable along with the
+/// sizes, which bound the operands so the RLC of the input splits into them in a unique way.
 #[derive(Clone, Debug)]
-struct ModExpInputs<F> {
-    base_len: SizeRepresent<F>,
-    modulus_len: SizeRepresent<F>,
-    exp_len: SizeRepresent<F>,
+struct ModExpInputs<F, const SIZE_LIMIT: usize> {
+    base_len: SizeRepresent<F, SIZE_LIMIT>,
+    modulus_len: SizeRepresent<F, SIZE_LIMIT>,
+    exp_len: SizeRepresent<F, SIZE_LIMIT>,
     base_pow: RandPow<F>,
-    base: Word<F>,
+    base_rlc: Cell<F>,
     modulus_pow: RandPow<F>,
-    modulus: Word<F>,
+    modulus_rlc: Cell<F>,
     exp_pow: RandPow<F>,
-    exp: Word<F>,
+    exp_rlc: Cell<F>,
+    exp_bit_len: Cell<F>,
     input_valid: Cell<F>,
     input_bytes_rlc: Expression<F>,
+    /// sizes of [base, exp, modulus] if the input is valid, or else zero
+    lens_expected: [Expression<F>; 3],
     input_len_expected: Expression<F>,
-    pub base_limbs: Limbs<F>,
-    pub exp_limbs: Limbs<F>,
-    pub modulus_limbs: Limbs<F>,
 }
 
-impl<F: Field> ModExpInputs<F> {
+impl<F: Field, const SIZE_LIMIT: usize> ModExpInputs<F, SIZE_LIMIT> {
     pub fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         let base_len = SizeRepresent::configure(cb);
         let modulus_len = SizeRepresent::configure(cb);
@@ -279,13 +280,10 @@ impl<F: Field> ModExpInputs<F> {
             RandPowRepresent::<_, 6>::base_pows_expr(cb.challenges().keccak_input())[5].clone(); //r**32
         let r_pow_64 = r_pow_32.clone().square();
 
-        let base = cb.query_bytes();
-        let modulus = cb.query_bytes();
-        let exp = cb.query_bytes();
-
-        let base_limbs = Limbs::configure(cb, &base);
-        let exp_limbs = Limbs::configure(cb, &exp);
-        let modulus_limbs = Limbs::configure(cb, &modulus);
+        let base_rlc = cb.query_cell_phase2();
+        let modulus_rlc = cb.query_cell_phase2();
+        let exp_rlc = cb.query_cell_phase2();
+        let exp_bit_len = cb.query_cell();
 
         let input_valid = cb.query_bool();
         cb.require_equal(
@@ -297,6 +295,11 @@ impl<F: Field> ModExpInputs<F> {
                 modulus_len.is_valid(),
             ]),
         );
+        cb.condition(not::expr(input_valid.expr()), |cb| {
+            for rlc in [&base_rlc, &modulus_rlc, &exp_rlc] {
+                cb.require_zero("no operand for invalid input", rlc.expr());
+            }
+        });
 
         let base_len_expected = util::select::expr(input_valid.expr(), base_len.value(), 0.expr());
 
@@ -310,13 +313,12 @@ impl<F: Field> ModExpInputs<F> {
             + exp_len_expected.clone()
             + modulus_len_expected.clone();
 
-        // we put correct size in each input word if input is valid
-        // else we just put as most as possible bytes (32) into it
-        // so we finally handle the memory in limited sized (32*3)
+        // the input is the 3 sizes followed by the operands if input is valid, or else just
+        // the sizes
         let modulus_pow = RandPow::configure(
             cb,
             cb.challenges().keccak_input(),
-            modulus_len_expected,
+            modulus_len_expected.clone(),
             None,
         );
 
@@ -324,7 +326,7 @@ impl<F: Field> ModExpInputs<F> {
         let exp_pow = RandPow::configure(
             cb,
             cb.challenges().keccak_input(),
-            exp_len_expected,
+            exp_len_expected.clone(),
             Some(modulus_pow.expr()),
         );
 
@@ -332,43 +334,41 @@ impl<F: Field> ModExpInputs<F> {
         let base_pow = RandPow::configure(
             cb,
             cb.challenges().keccak_input(),
-            base_len_expected,
+            base_len_expected.clone(),
             Some(exp_pow.expr()),
         );
 
-        let input_bytes_rlc = rlc_word_rev(&modulus, cb.challenges().keccak_input()) //rlc of base
-            + modulus_pow.expr() * rlc_word_rev(&exp, cb.challenges().keccak_input()) //rlc of exp plus r**base_len
-            + exp_pow.expr() * rlc_word_rev(&base, cb.challenges().keccak_input()) //rlc of exp plus r**(base_len + exp_len)
+        let input_bytes_rlc = modulus_rlc.expr() //rlc of modulus
+            + modulus_pow.expr() * exp_rlc.expr() //rlc of exp plus r**modulus_len
+            + exp_pow.expr() * base_rlc.expr() //rlc of base plus r**(modulus_len + exp_len)
             + base_pow.expr() * modulus_len.memory_rlc()
             + base_pow.expr() * r_pow_32 * exp_len.memory_rlc()
             + base_pow.expr() * r_pow_64 * base_len.memory_rlc();
 
-        // println!("phase 2 cell used {}",
-        //     padding_pow.phase2_cell_cost() + [&modulus_pow, &exp_pow,
-        // &base_pow].iter().map(|pw|pw.phase2_cell_cost()).sum::<usize>() );
-
         Self {
             base_len,
             modulus_len,
             exp_len,
             base_pow,
-            base,
+            base_rlc,
             modulus_pow,
-            modulus,
+            modulus_rlc,
             exp_pow,
-            exp,
+            exp_rlc,
+            exp_bit_len,
             input_valid,
             input_bytes_rlc,
+            lens_expected: [base_len_expected, exp_len_expected, modulus_len_expected],
             input_len_expected,
-            base_limbs,
-            exp_limbs,
-            modulus_limbs,
         }
     }
 
     pub fn modulus_len(&self) -> Expression<F> {
         self.modulus_len.value()
     }
+    pub fn lens(&self) -> [Expression<F>; 3] {
+        [&self.base_len, &self.exp_len, &self.modulus_len].map(SizeRepresent::value)
+    }
     pub fn is_valid(&self) -> Expression<F> {
         self.input_valid.expr()
     }
@@ -383,23 +383,25 @@ impl<F: Field> ModExpInputs<F> {
         &self,
         region: &mut CachedRegion<'_, '_, F>,
         offset: usize,
-        (input_valid, lens, values): InputParsedResult,
+        data: &ModExpAuxData<SIZE_LIMIT>,
     ) -> Result<(), Error> {
         self.input_valid.assign(
             region,
             offset,
-            Value::known(if input_valid { F::one() } else { F::zero() }),
+            Value::known(if data.valid { F::one() } else { F::zero() }),
         )?;
 
         for (len, len_represent) in
-            lens.iter()
+            data.input_lens
+                .iter()
                 .zip([&self.base_len, &self.exp_len, &self.modulus_len])
         {
             len_represent.assign(region, offset, len)?;
         }
 
         let mut linked_v = None;
-        for (len, pow) in lens
+        for (len, pow) in data
+            .input_lens
             .iter()
             .zip([&self.base_pow, &self.exp_pow, &self.modulus_pow])
             .rev()
@@ -407,24 +409,30 @@ impl<F: Field> ModExpInputs<F> {
             let assigned = pow.assign(
                 region,
                 offset,
-                if input_valid { len.as_usize() } else { 0 },
+                if data.valid { len.as_usize() } else { 0 },
                 linked_v,
             )?;
 
             linked_v = Some(assigned);
         }
 
-        for (val_r, input_limbs) in
-            values
+        // the operands are all zero if the input is invalid
+        for (value, cell) in
+            data.inputs
                 .iter()
-                .zip([&self.base_limbs, &self.exp_limbs, &self.modulus_limbs])
+                .zip([&self.base_rlc, &self.exp_rlc, &self.modulus_rlc])
         {
-            input_limbs.assign(region, offset, val_r)?;
-        }
-
-        for (&val, input_bytes) in values.iter().zip([&self.base, &self.exp, &self.modulus]) {
-            assign_word(region, offset, input_bytes, val)?;
+            cell.assign(
+                region,
+                offset,
+                rlc_bytes_rev(value, region.challenges().keccak_input()),
+            )?;
         }
+        self.exp_bit_len.assign(
+            region,
+            offset,
+            Value::known(F::from(data.exp_bit_len() as u64)),
+        )?;
 
         Ok(())
     }
@@ -432,10 +440,9 @@ impl<F: Field> ModExpInputs<F> {
 
 #[derive(Clone, Debug)]
 struct ModExpOutputs<F> {
-    result: Word<F>,
+    result_rlc: Cell<F>,
     is_result_zero: IsZeroGadget<F>,
     output_bytes_rlc: Expression<F>,
-    pub result_limbs: Limbs<F>,
 }
 
 impl<F: Field> ModExpOutputs<F> {
@@ -449,20 +456,17 @@ impl<F: Field> ModExpOutputs<F> {
             IsZeroGadget::construct(cb, output_len)
         });
 
-        let result = cb.query_bytes();
-        let result_limbs = Limbs::configure(cb, &result);
+        // the output is the result left padded to the size of the modulus, which is greater
+        // than the result, so they have the same RLC
+        let result_rlc = cb.query_cell_phase2();
 
-        let output_bytes_rlc = util::select::expr(
-            is_result_zero.expr(),
-            0.expr(),
-            rlc_word_rev(&result, cb.challenges().keccak_input()),
-        );
+        let output_bytes_rlc =
+            util::select::expr(is_result_zero.expr(), 0.expr(), result_rlc.expr());
 
         Self {
-            result,
+            result_rlc,
             is_result_zero,
             output_bytes_rlc,
-            result_limbs,
         }
     }
 
@@ -474,91 +478,31 @@ impl<F: Field> ModExpOutputs<F> {
         self.output_bytes_rlc.clone()
     }
 
-    pub fn assign(
+    pub fn assign<const SIZE_LIMIT: usize>(
         &self,
         region: &mut CachedRegion<'_, '_, F>,
         offset: usize,
-        (output_len, data): OutputParsedResult,
+        data: &ModExpAuxData<SIZE_LIMIT>,
     ) -> Result<(), Error> {
         self.is_result_zero
-            .assign(region, offset, F::from(output_len as u64))?;
-        self.result_limbs.assign(region, offset, &data)?;
-        assign_word(region, offset, &self.result, data)?;
-        Ok(())
-    }
-}
-
-#[derive(Clone, Debug)]
-pub(crate) struct Limbs<F> {
-    byte14_split_lo: Cell<F>,
-    byte14_split_hi: Cell<F>,
-    limbs: [Expression<F>; 3],
-}
-
-impl<F: Field> Limbs<F> {
-    pub fn configure(cb: &mut EVMConstraintBuilder<F>, word: &Word<F>) -> Self {
-        let byte14_split_lo = cb.query_byte();
-        let byte14_split_hi = cb.query_byte();
-
-        cb.require_equal(
-            "split 14th byte in word into half",
-            word[MODEXP_SIZE_LIMIT - 14].expr(),
-            byte14_split_lo.expr() + byte14_split_hi.expr(),
-        );
-
-        let inv_16 = Expression::Constant(F::from(16u64).invert().unwrap());
-
-        let limbs = [
-            expr_from_bytes(
-                &std::iter::once(&byte14_split_lo)
-                    .chain(&word[MODEXP_SIZE_LIMIT - 13..])
-                    .collect::<Vec<_>>(),
-            ),
-            expr_from_bytes(
-                &word[MODEXP_SIZE_LIMIT - 27..MODEXP_SIZE_LIMIT - 14]
-                    .iter()
-                    .chain(std::iter::once(&byte14_split_hi))
-                    .collect::<Vec<_>>(),
-            ) * inv_16,
-            expr_from_bytes(&word[..MODEXP_SIZE_LIMIT - 27]),
-        ];
-
-        Self {
-            byte14_split_hi,
-            byte14_split_lo,
-            limbs,
-        }
-    }
-
-    pub fn limbs(&self) -> [Expression<F>; 3] {
-        self.limbs.clone()
-    }
-
-    pub fn assign(
-        &self,
-        region: &mut CachedRegion<'_, '_, F>,
-        offset: usize,
-        big_int: &[u8; MODEXP_SIZE_LIMIT],
-    ) -> Result<(), Error> {
-        let byte14_lo = big_int[MODEXP_SIZE_LIMIT - 14] & 0xf;
-        let byte14_hi = big_int[MODEXP_SIZE_LIMIT - 14] & 0xf0;
-
-        self.byte14_split_lo
-            .assign(region, offset, Value::known(F::from(byte14_lo as u64)))?;
-        self.byte14_split_hi
-            .assign(region, offset, Value::known(F::from(byte14_hi as u64)))?;
+            .assign(region, offset, F::from(data.output_len as u64))?;
+        self.result_rlc.assign(
+            region,
+            offset,
+            rlc_bytes_rev(&data.result(), region.challenges().keccak_input()),
+        )?;
         Ok(())
     }
 }
 
+/// Gas cost of EIP-2565, with the sizes of valid inputs and the bit length of the exponent
 #[derive(Clone, Debug)]
 pub(crate) struct ModExpGasCost<F> {
-    max_length: MinMaxGadget<F, 1>,
+    max_length: MinMaxGadget<F, SIZE_REPRESENT_BYTES>,
     words: ConstantDivisionGadget<F, 1>,
-    exp_is_zero: IsZeroGadget<F>,
-    exp_byte_size: ByteSizeGadget<F>,
-    exp_msb_bit_length: BitLengthGadget<F>,
-    exp_msb: BinaryNumberGadget<F, N_BITS_U8>,
+    exp_tail_len: MinMaxGadget<F, SIZE_REPRESENT_BYTES>,
+    iteration_count: MinMaxGadget<F, SIZE_REPRESENT_BYTES>,
+    min_iteration_count: MinMaxGadget<F, SIZE_REPRESENT_BYTES>,
     calc_gas: ConstantDivisionGadget<F, N_BYTES_U64>,
     dynamic_gas: MinMaxGadget<F, N_BYTES_U64>,
 }
@@ -566,56 +510,29 @@ pub(crate) struct ModExpGasCost<F> {
 impl<F: Field> ModExpGasCost<F> {
     fn construct(
         cb: &mut EVMConstraintBuilder<F>,
-        b_size: &SizeRepresent<F>,
-        exp: &[Cell<F>; MODEXP_SIZE_LIMIT],
-        m_size: &SizeRepresent<F>,
+        [b_size, e_size, m_size]: [Expression<F>; 3],
+        exp_bit_length: Expression<F>,
     ) -> Self {
-        let max_length = MinMaxGadget::construct(cb, b_size.value(), m_size.value());
+        let max_length = MinMaxGadget::construct(cb, b_size, m_size);
         let words = ConstantDivisionGadget::construct(cb, max_length.max() + 7.expr(), 8);
         let multiplication_complexity = words.quotient() * words.quotient();
-        let exp_is_zero = IsZeroGadget::construct(
+
+        // The iteration count is `8 * (Esize - 32) + max(bit length of the first 32 bytes of
+        // the exponent, 1) - 1` if Esize > 32, or else `max(bit length of exponent, 1) - 1`, and
+        // at least 1. That is `max(bit length of exponent - 1, 8 * (max(Esize, 32) - 32), 1)`, as
+        // the first 32 bytes are zero if the bit length is not greater than `8 * (Esize - 32)`.
+        let exp_tail_len = MinMaxGadget::construct(cb, e_size, 32.expr());
+        let iteration_count = MinMaxGadget::construct(
             cb,
-            rlc::expr(&exp.clone().map(|c| c.expr()), cb.challenges().evm_word()),
+            exp_bit_length,
+            N_BITS_U8.expr() * (exp_tail_len.max() - 32.expr()) + 1.expr(),
         );
-
-        let (exp_byte_size, exp_msb, exp_msb_bit_length) =
-            cb.condition(not::expr(exp_is_zero.expr()), |cb| {
-                let exp_byte_size = ByteSizeGadget::construct(
-                    cb,
-                    exp.iter()
-                        .rev()
-                        .map(Expr::expr)
-                        .collect::<Vec<Expression<F>>>()
-                        .try_into()
-                        .unwrap(),
-                );
-                let exp_msb =
-                    BinaryNumberGadget::construct(cb, exp_byte_size.most_significant_value.expr());
-                let exp_msb_bit_length = BitLengthGadget::construct(
-                    cb,
-                    exp_msb
-                        .bits
-                        .iter()
-                        .rev()
-                        .map(Expr::expr)
-                        .collect::<Vec<Expression<F>>>()
-                        .try_into()
-                        .unwrap(),
-                );
-                (exp_byte_size, exp_msb, exp_msb_bit_length)
-            });
-        let exp_bit_length =
-            (exp_byte_size.size() - 1.expr()) * N_BITS_U8.expr() + exp_msb_bit_length.size();
-
-        // We already restrict Esize <= 32. So we can completely ignore the branch concerning
-        // Esize > 32. We only care about whether or not exponent is zero.
-        let iteration_count = select::expr(
-            exp_is_zero.expr(),
-            0.expr(),
-            exp_bit_length.expr() - 1.expr(),
+        let min_iteration_count = MinMaxGadget::construct(cb, iteration_count.max(), 2.expr());
+        let calc_gas = ConstantDivisionGadget::construct(
+            cb,
+            multiplication_complexity * (min_iteration_count.max() - 1.expr()),
+            3,
         );
-        let calc_gas =
-            ConstantDivisionGadget::construct(cb, multiplication_complexity * iteration_count, 3);
         let dynamic_gas = MinMaxGadget::construct(
             cb,
             GasCost::PRECOMPILE_MODEXP_MIN.expr(),
@@ -625,10 +542,9 @@ impl<F: Field> ModExpGasCost<F> {
         Self {
             max_length,
             words,
-            exp_is_zero,
-            exp_byte_size,
-            exp_msb,
-            exp_msb_bit_length,
+            exp_tail_len,
+            iteration_count,
+            min_iteration_count,
             calc_gas,
             dynamic_gas,
         }
@@ -638,72 +554,47 @@ impl<F: Field> ModExpGasCost<F> {
         &self,
         region: &mut CachedRegion<'_, '_, F>,
         offset: usize,
-        b_size: &U256,
-        m_size: &U256,
-        exponent: &[u8; MODEXP_SIZE_LIMIT],
+        [b_size, e_size, m_size]: [u64; 3],
+        exp_bit_length: u64,
     ) -> Result<u64, Error> {
-        let mut base_len = [0u8; 32];
-        base_len[(32 - SIZE_REPRESENT_BYTES)..]
-            .copy_from_slice(&b_size.to_be_bytes()[(32 - SIZE_REPRESENT_BYTES)..]);
-        let mut mod_len = [0u8; 32];
-        mod_len[(32 - SIZE_REPRESENT_BYTES)..]
-            .copy_from_slice(&m_size.to_be_bytes()[(32 - SIZE_REPRESENT_BYTES)..]);
-        let b_size = U256::from_big_endian(&base_len);
-        let m_size = U256::from_big_endian(&mod_len);
-
-        self.max_length.assign(
-            region,
-            offset,
-            b_size.to_scalar().expect("Bsize is within scalar field"),
-            m_size.to_scalar().expect("Msize is within scalar field"),
-        )?;
+        self.max_length
+            .assign(region, offset, F::from(b_size), F::from(m_size))?;
+        let words = (b_size.max(m_size) + 7) / 8;
         self.words
-            .assign(region, offset, b_size.max(m_size).as_u128() + 7u128)?;
-        let exp_word = U256::from_big_endian(exponent);
-        self.exp_is_zero.assign_value(
+            .assign(region, offset, (b_size.max(m_size) + 7).into())?;
+        let multiplication_complexity = words * words;
+
+        self.exp_tail_len
+            .assign(region, offset, F::from(e_size), F::from(32))?;
+        let exp_tail_bits = N_BITS_U8 as u64 * (e_size.max(32) - 32);
+        self.iteration_count.assign(
             region,
             offset,
-            region
-                .challenges()
-                .evm_word()
-                .map(|r| rlc::value(exponent, r)),
+            F::from(exp_bit_length),
+            F::from(exp_tail_bits + 1),
         )?;
-        self.exp_byte_size
-            .assign(region, offset, ByteOrWord::Word(exp_word))?;
-        let exp_byte_size = (exp_word.bits() + 7) / 8;
-        let exp_msb = if exp_byte_size > 0 {
-            exponent[N_BYTES_WORD - exp_byte_size]
-        } else {
-            0
-        };
-        self.exp_msb.assign(region, offset, exp_msb)?;
-        self.exp_msb_bit_length
-            .assign(region, offset, ByteOrWord::Byte(exp_msb))?;
-        let exp_bit_length = exp_word.bits();
-        let max_length = b_size.max(m_size);
-        let words = (max_length + 7) / 8;
-        let multiplication_complexity = words * words;
-        let iteration_count = if exp_word.is_zero() {
-            0
-        } else {
-            exp_bit_length - 1
-        };
+        let iteration_count = exp_bit_length.max(exp_tail_bits + 1);
+        self.min_iteration_count
+            .assign(region, offset, F::from(iteration_count), F::from(2))?;
+        let iteration_count = iteration_count.max(2) - 1;
+
         let numerator = multiplication_complexity * iteration_count;
-        self.calc_gas.assign(region, offset, numerator.as_u128())?;
+        self.calc_gas.assign(region, offset, numerator.into())?;
         self.dynamic_gas.assign(
             region,
             offset,
             F::from(GasCost::PRECOMPILE_MODEXP_MIN.0),
-            F::from((numerator / 3).as_u64()),
+            F::from(numerator / 3),
         )?;
-        let gas_cost = std::cmp::max(GasCost::PRECOMPILE_MODEXP_MIN.0, (numerator / 3).as_u64());
-
+        let gas_cost = std::cmp::max(GasCost::PRECOMPILE_MODEXP_MIN.0, numerator / 3);
         Ok(gas_cost)
     }
 }
 
+/// Gadget of the modexp precompile, with operands of up to `SIZE_LIMIT` bytes. A call with a
+/// larger operand fails.
 #[derive(Clone, Debug)]
-pub struct ModExpGadget<F> {
+pub struct ModExpGadget<F, const SIZE_LIMIT: usize = MODEXP_SIZE_LIMIT> {
     input_bytes_rlc: Cell<F>,
     output_bytes_rlc: Cell<F>,
     return_bytes_rlc: Cell<F>,
@@ -720,29 +611,34 @@ pub struct ModExpGadget<F> {
     return_data_length: Cell<F>,
     restore_context_gadget: RestoreContextGadget<F>,
 
-    input: ModExpInputs<F>,
-    padding_zero: RandPowRepresent<F, INPUT_REPRESENT_BITS>,
+    input: ModExpInputs<F, SIZE_LIMIT>,
     output: ModExpOutputs<F>,
 
-    input_bytes_acc: Cell<F>,
     is_gas_insufficient: LtGadget<F, N_BYTES_U64>,
     gas_cost_gadget: ModExpGasCost<F>,
-    garbage_bytes_holder: [Cell<F>; INPUT_LIMIT - 96],
 }
 
-impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
+impl<F: Field, const SIZE_LIMIT: usize> ExecutionGadget<F> for ModExpGadget<F, SIZE_LIMIT> {
     const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileBigModExp;
 
     const NAME: &'static str = "MODEXP";
 
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        assert!(
+            SIZE_LIMIT < 1 << SIZE_REPRESENT_BITS,
+            "modexp size limit {SIZE_LIMIT} can't be represented"
+        );
+        // the input is right padded up to the input limit of the default size limit
+        assert!(
+            ModExpAuxData::<SIZE_LIMIT>::INPUT_LIMIT <= MODEXP_INPUT_LIMIT,
+            "modexp size limit {SIZE_LIMIT} over the padding limit"
+        );
+
         let (input_bytes_rlc, output_bytes_rlc, return_bytes_rlc) = (
             cb.query_cell_phase2(),
             cb.query_cell_phase2(),
             cb.query_cell_phase2(),
         );
-        // we 'copy' the acc_bytes cell inside call_op step, so it must be the first query cells
-        let input_bytes_acc = cb.query_cell_phase2();
 
         let [is_success, callee_address, is_root, call_data_offset, call_data_length, return_data_offset, return_data_length] =
             [
@@ -763,15 +659,9 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
         );
 
         let input = ModExpInputs::configure(cb);
-        let padding_zero = RandPowRepresent::configure(
-            cb,
-            cb.challenges().keccak_input(),
-            MODEXP_INPUT_LIMIT.expr() - input.len_expected(),
-            None,
-        );
 
         let gas_cost_gadget =
-            ModExpGasCost::construct(cb, &input.base_len, &input.exp, &input.modulus_len);
+            ModExpGasCost::construct(cb, input.lens_expected.clone(), input.exp_bit_len.expr());
         let is_gas_insufficient = LtGadget::construct(
             cb,
             cb.curr.state.gas_left.expr(),
@@ -787,32 +677,21 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
             call_success.clone(),
         );
 
-        let output = ModExpOutputs::configure(
-            cb,
-            //FIXME: there may be still some edge cases lead to nil output (even modulus_len is
-            // not 0)
-            call_success,
-            input.modulus_len(),
-        );
+        let output = ModExpOutputs::configure(cb, call_success, input.modulus_len());
 
-        cb.condition(util::not::expr(output.is_nil()), |cb| {
+        // the lookup is required for a valid input even if it runs out of gas, as the gas cost
+        // depends on the bit length of the exponent
+        cb.condition(input.is_valid(), |cb| {
             cb.modexp_table_lookup(
-                input.base_limbs.limbs(),
-                input.exp_limbs.limbs(),
-                input.modulus_limbs.limbs(),
-                output.result_limbs.limbs(),
+                input.base_rlc.expr(),
+                input.exp_rlc.expr(),
+                input.modulus_rlc.expr(),
+                output.result_rlc.expr(),
+                input.exp_bit_len.expr(),
+                input.lens(),
             );
         });
 
-        let garbage_bytes_holder = cb.query_bytes();
-
-        cb.require_equal(
-            "input acc bytes with padding must equal",
-            input_bytes_acc.expr(),
-            padding_zero.expr() * input.bytes_rlc()
-                + rlc_rev(&garbage_bytes_holder, cb.challenges().keccak_input()),
-        );
-
         cb.require_equal(
             "output acc bytes must equal",
             output_bytes_rlc.expr(),
@@ -825,7 +704,9 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
             cb.curr.state.gas_left.expr(),
         );
 
-        let required_input_len = MODEXP_INPUT_LIMIT.expr();
+        // the input bytes are the call data up to the expected input length, and right padded
+        // with zeroes to it if the call data is shorter
+        let required_input_len = input.len_expected();
         let pad_right = LtGadget::construct(cb, call_data_length.expr(), required_input_len.expr());
         let padding = cb.condition(pad_right.expr(), |cb| {
             PaddingGadget::construct(
@@ -843,9 +724,9 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
             );
         });
         cb.require_equal(
-            "copy padded input bytes",
+            "padded input bytes are the sizes and the operands",
             padding.padded_rlc(),
-            input_bytes_acc.expr(),
+            input.bytes_rlc(),
         );
 
         let restore_context_gadget = super::gen_restore_context(
@@ -873,12 +754,9 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
             return_data_length,
             restore_context_gadget,
             input,
-            padding_zero,
             output,
-            input_bytes_acc,
             is_gas_insufficient,
             gas_cost_gadget,
-            garbage_bytes_holder,
         }
     }
 
@@ -892,78 +770,44 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
         step: &ExecStep,
     ) -> Result<(), Error> {
         if let Some(PrecompileAuxData::Modexp(data)) = &step.aux_data {
-            self.input
-                .assign(region, offset, (data.valid, data.input_lens, data.inputs))?;
+            // the input is parsed again with the size limit of the gadget
+            let data = &ModExpAuxData::<SIZE_LIMIT>::new(
+                &data.input_bytes,
+                &data.output_bytes,
+                &data.return_bytes,
+            );
+            self.input.assign(region, offset, data)?;
+            self.output.assign(region, offset, data)?;
 
-            let input_expected_len = 96
-                + if data.valid {
-                    data.input_lens.iter().map(U256::as_usize).sum::<usize>()
-                } else {
-                    0
-                };
-
-            let garbage_bytes = if call.call_data_length as usize > input_expected_len {
-                let mut bts = vec![0; input_expected_len - 96]; //front prefix zero
-                bts.append(&mut Vec::from(&data.input_bytes[input_expected_len..]));
-                bts.resize(96, 0); //padding zero
-                bts
+            let input_lens_expected = if data.valid {
+                data.input_lens.map(|len| len.as_u64())
             } else {
-                Vec::from([0u8; 96])
+                [0; 3]
             };
+            let input_len_expected = 96 + input_lens_expected.iter().sum::<u64>() as usize;
 
-            self.padding_zero
-                .assign(region, offset, INPUT_LIMIT - input_expected_len, None)?;
-
-            for (cell, bt) in self.garbage_bytes_holder.iter().zip(garbage_bytes) {
-                cell.assign(region, offset, Value::known(F::from(bt as u64)))?;
-            }
-
-            self.output
-                .assign(region, offset, (data.output_len, data.output))?;
-
-            let input_rlc = region
-                .challenges()
-                .keccak_input()
-                .map(|randomness| rlc::value(data.input_bytes.iter().rev(), randomness));
-
+            // only the expected input bytes are copied from the call data
+            let input_rlc = rlc_bytes_rev(
+                &data.input_bytes[..data.input_bytes.len().min(input_len_expected)],
+                region.challenges().keccak_input(),
+            );
             self.input_bytes_rlc.assign(region, offset, input_rlc)?;
+            self.output_bytes_rlc.assign(
+                region,
+                offset,
+                rlc_bytes_rev(&data.output_bytes, region.challenges().keccak_input()),
+            )?;
             self.return_bytes_rlc.assign(
                 region,
                 offset,
-                region
-                    .challenges()
-                    .keccak_input()
-                    .map(|r| rlc::value(data.return_bytes.iter().rev(), r)),
+                rlc_bytes_rev(&data.return_bytes, region.challenges().keccak_input()),
             )?;
 
-            // if the input to modexp has more than 192 bytes, then we only keep the first 192 bytes
-            // and discard the remaining bytes
-            let input_len_limit = INPUT_LIMIT as u64;
-            let n_padded_zeros = if call.call_data_length > input_len_limit {
-                0
-            } else {
-                input_len_limit - call.call_data_length
-            };
-            let n_padded_zeroes_pow = region
-                .challenges()
-                .keccak_input()
-                .map(|r| r.pow([n_padded_zeros, 0, 0, 0]));
-
-            let output_rlc = region
-                .challenges()
-                .keccak_input()
-                .map(|randomness| rlc::value(data.output_bytes.iter().rev(), randomness));
-
-            self.input_bytes_acc
-                .assign(region, offset, n_padded_zeroes_pow * input_rlc)?;
-            self.output_bytes_rlc.assign(region, offset, output_rlc)?;
-
             let required_gas_cost = self.gas_cost_gadget.assign(
                 region,
                 offset,
-                &data.input_lens[0],
-                &data.input_lens[2],
-                &data.inputs[1],
+                input_lens_expected,
+                data.exp_bit_len() as u64,
             )?;
             self.is_gas_insufficient.assign(
                 region,
@@ -971,16 +815,17 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
                 F::from(step.gas_left),
                 F::from(required_gas_cost),
             )?;
-            self.pad_right
-                .assign(region, offset, call.call_data_length.into(), 192.into())?;
+            self.pad_right.assign(
+                region,
+                offset,
+                call.call_data_length.into(),
+                F::from(input_len_expected as u64),
+            )?;
             self.padding.assign(
                 region,
                 offset,
-                PrecompileCalls::Modexp,
-                region
-                    .challenges()
-                    .keccak_input()
-                    .map(|r| rlc::value(data.input_bytes.iter().rev(), r)),
+                Some(input_len_expected),
+                input_rlc,
                 call.call_data_length,
                 region.challenges().keccak_input(),
             )?;
@@ -1030,76 +875,18 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use super::*;
     use bus_mapping::{
+        circuit_input_builder::PrecompileEvent,
         evm::{OpcodeId, PrecompileCallArgs},
-        precompile::PrecompileCalls,
+        precompile::{ModExpAuxData, PrecompileAuxData, PrecompileCalls, MODEXP_SIZE_LIMIT},
     };
-    use eth_types::{bytecode, word, ToWord};
-    use ethers_core::k256::elliptic_curve::PrimeField;
+    use eth_types::{bytecode, word, Bytecode, ToBigEndian, ToWord, Word};
+    use halo2_proofs::halo2curves::bn256::Fr;
     use itertools::Itertools;
     use mock::TestContext;
     use std::sync::LazyLock;
 
-    use crate::test_util::CircuitTestBuilder;
-
-    #[test]
-    fn test_limbs() {
-        use crate::table::ModExpTable;
-        use halo2_proofs::halo2curves::bn256::Fr;
-        use misc_precompiled_circuit::circuits::modexp::Number;
-        use num_bigint::BigUint;
-
-        // simply take an hash for test
-        let bi = BigUint::parse_bytes(
-            b"fcb51a0695d8f838b1ee009b3fbf66bda078cd64590202a864a8f3e8c4315c47",
-            16,
-        )
-        .unwrap();
-        let n = Number::<Fr>::from_bn(&bi);
-        let w = word!("0xfcb51a0695d8f838b1ee009b3fbf66bda078cd64590202a864a8f3e8c4315c47");
-        let mut bytes = [0u8; 32];
-        w.to_big_endian(&mut bytes);
-        assert_eq!(BigUint::from_bytes_be(&bytes), bi);
-
-        let byte14_lo = bytes[MODEXP_SIZE_LIMIT - 14] & 0xf;
-        let byte14_hi = bytes[MODEXP_SIZE_LIMIT - 14] & 0xf0;
-
-        let limb0: Fr = U256::from_big_endian(
-            &(std::iter::once(byte14_lo))
-                .chain(bytes[MODEXP_SIZE_LIMIT - 13..].iter().copied())
-                .collect::<Vec<_>>(),
-        )
-        .to_scalar()
-        .unwrap();
-
-        let limb1: Fr = U256::from_big_endian(
-            &bytes[MODEXP_SIZE_LIMIT - 27..MODEXP_SIZE_LIMIT - 14]
-                .iter()
-                .copied()
-                .chain(std::iter::once(byte14_hi))
-                .collect::<Vec<_>>(),
-        )
-        .to_scalar()
-        .unwrap();
-
-        let limb2: Fr = U256::from_big_endian(&bytes[..MODEXP_SIZE_LIMIT - 27])
-            .to_scalar()
-            .unwrap();
-
-        assert_eq!(limb0, n.limbs[0].value);
-        assert_eq!(limb1, n.limbs[1].value * Fr::from(16_u64));
-        assert_eq!(limb2, n.limbs[2].value);
-
-        let nt: Fr = ModExpTable::native_u256(&w);
-
-        let table_split = ModExpTable::split_u256_108bit_limbs(&w);
-        assert_eq!(Fr::from_u128(table_split[0]), n.limbs[0].value);
-        assert_eq!(Fr::from_u128(table_split[1]), n.limbs[1].value);
-        assert_eq!(Fr::from_u128(table_split[2]), n.limbs[2].value);
-        assert_eq!(nt, n.limbs[3].value);
-        //Limb::new(None, value)
-    }
+    use crate::{test_util::CircuitTestBuilder, witness::Block};
 
     static TEST_VECTOR: LazyLock<Vec<PrecompileCallArgs>> = LazyLock::new(|| {
         vec![
@@ -1397,6 +1184,72 @@ mod test {
         ]
     });
 
+    // store the input in memory from offset 0
+    fn store_input(input: &[u8]) -> Bytecode {
+        let mut code = Bytecode::default();
+        for (idx, chunk) in input.chunks(32).enumerate() {
+            let mut word = [0u8; 32];
+            word[..chunk.len()].copy_from_slice(chunk);
+            code.push(32, Word::from_big_endian(&word))
+                .push(32, idx * 32)
+                .write_op(OpcodeId::MSTORE);
+        }
+        code
+    }
+
+    // the sizes followed by the operands, filled with arbitrary bytes with the leading one set
+    fn large_input(base_len: usize, exp_len: usize, modulus_len: usize) -> Vec<u8> {
+        let mut input = [base_len, exp_len, modulus_len]
+            .iter()
+            .flat_map(|&len| Word::from(len).to_be_bytes())
+            .collect::<Vec<_>>();
+        for (seed, len) in [(0x1fu8, base_len), (0x2bu8, exp_len), (0x3du8, modulus_len)] {
+            input.extend((0..len).map(|i| {
+                if i == 0 {
+                    0x80
+                } else {
+                    (i as u8).wrapping_mul(seed) ^ seed
+                }
+            }));
+        }
+        input
+    }
+
+    static TEST_LARGE_VECTOR: LazyLock<Vec<PrecompileCallArgs>> = LazyLock::new(|| {
+        vec![
+            PrecompileCallArgs {
+                name: "modexp 2048-bit operands",
+                setup_code: store_input(&large_input(0x100, 0x3, 0x100)),
+                call_data_offset: 0x0.into(),
+                call_data_length: 0x263.into(),
+                ret_offset: 0x280.into(),
+                ret_size: 0x100.into(),
+                address: PrecompileCalls::Modexp.address().to_word(),
+                ..Default::default()
+            },
+            PrecompileCallArgs {
+                name: "modexp exponent longer than 32 bytes, call data longer than input",
+                setup_code: store_input(&large_input(0x28, 0x21, 0x40)),
+                call_data_offset: 0x0.into(),
+                call_data_length: 0x120.into(),
+                ret_offset: 0x120.into(),
+                ret_size: 0x40.into(),
+                address: PrecompileCalls::Modexp.address().to_word(),
+                ..Default::default()
+            },
+            PrecompileCallArgs {
+                name: "modexp 2048-bit operands with padding",
+                setup_code: store_input(&large_input(0x100, 0x3, 0x100)[..0x180]),
+                call_data_offset: 0x0.into(),
+                call_data_length: 0x180.into(),
+                ret_offset: 0x180.into(),
+                ret_size: 0x100.into(),
+                address: PrecompileCalls::Modexp.address().to_word(),
+                ..Default::default()
+            },
+        ]
+    });
+
     static TEST_U256_VECTOR: LazyLock<Vec<PrecompileCallArgs>> = LazyLock::new(|| {
         vec![
             PrecompileCallArgs {
@@ -1482,7 +1335,7 @@ mod test {
                     PUSH1(0x20)
                     MSTORE
                     // Msize
-                    PUSH1(0x21)
+                    PUSH2(0x0101)
                     PUSH1(0x40)
                     MSTORE
                     // B, E and M
@@ -1502,15 +1355,15 @@ mod test {
                 name: "modexp Base&Esize&Msize length too large invalid",
                 setup_code: bytecode! {
                     // Base size
-                    PUSH1(0x21)
+                    PUSH2(0x0101)
                     PUSH1(0x00)
                     MSTORE
                     // Esize
-                    PUSH1(0x21)
+                    PUSH2(0x0101)
                     PUSH1(0x20)
                     MSTORE
                     // Msize
-                    PUSH1(0x21)
+                    PUSH2(0x0101)
                     PUSH1(0x40)
                     MSTORE
                     // B, E and M
@@ -1607,6 +1460,18 @@ mod test {
         }
     }
 
+    #[test]
+    fn precompile_modexp_test_large() {
+        for test_vector in TEST_LARGE_VECTOR.iter() {
+            let bytecode = test_vector.with_call_op(OpcodeId::STATICCALL);
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+            )
+            .run();
+        }
+    }
+
     // notice, "invalid" test would not actuall work until bus-mapping put calling fail case being
     // handle in normal CallOp, i.e. return None in
     // bus_mapping::circuit_input_builder::input_state_ref::CircuitInputStateRef::get_step_err
@@ -1624,4 +1489,110 @@ mod test {
             .run();
         }
     }
+
+    // a call with a modulus of `modulus_len` bytes, all provided in the call data
+    fn size_limit_call(modulus_len: usize) -> Bytecode {
+        PrecompileCallArgs {
+            name: "modexp modulus at the size limit",
+            setup_code: store_input(&large_input(1, 1, modulus_len)),
+            call_data_offset: 0x0.into(),
+            call_data_length: (96 + 2 + modulus_len).into(),
+            ret_offset: 0x200.into(),
+            ret_size: modulus_len.into(),
+            address: PrecompileCalls::Modexp.address().to_word(),
+            ..Default::default()
+        }
+        .with_call_op(OpcodeId::STATICCALL)
+    }
+
+    fn modexp_aux_data(block: &mut Block<Fr>) -> &mut ModExpAuxData {
+        block
+            .txs
+            .iter_mut()
+            .flat_map(|tx| tx.steps.iter_mut())
+            .find_map(|step| match &mut step.aux_data {
+                Some(PrecompileAuxData::Modexp(data)) => Some(data),
+                _ => None,
+            })
+            .expect("a modexp step")
+    }
+
+    // the EVM circuit rejects the block once it's tampered by `modifier`
+    fn test_tampered(bytecode: Bytecode, modifier: Box<dyn Fn(&mut Block<Fr>)>) {
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+        )
+        .block_modifier(modifier)
+        .evm_checks(Some(Box::new(|prover, gate_rows, lookup_rows| {
+            assert!(prover
+                .verify_at_rows_par(gate_rows.iter().cloned(), lookup_rows.iter().cloned())
+                .is_err())
+        })))
+        .state_checks(None)
+        .copy_checks(None)
+        .run();
+    }
+
+    #[test]
+    fn precompile_modexp_test_wrong_result() {
+        let bytecode = TEST_U256_VECTOR[0].with_call_op(OpcodeId::STATICCALL);
+
+        // the result in the modexp table
+        test_tampered(
+            bytecode.clone(),
+            Box::new(|block| {
+                for event in block.precompile_events.events.iter_mut() {
+                    if let PrecompileEvent::ModExp(event) = event {
+                        event.result += 1u32;
+                    }
+                }
+            }),
+        );
+        // the output of the call
+        test_tampered(
+            bytecode,
+            Box::new(|block| {
+                let data = modexp_aux_data(block);
+                *data.output_bytes.last_mut().unwrap() ^= 1;
+            }),
+        );
+    }
+
+    #[test]
+    fn precompile_modexp_test_size_limit() {
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(size_limit_call(MODEXP_SIZE_LIMIT))
+                .unwrap(),
+        )
+        .run();
+    }
+
+    // the call fails with a modulus over the size limit, see the note on the invalid tests above
+    #[cfg(feature = "scroll")]
+    #[test]
+    fn precompile_modexp_test_over_size_limit() {
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(size_limit_call(MODEXP_SIZE_LIMIT + 1))
+                .unwrap(),
+        )
+        .run();
+    }
+
+    // the modulus over the size limit can't be truncated to the limit, to pass the call as a
+    // successful one
+    #[cfg(feature = "scroll")]
+    #[test]
+    fn precompile_modexp_test_truncated_operand() {
+        test_tampered(
+            size_limit_call(MODEXP_SIZE_LIMIT + 1),
+            Box::new(|block| {
+                let data = modexp_aux_data(block);
+                let mut input = large_input(1, 1, MODEXP_SIZE_LIMIT);
+                input[96 + 2..].copy_from_slice(&data.input_bytes[96 + 3..]);
+                let output = ModExpAuxData::<MODEXP_SIZE_LIMIT>::new(&input, &[], &[]).result();
+                *data = ModExpAuxData::new(&input, &output, &output);
+                assert!(data.valid);
+            }),
+        );
+    }
 }
@@ -0,0 +1,653 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, STACK_CAPACITY},
+        step::ExecutionState,
+        util::{
+            common_gadget::{RestoreContextGadget, TransferToGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
+                Transition::{Delta, Same, To},
+            },
+            from_bytes,
+            math_gadget::{IsEqualGadget, IsZeroGadget, RangeCheckGadget},
+            not, or, CachedRegion, Cell, StepRws, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag},
+    util::Expr,
+};
+use either::Either;
+use eth_types::{
+    evm_types::{GasCost, OpcodeId},
+    Field, ToLittleEndian, ToScalar,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for [`OpcodeId::SELFDESTRUCT`].
+///
+/// The balance of the current account is moved to the beneficiary, and the
+/// call halts successfully. Without the `shanghai` feature the current account
+/// is always destructed. With the `shanghai` feature, EIP-6780 applies and the
+/// account is only destructed when it has been created in the same
+/// transaction, which is read from the `TxCreatedAccount` rw written by the
+/// creation `BeginTx` or `CREATE*`.
+///
+/// The account keeps its code, nonce and storage until the end of the
+/// transaction: it's only marked with a `TxDestructedAccount` rw here, and
+/// destructed by the `DestructAccount` steps after `EndTx`.
+#[derive(Clone, Debug)]
+pub(crate) struct SelfDestructGadget<F> {
+    opcode: Cell<F>,
+    beneficiary_word: Word<F>,
+    tx_id: Cell<F>,
+    is_static: Cell<F>,
+    callee_address: Cell<F>,
+    reversion_info: ReversionInfo<F>,
+    is_warm: Cell<F>,
+    beneficiary_code_hash: Cell<F>,
+    beneficiary_not_exists: IsZeroGadget<F>,
+    balance: Word<F>,
+    value_is_zero: IsZeroGadget<F>,
+    is_self: IsEqualGadget<F>,
+    /// Whether the account was created in the current transaction (EIP-6780).
+    #[cfg(feature = "shanghai")]
+    is_created_in_tx: Cell<F>,
+    is_destructed_prev: Cell<F>,
+    transfer: TransferToGadget<F>,
+    sufficient_gas: RangeCheckGadget<F, N_BYTES_GAS>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for SelfDestructGadget<F> {
+    const NAME: &'static str = "SELFDESTRUCT";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::SELFDESTRUCT;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.opcode_lookup(opcode.expr(), 1.expr());
+        // We do the responsible opcode check explicitly here because we're not using
+        // the `SameContextGadget` for `SELFDESTRUCT`.
+        cb.require_equal(
+            "Opcode should be SELFDESTRUCT",
+            opcode.expr(),
+            OpcodeId::SELFDESTRUCT.expr(),
+        );
+
+        let beneficiary_word = cb.query_word_rlc();
+        let beneficiary = from_bytes::expr(&beneficiary_word.cells[..N_BYTES_ACCOUNT_ADDRESS]);
+        cb.stack_pop(beneficiary_word.expr());
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let is_static = cb.call_context(None, CallContextFieldTag::IsStatic);
+        cb.require_zero(
+            "SELFDESTRUCT is not allowed in static call",
+            is_static.expr(),
+        );
+        let callee_address = cb.call_context(None, CallContextFieldTag::CalleeAddress);
+        let mut reversion_info = cb.reversion_info_read(None);
+
+        let is_warm = cb.query_bool();
+        cb.account_access_list_write(
+            tx_id.expr(),
+            beneficiary.expr(),
+            1.expr(),
+            is_warm.expr(),
+            Some(&mut reversion_info),
+        );
+
+        // For non-existing accounts the code_hash must be 0 in the rw_table.
+        let beneficiary_code_hash = cb.query_cell_phase2();
+        cb.account_read(
+            beneficiary.expr(),
+            AccountFieldTag::CodeHash,
+            beneficiary_code_hash.expr(),
+        );
+        let beneficiary_not_exists = IsZeroGadget::construct(cb, beneficiary_code_hash.expr());
+
+        let balance = cb.query_word_rlc();
+        cb.account_read(
+            callee_address.expr(),
+            AccountFieldTag::Balance,
+            balance.expr(),
+        );
+        let value_is_zero = IsZeroGadget::construct(cb, balance.expr());
+        let is_self = IsEqualGadget::construct(cb, beneficiary.expr(), callee_address.expr());
+
+        #[cfg(feature = "shanghai")]
+        let is_created_in_tx = cb.query_bool();
+        #[cfg(feature = "shanghai")]
+        cb.tx_created_account_read(tx_id.expr(), callee_address.expr(), is_created_in_tx.expr());
+        #[cfg(feature = "shanghai")]
+        let is_destructed = is_created_in_tx.expr();
+        #[cfg(not(feature = "shanghai"))]
+        let is_destructed = 1.expr();
+
+        // The account may already be marked by an earlier SELFDESTRUCT in the tx.
+        let is_destructed_prev = cb.query_bool();
+        cb.condition(is_destructed.expr(), |cb| {
+            cb.tx_destructed_account_write(
+                tx_id.expr(),
+                callee_address.expr(),
+                1.expr(),
+                is_destructed_prev.expr(),
+                Some(&mut reversion_info),
+            );
+        });
+
+        // The balance is moved to the beneficiary, or burnt if the beneficiary is
+        // the destructed account itself.
+        let is_balance_cleared = not::expr(value_is_zero.expr())
+            * or::expr([not::expr(is_self.expr()), is_destructed.expr()]);
+        cb.condition(is_balance_cleared.expr(), |cb| {
+            cb.account_write(
+                callee_address.expr(),
+                AccountFieldTag::Balance,
+                0.expr(),
+                balance.expr(),
+                Some(&mut reversion_info),
+            );
+        });
+        let transfer = cb.condition(not::expr(is_self.expr()), |cb| {
+            TransferToGadget::construct_with_is_zero(
+                cb,
+                beneficiary.expr(),
+                not::expr(beneficiary_not_exists.expr()),
+                0.expr(),
+                beneficiary_code_hash.expr(),
+                balance.clone(),
+                Either::Right(value_is_zero.expr()),
+                Some(&mut reversion_info),
+            )
+        });
+
+        // Call ends with SELFDESTRUCT must be successful
+        cb.call_context_lookup(false.expr(), None, CallContextFieldTag::IsSuccess, 1.expr());
+
+        let gas_cost = GasCost::SELFDESTRUCT.expr()
+            + not::expr(is_warm.expr()) * GasCost::COLD_ACCOUNT_ACCESS.expr()
+            + beneficiary_not_exists.expr()
+                * not::expr(value_is_zero.expr())
+                * GasCost::NEW_ACCOUNT.expr();
+        let sufficient_gas =
+            RangeCheckGadget::construct(cb, cb.curr.state.gas_left.expr() - gas_cost.expr());
+
+        // access list + destructed account + self balance + receiver code hash and
+        // balance
+        let reversible_write_counter_increase = 1.expr()
+            + is_destructed
+            + is_balance_cleared
+            + not::expr(is_self.expr())
+                * not::expr(value_is_zero.expr())
+                * (1.expr() + beneficiary_not_exists.expr());
+
+        let is_to_end_tx = cb.next.execution_state_selector([ExecutionState::EndTx]);
+        cb.require_equal(
+            "Go to EndTx only when is_root",
+            cb.curr.state.is_root.expr(),
+            is_to_end_tx,
+        );
+
+        // When it's a root call
+        cb.condition(cb.curr.state.is_root.expr(), |cb| {
+            cb.require_step_state_transition(StepStateTransition {
+                call_id: Same,
+                rw_counter: Delta(cb.rw_counter_offset()),
+                program_counter: To(0.expr()),
+                stack_pointer: To(STACK_CAPACITY.expr()),
+                gas_left: Delta(-gas_cost.expr()),
+                reversible_write_counter: To(0.expr()),
+                memory_word_size: To(0.expr()),
+                end_tx: To(1.expr()),
+                ..StepStateTransition::default()
+            });
+        });
+
+        // When it's an internal call
+        let restore_context = cb.condition(not::expr(cb.curr.state.is_root.expr()), |cb| {
+            RestoreContextGadget::construct2(
+                cb,
+                true.expr(),
+                gas_cost,
+                0.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+                reversible_write_counter_increase,
+            )
+        });
+
+        Self {
+            opcode,
+            beneficiary_word,
+            tx_id,
+            is_static,
+            callee_address,
+            reversion_info,
+            is_warm,
+            beneficiary_code_hash,
+            beneficiary_not_exists,
+            balance,
+            value_is_zero,
+            is_self,
+            #[cfg(feature = "shanghai")]
+            is_created_in_tx,
+            is_destructed_prev,
+            transfer,
+            sufficient_gas,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode.unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let mut rws = StepRws::new(block, step);
+
+        let beneficiary = rws.next().stack_value();
+        self.beneficiary_word
+            .assign(region, offset, Some(beneficiary.to_le_bytes()))?;
+        let beneficiary_address = beneficiary.to_scalar().unwrap();
+
+        rws.next(); // TxId
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
+        rws.next(); // IsStatic
+        self.is_static
+            .assign(region, offset, Value::known(F::from(call.is_static as u64)))?;
+        rws.next(); // CalleeAddress
+        let callee_address = call.address.to_scalar().unwrap();
+        self.callee_address
+            .assign(region, offset, Value::known(callee_address))?;
+
+        rws.offset_add(2); // RwCounterEndOfReversion, IsPersistent
+        self.reversion_info.assign(
+            region,
+            offset,
+            call.rw_counter_end_of_reversion,
+            call.is_persistent,
+        )?;
+
+        let (_, is_warm) = rws.next().tx_access_list_value_pair();
+        self.is_warm
+            .assign(region, offset, Value::known(F::from(is_warm)))?;
+
+        let beneficiary_code_hash = rws.next().account_value_pair().0;
+        self.beneficiary_code_hash.assign(
+            region,
+            offset,
+            region.code_hash(beneficiary_code_hash),
+        )?;
+        self.beneficiary_not_exists.assign_value(
+            region,
+            offset,
+            region.code_hash(beneficiary_code_hash),
+        )?;
+        let beneficiary_exists = !beneficiary_code_hash.is_zero();
+
+        let value = rws.next().account_value_pair().0;
+        self.balance
+            .assign(region, offset, Some(value.to_le_bytes()))?;
+        self.value_is_zero
+            .assign_value(region, offset, region.word_rlc(value))?;
+
+        let is_self = beneficiary_address == callee_address;
+        self.is_self
+            .assign(region, offset, beneficiary_address, callee_address)?;
+
+        #[cfg(feature = "shanghai")]
+        let is_destructed = {
+            let (is_created_in_tx, _) = rws.next().tx_created_account_value_pair();
+            self.is_created_in_tx.assign(
+                region,
+                offset,
+                Value::known(F::from(is_created_in_tx)),
+            )?;
+            is_created_in_tx
+        };
+        #[cfg(not(feature = "shanghai"))]
+        let is_destructed = true;
+
+        if is_destructed {
+            let (_, is_destructed_prev) = rws.next().tx_destructed_account_value_pair();
+            self.is_destructed_prev.assign(
+                region,
+                offset,
+                Value::known(F::from(is_destructed_prev)),
+            )?;
+        }
+        if !value.is_zero() && (!is_self || is_destructed) {
+            rws.next(); // Balance write of the current account
+        }
+        if !is_self {
+            self.transfer.assign_from_rws(
+                region,
+                offset,
+                beneficiary_exists,
+                false,
+                value,
+                &mut rws,
+            )?;
+        }
+        rws.next(); // IsSuccess
+
+        let gas_cost = step.gas_cost;
+        self.sufficient_gas
+            .assign(region, offset, F::from(step.gas_left - gas_cost))?;
+
+        if !call.is_root {
+            self.restore_context
+                .assign(region, offset, block, call, step, rws.offset())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{table::RwTableTag, test_util::CircuitTestBuilder, witness::Rw};
+    use eth_types::{address, bytecode, Address, Bytecode, ToWord, Word};
+    use mock::TestContext;
+
+    const CALLER: Address = Address::repeat_byte(0x01);
+    const CONTRACT: Address = Address::repeat_byte(0x10);
+
+    fn selfdestruct_code(beneficiary: Address) -> Bytecode {
+        bytecode! {
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        }
+    }
+
+    fn test_root_ok(beneficiary: Address, balance: Word) {
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(CALLER).balance(Word::from(1u64 << 30));
+                accs[1]
+                    .address(CONTRACT)
+                    .balance(balance)
+                    .nonce(Word::one())
+                    .code(selfdestruct_code(beneficiary));
+                accs[2]
+                    .address(address!("0x0000000000000000000000000000000000cafe03"))
+                    .balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(Word::from(100_000));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    fn test_internal_ok(beneficiary: Address, balance: Word) {
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(CALLER).balance(Word::from(1u64 << 30));
+                accs[1]
+                    .address(address!("0x0000000000000000000000000000000000000020"))
+                    .balance(Word::from(1u64 << 20))
+                    .code(bytecode! {
+                        PUSH1(0)
+                        PUSH1(0)
+                        PUSH1(0)
+                        PUSH1(0)
+                        PUSH1(0)
+                        PUSH20(CONTRACT.to_word())
+                        GAS
+                        CALL
+                        STOP
+                    });
+                accs[2]
+                    .address(CONTRACT)
+                    .balance(balance)
+                    .nonce(Word::one())
+                    .code(selfdestruct_code(beneficiary));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(Word::from(200_000));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn selfdestruct_root_to_existing_account() {
+        test_root_ok(
+            address!("0x0000000000000000000000000000000000cafe03"),
+            Word::from(1000u64),
+        );
+    }
+
+    #[test]
+    fn selfdestruct_root_to_non_existing_account() {
+        test_root_ok(
+            address!("0x0000000000000000000000000000000000cafe04"),
+            Word::from(1000u64),
+        );
+        test_root_ok(
+            address!("0x0000000000000000000000000000000000cafe04"),
+            Word::zero(),
+        );
+    }
+
+    #[test]
+    fn selfdestruct_root_to_self() {
+        test_root_ok(CONTRACT, Word::from(1000u64));
+        test_root_ok(CONTRACT, Word::zero());
+    }
+
+    #[test]
+    fn selfdestruct_internal() {
+        test_internal_ok(
+            address!("0x0000000000000000000000000000000000cafe03"),
+            Word::from(1000u64),
+        );
+        test_internal_ok(CALLER, Word::from(1000u64));
+        test_internal_ok(CONTRACT, Word::from(1000u64));
+    }
+
+    fn call(address: Address) -> Bytecode {
+        bytecode! {
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH20(address.to_word())
+            PUSH2(50000)
+            CALL
+            POP
+        }
+    }
+
+    // `entry_code` runs in a contract called by the tx, and can call the
+    // self destructing CONTRACT.
+    fn test_entry_ok(entry_code: Bytecode) {
+        let entry = address!("0x0000000000000000000000000000000000cafe05");
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(CALLER).balance(Word::from(1u64 << 30));
+                accs[1]
+                    .address(CONTRACT)
+                    .balance(Word::from(1000u64))
+                    .nonce(Word::one())
+                    .code(selfdestruct_code(CALLER))
+                    .storage([(Word::one(), Word::from(0x1234u64))].into_iter());
+                accs[2].address(entry).code(entry_code);
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[2].address)
+                    .gas(Word::from(1_000_000));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    // The account keeps its code until the end of the tx, so it can be called
+    // back and its code read after SELFDESTRUCT.
+    #[test]
+    fn selfdestruct_called_back_in_same_tx() {
+        let mut code = call(CONTRACT);
+        code.append(&call(CONTRACT));
+        code.append(&bytecode! {
+            PUSH20(CONTRACT.to_word())
+            EXTCODEHASH
+            PUSH20(CONTRACT.to_word())
+            EXTCODESIZE
+            STOP
+        });
+        test_entry_ok(code);
+    }
+
+    // A reverted SELFDESTRUCT unmarks the account, which isn't destructed.
+    #[test]
+    fn selfdestruct_reverted() {
+        let mut code = call(CONTRACT);
+        code.append(&bytecode! {
+            PUSH1(0)
+            PUSH1(0)
+            REVERT
+        });
+        test_entry_ok(code);
+    }
+
+    fn creation_ctx(beneficiary: Address) -> TestContext<2, 1> {
+        TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(CALLER).balance(Word::from(1u64 << 30));
+                accs[1]
+                    .address(address!("0x0000000000000000000000000000000000cafe03"))
+                    .balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .value(Word::from(1000u64))
+                    .gas(Word::from(200_000))
+                    .input(selfdestruct_code(beneficiary).code().into());
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn selfdestruct_created_in_tx() {
+        CircuitTestBuilder::new_from_test_ctx(creation_ctx(address!(
+            "0x0000000000000000000000000000000000cafe03"
+        )))
+        .run();
+    }
+
+    // The contract is created by the tx, so SELFDESTRUCT reads `true` from the
+    // TxCreatedAccount rw. Unmarking the creation must make the read inconsistent.
+    #[cfg(feature = "shanghai")]
+    #[test]
+    fn selfdestruct_created_in_tx_not_marked() {
+        CircuitTestBuilder::new_from_test_ctx(creation_ctx(address!(
+            "0x0000000000000000000000000000000000cafe03"
+        )))
+        .block_modifier(Box::new(|block| {
+            let rws = block.rws.0.get_mut(&RwTableTag::TxCreatedAccount).unwrap();
+            let write = rws.iter_mut().find(|rw| rw.is_write()).unwrap();
+            if let Rw::TxCreatedAccount { is_created, .. } = write {
+                *is_created = false;
+            }
+        }))
+        .evm_checks(None)
+        .state_checks(Some(Box::new(|prover, gate_rows, lookup_rows| {
+            assert!(prover
+                .verify_at_rows_par(gate_rows.iter().cloned(), lookup_rows.iter().cloned())
+                .is_err())
+        })))
+        .copy_checks(None)
+        .run();
+    }
+
+    // A contract created in an earlier tx isn't destructed (EIP-6780), and
+    // reading `true` without a creation in the same tx must fail.
+    #[cfg(feature = "shanghai")]
+    #[test]
+    fn selfdestruct_not_created_in_tx_marked() {
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(CALLER).balance(Word::from(1u64 << 30));
+                accs[1]
+                    .address(CONTRACT)
+                    .balance(Word::from(1000u64))
+                    .nonce(Word::one())
+                    .code(selfdestruct_code(CALLER));
+                accs[2]
+                    .address(address!("0x0000000000000000000000000000000000cafe03"))
+                    .balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(Word::from(100_000));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .block_modifier(Box::new(|block| {
+                let rws = block.rws.0.get_mut(&RwTableTag::TxCreatedAccount).unwrap();
+                assert_eq!(rws.len(), 1);
+                if let Rw::TxCreatedAccount {
+                    is_created,
+                    is_created_prev,
+                    ..
+                } = &mut rws[0]
+                {
+                    assert!(!*is_created);
+                    *is_created = true;
+                    *is_created_prev = true;
+                }
+            }))
+            .evm_checks(None)
+            .state_checks(Some(Box::new(|prover, gate_rows, lookup_rows| {
+                assert!(prover
+                    .verify_at_rows_par(gate_rows.iter().cloned(), lookup_rows.iter().cloned())
+                    .is_err())
+            })))
+            .copy_checks(None)
+            .run();
+    }
+}
@@ -38,6 +38,8 @@ pub(crate) const EVM_LOOKUP_COLS: usize = FIXED_TABLE_LOOKUPS
     + COPY_TABLE_LOOKUPS
     + KECCAK_TABLE_LOOKUPS
     + SHA256_TABLE_LOOKUPS
+    + RIPEMD160_TABLE_LOOKUPS
+    + BLAKE2F_TABLE_LOOKUPS
     + EXP_TABLE_LOOKUPS
     + SIG_TABLE_LOOKUPS
     + MODEXP_TABLE_LOOKUPS
@@ -54,6 +56,8 @@ pub(crate) const LOOKUP_CONFIG: &[(Table, usize)] = &[
     (Table::Copy, COPY_TABLE_LOOKUPS),
     (Table::Keccak, KECCAK_TABLE_LOOKUPS),
     (Table::Sha256, SHA256_TABLE_LOOKUPS),
+    (Table::Ripemd160, RIPEMD160_TABLE_LOOKUPS),
+    (Table::Blake2f, BLAKE2F_TABLE_LOOKUPS),
     (Table::Exp, EXP_TABLE_LOOKUPS),
     (Table::Sig, SIG_TABLE_LOOKUPS),
     (Table::ModExp, MODEXP_TABLE_LOOKUPS),
@@ -85,6 +89,12 @@ pub const KECCAK_TABLE_LOOKUPS: usize = 1;
 /// Keccak Table lookups done in EVMCircuit
 pub const SHA256_TABLE_LOOKUPS: usize = 1;
 
+/// Ripemd160 Table lookups done in EVMCircuit
+pub const RIPEMD160_TABLE_LOOKUPS: usize = 1;
+
+/// Blake2f Table lookups done in EVMCircuit
+pub const BLAKE2F_TABLE_LOOKUPS: usize = 1;
+
 /// Exp Table lookups done in EVMCircuit
 pub const EXP_TABLE_LOOKUPS: usize = 1;
 
@@ -14,6 +14,7 @@ use halo2_proofs::{
     circuit::Value,
     plonk::{Advice, Column, ConstraintSystem, Error, Expression},
 };
+use serde::{Deserialize, Serialize};
 use std::{fmt::Display, iter, marker::ConstParamTy};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
@@ -35,11 +36,14 @@ impl From<PrecompileCalls> for ExecutionState {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(ConstParamTy, Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter)]
+#[derive(
+    ConstParamTy, Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize,
+)]
 pub enum ExecutionState {
     // Internal state
     BeginTx,
     EndTx,
+    DestructAccount,
     EndInnerBlock,
     EndBlock,
     // Opcode successful cases
@@ -88,6 +92,14 @@ pub enum ExecutionState {
     MEMORY, // MLOAD, MSTORE, MSTORE8
     SLOAD,
     SSTORE,
+    #[cfg(feature = "cancun")]
+    TLOAD,
+    #[cfg(feature = "cancun")]
+    TSTORE,
+    #[cfg(feature = "cancun")]
+    MCOPY,
+    #[cfg(feature = "cancun")]
+    BLOBHASH,
     JUMP,
     JUMPI,
     PC,
@@ -288,6 +300,14 @@ impl ExecutionState {
             }
             Self::SLOAD => vec![OpcodeId::SLOAD],
             Self::SSTORE => vec![OpcodeId::SSTORE],
+            #[cfg(feature = "cancun")]
+            Self::TLOAD => vec![OpcodeId::TLOAD],
+            #[cfg(feature = "cancun")]
+            Self::TSTORE => vec![OpcodeId::TSTORE],
+            #[cfg(feature = "cancun")]
+            Self::MCOPY => vec![OpcodeId::MCOPY],
+            #[cfg(feature = "cancun")]
+            Self::BLOBHASH => vec![OpcodeId::BLOBHASH],
             Self::JUMP => vec![OpcodeId::JUMP],
             Self::JUMPI => vec![OpcodeId::JUMPI],
             Self::PC => vec![OpcodeId::PC],
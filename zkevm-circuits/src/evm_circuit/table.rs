@@ -155,6 +155,8 @@ pub(crate) enum Table {
     Copy,
     Keccak,
     Sha256,
+    Ripemd160,
+    Blake2f,
     Exp,
     Sig,
     ModExp,
@@ -314,6 +316,23 @@ pub(crate) enum Lookup<F> {
         /// the final output sha256 hash of the input.
         output_rlc: Expression<F>,
     },
+    /// Lookup to ripemd160 table.
+    Ripemd160Table {
+        /// Accumulator to the input.
+        input_rlc: Expression<F>,
+        /// Length of input that is being hashed.
+        input_len: Expression<F>,
+        /// Output (hash) until this state. This is the RLC representation of
+        /// the final output ripemd160 hash of the input.
+        output_rlc: Expression<F>,
+    },
+    /// Lookup to blake2f table.
+    Blake2fTable {
+        /// RLC of the 213 input bytes.
+        input_rlc: Expression<F>,
+        /// RLC of the 64 output bytes, i.e. the state vector after compression.
+        output_rlc: Expression<F>,
+    },
     /// Lookup to exponentiation table.
     ExpTable {
         base_limbs: [Expression<F>; 4],
@@ -329,10 +348,14 @@ pub(crate) enum Lookup<F> {
         is_valid: Expression<F>,
     },
     ModExpTable {
-        base_limbs: [Expression<F>; 3],
-        exp_limbs: [Expression<F>; 3],
-        modulus_limbs: [Expression<F>; 3],
-        result_limbs: [Expression<F>; 3],
+        base_rlc: Expression<F>,
+        exp_rlc: Expression<F>,
+        modulus_rlc: Expression<F>,
+        result_rlc: Expression<F>,
+        exp_bit_len: Expression<F>,
+        base_len: Expression<F>,
+        exp_len: Expression<F>,
+        modulus_len: Expression<F>,
     },
     EccTable {
         op_type: Expression<F>,
@@ -368,6 +391,8 @@ impl<F: Field> Lookup<F> {
             Self::CopyTable { .. } => Table::Copy,
             Self::KeccakTable { .. } => Table::Keccak,
             Self::Sha256Table { .. } => Table::Sha256,
+            Self::Ripemd160Table { .. } => Table::Ripemd160,
+            Self::Blake2fTable { .. } => Table::Blake2f,
             Self::ExpTable { .. } => Table::Exp,
             Self::SigTable { .. } => Table::Sig,
             Self::ModExpTable { .. } => Table::ModExp,
@@ -488,6 +513,26 @@ impl<F: Field> Lookup<F> {
                 input_len.clone(),
                 output_rlc.clone(),
             ],
+            Self::Ripemd160Table {
+                input_rlc,
+                input_len,
+                output_rlc,
+            } => vec![
+                1.expr(), // q_enable
+                1.expr(), // is_final
+                input_rlc.clone(),
+                input_len.clone(),
+                output_rlc.clone(),
+            ],
+            Self::Blake2fTable {
+                input_rlc,
+                output_rlc,
+            } => vec![
+                1.expr(), // q_enable
+                1.expr(), // is_final
+                input_rlc.clone(),
+                output_rlc.clone(),
+            ],
             Self::ExpTable {
                 base_limbs,
                 exponent_lo_hi,
@@ -521,24 +566,25 @@ impl<F: Field> Lookup<F> {
                 is_valid.clone(),
             ],
             Self::ModExpTable {
-                base_limbs,
-                exp_limbs,
-                modulus_limbs,
-                result_limbs,
+                base_rlc,
+                exp_rlc,
+                modulus_rlc,
+                result_rlc,
+                exp_bit_len,
+                base_len,
+                exp_len,
+                modulus_len,
             } => vec![
+                1.expr(), // q_enable
                 1.expr(), // q_head
-                base_limbs[0].clone(),
-                exp_limbs[0].clone(),
-                modulus_limbs[0].clone(),
-                result_limbs[0].clone(),
-                base_limbs[1].clone(),
-                exp_limbs[1].clone(),
-                modulus_limbs[1].clone(),
-                result_limbs[1].clone(),
-                base_limbs[2].clone(),
-                exp_limbs[2].clone(),
-                modulus_limbs[2].clone(),
-                result_limbs[2].clone(),
+                base_rlc.clone(),
+                exp_rlc.clone(),
+                modulus_rlc.clone(),
+                result_rlc.clone(),
+                exp_bit_len.clone(),
+                base_len.clone(),
+                exp_len.clone(),
+                modulus_len.clone(),
             ],
             Self::EccTable {
                 op_type,
@@ -1108,6 +1108,158 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // Transient Storage
+
+    pub(crate) fn transient_storage_read(
+        &mut self,
+        account_address: Expression<F>,
+        key: Expression<F>,
+        value: Expression<F>,
+        tx_id: Expression<F>,
+    ) {
+        self.rw_lookup(
+            "transient_storage_read",
+            false.expr(),
+            RwTableTag::TransientStorage,
+            RwValues::new(
+                tx_id,
+                account_address,
+                0.expr(),
+                key,
+                value.clone(),
+                value,
+                0.expr(),
+                0.expr(),
+            ),
+        );
+    }
+
+    pub(crate) fn transient_storage_write(
+        &mut self,
+        account_address: Expression<F>,
+        key: Expression<F>,
+        value: Expression<F>,
+        value_prev: Expression<F>,
+        tx_id: Expression<F>,
+        reversion_info: Option<&mut ReversionInfo<F>>,
+    ) {
+        self.reversible_write(
+            "TransientStorage write",
+            RwTableTag::TransientStorage,
+            RwValues::new(
+                tx_id,
+                account_address,
+                0.expr(),
+                key,
+                value,
+                value_prev,
+                0.expr(),
+                0.expr(),
+            ),
+            reversion_info,
+        );
+    }
+
+    // Accounts created in the tx (EIP-6780)
+
+    pub(crate) fn tx_created_account_write(
+        &mut self,
+        tx_id: Expression<F>,
+        account_address: Expression<F>,
+        value: Expression<F>,
+        value_prev: Expression<F>,
+        reversion_info: Option<&mut ReversionInfo<F>>,
+    ) {
+        self.reversible_write(
+            "TxCreatedAccount write",
+            RwTableTag::TxCreatedAccount,
+            RwValues::new(
+                tx_id,
+                account_address,
+                0.expr(),
+                0.expr(),
+                value,
+                value_prev,
+                0.expr(),
+                0.expr(),
+            ),
+            reversion_info,
+        );
+    }
+
+    pub(crate) fn tx_created_account_read(
+        &mut self,
+        tx_id: Expression<F>,
+        account_address: Expression<F>,
+        value: Expression<F>,
+    ) {
+        self.rw_lookup(
+            "tx created account read",
+            false.expr(),
+            RwTableTag::TxCreatedAccount,
+            RwValues::new(
+                tx_id,
+                account_address,
+                0.expr(),
+                0.expr(),
+                value.clone(),
+                value,
+                0.expr(),
+                0.expr(),
+            ),
+        );
+    }
+
+    // Accounts self destructed in the tx, destructed after EndTx
+
+    pub(crate) fn tx_destructed_account_write(
+        &mut self,
+        tx_id: Expression<F>,
+        account_address: Expression<F>,
+        value: Expression<F>,
+        value_prev: Expression<F>,
+        reversion_info: Option<&mut ReversionInfo<F>>,
+    ) {
+        self.reversible_write(
+            "TxDestructedAccount write",
+            RwTableTag::TxDestructedAccount,
+            RwValues::new(
+                tx_id,
+                account_address,
+                0.expr(),
+                0.expr(),
+                value,
+                value_prev,
+                0.expr(),
+                0.expr(),
+            ),
+            reversion_info,
+        );
+    }
+
+    pub(crate) fn tx_destructed_account_read(
+        &mut self,
+        tx_id: Expression<F>,
+        account_address: Expression<F>,
+        value: Expression<F>,
+    ) {
+        self.rw_lookup(
+            "tx destructed account read",
+            false.expr(),
+            RwTableTag::TxDestructedAccount,
+            RwValues::new(
+                tx_id,
+                account_address,
+                0.expr(),
+                0.expr(),
+                value.clone(),
+                value,
+                0.expr(),
+                0.expr(),
+            ),
+        );
+    }
+
     // Call context
 
     pub(crate) fn call_context(
@@ -1521,21 +1673,61 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // RIPEMD160 Table
+
+    pub(crate) fn ripemd160_table_lookup(
+        &mut self,
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        output_rlc: Expression<F>,
+    ) {
+        self.add_lookup(
+            "ripemd160 lookup",
+            Lookup::Ripemd160Table {
+                input_rlc,
+                input_len,
+                output_rlc,
+            },
+        );
+    }
+
+    // Blake2f Table
+
+    pub(crate) fn blake2f_table_lookup(
+        &mut self,
+        input_rlc: Expression<F>,
+        output_rlc: Expression<F>,
+    ) {
+        self.add_lookup(
+            "blake2f lookup",
+            Lookup::Blake2fTable {
+                input_rlc,
+                output_rlc,
+            },
+        );
+    }
+
     // ModExp table
     pub(crate) fn modexp_table_lookup(
         &mut self,
-        base_limbs: [Expression<F>; 3],
-        exp_limbs: [Expression<F>; 3],
-        modulus_limbs: [Expression<F>; 3],
-        result_limbs: [Expression<F>; 3],
+        base_rlc: Expression<F>,
+        exp_rlc: Expression<F>,
+        modulus_rlc: Expression<F>,
+        result_rlc: Expression<F>,
+        exp_bit_len: Expression<F>,
+        [base_len, exp_len, modulus_len]: [Expression<F>; 3],
     ) {
         self.add_lookup(
-            "u256 exponentiation modulus lookup",
+            "big integer exponentiation modulus lookup",
             Lookup::ModExpTable {
-                base_limbs,
-                exp_limbs,
-                modulus_limbs,
-                result_limbs,
+                base_rlc,
+                exp_rlc,
+                modulus_rlc,
+                result_rlc,
+                exp_bit_len,
+                base_len,
+                exp_len,
+                modulus_len,
             },
         );
     }
@@ -512,7 +512,7 @@ impl<F: Field> ExpCircuitConfig<F> {
         Ok(())
     }
 
-    fn min_num_rows(exp_events: &[ExpEvent]) -> usize {
+    pub(crate) fn min_num_rows(exp_events: &[ExpEvent]) -> usize {
         exp_events
             .iter()
             .map(|e| e.steps.len() * OFFSET_INCREMENT)
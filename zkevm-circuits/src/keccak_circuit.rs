@@ -1026,24 +1026,9 @@ impl<F: Field> SubCircuit<F> for KeccakCircuit<F> {
 
     /// Return the minimum number of rows required to prove the block
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
-        let rows_per_chunk = (NUM_ROUNDS + 1) * get_num_rows_per_round();
-        let aux_tables_rows = [
-            normalize_table_size(6),
-            normalize_table_size(4),
-            normalize_table_size(3),
-            lookup_table_size(CHI_BASE_LOOKUP_TABLE.len()),
-        ];
-        (
-            block
-                .keccak_inputs
-                .iter()
-                .map(|bytes| (bytes.len() as f64 / 136.0).ceil() as usize * rows_per_chunk)
-                .sum::<usize>()
-                + get_num_rows_per_round(), // reserved for first 12 dummy rows
-            max(
-                block.circuits_params.max_keccak_rows,
-                *(aux_tables_rows.iter().max().unwrap()),
-            ),
+        Self::min_num_rows(
+            block.keccak_inputs.iter().map(Vec::len),
+            block.circuits_params.max_keccak_rows,
         )
     }
 
@@ -1086,6 +1071,29 @@ impl<F: Field> KeccakCircuit<F> {
         }
     }
 
+    /// Minimum and total number of rows of the circuit for inputs of `input_lens` bytes.
+    pub(crate) fn min_num_rows(
+        input_lens: impl Iterator<Item = usize>,
+        max_keccak_rows: usize,
+    ) -> (usize, usize) {
+        let aux_tables_rows = [
+            normalize_table_size(6),
+            normalize_table_size(4),
+            normalize_table_size(3),
+            lookup_table_size(CHI_BASE_LOOKUP_TABLE.len()),
+        ];
+        (
+            input_lens.map(Self::num_rows_of_input).sum::<usize>() + get_num_rows_per_round(), /* reserved for first 12 dummy rows */
+            max(max_keccak_rows, *(aux_tables_rows.iter().max().unwrap())),
+        )
+    }
+
+    /// The number of rows used to hash an input of `len` bytes
+    pub(crate) fn num_rows_of_input(len: usize) -> usize {
+        let rows_per_chunk = (NUM_ROUNDS + 1) * get_num_rows_per_round();
+        (len as f64 / 136.0).ceil() as usize * rows_per_chunk
+    }
+
     /// Sets the witness using the data to be hashed
     pub(crate) fn generate_witness(&self, challenges: Challenges<Value<F>>) -> Vec<KeccakRow<F>> {
         multi_keccak(self.inputs.as_slice(), challenges, self.capacity())
@@ -1,9 +1,9 @@
 //! # zk_evm
 
 // We should try not to use incomplete_features unless it is really really needed and cannot be
-// avoided like `adt_const_params` used by DummyGadget
+// avoided like `adt_const_params` used by CreateGadget
 #![allow(incomplete_features)]
-// Needed by DummyGadget in evm circuit
+// Needed by CreateGadget in evm circuit
 #![feature(adt_const_params)]
 #![feature(slice_group_by)]
 #![feature(lazy_cell)]
@@ -23,10 +23,17 @@
 // we'd better ensure usize is 64-bit on target arch.
 #[cfg(not(target_pointer_width = "64"))]
 compile_error!("This program requires a 64-bit target architecture.");
+#[cfg(all(
+    feature = "eth-mpt",
+    any(feature = "zktrie", feature = "poseidon-codehash")
+))]
+compile_error!("The eth-mpt feature can't be enabled with zktrie or poseidon-codehash.");
 
+pub mod blake2f_circuit;
 pub mod bytecode_circuit;
 pub mod copy_circuit;
 pub mod ecc_circuit;
+pub mod eth_mpt_circuit;
 pub mod evm_circuit;
 pub mod exp_circuit;
 pub mod keccak_circuit;
@@ -38,6 +45,7 @@ pub mod sig_circuit;
 // we don't use this for aggregation
 //pub mod root_circuit;
 pub mod modexp_circuit;
+pub mod ripemd160_circuit;
 pub mod sha256_circuit;
 pub mod state_circuit;
 pub mod super_circuit;
@@ -1,208 +1,1287 @@
 //! The Modexp circuit is responsible for modexp operations on big integer from precompiled contract
-//! calls ModExp, current the size of supported integer is up to 32 bytes (U256)
+//! calls ModExp, the size of supported integer is up to SIZE_LIMIT bytes, MODEXP_SIZE_LIMIT by
+//! default.
+//!
+//! The operands are represented by SIZE_LIMIT / 8 limbs of 64 bits, each in a column. The circuit
+//! is made of blocks of BLOCK_ROWS rows, and an event takes
+//! - a head block, with the operands and the reduction of the base by the modulus
+//! - a skip block for each leading zero limb of the exponent
+//! - a step block for each bit of the other limbs of the exponent, from the most significant one,
+//!   which squares the accumulator and multiplies it by the base if the bit is set
+//! - a tail block, with the result and the row of the ModExpTable
+//!
+//! so the number of rows scales with the size of the exponent. The unused blocks are padding
+//! ones, all zero.
+//!
+//! A modular multiplication `a * b = q * n + r` with `r < n` takes MULMOD_ROWS rows, where the
+//! quotient, the remainder, `n - r - 1` and the carries of the limb columns of the equation are
+//! range checked by u16 parts. A modulus of zero is replaced by one, which makes the result zero.
+//!
+//! The operands are bounded by their sizes in the call data, so the RLC of the call data of a
+//! precompile call splits into the RLCs of the table in a unique way.
 
 #[cfg(any(feature = "test", test, feature = "test-circuits"))]
 mod dev;
 #[cfg(any(feature = "test", test, feature = "test-circuits"))]
 mod test;
 
+use std::{array, iter::successors, marker::PhantomData};
+
 use halo2_proofs::{
     circuit::{Layouter, Region, Value},
-    plonk::{Advice, Column, ConstraintSystem, Error},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, VirtualCells,
+    },
+    poly::Rotation,
 };
+use num::{BigInt, BigUint, Integer, One, ToPrimitive, Zero};
 
 use crate::{
-    table::ModExpTable,
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{ModExpTable, U16Table, U8Table},
     util::{Challenges, SubCircuit, SubCircuitConfig},
     witness,
 };
-use bus_mapping::circuit_input_builder::BigModExp;
-use eth_types::{Field, Word};
-
-//use misc_precompiled_circuit::value_for_assign;
-use misc_precompiled_circuit::circuits::{
-    modexp::{ModExpChip, Number},
-    range::{RangeCheckChip, RangeCheckConfig},
-    CommonGateConfig as ModExpConfig, Limb,
-};
+use bus_mapping::{circuit_input_builder::BigModExp, precompile::MODEXP_SIZE_LIMIT};
+use eth_types::Field;
+use gadgets::util::{expr_from_bytes, expr_from_u16, not, pow_of_two, sum, Expr};
+
+const LIMB_BITS: usize = 64;
+const N_BYTES_PER_LIMB: usize = LIMB_BITS / 8;
+const BLOCK_ROWS: usize = 64;
+
+// Rows of a modular multiplication, relative to its first row. A value row is followed by the
+// rows of its u16 parts.
+const MULMOD_A: usize = 0;
+const MULMOD_B: usize = 1;
+const MULMOD_N: usize = 2;
+const MULMOD_Q: usize = 3;
+const MULMOD_R: usize = 8;
+/// n - r - 1
+const MULMOD_D: usize = 13;
+/// borrows of n - r - 1
+const MULMOD_LT: usize = 18;
+/// carries of the limb columns [0, n_limbs)
+const MULMOD_CARRY_LO: usize = 19;
+/// carries of the limb columns [n_limbs, 2 * n_limbs)
+const MULMOD_CARRY_HI: usize = 25;
+const MULMOD_ROWS: usize = 31;
+const N_LIMB_PARTS: usize = 4;
+const N_CARRY_PARTS: usize = 5;
+/// The carries can be negative, they are stored with this offset in 80 bits.
+const CARRY_OFFSET: u128 = 1 << 79;
+
+// Rows of a block. A value row of the head and tail blocks is followed by the rows of its bytes.
+/// Exponent of the head block, result of the tail block, and the exponent register (the limbs
+/// not consumed yet, the current one being the most significant one) of the skip and step blocks
+const REGISTER_ROW: usize = 0;
+/// Base reduced by the modulus, in the skip and step blocks
+const BASE_RED_ROW: usize = 1;
+/// Squaring of the accumulator, in the step blocks
+const SQUARE_MULMOD: usize = 2;
+/// Multiplication of the squared accumulator by the base or one in the step blocks, and the
+/// reduction of the base in the head block. It takes the remainder of the squaring as `a`, which
+/// is the base in the head block.
+const MULTIPLY_MULMOD: usize = SQUARE_MULMOD + MULMOD_ROWS;
+const BASE_ROW: usize = SQUARE_MULMOD + MULMOD_R;
+const MODULUS_ROW: usize = 20;
+const VALUE_ROWS: [usize; 3] = [REGISTER_ROW, BASE_ROW, MODULUS_ROW];
+/// Row after the bytes of an operand of the head block, relative to its value row, with whether
+/// each limb is below the size of the operand
+const FULL_LIMBS_OFFSET: usize = 1 + N_BYTES_PER_LIMB;
 
-/// ModExp circuit config
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BlockKind {
+    Head,
+    Skip,
+    Step,
+    Tail,
+    Padding,
+}
+
+/// Values assigned to a block
 #[derive(Clone, Debug)]
-pub struct ModExpCircuitConfig {
-    modexp_config: ModExpConfig,
-    rangecheck_config: RangeCheckConfig,
-    modexp_table: ModExpTable,
+struct BlockAssignment {
+    kind: BlockKind,
+    rows: Vec<Vec<u128>>,
+    /// Values whose RLCs are assigned at the rows
+    rlc_values: Vec<(usize, BigUint)>,
+    byte_len: Vec<u64>,
+    bit: u64,
+    exp_acc: u64,
+    started: u64,
+    bit_idx: u64,
+    limb_end: u64,
+    limbs_done: u64,
+    modulus_is_zero: u64,
+    exp_bit_len: u64,
 }
 
-impl<F: Field> SubCircuitConfig<F> for ModExpCircuitConfig {
-    type ConfigArgs = ModExpTable;
+impl BlockAssignment {
+    fn new(kind: BlockKind, n_limbs: usize) -> Self {
+        Self {
+            kind,
+            rows: vec![vec![0; n_limbs]; BLOCK_ROWS],
+            rlc_values: vec![],
+            byte_len: vec![0; BLOCK_ROWS],
+            bit: 0,
+            exp_acc: 0,
+            started: 0,
+            bit_idx: 0,
+            limb_end: 0,
+            limbs_done: 0,
+            modulus_is_zero: 0,
+            exp_bit_len: 0,
+        }
+    }
+
+    fn n_limbs(&self) -> usize {
+        self.rows[0].len()
+    }
+
+    fn set_value(&mut self, row: usize, value: &BigUint) {
+        let limbs = to_limbs(value, self.n_limbs());
+        for j in 0..N_BYTES_PER_LIMB {
+            self.rows[row + 1 + j] = limbs.iter().map(|limb| (limb >> (8 * j)) & 0xff).collect();
+        }
+        self.rows[row] = limbs;
+        self.rlc_values.push((row, value.clone()));
+    }
+
+    /// Assign an operand of the head block with its size in bytes.
+    fn set_operand(&mut self, row: usize, value: &BigUint, size: usize) {
+        self.set_value(row, value);
+        self.byte_len[row] = size as u64;
+        for j in 0..N_BYTES_PER_LIMB {
+            self.byte_len[row + 1 + j] = (j < size % N_BYTES_PER_LIMB) as u64;
+        }
+        self.rows[row + FULL_LIMBS_OFFSET] = (0..self.n_limbs())
+            .map(|k| (k < size / N_BYTES_PER_LIMB) as u128)
+            .collect();
+    }
+
+    fn set_parts(&mut self, row: usize, limbs: &[u128], n_parts: usize) {
+        self.rows[row] = limbs.to_vec();
+        for j in 0..n_parts {
+            self.rows[row + 1 + j] = limbs
+                .iter()
+                .map(|limb| (limb >> (16 * j)) & 0xffff)
+                .collect();
+        }
+    }
+
+    /// Assign the modular multiplication at `offset`, return the remainder.
+    fn set_mulmod(&mut self, offset: usize, a: &BigUint, b: &BigUint, n: &BigUint) -> BigUint {
+        let (quotient, remainder) = (a * b).div_rem(n);
+        let d = n - &remainder - 1u8;
+        let n_limbs = self.n_limbs();
+        let [a, b, n, q, r, d] =
+            [a, b, n, &quotient, &remainder, &d].map(|value| to_limbs(value, n_limbs));
+
+        self.rows[offset + MULMOD_A] = a.clone();
+        self.rows[offset + MULMOD_B] = b.clone();
+        self.rows[offset + MULMOD_N] = n.clone();
+        self.set_parts(offset + MULMOD_Q, &q, N_LIMB_PARTS);
+        self.set_parts(offset + MULMOD_R, &r, N_LIMB_PARTS);
+        self.set_parts(offset + MULMOD_D, &d, N_LIMB_PARTS);
+
+        // r + d + 1 = n
+        let mut borrow = 1;
+        for (k, cell) in self.rows[offset + MULMOD_LT].iter_mut().enumerate() {
+            borrow = (r[k] + d[k] + borrow) >> LIMB_BITS;
+            *cell = borrow;
+        }
+
+        // a * b - q * n - r = 0, by limb columns
+        let mut carry = BigInt::zero();
+        let mut carries = vec![0; 2 * n_limbs];
+        for (k, stored) in carries.iter_mut().enumerate() {
+            let mut column = carry;
+            for i in k.saturating_sub(n_limbs - 1)..=k.min(n_limbs - 1) {
+                column += BigInt::from(a[i] * b[k - i]) - BigInt::from(q[i] * n[k - i]);
+            }
+            if k < n_limbs {
+                column -= r[k];
+            }
+            carry = column >> LIMB_BITS;
+            *stored = (&carry + CARRY_OFFSET)
+                .to_u128()
+                .expect("carry of mulmod is in 80 bits");
+        }
+        self.set_parts(offset + MULMOD_CARRY_LO, &carries[..n_limbs], N_CARRY_PARTS);
+        self.set_parts(offset + MULMOD_CARRY_HI, &carries[n_limbs..], N_CARRY_PARTS);
+
+        remainder
+    }
+}
+
+/// Limbs of a value, the limbs beyond `n_limbs` are dropped.
+fn to_limbs(value: &BigUint, n_limbs: usize) -> Vec<u128> {
+    let mut limbs = vec![0; n_limbs];
+    for (limb, digit) in limbs.iter_mut().zip(value.iter_u64_digits()) {
+        *limb = digit as u128;
+    }
+    limbs
+}
+
+/// Number of the limbs of the exponent processed bit by bit, the leading zero ones are skipped.
+fn n_step_limbs(exponent: &BigUint) -> usize {
+    (exponent.bits() as usize).div_ceil(LIMB_BITS).max(1)
+}
+
+/// Number of the blocks of an event.
+fn n_event_blocks(event: &BigModExp, n_limbs: usize) -> usize {
+    let n_step_limbs = n_step_limbs(&event.exponent);
+    2 + n_limbs.saturating_sub(n_step_limbs) + n_step_limbs * LIMB_BITS
+}
+
+/// Blocks of an event, from the head to the tail.
+fn event_blocks(event: &BigModExp, n_limbs: usize) -> Vec<BlockAssignment> {
+    let modulus = if event.modulus.is_zero() {
+        BigUint::one()
+    } else {
+        event.modulus.clone()
+    };
+
+    let mut head = BlockAssignment::new(BlockKind::Head, n_limbs);
+    head.set_operand(REGISTER_ROW, &event.exponent, event.exponent_len);
+    head.set_operand(BASE_ROW, &event.base, event.base_len);
+    head.set_operand(MODULUS_ROW, &event.modulus, event.modulus_len);
+    head.modulus_is_zero = event.modulus.is_zero() as u64;
+    let base_red = head.set_mulmod(MULTIPLY_MULMOD, &event.base, &BigUint::one(), &modulus);
+    let mut blocks = vec![head];
+
+    let n_step_limbs = n_step_limbs(&event.exponent);
+    let mut register = to_limbs(&event.exponent, n_limbs);
+    let mut limbs_done = 0;
+    let shift = |register: &[u128]| -> Vec<u128> {
+        (0..n_limbs)
+            .map(|k| k.checked_sub(1).map_or(0, |k| register[k]))
+            .collect()
+    };
+    for _ in n_step_limbs..n_limbs {
+        let mut skip = BlockAssignment::new(BlockKind::Skip, n_limbs);
+        skip.rows[REGISTER_ROW] = register.clone();
+        skip.rows[BASE_RED_ROW] = to_limbs(&base_red, n_limbs);
+        skip.rows[MULTIPLY_MULMOD + MULMOD_N] = to_limbs(&modulus, n_limbs);
+        limbs_done += 1;
+        skip.limbs_done = limbs_done;
+        blocks.push(skip);
+        register = shift(&register);
+    }
+
+    let mut acc = BigUint::one();
+    let mut started = 0;
+    let mut exp_bit_len = 0;
+    for _ in 0..n_step_limbs {
+        let limb = register[n_limbs - 1] as u64;
+        for bit_idx in 0..LIMB_BITS {
+            let bit = (limb >> (LIMB_BITS - 1 - bit_idx)) & 1;
+            let limb_end = (bit_idx == LIMB_BITS - 1) as u64;
+            started |= bit;
+            exp_bit_len += started;
+            limbs_done += limb_end;
+
+            let mut step = BlockAssignment::new(BlockKind::Step, n_limbs);
+            step.rows[REGISTER_ROW] = register.clone();
+            step.rows[BASE_RED_ROW] = to_limbs(&base_red, n_limbs);
+            let squared = step.set_mulmod(SQUARE_MULMOD, &acc, &acc, &modulus);
+            let multiplier = if bit == 1 {
+                base_red.clone()
+            } else {
+                BigUint::one()
+            };
+            acc = step.set_mulmod(MULTIPLY_MULMOD, &squared, &multiplier, &modulus);
+            step.bit = bit;
+            step.exp_acc = limb >> (LIMB_BITS - 1 - bit_idx);
+            step.started = started;
+            step.bit_idx = bit_idx as u64;
+            step.limb_end = limb_end;
+            step.limbs_done = limbs_done;
+            step.exp_bit_len = exp_bit_len;
+            blocks.push(step);
+        }
+        register = shift(&register);
+    }
+
+    // the result of the event is in the table, which must match the accumulator
+    let mut tail = BlockAssignment::new(BlockKind::Tail, n_limbs);
+    tail.set_value(REGISTER_ROW, &acc);
+    tail.exp_bit_len = exp_bit_len;
+    blocks.push(tail);
+
+    blocks
+}
+
+fn query_row<F: Field>(
+    meta: &mut VirtualCells<F>,
+    limbs: &[Column<Advice>],
+    rotation: i32,
+) -> Vec<Expression<F>> {
+    limbs
+        .iter()
+        .map(|&col| meta.query_advice(col, Rotation(rotation)))
+        .collect()
+}
+
+/// Limbs composed of the u16 parts in the rows after `rotation`.
+fn query_u16_parts<F: Field>(
+    meta: &mut VirtualCells<F>,
+    limbs: &[Column<Advice>],
+    rotation: i32,
+    n_parts: usize,
+) -> Vec<Expression<F>> {
+    limbs
+        .iter()
+        .map(|&col| {
+            let parts = (0..n_parts)
+                .map(|j| meta.query_advice(col, Rotation(rotation + 1 + j as i32)))
+                .collect::<Vec<_>>();
+            expr_from_u16(&parts)
+        })
+        .collect()
+}
+
+/// ModExp circuit config, for operands of up to `SIZE_LIMIT` bytes
+#[derive(Clone, Debug)]
+pub struct ModExpCircuitConfig<const SIZE_LIMIT: usize = MODEXP_SIZE_LIMIT> {
+    /// First row of each block
+    q_block: Column<Fixed>,
+    /// First row of the first block
+    q_first: Column<Fixed>,
+    /// Value rows of the head block
+    q_value: Column<Fixed>,
+    /// First row of the squaring of a block
+    q_square: Column<Fixed>,
+    /// First row of the multiplication of a block
+    q_multiply: Column<Fixed>,
+    /// Byte rows of the head and tail blocks
+    q_u8: Column<Fixed>,
+    /// Rows of the u16 parts of the multiplications
+    q_u16: Column<Fixed>,
+    is_head: Column<Advice>,
+    is_skip: Column<Advice>,
+    is_step: Column<Advice>,
+    is_tail: Column<Advice>,
+    limbs: Vec<Column<Advice>>,
+    /// RLC of the bytes of the value rows
+    rlc: Column<Advice>,
+    /// Size of an operand of the head block at its value row, and whether the index of a byte in
+    /// its limb is below the size modulo 8 at its byte rows
+    byte_len: Column<Advice>,
+    /// Bit of the exponent of a step block
+    bit: Column<Advice>,
+    /// Bits of the current limb of the exponent, up to the one of a step block
+    exp_acc: Column<Advice>,
+    /// Whether a set bit of the exponent is reached
+    started: Column<Advice>,
+    /// Index of the bit in the current limb of the exponent, from the most significant one
+    bit_idx: Column<Advice>,
+    /// Whether the bit is the last one of the current limb of the exponent
+    limb_end: Column<Advice>,
+    /// Number of the limbs of the exponent consumed, with the current one if it ends
+    limbs_done: Column<Advice>,
+    /// Whether the modulus of the head block is zero
+    modulus_is_zero: Column<Advice>,
+    /// Inverse of the sum of the limbs of the modulus of the head block if it's not zero
+    modulus_inv: Column<Advice>,
+    modexp_table: ModExpTable<SIZE_LIMIT>,
+}
+
+/// Circuit configuration arguments
+pub struct ModExpCircuitConfigArgs<F: Field, const SIZE_LIMIT: usize = MODEXP_SIZE_LIMIT> {
+    /// ModExpTable
+    pub modexp_table: ModExpTable<SIZE_LIMIT>,
+    /// u8 lookup table
+    pub u8_table: U8Table,
+    /// u16 lookup table
+    pub u16_table: U16Table,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field, const SIZE_LIMIT: usize> SubCircuitConfig<F> for ModExpCircuitConfig<SIZE_LIMIT> {
+    type ConfigArgs = ModExpCircuitConfigArgs<F, SIZE_LIMIT>;
 
     /// Return a new ModExpCircuitConfig
-    fn new(meta: &mut ConstraintSystem<F>, modexp_table: Self::ConfigArgs) -> Self {
-        let rangecheck_config = RangeCheckChip::configure(meta);
-        let modexp_config = ModExpChip::configure(meta, &rangecheck_config);
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            modexp_table,
+            u8_table,
+            u16_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        assert!(
+            SIZE_LIMIT > 0 && SIZE_LIMIT % N_BYTES_PER_LIMB == 0,
+            "modexp size limit {SIZE_LIMIT} is not a multiple of the limb size"
+        );
+        let n_limbs = Self::N_LIMBS;
+        let q_enable = modexp_table.q_enable;
+        let [q_block, q_first, q_value, q_square, q_multiply, q_u8, q_u16] =
+            array::from_fn(|_| meta.fixed_column());
+        let [is_head, is_skip, is_step, is_tail] = array::from_fn(|_| meta.advice_column());
+        let limbs = (0..n_limbs)
+            .map(|_| meta.advice_column())
+            .collect::<Vec<_>>();
+        let rlc = meta.advice_column_in(SecondPhase);
+        let byte_len = meta.advice_column();
+        let [bit, exp_acc, started, bit_idx, limb_end, limbs_done, modulus_is_zero, modulus_inv] =
+            array::from_fn(|_| meta.advice_column());
+        let kinds = [is_head, is_skip, is_step, is_tail];
+
+        // rotation of a row of the previous block, from the first row of a block
+        let prev = |row: usize| row as i32 - BLOCK_ROWS as i32;
+
+        meta.create_gate("modexp block kind", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let flags = kinds.map(|col| meta.query_advice(col, Rotation::cur()));
+            for flag in flags.iter() {
+                cb.require_boolean("block kind flag is boolean", flag.clone());
+            }
+            cb.require_boolean("at most one block kind", sum::expr(flags.iter()));
+
+            let q_block = meta.query_fixed(q_block, Rotation::cur());
+            let prev_flags = kinds.map(|col| meta.query_advice(col, Rotation::prev()));
+            cb.condition(not::expr(q_block.clone()), |cb| {
+                for (flag, prev_flag) in flags.iter().zip(prev_flags) {
+                    cb.require_equal("block kind is the same in a block", flag.clone(), prev_flag);
+                }
+            });
+
+            cb.require_equal(
+                "the table row of an event is the first row of its tail block",
+                meta.query_advice(modexp_table.q_head, Rotation::cur()),
+                q_block * flags[3].clone(),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("modexp block", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            let [head, skip, step, tail] = kinds.map(|col| meta.query_advice(col, Rotation::cur()));
+            let [prev_head, prev_skip, prev_step, _] =
+                kinds.map(|col| meta.query_advice(col, Rotation(prev(0))));
+            let padding = not::expr(sum::expr([&head, &skip, &step, &tail]));
+
+            cb.require_zero(
+                "first block is a head or padding one",
+                q_first.expr() * sum::expr([&skip, &step, &tail]),
+            );
+            cb.condition(not::expr(q_first), |cb| {
+                cb.require_zero(
+                    "head or padding block follows a tail or padding one",
+                    (head.expr() + padding) * sum::expr([&prev_head, &prev_skip, &prev_step]),
+                );
+                cb.require_zero(
+                    "skip block follows a head or skip one",
+                    skip.expr() * not::expr(prev_head.expr() + prev_skip.expr()),
+                );
+                cb.require_zero(
+                    "step block follows a head, skip or step one",
+                    step.expr() * not::expr(sum::expr([&prev_head, &prev_skip, &prev_step])),
+                );
+                cb.require_zero(
+                    "tail block follows a step one",
+                    tail.expr() * not::expr(prev_step.expr()),
+                );
+            });
+
+            // RLCs and sizes of the base, exponent and modulus
+            let operand_cols = [
+                modexp_table.base,
+                modexp_table.exp,
+                modexp_table.modulus,
+                modexp_table.base_len,
+                modexp_table.exp_len,
+                modexp_table.modulus_len,
+            ];
+            let operand_values = operand_cols.map(|col| meta.query_advice(col, Rotation::cur()));
+            let prev_operand_values =
+                operand_cols.map(|col| meta.query_advice(col, Rotation(prev(0))));
+            let result_rlc = meta.query_advice(modexp_table.result, Rotation::cur());
+            let exp_bit_len = meta.query_advice(modexp_table.exp_bit_len, Rotation::cur());
+            let prev_exp_bit_len = meta.query_advice(modexp_table.exp_bit_len, Rotation(prev(0)));
+            // base, exponent and modulus of the head block, or the result of the tail block
+            let [base_rlc, value_rlc, modulus_rlc] = [BASE_ROW, REGISTER_ROW, MODULUS_ROW]
+                .map(|row| meta.query_advice(rlc, Rotation(row as i32)));
+            let [base_len, exp_len, modulus_len] = [BASE_ROW, REGISTER_ROW, MODULUS_ROW]
+                .map(|row| meta.query_advice(byte_len, Rotation(row as i32)));
+
+            let aux = [bit, exp_acc, started, bit_idx, limb_end, limbs_done];
+            let [bit, exp_acc, started, bit_idx, limb_end, limbs_done] =
+                aux.map(|col| meta.query_advice(col, Rotation::cur()));
+            let [_, prev_exp_acc, prev_started, prev_bit_idx, prev_limb_end, prev_limbs_done] =
+                aux.map(|col| meta.query_advice(col, Rotation(prev(0))));
+            let modulus_is_zero = meta.query_advice(modulus_is_zero, Rotation::cur());
+            let modulus_inv = meta.query_advice(modulus_inv, Rotation::cur());
+
+            let register = query_row(meta, &limbs, REGISTER_ROW as i32);
+            let prev_register = query_row(meta, &limbs, prev(REGISTER_ROW));
+            let base_red = query_row(meta, &limbs, BASE_RED_ROW as i32);
+            let prev_base_red = query_row(meta, &limbs, prev(BASE_RED_ROW));
+            let base = query_row(meta, &limbs, BASE_ROW as i32);
+            let modulus = query_row(meta, &limbs, MODULUS_ROW as i32);
+            // remainder of the multiplication of the previous block, which is the reduced base
+            // for a head block and the accumulator for a step block
+            let prev_acc = query_row(meta, &limbs, prev(MULTIPLY_MULMOD + MULMOD_R));
+            let [square_a, square_b, square_n] = [MULMOD_A, MULMOD_B, MULMOD_N]
+                .map(|row| query_row(meta, &limbs, (SQUARE_MULMOD + row) as i32));
+            let [multiply_a, multiply_b, multiply_n] = [MULMOD_A, MULMOD_B, MULMOD_N]
+                .map(|row| query_row(meta, &limbs, (MULTIPLY_MULMOD + row) as i32));
+            let prev_multiply_n = query_row(meta, &limbs, prev(MULTIPLY_MULMOD + MULMOD_N));
+            let one_limb = |k: usize| if k == 0 { 1.expr() } else { 0.expr() };
+
+            cb.condition(head.expr(), |cb| {
+                for (operand_value, value) in operand_values.iter().zip([
+                    &base_rlc,
+                    &value_rlc,
+                    &modulus_rlc,
+                    &base_len,
+                    &exp_len,
+                    &modulus_len,
+                ]) {
+                    cb.require_equal(
+                        "table operand is the RLC or the size of the operand",
+                        operand_value.expr(),
+                        value.expr(),
+                    );
+                }
+
+                let modulus_sum = sum::expr(modulus.iter());
+                cb.require_equal(
+                    "modulus_inv is the inverse of the sum of the modulus limbs",
+                    modulus_sum.expr() * modulus_inv,
+                    not::expr(modulus_is_zero.expr()),
+                );
+                cb.require_zero(
+                    "modulus_is_zero is zero if the modulus is not zero",
+                    modulus_sum * modulus_is_zero.expr(),
+                );
+                for k in 0..n_limbs {
+                    cb.require_equal(
+                        "base is reduced by the modulus, or by one if it's zero",
+                        multiply_n[k].expr(),
+                        modulus[k].expr() + one_limb(k) * modulus_is_zero.expr(),
+                    );
+                    cb.require_equal(
+                        "base is reduced by a multiplication by one",
+                        multiply_b[k].expr(),
+                        one_limb(k),
+                    );
+                }
+            });
+
+            cb.condition(head.expr() + step.expr(), |cb| {
+                for k in 0..n_limbs {
+                    cb.require_equal(
+                        "multiplication takes the base or the squared accumulator",
+                        multiply_a[k].expr(),
+                        base[k].expr(),
+                    );
+                }
+            });
+
+            cb.condition(skip.expr() + step.expr(), |cb| {
+                let consume = prev_skip.expr() + prev_step.expr() * prev_limb_end.expr();
+                let keep = prev_step.expr() * not::expr(prev_limb_end.expr());
+                for k in 0..n_limbs {
+                    let shifted = if k == 0 {
+                        0.expr()
+                    } else {
+                        prev_register[k - 1].expr()
+                    };
+                    cb.require_equal(
+                        "exponent register is shifted by a limb once the limb is consumed",
+                        register[k].expr(),
+                        prev_head.expr() * prev_register[k].expr()
+                            + consume.expr() * shifted
+                            + keep.expr() * prev_register[k].expr(),
+                    );
+                    cb.require_equal(
+                        "base_red is the one reduced by the head block",
+                        base_red[k].expr(),
+                        prev_head.expr() * prev_acc[k].expr()
+                            + not::expr(prev_head.expr()) * prev_base_red[k].expr(),
+                    );
+                    cb.require_equal(
+                        "modulus is the one of the head block",
+                        multiply_n[k].expr(),
+                        prev_multiply_n[k].expr(),
+                    );
+                }
+                for (operand_value, prev_operand_value) in
+                    operand_values.iter().zip(prev_operand_values.iter())
+                {
+                    cb.require_equal(
+                        "table operand is the one of the previous block",
+                        operand_value.expr(),
+                        prev_operand_value.expr(),
+                    );
+                }
+            });
+
+            cb.condition(skip.expr(), |cb| {
+                cb.require_zero(
+                    "skipped limb of the exponent is zero",
+                    register[n_limbs - 1].expr(),
+                );
+                cb.require_equal(
+                    "limbs_done counts the skipped limbs",
+                    limbs_done.expr(),
+                    prev_skip.expr() * prev_limbs_done.expr() + 1.expr(),
+                );
+                cb.require_zero("no bit of the exponent before a step", exp_bit_len.expr());
+            });
+
+            cb.condition(step.expr(), |cb| {
+                let keep = prev_step.expr() * not::expr(prev_limb_end.expr());
+                cb.require_boolean("bit is boolean", bit.expr());
+                cb.require_boolean("limb_end is boolean", limb_end.expr());
+                cb.require_equal(
+                    "bit_idx starts from zero at each limb",
+                    bit_idx.expr(),
+                    keep.expr() * (prev_bit_idx.expr() + 1.expr()),
+                );
+                cb.require_zero(
+                    "limb ends at its last bit",
+                    limb_end.expr() * (bit_idx.expr() - (LIMB_BITS - 1).expr()),
+                );
+                cb.require_equal(
+                    "exp_acc accumulates the bits of the limb",
+                    exp_acc.expr(),
+                    2.expr() * keep * prev_exp_acc.expr() + bit.expr(),
+                );
+                cb.require_zero(
+                    "bits of the limb compose the current limb of the exponent",
+                    limb_end.expr() * (exp_acc.expr() - register[n_limbs - 1].expr()),
+                );
+                cb.require_equal(
+                    "started once a bit is set",
+                    started.expr(),
+                    prev_step.expr()
+                        * (prev_started.expr() + bit.expr() - prev_started.expr() * bit.expr())
+                        + not::expr(prev_step.expr()) * bit.expr(),
+                );
+                cb.require_equal(
+                    "exp_bit_len counts the bits from the first set one",
+                    exp_bit_len.expr(),
+                    prev_step.expr() * prev_exp_bit_len.expr() + started.expr(),
+                );
+                cb.require_equal(
+                    "limbs_done counts the ended limbs",
+                    limbs_done.expr(),
+                    (prev_skip.expr() + prev_step.expr()) * prev_limbs_done.expr()
+                        + limb_end.expr(),
+                );
+                for k in 0..n_limbs {
+                    cb.require_equal(
+                        "accumulator is one before the first step",
+                        square_a[k].expr(),
+                        prev_step.expr() * prev_acc[k].expr()
+                            + not::expr(prev_step.expr()) * one_limb(k),
+                    );
+                    cb.require_equal(
+                        "accumulator is squared",
+                        square_b[k].expr(),
+                        square_a[k].expr(),
+                    );
+                    cb.require_equal(
+                        "squaring is reduced by the modulus",
+                        square_n[k].expr(),
+                        multiply_n[k].expr(),
+                    );
+                    cb.require_equal(
+                        "squared accumulator is multiplied by the base if the bit is set",
+                        multiply_b[k].expr(),
+                        bit.expr() * base_red[k].expr() + not::expr(bit.expr()) * one_limb(k),
+                    );
+                }
+            });
+
+            cb.condition(tail.expr(), |cb| {
+                cb.require_equal(
+                    "tail block follows the end of a limb",
+                    prev_limb_end.expr(),
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "all limbs of the exponent are consumed",
+                    prev_limbs_done.expr(),
+                    n_limbs.expr(),
+                );
+                for k in 0..n_limbs {
+                    cb.require_equal(
+                        "result is the accumulator",
+                        register[k].expr(),
+                        prev_acc[k].expr(),
+                    );
+                }
+                cb.require_equal(
+                    "table result is the RLC of the result",
+                    result_rlc.expr(),
+                    value_rlc.expr(),
+                );
+                for (operand_value, prev_operand_value) in
+                    operand_values.iter().zip(prev_operand_values.iter())
+                {
+                    cb.require_equal(
+                        "table operand is the one of the previous block",
+                        operand_value.expr(),
+                        prev_operand_value.expr(),
+                    );
+                }
+                cb.require_equal(
+                    "exp_bit_len is the one of the last step",
+                    exp_bit_len.expr(),
+                    prev_exp_bit_len.expr(),
+                );
+            });
+
+            cb.gate(meta.query_fixed(q_block, Rotation::cur()))
+        });
+
+        meta.create_gate("modexp value", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let values = query_row(meta, &limbs, 0);
+            let bytes = (0..N_BYTES_PER_LIMB)
+                .map(|j| query_row(meta, &limbs, 1 + j as i32))
+                .collect::<Vec<_>>();
+            for (k, value) in values.into_iter().enumerate() {
+                let limb_bytes = bytes
+                    .iter()
+                    .map(|bytes| bytes[k].expr())
+                    .collect::<Vec<_>>();
+                cb.require_equal(
+                    "limb is composed of its bytes",
+                    value,
+                    expr_from_bytes(&limb_bytes),
+                );
+            }
+
+            // the bytes are big-endian, i.e. the RLC is the one of the reversed little-endian
+            // bytes of the limbs
+            let r = challenges.keccak_input();
+            let r_pows = successors(Some(1.expr()), |pow: &Expression<F>| {
+                Some(pow.expr() * r.expr())
+            })
+            .take(N_BYTES_PER_LIMB + 1)
+            .collect::<Vec<_>>();
+            let value_rlc = (0..n_limbs).rev().fold(0.expr(), |acc, k| {
+                acc * r_pows[N_BYTES_PER_LIMB].expr()
+                    + sum::expr(
+                        (0..N_BYTES_PER_LIMB).map(|j| bytes[j][k].expr() * r_pows[j].expr()),
+                    )
+            });
+            cb.require_equal(
+                "rlc is the RLC of the big-endian bytes of the value",
+                meta.query_advice(rlc, Rotation::cur()),
+                value_rlc,
+            );
+
+            let is_head = meta.query_advice(is_head, Rotation::cur());
+            let is_tail = meta.query_advice(is_tail, Rotation::cur());
+            cb.gate(
+                meta.query_fixed(q_value, Rotation::cur()) * is_head
+                    + meta.query_fixed(q_block, Rotation::cur()) * is_tail,
+            )
+        });
+
+        meta.create_gate("modexp operand size", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            // the bytes of an operand beyond its size are zero, i.e. the limbs above the partial
+            // one, and the bytes of the partial limb from the index of the size modulo 8
+            let size = meta.query_advice(byte_len, Rotation::cur());
+            let in_partial = (0..N_BYTES_PER_LIMB)
+                .map(|j| meta.query_advice(byte_len, Rotation(1 + j as i32)))
+                .collect::<Vec<_>>();
+            let full = query_row(meta, &limbs, FULL_LIMBS_OFFSET as i32);
+            for flags in [&in_partial, &full] {
+                for (idx, flag) in flags.iter().enumerate() {
+                    cb.require_boolean("operand size flag is boolean", flag.expr());
+                    if idx > 0 {
+                        cb.require_zero(
+                            "operand size flags are set up to an index",
+                            flag.expr() * not::expr(flags[idx - 1].expr()),
+                        );
+                    }
+                }
+            }
+            cb.require_equal(
+                "size is the number of bytes of the full limbs and of the partial one",
+                size,
+                N_BYTES_PER_LIMB.expr() * sum::expr(full.iter()) + sum::expr(in_partial.iter()),
+            );
+            for (j, in_partial) in in_partial.iter().enumerate() {
+                let bytes = query_row(meta, &limbs, 1 + j as i32);
+                for (k, byte) in bytes.into_iter().enumerate() {
+                    let prev_full = if k == 0 { 1.expr() } else { full[k - 1].expr() };
+                    let is_partial = prev_full - full[k].expr();
+                    cb.require_zero(
+                        "byte beyond the operand size is zero",
+                        byte * not::expr(full[k].expr() + is_partial * in_partial.expr()),
+                    );
+                }
+            }
+
+            cb.gate(
+                meta.query_fixed(q_value, Rotation::cur())
+                    * meta.query_advice(is_head, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("modexp mulmod", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let [a, b, n, q, r, d, borrows, carries_lo, carries_hi] = [
+                MULMOD_A,
+                MULMOD_B,
+                MULMOD_N,
+                MULMOD_Q,
+                MULMOD_R,
+                MULMOD_D,
+                MULMOD_LT,
+                MULMOD_CARRY_LO,
+                MULMOD_CARRY_HI,
+            ]
+            .map(|row| query_row(meta, &limbs, row as i32));
+            for (values, row, n_parts) in [
+                (&q, MULMOD_Q, N_LIMB_PARTS),
+                (&r, MULMOD_R, N_LIMB_PARTS),
+                (&d, MULMOD_D, N_LIMB_PARTS),
+                (&carries_lo, MULMOD_CARRY_LO, N_CARRY_PARTS),
+                (&carries_hi, MULMOD_CARRY_HI, N_CARRY_PARTS),
+            ] {
+                for (value, composed) in values
+                    .iter()
+                    .zip(query_u16_parts(meta, &limbs, row as i32, n_parts))
+                {
+                    cb.require_equal("value is composed of its u16 parts", value.expr(), composed);
+                }
+            }
+
+            let two_64 = Expression::Constant(pow_of_two::<F>(LIMB_BITS));
+            let carries = carries_lo
+                .iter()
+                .chain(carries_hi.iter())
+                .map(|carry| carry.expr() - Expression::Constant(F::from_u128(CARRY_OFFSET)))
+                .collect::<Vec<_>>();
+            for k in 0..2 * n_limbs {
+                let mut column = if k == 0 {
+                    0.expr()
+                } else {
+                    carries[k - 1].expr()
+                };
+                for i in k.saturating_sub(n_limbs - 1)..=k.min(n_limbs - 1) {
+                    column = column + a[i].expr() * b[k - i].expr() - q[i].expr() * n[k - i].expr();
+                }
+                if k < n_limbs {
+                    column = column - r[k].expr();
+                }
+                cb.require_equal(
+                    "a * b = q * n + r in each limb column",
+                    column,
+                    carries[k].expr() * two_64.expr(),
+                );
+            }
+            cb.require_zero(
+                "no carry out of the last limb column",
+                carries[2 * n_limbs - 1].expr(),
+            );
+
+            for k in 0..n_limbs {
+                let borrow_in = if k == 0 {
+                    1.expr()
+                } else {
+                    borrows[k - 1].expr()
+                };
+                cb.require_boolean("borrow is boolean", borrows[k].expr());
+                cb.require_equal(
+                    "r + d + 1 = n in each limb",
+                    r[k].expr() + d[k].expr() + borrow_in,
+                    n[k].expr() + borrows[k].expr() * two_64.expr(),
+                );
+            }
+            cb.require_zero("r < n", borrows[n_limbs - 1].expr());
+
+            let is_head = meta.query_advice(is_head, Rotation::cur());
+            let is_step = meta.query_advice(is_step, Rotation::cur());
+            cb.gate(
+                meta.query_fixed(q_square, Rotation::cur()) * is_step.expr()
+                    + meta.query_fixed(q_multiply, Rotation::cur()) * (is_step + is_head),
+            )
+        });
+
+        for &col in limbs.iter() {
+            meta.lookup("modexp u16 part", |meta| {
+                let q_u16 = meta.query_fixed(q_u16, Rotation::cur());
+                vec![(
+                    q_u16 * meta.query_advice(col, Rotation::cur()),
+                    u16_table.into(),
+                )]
+            });
+            meta.lookup("modexp byte", |meta| {
+                let condition = meta.query_fixed(q_u8, Rotation::cur())
+                    * (meta.query_advice(is_head, Rotation::cur())
+                        + meta.query_advice(is_tail, Rotation::cur()));
+                vec![(
+                    condition * meta.query_advice(col, Rotation::cur()),
+                    u8_table.into(),
+                )]
+            });
+        }
+        meta.lookup("modexp bit_idx < 64", |meta| {
+            let condition = meta.query_fixed(q_block, Rotation::cur())
+                * meta.query_advice(is_step, Rotation::cur());
+            let bit_idx = meta.query_advice(bit_idx, Rotation::cur());
+            vec![(condition * bit_idx * 4.expr(), u8_table.into())]
+        });
+
         Self {
-            rangecheck_config,
-            modexp_config,
+            q_block,
+            q_first,
+            q_value,
+            q_square,
+            q_multiply,
+            q_u8,
+            q_u16,
+            is_head,
+            is_skip,
+            is_step,
+            is_tail,
+            limbs,
+            rlc,
+            byte_len,
+            bit,
+            exp_acc,
+            started,
+            bit_idx,
+            limb_end,
+            limbs_done,
+            modulus_is_zero,
+            modulus_inv,
             modexp_table,
         }
     }
 }
 
-impl ModExpCircuitConfig {
-    pub(crate) fn assign_group<F: Field>(
-        &self,
-        region: &mut Region<F>,
-        table_offset: usize,
-        mut calc_offset: usize,
-        event: &BigModExp,
-        modexp_chip: &ModExpChip<F>,
-        range_check_chip: &mut RangeCheckChip<F>,
-    ) -> Result<usize, Error> {
-        let base = self.assign_value(region, table_offset, self.modexp_table.base, &event.base)?;
-        let exp =
-            self.assign_value(region, table_offset, self.modexp_table.exp, &event.exponent)?;
-        let modulus = self.assign_value(
-            region,
-            table_offset,
-            self.modexp_table.modulus,
-            &event.modulus,
-        )?;
-        let ret = modexp_chip.mod_exp(
-            region,
-            range_check_chip,
-            &mut calc_offset,
-            &base,
-            &exp,
-            &modulus,
-        )?;
-        for i in 0..4 {
-            region.assign_fixed(
-                || format!("modexp table head {}", table_offset + i),
-                self.modexp_table.q_head,
-                table_offset + i,
-                || Value::known(if i == 0 { F::one() } else { F::zero() }),
-            )?;
+impl<const SIZE_LIMIT: usize> ModExpCircuitConfig<SIZE_LIMIT> {
+    /// Number of the 64-bit limbs of an operand
+    const N_LIMBS: usize = SIZE_LIMIT / N_BYTES_PER_LIMB;
+
+    fn assign_fixed<F: Field>(&self, region: &mut Region<F>, n_blocks: usize) -> Result<(), Error> {
+        let is_byte_row = |offset: usize| {
+            VALUE_ROWS
+                .iter()
+                .any(|&row| (row + 1..=row + N_BYTES_PER_LIMB).contains(&offset))
+        };
+        let is_part_row = |offset: usize| {
+            [SQUARE_MULMOD, MULTIPLY_MULMOD].into_iter().any(|mulmod| {
+                offset.checked_sub(mulmod).map_or(false, |offset| {
+                    [
+                        (MULMOD_Q, N_LIMB_PARTS),
+                        (MULMOD_R, N_LIMB_PARTS),
+                        (MULMOD_D, N_LIMB_PARTS),
+                        (MULMOD_CARRY_LO, N_CARRY_PARTS),
+                        (MULMOD_CARRY_HI, N_CARRY_PARTS),
+                    ]
+                    .into_iter()
+                    .any(|(row, n_parts)| (row + 1..=row + n_parts).contains(&offset))
+                })
+            })
+        };
 
-            ret.limbs[i]
-                .cell
-                .clone()
-                .expect("should has assigned after modexp")
-                .copy_advice(
-                    || "copy to result limbs",
-                    region,
-                    self.modexp_table.result,
-                    table_offset + i,
+        for row in 0..n_blocks * BLOCK_ROWS {
+            let offset = row % BLOCK_ROWS;
+            for (col, enabled) in [
+                (self.modexp_table.q_enable, true),
+                (self.q_block, offset == 0),
+                (self.q_first, row == 0),
+                (self.q_value, VALUE_ROWS.contains(&offset)),
+                (self.q_square, offset == SQUARE_MULMOD),
+                (self.q_multiply, offset == MULTIPLY_MULMOD),
+                (self.q_u8, is_byte_row(offset)),
+                (self.q_u16, is_part_row(offset)),
+            ] {
+                region.assign_fixed(
+                    || format!("modexp circuit selector {row}"),
+                    col,
+                    row,
+                    || Value::known(F::from(enabled as u64)),
                 )?;
+            }
         }
-        Ok(calc_offset)
+
+        Ok(())
     }
 
-    fn assign_value<F: Field>(
+    /// Assign a block at `offset`, with the table values of its event: the RLCs of the base,
+    /// exponent, modulus and result, and the sizes of the base, exponent and modulus.
+    fn assign_block<F: Field>(
         &self,
         region: &mut Region<F>,
         offset: usize,
-        col: Column<Advice>,
-        value: &Word,
-    ) -> Result<Number<F>, Error> {
-        let limbs_v = ModExpTable::split_u256_108bit_limbs(value);
-        let native_v = ModExpTable::native_u256(value);
-        let mut limbs = Vec::new();
-
-        for (i, limb) in limbs_v.into_iter().enumerate() {
-            let fv = F::from_u128(limb);
-            let c = region.assign_advice(
-                || "assign modexp limb",
-                col,
-                offset + i,
-                || Value::known(fv),
+        block: &BlockAssignment,
+        table_values: &[Value<F>; 7],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let modulus_inv = block.rows[MODULUS_ROW]
+            .iter()
+            .fold(F::zero(), |acc, &limb| acc + F::from_u128(limb))
+            .invert()
+            .unwrap_or(F::zero());
+
+        for (i, limbs) in block.rows.iter().enumerate() {
+            let row = offset + i;
+            let is_first = i == 0;
+
+            for (col, kind) in [
+                (self.is_head, BlockKind::Head),
+                (self.is_skip, BlockKind::Skip),
+                (self.is_step, BlockKind::Step),
+                (self.is_tail, BlockKind::Tail),
+            ] {
+                region.assign_advice(
+                    || format!("modexp block kind {row}"),
+                    col,
+                    row,
+                    || Value::known(F::from((block.kind == kind) as u64)),
+                )?;
+            }
+            for (&col, &limb) in self.limbs.iter().zip(limbs.iter()) {
+                region.assign_advice(
+                    || format!("modexp limb {row}"),
+                    col,
+                    row,
+                    || Value::known(F::from_u128(limb)),
+                )?;
+            }
+            let rlc = block
+                .rlc_values
+                .iter()
+                .find(|(value_row, _)| *value_row == i)
+                .map_or(Value::known(F::zero()), |(_, value)| {
+                    ModExpTable::<SIZE_LIMIT>::value_rlc(value, challenges)
+                });
+            region.assign_advice(|| format!("modexp rlc {row}"), self.rlc, row, || rlc)?;
+            region.assign_advice(
+                || format!("modexp byte_len {row}"),
+                self.byte_len,
+                row,
+                || Value::known(F::from(block.byte_len[i])),
             )?;
-            limbs.push(Limb::new(Some(c), fv));
+
+            for (col, value) in [
+                (self.bit, block.bit),
+                (self.exp_acc, block.exp_acc),
+                (self.started, block.started),
+                (self.bit_idx, block.bit_idx),
+                (self.limb_end, block.limb_end),
+                (self.limbs_done, block.limbs_done),
+                (self.modulus_is_zero, block.modulus_is_zero),
+                (self.modexp_table.exp_bit_len, block.exp_bit_len),
+                (
+                    self.modexp_table.q_head,
+                    (block.kind == BlockKind::Tail) as u64,
+                ),
+            ] {
+                let value = if is_first { value } else { 0 };
+                region.assign_advice(
+                    || format!("modexp block value {row}"),
+                    col,
+                    row,
+                    || Value::known(F::from(value)),
+                )?;
+            }
+            region.assign_advice(
+                || format!("modexp modulus_inv {row}"),
+                self.modulus_inv,
+                row,
+                || {
+                    Value::known(if is_first && block.kind == BlockKind::Head {
+                        modulus_inv
+                    } else {
+                        F::zero()
+                    })
+                },
+            )?;
+
+            let table_cols = [
+                self.modexp_table.base,
+                self.modexp_table.exp,
+                self.modexp_table.modulus,
+                self.modexp_table.result,
+                self.modexp_table.base_len,
+                self.modexp_table.exp_len,
+                self.modexp_table.modulus_len,
+            ];
+            for (idx, (col, value)) in table_cols.into_iter().zip(table_values).enumerate() {
+                let is_assigned = is_first
+                    && match block.kind {
+                        BlockKind::Padding => false,
+                        BlockKind::Tail => true,
+                        // the result is in the tail block only
+                        _ => idx != 3,
+                    };
+                region.assign_advice(
+                    || format!("modexp table {row}"),
+                    col,
+                    row,
+                    || {
+                        if is_assigned {
+                            *value
+                        } else {
+                            Value::known(F::zero())
+                        }
+                    },
+                )?;
+            }
         }
-        let c = region.assign_advice(
-            || "assign modexp native",
-            col,
-            offset + 3,
-            || Value::known(native_v),
-        )?;
-        limbs.push(Limb::new(Some(c), native_v));
-        Ok(Number {
-            limbs: limbs.try_into().expect("just 4 pushes"),
-        })
+
+        Ok(())
     }
 }
 
-const MODEXPCONFIG_EACH_CHIP_ROWS: usize = 39962;
-
-/// ModExp circuit for precompile modexp
+/// ModExp circuit for precompile modexp, with operands of up to `SIZE_LIMIT` bytes
 #[derive(Clone, Debug, Default)]
-pub struct ModExpCircuit<F: Field>(Vec<BigModExp>, std::marker::PhantomData<F>);
+pub struct ModExpCircuit<F: Field, const SIZE_LIMIT: usize = MODEXP_SIZE_LIMIT> {
+    events: Vec<BigModExp>,
+    /// Max number of rows, the blocks after the events are padding ones. The circuit has just
+    /// the rows of the events if it's zero.
+    max_rows: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field, const SIZE_LIMIT: usize> ModExpCircuit<F, SIZE_LIMIT> {
+    /// Return a new ModExpCircuit
+    pub fn new(events: Vec<BigModExp>, max_rows: usize) -> Self {
+        Self {
+            events,
+            max_rows,
+            _marker: PhantomData,
+        }
+    }
 
-impl<F: Field> SubCircuit<F> for ModExpCircuit<F> {
-    type Config = ModExpCircuitConfig;
+    /// Number of the rows taken by the events
+    pub fn min_num_rows(events: &[BigModExp]) -> usize {
+        let n_limbs = ModExpCircuitConfig::<SIZE_LIMIT>::N_LIMBS;
+        events
+            .iter()
+            .map(|event| n_event_blocks(event, n_limbs))
+            .sum::<usize>()
+            * BLOCK_ROWS
+    }
+
+    /// Minimum and total number of rows of the circuit for the events
+    pub(crate) fn min_num_rows_of(events: &[BigModExp], max_keccak_rows: usize) -> (usize, usize) {
+        let real_len = Self::min_num_rows(events);
+        (real_len, real_len.max(max_keccak_rows).max(4096))
+    }
+
+    fn num_blocks(&self) -> usize {
+        if self.max_rows == 0 {
+            Self::min_num_rows(&self.events) / BLOCK_ROWS
+        } else {
+            // a row is reserved for the blank row of the table
+            self.max_rows
+                .saturating_sub(<Self as SubCircuit<F>>::unusable_rows() + 1)
+                / BLOCK_ROWS
+        }
+    }
+}
+
+impl<F: Field, const SIZE_LIMIT: usize> SubCircuit<F> for ModExpCircuit<F, SIZE_LIMIT> {
+    type Config = ModExpCircuitConfig<SIZE_LIMIT>;
 
     fn unusable_rows() -> usize {
-        // No column queried at more than 4 distinct rotations, so returns 8 as
-        // minimum unusable rows.
-        8
+        // The limb columns are queried at 38 distinct rotations at
+        // - Rotation(-64), Rotation(-63), Rotation(-29), Rotation(-23): previous block
+        // - Rotation(0) to Rotation(30): value rows with their bytes, and multiplications
+        // - Rotation(33), Rotation(34), Rotation(35): multiplication of the block
+        // so returns 38 + 3 unusable rows.
+        41
     }
 
     fn new_from_block(block: &witness::Block<F>) -> Self {
-        let event_limit = block.circuits_params.max_keccak_rows / MODEXPCONFIG_EACH_CHIP_ROWS;
-
-        let mut exp_events = block.get_big_modexp();
-        if event_limit != 0 {
+        let circuit = Self::new(
+            block.get_big_modexp(),
+            block.circuits_params.max_keccak_rows,
+        );
+        if circuit.max_rows != 0 {
+            let rows = Self::min_num_rows(&circuit.events);
+            let max_rows = circuit.num_blocks() * BLOCK_ROWS;
             assert!(
-                exp_events.len() <= event_limit,
-                "no enough rows for modexp circuit, expected {}, limit {}",
-                exp_events.len(),
-                event_limit,
+                rows <= max_rows,
+                "no enough rows for modexp circuit, expected {rows}, limit {max_rows}",
             );
-            exp_events.resize(event_limit, Default::default());
-            log::info!("modexp circuit work with maxium {} entries", event_limit);
+            log::info!("modexp circuit work with maximum {max_rows} rows");
         }
 
-        Self(exp_events, Default::default())
+        circuit
     }
 
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
-        let exp_events = block.get_big_modexp();
-        let real_len = exp_events.len() * MODEXPCONFIG_EACH_CHIP_ROWS;
-        (
-            real_len,
-            real_len
-                .max(block.circuits_params.max_keccak_rows)
-                .max(4096),
+        Self::min_num_rows_of(
+            &block.get_big_modexp(),
+            block.circuits_params.max_keccak_rows,
         )
     }
 
     fn synthesize_sub(
         &self,
         config: &Self::Config,
-        _challenges: &Challenges<Value<F>>,
+        challenges: &Challenges<Value<F>>,
         layouter: &mut impl Layouter<F>,
     ) -> Result<(), Error> {
-        let modexp_chip = ModExpChip::new(config.modexp_config.clone());
-        let mut range_chip = RangeCheckChip::new(config.rangecheck_config.clone());
+        let n_blocks = self.num_blocks();
+        let n_limbs = ModExpCircuitConfig::<SIZE_LIMIT>::N_LIMBS;
 
         layouter.assign_region(
             || "modexp circuit",
             |mut region| {
-                range_chip.initialize(&mut region)?;
-                let _modexp_count = self.0.len();
-                let mut calc_offset = 0;
-                for (n, event) in self.0.iter().enumerate() {
-                    calc_offset = config.assign_group(
+                config.assign_fixed(&mut region, n_blocks)?;
+
+                let mut offset = 0;
+                for event in self.events.iter() {
+                    let [base, exp, modulus, result] =
+                        [&event.base, &event.exponent, &event.modulus, &event.result]
+                            .map(|value| ModExpTable::<SIZE_LIMIT>::value_rlc(value, challenges));
+                    let [base_len, exp_len, modulus_len] =
+                        [event.base_len, event.exponent_len, event.modulus_len]
+                            .map(|len| Value::known(F::from(len as u64)));
+                    let table_values = [base, exp, modulus, result, base_len, exp_len, modulus_len];
+                    for block in event_blocks(event, n_limbs) {
+                        config.assign_block(
+                            &mut region,
+                            offset,
+                            &block,
+                            &table_values,
+                            challenges,
+                        )?;
+                        offset += BLOCK_ROWS;
+                    }
+                }
+
+                let padding = BlockAssignment::new(BlockKind::Padding, n_limbs);
+                let padding_values = [(); 7].map(|_| Value::known(F::zero()));
+                while offset < n_blocks * BLOCK_ROWS {
+                    config.assign_block(
                         &mut region,
-                        n * 4,
-                        calc_offset,
-                        event,
-                        &modexp_chip,
-                        &mut range_chip,
+                        offset,
+                        &padding,
+                        &padding_values,
+                        challenges,
                     )?;
+                    offset += BLOCK_ROWS;
                 }
-                //assert_eq!(max(calc_offset, range_chip.offset), MODEXPCONFIG_EACH_CHIP_ROWS *
-                // modexp_count);
+
                 Ok(())
             },
         )?;
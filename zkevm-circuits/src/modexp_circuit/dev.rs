@@ -6,8 +6,13 @@ use halo2_proofs::{
     plonk::{Circuit, ConstraintSystem},
 };
 
-impl Circuit<Fr> for ModExpCircuit<Fr> {
-    type Config = (ModExpCircuitConfig, MockChallenges);
+impl<const SIZE_LIMIT: usize> Circuit<Fr> for ModExpCircuit<Fr, SIZE_LIMIT> {
+    type Config = (
+        ModExpCircuitConfig<SIZE_LIMIT>,
+        U8Table,
+        U16Table,
+        MockChallenges,
+    );
     type FloorPlanner = SimpleFloorPlanner;
     #[cfg(feature = "circuit-params")]
     type Params = ();
@@ -18,19 +23,34 @@ impl Circuit<Fr> for ModExpCircuit<Fr> {
 
     fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
         let modexp_table = ModExpTable::construct(meta);
+        let u8_table = U8Table::construct(meta);
+        let u16_table = U16Table::construct(meta);
         let challenge = MockChallenges::construct(meta);
+        let challenges = challenge.exprs(meta);
         (
-            <ModExpCircuitConfig as SubCircuitConfig<Fr>>::new(meta, modexp_table),
+            <ModExpCircuitConfig<SIZE_LIMIT> as SubCircuitConfig<Fr>>::new(
+                meta,
+                ModExpCircuitConfigArgs {
+                    modexp_table,
+                    u8_table,
+                    u16_table,
+                    challenges,
+                },
+            ),
+            u8_table,
+            u16_table,
             challenge,
         )
     }
 
     fn synthesize(
         &self,
-        (config, challenge): Self::Config,
+        (config, u8_table, u16_table, challenge): Self::Config,
         mut layouter: impl Layouter<Fr>,
     ) -> Result<(), Error> {
         let challenges = challenge.values(&layouter);
+        u8_table.load(&mut layouter)?;
+        u16_table.load(&mut layouter)?;
         <Self as SubCircuit<Fr>>::synthesize_sub(self, &config, &challenges, &mut layouter)
     }
 }
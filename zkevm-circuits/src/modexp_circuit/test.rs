@@ -1,32 +1,33 @@
 #![allow(unused_imports)]
 use super::*;
 
-use eth_types::U256;
-use halo2_proofs::dev::MockProver;
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
 
 #[test]
 fn test_modexp_circuit_00() {
-    let event1 = construct_modexp(Word::from(1u128), Word::from(3u128), Word::from(7u128));
+    let event1 = construct_modexp(1u8.into(), 3u8.into(), 7u8.into());
 
-    let test_circuit = ModExpCircuit(vec![event1], Default::default());
-    let prover = MockProver::run(16, &test_circuit, vec![]).unwrap();
+    let test_circuit = ModExpCircuit::<Fr>::new(vec![event1], 0);
+    let prover = MockProver::run(17, &test_circuit, vec![]).unwrap();
     assert_eq!(prover.verify(), Ok(()));
 }
 
 #[test]
 fn test_modexp_circuit_01() {
-    let event1 = construct_modexp(Word::from(1u128), Word::from(2u128), Word::from(7u128));
+    let event1 = construct_modexp(1u8.into(), 2u8.into(), 7u8.into());
 
-    let test_circuit = ModExpCircuit(vec![event1], Default::default());
-    let prover = MockProver::run(16, &test_circuit, vec![]).unwrap();
+    let test_circuit = ModExpCircuit::<Fr>::new(vec![event1], 0);
+    let prover = MockProver::run(17, &test_circuit, vec![]).unwrap();
     assert_eq!(prover.verify(), Ok(()));
 }
+
 #[test]
 fn test_modexp_circuit_02() {
-    let event1 = construct_modexp(Word::from(2u128), Word::from(2u128), Word::from(7u128));
-    let event2 = construct_modexp(Word::from(3u128), Word::from(21u128), Word::from(78u128));
+    let event1 = construct_modexp(2u8.into(), 2u8.into(), 7u8.into());
+    let event2 = construct_modexp(3u8.into(), 21u8.into(), 78u8.into());
 
-    let test_circuit = ModExpCircuit(vec![event1, event2], Default::default());
+    // padded to more blocks than the events
+    let test_circuit = ModExpCircuit::<Fr>::new(vec![event1, event2], 1 << 15);
     let prover = MockProver::run(17, &test_circuit, vec![]).unwrap();
     assert_eq!(prover.verify(), Ok(()));
 }
@@ -34,21 +35,131 @@ fn test_modexp_circuit_02() {
 // test all zeros case (exp == mod == base == 0)
 #[test]
 fn test_modexp_circuit_03() {
-    let event1 = construct_modexp(Word::from(0u128), Word::from(0u128), Word::from(0u128));
+    let event1 = construct_modexp(0u8.into(), 0u8.into(), 0u8.into());
+
+    let test_circuit = ModExpCircuit::<Fr>::new(vec![event1], 0);
+    let prover = MockProver::run(17, &test_circuit, vec![]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+// RSA verification with a 2048-bit modulus
+#[test]
+fn test_modexp_circuit_2048_bits() {
+    let modulus = (BigUint::one() << 2047u32) + 0x2b6du32;
+    let base = &modulus - 0x1234_5678u32;
+    let event1 = construct_modexp(base, 65537u32.into(), modulus);
 
-    let test_circuit = ModExpCircuit(vec![event1], Default::default());
-    let prover = MockProver::run(16, &test_circuit, vec![]).unwrap();
+    let test_circuit = ModExpCircuit::<Fr>::new(vec![event1], 0);
+    let prover = MockProver::run(17, &test_circuit, vec![]).unwrap();
     assert_eq!(prover.verify(), Ok(()));
 }
 
-fn construct_modexp(base: U256, exp: U256, modulus: U256) -> BigModExp {
-    let result = if modulus == Word::zero() {
-        Word::zero()
+// the exponent has more than one limb
+#[test]
+fn test_modexp_circuit_long_exponent() {
+    let event1 = construct_modexp(
+        3u8.into(),
+        (BigUint::one() << 100u32) + 5u8,
+        (BigUint::one() << 255u32) - 19u8,
+    );
+    assert_eq!(
+        ModExpCircuit::<Fr>::min_num_rows(&[event1.clone()]),
+        160 * BLOCK_ROWS
+    );
+
+    let test_circuit = ModExpCircuit::<Fr>::new(vec![event1], 0);
+    let prover = MockProver::run(17, &test_circuit, vec![]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+// an operand exceeding its size in the call data can't be looked up
+#[test]
+fn test_modexp_circuit_operand_exceeds_size() {
+    let mut event1 = construct_modexp(3u8.into(), 5u8.into(), 0x1_0007u32.into());
+    event1.modulus_len = 2;
+
+    let test_circuit = ModExpCircuit::<Fr>::new(vec![event1], 0);
+    let prover = MockProver::run(17, &test_circuit, vec![]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+// the operands at the size limit, with leading zero bytes
+#[test]
+fn test_modexp_circuit_size_limit() {
+    let mut event1 = construct_modexp(
+        (BigUint::one() << 2040u32) + 7u8,
+        3u8.into(),
+        (BigUint::one() << 2047u32) + 0x2b6du32,
+    );
+    event1.base_len = MODEXP_SIZE_LIMIT;
+    event1.exponent_len = MODEXP_SIZE_LIMIT;
+    event1.modulus_len = MODEXP_SIZE_LIMIT;
+
+    let test_circuit = ModExpCircuit::<Fr>::new(vec![event1], 0);
+    let prover = MockProver::run(17, &test_circuit, vec![]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+// an operand over the size limit is truncated to the limbs, which don't match the table
+#[test]
+fn test_modexp_circuit_over_size_limit() {
+    let event1 = construct_modexp(
+        3u8.into(),
+        5u8.into(),
+        (BigUint::one() << 2048u32) + 0x2b6du32,
+    );
+    assert_eq!(event1.modulus_len, MODEXP_SIZE_LIMIT + 1);
+
+    let test_circuit = ModExpCircuit::<Fr>::new(vec![event1], 0);
+    let prover = MockProver::run(17, &test_circuit, vec![]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn test_modexp_circuit_wrong_result() {
+    let mut event1 = construct_modexp(3u8.into(), 5u8.into(), 7u8.into());
+    event1.result += 1u8;
+
+    let test_circuit = ModExpCircuit::<Fr>::new(vec![event1], 0);
+    let prover = MockProver::run(17, &test_circuit, vec![]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn test_modexp_circuit_configured_size_limit() {
+    let event1 = construct_modexp(
+        3u8.into(),
+        (BigUint::one() << 100u32) + 5u8,
+        (BigUint::one() << 255u32) - 19u8,
+    );
+    assert_eq!(
+        ModExpCircuit::<Fr, 32>::min_num_rows(&[event1.clone()]),
+        132 * BLOCK_ROWS
+    );
+
+    let test_circuit = ModExpCircuit::<Fr, 32>::new(vec![event1], 0);
+    let prover = MockProver::run(17, &test_circuit, vec![]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    // a 33-byte modulus is over the limit
+    let event2 = construct_modexp(3u8.into(), 5u8.into(), (BigUint::one() << 256u32) + 7u8);
+    let test_circuit = ModExpCircuit::<Fr, 32>::new(vec![event2], 0);
+    let prover = MockProver::run(17, &test_circuit, vec![]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+fn construct_modexp(base: BigUint, exp: BigUint, modulus: BigUint) -> BigModExp {
+    let result = if modulus.is_zero() {
+        BigUint::zero()
     } else {
-        base.pow(exp).div_mod(modulus).1
+        base.modpow(&exp, &modulus)
     };
 
+    let size = |value: &BigUint| (value.bits() as usize).div_ceil(8);
     BigModExp {
+        base_len: size(&base),
+        exponent_len: size(&exp),
+        modulus_len: size(&modulus),
         base,
         exponent: exp,
         modulus,
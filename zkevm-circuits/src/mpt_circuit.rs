@@ -36,6 +36,10 @@ impl PoseidonLookup for PoseidonTable {
     }
 }
 
+/// For an empty storage proof, we may need to lookup the canonical representations of three
+/// different keys. Each lookup requires 32 rows.
+const ROWS_PER_MPT_UPDATE: usize = 3 * 32;
+
 /// Circuit wrapped with mpt table data
 #[derive(Clone, Debug, Default)]
 pub struct MptCircuit<F: Field> {
@@ -87,6 +91,18 @@ impl SubCircuitConfig<Fr> for MptCircuitConfig<Fr> {
     }
 }
 
+impl MptCircuit<Fr> {
+    /// Minimum and total number of rows of the circuit for `num_updates` mpt updates.
+    pub(crate) fn min_num_rows(num_updates: usize, max_mpt_rows: usize) -> (usize, usize) {
+        (
+            // The key bit lookup within the mpt circuit requires a minimum of 8 * 256 rows. The +1
+            // comes from the fact that the mpt circuit starts assigning at offset = 1.
+            ROWS_PER_MPT_UPDATE * num_updates,
+            max_mpt_rows.max(8 * 256 + 1),
+        )
+    }
+}
+
 #[cfg(any(feature = "test", test))]
 impl SubCircuit<Fr> for MptCircuit<Fr> {
     type Config = MptCircuitConfig<Fr>;
@@ -119,14 +135,7 @@ impl SubCircuit<Fr> for MptCircuit<Fr> {
     }
 
     fn min_num_rows_block(block: &witness::Block<Fr>) -> (usize, usize) {
-        (
-            // For an empty storage proof, we may need to lookup the canonical representations of
-            // three different keys. Each lookup requires 32 rows.
-            // The key bit lookup within the mpt circuit requires a minimum of 8 * 256 rows. The +1
-            // comes from the fact that the mpt circuit starts assigning at offset = 1.
-            3 * 32 * block.mpt_updates.len(),
-            block.circuits_params.max_mpt_rows.max(8 * 256 + 1),
-        )
+        Self::min_num_rows(block.mpt_updates.len(), block.circuits_params.max_mpt_rows)
     }
 
     /// Make the assignments to the MptCircuit, notice it fill mpt table
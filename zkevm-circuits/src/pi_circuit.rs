@@ -4,19 +4,22 @@
 /// Defines PiTestCircuit
 pub mod dev;
 mod param;
+mod receipt;
 #[cfg(any(feature = "test", test, feature = "test-circuits"))]
 mod test;
 
 use std::{cell::RefCell, collections::BTreeMap, iter, marker::PhantomData, str::FromStr};
 
 use crate::{evm_circuit::util::constraint_builder::ConstrainBuilderCommon, table::KeccakTable};
-use bus_mapping::circuit_input_builder::get_dummy_tx_hash;
+use bus_mapping::circuit_input_builder::{
+    get_dummy_tx_hash, receipts_data_bytes, CircuitsParams, N_BYTES_RECEIPTS_DATA,
+};
 use eth_types::{Address, Field, Hash, ToBigEndian, ToWord, Word, H256};
 use ethers_core::utils::keccak256;
 use halo2_proofs::plonk::{Assigned, Expression, Fixed, Instance};
 
 use crate::{
-    table::{BlockTable, LookupTable, TxTable},
+    table::{BlockTable, LookupTable, RlpFsmRlpTable, RwTable, TxTable, U8Table},
     util::{Challenges, SubCircuit, SubCircuitConfig},
 };
 #[cfg(feature = "onephase")]
@@ -28,12 +31,12 @@ use crate::{
     evm_circuit::{util::constraint_builder::BaseConstraintBuilder, EvmCircuitExports},
     pi_circuit::param::{
         BASE_FEE_OFFSET, BLOCK_HEADER_BYTES_NUM, BLOCK_LEN, BLOCK_NUM_OFFSET, BYTE_POW_BASE,
-        CHAIN_ID_OFFSET, GAS_LIMIT_OFFSET, KECCAK_DIGEST_SIZE, RPI_CELL_IDX,
+        CHAIN_ID_OFFSET, CUM_NUM_TXS_OFFSET, GAS_LIMIT_OFFSET, KECCAK_DIGEST_SIZE, RPI_CELL_IDX,
         RPI_LENGTH_ACC_CELL_IDX, RPI_RLC_ACC_CELL_IDX, TIMESTAMP_OFFSET,
     },
     state_circuit::StateCircuitExports,
     tx_circuit::{CHAIN_ID_OFFSET as CHAIN_ID_OFFSET_IN_TX, TX_HASH_OFFSET, TX_LEN},
-    witness::{self, Block, BlockContext, BlockContexts, Transaction},
+    witness::{self, Block, BlockContext, BlockContexts, Receipt, Rw, Transaction},
 };
 use bus_mapping::util::read_env_var;
 use gadgets::util::{and, not, select, Expr};
@@ -42,6 +45,9 @@ use halo2_proofs::{
     plonk::{Advice, Column, ConstraintSystem, Error, Selector},
     poly::Rotation,
 };
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+use receipt::ReceiptsColumn;
+use receipt::{ReceiptsCells, ReceiptsConfig};
 
 use crate::{
     evm_circuit::param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_U64, N_BYTES_WORD},
@@ -95,6 +101,10 @@ pub struct PublicData {
     pub max_calldata: usize,
     /// Max number of supported inner blocks in a chunk
     pub max_inner_blocks: usize,
+    /// TxReceipt and TxLog rws of the txs, sorted by key as in the rw table
+    pub receipt_rws: Vec<Rw>,
+    /// Id of the first receipt in the RLP table
+    pub first_receipt_rlp_id: u64,
 }
 
 impl PublicData {
@@ -136,6 +146,11 @@ impl PublicData {
         num_all_txs_in_blocks
     }
 
+    /// The receipts of the txs, from their TxReceipt and TxLog rws.
+    fn receipts(&self) -> Vec<Receipt> {
+        witness::receipts_from_rws(&self.transactions, &self.receipt_rws)
+    }
+
     /// Compute the bytes for dataHash from the verifier's perspective.
     fn data_bytes(&self) -> Vec<u8> {
         log::debug!(
@@ -180,12 +195,15 @@ impl PublicData {
                     .iter()
                     .flat_map(|tx| tx.hash.to_fixed_bytes()),
             )
+            // Receipts root and logs bloom
+            .chain(receipts_data_bytes(&self.receipts()))
             .collect::<Vec<u8>>();
 
         assert_eq!(
             result.len(),
             BLOCK_HEADER_BYTES_NUM * self.block_ctxs.ctxs.len()
                 + KECCAK_DIGEST_SIZE * self.transactions.len()
+                + N_BYTES_RECEIPTS_DATA
         );
         result
     }
@@ -256,6 +274,14 @@ impl PublicData {
         self.q_tx_hashes_start_offset() + KECCAK_DIGEST_SIZE * self.max_txs
     }
 
+    fn q_receipts_start_offset(&self) -> usize {
+        self.q_tx_hashes_end_offset()
+    }
+
+    fn q_receipts_end_offset(&self) -> usize {
+        self.q_receipts_start_offset() + N_BYTES_RECEIPTS_DATA
+    }
+
     fn data_bytes_start_offset(&self) -> usize {
         // we start assigning data bytes at offset == 0.
         0
@@ -265,6 +291,7 @@ impl PublicData {
         self.data_bytes_start_offset()
             + self.max_inner_blocks * BLOCK_HEADER_BYTES_NUM
             + self.max_txs * KECCAK_DIGEST_SIZE
+            + N_BYTES_RECEIPTS_DATA
     }
 
     fn pi_bytes_start_offset(&self) -> usize {
@@ -349,6 +376,8 @@ pub struct PiCircuitConfig<F: Field> {
     real_rpi: Column<Advice>,
     q_tx_hashes: Column<Fixed>,
     q_block_context: Column<Fixed>,
+    // receipts root and logs bloom
+    q_receipts: Column<Fixed>,
 
     // columns for assertion about cum_num_txs in block table
     cum_num_txs: Column<Advice>,
@@ -370,6 +399,12 @@ pub struct PiCircuitConfig<F: Field> {
     block_table: BlockTable,
     tx_table: TxTable,
     keccak_table: KeccakTable,
+    rw_table: RwTable,
+    rlp_table: RlpFsmRlpTable,
+    u8_table: U8Table,
+
+    // receipts root and logs bloom from the rw table
+    receipts: ReceiptsConfig<F>,
 
     _marker: PhantomData<F>,
 }
@@ -382,6 +417,12 @@ pub struct PiCircuitConfigArgs<F: Field> {
     pub block_table: BlockTable,
     /// Keccak Table
     pub keccak_table: KeccakTable,
+    /// RwTable
+    pub rw_table: RwTable,
+    /// RlpFsmRlpTable
+    pub rlp_table: RlpFsmRlpTable,
+    /// U8Table
+    pub u8_table: U8Table,
     /// Challenges
     pub challenges: Challenges<Expression<F>>,
 }
@@ -396,6 +437,9 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             block_table,
             tx_table,
             keccak_table,
+            rw_table,
+            rlp_table,
+            u8_table,
             challenges,
         }: Self::ConfigArgs,
     ) -> Self {
@@ -438,6 +482,7 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
 
         let q_block_context = meta.fixed_column();
         let q_tx_hashes = meta.fixed_column();
+        let q_receipts = meta.fixed_column();
 
         let q_not_end = meta.complex_selector();
         // We are accumulating bytes for three different purposes
@@ -599,6 +644,17 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             cb.gate(meta.query_fixed(q_tx_hashes, Rotation::cur()))
         });
 
+        meta.create_gate("receipts root and logs bloom", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_zero(
+                "receipts root and logs bloom are not padding",
+                meta.query_advice(is_rpi_padding, Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(q_receipts, Rotation::cur()))
+        });
+
         // We reuse the layout for rpi to compute the keccak output.
         // The 32 bytes of keccak output are combined into (hi, lo)
         //  where r = challenges.evm_word().
@@ -689,10 +745,24 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             }
         );
 
+        let receipts = ReceiptsConfig::configure(
+            meta,
+            rw_table,
+            &tx_table,
+            rlp_table,
+            &keccak_table,
+            u8_table,
+            &challenges,
+        );
+
         Self {
             block_table,
             tx_table,
             keccak_table,
+            rw_table,
+            rlp_table,
+            u8_table,
+            receipts,
             constant,
             raw_public_inputs: rpi,
             rpi_field_bytes: rpi_bytes,
@@ -702,6 +772,7 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             is_rpi_padding,
             real_rpi,
             q_tx_hashes,
+            q_receipts,
             q_field_step,
             is_field_rlc,
             q_not_end,
@@ -754,6 +825,10 @@ impl<F: Field> PiCircuitConfig<F> {
     /// |          | ...                    |                          |
     /// |          | DUMMY_TX_HASH          |                          |
     /// |          |------------------------|--------------------------|
+    /// |          | receipts_root          | <- q_receipts == 1       |
+    /// |          | logs_bloom             | <- q_receipts == 1       |
+    /// |          | (receipts-root feature)|                          |
+    /// |          |------------------------|--------------------------|
     /// |          | rlc(data_bytes)        | <- q_keccak == 1         |
     /// |----------|------------------------|--------------------------|
     /// |          | rpi initialise         |                          |
@@ -791,6 +866,7 @@ impl<F: Field> PiCircuitConfig<F> {
         public_data: &PublicData,
         block_value_cells: &[AssignedCell<F, F>],
         tx_value_cells: &[AssignedCell<F, F>],
+        receipts_cells: &ReceiptsCells<F>,
         challenges: &Challenges<Value<F>>,
     ) -> Result<(PiHashExport<F>, Connections<F>), Error> {
         // 1. Assign data bytes.
@@ -800,6 +876,7 @@ impl<F: Field> PiCircuitConfig<F> {
             public_data,
             block_value_cells,
             tx_value_cells,
+            receipts_cells,
             challenges,
         )?;
         debug_assert_eq!(offset, public_data.pi_bytes_start_offset());
@@ -831,6 +908,7 @@ impl<F: Field> PiCircuitConfig<F> {
 
     /// Assign data bytes, that represent the pre-image to data_hash.
     /// i.e. keccak256(rlc(data_bytes)) == data_hash.
+    #[allow(clippy::too_many_arguments)]
     fn assign_data_bytes(
         &self,
         region: &mut Region<'_, F>,
@@ -838,6 +916,7 @@ impl<F: Field> PiCircuitConfig<F> {
         public_data: &PublicData,
         block_value_cells: &[AssignedCell<F, F>],
         tx_value_cells: &[AssignedCell<F, F>],
+        receipts_cells: &ReceiptsCells<F>,
         challenges: &Challenges<Value<F>>,
     ) -> Result<(usize, AssignedCell<F, F>), Error> {
         // Initialise the RLC accumulator and length values.
@@ -864,6 +943,15 @@ impl<F: Field> PiCircuitConfig<F> {
                 || Value::known(F::one()),
             )?;
         }
+        // Enable fixed columns for receipts root and logs bloom.
+        for q_offset in public_data.q_receipts_start_offset()..public_data.q_receipts_end_offset() {
+            region.assign_fixed(
+                || "q_receipts",
+                self.q_receipts,
+                q_offset,
+                || Value::known(F::one()),
+            )?;
+        }
         // Enable RLC accumulator consistency check throughout the above rows.
         for q_offset in public_data.data_bytes_start_offset()..public_data.data_bytes_end_offset() {
             self.q_not_end.enable(region, q_offset)?;
@@ -987,6 +1075,44 @@ impl<F: Field> PiCircuitConfig<F> {
             )?;
         }
 
+        // Assign receipts root and logs bloom values, part of the data bytes
+        // with the `receipts-root` feature only.
+        let receipts_data_bytes = receipts_data_bytes(&public_data.receipts());
+        let (root, bloom) =
+            receipts_data_bytes.split_at(receipts_data_bytes.len().min(KECCAK_DIGEST_SIZE));
+        let mut receipts_copy_cells = vec![];
+        for value_be_bytes in [root, bloom].into_iter().filter(|bytes| !bytes.is_empty()) {
+            let (tmp_offset, tmp_rpi_rlc_acc, tmp_rpi_length, cells) = self.assign_field(
+                region,
+                offset,
+                value_be_bytes,
+                RpiFieldType::DefaultType,
+                false,
+                rpi_rlc_acc,
+                rpi_length,
+                challenges,
+            )?;
+            offset = tmp_offset;
+            rpi_rlc_acc = tmp_rpi_rlc_acc;
+            rpi_length = tmp_rpi_length;
+            receipts_copy_cells.push(cells[RPI_CELL_IDX].clone());
+            data_bytes_rlc = Some(cells[RPI_RLC_ACC_CELL_IDX].clone());
+            data_bytes_length = Some(cells[RPI_LENGTH_ACC_CELL_IDX].clone());
+        }
+        // Copy receipts root and logs bloom from the receipts section
+        for (cell, receipts_cell) in receipts_copy_cells
+            .into_iter()
+            .zip([&receipts_cells.root, &receipts_cells.bloom])
+        {
+            region.constrain_equal(cell.cell(), receipts_cell.cell())?;
+        }
+        // The receipts are the ones of the txs of the chunk
+        region.constrain_equal(
+            receipts_cells.num_txs.cell(),
+            block_value_cells[(public_data.max_inner_blocks - 1) * BLOCK_LEN + CUM_NUM_TXS_OFFSET]
+                .cell(),
+        )?;
+
         // Assign row for validating lookup to check:
         // data_hash == keccak256(rlc(data_bytes))
         data_bytes_rlc.unwrap().copy_advice(
@@ -1617,6 +1743,8 @@ pub struct PiCircuit<F: Field> {
 
     connections: RefCell<Option<Connections<F>>>,
     tx_value_cells: RefCell<Option<Vec<AssignedCell<F, F>>>>,
+    #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+    overrides: std::collections::HashMap<(ReceiptsColumn, usize), F>,
 }
 
 impl<F: Field> PiCircuit<F> {
@@ -1652,6 +1780,8 @@ impl<F: Field> PiCircuit<F> {
             prev_state_root: H256(block.mpt_updates.old_root().to_be_bytes()),
             next_state_root,
             withdraw_trie_root: H256(block.withdraw_root.to_be_bytes()),
+            receipt_rws: block.receipt_rws(),
+            first_receipt_rlp_id: block.first_receipt_rlp_id(),
         };
 
         Self {
@@ -1659,6 +1789,8 @@ impl<F: Field> PiCircuit<F> {
             _marker: PhantomData,
             connections: Default::default(),
             tx_value_cells: RefCell::new(None),
+            #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+            overrides: Default::default(),
         }
     }
 
@@ -1725,28 +1857,16 @@ impl<F: Field> PiCircuit<F> {
             },
         )
     }
-}
 
-impl<F: Field> SubCircuit<F> for PiCircuit<F> {
-    type Config = PiCircuitConfig<F>;
-
-    fn new_from_block(block: &Block<F>) -> Self {
-        PiCircuit::new(
-            block.circuits_params.max_txs,
-            block.circuits_params.max_calldata,
-            block.circuits_params.max_inner_blocks,
-            block,
-        )
-    }
-
-    /// Return the minimum number of rows required to prove the block
-    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
-        let tx_usage = block.txs.len() as f32 / block.circuits_params.max_txs as f32;
-        let max_inner_blocks = block.circuits_params.max_inner_blocks;
-        let max_txs = block.circuits_params.max_txs;
+    /// Minimum and total number of rows of the circuit for a block of `num_txs` txs.
+    pub(crate) fn min_num_rows(num_txs: usize, circuits_params: &CircuitsParams) -> (usize, usize) {
+        let tx_usage = num_txs as f32 / circuits_params.max_txs as f32;
+        let max_inner_blocks = circuits_params.max_inner_blocks;
+        let max_txs = circuits_params.max_txs;
 
         let num_rows = 1 + max_inner_blocks * BLOCK_HEADER_BYTES_NUM
             + max_txs * KECCAK_DIGEST_SIZE
+            + N_BYTES_RECEIPTS_DATA // receipts root & logs bloom
             + 1 // for data hash row
             + 1 // for pi bytes start row
             + N_BYTES_U64 // chain_id
@@ -1757,12 +1877,35 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
             + 1 // for coinbase & difficulty start row
             + N_BYTES_ACCOUNT_ADDRESS
             + N_BYTES_WORD;
+        // the receipts section is in its own columns
+        let num_rows = num_rows.max(ReceiptsConfig::<F>::min_num_rows(
+            max_txs,
+            circuits_params.max_calldata,
+        ));
 
         (
-            (tx_usage * block.circuits_params.max_vertical_circuit_rows as f32).ceil() as usize,
+            (tx_usage * circuits_params.max_vertical_circuit_rows as f32).ceil() as usize,
             num_rows,
         )
     }
+}
+
+impl<F: Field> SubCircuit<F> for PiCircuit<F> {
+    type Config = PiCircuitConfig<F>;
+
+    fn new_from_block(block: &Block<F>) -> Self {
+        PiCircuit::new(
+            block.circuits_params.max_txs,
+            block.circuits_params.max_calldata,
+            block.circuits_params.max_inner_blocks,
+            block,
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        Self::min_num_rows(block.txs.len(), &block.circuits_params)
+    }
 
     /// Compute the public inputs for this circuit.
     fn instance(&self) -> Vec<Vec<F>> {
@@ -1787,6 +1930,13 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
         challenges: &Challenges<Value<F>>,
         layouter: &mut impl Layouter<F>,
     ) -> Result<(), Error> {
+        let receipts_cells = config.receipts.assign(
+            layouter,
+            &self.public_data,
+            #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+            &self.overrides,
+            challenges,
+        )?;
         let pi_cells = layouter.assign_region(
             || "pi region",
             |mut region| {
@@ -1808,6 +1958,7 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                     &self.public_data,
                     &block_value_cells,
                     &tx_value_cells,
+                    &receipts_cells,
                     challenges,
                 )?;
 
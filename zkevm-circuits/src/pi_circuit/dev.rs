@@ -1,4 +1,5 @@
 use super::*;
+use bus_mapping::circuit_input_builder::keccak_inputs_receipts;
 
 // We define the PiTestCircuit as a wrapper over PiCircuit extended to take the
 // generic const parameters MAX_TXS and MAX_CALLDATA.  This is necessary because
@@ -31,9 +32,12 @@ impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MAX_INNER_
                 next_state_root: H256::zero(),
                 withdraw_trie_root: H256::zero(),
                 block_ctxs: Default::default(),
+                receipt_rws: vec![],
+                first_receipt_rlp_id: MAX_TXS as u64 + 1,
             },
             connections: Default::default(),
             tx_value_cells: Default::default(),
+            overrides: Default::default(),
             _marker: PhantomData,
         })
     }
@@ -89,6 +93,9 @@ impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MAX_INNER_
         let block_table = BlockTable::construct(meta);
         let tx_table = TxTable::construct(meta);
         let keccak_table = KeccakTable::construct(meta);
+        let rw_table = RwTable::construct(meta);
+        let rlp_table = RlpFsmRlpTable::construct(meta);
+        let u8_table = U8Table::construct(meta);
         let challenges = Challenges::construct(meta);
         let challenge_exprs = challenges.exprs(meta);
         (
@@ -98,6 +105,9 @@ impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MAX_INNER_
                     block_table,
                     keccak_table,
                     tx_table,
+                    rw_table,
+                    rlp_table,
+                    u8_table,
                     challenges: challenge_exprs,
                 },
             ),
@@ -105,10 +115,21 @@ impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MAX_INNER_
         )
     }
 
-    fn synthesize(
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+        self.synthesize_with_rws(config, layouter, &self.0.public_data.receipt_rws)
+    }
+}
+
+impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MAX_INNER_BLOCKS: usize>
+    PiTestCircuit<F, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>
+{
+    /// Synthesize the circuit with `rws` in the rw table, which otherwise
+    /// holds the receipt rws of the public data.
+    pub(crate) fn synthesize_with_rws(
         &self,
-        (config, challenges): Self::Config,
+        (config, challenges): (PiCircuitConfig<F>, Challenges),
         mut layouter: impl Layouter<F>,
+        rws: &[Rw],
     ) -> Result<(), Error> {
         let challenges = challenges.values(&layouter);
 
@@ -121,15 +142,28 @@ impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MAX_INNER_
             self.0.public_data.chain_id,
             &challenges,
         )?;
-        // assign keccak table
-        let data_bytes = self.0.public_data.data_bytes();
-        let pi_bytes = self
-            .0
-            .public_data
-            .pi_bytes(self.0.public_data.get_data_hash());
+        // assign rw table
+        let public_data = &self.0.public_data;
         config
-            .keccak_table
-            .dev_load(&mut layouter, vec![&data_bytes, &pi_bytes], &challenges)?;
+            .rw_table
+            .load(&mut layouter, rws, rws.len() + 1, challenges.evm_word())?;
+        // assign RLP table
+        let receipts = public_data.receipts();
+        config.rlp_table.dev_load(
+            &mut layouter,
+            witness::receipt_rlps(&receipts, public_data.first_receipt_rlp_id),
+            &challenges,
+        )?;
+        config.u8_table.load(&mut layouter)?;
+        // assign keccak table
+        let data_bytes = public_data.data_bytes();
+        let pi_bytes = public_data.pi_bytes(public_data.get_data_hash());
+        let receipts_inputs = keccak_inputs_receipts(&receipts);
+        config.keccak_table.dev_load(
+            &mut layouter,
+            [&data_bytes, &pi_bytes].into_iter().chain(&receipts_inputs),
+            &challenges,
+        )?;
 
         self.0.import_tx_values(tx_value_cells);
         self.0.synthesize_sub(&config, &challenges, &mut layouter)?;
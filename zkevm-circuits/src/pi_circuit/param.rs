@@ -19,5 +19,5 @@ pub(super) const DIFFICULTY_OFFSET: usize = 3;
 pub(super) const GAS_LIMIT_OFFSET: usize = 4;
 pub(super) const BASE_FEE_OFFSET: usize = 5;
 pub(super) const CHAIN_ID_OFFSET: usize = 6;
-// pub(super) const CUM_NUM_TXS_OFFSET: usize = 8;
+pub(super) const CUM_NUM_TXS_OFFSET: usize = 8;
 pub(super) const NUM_ALL_TXS_OFFSET: usize = 9;
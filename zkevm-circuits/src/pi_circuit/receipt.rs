@@ -0,0 +1,3238 @@
+//! The receipts section of the PI circuit proves the root of the receipts trie
+//! and the logs bloom of the chunk from the TxReceipt and TxLog entries of the
+//! rw table. It is laid out in four regions:
+//!
+//! - the receipt rows, one per tx, look up the status, the cumulative gas and the number of logs of
+//!   the receipt in the rw table and its encoding in the RLP table, and derive the length of the
+//!   encoding from the one of its logs;
+//! - the log rows walk the address, topics and data of each log, looking them up in the rw table
+//!   and in the RLP table. The addresses and topics are hashed in the keccak table, 8 bytes of
+//!   their hash per row, and set the 3 bits of the bloom given by the first 6 bytes of their hash;
+//! - the bloom rows hold the 2048 bits of the bloom of the chunk followed by the ones of the bloom
+//!   of each tx. A bit is set iff an address or a topic of the logs of the tx, or of the chunk,
+//!   sets it;
+//! - the trie rows hold the nodes of the receipts trie, which are decoded by the RLP circuit and
+//!   hashed in the keccak table. Each node is linked to the child of its parent at the same key
+//!   prefix, and the leaves hold exactly the receipts at their keys.
+//!
+//! The rw table being sorted by key, the row following the last topic, or the
+//! last data byte, of a log in the rw table is not a further one: the logs hold
+//! all their topics and data. The trie is the canonical one of the receipts:
+//! its branches have at least 2 children and no value, and its extensions have
+//! a non empty path and a branch as child.
+
+use super::PublicData;
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        pow_of_two, pow_of_two_expr, rlc,
+    },
+    table::{
+        KeccakTable, LookupTable, RlpFsmRlpTable, RwTable, RwTableTag, TxContextFieldTag,
+        TxLogFieldTag, TxReceiptFieldTag, TxTable, U8Table,
+    },
+    util::{build_tx_log_expression, rlc_be_bytes, Challenges},
+    witness::{
+        empty_trie_root,
+        eth_mpt::{hp_encode, TrieNode},
+        logs_bloom, Format, Receipt, ReceiptLog, ReceiptsTrie, RlpTag, Rw, Tag,
+    },
+};
+use bus_mapping::circuit_input_builder::receipt_key;
+use eth_types::{geth_types::TxType, Field, ToLittleEndian, ToScalar, ToWord, H256};
+use ethers_core::{
+    types::Bloom,
+    utils::{keccak256, rlp::RlpStream},
+};
+use gadgets::{
+    is_zero::{IsZeroChip, IsZeroConfig, IsZeroInstruction},
+    util::{not, sum, Expr},
+};
+#[cfg(feature = "onephase")]
+use halo2_proofs::plonk::FirstPhase as SecondPhase;
+#[cfg(not(feature = "onephase"))]
+use halo2_proofs::plonk::SecondPhase;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, TableColumn, VirtualCells,
+    },
+    poly::Rotation,
+};
+use itertools::Itertools;
+use std::{
+    collections::{HashMap, HashSet},
+    iter,
+};
+use strum::IntoEnumIterator;
+
+/// Number of bits of a bloom.
+const N_BLOOM_BITS: usize = 2048;
+
+/// Number of bytes of the RLP encoding of the status and the bloom of a
+/// receipt.
+const N_BYTES_STATUS_AND_BLOOM: u64 = 1 + 3 + 256;
+
+/// Rows of an address or a topic of a log, each holding 8 bytes of its hash.
+const ROWS_PER_ITEM: usize = 4;
+const N_HASH_BYTES_PER_ROW: usize = 32 / ROWS_PER_ITEM;
+
+/// The flags of the log rows: the first row of an address or a topic, followed
+/// by the 3 rows of the rest of its hash, and a byte of the data.
+const IS_ADDRESS: usize = 0;
+const IS_TOPIC: usize = 1;
+const IS_HASH_1: usize = 2;
+const IS_HASH_2: usize = 3;
+const IS_HASH_3: usize = 4;
+const IS_DATA: usize = 5;
+
+/// Rows of a node of the receipts trie: the node followed by its 17 items.
+const ROWS_PER_NODE: usize = 18;
+
+/// Maximum number of bytes of the hex-prefix encoded path of an extension or a
+/// leaf, as the keys of the receipts trie have at most 6 nibbles.
+const MAX_PATH_BYTES: usize = 4;
+
+/// The number of bytes of the header of an RLP string or list from the length
+/// of its payload, below 2^24: a single byte up to 55 bytes, and one more than
+/// the bytes of the length otherwise.
+#[derive(Clone, Copy, Debug)]
+struct HeaderLenGadget {
+    /// Little endian bytes of the length of the payload
+    bytes: [Column<Advice>; 3],
+    /// Whether the header is a single byte, or has 1, 2 or 3 bytes of length
+    is_len_bytes: [Column<Advice>; 4],
+    /// Inverse of the most significant byte of the length
+    msb_inv: Column<Advice>,
+}
+
+impl HeaderLenGadget {
+    fn configure<F: Field>(
+        meta: &mut ConstraintSystem<F>,
+        name: &'static str,
+        enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F> + Copy,
+        len: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        u8_table: U8Table,
+    ) -> Self {
+        let bytes = [(); 3].map(|_| meta.advice_column());
+        let is_len_bytes = [(); 4].map(|_| meta.advice_column());
+        let msb_inv = meta.advice_column();
+
+        meta.create_gate(name, |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let [b0, b1, b2] = bytes.map(|column| meta.query_advice(column, Rotation::cur()));
+            let [s0, s1, s2, s3] =
+                is_len_bytes.map(|column| meta.query_advice(column, Rotation::cur()));
+            for s in [&s0, &s1, &s2, &s3] {
+                cb.require_boolean("header size flag is boolean", s.expr());
+            }
+            cb.require_equal(
+                "header has a single size",
+                s0.expr() + s1.expr() + s2.expr() + s3.expr(),
+                1.expr(),
+            );
+            cb.require_equal(
+                "length is its bytes",
+                len(meta),
+                b0 + b1.expr() * 256.expr() + b2.expr() * 65536.expr(),
+            );
+            cb.require_zero(
+                "length has at most 1 byte",
+                (s0 + s1) * (b1.expr() + b2.expr()),
+            );
+            cb.require_zero("length has at most 2 bytes", s2.expr() * b2.expr());
+            cb.require_equal(
+                "most significant byte of the length is not zero",
+                (s2.expr() * b1 + s3.expr() * b2) * meta.query_advice(msb_inv, Rotation::cur()),
+                s2 + s3,
+            );
+
+            cb.gate(enable(meta))
+        });
+        for column in bytes {
+            meta.lookup(name, |meta| {
+                vec![(
+                    enable(meta) * meta.query_advice(column, Rotation::cur()),
+                    u8_table.into(),
+                )]
+            });
+        }
+        meta.lookup(name, |meta| {
+            let b0 = meta.query_advice(bytes[0], Rotation::cur());
+            let s0 = meta.query_advice(is_len_bytes[0], Rotation::cur());
+            let s1 = meta.query_advice(is_len_bytes[1], Rotation::cur());
+            // a length up to 55 has no length bytes, and a single byte length
+            // is above 55
+            vec![(
+                enable(meta) * (s0 * (55.expr() - b0.expr()) + s1 * (b0 - 56.expr())),
+                u8_table.into(),
+            )]
+        });
+
+        Self {
+            bytes,
+            is_len_bytes,
+            msb_inv,
+        }
+    }
+
+    /// The number of bytes of the header.
+    fn expr<F: Field>(&self, meta: &mut VirtualCells<'_, F>) -> Expression<F> {
+        sum::expr(
+            self.is_len_bytes
+                .iter()
+                .zip(1u64..)
+                .map(|(column, size)| meta.query_advice(*column, Rotation::cur()) * size.expr()),
+        )
+    }
+
+    fn assign<F: Field>(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        len: usize,
+    ) -> Result<(), Error> {
+        assert!(len < 1 << 24, "RLP payload of {len} bytes");
+        let bytes = [len & 0xff, (len >> 8) & 0xff, len >> 16];
+        let n_len_bytes = header_len(len) - 1;
+        let msb_inv = match n_len_bytes {
+            2 | 3 => F::from(bytes[n_len_bytes - 1] as u64).invert().unwrap(),
+            _ => F::zero(),
+        };
+
+        for (column, value) in self.bytes.into_iter().zip_eq(bytes) {
+            region.assign_advice(
+                || "rlp header length byte",
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+        for (i, column) in self.is_len_bytes.into_iter().enumerate() {
+            region.assign_advice(
+                || "rlp header size",
+                column,
+                offset,
+                || Value::known(F::from((i == n_len_bytes) as u64)),
+            )?;
+        }
+        region.assign_advice(
+            || "rlp header length msb inverse",
+            self.msb_inv,
+            offset,
+            || Value::known(msb_inv),
+        )?;
+        Ok(())
+    }
+}
+
+/// The number of bytes of the header of an RLP string or list with a payload
+/// of `len` bytes.
+fn header_len(len: usize) -> usize {
+    match len {
+        0..=55 => 1,
+        56..=0xff => 2,
+        0x100..=0xffff => 3,
+        _ => 4,
+    }
+}
+
+/// Columns of the receipt rows, one per tx.
+#[derive(Clone, Debug)]
+struct ReceiptColumns<F> {
+    q_enable: Column<Fixed>,
+    q_last: Column<Fixed>,
+    tx_id: Column<Fixed>,
+    /// The key of the receipt in the trie, as an integer, and its number of
+    /// nibbles
+    key: Column<Fixed>,
+    key_len: Column<Fixed>,
+    /// Number of txs from this one to the last one, the first row holding the
+    /// number of txs of the chunk
+    remaining: Column<Advice>,
+    remaining_is_zero: IsZeroConfig<F>,
+    is_real: Column<Advice>,
+    /// Id of the receipt in the RLP table
+    rlp_id: Column<Advice>,
+    tx_type: Column<Advice>,
+    /// EIP-2718 type of the tx, prefixing the receipt if not zero
+    type_byte: Column<Advice>,
+    type_byte_is_zero: IsZeroConfig<F>,
+    is_typed: Column<Advice>,
+    status: Column<Advice>,
+    gas: Column<Advice>,
+    gas_is_zero: IsZeroConfig<F>,
+    gas_bytes_rlc: Column<Advice>,
+    gas_len: Column<Advice>,
+    gas_len_is_one: IsZeroConfig<F>,
+    /// Whether the cumulative gas is a single byte below 0x80, its own
+    /// encoding
+    gas_is_small: Column<Advice>,
+    log_len: Column<Advice>,
+    log_len_is_zero: IsZeroConfig<F>,
+    has_logs: Column<Advice>,
+    /// Length of the RLP encoding of the logs, without their list header
+    logs_len: Column<Advice>,
+    logs_header: HeaderLenGadget,
+    payload_header: HeaderLenGadget,
+    bloom_rlc: Column<Advice>,
+    /// RLC and length of the encoding of the receipt
+    rlc: Column<Advice>,
+    len: Column<Advice>,
+    /// Rw counters of the status, the cumulative gas and the number of logs
+    rw_counters: [Column<Advice>; 3],
+}
+
+/// Columns of the log rows, walking the logs of all the receipts.
+#[derive(Clone, Debug)]
+struct LogColumns<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_last: Column<Fixed>,
+    /// One flag per kind of row, all of them unset on the padding rows
+    flags: [Column<Advice>; 6],
+    is_log_end: Column<Advice>,
+    /// Whether the row ends the last log of the tx
+    is_tx_end: Column<Advice>,
+    tx_id: Column<Advice>,
+    log_id: Column<Advice>,
+    /// Id and format of the receipt in the RLP table
+    rlp_id: Column<Advice>,
+    format: Column<Advice>,
+    /// The address, the word RLC of the topic, or the byte of the data
+    value: Column<Advice>,
+    rw_counter: Column<Advice>,
+    /// Tag, id and address of the row following the item in the rw table
+    next_rw: [Column<Advice>; 3],
+    /// Inverses of the differences between the key of that row and the ones
+    /// of the next topic and of the next data byte of the log
+    next_topic_inv: Column<Advice>,
+    next_data_inv: Column<Advice>,
+    /// Keccak input RLC of the address or the topic
+    item_rlc: Column<Advice>,
+    hash_bytes: [Column<Advice>; N_HASH_BYTES_PER_ROW],
+    /// Word RLC of the bytes of the hash up to the row
+    hash_acc: Column<Advice>,
+    /// The index of the bloom bit set by the row is `256 * low3 + lo`, from
+    /// the bytes `8 * q + low3` and `lo` of the hash
+    bloom_q: Column<Advice>,
+    bloom_low3: Column<Advice>,
+    bloom_lo: Column<Advice>,
+    topic_cnt: Column<Advice>,
+    data_cnt: Column<Advice>,
+    data_rlc: Column<Advice>,
+    /// Whether the data byte is below 0x80
+    is_small: Column<Advice>,
+    data_is_zero: IsZeroConfig<F>,
+    data_is_one: IsZeroConfig<F>,
+    data_header: HeaderLenGadget,
+    /// Whether the topics are more than one, so that their list header has a
+    /// length byte
+    topics_long: Column<Advice>,
+    log_header: HeaderLenGadget,
+    /// Length of the RLP encoding of the log, set on its last row
+    log_enc: Column<Advice>,
+    /// Length of the RLP encoding of the previous logs of the tx
+    logs_acc: Column<Advice>,
+}
+
+/// Columns of the bloom rows, one per bit of each bloom.
+#[derive(Clone, Copy, Debug)]
+struct BloomColumns {
+    q_enable: Column<Fixed>,
+    /// 0 for the bloom of the chunk, and the tx id for the bloom of a tx
+    bloom_id: Column<Fixed>,
+    /// Index of the bit, the first row holding the most significant one
+    bit_idx: Column<Fixed>,
+    q_byte_first: Column<Fixed>,
+    q_byte_last: Column<Fixed>,
+    q_bloom_first: Column<Fixed>,
+    q_bloom_last: Column<Fixed>,
+    q_tx_bloom: Column<Fixed>,
+    q_chunk_bloom: Column<Fixed>,
+    bit: Column<Advice>,
+    /// The bits of the byte up to the row
+    byte_acc: Column<Advice>,
+    /// Keccak input RLC and word RLC of the bytes of the bloom up to the
+    /// previous byte, or up to the byte of the row on its last bit
+    rlc: Column<Advice>,
+    word_rlc: Column<Advice>,
+}
+
+/// Columns of the trie rows, each node spanning [`ROWS_PER_NODE`] rows: the
+/// node followed by its items.
+#[derive(Clone, Debug)]
+struct TrieColumns<F> {
+    q_node: Column<Fixed>,
+    q_child: Column<Fixed>,
+    q_root: Column<Fixed>,
+    q_first_child: Column<Fixed>,
+    /// The 17th item of a branch, its value
+    q_value_slot: Column<Fixed>,
+    nibble: Column<Fixed>,
+    is_branch: Column<Advice>,
+    is_extension: Column<Advice>,
+    is_leaf: Column<Advice>,
+    /// Id of the node in the RLP table
+    rlp_id: Column<Advice>,
+    /// Word RLC of the hash of the node
+    hash: Column<Advice>,
+    rlc: Column<Advice>,
+    len: Column<Advice>,
+    len_bytes: Column<Advice>,
+    /// The key prefix of the node, as an integer, and its number of nibbles
+    prefix: Column<Advice>,
+    prefix_len: Column<Advice>,
+    /// Hex-prefix encoded path of an extension or a leaf
+    path: [Column<Advice>; MAX_PATH_BYTES],
+    path_flag: Column<Advice>,
+    first_nibble: Column<Advice>,
+    is_odd: Column<Advice>,
+    /// One flag per number of bytes of the path
+    path_sel: [Column<Advice>; MAX_PATH_BYTES],
+    path_rlc: Column<Advice>,
+    /// The nibbles of the path, as an integer, their number, and 16 to the
+    /// power of their number
+    path_value: Column<Advice>,
+    path_nibbles: Column<Advice>,
+    path_pow: Column<Advice>,
+    /// The key prefix followed by the path
+    key: Column<Advice>,
+    key_len: Column<Advice>,
+    /// Value of a leaf
+    value_rlc: Column<Advice>,
+    value_len: Column<Advice>,
+    /// The root of the trie, and the number of txs which it is empty without
+    root: Column<Advice>,
+    num_txs: Column<Advice>,
+    num_txs_is_zero: IsZeroConfig<F>,
+    /// Word RLC and keccak input RLC of the hash of the child
+    child_hash: Column<Advice>,
+    child_bytes_rlc: Column<Advice>,
+    child_none: Column<Advice>,
+    /// Whether the item is a child node
+    child_active: Column<Advice>,
+    /// Whether the child is a branch
+    child_is_branch: Column<Advice>,
+    /// Number of child nodes of the node up to the item
+    child_cnt: Column<Advice>,
+    /// The key prefix of the child
+    child_prefix: Column<Advice>,
+    child_prefix_len: Column<Advice>,
+}
+
+/// Config of the receipts section of the PI circuit.
+#[derive(Clone, Debug)]
+pub(super) struct ReceiptsConfig<F> {
+    receipt: ReceiptColumns<F>,
+    log: LogColumns<F>,
+    bloom: BloomColumns,
+    trie: TrieColumns<F>,
+    /// The EIP-2718 type of each tx type
+    tx_types: [TableColumn; 2],
+}
+
+/// Cells of the receipts section shared with the rest of the PI circuit.
+pub(super) struct ReceiptsCells<F: Field> {
+    /// Number of txs of the chunk
+    pub(super) num_txs: AssignedCell<F, F>,
+    /// Word RLC of the receipts root
+    pub(super) root: AssignedCell<F, F>,
+    /// Word RLC of the logs bloom of the chunk
+    pub(super) bloom: AssignedCell<F, F>,
+}
+
+/// Advice columns of the receipts section whose cells can be overridden to
+/// test that a tampered witness is rejected, at an offset in their region.
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+#[derive(Hash, Eq, PartialEq, Clone, Copy, Debug)]
+pub(super) enum ReceiptsColumn {
+    /// The address, topic or data byte of a log row
+    LogValue,
+    /// A bit of a bloom, the chunk one followed by the ones of the txs
+    BloomBit,
+    /// The keccak input RLC of a trie node, the root being the first one
+    NodeRlc,
+}
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+impl ReceiptsColumn {
+    fn value<F: Field>(&self, config: &ReceiptsConfig<F>) -> Column<Advice> {
+        match self {
+            Self::LogValue => config.log.value,
+            Self::BloomBit => config.bloom.bit,
+            Self::NodeRlc => config.trie.rlc,
+        }
+    }
+}
+
+impl<F: Field> ReceiptsConfig<F> {
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn configure(
+        meta: &mut ConstraintSystem<F>,
+        rw_table: RwTable,
+        tx_table: &TxTable,
+        rlp_table: RlpFsmRlpTable,
+        keccak_table: &KeccakTable,
+        u8_table: U8Table,
+        challenges: &Challenges<Expression<F>>,
+    ) -> Self {
+        let keccak_rand = challenges.keccak_input();
+        let word_rand = challenges.evm_word();
+
+        let tx_types = [(); 2].map(|_| meta.lookup_table_column());
+
+        // receipt rows
+        let q_receipt = meta.fixed_column();
+        let rc_remaining = meta.advice_column();
+        let rc_remaining_inv = meta.advice_column();
+        let rc_remaining_is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_fixed(q_receipt, Rotation::cur()),
+            |meta| meta.query_advice(rc_remaining, Rotation::cur()),
+            rc_remaining_inv,
+        );
+        let rc_type_byte = meta.advice_column();
+        let rc_type_byte_inv = meta.advice_column();
+        let rc_type_byte_is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_fixed(q_receipt, Rotation::cur()),
+            |meta| meta.query_advice(rc_type_byte, Rotation::cur()),
+            rc_type_byte_inv,
+        );
+        let rc_log_len = meta.advice_column();
+        let rc_log_len_inv = meta.advice_column();
+        let rc_log_len_is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_fixed(q_receipt, Rotation::cur()),
+            |meta| meta.query_advice(rc_log_len, Rotation::cur()),
+            rc_log_len_inv,
+        );
+        let rc_gas = meta.advice_column();
+        let rc_gas_inv = meta.advice_column();
+        let rc_gas_is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_fixed(q_receipt, Rotation::cur()),
+            |meta| meta.query_advice(rc_gas, Rotation::cur()),
+            rc_gas_inv,
+        );
+        let rc_gas_len = meta.advice_column();
+        let rc_gas_len_is_one_inv = meta.advice_column();
+        let rc_gas_len_is_one = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_fixed(q_receipt, Rotation::cur()),
+            |meta| meta.query_advice(rc_gas_len, Rotation::cur()) - 1.expr(),
+            rc_gas_len_is_one_inv,
+        );
+        let rc_gas_is_small = meta.advice_column();
+        // the encoding of the cumulative gas has a header unless it is a
+        // single byte below 0x80
+        let gas_enc_len = move |meta: &mut VirtualCells<'_, F>| {
+            meta.query_advice(rc_gas_len, Rotation::cur()) + 1.expr()
+                - meta.query_advice(rc_gas_is_small, Rotation::cur())
+        };
+        let rc_logs_len = meta.advice_column();
+        let rc_logs_header = HeaderLenGadget::configure(
+            meta,
+            "pi receipts: header of the logs",
+            |meta| meta.query_fixed(q_receipt, Rotation::cur()),
+            |meta| meta.query_advice(rc_logs_len, Rotation::cur()),
+            u8_table,
+        );
+        let rc_payload_header = HeaderLenGadget::configure(
+            meta,
+            "pi receipts: header of the receipt",
+            |meta| meta.query_fixed(q_receipt, Rotation::cur()),
+            |meta| {
+                N_BYTES_STATUS_AND_BLOOM.expr()
+                    + gas_enc_len(meta)
+                    + rc_logs_header.expr(meta)
+                    + meta.query_advice(rc_logs_len, Rotation::cur())
+            },
+            u8_table,
+        );
+        let receipt = ReceiptColumns {
+            q_enable: q_receipt,
+            q_last: meta.fixed_column(),
+            tx_id: meta.fixed_column(),
+            key: meta.fixed_column(),
+            key_len: meta.fixed_column(),
+            remaining: rc_remaining,
+            remaining_is_zero: rc_remaining_is_zero,
+            is_real: meta.advice_column(),
+            rlp_id: meta.advice_column(),
+            tx_type: meta.advice_column(),
+            type_byte: rc_type_byte,
+            type_byte_is_zero: rc_type_byte_is_zero,
+            is_typed: meta.advice_column(),
+            status: meta.advice_column(),
+            gas: rc_gas,
+            gas_is_zero: rc_gas_is_zero,
+            gas_bytes_rlc: meta.advice_column_in(SecondPhase),
+            gas_len: rc_gas_len,
+            gas_len_is_one: rc_gas_len_is_one,
+            gas_is_small: rc_gas_is_small,
+            log_len: rc_log_len,
+            log_len_is_zero: rc_log_len_is_zero,
+            has_logs: meta.advice_column(),
+            logs_len: rc_logs_len,
+            logs_header: rc_logs_header,
+            payload_header: rc_payload_header,
+            bloom_rlc: meta.advice_column_in(SecondPhase),
+            rlc: meta.advice_column_in(SecondPhase),
+            len: meta.advice_column(),
+            rw_counters: [(); 3].map(|_| meta.advice_column()),
+        };
+        meta.enable_equality(receipt.remaining);
+
+        // log rows
+        let q_log = meta.fixed_column();
+        let lc_flags = [(); 6].map(|_| meta.advice_column());
+        let lc_is_log_end = meta.advice_column();
+        let log_end = move |meta: &mut VirtualCells<'_, F>| {
+            meta.query_fixed(q_log, Rotation::cur())
+                * meta.query_advice(lc_is_log_end, Rotation::cur())
+        };
+        let lc_data_cnt = meta.advice_column();
+        let lc_data_is_zero_inv = meta.advice_column();
+        let lc_data_is_zero = IsZeroChip::configure(
+            meta,
+            log_end,
+            |meta| meta.query_advice(lc_data_cnt, Rotation::cur()),
+            lc_data_is_zero_inv,
+        );
+        let lc_data_is_one_inv = meta.advice_column();
+        let lc_data_is_one = IsZeroChip::configure(
+            meta,
+            log_end,
+            |meta| meta.query_advice(lc_data_cnt, Rotation::cur()) - 1.expr(),
+            lc_data_is_one_inv,
+        );
+        let lc_data_header = HeaderLenGadget::configure(
+            meta,
+            "pi receipts: header of the data of a log",
+            log_end,
+            |meta| meta.query_advice(lc_data_cnt, Rotation::cur()),
+            u8_table,
+        );
+        let lc_topic_cnt = meta.advice_column();
+        let lc_topics_long = meta.advice_column();
+        let lc_is_small = meta.advice_column();
+        // the length of the encoding of a log without its list header: the
+        // address, the topics and the data, a single data byte below 0x80
+        // being its own encoding
+        let log_payload_len = {
+            let data_is_one = lc_data_is_one.clone();
+            move |meta: &mut VirtualCells<'_, F>| {
+                let topic_cnt = meta.query_advice(lc_topic_cnt, Rotation::cur());
+                let data_cnt = meta.query_advice(lc_data_cnt, Rotation::cur());
+                21.expr()
+                    + 1.expr()
+                    + topic_cnt * 33.expr()
+                    + meta.query_advice(lc_topics_long, Rotation::cur())
+                    + data_cnt
+                    + lc_data_header.expr(meta)
+                    - data_is_one.expr() * meta.query_advice(lc_is_small, Rotation::cur())
+            }
+        };
+        let lc_log_header = HeaderLenGadget::configure(
+            meta,
+            "pi receipts: header of a log",
+            log_end,
+            log_payload_len.clone(),
+            u8_table,
+        );
+        let log = LogColumns {
+            q_enable: q_log,
+            q_first: meta.fixed_column(),
+            q_last: meta.fixed_column(),
+            flags: lc_flags,
+            is_log_end: lc_is_log_end,
+            is_tx_end: meta.advice_column(),
+            tx_id: meta.advice_column(),
+            log_id: meta.advice_column(),
+            rlp_id: meta.advice_column(),
+            format: meta.advice_column(),
+            value: meta.advice_column_in(SecondPhase),
+            rw_counter: meta.advice_column(),
+            next_rw: [(); 3].map(|_| meta.advice_column()),
+            next_topic_inv: meta.advice_column(),
+            next_data_inv: meta.advice_column(),
+            item_rlc: meta.advice_column_in(SecondPhase),
+            hash_bytes: [(); N_HASH_BYTES_PER_ROW].map(|_| meta.advice_column()),
+            hash_acc: meta.advice_column_in(SecondPhase),
+            bloom_q: meta.advice_column(),
+            bloom_low3: meta.advice_column(),
+            bloom_lo: meta.advice_column(),
+            topic_cnt: lc_topic_cnt,
+            data_cnt: lc_data_cnt,
+            data_rlc: meta.advice_column_in(SecondPhase),
+            is_small: lc_is_small,
+            data_is_zero: lc_data_is_zero,
+            data_is_one: lc_data_is_one,
+            data_header: lc_data_header,
+            topics_long: lc_topics_long,
+            log_header: lc_log_header,
+            log_enc: meta.advice_column(),
+            logs_acc: meta.advice_column(),
+        };
+
+        // bloom rows
+        let bloom = BloomColumns {
+            q_enable: meta.fixed_column(),
+            bloom_id: meta.fixed_column(),
+            bit_idx: meta.fixed_column(),
+            q_byte_first: meta.fixed_column(),
+            q_byte_last: meta.fixed_column(),
+            q_bloom_first: meta.fixed_column(),
+            q_bloom_last: meta.fixed_column(),
+            q_tx_bloom: meta.fixed_column(),
+            q_chunk_bloom: meta.fixed_column(),
+            bit: meta.advice_column(),
+            byte_acc: meta.advice_column(),
+            rlc: meta.advice_column_in(SecondPhase),
+            word_rlc: meta.advice_column_in(SecondPhase),
+        };
+        meta.enable_equality(bloom.word_rlc);
+
+        // trie rows
+        let q_root = meta.fixed_column();
+        let tc_num_txs = meta.advice_column();
+        let tc_num_txs_inv = meta.advice_column();
+        let tc_num_txs_is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_fixed(q_root, Rotation::cur()),
+            |meta| meta.query_advice(tc_num_txs, Rotation::cur()),
+            tc_num_txs_inv,
+        );
+        let trie = TrieColumns {
+            q_node: meta.fixed_column(),
+            q_child: meta.fixed_column(),
+            q_root,
+            q_first_child: meta.fixed_column(),
+            q_value_slot: meta.fixed_column(),
+            nibble: meta.fixed_column(),
+            is_branch: meta.advice_column(),
+            is_extension: meta.advice_column(),
+            is_leaf: meta.advice_column(),
+            rlp_id: meta.advice_column(),
+            hash: meta.advice_column_in(SecondPhase),
+            rlc: meta.advice_column_in(SecondPhase),
+            len: meta.advice_column(),
+            len_bytes: meta.advice_column(),
+            prefix: meta.advice_column(),
+            prefix_len: meta.advice_column(),
+            path: [(); MAX_PATH_BYTES].map(|_| meta.advice_column()),
+            path_flag: meta.advice_column(),
+            first_nibble: meta.advice_column(),
+            is_odd: meta.advice_column(),
+            path_sel: [(); MAX_PATH_BYTES].map(|_| meta.advice_column()),
+            path_rlc: meta.advice_column_in(SecondPhase),
+            path_value: meta.advice_column(),
+            path_nibbles: meta.advice_column(),
+            path_pow: meta.advice_column(),
+            key: meta.advice_column(),
+            key_len: meta.advice_column(),
+            value_rlc: meta.advice_column_in(SecondPhase),
+            value_len: meta.advice_column(),
+            root: meta.advice_column_in(SecondPhase),
+            num_txs: tc_num_txs,
+            num_txs_is_zero: tc_num_txs_is_zero,
+            child_hash: meta.advice_column_in(SecondPhase),
+            child_bytes_rlc: meta.advice_column_in(SecondPhase),
+            child_none: meta.advice_column(),
+            child_active: meta.advice_column(),
+            child_is_branch: meta.advice_column(),
+            child_cnt: meta.advice_column(),
+            child_prefix: meta.advice_column(),
+            child_prefix_len: meta.advice_column(),
+        };
+        meta.enable_equality(trie.root);
+        meta.enable_equality(trie.num_txs);
+
+        Self::configure_receipts(
+            meta,
+            &receipt,
+            &log,
+            &bloom,
+            &trie,
+            gas_enc_len,
+            tx_types,
+            rw_table,
+            tx_table,
+            rlp_table,
+            u8_table,
+        );
+        Self::configure_logs(
+            meta,
+            &receipt,
+            &log,
+            &bloom,
+            log_payload_len,
+            rw_table,
+            rlp_table,
+            keccak_table,
+            u8_table,
+            &keccak_rand,
+            &word_rand,
+        );
+        Self::configure_blooms(meta, &log, &bloom, &keccak_rand, &word_rand);
+        Self::configure_trie(
+            meta,
+            &receipt,
+            &trie,
+            rlp_table,
+            keccak_table,
+            u8_table,
+            &keccak_rand,
+            &word_rand,
+        );
+
+        Self {
+            receipt,
+            log,
+            bloom,
+            trie,
+            tx_types,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn configure_receipts(
+        meta: &mut ConstraintSystem<F>,
+        receipt: &ReceiptColumns<F>,
+        log: &LogColumns<F>,
+        bloom: &BloomColumns,
+        trie: &TrieColumns<F>,
+        gas_enc_len: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        tx_types: [TableColumn; 2],
+        rw_table: RwTable,
+        tx_table: &TxTable,
+        rlp_table: RlpFsmRlpTable,
+        u8_table: U8Table,
+    ) {
+        let rc = receipt.clone();
+        let q_real = move |meta: &mut VirtualCells<'_, F>| {
+            meta.query_fixed(rc.q_enable, Rotation::cur())
+                * meta.query_advice(rc.is_real, Rotation::cur())
+        };
+        let format = move |meta: &mut VirtualCells<'_, F>| {
+            Format::Receipt.expr() + meta.query_advice(rc.is_typed, Rotation::cur())
+        };
+
+        meta.create_gate("pi receipts: receipt rows", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let q_last = meta.query_fixed(rc.q_last, Rotation::cur());
+            let remaining = meta.query_advice(rc.remaining, Rotation::cur());
+            let is_real = meta.query_advice(rc.is_real, Rotation::cur());
+            let is_typed = meta.query_advice(rc.is_typed, Rotation::cur());
+            let has_logs = meta.query_advice(rc.has_logs, Rotation::cur());
+            let log_len = meta.query_advice(rc.log_len, Rotation::cur());
+
+            cb.require_equal(
+                "tx is real iff txs remain",
+                is_real.expr(),
+                not::expr(rc.remaining_is_zero.expr()),
+            );
+            cb.require_equal(
+                "one tx less remains after a real tx",
+                remaining - is_real.expr(),
+                not::expr(q_last) * meta.query_advice(rc.remaining, Rotation::next()),
+            );
+            cb.require_equal(
+                "receipt is typed iff its type byte is not zero",
+                is_typed.expr(),
+                not::expr(rc.type_byte_is_zero.expr()),
+            );
+            cb.require_equal(
+                "receipt has logs iff their number is not zero",
+                has_logs.expr(),
+                not::expr(rc.log_len_is_zero.expr()),
+            );
+            cb.require_boolean(
+                "status is boolean",
+                meta.query_advice(rc.status, Rotation::cur()),
+            );
+            cb.require_zero(
+                "padding tx has no logs",
+                not::expr(is_real.expr()) * log_len,
+            );
+            cb.require_zero(
+                "logs are empty without logs",
+                not::expr(has_logs) * meta.query_advice(rc.logs_len, Rotation::cur()),
+            );
+            let gas_is_small = meta.query_advice(rc.gas_is_small, Rotation::cur());
+            cb.require_boolean("gas_is_small is boolean", gas_is_small.expr());
+            cb.require_zero(
+                "small cumulative gas is a single byte",
+                gas_is_small * not::expr(rc.gas_len_is_one.expr()),
+            );
+
+            let payload_len = N_BYTES_STATUS_AND_BLOOM.expr()
+                + gas_enc_len(meta)
+                + rc.logs_header.expr(meta)
+                + meta.query_advice(rc.logs_len, Rotation::cur());
+            let len = is_typed + rc.payload_header.expr(meta) + payload_len;
+            cb.condition(is_real, |cb| {
+                cb.require_equal(
+                    "length of the receipt",
+                    meta.query_advice(rc.len, Rotation::cur()),
+                    len,
+                );
+            });
+
+            cb.gate(meta.query_fixed(rc.q_enable, Rotation::cur()))
+        });
+
+        let fields = [
+            TxReceiptFieldTag::PostStateOrStatus,
+            TxReceiptFieldTag::CumulativeGasUsed,
+            TxReceiptFieldTag::LogLength,
+        ];
+        let values = [rc.status, rc.gas, rc.log_len];
+        for ((field_tag, value), rw_counter) in fields.into_iter().zip(values).zip(rc.rw_counters) {
+            meta.lookup_any("pi receipts: receipt field in rw table", |meta| {
+                let enable = q_real(meta);
+                vec![
+                    1.expr(), // q_enable
+                    meta.query_advice(rw_counter, Rotation::cur()),
+                    1.expr(), // is_write
+                    RwTableTag::TxReceipt.expr(),
+                    meta.query_fixed(rc.tx_id, Rotation::cur()),
+                    0.expr(), // address
+                    field_tag.expr(),
+                    0.expr(), // storage_key
+                    meta.query_advice(value, Rotation::cur()),
+                    0.expr(), // value_prev
+                    0.expr(), // aux1
+                    0.expr(), // aux2
+                ]
+                .into_iter()
+                .zip_eq(rw_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.expr() * arg, table))
+                .collect()
+            });
+        }
+
+        meta.lookup_any("pi receipts: tx type in tx table", |meta| {
+            let enable = q_real(meta);
+            vec![
+                1.expr(), // q_enable
+                meta.query_fixed(rc.tx_id, Rotation::cur()),
+                TxContextFieldTag::TxType.expr(),
+                0.expr(), // index
+                meta.query_advice(rc.tx_type, Rotation::cur()),
+                0.expr(), // access_list_address
+            ]
+            .into_iter()
+            .zip_eq(tx_table.table_exprs(meta))
+            .map(|(arg, table)| (enable.expr() * arg, table))
+            .collect()
+        });
+        meta.lookup("pi receipts: type byte of the tx type", |meta| {
+            let enable = q_real(meta);
+            vec![
+                (
+                    enable.expr() * meta.query_advice(rc.tx_type, Rotation::cur()),
+                    tx_types[0],
+                ),
+                (
+                    enable * meta.query_advice(rc.type_byte, Rotation::cur()),
+                    tx_types[1],
+                ),
+            ]
+        });
+
+        meta.lookup_any("pi receipts: type of the receipt in RLP table", |meta| {
+            let enable = q_real(meta) * meta.query_advice(rc.is_typed, Rotation::cur());
+            let type_byte = meta.query_advice(rc.type_byte, Rotation::cur());
+            rlp_output(
+                meta.query_advice(rc.rlp_id, Rotation::cur()),
+                format(meta),
+                Tag::TxType.expr(),
+                type_byte.expr(),
+                type_byte,
+                1.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+            )
+            .into_iter()
+            .zip_eq(rlp_table.table_exprs(meta))
+            .map(|(arg, table)| (enable.expr() * arg, table))
+            .collect()
+        });
+        meta.lookup_any("pi receipts: status of the receipt in RLP table", |meta| {
+            let enable = q_real(meta);
+            let status = meta.query_advice(rc.status, Rotation::cur());
+            // a zero status is encoded as the empty string
+            rlp_output(
+                meta.query_advice(rc.rlp_id, Rotation::cur()),
+                format(meta),
+                Tag::Nonce.expr(),
+                status.expr(),
+                status.expr(),
+                status.expr(),
+                not::expr(status),
+                0.expr(),
+                0.expr(),
+            )
+            .into_iter()
+            .zip_eq(rlp_table.table_exprs(meta))
+            .map(|(arg, table)| (enable.expr() * arg, table))
+            .collect()
+        });
+        let gas_is_zero = rc.gas_is_zero.clone();
+        meta.lookup_any("pi receipts: gas of the receipt in RLP table", |meta| {
+            let enable = q_real(meta);
+            // a zero cumulative gas is encoded as the empty string
+            rlp_output(
+                meta.query_advice(rc.rlp_id, Rotation::cur()),
+                format(meta),
+                Tag::Gas.expr(),
+                meta.query_advice(rc.gas, Rotation::cur()),
+                meta.query_advice(rc.gas_bytes_rlc, Rotation::cur()),
+                meta.query_advice(rc.gas_len, Rotation::cur()),
+                gas_is_zero.expr(),
+                0.expr(),
+                0.expr(),
+            )
+            .into_iter()
+            .zip_eq(rlp_table.table_exprs(meta))
+            .map(|(arg, table)| (enable.expr() * arg, table))
+            .collect()
+        });
+        let gas_len_is_one = rc.gas_len_is_one.clone();
+        meta.lookup("pi receipts: small cumulative gas is below 0x80", |meta| {
+            let enable = q_real(meta);
+            let gas = meta.query_advice(rc.gas, Rotation::cur());
+            let is_small = meta.query_advice(rc.gas_is_small, Rotation::cur());
+            // a single byte is small iff it is below 0x80
+            vec![(
+                enable
+                    * (is_small.expr() * (0x7f.expr() - gas.expr())
+                        + not::expr(is_small) * gas_len_is_one.expr() * (gas - 0x80.expr())),
+                u8_table.into(),
+            )]
+        });
+        meta.lookup_any("pi receipts: bloom of the receipt in RLP table", |meta| {
+            let enable = q_real(meta);
+            let bloom_rlc = meta.query_advice(rc.bloom_rlc, Rotation::cur());
+            rlp_output(
+                meta.query_advice(rc.rlp_id, Rotation::cur()),
+                format(meta),
+                Tag::Data.expr(),
+                bloom_rlc.expr(),
+                bloom_rlc,
+                256.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+            )
+            .into_iter()
+            .zip_eq(rlp_table.table_exprs(meta))
+            .map(|(arg, table)| (enable.expr() * arg, table))
+            .collect()
+        });
+        meta.lookup_any("pi receipts: length of the receipt in RLP table", |meta| {
+            let enable = q_real(meta);
+            // the payload of a receipt is longer than 255 bytes
+            rlp_output(
+                meta.query_advice(rc.rlp_id, Rotation::cur()),
+                format(meta),
+                RlpTag::Len.expr(),
+                meta.query_advice(rc.len, Rotation::cur()),
+                0.expr(),
+                rc.payload_header.expr(meta) - 1.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+            )
+            .into_iter()
+            .zip_eq(rlp_table.table_exprs(meta))
+            .map(|(arg, table)| (enable.expr() * arg, table))
+            .collect()
+        });
+        meta.lookup_any("pi receipts: rlc of the receipt in RLP table", |meta| {
+            let enable = q_real(meta);
+            rlp_output(
+                meta.query_advice(rc.rlp_id, Rotation::cur()),
+                format(meta),
+                RlpTag::RLC.expr(),
+                meta.query_advice(rc.rlc, Rotation::cur()),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+            )
+            .into_iter()
+            .zip_eq(rlp_table.table_exprs(meta))
+            .map(|(arg, table)| (enable.expr() * arg, table))
+            .collect()
+        });
+
+        meta.lookup_any("pi receipts: bloom of the receipt in bloom rows", |meta| {
+            let enable = q_real(meta);
+            let q_bloom = meta.query_fixed(bloom.q_bloom_last, Rotation::cur())
+                * meta.query_fixed(bloom.q_tx_bloom, Rotation::cur());
+            vec![
+                (enable.expr(), q_bloom.expr()),
+                (
+                    enable.expr() * meta.query_fixed(rc.tx_id, Rotation::cur()),
+                    q_bloom.expr() * meta.query_fixed(bloom.bloom_id, Rotation::cur()),
+                ),
+                (
+                    enable * meta.query_advice(rc.bloom_rlc, Rotation::cur()),
+                    q_bloom * meta.query_advice(bloom.rlc, Rotation::cur()),
+                ),
+            ]
+        });
+        meta.lookup_any("pi receipts: logs of the receipt in log rows", |meta| {
+            let enable = meta.query_fixed(rc.q_enable, Rotation::cur())
+                * meta.query_advice(rc.has_logs, Rotation::cur());
+            let q_tx_end = meta.query_fixed(log.q_enable, Rotation::cur())
+                * meta.query_advice(log.is_tx_end, Rotation::cur());
+            vec![
+                (1.expr(), 1.expr()),
+                (
+                    meta.query_fixed(rc.tx_id, Rotation::cur()),
+                    meta.query_advice(log.tx_id, Rotation::cur()),
+                ),
+                (
+                    meta.query_advice(rc.log_len, Rotation::cur()),
+                    meta.query_advice(log.log_id, Rotation::cur()),
+                ),
+                (
+                    meta.query_advice(rc.logs_len, Rotation::cur()),
+                    meta.query_advice(log.logs_acc, Rotation::cur())
+                        + meta.query_advice(log.log_enc, Rotation::cur()),
+                ),
+                (
+                    meta.query_advice(rc.rlp_id, Rotation::cur()),
+                    meta.query_advice(log.rlp_id, Rotation::cur()),
+                ),
+                (format(meta), meta.query_advice(log.format, Rotation::cur())),
+            ]
+            .into_iter()
+            .map(|(arg, table)| (enable.expr() * arg, q_tx_end.expr() * table))
+            .collect()
+        });
+        meta.lookup_any("pi receipts: receipt in a leaf of the trie", |meta| {
+            let enable = q_real(meta);
+            let q_leaf = meta.query_fixed(trie.q_node, Rotation::cur())
+                * meta.query_advice(trie.is_leaf, Rotation::cur());
+            vec![
+                (1.expr(), 1.expr()),
+                (
+                    meta.query_fixed(rc.key, Rotation::cur()),
+                    meta.query_advice(trie.key, Rotation::cur()),
+                ),
+                (
+                    meta.query_fixed(rc.key_len, Rotation::cur()),
+                    meta.query_advice(trie.key_len, Rotation::cur()),
+                ),
+                (
+                    meta.query_advice(rc.rlc, Rotation::cur()),
+                    meta.query_advice(trie.value_rlc, Rotation::cur()),
+                ),
+                (
+                    meta.query_advice(rc.len, Rotation::cur()),
+                    meta.query_advice(trie.value_len, Rotation::cur()),
+                ),
+            ]
+            .into_iter()
+            .map(|(arg, table)| (enable.expr() * arg, q_leaf.expr() * table))
+            .collect()
+        });
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn configure_logs(
+        meta: &mut ConstraintSystem<F>,
+        receipt: &ReceiptColumns<F>,
+        log: &LogColumns<F>,
+        bloom: &BloomColumns,
+        log_payload_len: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        rw_table: RwTable,
+        rlp_table: RlpFsmRlpTable,
+        keccak_table: &KeccakTable,
+        u8_table: U8Table,
+        keccak_rand: &Expression<F>,
+        word_rand: &Expression<F>,
+    ) {
+        let lc = log.clone();
+        let flag = move |meta: &mut VirtualCells<'_, F>, flag: usize, at: Rotation| {
+            meta.query_advice(lc.flags[flag], at)
+        };
+        let is_start = move |meta: &mut VirtualCells<'_, F>| {
+            flag(meta, IS_ADDRESS, Rotation::cur()) + flag(meta, IS_TOPIC, Rotation::cur())
+        };
+        // the rows setting a bit of the bloom
+        let is_indexed = move |meta: &mut VirtualCells<'_, F>| {
+            is_start(meta)
+                + flag(meta, IS_HASH_1, Rotation::cur())
+                + flag(meta, IS_HASH_2, Rotation::cur())
+        };
+        let bloom_idx = move |meta: &mut VirtualCells<'_, F>| {
+            meta.query_advice(lc.bloom_low3, Rotation::cur()) * 256.expr()
+                + meta.query_advice(lc.bloom_lo, Rotation::cur())
+        };
+
+        meta.create_gate("pi receipts: log rows", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let flags = (0..lc.flags.len())
+                .map(|i| flag(meta, i, Rotation::cur()))
+                .collect::<Vec<_>>();
+            let flags_next = (0..lc.flags.len())
+                .map(|i| flag(meta, i, Rotation::next()))
+                .collect::<Vec<_>>();
+            let active = sum::expr(&flags);
+            let active_next = sum::expr(&flags_next);
+            let is_start = flags[IS_ADDRESS].expr() + flags[IS_TOPIC].expr();
+            let is_address_next = flags_next[IS_ADDRESS].expr();
+            // whether the next row continues the log of the row
+            let is_cont_next = active_next.expr() - is_address_next.expr();
+            let q_first = meta.query_fixed(lc.q_first, Rotation::cur());
+            let not_last = not::expr(meta.query_fixed(lc.q_last, Rotation::cur()));
+            let is_log_end = meta.query_advice(lc.is_log_end, Rotation::cur());
+            let is_tx_end = meta.query_advice(lc.is_tx_end, Rotation::cur());
+
+            for flag in flags.iter() {
+                cb.require_boolean("log row flag is boolean", flag.expr());
+            }
+            cb.require_boolean("log row is active or padding", active.expr());
+            cb.require_zero(
+                "last log row is padding",
+                not::expr(not_last.expr()) * active.expr(),
+            );
+            cb.require_equal(
+                "log ends on its last active row",
+                is_log_end.expr(),
+                not_last.expr() * (active.expr() - is_cont_next.expr()),
+            );
+            cb.require_boolean("is_tx_end is boolean", is_tx_end.expr());
+            cb.require_zero(
+                "logs of a tx end with a log",
+                is_tx_end.expr() * not::expr(is_log_end.expr()),
+            );
+            cb.require_boolean(
+                "is_small is boolean",
+                meta.query_advice(lc.is_small, Rotation::cur()),
+            );
+
+            cb.condition(not_last, |cb| {
+                cb.require_equal(
+                    "first row of the rest of a hash follows an address or a topic",
+                    flags_next[IS_HASH_1].expr(),
+                    is_start.expr(),
+                );
+                cb.require_equal(
+                    "second row of the rest of a hash",
+                    flags_next[IS_HASH_2].expr(),
+                    flags[IS_HASH_1].expr(),
+                );
+                cb.require_equal(
+                    "third row of the rest of a hash",
+                    flags_next[IS_HASH_3].expr(),
+                    flags[IS_HASH_2].expr(),
+                );
+                cb.require_zero(
+                    "topics follow an address or a topic",
+                    flags_next[IS_TOPIC].expr() * not::expr(flags[IS_HASH_3].expr()),
+                );
+                cb.require_zero(
+                    "data follows the topics",
+                    flags_next[IS_DATA].expr()
+                        * not::expr(flags[IS_HASH_3].expr() + flags[IS_DATA].expr()),
+                );
+                cb.require_zero(
+                    "padding rows are at the end",
+                    not::expr(active.expr()) * active_next.expr(),
+                );
+                cb.require_zero(
+                    "the logs of a tx are followed by the ones of another tx",
+                    is_log_end.expr()
+                        * not::expr(is_address_next.expr())
+                        * not::expr(is_tx_end.expr()),
+                );
+
+                for column in [lc.tx_id, lc.log_id, lc.rlp_id, lc.format, lc.logs_acc] {
+                    cb.require_zero(
+                        "log is constant over its rows",
+                        is_cont_next.expr()
+                            * (meta.query_advice(column, Rotation::next())
+                                - meta.query_advice(column, Rotation::cur())),
+                    );
+                }
+                let next_log = is_address_next.expr() * not::expr(is_tx_end.expr());
+                for column in [lc.tx_id, lc.rlp_id, lc.format] {
+                    cb.require_zero(
+                        "next log of a tx is in the same receipt",
+                        next_log.expr()
+                            * (meta.query_advice(column, Rotation::next())
+                                - meta.query_advice(column, Rotation::cur())),
+                    );
+                }
+                cb.require_zero(
+                    "next log of a tx has the next id",
+                    next_log.expr()
+                        * (meta.query_advice(lc.log_id, Rotation::next())
+                            - meta.query_advice(lc.log_id, Rotation::cur())
+                            - 1.expr()),
+                );
+                cb.require_zero(
+                    "next log of a tx follows the encoding of the log",
+                    next_log
+                        * (meta.query_advice(lc.logs_acc, Rotation::next())
+                            - meta.query_advice(lc.logs_acc, Rotation::cur())
+                            - meta.query_advice(lc.log_enc, Rotation::cur())),
+                );
+                let next_tx = is_address_next * is_tx_end;
+                cb.require_zero(
+                    "first log of a tx has id 1",
+                    next_tx.expr() * (meta.query_advice(lc.log_id, Rotation::next()) - 1.expr()),
+                );
+                cb.require_zero(
+                    "first log of a tx is first in its encoding",
+                    next_tx * meta.query_advice(lc.logs_acc, Rotation::next()),
+                );
+            });
+            cb.condition(q_first, |cb| {
+                cb.require_zero(
+                    "first log row is an address or padding",
+                    active.expr() - flags[IS_ADDRESS].expr(),
+                );
+                cb.require_zero(
+                    "first log has id 1",
+                    flags[IS_ADDRESS].expr()
+                        * (meta.query_advice(lc.log_id, Rotation::cur()) - 1.expr()),
+                );
+                cb.require_zero(
+                    "first log is first in its encoding",
+                    meta.query_advice(lc.logs_acc, Rotation::cur()),
+                );
+            });
+
+            // counters of the topics and the data
+            let topic_cnt = meta.query_advice(lc.topic_cnt, Rotation::cur());
+            let topic_cnt_prev = meta.query_advice(lc.topic_cnt, Rotation::prev());
+            let data_cnt = meta.query_advice(lc.data_cnt, Rotation::cur());
+            let data_cnt_prev = meta.query_advice(lc.data_cnt, Rotation::prev());
+            let data_rlc = meta.query_advice(lc.data_rlc, Rotation::cur());
+            let data_rlc_prev = meta.query_advice(lc.data_rlc, Rotation::prev());
+            let is_hash =
+                flags[IS_HASH_1].expr() + flags[IS_HASH_2].expr() + flags[IS_HASH_3].expr();
+            cb.require_zero(
+                "no topic is counted at the address",
+                flags[IS_ADDRESS].expr() * topic_cnt.expr(),
+            );
+            cb.require_zero(
+                "topics are counted",
+                flags[IS_TOPIC].expr() * (topic_cnt.expr() - topic_cnt_prev.expr() - 1.expr()),
+            );
+            cb.require_zero(
+                "topics count is kept after a topic",
+                (is_hash.expr() + flags[IS_DATA].expr()) * (topic_cnt.expr() - topic_cnt_prev),
+            );
+            cb.require_zero(
+                "data is counted",
+                flags[IS_DATA].expr() * (data_cnt.expr() - data_cnt_prev - 1.expr()),
+            );
+            cb.require_zero(
+                "data is only counted on data rows",
+                not::expr(flags[IS_DATA].expr()) * data_cnt.expr(),
+            );
+            cb.require_zero(
+                "rlc of the data",
+                flags[IS_DATA].expr()
+                    * (data_rlc.expr()
+                        - data_rlc_prev * keccak_rand.expr()
+                        - meta.query_advice(lc.value, Rotation::cur())),
+            );
+            cb.require_zero(
+                "rlc of the data is only accumulated on data rows",
+                not::expr(flags[IS_DATA].expr()) * data_rlc,
+            );
+
+            // the rw table being sorted by key, the row following the last
+            // topic of the log in the rw table is not a further topic, and the
+            // one following its last data byte, or its last topic without
+            // data, is not a further data byte
+            let at_next_item = Rotation(ROWS_PER_ITEM as i32);
+            let topic_follows = flag(meta, IS_TOPIC, at_next_item);
+            let data_follows = flag(meta, IS_DATA, at_next_item);
+            let is_topics_end = is_start.expr() * not::expr(topic_follows.expr());
+            let is_data_end = is_start.expr() * not::expr(topic_follows) * not::expr(data_follows)
+                + flags[IS_DATA].expr() * not::expr(flags_next[IS_DATA].expr());
+            let [next_tag, next_id, next_address] = lc
+                .next_rw
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let tx_id = meta.query_advice(lc.tx_id, Rotation::cur());
+            let log_id = meta.query_advice(lc.log_id, Rotation::cur());
+            let next_key_diff = |field_tag: TxLogFieldTag, index: Expression<F>| {
+                (next_tag.expr() - RwTableTag::TxLog.expr()) * pow_of_two_expr(224)
+                    + (next_id.expr() - tx_id.expr()) * pow_of_two_expr(160)
+                    + next_address.expr()
+                    - build_tx_log_expression(index, field_tag.expr(), log_id.expr())
+            };
+            cb.require_zero(
+                "no topic follows the last one in the rw table",
+                is_topics_end
+                    * (next_key_diff(TxLogFieldTag::Topic, topic_cnt.expr())
+                        * meta.query_advice(lc.next_topic_inv, Rotation::cur())
+                        - 1.expr()),
+            );
+            cb.require_zero(
+                "no data byte follows the last one in the rw table",
+                is_data_end
+                    * (next_key_diff(TxLogFieldTag::Data, data_cnt.expr())
+                        * meta.query_advice(lc.next_data_inv, Rotation::cur())
+                        - 1.expr()),
+            );
+
+            // hash of the address or the topic, 8 bytes per row
+            let hash_bytes_rlc = lc.hash_bytes.iter().fold(0.expr(), |acc, column| {
+                acc * word_rand.expr() + meta.query_advice(*column, Rotation::cur())
+            });
+            let pow_8 = (0..N_HASH_BYTES_PER_ROW).fold(1.expr(), |acc, _| acc * word_rand.expr());
+            cb.require_zero(
+                "hash is accumulated over the rows of the item",
+                (is_start.expr() + is_hash.expr())
+                    * (meta.query_advice(lc.hash_acc, Rotation::cur()) - hash_bytes_rlc)
+                    - is_hash * meta.query_advice(lc.hash_acc, Rotation::prev()) * pow_8,
+            );
+
+            // the 3 bits of the bloom set by the item are given by the pairs
+            // of bytes of the hash in its first row
+            let is_indexed = [
+                is_start.expr(),
+                flags[IS_HASH_1].expr(),
+                flags[IS_HASH_2].expr(),
+            ];
+            let (mut hi, mut lo) = (0.expr(), 0.expr());
+            for (i, flag) in is_indexed.iter().enumerate() {
+                // the hash bytes of the first row are at the rotation -i
+                let at = Rotation(-(i as i32));
+                hi = hi + flag.expr() * meta.query_advice(lc.hash_bytes[2 * i], at);
+                lo = lo + flag.expr() * meta.query_advice(lc.hash_bytes[2 * i + 1], at);
+            }
+            let is_indexed = sum::expr(&is_indexed);
+            cb.require_equal(
+                "high byte of the bloom index",
+                hi,
+                is_indexed.expr()
+                    * (meta.query_advice(lc.bloom_q, Rotation::cur()) * 8.expr()
+                        + meta.query_advice(lc.bloom_low3, Rotation::cur())),
+            );
+            cb.require_equal(
+                "low byte of the bloom index",
+                lo,
+                is_indexed * meta.query_advice(lc.bloom_lo, Rotation::cur()),
+            );
+
+            // length of the encoding of the log
+            let long = meta.query_advice(lc.topics_long, Rotation::cur());
+            let log_enc = lc.log_header.expr(meta) + log_payload_len(meta);
+            cb.condition(is_log_end, |cb| {
+                cb.require_boolean("topics_long is boolean", long.expr());
+                cb.require_zero(
+                    "at most one topic without a length byte",
+                    not::expr(long.expr()) * topic_cnt.expr() * (topic_cnt.expr() - 1.expr()),
+                );
+                cb.require_zero(
+                    "2 to 4 topics with a length byte",
+                    long * (topic_cnt.expr() - 2.expr())
+                        * (topic_cnt.expr() - 3.expr())
+                        * (topic_cnt - 4.expr()),
+                );
+                cb.require_equal(
+                    "length of the encoding of the log",
+                    meta.query_advice(lc.log_enc, Rotation::cur()),
+                    log_enc,
+                );
+            });
+
+            cb.gate(meta.query_fixed(lc.q_enable, Rotation::cur()))
+        });
+
+        meta.lookup_any("pi receipts: log item in rw table", |meta| {
+            let is_address = flag(meta, IS_ADDRESS, Rotation::cur());
+            let is_topic = flag(meta, IS_TOPIC, Rotation::cur());
+            let is_data = flag(meta, IS_DATA, Rotation::cur());
+            let enable = meta.query_fixed(lc.q_enable, Rotation::cur())
+                * (is_address.expr() + is_topic.expr() + is_data.expr());
+            let field_tag = is_address * TxLogFieldTag::Address.expr()
+                + is_topic.expr() * TxLogFieldTag::Topic.expr()
+                + is_data.expr() * TxLogFieldTag::Data.expr();
+            let index = is_topic * (meta.query_advice(lc.topic_cnt, Rotation::cur()) - 1.expr())
+                + is_data * (meta.query_advice(lc.data_cnt, Rotation::cur()) - 1.expr());
+            vec![
+                1.expr(), // q_enable
+                meta.query_advice(lc.rw_counter, Rotation::cur()),
+                1.expr(), // is_write
+                RwTableTag::TxLog.expr(),
+                meta.query_advice(lc.tx_id, Rotation::cur()),
+                build_tx_log_expression(
+                    index,
+                    field_tag,
+                    meta.query_advice(lc.log_id, Rotation::cur()),
+                ),
+                0.expr(), // field_tag
+                0.expr(), // storage_key
+                meta.query_advice(lc.value, Rotation::cur()),
+                0.expr(), // value_prev
+                0.expr(), // aux1
+                0.expr(), // aux2
+            ]
+            .into_iter()
+            .chain(
+                lc.next_rw
+                    .map(|column| meta.query_advice(column, Rotation::cur())),
+            )
+            .zip_eq(
+                rw_table.table_exprs(meta).into_iter().chain(
+                    [rw_table.tag, rw_table.id, rw_table.address]
+                        .map(|column| meta.query_advice(column, Rotation::next())),
+                ),
+            )
+            .map(|(arg, table)| (enable.expr() * arg, table))
+            .collect()
+        });
+        meta.lookup_any("pi receipts: log item in RLP table", |meta| {
+            let enable = meta.query_fixed(lc.q_enable, Rotation::cur()) * is_start(meta);
+            let is_address = flag(meta, IS_ADDRESS, Rotation::cur());
+            let is_topic = flag(meta, IS_TOPIC, Rotation::cur());
+            // the topics of a log are indexed from 1
+            rlp_output(
+                meta.query_advice(lc.rlp_id, Rotation::cur()),
+                meta.query_advice(lc.format, Rotation::cur()),
+                is_address * Tag::AccessListAddress.expr()
+                    + is_topic.expr() * Tag::AccessListStorageKey.expr(),
+                meta.query_advice(lc.value, Rotation::cur()),
+                meta.query_advice(lc.item_rlc, Rotation::cur()),
+                20.expr() + is_topic.expr() * 12.expr(),
+                0.expr(),
+                meta.query_advice(lc.log_id, Rotation::cur()),
+                is_topic * meta.query_advice(lc.topic_cnt, Rotation::cur()),
+            )
+            .into_iter()
+            .zip_eq(rlp_table.table_exprs(meta))
+            .map(|(arg, table)| (enable.expr() * arg, table))
+            .collect()
+        });
+        let data_is_zero = lc.data_is_zero.clone();
+        meta.lookup_any("pi receipts: log data in RLP table", |meta| {
+            let enable = meta.query_fixed(lc.q_enable, Rotation::cur())
+                * meta.query_advice(lc.is_log_end, Rotation::cur());
+            let data_rlc = meta.query_advice(lc.data_rlc, Rotation::cur());
+            rlp_output(
+                meta.query_advice(lc.rlp_id, Rotation::cur()),
+                meta.query_advice(lc.format, Rotation::cur()),
+                Tag::Data.expr(),
+                data_rlc.expr(),
+                data_rlc,
+                meta.query_advice(lc.data_cnt, Rotation::cur()),
+                data_is_zero.expr(),
+                meta.query_advice(lc.log_id, Rotation::cur()),
+                0.expr(),
+            )
+            .into_iter()
+            .zip_eq(rlp_table.table_exprs(meta))
+            .map(|(arg, table)| (enable.expr() * arg, table))
+            .collect()
+        });
+        meta.lookup_any(
+            "pi receipts: hash of the log item in keccak table",
+            |meta| {
+                let enable = meta.query_fixed(lc.q_enable, Rotation::cur())
+                    * flag(meta, IS_HASH_3, Rotation::cur());
+                let at = Rotation(-(ROWS_PER_ITEM as i32 - 1));
+                vec![
+                    1.expr(), // q_enable
+                    1.expr(), // is_final
+                    meta.query_advice(lc.item_rlc, at),
+                    20.expr() + flag(meta, IS_TOPIC, at) * 12.expr(),
+                    meta.query_advice(lc.hash_acc, Rotation::cur()),
+                ]
+                .into_iter()
+                .zip_eq(keccak_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.expr() * arg, table))
+                .collect()
+            },
+        );
+
+        meta.lookup_any(
+            "pi receipts: bit of the log item in the bloom of its tx",
+            |meta| {
+                let enable = meta.query_fixed(lc.q_enable, Rotation::cur()) * is_indexed(meta);
+                let q_bit = meta.query_fixed(bloom.q_tx_bloom, Rotation::cur())
+                    * meta.query_advice(bloom.bit, Rotation::cur());
+                vec![
+                    (1.expr(), 1.expr()),
+                    (
+                        meta.query_advice(lc.tx_id, Rotation::cur()),
+                        meta.query_fixed(bloom.bloom_id, Rotation::cur()),
+                    ),
+                    (
+                        bloom_idx(meta),
+                        meta.query_fixed(bloom.bit_idx, Rotation::cur()),
+                    ),
+                ]
+                .into_iter()
+                .map(|(arg, table)| (enable.expr() * arg, q_bit.expr() * table))
+                .collect()
+            },
+        );
+        meta.lookup_any(
+            "pi receipts: bit of the log item in the bloom of the chunk",
+            |meta| {
+                let enable = meta.query_fixed(lc.q_enable, Rotation::cur()) * is_indexed(meta);
+                let q_bit = meta.query_fixed(bloom.q_chunk_bloom, Rotation::cur())
+                    * meta.query_advice(bloom.bit, Rotation::cur());
+                vec![
+                    (1.expr(), 1.expr()),
+                    (
+                        bloom_idx(meta),
+                        meta.query_fixed(bloom.bit_idx, Rotation::cur()),
+                    ),
+                ]
+                .into_iter()
+                .map(|(arg, table)| (enable.expr() * arg, q_bit.expr() * table))
+                .collect()
+            },
+        );
+
+        meta.lookup("pi receipts: bloom index bytes range", |meta| {
+            let q_enable = meta.query_fixed(lc.q_enable, Rotation::cur());
+            vec![(
+                q_enable * meta.query_advice(lc.bloom_q, Rotation::cur()) * 8.expr(),
+                u8_table.into(),
+            )]
+        });
+        meta.lookup("pi receipts: bloom index bytes range", |meta| {
+            let q_enable = meta.query_fixed(lc.q_enable, Rotation::cur());
+            vec![(
+                q_enable * meta.query_advice(lc.bloom_low3, Rotation::cur()) * 32.expr(),
+                u8_table.into(),
+            )]
+        });
+        meta.lookup("pi receipts: bloom index bytes range", |meta| {
+            let q_enable = meta.query_fixed(lc.q_enable, Rotation::cur());
+            vec![(
+                q_enable * meta.query_advice(lc.bloom_lo, Rotation::cur()),
+                u8_table.into(),
+            )]
+        });
+        meta.lookup("pi receipts: small data byte is below 0x80", |meta| {
+            let enable = meta.query_fixed(lc.q_enable, Rotation::cur())
+                * flag(meta, IS_DATA, Rotation::cur());
+            let byte = meta.query_advice(lc.value, Rotation::cur());
+            let is_small = meta.query_advice(lc.is_small, Rotation::cur());
+            vec![(
+                enable
+                    * (is_small.expr() * (0x7f.expr() - byte.expr())
+                        + not::expr(is_small) * (byte - 0x80.expr())),
+                u8_table.into(),
+            )]
+        });
+
+        meta.lookup_any("pi receipts: logs of a real receipt", |meta| {
+            let enable = meta.query_fixed(lc.q_enable, Rotation::cur())
+                * flag(meta, IS_ADDRESS, Rotation::cur());
+            let q_real = meta.query_fixed(receipt.q_enable, Rotation::cur())
+                * meta.query_advice(receipt.is_real, Rotation::cur());
+            vec![
+                (1.expr(), 1.expr()),
+                (
+                    meta.query_advice(lc.tx_id, Rotation::cur()),
+                    meta.query_fixed(receipt.tx_id, Rotation::cur()),
+                ),
+                (
+                    meta.query_advice(lc.rlp_id, Rotation::cur()),
+                    meta.query_advice(receipt.rlp_id, Rotation::cur()),
+                ),
+                (
+                    meta.query_advice(lc.format, Rotation::cur()),
+                    Format::Receipt.expr() + meta.query_advice(receipt.is_typed, Rotation::cur()),
+                ),
+            ]
+            .into_iter()
+            .map(|(arg, table)| (enable.expr() * arg, q_real.expr() * table))
+            .collect()
+        });
+    }
+
+    fn configure_blooms(
+        meta: &mut ConstraintSystem<F>,
+        log: &LogColumns<F>,
+        bloom: &BloomColumns,
+        keccak_rand: &Expression<F>,
+        word_rand: &Expression<F>,
+    ) {
+        let bc = *bloom;
+
+        meta.create_gate("pi receipts: bloom rows", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let bit = meta.query_advice(bc.bit, Rotation::cur());
+            let byte_acc = meta.query_advice(bc.byte_acc, Rotation::cur());
+            let q_byte_last = meta.query_fixed(bc.q_byte_last, Rotation::cur());
+            cb.require_boolean("bloom bit is boolean", bit.expr());
+            cb.require_equal(
+                "bits of the byte",
+                byte_acc.expr(),
+                not::expr(meta.query_fixed(bc.q_byte_first, Rotation::cur()))
+                    * meta.query_advice(bc.byte_acc, Rotation::prev())
+                    * 2.expr()
+                    + bit,
+            );
+            let not_first = not::expr(meta.query_fixed(bc.q_bloom_first, Rotation::cur()));
+            for (column, rand) in [(bc.rlc, keccak_rand), (bc.word_rlc, word_rand)] {
+                let prev = not_first.expr() * meta.query_advice(column, Rotation::prev());
+                cb.require_equal(
+                    "rlc of the bytes of the bloom",
+                    meta.query_advice(column, Rotation::cur()),
+                    prev.expr()
+                        + q_byte_last.expr() * (prev * (rand.expr() - 1.expr()) + byte_acc.expr()),
+                );
+            }
+
+            cb.gate(meta.query_fixed(bc.q_enable, Rotation::cur()))
+        });
+
+        let lc = log.clone();
+        let log_item = move |meta: &mut VirtualCells<'_, F>| {
+            meta.query_fixed(lc.q_enable, Rotation::cur())
+                * (meta.query_advice(lc.flags[IS_ADDRESS], Rotation::cur())
+                    + meta.query_advice(lc.flags[IS_TOPIC], Rotation::cur())
+                    + meta.query_advice(lc.flags[IS_HASH_1], Rotation::cur())
+                    + meta.query_advice(lc.flags[IS_HASH_2], Rotation::cur()))
+        };
+        let bloom_idx = move |meta: &mut VirtualCells<'_, F>| {
+            meta.query_advice(lc.bloom_low3, Rotation::cur()) * 256.expr()
+                + meta.query_advice(lc.bloom_lo, Rotation::cur())
+        };
+        meta.lookup_any(
+            "pi receipts: bit of the bloom of a tx set by a log item",
+            |meta| {
+                let enable = meta.query_fixed(bc.q_tx_bloom, Rotation::cur())
+                    * meta.query_advice(bc.bit, Rotation::cur());
+                let q_item = log_item(meta);
+                vec![
+                    (1.expr(), 1.expr()),
+                    (
+                        meta.query_fixed(bc.bloom_id, Rotation::cur()),
+                        meta.query_advice(lc.tx_id, Rotation::cur()),
+                    ),
+                    (
+                        meta.query_fixed(bc.bit_idx, Rotation::cur()),
+                        bloom_idx(meta),
+                    ),
+                ]
+                .into_iter()
+                .map(|(arg, table)| (enable.expr() * arg, q_item.expr() * table))
+                .collect()
+            },
+        );
+        meta.lookup_any(
+            "pi receipts: bit of the bloom of the chunk set by a log item",
+            |meta| {
+                let enable = meta.query_fixed(bc.q_chunk_bloom, Rotation::cur())
+                    * meta.query_advice(bc.bit, Rotation::cur());
+                let q_item = log_item(meta);
+                vec![
+                    (1.expr(), 1.expr()),
+                    (
+                        meta.query_fixed(bc.bit_idx, Rotation::cur()),
+                        bloom_idx(meta),
+                    ),
+                ]
+                .into_iter()
+                .map(|(arg, table)| (enable.expr() * arg, q_item.expr() * table))
+                .collect()
+            },
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn configure_trie(
+        meta: &mut ConstraintSystem<F>,
+        receipt: &ReceiptColumns<F>,
+        trie: &TrieColumns<F>,
+        rlp_table: RlpFsmRlpTable,
+        keccak_table: &KeccakTable,
+        u8_table: U8Table,
+        keccak_rand: &Expression<F>,
+        word_rand: &Expression<F>,
+    ) {
+        let tc = trie.clone();
+        let is_active = move |meta: &mut VirtualCells<'_, F>| {
+            meta.query_advice(tc.is_branch, Rotation::cur())
+                + meta.query_advice(tc.is_extension, Rotation::cur())
+                + meta.query_advice(tc.is_leaf, Rotation::cur())
+        };
+        let node_format = move |meta: &mut VirtualCells<'_, F>| {
+            meta.query_advice(tc.is_branch, Rotation::cur()) * Format::MptBranch.expr()
+                + meta.query_advice(tc.is_extension, Rotation::cur()) * Format::MptExtension.expr()
+                + meta.query_advice(tc.is_leaf, Rotation::cur()) * Format::MptLeaf.expr()
+        };
+        let q_active_node = move |meta: &mut VirtualCells<'_, F>| {
+            meta.query_fixed(tc.q_node, Rotation::cur()) * is_active(meta)
+        };
+        let empty_root = rlc::expr(
+            &empty_trie_root()
+                .to_word()
+                .to_le_bytes()
+                .map(|byte| byte.expr()),
+            word_rand.expr(),
+        );
+
+        meta.create_gate("pi receipts: trie nodes", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_branch = meta.query_advice(tc.is_branch, Rotation::cur());
+            let is_extension = meta.query_advice(tc.is_extension, Rotation::cur());
+            let is_leaf = meta.query_advice(tc.is_leaf, Rotation::cur());
+            for kind in [&is_branch, &is_extension, &is_leaf] {
+                cb.require_boolean("node kind is boolean", kind.expr());
+            }
+            cb.require_boolean("node is active or padding", is_active(meta));
+
+            // the path of an extension or a leaf, hex-prefix encoded
+            let path = tc
+                .path
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let sel = tc
+                .path_sel
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let flag = meta.query_advice(tc.path_flag, Rotation::cur());
+            let first_nibble = meta.query_advice(tc.first_nibble, Rotation::cur());
+            let is_odd = meta.query_advice(tc.is_odd, Rotation::cur());
+            let (mut path_rlc, mut path_value, mut path_nibbles, mut path_pow) =
+                (0.expr(), 0.expr(), 0.expr(), 0.expr());
+            let (mut rlc_acc, mut value_acc) = (0.expr(), 0.expr());
+            for (i, sel) in sel.iter().enumerate() {
+                rlc_acc = rlc_acc * keccak_rand.expr() + path[i].expr();
+                value_acc = if i == 0 {
+                    is_odd.expr() * first_nibble.expr()
+                } else {
+                    value_acc * 256.expr() + path[i].expr()
+                };
+                let pow = (1u64 << (8 * i)).expr() * (1.expr() + is_odd.expr() * 15.expr());
+                path_rlc = path_rlc + sel.expr() * rlc_acc.expr();
+                path_value = path_value + sel.expr() * value_acc.expr();
+                path_nibbles = path_nibbles + sel.expr() * (2 * i).expr();
+                path_pow = path_pow + sel.expr() * pow;
+            }
+            let prefix = meta.query_advice(tc.prefix, Rotation::cur());
+            let prefix_len = meta.query_advice(tc.prefix_len, Rotation::cur());
+            let path_value_col = meta.query_advice(tc.path_value, Rotation::cur());
+            let path_nibbles_col = meta.query_advice(tc.path_nibbles, Rotation::cur());
+            let path_pow_col = meta.query_advice(tc.path_pow, Rotation::cur());
+            cb.condition(is_extension.expr() + is_leaf.expr(), |cb| {
+                cb.require_boolean("path is odd or even", is_odd.expr());
+                cb.require_equal(
+                    "flag of the hex-prefix encoding",
+                    flag.expr(),
+                    is_leaf.expr() * 2.expr() + is_odd.expr(),
+                );
+                cb.require_zero(
+                    "no first nibble in an even path",
+                    not::expr(is_odd.expr()) * first_nibble.expr(),
+                );
+                cb.require_equal(
+                    "first byte of the hex-prefix encoding",
+                    path[0].expr(),
+                    flag * 16.expr() + first_nibble,
+                );
+                for sel in sel.iter() {
+                    cb.require_boolean("path length flag is boolean", sel.expr());
+                }
+                cb.require_equal("path has a single length", sum::expr(&sel), 1.expr());
+                cb.require_equal(
+                    "rlc of the path",
+                    meta.query_advice(tc.path_rlc, Rotation::cur()),
+                    path_rlc,
+                );
+                cb.require_equal("nibbles of the path", path_value_col.expr(), path_value);
+                cb.require_equal(
+                    "number of nibbles of the path",
+                    path_nibbles_col.expr(),
+                    path_nibbles + is_odd.expr(),
+                );
+                cb.require_equal(
+                    "power of the nibbles of the path",
+                    path_pow_col.expr(),
+                    path_pow,
+                );
+                cb.require_equal(
+                    "key of the node",
+                    meta.query_advice(tc.key, Rotation::cur()),
+                    prefix.expr() * path_pow_col + path_value_col,
+                );
+                cb.require_equal(
+                    "length of the key of the node",
+                    meta.query_advice(tc.key_len, Rotation::cur()),
+                    prefix_len.expr() + path_nibbles_col,
+                );
+            });
+
+            cb.gate(meta.query_fixed(tc.q_node, Rotation::cur()))
+        });
+
+        let num_txs_is_zero = tc.num_txs_is_zero.clone();
+        meta.create_gate("pi receipts: trie root", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let active = is_active(meta);
+            cb.require_equal(
+                "root is a node iff there are txs",
+                active.expr(),
+                not::expr(num_txs_is_zero.expr()),
+            );
+            cb.require_zero(
+                "root has no key prefix",
+                meta.query_advice(tc.prefix, Rotation::cur()),
+            );
+            cb.require_zero(
+                "root has an empty key prefix",
+                meta.query_advice(tc.prefix_len, Rotation::cur()),
+            );
+            cb.require_equal(
+                "root of the trie",
+                meta.query_advice(tc.root, Rotation::cur()),
+                active.expr() * meta.query_advice(tc.hash, Rotation::cur())
+                    + not::expr(active) * empty_root,
+            );
+
+            cb.gate(meta.query_fixed(tc.q_root, Rotation::cur()))
+        });
+
+        meta.create_gate("pi receipts: trie node items", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            for column in [
+                tc.is_branch,
+                tc.is_extension,
+                tc.rlp_id,
+                tc.prefix,
+                tc.prefix_len,
+                tc.key,
+                tc.key_len,
+            ] {
+                cb.require_equal(
+                    "node is constant over its items",
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::prev()),
+                );
+            }
+
+            let is_branch = meta.query_advice(tc.is_branch, Rotation::cur());
+            let is_extension = meta.query_advice(tc.is_extension, Rotation::cur());
+            let q_first_child = meta.query_fixed(tc.q_first_child, Rotation::cur());
+            let q_value_slot = meta.query_fixed(tc.q_value_slot, Rotation::cur());
+            let child_none = meta.query_advice(tc.child_none, Rotation::cur());
+            cb.require_boolean("child_none is boolean", child_none.expr());
+            cb.require_equal(
+                "item is a child node",
+                meta.query_advice(tc.child_active, Rotation::cur()),
+                (is_branch.expr() * not::expr(q_value_slot.expr())
+                    + is_extension.expr() * q_first_child.expr())
+                    * not::expr(child_none.expr()),
+            );
+            cb.require_zero(
+                "value of a branch is empty",
+                is_branch.expr() * q_value_slot * not::expr(child_none.expr()),
+            );
+            cb.require_zero(
+                "child of an extension is not empty",
+                is_extension.expr() * q_first_child.expr() * child_none,
+            );
+            cb.require_zero(
+                "child of an extension is a branch",
+                is_extension.expr()
+                    * q_first_child.expr()
+                    * not::expr(meta.query_advice(tc.child_is_branch, Rotation::cur())),
+            );
+            cb.require_equal(
+                "child nodes are counted",
+                meta.query_advice(tc.child_cnt, Rotation::cur()),
+                not::expr(q_first_child) * meta.query_advice(tc.child_cnt, Rotation::prev())
+                    + meta.query_advice(tc.child_active, Rotation::cur()),
+            );
+            cb.require_equal(
+                "key prefix of the child",
+                meta.query_advice(tc.child_prefix, Rotation::cur()),
+                is_branch.expr()
+                    * (meta.query_advice(tc.prefix, Rotation::cur()) * 16.expr()
+                        + meta.query_fixed(tc.nibble, Rotation::cur()))
+                    + is_extension.expr() * meta.query_advice(tc.key, Rotation::cur()),
+            );
+            cb.require_equal(
+                "length of the key prefix of the child",
+                meta.query_advice(tc.child_prefix_len, Rotation::cur()),
+                is_branch * (meta.query_advice(tc.prefix_len, Rotation::cur()) + 1.expr())
+                    + is_extension * meta.query_advice(tc.key_len, Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(tc.q_child, Rotation::cur()))
+        });
+
+        meta.lookup_any(
+            "pi receipts: length of the trie node in RLP table",
+            |meta| {
+                let enable = q_active_node(meta);
+                rlp_output(
+                    meta.query_advice(tc.rlp_id, Rotation::cur()),
+                    node_format(meta),
+                    RlpTag::Len.expr(),
+                    meta.query_advice(tc.len, Rotation::cur()),
+                    0.expr(),
+                    meta.query_advice(tc.len_bytes, Rotation::cur()),
+                    0.expr(),
+                    0.expr(),
+                    0.expr(),
+                )
+                .into_iter()
+                .zip_eq(rlp_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.expr() * arg, table))
+                .collect()
+            },
+        );
+        meta.lookup_any("pi receipts: rlc of the trie node in RLP table", |meta| {
+            let enable = q_active_node(meta);
+            rlp_output(
+                meta.query_advice(tc.rlp_id, Rotation::cur()),
+                node_format(meta),
+                RlpTag::RLC.expr(),
+                meta.query_advice(tc.rlc, Rotation::cur()),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+            )
+            .into_iter()
+            .zip_eq(rlp_table.table_exprs(meta))
+            .map(|(arg, table)| (enable.expr() * arg, table))
+            .collect()
+        });
+        meta.lookup_any(
+            "pi receipts: hash of the trie node in keccak table",
+            |meta| {
+                let enable = q_active_node(meta);
+                vec![
+                    1.expr(), // q_enable
+                    1.expr(), // is_final
+                    meta.query_advice(tc.rlc, Rotation::cur()),
+                    meta.query_advice(tc.len, Rotation::cur()),
+                    meta.query_advice(tc.hash, Rotation::cur()),
+                ]
+                .into_iter()
+                .zip_eq(keccak_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.expr() * arg, table))
+                .collect()
+            },
+        );
+        meta.lookup_any("pi receipts: path of the trie node in RLP table", |meta| {
+            let enable = meta.query_fixed(tc.q_node, Rotation::cur())
+                * (meta.query_advice(tc.is_extension, Rotation::cur())
+                    + meta.query_advice(tc.is_leaf, Rotation::cur()));
+            let path_rlc = meta.query_advice(tc.path_rlc, Rotation::cur());
+            let path_len = sum::expr(
+                tc.path_sel
+                    .iter()
+                    .zip(1u64..)
+                    .map(|(column, len)| meta.query_advice(*column, Rotation::cur()) * len.expr()),
+            );
+            rlp_output(
+                meta.query_advice(tc.rlp_id, Rotation::cur()),
+                node_format(meta),
+                Tag::MptPath.expr(),
+                path_rlc.expr(),
+                path_rlc,
+                path_len,
+                0.expr(),
+                0.expr(),
+                0.expr(),
+            )
+            .into_iter()
+            .zip_eq(rlp_table.table_exprs(meta))
+            .map(|(arg, table)| (enable.expr() * arg, table))
+            .collect()
+        });
+        meta.lookup_any("pi receipts: value of the leaf in RLP table", |meta| {
+            let enable = meta.query_fixed(tc.q_node, Rotation::cur())
+                * meta.query_advice(tc.is_leaf, Rotation::cur());
+            let value_rlc = meta.query_advice(tc.value_rlc, Rotation::cur());
+            rlp_output(
+                meta.query_advice(tc.rlp_id, Rotation::cur()),
+                Format::MptLeaf.expr(),
+                Tag::Data.expr(),
+                value_rlc.expr(),
+                value_rlc,
+                meta.query_advice(tc.value_len, Rotation::cur()),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+            )
+            .into_iter()
+            .zip_eq(rlp_table.table_exprs(meta))
+            .map(|(arg, table)| (enable.expr() * arg, table))
+            .collect()
+        });
+        meta.lookup_any("pi receipts: item of the trie node in RLP table", |meta| {
+            let is_branch = meta.query_advice(tc.is_branch, Rotation::cur());
+            let is_extension = meta.query_advice(tc.is_extension, Rotation::cur());
+            let q_first_child = meta.query_fixed(tc.q_first_child, Rotation::cur());
+            let enable = meta.query_fixed(tc.q_child, Rotation::cur())
+                * (is_branch.expr() + is_extension.expr() * q_first_child);
+            let child_none = meta.query_advice(tc.child_none, Rotation::cur());
+            // the items of a node are indexed from 1
+            rlp_output(
+                meta.query_advice(tc.rlp_id, Rotation::cur()),
+                is_branch.expr() * Format::MptBranch.expr()
+                    + is_extension.expr() * Format::MptExtension.expr(),
+                Tag::MptChild.expr(),
+                meta.query_advice(tc.child_hash, Rotation::cur()),
+                meta.query_advice(tc.child_bytes_rlc, Rotation::cur()),
+                not::expr(child_none.expr()) * 32.expr(),
+                child_none,
+                is_branch * (meta.query_fixed(tc.nibble, Rotation::cur()) + 1.expr())
+                    + is_extension,
+                0.expr(),
+            )
+            .into_iter()
+            .zip_eq(rlp_table.table_exprs(meta))
+            .map(|(arg, table)| (enable.expr() * arg, table))
+            .collect()
+        });
+
+        meta.lookup_any(
+            "pi receipts: trie node is the child of its parent",
+            |meta| {
+                let enable = (meta.query_fixed(tc.q_node, Rotation::cur())
+                    - meta.query_fixed(tc.q_root, Rotation::cur()))
+                    * is_active(meta);
+                let q_child = meta.query_fixed(tc.q_child, Rotation::cur())
+                    * meta.query_advice(tc.child_active, Rotation::cur());
+                [
+                    (tc.hash, tc.child_hash),
+                    (tc.prefix, tc.child_prefix),
+                    (tc.prefix_len, tc.child_prefix_len),
+                ]
+                .into_iter()
+                .map(|(arg, table)| {
+                    (
+                        meta.query_advice(arg, Rotation::cur()),
+                        meta.query_advice(table, Rotation::cur()),
+                    )
+                })
+                .chain(iter::once((1.expr(), 1.expr())))
+                .map(|(arg, table)| (enable.expr() * arg, q_child.expr() * table))
+                .collect()
+            },
+        );
+        meta.lookup_any("pi receipts: child of a trie node is a node", |meta| {
+            let enable = meta.query_fixed(tc.q_child, Rotation::cur())
+                * meta.query_advice(tc.child_active, Rotation::cur());
+            let q_node = q_active_node(meta);
+            [
+                (tc.child_hash, tc.hash),
+                (tc.child_prefix, tc.prefix),
+                (tc.child_prefix_len, tc.prefix_len),
+            ]
+            .into_iter()
+            .map(|(arg, table)| {
+                (
+                    meta.query_advice(arg, Rotation::cur()),
+                    meta.query_advice(table, Rotation::cur()),
+                )
+            })
+            .chain([
+                (1.expr(), 1.expr()),
+                (
+                    meta.query_advice(tc.child_is_branch, Rotation::cur()),
+                    meta.query_advice(tc.is_branch, Rotation::cur()),
+                ),
+            ])
+            .map(|(arg, table)| (enable.expr() * arg, q_node.expr() * table))
+            .collect()
+        });
+        meta.lookup_any("pi receipts: leaf of the trie is a receipt", |meta| {
+            let enable = meta.query_fixed(tc.q_node, Rotation::cur())
+                * meta.query_advice(tc.is_leaf, Rotation::cur());
+            let q_real = meta.query_fixed(receipt.q_enable, Rotation::cur())
+                * meta.query_advice(receipt.is_real, Rotation::cur());
+            vec![
+                (1.expr(), 1.expr()),
+                (
+                    meta.query_advice(tc.key, Rotation::cur()),
+                    meta.query_fixed(receipt.key, Rotation::cur()),
+                ),
+                (
+                    meta.query_advice(tc.key_len, Rotation::cur()),
+                    meta.query_fixed(receipt.key_len, Rotation::cur()),
+                ),
+                (
+                    meta.query_advice(tc.value_rlc, Rotation::cur()),
+                    meta.query_advice(receipt.rlc, Rotation::cur()),
+                ),
+                (
+                    meta.query_advice(tc.value_len, Rotation::cur()),
+                    meta.query_advice(receipt.len, Rotation::cur()),
+                ),
+            ]
+            .into_iter()
+            .map(|(arg, table)| (enable.expr() * arg, q_real.expr() * table))
+            .collect()
+        });
+
+        meta.lookup("pi receipts: branch has at least 2 children", |meta| {
+            let enable = meta.query_fixed(tc.q_value_slot, Rotation::cur())
+                * meta.query_advice(tc.is_branch, Rotation::cur());
+            vec![(
+                enable * (meta.query_advice(tc.child_cnt, Rotation::cur()) - 2.expr()),
+                u8_table.into(),
+            )]
+        });
+        meta.lookup("pi receipts: path of an extension is not empty", |meta| {
+            let enable = meta.query_fixed(tc.q_node, Rotation::cur())
+                * meta.query_advice(tc.is_extension, Rotation::cur());
+            vec![(
+                enable * (meta.query_advice(tc.path_nibbles, Rotation::cur()) - 1.expr()),
+                u8_table.into(),
+            )]
+        });
+        meta.lookup("pi receipts: hex-prefix flag range", |meta| {
+            let q_node = meta.query_fixed(tc.q_node, Rotation::cur());
+            vec![(
+                q_node * meta.query_advice(tc.path_flag, Rotation::cur()) * 16.expr(),
+                u8_table.into(),
+            )]
+        });
+        meta.lookup(
+            "pi receipts: first nibble of the path is below 16",
+            |meta| {
+                let q_node = meta.query_fixed(tc.q_node, Rotation::cur());
+                vec![(
+                    q_node * meta.query_advice(tc.first_nibble, Rotation::cur()) * 16.expr(),
+                    u8_table.into(),
+                )]
+            },
+        );
+    }
+}
+
+/// A row of the logs.
+#[derive(Clone, Copy, Debug)]
+struct LogRow<F> {
+    /// The flag set by the row, none on the padding rows
+    flag: Option<usize>,
+    is_log_end: bool,
+    is_tx_end: bool,
+    tx_id: u64,
+    log_id: u64,
+    rlp_id: u64,
+    format: u64,
+    value: Value<F>,
+    rw_counter: u64,
+    next_rw: [F; 3],
+    next_topic_inv: F,
+    next_data_inv: F,
+    item_rlc: Value<F>,
+    hash_bytes: [u8; N_HASH_BYTES_PER_ROW],
+    hash_acc: Value<F>,
+    /// The bytes of the hash giving the bloom index set by the row
+    bloom_bytes: [u8; 2],
+    topic_cnt: u64,
+    data_cnt: u64,
+    data_rlc: Value<F>,
+    is_small: bool,
+    /// Length of the encoding of the log without its list header, on its last
+    /// row
+    log_payload_len: usize,
+    logs_acc: u64,
+}
+
+impl<F: Field> Default for LogRow<F> {
+    fn default() -> Self {
+        Self {
+            flag: None,
+            is_log_end: false,
+            is_tx_end: false,
+            tx_id: 0,
+            log_id: 0,
+            rlp_id: 0,
+            format: 0,
+            value: Value::known(F::zero()),
+            rw_counter: 0,
+            next_rw: [F::zero(); 3],
+            next_topic_inv: F::zero(),
+            next_data_inv: F::zero(),
+            item_rlc: Value::known(F::zero()),
+            hash_bytes: [0; N_HASH_BYTES_PER_ROW],
+            hash_acc: Value::known(F::zero()),
+            bloom_bytes: [0; 2],
+            topic_cnt: 0,
+            data_cnt: 0,
+            data_rlc: Value::known(F::zero()),
+            is_small: false,
+            log_payload_len: 0,
+            logs_acc: 0,
+        }
+    }
+}
+
+/// The tag, id and address of a row of the rw table, zero past its end.
+fn rw_key<F: Field>(rw: Option<&Rw>) -> [F; 3] {
+    rw.map_or([F::zero(); 3], |rw| {
+        [
+            F::from(rw.tag() as u64),
+            F::from(rw.id().unwrap_or_default() as u64),
+            rw.address().unwrap_or_default().to_scalar().unwrap(),
+        ]
+    })
+}
+
+/// The inverse of the difference between the key of a row of the rw table and
+/// the one of an item of a log, or zero if they are equal.
+fn tx_log_key_diff_inv<F: Field>(
+    rw_key: [F; 3],
+    tx_id: usize,
+    log_id: u64,
+    field_tag: TxLogFieldTag,
+    index: u64,
+) -> F {
+    let [tag, id, address] = rw_key;
+    let log_address = F::from(index)
+        + F::from(field_tag as u64) * pow_of_two::<F>(32)
+        + F::from(log_id) * pow_of_two::<F>(48);
+    let diff = (tag - F::from(RwTableTag::TxLog as u64)) * pow_of_two::<F>(224)
+        + (id - F::from(tx_id as u64)) * pow_of_two::<F>(160)
+        + address
+        - log_address;
+    diff.invert().unwrap_or(F::zero())
+}
+
+/// The rows of the logs of the receipts, whose ids in the RLP table start at
+/// `first_rlp_id`. The rw counter of each item of a log is given with the key
+/// of the row following it in the rw table.
+fn log_rows<F: Field>(
+    receipts: &[Receipt],
+    first_rlp_id: u64,
+    log_rws: &HashMap<(usize, u64, u64, usize), (usize, [F; 3])>,
+    challenges: &Challenges<Value<F>>,
+) -> Vec<LogRow<F>> {
+    let keccak_rand = challenges.keccak_input();
+    let word_rand = challenges.evm_word();
+    let log_rw = |tx_id: usize, log_id: u64, field_tag: TxLogFieldTag, index: usize| {
+        let (rw_counter, next_rw) = log_rws
+            .get(&(tx_id, log_id, field_tag as u64, index))
+            .copied()
+            .unwrap_or((0, [F::zero(); 3]));
+        (rw_counter as u64, next_rw)
+    };
+
+    let mut rows = vec![];
+    for (tx_idx, receipt) in receipts.iter().enumerate() {
+        let tx_id = tx_idx + 1;
+        let mut logs_acc = 0;
+        for (log_id, log) in (1..).zip(receipt.logs.iter()) {
+            let log_row = LogRow {
+                tx_id: tx_id as u64,
+                log_id,
+                rlp_id: first_rlp_id + tx_idx as u64,
+                format: receipt_format(receipt) as u64,
+                logs_acc: logs_acc as u64,
+                ..Default::default()
+            };
+            for (index, item) in log.bloom_items().enumerate() {
+                let (flag, value, (item_rw_counter, next_rw)) = if index == 0 {
+                    (
+                        IS_ADDRESS,
+                        Value::known(log.address.to_scalar().unwrap()),
+                        log_rw(tx_id, log_id, TxLogFieldTag::Address, 0),
+                    )
+                } else {
+                    (
+                        IS_TOPIC,
+                        rlc_be_bytes(item, word_rand),
+                        log_rw(tx_id, log_id, TxLogFieldTag::Topic, index - 1),
+                    )
+                };
+                let hash = keccak256(item);
+                for (i, hash_bytes) in hash.chunks(N_HASH_BYTES_PER_ROW).enumerate() {
+                    let mut row = LogRow {
+                        flag: Some(if i == 0 { flag } else { IS_HASH_1 + i - 1 }),
+                        hash_bytes: hash_bytes.try_into().unwrap(),
+                        hash_acc: rlc_be_bytes(&hash[..N_HASH_BYTES_PER_ROW * (i + 1)], word_rand),
+                        topic_cnt: index as u64,
+                        ..log_row
+                    };
+                    if i == 0 {
+                        row.value = value;
+                        row.rw_counter = item_rw_counter;
+                        row.next_rw = next_rw;
+                        row.next_topic_inv = tx_log_key_diff_inv(
+                            next_rw,
+                            tx_id,
+                            log_id,
+                            TxLogFieldTag::Topic,
+                            index as u64,
+                        );
+                        row.next_data_inv =
+                            tx_log_key_diff_inv(next_rw, tx_id, log_id, TxLogFieldTag::Data, 0);
+                        row.item_rlc = rlc_be_bytes(item, keccak_rand);
+                    }
+                    if i < ROWS_PER_ITEM - 1 {
+                        row.bloom_bytes = [hash[2 * i], hash[2 * i + 1]];
+                    }
+                    rows.push(row);
+                }
+            }
+            let mut data_rlc = Value::known(F::zero());
+            for (index, &byte) in log.data.iter().enumerate() {
+                data_rlc = data_rlc * keccak_rand + Value::known(F::from(byte as u64));
+                let (rw_counter, next_rw) = log_rw(tx_id, log_id, TxLogFieldTag::Data, index);
+                rows.push(LogRow {
+                    flag: Some(IS_DATA),
+                    value: known(byte as u64),
+                    rw_counter,
+                    next_rw,
+                    next_data_inv: tx_log_key_diff_inv(
+                        next_rw,
+                        tx_id,
+                        log_id,
+                        TxLogFieldTag::Data,
+                        index as u64 + 1,
+                    ),
+                    topic_cnt: log.topics.len() as u64,
+                    data_cnt: index as u64 + 1,
+                    data_rlc,
+                    is_small: byte < 0x80,
+                    ..log_row
+                });
+            }
+
+            let last = rows.last_mut().expect("a log has an address");
+            last.is_log_end = true;
+            last.is_tx_end = log_id as usize == receipt.logs.len();
+            last.log_payload_len = log_payload_len(log);
+            logs_acc += log_rlp_len(log);
+        }
+    }
+    rows
+}
+
+/// The length of the RLP encoding of a log without its list header: its
+/// address, its topics and its data.
+fn log_payload_len(log: &ReceiptLog) -> usize {
+    let topics_len = 33 * log.topics.len();
+    let data_len = match log.data.as_slice() {
+        [byte] if *byte < 0x80 => 1,
+        data => header_len(data.len()) + data.len(),
+    };
+    21 + header_len(topics_len) + topics_len + data_len
+}
+
+/// The length of the RLP encoding of a log.
+fn log_rlp_len(log: &ReceiptLog) -> usize {
+    let payload_len = log_payload_len(log);
+    debug_assert_eq!(
+        header_len(payload_len) + payload_len,
+        RlpStream::new_list(3)
+            .append(&log.address)
+            .append_list(&log.topics)
+            .append(&log.data)
+            .out()
+            .len()
+    );
+    header_len(payload_len) + payload_len
+}
+
+fn receipt_format(receipt: &Receipt) -> Format {
+    if receipt.tx_type == 0 {
+        Format::Receipt
+    } else {
+        Format::TypedReceipt
+    }
+}
+
+/// Maximum number of nodes of the receipts trie of `max_txs` receipts: as many
+/// leaves, fewer branches, and at most one extension per branch.
+fn max_trie_nodes(max_txs: usize) -> usize {
+    (3 * max_txs).max(1)
+}
+
+/// A node of the receipts trie with its id in the RLP table, its RLP encoding
+/// and its key prefix.
+struct NodeWitness<'a> {
+    id: u64,
+    bytes: &'a [u8],
+    node: &'a TrieNode,
+    prefix: &'a [u8],
+}
+
+/// The integer of big endian nibbles.
+fn nibbles_value(nibbles: &[u8]) -> u64 {
+    nibbles
+        .iter()
+        .fold(0, |acc, &nibble| (acc << 4) | nibble as u64)
+}
+
+impl<F: Field> ReceiptsConfig<F> {
+    /// Number of rows of the section.
+    pub(super) fn min_num_rows(max_txs: usize, max_calldata: usize) -> usize {
+        max_txs
+            + max_calldata
+            + 1
+            + N_BLOOM_BITS * (max_txs + 1)
+            + ROWS_PER_NODE * max_trie_nodes(max_txs)
+    }
+
+    /// Assign the section, returning the cells of the number of txs, the
+    /// receipts root and the logs bloom.
+    pub(super) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        public_data: &PublicData,
+        #[cfg(any(feature = "test", test, feature = "test-circuits"))] overrides: &HashMap<
+            (ReceiptsColumn, usize),
+            F,
+        >,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<ReceiptsCells<F>, Error> {
+        let receipts = public_data.receipts();
+        let mut receipt_rw_counters = HashMap::new();
+        let mut log_rws = HashMap::new();
+        // the rws are in the order of the rw table
+        for (i, rw) in public_data.receipt_rws.iter().enumerate() {
+            match *rw {
+                Rw::TxReceipt {
+                    rw_counter,
+                    tx_id,
+                    field_tag,
+                    ..
+                } => {
+                    receipt_rw_counters.insert((tx_id, field_tag as u64), rw_counter);
+                }
+                Rw::TxLog {
+                    rw_counter,
+                    tx_id,
+                    log_id,
+                    field_tag,
+                    index,
+                    ..
+                } => {
+                    let next_rw = rw_key(public_data.receipt_rws.get(i + 1));
+                    log_rws.insert(
+                        (tx_id, log_id, field_tag as u64, index),
+                        (rw_counter, next_rw),
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        layouter.assign_table(
+            || "pi receipts: tx types",
+            |mut table| {
+                for (offset, tx_type) in TxType::iter().enumerate() {
+                    for (column, value) in self
+                        .tx_types
+                        .into_iter()
+                        .zip_eq([tx_type as u64, tx_type.eip2718_type() as u64])
+                    {
+                        table.assign_cell(
+                            || "tx type",
+                            column,
+                            offset,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )?;
+
+        let num_txs = layouter.assign_region(
+            || "pi receipts: receipts",
+            |mut region| {
+                self.assign_receipts(
+                    &mut region,
+                    public_data,
+                    &receipts,
+                    &receipt_rw_counters,
+                    challenges,
+                )
+            },
+        )?;
+        layouter.assign_region(
+            || "pi receipts: logs",
+            |mut region| {
+                let rows = log_rows(
+                    &receipts,
+                    public_data.first_receipt_rlp_id,
+                    &log_rws,
+                    challenges,
+                );
+                self.assign_logs(&mut region, public_data.max_calldata + 1, rows)?;
+                #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+                self.assign_overrides(&mut region, overrides, ReceiptsColumn::LogValue)?;
+                Ok(())
+            },
+        )?;
+        let bloom = layouter.assign_region(
+            || "pi receipts: blooms",
+            |mut region| {
+                let bloom =
+                    self.assign_blooms(&mut region, public_data.max_txs, &receipts, challenges)?;
+                #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+                self.assign_overrides(&mut region, overrides, ReceiptsColumn::BloomBit)?;
+                Ok(bloom)
+            },
+        )?;
+        let root = layouter.assign_region(
+            || "pi receipts: trie",
+            |mut region| {
+                let root =
+                    self.assign_trie(&mut region, public_data, &receipts, &num_txs, challenges)?;
+                #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+                self.assign_overrides(&mut region, overrides, ReceiptsColumn::NodeRlc)?;
+                Ok(root)
+            },
+        )?;
+
+        Ok(ReceiptsCells {
+            num_txs,
+            root,
+            bloom,
+        })
+    }
+
+    /// Override the cells of `column` in its region.
+    #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+    fn assign_overrides(
+        &self,
+        region: &mut Region<'_, F>,
+        overrides: &HashMap<(ReceiptsColumn, usize), F>,
+        column: ReceiptsColumn,
+    ) -> Result<(), Error> {
+        for (&(_, offset), &value) in overrides.iter().filter(|((c, _), _)| *c == column) {
+            region.assign_advice(
+                || "pi receipts: override",
+                column.value(self),
+                offset,
+                || Value::known(value),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn assign_receipts(
+        &self,
+        region: &mut Region<'_, F>,
+        public_data: &PublicData,
+        receipts: &[Receipt],
+        rw_counters: &HashMap<(usize, u64), usize>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let rc = &self.receipt;
+        let keccak_rand = challenges.keccak_input();
+
+        let mut num_txs = None;
+        for offset in 0..public_data.max_txs {
+            let tx_id = offset + 1;
+            let key = receipt_key(offset);
+            for (column, value) in [
+                (rc.q_enable, 1),
+                (rc.q_last, (offset + 1 == public_data.max_txs) as u64),
+                (rc.tx_id, tx_id as u64),
+                (
+                    rc.key,
+                    key.iter().fold(0, |acc, &byte| (acc << 8) | byte as u64),
+                ),
+                (rc.key_len, 2 * key.len() as u64),
+            ] {
+                region.assign_fixed(
+                    || "pi receipts: receipt",
+                    column,
+                    offset,
+                    || Value::known(F::from(value)),
+                )?;
+            }
+
+            let remaining = receipts.len().saturating_sub(offset) as u64;
+            let cell = region.assign_advice(
+                || "pi receipts: remaining txs",
+                rc.remaining,
+                offset,
+                || Value::known(F::from(remaining)),
+            )?;
+            if offset == 0 {
+                num_txs = Some(cell);
+            }
+
+            let receipt = receipts.get(offset);
+            let type_byte = receipt.map_or(0, |receipt| receipt.tx_type as u64);
+            let gas = receipt.map_or(0, |receipt| receipt.cumulative_gas_used);
+            let gas_be_bytes = gas.to_be_bytes();
+            let gas_bytes = &gas_be_bytes[gas.leading_zeros() as usize / 8..];
+            let gas_is_small = (1..0x80).contains(&gas);
+            let log_len = receipt.map_or(0, |receipt| receipt.logs.len() as u64);
+            let logs_len = receipt.map_or(0, |receipt| receipt.logs.iter().map(log_rlp_len).sum());
+            let bytes = receipt.map(Receipt::rlp_bytes).unwrap_or_default();
+            let rlp_id = receipt.map_or(0, |_| public_data.first_receipt_rlp_id + offset as u64);
+            let tx_type = public_data
+                .transactions
+                .get(offset)
+                .map_or(0, |tx| tx.tx_type as u64);
+            let bloom_rlc = receipt.map_or(Value::known(F::zero()), |receipt| {
+                rlc_be_bytes(receipt.bloom().as_bytes(), keccak_rand)
+            });
+            let rw_counters = [
+                TxReceiptFieldTag::PostStateOrStatus,
+                TxReceiptFieldTag::CumulativeGasUsed,
+                TxReceiptFieldTag::LogLength,
+            ]
+            .map(|field_tag| {
+                known(
+                    rw_counters
+                        .get(&(tx_id, field_tag as u64))
+                        .copied()
+                        .unwrap_or_default() as u64,
+                )
+            });
+
+            for (column, value) in [
+                (rc.is_real, known(receipt.is_some() as u64)),
+                (rc.rlp_id, known(rlp_id)),
+                (rc.tx_type, known(tx_type)),
+                (rc.type_byte, known(type_byte)),
+                (rc.is_typed, known((type_byte != 0) as u64)),
+                (
+                    rc.status,
+                    known(receipt.map_or(0, |receipt| receipt.status as u64)),
+                ),
+                (rc.gas, known(gas)),
+                (rc.gas_bytes_rlc, rlc_be_bytes(gas_bytes, keccak_rand)),
+                (rc.gas_len, known(gas_bytes.len() as u64)),
+                (rc.gas_is_small, known(gas_is_small as u64)),
+                (rc.log_len, known(log_len)),
+                (rc.has_logs, known((log_len != 0) as u64)),
+                (rc.logs_len, known(logs_len as u64)),
+                (rc.bloom_rlc, bloom_rlc),
+                (rc.rlc, rlc_be_bytes(&bytes, keccak_rand)),
+                (rc.len, known(bytes.len() as u64)),
+            ]
+            .into_iter()
+            .chain(rc.rw_counters.into_iter().zip_eq(rw_counters))
+            {
+                region.assign_advice(|| "pi receipts: receipt", column, offset, || value)?;
+            }
+
+            for (config, value) in [
+                (&rc.remaining_is_zero, known(remaining)),
+                (&rc.type_byte_is_zero, known(type_byte)),
+                (&rc.gas_is_zero, known(gas)),
+                (
+                    &rc.gas_len_is_one,
+                    known::<F>(gas_bytes.len() as u64) - known(1),
+                ),
+                (&rc.log_len_is_zero, known(log_len)),
+            ] {
+                IsZeroChip::construct(config.clone()).assign(region, offset, value)?;
+            }
+            rc.logs_header.assign(region, offset, logs_len)?;
+            rc.payload_header.assign(
+                region,
+                offset,
+                N_BYTES_STATUS_AND_BLOOM as usize
+                    + gas_bytes.len()
+                    + !gas_is_small as usize
+                    + header_len(logs_len)
+                    + logs_len,
+            )?;
+        }
+
+        Ok(num_txs.expect("at least one tx is supported"))
+    }
+
+    fn assign_logs(
+        &self,
+        region: &mut Region<'_, F>,
+        n_rows: usize,
+        rows: Vec<LogRow<F>>,
+    ) -> Result<(), Error> {
+        let lc = &self.log;
+        assert!(
+            rows.len() < n_rows,
+            "{} rows of logs exceed the {} rows of the section",
+            rows.len(),
+            n_rows - 1
+        );
+
+        for (offset, row) in rows
+            .into_iter()
+            .chain(iter::repeat(LogRow::default()))
+            .take(n_rows)
+            .enumerate()
+        {
+            for (column, value) in [
+                (lc.q_enable, true),
+                (lc.q_first, offset == 0),
+                (lc.q_last, offset + 1 == n_rows),
+            ] {
+                region.assign_fixed(
+                    || "pi receipts: log",
+                    column,
+                    offset,
+                    || Value::known(F::from(value as u64)),
+                )?;
+            }
+
+            let [hi, lo] = row.bloom_bytes;
+            let log_enc = if row.is_log_end {
+                header_len(row.log_payload_len) + row.log_payload_len
+            } else {
+                0
+            };
+            for (column, value) in [
+                (lc.is_log_end, known(row.is_log_end as u64)),
+                (lc.is_tx_end, known(row.is_tx_end as u64)),
+                (lc.tx_id, known(row.tx_id)),
+                (lc.log_id, known(row.log_id)),
+                (lc.rlp_id, known(row.rlp_id)),
+                (lc.format, known(row.format)),
+                (lc.value, row.value),
+                (lc.rw_counter, known(row.rw_counter)),
+                (lc.next_topic_inv, Value::known(row.next_topic_inv)),
+                (lc.next_data_inv, Value::known(row.next_data_inv)),
+                (lc.item_rlc, row.item_rlc),
+                (lc.hash_acc, row.hash_acc),
+                (lc.bloom_q, known(hi as u64 >> 3)),
+                (lc.bloom_low3, known(hi as u64 & 7)),
+                (lc.bloom_lo, known(lo as u64)),
+                (lc.topic_cnt, known(row.topic_cnt)),
+                (lc.data_cnt, known(row.data_cnt)),
+                (lc.data_rlc, row.data_rlc),
+                (lc.is_small, known(row.is_small as u64)),
+                (lc.topics_long, known((row.topic_cnt > 1) as u64)),
+                (lc.log_enc, known(log_enc as u64)),
+                (lc.logs_acc, known(row.logs_acc)),
+            ]
+            .into_iter()
+            .chain(
+                lc.flags
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, column)| (column, known((row.flag == Some(i)) as u64))),
+            )
+            .chain(
+                lc.hash_bytes
+                    .into_iter()
+                    .zip_eq(row.hash_bytes)
+                    .map(|(column, byte)| (column, known(byte as u64))),
+            )
+            .chain(
+                lc.next_rw
+                    .into_iter()
+                    .zip_eq(row.next_rw)
+                    .map(|(column, value)| (column, Value::known(value))),
+            ) {
+                region.assign_advice(|| "pi receipts: log", column, offset, || value)?;
+            }
+
+            let data_cnt = F::from(row.data_cnt);
+            IsZeroChip::construct(lc.data_is_zero.clone()).assign(
+                region,
+                offset,
+                Value::known(data_cnt),
+            )?;
+            IsZeroChip::construct(lc.data_is_one.clone()).assign(
+                region,
+                offset,
+                Value::known(data_cnt - F::one()),
+            )?;
+            lc.data_header
+                .assign(region, offset, row.data_cnt as usize)?;
+            lc.log_header.assign(region, offset, row.log_payload_len)?;
+        }
+
+        Ok(())
+    }
+
+    fn assign_blooms(
+        &self,
+        region: &mut Region<'_, F>,
+        max_txs: usize,
+        receipts: &[Receipt],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let bc = self.bloom;
+        let keccak_rand = challenges.keccak_input();
+        let word_rand = challenges.evm_word();
+
+        let blooms = iter::once(logs_bloom(receipts))
+            .chain(receipts.iter().map(Receipt::bloom))
+            .chain(iter::repeat(Bloom::zero()))
+            .take(max_txs + 1);
+        let mut chunk_bloom = None;
+        for (bloom_id, bloom) in blooms.enumerate() {
+            let (mut rlc, mut word_rlc) = (Value::known(F::zero()), Value::known(F::zero()));
+            for (i, &byte) in bloom.0.iter().enumerate() {
+                let (prev_rlc, prev_word_rlc) = (rlc, word_rlc);
+                rlc = rlc * keccak_rand + Value::known(F::from(byte as u64));
+                word_rlc = word_rlc * word_rand + Value::known(F::from(byte as u64));
+                for j in 0..8 {
+                    let bit_pos = 8 * i + j;
+                    let offset = bloom_id * N_BLOOM_BITS + bit_pos;
+                    for (column, value) in [
+                        (bc.q_enable, 1),
+                        (bc.bloom_id, bloom_id as u64),
+                        (bc.bit_idx, (N_BLOOM_BITS - 1 - bit_pos) as u64),
+                        (bc.q_byte_first, (j == 0) as u64),
+                        (bc.q_byte_last, (j == 7) as u64),
+                        (bc.q_bloom_first, (bit_pos == 0) as u64),
+                        (bc.q_bloom_last, (bit_pos == N_BLOOM_BITS - 1) as u64),
+                        (bc.q_tx_bloom, (bloom_id != 0) as u64),
+                        (bc.q_chunk_bloom, (bloom_id == 0) as u64),
+                    ] {
+                        region.assign_fixed(
+                            || "pi receipts: bloom",
+                            column,
+                            offset,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+
+                    // the rlcs take the byte on its last bit
+                    let (rlc, word_rlc) = if j == 7 {
+                        (rlc, word_rlc)
+                    } else {
+                        (prev_rlc, prev_word_rlc)
+                    };
+                    for (column, value) in [
+                        (bc.bit, known((byte >> (7 - j)) as u64 & 1)),
+                        (bc.byte_acc, known((byte >> (7 - j)) as u64)),
+                        (bc.rlc, rlc),
+                    ] {
+                        region.assign_advice(|| "pi receipts: bloom", column, offset, || value)?;
+                    }
+                    let cell = region.assign_advice(
+                        || "pi receipts: bloom word rlc",
+                        bc.word_rlc,
+                        offset,
+                        || word_rlc,
+                    )?;
+                    if bloom_id == 0 && bit_pos == N_BLOOM_BITS - 1 {
+                        chunk_bloom = Some(cell);
+                    }
+                }
+            }
+        }
+
+        Ok(chunk_bloom.expect("the bloom of the chunk is assigned"))
+    }
+
+    fn assign_trie(
+        &self,
+        region: &mut Region<'_, F>,
+        public_data: &PublicData,
+        receipts: &[Receipt],
+        num_txs: &AssignedCell<F, F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let tc = &self.trie;
+        let trie = ReceiptsTrie::new(receipts);
+        let nodes = trie
+            .nodes
+            .iter()
+            .map(|bytes| TrieNode::decode(bytes).expect("receipts trie nodes are well formed"))
+            .collect::<Vec<_>>();
+        let node_index = trie
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, bytes)| (H256(keccak256(bytes)), index))
+            .collect::<HashMap<_, _>>();
+        let branches = node_index
+            .iter()
+            .filter(|(_, &index)| matches!(nodes[index], TrieNode::Branch(_)))
+            .map(|(hash, _)| *hash)
+            .collect::<HashSet<_>>();
+        // the trie nodes follow the receipts in the RLP table
+        let first_node_id = public_data.first_receipt_rlp_id + receipts.len() as u64;
+
+        // walk the trie from its root, the last node, to get the key prefix of
+        // each node
+        let mut stack = trie
+            .nodes
+            .len()
+            .checked_sub(1)
+            .map(|root| (root, vec![]))
+            .into_iter()
+            .collect::<Vec<_>>();
+        let mut walk = vec![];
+        while let Some((index, prefix)) = stack.pop() {
+            match &nodes[index] {
+                TrieNode::Branch(children) => {
+                    for (nibble, child) in children.iter().enumerate().rev() {
+                        if !child.is_empty() {
+                            let child_prefix = [prefix.as_slice(), &[nibble as u8]].concat();
+                            stack.push((node_index[&H256::from_slice(child)], child_prefix));
+                        }
+                    }
+                }
+                TrieNode::Extension(path, child) => {
+                    let child_prefix = [prefix.as_slice(), path.as_slice()].concat();
+                    stack.push((node_index[&H256::from_slice(child)], child_prefix));
+                }
+                TrieNode::Leaf(..) => {}
+            }
+            walk.push((index, prefix));
+        }
+
+        let n_nodes = max_trie_nodes(public_data.max_txs);
+        assert!(walk.len() <= n_nodes, "receipts trie has too many nodes");
+        for (i, node) in walk
+            .iter()
+            .map(|(index, prefix)| {
+                Some(NodeWitness {
+                    id: first_node_id + *index as u64,
+                    bytes: &trie.nodes[*index],
+                    node: &nodes[*index],
+                    prefix,
+                })
+            })
+            .chain(iter::repeat_with(|| None))
+            .take(n_nodes)
+            .enumerate()
+        {
+            self.assign_node(region, i * ROWS_PER_NODE, node, &branches, challenges)?;
+        }
+
+        let num_txs =
+            num_txs.copy_advice(|| "pi receipts: number of txs", region, tc.num_txs, 0)?;
+        IsZeroChip::construct(tc.num_txs_is_zero.clone()).assign(
+            region,
+            0,
+            num_txs.value().copied(),
+        )?;
+        region.assign_advice(
+            || "pi receipts: root",
+            tc.root,
+            0,
+            || rlc_be_bytes(trie.root.as_bytes(), challenges.evm_word()),
+        )
+    }
+
+    fn assign_node(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        node: Option<NodeWitness<'_>>,
+        branches: &HashSet<H256>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let tc = &self.trie;
+        let keccak_rand = challenges.keccak_input();
+        let word_rand = challenges.evm_word();
+
+        for row in 0..ROWS_PER_NODE {
+            for (column, value) in [
+                (tc.q_node, (row == 0) as u64),
+                (tc.q_child, (row != 0) as u64),
+                (tc.q_root, (offset == 0 && row == 0) as u64),
+                (tc.q_first_child, (row == 1) as u64),
+                (tc.q_value_slot, (row == ROWS_PER_NODE - 1) as u64),
+                (tc.nibble, row.saturating_sub(1) as u64),
+            ] {
+                region.assign_fixed(
+                    || "pi receipts: trie",
+                    column,
+                    offset + row,
+                    || Value::known(F::from(value)),
+                )?;
+            }
+        }
+
+        let trie_node = node.as_ref().map(|node| node.node);
+        let is_branch = matches!(trie_node, Some(TrieNode::Branch(_)));
+        let is_extension = matches!(trie_node, Some(TrieNode::Extension(..)));
+        let is_leaf = matches!(trie_node, Some(TrieNode::Leaf(..)));
+        let prefix = node.as_ref().map_or(&[][..], |node| node.prefix);
+        let path = match trie_node {
+            Some(TrieNode::Extension(path, _) | TrieNode::Leaf(path, _)) => path.as_slice(),
+            _ => &[][..],
+        };
+        let key = [prefix, path].concat();
+        let (key_value, key_len) = if is_extension || is_leaf {
+            (nibbles_value(&key), key.len() as u64)
+        } else {
+            (0, 0)
+        };
+        let prefix_value = nibbles_value(prefix);
+
+        // the columns of the node constant over its items
+        for row in 0..ROWS_PER_NODE {
+            for (column, value) in [
+                (tc.is_branch, is_branch as u64),
+                (tc.is_extension, is_extension as u64),
+                (tc.is_leaf, is_leaf as u64),
+                (tc.rlp_id, node.as_ref().map_or(0, |node| node.id)),
+                (tc.prefix, prefix_value),
+                (tc.prefix_len, prefix.len() as u64),
+                (tc.key, key_value),
+                (tc.key_len, key_len),
+            ] {
+                region.assign_advice(
+                    || "pi receipts: trie node",
+                    column,
+                    offset + row,
+                    || Value::known(F::from(value)),
+                )?;
+            }
+        }
+
+        let bytes = node.as_ref().map_or(&[][..], |node| node.bytes);
+        let (hash, len_bytes) = match bytes.first() {
+            Some(&header) => (
+                rlc_be_bytes(&keccak256(bytes), word_rand),
+                if header < 0xf8 {
+                    1
+                } else {
+                    (header - 0xf7) as u64
+                },
+            ),
+            None => (Value::known(F::zero()), 0),
+        };
+        let hp = if is_extension || is_leaf {
+            hp_encode(path, is_leaf)
+        } else {
+            vec![]
+        };
+        let value = match trie_node {
+            Some(TrieNode::Leaf(_, value)) => value.as_slice(),
+            _ => &[][..],
+        };
+        for (column, value) in [
+            (tc.hash, hash),
+            (tc.rlc, rlc_be_bytes(bytes, keccak_rand)),
+            (tc.len, known(bytes.len() as u64)),
+            (tc.len_bytes, known(len_bytes)),
+            (
+                tc.path_flag,
+                known(hp.first().map_or(0, |byte| byte >> 4) as u64),
+            ),
+            (
+                tc.first_nibble,
+                known(hp.first().map_or(0, |byte| byte & 0xf) as u64),
+            ),
+            (tc.is_odd, known(path.len() as u64 % 2)),
+            (tc.path_rlc, rlc_be_bytes(&hp, keccak_rand)),
+            (tc.path_value, known(nibbles_value(path))),
+            (tc.path_nibbles, known(path.len() as u64)),
+            (tc.path_pow, known(1 << (4 * path.len()))),
+            (tc.value_rlc, rlc_be_bytes(value, keccak_rand)),
+            (tc.value_len, known(value.len() as u64)),
+        ]
+        .into_iter()
+        .chain(
+            tc.path
+                .into_iter()
+                .enumerate()
+                .map(|(i, column)| (column, known(hp.get(i).copied().unwrap_or_default() as u64))),
+        )
+        .chain(
+            tc.path_sel
+                .into_iter()
+                .enumerate()
+                .map(|(i, column)| (column, known((i + 1 == hp.len()) as u64))),
+        ) {
+            region.assign_advice(|| "pi receipts: trie node", column, offset, || value)?;
+        }
+
+        let mut child_cnt = 0;
+        for row in 1..ROWS_PER_NODE {
+            let slot = row - 1;
+            let (child, child_prefix, child_prefix_len) = match trie_node {
+                Some(TrieNode::Branch(children)) => (
+                    children.get(slot).map_or(&[][..], Vec::as_slice),
+                    (prefix_value << 4) + slot as u64,
+                    prefix.len() as u64 + 1,
+                ),
+                Some(TrieNode::Extension(_, child)) => (
+                    if row == 1 { child.as_slice() } else { &[][..] },
+                    key_value,
+                    key_len,
+                ),
+                _ => (&[][..], 0, 0),
+            };
+            let child_active = !child.is_empty() && (is_branch || is_extension);
+            child_cnt += child_active as u64;
+            let child_is_branch = !child.is_empty() && branches.contains(&H256::from_slice(child));
+            let child_hash = if child.is_empty() {
+                Value::known(F::zero())
+            } else {
+                rlc_be_bytes(child, word_rand)
+            };
+            for (column, value) in [
+                (tc.child_hash, child_hash),
+                (tc.child_bytes_rlc, rlc_be_bytes(child, keccak_rand)),
+                (tc.child_none, known(child.is_empty() as u64)),
+                (tc.child_active, known(child_active as u64)),
+                (tc.child_is_branch, known(child_is_branch as u64)),
+                (tc.child_cnt, known(child_cnt)),
+                (tc.child_prefix, known(child_prefix)),
+                (tc.child_prefix_len, known(child_prefix_len)),
+            ] {
+                region.assign_advice(
+                    || "pi receipts: trie item",
+                    column,
+                    offset + row,
+                    || value,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The output row of the RLP table for a field of an instance.
+#[allow(clippy::too_many_arguments)]
+fn rlp_output<F: Field>(
+    id: Expression<F>,
+    format: Expression<F>,
+    tag: Expression<F>,
+    value: Expression<F>,
+    bytes_rlc: Expression<F>,
+    len: Expression<F>,
+    is_none: Expression<F>,
+    access_list_idx: Expression<F>,
+    storage_key_idx: Expression<F>,
+) -> Vec<Expression<F>> {
+    vec![
+        1.expr(), // q_enable
+        id,
+        format,
+        tag,
+        value,
+        bytes_rlc,
+        len,
+        1.expr(), // is_output
+        is_none,
+        access_list_idx,
+        storage_key_idx,
+    ]
+}
+
+fn known<F: Field>(value: u64) -> Value<F> {
+    Value::known(F::from(value))
+}
@@ -14,10 +14,16 @@ use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
 use std::env::set_var;
 
-use crate::{super_circuit::test::block_2tx, witness::block_convert};
+use crate::{
+    super_circuit::test::block_2tx,
+    table::{TxLogFieldTag, TxReceiptFieldTag},
+    witness::{block_convert, logs_bloom, ReceiptLog, ReceiptsTrie},
+};
 use bus_mapping::{circuit_input_builder::CircuitsParams, mock::BlockData};
 use eth_types::{bytecode, geth_types::GethData};
+use ethers_core::types::Bloom;
 use mock::{test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
+use std::collections::HashMap;
 
 // #[test]
 // fn pi_circuit_unusable_rows() {
@@ -85,6 +91,13 @@ fn empty_block() -> Block<Fr> {
     }
 }
 
+fn set_difficulty_and_coinbase() {
+    let mut difficulty_be_bytes = [0u8; 32];
+    MOCK_DIFFICULTY.to_big_endian(&mut difficulty_be_bytes);
+    set_var("DIFFICULTY", hex::encode(difficulty_be_bytes));
+    set_var("COINBASE", "0x0000000000000000000000000000000000000000");
+}
+
 #[cfg(feature = "scroll")]
 #[test]
 fn serial_test_simple_pi() {
@@ -92,11 +105,7 @@ fn serial_test_simple_pi() {
     const MAX_CALLDATA: usize = 20;
     const MAX_INNER_BLOCKS: usize = 4;
 
-    let mut difficulty_be_bytes = [0u8; 32];
-    MOCK_DIFFICULTY.to_big_endian(&mut difficulty_be_bytes);
-    set_var("DIFFICULTY", hex::encode(difficulty_be_bytes));
-    set_var("COINBASE", "0x0000000000000000000000000000000000000000");
-
+    set_difficulty_and_coinbase();
     let block = block_1tx();
 
     let k = 16;
@@ -150,3 +159,288 @@ fn variadic_size_check() {
     run_size_check::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>([block_0, block_2.clone()]);
     run_size_check::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>([block_1, block_2]);
 }
+
+/// The PI circuit used to test the receipts section, whose log rows fit a log
+/// with 32 bytes of data.
+type ReceiptsTestCircuit = PiTestCircuit<Fr, 4, 64, 4>;
+
+/// A PI circuit whose rw table holds `rws` instead of the receipt rws of its
+/// public data.
+#[derive(Clone, Default)]
+struct PiCircuitWithRws {
+    circuit: ReceiptsTestCircuit,
+    rws: Vec<Rw>,
+}
+
+impl Circuit<Fr> for PiCircuitWithRws {
+    type Config = (PiCircuitConfig<Fr>, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        ReceiptsTestCircuit::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<Fr>) -> Result<(), Error> {
+        self.circuit
+            .synthesize_with_rws(config, layouter, &self.rws)
+    }
+}
+
+/// The USDT approval logged by tx 0x21f6..0616 of mainnet block 19526841.
+fn mainnet_log() -> ReceiptLog {
+    ReceiptLog {
+        address: Address::from_str("0xdac17f958d2ee523a2206206994597c13d831ec7").unwrap(),
+        topics: [
+            "0x8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925",
+            "0x0000000000000000000000009a53bfba35269414f3b2d20b52ca01b15932c7b2",
+            "0x00000000000000000000000039e5dbb9d2fead31234d7c647d6ce77d85826f76",
+        ]
+        .into_iter()
+        .map(|topic| H256::from_str(topic).unwrap())
+        .collect(),
+        data: hex::decode("00000000000000000000000000000000000000000052b7d2dcc80cd2e4000000")
+            .unwrap(),
+    }
+}
+
+/// The receipts of the txs of [`block_2txs`]: the first one logs
+/// [`mainnet_log`] and the second one fails.
+fn mainnet_receipts() -> Vec<Receipt> {
+    vec![
+        Receipt {
+            tx_type: 0,
+            status: 1,
+            cumulative_gas_used: 0xbde1,
+            logs: vec![mainnet_log()],
+        },
+        Receipt {
+            tx_type: 0,
+            status: 0,
+            cumulative_gas_used: 0xbde1 + 0x5208,
+            logs: vec![],
+        },
+    ]
+}
+
+/// The TxReceipt and TxLog rws of `receipts`, sorted by key as in the rw
+/// table.
+fn receipt_rws(receipts: &[Receipt]) -> Vec<Rw> {
+    let mut rws = vec![];
+    for (tx_id, receipt) in (1..).zip(receipts) {
+        for (field_tag, value) in [
+            (TxReceiptFieldTag::PostStateOrStatus, receipt.status as u64),
+            (
+                TxReceiptFieldTag::CumulativeGasUsed,
+                receipt.cumulative_gas_used,
+            ),
+            (TxReceiptFieldTag::LogLength, receipt.logs.len() as u64),
+        ] {
+            rws.push(Rw::TxReceipt {
+                rw_counter: rws.len() + 1,
+                is_write: true,
+                tx_id,
+                field_tag,
+                value,
+            });
+        }
+        for (log_id, log) in (1..).zip(&receipt.logs) {
+            let items = iter::once((TxLogFieldTag::Address, 0, log.address.to_word()))
+                .chain(log.topics.iter().enumerate().map(|(index, topic)| {
+                    (
+                        TxLogFieldTag::Topic,
+                        index,
+                        Word::from_big_endian(topic.as_bytes()),
+                    )
+                }))
+                .chain(
+                    log.data
+                        .iter()
+                        .enumerate()
+                        .map(|(index, &byte)| (TxLogFieldTag::Data, index, Word::from(byte))),
+                );
+            for (field_tag, index, value) in items {
+                rws.push(Rw::TxLog {
+                    rw_counter: rws.len() + 1,
+                    is_write: true,
+                    tx_id,
+                    log_id,
+                    field_tag,
+                    index,
+                    value,
+                });
+            }
+        }
+    }
+    rws.sort_by_cached_key(Rw::as_key);
+    rws
+}
+
+/// The PI circuit of [`block_2txs`] with the receipts rebuilt from `rws`.
+fn circuit_with_receipt_rws(rws: Vec<Rw>) -> ReceiptsTestCircuit {
+    let mut circuit: ReceiptsTestCircuit = PiTestCircuit(PiCircuit::new(4, 64, 4, &block_2txs()));
+    circuit.0.public_data.receipt_rws = rws;
+    circuit
+}
+
+fn verify_receipts<C: Circuit<Fr>>(
+    circuit: &C,
+    instance: Vec<Vec<Fr>>,
+) -> Result<(), Vec<VerifyFailure>> {
+    MockProver::run(16, circuit, instance).unwrap().verify_par()
+}
+
+fn verify_receipts_with_overrides(
+    overrides: HashMap<(ReceiptsColumn, usize), Fr>,
+) -> Result<(), Vec<VerifyFailure>> {
+    let mut circuit = circuit_with_receipt_rws(receipt_rws(&mainnet_receipts()));
+    let instance = circuit.0.instance();
+    // Sanity check that the circuit without overrides is valid.
+    assert_eq!(verify_receipts(&circuit, instance.clone()), Ok(()));
+
+    circuit.0.overrides = overrides;
+    verify_receipts(&circuit, instance)
+}
+
+/// Verify the circuit whose receipts lack the rw of `dropped`, while its rw
+/// table holds them all.
+fn verify_receipts_without_rw(dropped: impl Fn(&Rw) -> bool) -> Result<(), Vec<VerifyFailure>> {
+    let rws = receipt_rws(&mainnet_receipts());
+    let circuit = circuit_with_receipt_rws(rws.iter().copied().filter(|rw| !dropped(rw)).collect());
+    assert_ne!(circuit.0.public_data.receipt_rws.len(), rws.len());
+    let instance = circuit.0.instance();
+    verify_receipts(&PiCircuitWithRws { circuit, rws }, instance)
+}
+
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_receipts_mainnet_log() {
+    set_difficulty_and_coinbase();
+    let receipts = mainnet_receipts();
+    // the logs bloom of tx 0x21f6..0616 in mainnet block 19526841
+    assert_eq!(
+        logs_bloom(&receipts),
+        Bloom::from_slice(
+            &hex::decode(concat!(
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "0000000000000000000000000000010000000000000000000000000000200000",
+                "0000000000000400000000000000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000040000080000",
+                "0000000000000000000000000000004000000000000000000800000000100000",
+                "0200000000000000000000800000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "0010000000000000000000000000000000010000000000000000000000000000",
+            ))
+            .unwrap()
+        )
+    );
+    // the root of the trie of the receipts, as computed by an independent
+    // implementation of the trie
+    assert_eq!(
+        ReceiptsTrie::new(&receipts).root,
+        H256::from_str("0x8c8b817c1ff02bf0ec4aada4775a6941c6beb1d31d2b7c5ba26f1eff20b3b663")
+            .unwrap()
+    );
+    // the receipts root of mainnet blocks without txs
+    assert_eq!(
+        ReceiptsTrie::new(&[]).root,
+        H256::from_str("0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421")
+            .unwrap()
+    );
+
+    let circuit = circuit_with_receipt_rws(receipt_rws(&receipts));
+    assert_eq!(circuit.0.public_data.receipts(), receipts);
+    let instance = circuit.0.instance();
+    assert_eq!(verify_receipts(&circuit, instance), Ok(()));
+}
+
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_receipts_cumulative_gas_around_0x80() {
+    set_difficulty_and_coinbase();
+    let receipts = [0x7f, 0x80].map(|cumulative_gas_used| Receipt {
+        cumulative_gas_used,
+        status: 1,
+        ..Default::default()
+    });
+    let circuit = circuit_with_receipt_rws(receipt_rws(&receipts));
+    let instance = circuit.0.instance();
+    assert_eq!(verify_receipts(&circuit, instance), Ok(()));
+}
+
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_receipts_tampered_topic() {
+    set_difficulty_and_coinbase();
+    // the log rows start with the 4 rows of the address, followed by the ones
+    // of the first topic
+    let overrides = HashMap::from([((ReceiptsColumn::LogValue, 4), Fr::from(0x1234))]);
+    assert!(verify_receipts_with_overrides(overrides).is_err());
+}
+
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_receipts_tampered_data_byte() {
+    set_difficulty_and_coinbase();
+    // the last data byte, zero, follows the 4 rows of the address and of each
+    // of the 3 topics, and the 31 other bytes
+    let overrides = HashMap::from([((ReceiptsColumn::LogValue, 16 + 31), Fr::one())]);
+    assert!(verify_receipts_with_overrides(overrides).is_err());
+}
+
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_receipts_tampered_bloom_bit() {
+    set_difficulty_and_coinbase();
+    // the most significant bit of the bloom of the chunk, unset
+    let overrides = HashMap::from([((ReceiptsColumn::BloomBit, 0), Fr::one())]);
+    assert!(verify_receipts_with_overrides(overrides).is_err());
+}
+
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_receipts_tampered_trie_node() {
+    set_difficulty_and_coinbase();
+    // the encoding of the root, the branch of the 2 receipts
+    let overrides = HashMap::from([((ReceiptsColumn::NodeRlc, 0), Fr::one())]);
+    assert!(verify_receipts_with_overrides(overrides).is_err());
+}
+
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_receipts_dropped_topic() {
+    set_difficulty_and_coinbase();
+    let result = verify_receipts_without_rw(|rw| {
+        matches!(
+            rw,
+            Rw::TxLog {
+                field_tag: TxLogFieldTag::Topic,
+                index: 2,
+                ..
+            }
+        )
+    });
+    assert!(result.is_err());
+}
+
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_receipts_dropped_data_byte() {
+    set_difficulty_and_coinbase();
+    let result = verify_receipts_without_rw(|rw| {
+        matches!(
+            rw,
+            Rw::TxLog {
+                field_tag: TxLogFieldTag::Data,
+                index: 31,
+                ..
+            }
+        )
+    });
+    assert!(result.is_err());
+}
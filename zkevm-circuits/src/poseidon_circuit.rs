@@ -162,18 +162,13 @@ impl<F: Field> SubCircuit<F> for PoseidonCircuit<F> {
             + storage_counter.len();
         log::debug!("poseidon circuit row num: dedup mpt from {prev_dedup_size} to {after_dedup_size}, mpt update len {}, smt trace len {}",
         block.mpt_updates.len(), block.mpt_updates.smt_traces.len());
-        let mpt_row_num = after_dedup_size * F::hash_block_size();
-        let byte_row_num = block
-            .bytecodes
-            .values()
-            .map(|bytecode| bytecode.bytes.len() / HASH_BLOCK_STEP_SIZE + 1)
-            .sum::<usize>()
-            * F::hash_block_size();
-        let total_row_num = mpt_row_num + byte_row_num;
-        log::debug!("poseidon circuit row num: {mpt_row_num}(mpt) + {byte_row_num}(bytecode) = {total_row_num}");
-        (
-            total_row_num,
-            block.circuits_params.max_poseidon_rows.max(total_row_num),
+        Self::min_num_rows(
+            after_dedup_size,
+            block
+                .bytecodes
+                .values()
+                .map(|bytecode| bytecode.bytes.len()),
+            block.circuits_params.max_poseidon_rows,
         )
     }
 
@@ -200,6 +195,25 @@ impl<F: Field> SubCircuit<F> for PoseidonCircuit<F> {
     }
 }
 
+impl<F: Field> PoseidonCircuit<F> {
+    /// Minimum and total number of rows of the circuit for `mpt_hashes` hashes of the mpt
+    /// and the hashes of bytecodes of `bytecode_lens` bytes.
+    pub(crate) fn min_num_rows(
+        mpt_hashes: usize,
+        bytecode_lens: impl Iterator<Item = usize>,
+        max_poseidon_rows: usize,
+    ) -> (usize, usize) {
+        let mpt_row_num = mpt_hashes * F::hash_block_size();
+        let byte_row_num = bytecode_lens
+            .map(|len| len / HASH_BLOCK_STEP_SIZE + 1)
+            .sum::<usize>()
+            * F::hash_block_size();
+        let total_row_num = mpt_row_num + byte_row_num;
+        log::debug!("poseidon circuit row num: {mpt_row_num}(mpt) + {byte_row_num}(bytecode) = {total_row_num}");
+        (total_row_num, max_poseidon_rows.max(total_row_num))
+    }
+}
+
 #[cfg(any(feature = "test", test))]
 impl<F: Field + Hashable> Circuit<F> for PoseidonCircuit<F> {
     type Config = (PoseidonCircuitConfig<F>, Challenges);
@@ -0,0 +1,153 @@
+//! The RIPEMD160 circuit serve for precompile RIPEMD-160 calls
+
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    halo2curves::bn256::Fr,
+    plonk::{Any, Column, ConstraintSystem, Error, Expression},
+};
+
+mod circuit;
+#[cfg(test)]
+mod test;
+
+pub use circuit::{CircuitConfig, BLOCK_ROWS, BLOCK_SIZE_IN_BYTES};
+use circuit::{Hasher, Ripemd160Table as TableTrait};
+
+use crate::{
+    table::{LookupTable, Ripemd160Table},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::circuit_input_builder::RIPEMD160;
+use eth_types::Field;
+
+impl TableTrait for Ripemd160Table {
+    fn cols(&self) -> [Column<Any>; 5] {
+        let tbl_cols = <Self as LookupTable<Fr>>::columns(self);
+        [
+            tbl_cols[0],
+            tbl_cols[2],
+            tbl_cols[3],
+            tbl_cols[4],
+            tbl_cols[1],
+        ]
+    }
+}
+
+/// Config args for RIPEMD160 circuit
+#[derive(Debug, Clone)]
+pub struct CircuitConfigArgs<F: Field> {
+    /// RIPEMD160 Table
+    pub ripemd160_table: Ripemd160Table,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl SubCircuitConfig<Fr> for CircuitConfig {
+    type ConfigArgs = CircuitConfigArgs<Fr>;
+
+    /// Return a new Ripemd160CircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<Fr>,
+        Self::ConfigArgs {
+            ripemd160_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        Self::configure(meta, ripemd160_table, challenges.keccak_input())
+    }
+}
+
+/// RIPEMD160 circuit for precompile ripemd160
+#[derive(Clone, Debug, Default)]
+pub struct Ripemd160Circuit<F: Field>(Vec<RIPEMD160>, usize, std::marker::PhantomData<F>);
+
+impl<F: Field> Ripemd160Circuit<F> {
+    fn expected_rows(&self) -> usize {
+        self.0
+            .iter()
+            // padding takes 9 bytes at least
+            .map(|evnt| (evnt.input.len() + 8) / BLOCK_SIZE_IN_BYTES + 1)
+            .reduce(|acc, v| acc + v)
+            .unwrap_or_default()
+            * BLOCK_ROWS
+    }
+
+    /// Minimum and total number of rows of the circuit for the ripemd160 events.
+    pub(crate) fn min_num_rows(events: Vec<RIPEMD160>, max_keccak_rows: usize) -> (usize, usize) {
+        let real_row = Self(events, 0, Default::default()).expected_rows();
+
+        (real_row, real_row.max(max_keccak_rows).max(4096))
+    }
+
+    fn with_row_limit(self, row_limit: usize) -> Self {
+        if row_limit != 0 {
+            let expected_rows = self.expected_rows();
+            assert!(
+                expected_rows <= row_limit,
+                "no enough rows for ripemd160 circuit, expected {expected_rows}, limit {row_limit}",
+            );
+            log::info!("ripemd160 circuit work with maxium {} rows", row_limit);
+        }
+        let inp = self.0;
+        let block_limit = row_limit / BLOCK_ROWS;
+
+        Self(inp, block_limit, Default::default())
+    }
+}
+
+impl SubCircuit<Fr> for Ripemd160Circuit<Fr> {
+    type Config = CircuitConfig;
+
+    fn unusable_rows() -> usize {
+        2
+    }
+
+    fn new_from_block(block: &witness::Block<Fr>) -> Self {
+        Self(block.get_ripemd160(), 0, Default::default())
+            .with_row_limit(block.circuits_params.max_keccak_rows)
+    }
+
+    fn min_num_rows_block(block: &witness::Block<Fr>) -> (usize, usize) {
+        Self::min_num_rows(block.get_ripemd160(), block.circuits_params.max_keccak_rows)
+    }
+
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<Fr>>,
+        layouter: &mut impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let chng = challenges.keccak_input();
+        let mut hasher = Hasher::new(config.clone(), layouter)?;
+
+        for hash_event in &self.0 {
+            hasher.update(layouter, chng, &hash_event.input)?;
+
+            let digest = hasher.finalize(layouter, chng)?;
+            // digest words of ripemd160 are little-endian
+            let ref_digest = hash_event
+                .digest
+                .chunks_exact(4)
+                .map(|bt| u32::from_le_bytes(bt.try_into().unwrap()))
+                .collect::<Vec<_>>();
+            for (w, check) in digest.into_iter().zip(ref_digest) {
+                w.value()
+                    .assert_if_known(|digest_word| **digest_word == Fr::from(check as u64));
+            }
+
+            if hasher.blocks() > self.1 {
+                log::error!("handled 512-bit block exceed limit ({})", self.1);
+                return Err(Error::Synthesis);
+            }
+        }
+
+        // paddings
+        for _i in hasher.blocks()..self.1 {
+            hasher.update(layouter, chng, &[])?;
+            hasher.finalize(layouter, chng)?;
+        }
+
+        Ok(())
+    }
+}
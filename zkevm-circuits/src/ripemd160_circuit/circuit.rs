@@ -0,0 +1,1301 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    halo2curves::bn256::Fr,
+    plonk::{
+        Advice, Any, Column, ConstraintSystem, Constraints, Error, Expression, Fixed, Selector,
+        TableColumn,
+    },
+    poly::Rotation,
+};
+use std::convert::TryInto;
+
+/// u32 size for RIPEMD160 digit
+pub const DIGEST_SIZE: usize = 5;
+/// bytes in a 512-bit block
+pub const BLOCK_SIZE_IN_BYTES: usize = 64;
+
+/// number of steps in each of the two parallel lines
+const STEPS: usize = 80;
+/// rows ahead of the steps of a line, which hold the initial working state
+const PRE_ROWS: usize = 5;
+
+/// rows for the header of a block, the first row holds the inherited cells
+const BYTE_OFFSET: usize = 2;
+const LEFT_OFFSET: usize = BYTE_OFFSET + BLOCK_SIZE_IN_BYTES;
+const RIGHT_OFFSET: usize = LEFT_OFFSET + PRE_ROWS + STEPS;
+const COMBINE_OFFSET: usize = RIGHT_OFFSET + PRE_ROWS + STEPS;
+/// rows used by a 512-bit block, the last one is the output row
+pub const BLOCK_ROWS: usize = COMBINE_OFFSET + DIGEST_SIZE;
+
+const IV: [u32; DIGEST_SIZE] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+/// all the rotation amounts used in the steps
+const ROTATIONS: [u32; 11] = [5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+
+const K_LEFT: [u32; 5] = [0x00000000, 0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xa953fd4e];
+const K_RIGHT: [u32; 5] = [0x50a28be6, 0x5c4dd124, 0x6d703ef3, 0x7a6d76e9, 0x00000000];
+
+#[rustfmt::skip]
+const R_LEFT: [usize; STEPS] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+    7, 4, 13, 1, 10, 6, 15, 3, 12, 0, 9, 5, 2, 14, 11, 8,
+    3, 10, 14, 4, 9, 15, 8, 1, 2, 7, 0, 6, 13, 11, 5, 12,
+    1, 9, 11, 10, 0, 8, 12, 4, 13, 3, 7, 15, 14, 5, 6, 2,
+    4, 0, 5, 9, 7, 12, 2, 10, 14, 1, 3, 8, 11, 6, 15, 13,
+];
+
+#[rustfmt::skip]
+const R_RIGHT: [usize; STEPS] = [
+    5, 14, 7, 0, 9, 2, 11, 4, 13, 6, 15, 8, 1, 10, 3, 12,
+    6, 11, 3, 7, 0, 13, 5, 10, 14, 15, 8, 12, 4, 9, 1, 2,
+    15, 5, 1, 3, 7, 14, 6, 9, 11, 8, 12, 2, 10, 0, 4, 13,
+    8, 6, 4, 1, 3, 11, 15, 0, 5, 12, 2, 13, 9, 7, 10, 14,
+    12, 15, 10, 4, 1, 5, 8, 7, 6, 2, 13, 14, 0, 3, 9, 11,
+];
+
+#[rustfmt::skip]
+const S_LEFT: [u32; STEPS] = [
+    11, 14, 15, 12, 5, 8, 7, 9, 11, 13, 14, 15, 6, 7, 9, 8,
+    7, 6, 8, 13, 11, 9, 7, 15, 7, 12, 15, 9, 11, 7, 13, 12,
+    11, 13, 6, 7, 14, 9, 13, 15, 14, 8, 13, 6, 5, 12, 7, 5,
+    11, 12, 14, 15, 14, 15, 9, 8, 9, 14, 5, 6, 8, 6, 5, 12,
+    9, 15, 5, 11, 6, 8, 13, 12, 5, 12, 13, 14, 11, 8, 5, 6,
+];
+
+#[rustfmt::skip]
+const S_RIGHT: [u32; STEPS] = [
+    8, 9, 9, 11, 13, 15, 15, 5, 7, 7, 8, 11, 14, 14, 12, 6,
+    9, 13, 15, 7, 12, 8, 9, 11, 7, 7, 12, 7, 6, 15, 13, 11,
+    9, 7, 15, 11, 8, 6, 6, 14, 12, 13, 5, 14, 13, 13, 7, 5,
+    15, 5, 8, 11, 14, 14, 6, 14, 6, 9, 12, 9, 12, 5, 15, 8,
+    8, 5, 12, 9, 12, 5, 14, 6, 8, 13, 6, 5, 15, 13, 11, 11,
+];
+
+/// the defination for a ripemd160 table
+pub trait Ripemd160Table {
+    /// the cols has layout [s_enable, input_bytes, input_len, hashes, effect]
+    fn cols(&self) -> [Column<Any>; 5];
+
+    /// s_enable col with cell *EQUAL TO 1* mark the row is an effect entry for
+    /// *ANY* 512-bit block of RIPEMD160
+    fn s_enable(&self) -> Column<Fixed> {
+        self.cols()[0]
+            .try_into()
+            .expect("must provide cols as expected layout")
+    }
+    /// input_rlc show the RLC for input bytes, the first byte is multipled with R^(n-1)
+    /// in which n is the length of bytes and R is random
+    fn input_rlc(&self) -> Column<Advice> {
+        self.cols()[1]
+            .try_into()
+            .expect("must provide cols as expected layout")
+    }
+    /// input_len show the accumulated lengh for input bytes
+    fn input_len(&self) -> Column<Advice> {
+        self.cols()[2]
+            .try_into()
+            .expect("must provide cols as expected layout")
+    }
+    /// hashes_rlc show the RLC for the 20-bytes digest of input bytes, the first byte
+    /// is multipled with R^19
+    fn hashes_rlc(&self) -> Column<Advice> {
+        self.cols()[3]
+            .try_into()
+            .expect("must provide cols as expected layout")
+    }
+    /// is_effect col is a phase 0 col, when the cell is equal to 1 indicate this 512-bit
+    /// block is the final one for current input bytes, the input_len in this row would
+    /// show the length *WITHOUT* padding of input bytes
+    fn is_effect(&self) -> Column<Advice> {
+        self.cols()[4]
+            .try_into()
+            .expect("must provide cols as expected layout")
+    }
+}
+
+/// CircuitConfig is the configure for RIPEMD160 circuit
+#[derive(Clone, Debug)]
+pub struct CircuitConfig {
+    byte_range: TableColumn,
+
+    byte: Column<Advice>,
+    word_acc: Column<Advice>, // accumulate the bytes into little-endian message words
+    s_padding: Column<Advice>, // indicate cur byte is padding
+    byte_counter: Column<Advice>, // counting for the input bytes
+    bytes_rlc: Column<Advice>, // phase 2 col obtained from RIPEMD160 table
+    s_final_block: Column<Advice>, // indicate it is the last block
+    digest_rlc: Column<Advice>, // phase 2 col obtained from RIPEMD160 table
+
+    bits: [Column<Advice>; 32],     // bits of the word produced in the row
+    sum_bits: [Column<Advice>; 32], // bits of the sum before rotation in a step
+    word: Column<Advice>,
+    word_rot: Column<Advice>,     // the word rotated left by 10 bits
+    addends: [Column<Advice>; 3], // the first one is the message word in steps
+    carry: Column<Advice>,
+    carry_rot: Column<Advice>,
+    chain: Column<Advice>, // the chaining value for the next block
+
+    s_output: Column<Fixed>, // indicate the row is used for output to ripemd160 table
+
+    byte_coeff: Column<Fixed>, // 256^i for the i-th byte in a message word
+    s_word_begin: Column<Fixed>, // the first byte of a message word
+    s_length: Column<Fixed>,   // the last 8 bytes for the bit length
+    s_pad_check: Column<Fixed>, // the last byte before the bit length
+    s_func: [Column<Fixed>; 5], // one-hot selector for the boolean functions
+    s_rot: [Column<Fixed>; 11], // one-hot selector for the rotation amount
+    round_const: Column<Fixed>,
+    init_iv: Column<Fixed>,
+
+    s_begin: Selector,     // indicate the header row of a block
+    s_byte: Selector,      // indicate the rows of input bytes
+    s_last_byte: Selector, // indicate the last byte
+    s_word: Selector,      // indicate the bits of word is assigned
+    s_step: Selector,      // indicate the steps in both lines
+    s_combine: Selector,   // indicate the rows combining two lines with chaining value
+}
+
+#[derive(Clone, Debug)]
+struct BlockInheritments {
+    s_final: AssignedCell<Fr, Fr>,
+    s_padding: AssignedCell<Fr, Fr>,
+    byte_counter: AssignedCell<Fr, Fr>,
+    bytes_rlc: AssignedCell<Fr, Fr>,
+    chain: [AssignedCell<Fr, Fr>; DIGEST_SIZE],
+    state: [u32; DIGEST_SIZE],
+}
+
+fn compose_rotl(bits: &[Expression<Fr>], s: usize) -> Expression<Fr> {
+    bits.iter()
+        .enumerate()
+        .fold(Expression::Constant(Fr::zero()), |acc, (i, bit)| {
+            acc + bit.clone() * Expression::Constant(Fr::from(1u64 << ((i + s) % 32)))
+        })
+}
+
+fn boolean_func(idx: usize, x: u32, y: u32, z: u32) -> u32 {
+    match idx {
+        0 => x ^ y ^ z,
+        1 => (x & y) | (!x & z),
+        2 => (x | !y) ^ z,
+        3 => (x & z) | (y & !z),
+        4 => x ^ (y | !z),
+        _ => unreachable!("ripemd160 has only 5 boolean functions"),
+    }
+}
+
+/// the boolean functions on single bits
+fn boolean_func_expr(
+    idx: usize,
+    x: Expression<Fr>,
+    y: Expression<Fr>,
+    z: Expression<Fr>,
+) -> Expression<Fr> {
+    let one = Expression::Constant(Fr::one());
+    let two = Expression::Constant(Fr::from(2u64));
+    let xor = |a: Expression<Fr>, b: Expression<Fr>| a.clone() + b.clone() - two.clone() * a * b;
+    match idx {
+        0 => xor(xor(x, y), z),
+        1 => x.clone() * y + (one - x) * z,
+        2 => xor(one - y.clone() + x * y, z),
+        3 => x * z.clone() + y * (one - z),
+        4 => xor(x, one - z.clone() + y * z),
+        _ => unreachable!("ripemd160 has only 5 boolean functions"),
+    }
+}
+
+impl CircuitConfig {
+    fn setup_gates(&self, meta: &mut ConstraintSystem<Fr>, rnd: Expression<Fr>) {
+        let one = Expression::Constant(Fr::one());
+        let two_pow_32 = Expression::Constant(Fr::from(1u64 << 32));
+
+        meta.create_gate("ripemd160 block beginning", |meta| {
+            // is *last block* final
+            let is_final_prev = meta.query_advice(self.s_final_block, Rotation::prev());
+            let is_not_final_prev = one.clone() - is_final_prev;
+            let is_final = meta.query_advice(self.s_final_block, Rotation::cur());
+
+            let mut constraints = vec![is_final.clone() * (one.clone() - is_final)];
+            // start from zero if last block is final, or continue from it
+            for col in [self.byte_counter, self.bytes_rlc, self.s_padding] {
+                constraints.push(
+                    meta.query_advice(col, Rotation::cur())
+                        - is_not_final_prev.clone() * meta.query_advice(col, Rotation::prev()),
+                );
+            }
+
+            Constraints::with_selector(meta.query_selector(self.s_begin), constraints)
+        });
+
+        meta.create_gate("ripemd160 input bytes", |meta| {
+            let byte = meta.query_advice(self.byte, Rotation::cur());
+            let s_padding = meta.query_advice(self.s_padding, Rotation::cur());
+            let s_padding_prev = meta.query_advice(self.s_padding, Rotation::prev());
+            let s_not_padding = one.clone() - s_padding.clone();
+            let is_final = meta.query_advice(self.s_final_block, Rotation::cur());
+            let is_final_prev = meta.query_advice(self.s_final_block, Rotation::prev());
+            let byte_counter = meta.query_advice(self.byte_counter, Rotation::cur());
+            let byte_counter_prev = meta.query_advice(self.byte_counter, Rotation::prev());
+            let bytes_rlc = meta.query_advice(self.bytes_rlc, Rotation::cur());
+            let bytes_rlc_prev = meta.query_advice(self.bytes_rlc, Rotation::prev());
+            let word_acc = meta.query_advice(self.word_acc, Rotation::cur());
+            let word_acc_prev = meta.query_advice(self.word_acc, Rotation::prev());
+
+            let byte_coeff = meta.query_fixed(self.byte_coeff, Rotation::cur());
+            let s_word_begin = meta.query_fixed(self.s_word_begin, Rotation::cur());
+            let s_length = meta.query_fixed(self.s_length, Rotation::cur());
+            let s_pad_check = meta.query_fixed(self.s_pad_check, Rotation::cur());
+
+            Constraints::with_selector(
+                meta.query_selector(self.s_byte),
+                vec![
+                    ("padding is bool", s_padding.clone() * s_not_padding.clone()),
+                    // if prev padding is 1, the following padding would always 1
+                    (
+                        "padding continue",
+                        s_padding_prev.clone() * s_not_padding.clone(),
+                    ),
+                    // the byte on first padding is 128 (first bit is 1)
+                    (
+                        "padding byte on change",
+                        (s_padding.clone() - s_padding_prev.clone())
+                            * (byte.clone() - Expression::Constant(Fr::from(128u64))),
+                    ),
+                    // the following padding bytes are 0, except for the bit length
+                    // in the last 8 bytes of the final block
+                    (
+                        "padding byte is zero",
+                        s_padding_prev * (one.clone() - s_length * is_final.clone()) * byte.clone(),
+                    ),
+                    (
+                        "byte counter",
+                        byte_counter - byte_counter_prev - s_not_padding.clone(),
+                    ),
+                    (
+                        "bytes rlc",
+                        bytes_rlc
+                            - bytes_rlc_prev.clone()
+                            - s_not_padding
+                                * (bytes_rlc_prev * (rnd.clone() - one.clone()) + byte.clone()),
+                    ),
+                    (
+                        "word accumulation",
+                        word_acc - (one.clone() - s_word_begin) * word_acc_prev - byte * byte_coeff,
+                    ),
+                    ("final continue", is_final.clone() - is_final_prev),
+                    // the block is final iff the padding has begun before the bit length,
+                    // so the bit length is always put in the first available block
+                    ("final condition", s_pad_check * (is_final - s_padding)),
+                ],
+            )
+        });
+
+        meta.create_gate("ripemd160 bit length", |meta| {
+            let is_final = meta.query_advice(self.s_final_block, Rotation::cur());
+            let byte_counter = meta.query_advice(self.byte_counter, Rotation::cur());
+            // the 64-bit length is put as two little-endian words
+            let length_lo = meta.query_advice(self.word_acc, Rotation(-4));
+            let length_hi = meta.query_advice(self.word_acc, Rotation::cur());
+
+            Constraints::with_selector(
+                meta.query_selector(self.s_last_byte),
+                vec![
+                    is_final
+                        * (length_hi * two_pow_32.clone() + length_lo
+                            - byte_counter * Expression::Constant(Fr::from(8u64))),
+                ],
+            )
+        });
+
+        meta.create_gate("ripemd160 word bits", |meta| {
+            let bits = self.bits.map(|col| meta.query_advice(col, Rotation::cur()));
+            let word = meta.query_advice(self.word, Rotation::cur());
+            let word_rot = meta.query_advice(self.word_rot, Rotation::cur());
+
+            let mut constraints = bits
+                .iter()
+                .map(|bit| bit.clone() * (one.clone() - bit.clone()))
+                .collect::<Vec<_>>();
+            constraints.push(word - compose_rotl(&bits, 0));
+            constraints.push(word_rot - compose_rotl(&bits, 10));
+
+            Constraints::with_selector(meta.query_selector(self.s_word), constraints)
+        });
+
+        // each row of the steps produces the new B, and the working state (A, B, C, D, E)
+        // is made of the words produced by the previous 5 rows:
+        // A = rotl10(B[-5]), B = B[-1], C = B[-2], D = rotl10(B[-3]), E = rotl10(B[-4])
+        meta.create_gate("ripemd160 step", |meta| {
+            let x = self.bits.map(|col| meta.query_advice(col, Rotation(-1)));
+            let y = self.bits.map(|col| meta.query_advice(col, Rotation(-2)));
+            let z = self.bits.map(|col| meta.query_advice(col, Rotation(-3)));
+            // bit i of rotl10(v) is the bit (i - 10) of v
+            let z = (0..32)
+                .map(|i| z[(i + 22) % 32].clone())
+                .collect::<Vec<_>>();
+            let a = meta.query_advice(self.word_rot, Rotation(-5));
+            let e = meta.query_advice(self.word_rot, Rotation(-4));
+            let msg = meta.query_advice(self.addends[0], Rotation::cur());
+            let round_const = meta.query_fixed(self.round_const, Rotation::cur());
+            let sum_bits = self
+                .sum_bits
+                .map(|col| meta.query_advice(col, Rotation::cur()));
+            let carry = meta.query_advice(self.carry, Rotation::cur());
+            let carry_rot = meta.query_advice(self.carry_rot, Rotation::cur());
+            let word = meta.query_advice(self.word, Rotation::cur());
+
+            let func = self.s_func.iter().enumerate().fold(
+                Expression::Constant(Fr::zero()),
+                |acc, (idx, col)| {
+                    let func_bits = (0..32)
+                        .map(|i| boolean_func_expr(idx, x[i].clone(), y[i].clone(), z[i].clone()))
+                        .collect::<Vec<_>>();
+                    acc + meta.query_fixed(*col, Rotation::cur()) * compose_rotl(&func_bits, 0)
+                },
+            );
+            let rotated = self.s_rot.iter().zip(ROTATIONS).fold(
+                Expression::Constant(Fr::zero()),
+                |acc, (col, s)| {
+                    acc + meta.query_fixed(*col, Rotation::cur())
+                        * compose_rotl(&sum_bits, s as usize)
+                },
+            );
+
+            let mut constraints = sum_bits
+                .iter()
+                .map(|bit| bit.clone() * (one.clone() - bit.clone()))
+                .collect::<Vec<_>>();
+            // A + f(B, C, D) + X + K < 2^34
+            constraints.push(
+                a + func + msg + round_const
+                    - compose_rotl(&sum_bits, 0)
+                    - carry.clone() * two_pow_32.clone(),
+            );
+            constraints.push((0..4).fold(one.clone(), |acc, i| {
+                acc * (carry.clone() - Expression::Constant(Fr::from(i)))
+            }));
+            constraints.push(carry_rot.clone() * (one.clone() - carry_rot.clone()));
+            constraints.push(rotated + e - word - carry_rot * two_pow_32.clone());
+
+            Constraints::with_selector(meta.query_selector(self.s_step), constraints)
+        });
+
+        meta.create_gate("ripemd160 combine", |meta| {
+            let addends = self
+                .addends
+                .map(|col| meta.query_advice(col, Rotation::cur()));
+            let bits = self.bits.map(|col| meta.query_advice(col, Rotation::cur()));
+            let word = meta.query_advice(self.word, Rotation::cur());
+            let carry = meta.query_advice(self.carry, Rotation::cur());
+            let chain = meta.query_advice(self.chain, Rotation::cur());
+            let init_iv = meta.query_fixed(self.init_iv, Rotation::cur());
+            let is_final = meta.query_advice(self.s_final_block, Rotation::cur());
+            let digest_rlc = meta.query_advice(self.digest_rlc, Rotation::cur());
+            let digest_rlc_prev = meta.query_advice(self.digest_rlc, Rotation::prev());
+
+            // the digest is output in little-endian bytes
+            let digest_rlc_calc = bits
+                .chunks_exact(8)
+                .fold(digest_rlc_prev, |acc, byte_bits| {
+                    acc * rnd.clone() + compose_rotl(byte_bits, 0)
+                });
+
+            Constraints::with_selector(
+                meta.query_selector(self.s_combine),
+                vec![
+                    addends.into_iter().fold(word.clone(), |acc, v| acc - v)
+                        + carry.clone() * two_pow_32.clone(),
+                    (0..3).fold(one.clone(), |acc, i| {
+                        acc * (carry.clone() - Expression::Constant(Fr::from(i)))
+                    }),
+                    // the chaining value is reset to iv after the final block
+                    chain - word.clone() - is_final * (init_iv - word),
+                    digest_rlc - digest_rlc_calc,
+                ],
+            )
+        });
+    }
+
+    /// Configures a circuit to include this chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<Fr>,
+        ripemd160_table: impl Ripemd160Table,
+        spec_challenge: Expression<Fr>,
+    ) -> Self {
+        let bytes_rlc = ripemd160_table.input_rlc();
+        let byte_counter = ripemd160_table.input_len();
+        let digest_rlc = ripemd160_table.hashes_rlc();
+        let s_output = ripemd160_table.s_enable();
+        let s_final_block = ripemd160_table.is_effect();
+
+        let byte = meta.advice_column();
+        let word_acc = meta.advice_column();
+        let s_padding = meta.advice_column();
+        let bits = [(); 32].map(|_| meta.advice_column());
+        let sum_bits = [(); 32].map(|_| meta.advice_column());
+        let word = meta.advice_column();
+        let word_rot = meta.advice_column();
+        let addends = [(); 3].map(|_| meta.advice_column());
+        let carry = meta.advice_column();
+        let carry_rot = meta.advice_column();
+        let chain = meta.advice_column();
+
+        let byte_coeff = meta.fixed_column();
+        let s_word_begin = meta.fixed_column();
+        let s_length = meta.fixed_column();
+        let s_pad_check = meta.fixed_column();
+        let s_func = [(); 5].map(|_| meta.fixed_column());
+        let s_rot = [(); 11].map(|_| meta.fixed_column());
+        let round_const = meta.fixed_column();
+        let init_iv = meta.fixed_column();
+
+        let s_begin = meta.selector();
+        let s_byte = meta.complex_selector();
+        let s_last_byte = meta.selector();
+        let s_word = meta.selector();
+        let s_step = meta.selector();
+        let s_combine = meta.selector();
+
+        let byte_range = meta.lookup_table_column();
+
+        for col in [
+            word_acc,
+            s_padding,
+            byte_counter,
+            bytes_rlc,
+            s_final_block,
+            digest_rlc,
+            word,
+            word_rot,
+            chain,
+        ]
+        .into_iter()
+        .chain(addends)
+        {
+            meta.enable_equality(col);
+        }
+
+        let ret = Self {
+            byte_range,
+
+            byte,
+            word_acc,
+            s_padding,
+            byte_counter,
+            bytes_rlc,
+            s_final_block,
+            digest_rlc,
+
+            bits,
+            sum_bits,
+            word,
+            word_rot,
+            addends,
+            carry,
+            carry_rot,
+            chain,
+
+            s_output,
+
+            byte_coeff,
+            s_word_begin,
+            s_length,
+            s_pad_check,
+            s_func,
+            s_rot,
+            round_const,
+            init_iv,
+
+            s_begin,
+            s_byte,
+            s_last_byte,
+            s_word,
+            s_step,
+            s_combine,
+        };
+
+        meta.lookup("byte range checking", |meta| {
+            let s_byte = meta.query_selector(ret.s_byte);
+            let byte = meta.query_advice(ret.byte, Rotation::cur());
+            vec![(s_byte * byte, byte_range)]
+        });
+
+        ret.setup_gates(meta, spec_challenge);
+
+        ret
+    }
+
+    /// assign a word with its bits, return the cells of the word and the word rotated
+    /// left by 10 bits
+    fn assign_word(
+        &self,
+        region: &mut Region<'_, Fr>,
+        offset: usize,
+        value: u32,
+    ) -> Result<(AssignedCell<Fr, Fr>, AssignedCell<Fr, Fr>), Error> {
+        self.s_word.enable(region, offset)?;
+        for (i, col) in self.bits.into_iter().enumerate() {
+            region.assign_advice(
+                || "word bit",
+                col,
+                offset,
+                || Value::known(Fr::from(((value >> i) & 1) as u64)),
+            )?;
+        }
+        let word = region.assign_advice(
+            || "word",
+            self.word,
+            offset,
+            || Value::known(Fr::from(value as u64)),
+        )?;
+        let word_rot = region.assign_advice(
+            || "word rotated",
+            self.word_rot,
+            offset,
+            || Value::known(Fr::from(value.rotate_left(10) as u64)),
+        )?;
+
+        Ok((word, word_rot))
+    }
+
+    /// assign the 80 steps of a line, return the cells for the final working
+    /// state (A, B, C, D, E)
+    #[allow(clippy::too_many_arguments)]
+    fn assign_line(
+        &self,
+        region: &mut Region<'_, Fr>,
+        offset: usize,
+        is_left: bool,
+        msg_words: &[(AssignedCell<Fr, Fr>, u32)],
+        chain: &[AssignedCell<Fr, Fr>; DIGEST_SIZE],
+        state: &[u32; DIGEST_SIZE],
+    ) -> Result<[(AssignedCell<Fr, Fr>, u32); 5], Error> {
+        let (msg_idx, rotations, round_consts) = if is_left {
+            (&R_LEFT, &S_LEFT, &K_LEFT)
+        } else {
+            (&R_RIGHT, &S_RIGHT, &K_RIGHT)
+        };
+
+        // the words produced by the pre rows are B[-4] .. B[0], so the initial state
+        // (h0, h1, h2, h3, h4) is (rotl10(B[-4]), B[0], B[-1], rotl10(B[-2]), rotl10(B[-3]))
+        let mut words = vec![
+            state[0].rotate_right(10),
+            state[4].rotate_right(10),
+            state[3].rotate_right(10),
+            state[2],
+            state[1],
+        ];
+        let mut cells = Vec::with_capacity(PRE_ROWS + STEPS);
+        for (i, &w) in words.iter().enumerate() {
+            cells.push(self.assign_word(region, offset + i, w)?);
+        }
+        region.constrain_equal(cells[0].1.cell(), chain[0].cell())?;
+        region.constrain_equal(cells[1].1.cell(), chain[4].cell())?;
+        region.constrain_equal(cells[2].1.cell(), chain[3].cell())?;
+        region.constrain_equal(cells[3].0.cell(), chain[2].cell())?;
+        region.constrain_equal(cells[4].0.cell(), chain[1].cell())?;
+
+        for j in 0..STEPS {
+            let row = offset + PRE_ROWS + j;
+            let round = j / 16;
+            let func_idx = if is_left { round } else { 4 - round };
+            let (msg_cell, msg) = &msg_words[msg_idx[j]];
+            let rotation = rotations[j];
+
+            let (a, b, c, d, e) = (
+                words[j].rotate_left(10),
+                words[j + 4],
+                words[j + 3],
+                words[j + 2].rotate_left(10),
+                words[j + 1].rotate_left(10),
+            );
+            let sum = a as u64
+                + boolean_func(func_idx, b, c, d) as u64
+                + *msg as u64
+                + round_consts[round] as u64;
+            let rotated = (sum as u32).rotate_left(rotation) as u64 + e as u64;
+
+            self.s_step.enable(region, row)?;
+            msg_cell.copy_advice(|| "message word", region, self.addends[0], row)?;
+            region.assign_fixed(
+                || "round constant",
+                self.round_const,
+                row,
+                || Value::known(Fr::from(round_consts[round] as u64)),
+            )?;
+            region.assign_fixed(
+                || "boolean function",
+                self.s_func[func_idx],
+                row,
+                || Value::known(Fr::one()),
+            )?;
+            region.assign_fixed(
+                || "rotation",
+                self.s_rot[(rotation - ROTATIONS[0]) as usize],
+                row,
+                || Value::known(Fr::one()),
+            )?;
+            for (i, col) in self.sum_bits.into_iter().enumerate() {
+                region.assign_advice(
+                    || "sum bit",
+                    col,
+                    row,
+                    || Value::known(Fr::from((sum >> i) & 1)),
+                )?;
+            }
+            region.assign_advice(
+                || "carry",
+                self.carry,
+                row,
+                || Value::known(Fr::from(sum >> 32)),
+            )?;
+            region.assign_advice(
+                || "carry of rotated",
+                self.carry_rot,
+                row,
+                || Value::known(Fr::from(rotated >> 32)),
+            )?;
+
+            words.push(rotated as u32);
+            cells.push(self.assign_word(region, row, rotated as u32)?);
+        }
+
+        // B[76] .. B[80] are in the last 5 rows
+        let last = |i: usize| cells[STEPS + i].clone();
+        Ok([
+            (last(0).1, words[STEPS].rotate_left(10)),
+            (last(4).0, words[STEPS + 4]),
+            (last(3).0, words[STEPS + 3]),
+            (last(2).1, words[STEPS + 2].rotate_left(10)),
+            (last(1).1, words[STEPS + 1].rotate_left(10)),
+        ])
+    }
+
+    fn initialize_block_head(
+        &self,
+        layouter: &mut impl Layouter<Fr>,
+    ) -> Result<BlockInheritments, Error> {
+        layouter.assign_region(
+            || "initialize hasher",
+            |mut region| {
+                // start as if a message has just been finalized
+                let s_final = region.assign_advice_from_constant(
+                    || "init s_final",
+                    self.s_final_block,
+                    0,
+                    Fr::one(),
+                )?;
+                let s_padding = region.assign_advice_from_constant(
+                    || "init padding",
+                    self.s_padding,
+                    0,
+                    Fr::zero(),
+                )?;
+                let bytes_rlc = region.assign_advice_from_constant(
+                    || "init bytes rlc",
+                    self.bytes_rlc,
+                    0,
+                    Fr::zero(),
+                )?;
+                let byte_counter = region.assign_advice_from_constant(
+                    || "init byte counter",
+                    self.byte_counter,
+                    0,
+                    Fr::zero(),
+                )?;
+                let chain = IV
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &iv)| {
+                        region.assign_advice_from_constant(
+                            || "init chaining value",
+                            self.chain,
+                            i,
+                            Fr::from(iv as u64),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+
+                Ok(BlockInheritments {
+                    s_final,
+                    s_padding,
+                    byte_counter,
+                    bytes_rlc,
+                    chain: chain.try_into().unwrap(),
+                    state: IV,
+                })
+            },
+        )
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn assign_block(
+        &self,
+        layouter: &mut impl Layouter<Fr>,
+        chng: Value<Fr>,
+        prev_block: &BlockInheritments,
+        input: &[u8],
+        padding_pos: Option<usize>,
+        is_final: bool,
+    ) -> Result<(BlockInheritments, [AssignedCell<Fr, Fr>; DIGEST_SIZE]), Error> {
+        assert_eq!(input.len(), BLOCK_SIZE_IN_BYTES);
+        let padding_pos = padding_pos.unwrap_or(BLOCK_SIZE_IN_BYTES);
+
+        layouter.assign_region(
+            || "ripemd160 block",
+            |mut region| {
+                prev_block.s_final.copy_advice(
+                    || "inheirt s_final",
+                    &mut region,
+                    self.s_final_block,
+                    0,
+                )?;
+                prev_block.s_padding.copy_advice(
+                    || "inheirt padding",
+                    &mut region,
+                    self.s_padding,
+                    0,
+                )?;
+                prev_block.bytes_rlc.copy_advice(
+                    || "inheirt bytes rlc",
+                    &mut region,
+                    self.bytes_rlc,
+                    0,
+                )?;
+                prev_block.byte_counter.copy_advice(
+                    || "inheirt byte counter",
+                    &mut region,
+                    self.byte_counter,
+                    0,
+                )?;
+
+                self.s_begin.enable(&mut region, 1)?;
+                let s_final_cell = region.assign_advice(
+                    || "header final",
+                    self.s_final_block,
+                    1,
+                    || Value::known(Fr::from(is_final as u64)),
+                )?;
+                let is_not_final_prev = prev_block.s_final.value().map(|v| Fr::one() - v);
+                let mut s_padding_cell = region.assign_advice(
+                    || "header padding",
+                    self.s_padding,
+                    1,
+                    || is_not_final_prev * prev_block.s_padding.value(),
+                )?;
+                let mut byte_counter_cell = region.assign_advice(
+                    || "header counter",
+                    self.byte_counter,
+                    1,
+                    || is_not_final_prev * prev_block.byte_counter.value(),
+                )?;
+                let mut bytes_rlc_cell = region.assign_advice(
+                    || "header rlc",
+                    self.bytes_rlc,
+                    1,
+                    || is_not_final_prev * prev_block.bytes_rlc.value(),
+                )?;
+                region.assign_advice(
+                    || "header word",
+                    self.word_acc,
+                    1,
+                    || Value::known(Fr::zero()),
+                )?;
+
+                let mut msg_words = Vec::with_capacity(BLOCK_SIZE_IN_BYTES / 4);
+                let mut word_acc = 0u32;
+                for (i, &byte) in input.iter().enumerate() {
+                    let row = BYTE_OFFSET + i;
+                    let now_padding = i >= padding_pos;
+                    let byte_pos = i % 4;
+
+                    self.s_byte.enable(&mut region, row)?;
+                    region.assign_advice(
+                        || "byte",
+                        self.byte,
+                        row,
+                        || Value::known(Fr::from(byte as u64)),
+                    )?;
+                    region.assign_fixed(
+                        || "byte coeff",
+                        self.byte_coeff,
+                        row,
+                        || Value::known(Fr::from(1u64 << (8 * byte_pos))),
+                    )?;
+                    region.assign_fixed(
+                        || "word begin",
+                        self.s_word_begin,
+                        row,
+                        || Value::known(Fr::from((byte_pos == 0) as u64)),
+                    )?;
+                    region.assign_fixed(
+                        || "length bytes",
+                        self.s_length,
+                        row,
+                        || Value::known(Fr::from((i >= BLOCK_SIZE_IN_BYTES - 8) as u64)),
+                    )?;
+                    region.assign_fixed(
+                        || "padding check",
+                        self.s_pad_check,
+                        row,
+                        || Value::known(Fr::from((i == BLOCK_SIZE_IN_BYTES - 9) as u64)),
+                    )?;
+
+                    s_padding_cell = region.assign_advice(
+                        || "padding",
+                        self.s_padding,
+                        row,
+                        || Value::known(Fr::from(now_padding as u64)),
+                    )?;
+                    region.assign_advice(
+                        || "final",
+                        self.s_final_block,
+                        row,
+                        || Value::known(Fr::from(is_final as u64)),
+                    )?;
+                    byte_counter_cell = region.assign_advice(
+                        || "byte counter",
+                        self.byte_counter,
+                        row,
+                        || {
+                            byte_counter_cell.value()
+                                + Value::known(if now_padding { Fr::zero() } else { Fr::one() })
+                        },
+                    )?;
+                    bytes_rlc_cell = region.assign_advice(
+                        || "bytes rlc",
+                        self.bytes_rlc,
+                        row,
+                        || {
+                            if now_padding {
+                                bytes_rlc_cell.value().map(Clone::clone)
+                            } else {
+                                chng * bytes_rlc_cell.value() + Value::known(Fr::from(byte as u64))
+                            }
+                        },
+                    )?;
+
+                    if byte_pos == 0 {
+                        word_acc = 0;
+                    }
+                    word_acc += (byte as u32) << (8 * byte_pos);
+                    let word_cell = region.assign_advice(
+                        || "word accumulation",
+                        self.word_acc,
+                        row,
+                        || Value::known(Fr::from(word_acc as u64)),
+                    )?;
+                    if byte_pos == 3 {
+                        msg_words.push((word_cell, word_acc));
+                    }
+                }
+                self.s_last_byte
+                    .enable(&mut region, BYTE_OFFSET + BLOCK_SIZE_IN_BYTES - 1)?;
+
+                let left = self.assign_line(
+                    &mut region,
+                    LEFT_OFFSET,
+                    true,
+                    &msg_words,
+                    &prev_block.chain,
+                    &prev_block.state,
+                )?;
+                let right = self.assign_line(
+                    &mut region,
+                    RIGHT_OFFSET,
+                    false,
+                    &msg_words,
+                    &prev_block.chain,
+                    &prev_block.state,
+                )?;
+
+                // h0' = h1 + C_L + D_R, h1' = h2 + D_L + E_R, h2' = h3 + E_L + A_R,
+                // h3' = h4 + A_L + B_R, h4' = h0 + B_L + C_R
+                let (a, b, c, d, e) = (0, 1, 2, 3, 4);
+                let operands = [
+                    (1, &left[c], &right[d]),
+                    (2, &left[d], &right[e]),
+                    (3, &left[e], &right[a]),
+                    (4, &left[a], &right[b]),
+                    (0, &left[b], &right[c]),
+                ];
+
+                let mut digest_rlc_cell = region.assign_advice_from_constant(
+                    || "init digest rlc",
+                    self.digest_rlc,
+                    COMBINE_OFFSET - 1,
+                    Fr::zero(),
+                )?;
+                let mut digest_cells = Vec::with_capacity(DIGEST_SIZE);
+                let mut chain_cells = Vec::with_capacity(DIGEST_SIZE);
+                let mut state = [0u32; DIGEST_SIZE];
+                for (i, (h_idx, (left_cell, left_v), (right_cell, right_v))) in
+                    operands.into_iter().enumerate()
+                {
+                    let row = COMBINE_OFFSET + i;
+                    let sum = prev_block.state[h_idx] as u64 + *left_v as u64 + *right_v as u64;
+                    let digest = sum as u32;
+
+                    self.s_combine.enable(&mut region, row)?;
+                    prev_block.chain[h_idx].copy_advice(
+                        || "chaining value",
+                        &mut region,
+                        self.addends[0],
+                        row,
+                    )?;
+                    left_cell.copy_advice(|| "left line", &mut region, self.addends[1], row)?;
+                    right_cell.copy_advice(|| "right line", &mut region, self.addends[2], row)?;
+                    region.assign_advice(
+                        || "carry",
+                        self.carry,
+                        row,
+                        || Value::known(Fr::from(sum >> 32)),
+                    )?;
+                    let (digest_cell, _) = self.assign_word(&mut region, row, digest)?;
+
+                    s_final_cell.copy_advice(|| "final", &mut region, self.s_final_block, row)?;
+                    region.assign_fixed(
+                        || "init iv",
+                        self.init_iv,
+                        row,
+                        || Value::known(Fr::from(IV[i] as u64)),
+                    )?;
+                    state[i] = if is_final { IV[i] } else { digest };
+                    chain_cells.push(region.assign_advice(
+                        || "chaining value",
+                        self.chain,
+                        row,
+                        || Value::known(Fr::from(state[i] as u64)),
+                    )?);
+
+                    digest_rlc_cell = region.assign_advice(
+                        || "digest rlc",
+                        self.digest_rlc,
+                        row,
+                        || {
+                            digest
+                                .to_le_bytes()
+                                .iter()
+                                .fold(digest_rlc_cell.value().map(Clone::clone), |acc, byte| {
+                                    acc * chng + Value::known(Fr::from(*byte as u64))
+                                })
+                        },
+                    )?;
+                    digest_cells.push(digest_cell);
+                }
+
+                // build output row
+                let final_row = COMBINE_OFFSET + DIGEST_SIZE - 1;
+                region.assign_fixed(
+                    || "mark s_output final",
+                    self.s_output,
+                    final_row,
+                    || Value::known(Fr::one()),
+                )?;
+                bytes_rlc_cell.copy_advice(
+                    || "copy input rlc",
+                    &mut region,
+                    self.bytes_rlc,
+                    final_row,
+                )?;
+                byte_counter_cell.copy_advice(
+                    || "copy bytes",
+                    &mut region,
+                    self.byte_counter,
+                    final_row,
+                )?;
+
+                Ok((
+                    BlockInheritments {
+                        s_final: s_final_cell,
+                        s_padding: s_padding_cell,
+                        byte_counter: byte_counter_cell,
+                        bytes_rlc: bytes_rlc_cell,
+                        chain: chain_cells.try_into().unwrap(),
+                        state,
+                    },
+                    digest_cells.try_into().unwrap(),
+                ))
+            },
+        )
+    }
+
+    fn initialize_constant_table(&self, layouter: &mut impl Layouter<Fr>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "byte range constant",
+            |mut tb| {
+                for i in 0..256 {
+                    tb.assign_cell(
+                        || "byte range",
+                        self.byte_range,
+                        i,
+                        || Value::known(Fr::from(i as u64)),
+                    )?;
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+/// ripemd160 hasher for byte stream
+#[derive(Debug)]
+pub struct Hasher {
+    chip: CircuitConfig,
+    hasher_state: BlockInheritments,
+    cur_block: Vec<u8>,
+    length: usize,
+    block_usage: usize,
+}
+
+impl Hasher {
+    /// return the number of 512-bit blocks which has been assigned
+    pub fn blocks(&self) -> usize {
+        self.block_usage
+    }
+
+    /// return the number bytes current update, 0 indicate a clean status
+    pub fn updated_size(&self) -> usize {
+        self.length
+    }
+
+    /// create a hasher, the circuit would be identify when block_usage is the same
+    pub fn new(chip: CircuitConfig, layouter: &mut impl Layouter<Fr>) -> Result<Self, Error> {
+        chip.initialize_constant_table(layouter)?;
+        let hasher_state = chip.initialize_block_head(layouter)?;
+        Ok(Self {
+            chip,
+            hasher_state,
+            cur_block: Vec::with_capacity(BLOCK_SIZE_IN_BYTES),
+            length: 0,
+            block_usage: 0,
+        })
+    }
+
+    /// update a single 512-bit block into layouter
+    fn update_block(
+        &mut self,
+        layouter: &mut impl Layouter<Fr>,
+        chng: Value<Fr>,
+        input: &[u8],
+        padding: Option<usize>,
+        is_final: bool,
+    ) -> Result<[AssignedCell<Fr, Fr>; DIGEST_SIZE], Error> {
+        let (hasher_state, digest) =
+            self.chip
+                .assign_block(layouter, chng, &self.hasher_state, input, padding, is_final)?;
+        self.hasher_state = hasher_state;
+        self.block_usage += 1;
+
+        Ok(digest)
+    }
+
+    /// Digest data, updating the internal state.
+    pub fn update(
+        &mut self,
+        layouter: &mut impl Layouter<Fr>,
+        chng: Value<Fr>,
+        mut data: &[u8],
+    ) -> Result<(), Error> {
+        use std::cmp::min;
+
+        self.length += data.len();
+
+        // Fill the current block, if possible.
+        let remaining = BLOCK_SIZE_IN_BYTES - self.cur_block.len();
+        let (l, r) = data.split_at(min(remaining, data.len()));
+        self.cur_block.extend_from_slice(l);
+        data = r;
+
+        // If we still don't have a full block, we are done.
+        if self.cur_block.len() < BLOCK_SIZE_IN_BYTES {
+            return Ok(());
+        }
+
+        // Process the now-full current block.
+        let cur_block = std::mem::take(&mut self.cur_block);
+        self.update_block(layouter, chng, &cur_block, None, false)?;
+
+        // Process any additional full blocks.
+        let mut chunks_iter = data.chunks_exact(BLOCK_SIZE_IN_BYTES);
+        for chunk in &mut chunks_iter {
+            self.update_block(layouter, chng, chunk, None, false)?;
+        }
+
+        // Cache the remaining partial block, if any.
+        let rem = chunks_iter.remainder();
+        self.cur_block.extend_from_slice(rem);
+
+        Ok(())
+    }
+
+    /// generate the final digest and ready for new update.
+    pub fn finalize(
+        &mut self,
+        layouter: &mut impl Layouter<Fr>,
+        chng: Value<Fr>,
+    ) -> Result<[AssignedCell<Fr, Fr>; DIGEST_SIZE], Error> {
+        // check padding requirement
+        let mut padding_pos = Some(self.cur_block.len());
+
+        // of course we have at least 1 byte left (or cur_block would have been compressed)
+        // push the additional 1bit
+        self.cur_block.push(128);
+        let remaining = BLOCK_SIZE_IN_BYTES - self.cur_block.len();
+
+        // if we have no enough space (64bit)， we need a extra block
+        if remaining < 8 {
+            self.cur_block.resize(BLOCK_SIZE_IN_BYTES, 0u8);
+            let cur_block = std::mem::take(&mut self.cur_block);
+            self.update_block(layouter, chng, &cur_block, padding_pos, false)?;
+
+            padding_pos = Some(0);
+        }
+
+        // the bit length is put in little-endian
+        self.cur_block.resize(BLOCK_SIZE_IN_BYTES - 8, 0u8);
+        self.cur_block
+            .extend(((self.length * 8) as u64).to_le_bytes());
+        assert_eq!(self.cur_block.len(), BLOCK_SIZE_IN_BYTES);
+
+        let cur_block = std::mem::take(&mut self.cur_block);
+        let digest = self.update_block(layouter, chng, &cur_block, padding_pos, true)?;
+        self.length = 0;
+
+        Ok(digest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, plonk::Circuit};
+
+    struct MyCircuit(Vec<(Vec<u8>, Option<[u32; DIGEST_SIZE]>)>);
+
+    impl Circuit<Fr> for MyCircuit {
+        type Config = CircuitConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            unimplemented!()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            struct DevTable {
+                s_enable: Column<Fixed>,
+                input_rlc: Column<Advice>,
+                input_len: Column<Advice>,
+                hashes_rlc: Column<Advice>,
+                is_effect: Column<Advice>,
+            }
+
+            impl Ripemd160Table for DevTable {
+                fn cols(&self) -> [Column<Any>; 5] {
+                    [
+                        self.s_enable.into(),
+                        self.input_rlc.into(),
+                        self.input_len.into(),
+                        self.hashes_rlc.into(),
+                        self.is_effect.into(),
+                    ]
+                }
+            }
+
+            let dev_table = DevTable {
+                s_enable: meta.fixed_column(),
+                input_rlc: meta.advice_column(),
+                input_len: meta.advice_column(),
+                hashes_rlc: meta.advice_column(),
+                is_effect: meta.advice_column(),
+            };
+            meta.enable_constant(dev_table.s_enable);
+
+            let chng = Expression::Constant(Fr::from(0x1000u64));
+            Self::Config::configure(meta, dev_table, chng)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let chng_v = Value::known(Fr::from(0x1000u64));
+            let mut hasher = Hasher::new(config, &mut layouter)?;
+
+            for (input, digest) in &self.0 {
+                hasher.update(&mut layouter, chng_v, input)?;
+                let ret_digest = hasher.finalize(&mut layouter, chng_v)?;
+                if let Some(check_digest) = digest {
+                    for (w, check) in ret_digest.into_iter().zip(*check_digest) {
+                        w.value()
+                            .assert_if_known(|digest_word| **digest_word == Fr::from(check as u64));
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    // digest words are little-endian
+    const DIGEST_ABC: [u32; DIGEST_SIZE] =
+        [0xf708b28e, 0x7a985de0, 0x8e4a049b, 0x87b0c698, 0xfc0b5af1];
+
+    const DIGEST_NIL: [u32; DIGEST_SIZE] =
+        [0xa585119c, 0x54fce9c5, 0x97082861, 0x48f5e87e, 0x318d25b2];
+
+    const DIGEST_MSG: [u32; DIGEST_SIZE] =
+        [0xef89065d, 0xe5fad249, 0xb181b872, 0xfa5fa823, 0x365f5921];
+
+    const DIGEST_A200: [u32; DIGEST_SIZE] =
+        [0x43425b2a, 0xe2fcc094, 0x0b4e5d66, 0x8d997e07, 0x0a16622d];
+
+    fn run(circuit: &MyCircuit) {
+        let prover = match MockProver::<Fr>::run(14, circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{e:#?}"),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn ripemd160_simple() {
+        run(&MyCircuit(vec![(b"abc".to_vec(), Some(DIGEST_ABC))]));
+    }
+
+    #[test]
+    fn ripemd160_multiple() {
+        run(&MyCircuit(vec![
+            (b"abc".to_vec(), Some(DIGEST_ABC)),
+            (b"message digest".to_vec(), Some(DIGEST_MSG)),
+        ]));
+    }
+
+    #[test]
+    fn ripemd160_nil() {
+        run(&MyCircuit(vec![
+            (vec![], Some(DIGEST_NIL)),
+            (vec![], Some(DIGEST_NIL)),
+            (vec![], Some(DIGEST_NIL)),
+        ]));
+    }
+
+    #[test]
+    fn ripemd160_padding() {
+        for sz in [32usize, 37, 55, 56, 58, 62, 63, 64, 119, 120] {
+            run(&MyCircuit(vec![
+                (vec![0xff; sz], None),
+                (vec![], Some(DIGEST_NIL)),
+                (b"abc".to_vec(), Some(DIGEST_ABC)),
+            ]));
+        }
+    }
+
+    #[test]
+    fn ripemd160_multi_blocks() {
+        run(&MyCircuit(vec![
+            (vec![b'a'; 200], Some(DIGEST_A200)),
+            (b"message digest".to_vec(), Some(DIGEST_MSG)),
+        ]));
+    }
+}
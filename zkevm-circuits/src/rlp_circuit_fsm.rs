@@ -17,8 +17,11 @@ use crate::{
         Block, DataTable, Format, RlpFsmWitnessGen, RlpFsmWitnessRow, RlpTag, RomTableRow, State,
         State::{DecodeTagStart, End},
         Tag,
-        Tag::{AccessListAddress, AccessListStorageKey, BeginObject, EndObject, EndVector, TxType},
-        Transaction,
+        Tag::{
+            AccessListAddress, AccessListStorageKey, BeginObject, BeginVector, BlobVersionedHash,
+            EndObject, EndVector, MptChild, TxType,
+        },
+        Transaction, TrieNodeRlp,
     },
 };
 use eth_types::Field;
@@ -97,6 +100,39 @@ impl RlpFsmDataTable {
             gas_cost_acc: meta.advice_column(),
         }
     }
+
+    /// Load the data table (only for dev).
+    pub fn dev_load<F: Field, RLP: RlpFsmWitnessGen<F>>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        inputs: Vec<RLP>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let rows = inputs
+            .into_iter()
+            .flat_map(|input| input.gen_data_table(challenges))
+            .collect::<Vec<_>>();
+
+        layouter.assign_region(
+            || "RLP data dev table",
+            |mut region| {
+                for (i, row) in rows.iter().enumerate() {
+                    for (&column, value) in <Self as LookupTable<F>>::advice_columns(self)
+                        .iter()
+                        .zip(row.values().into_iter())
+                    {
+                        region.assign_advice(
+                            || format!("RLP data table row: row = {i}"),
+                            column,
+                            i,
+                            || value,
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
 }
 
 /// Read-only Memory table for the new RLP circuit design based on state machine. This table allows
@@ -298,16 +334,22 @@ pub struct RlpCircuitConfig<F> {
     is_tag_end: Column<Advice>,
     is_tag_begin: Column<Advice>,
     /// Boolean to reduce the circuit's degree
-    /// is_case3 = (0xc0 <= byte_value < 0xf8) && (is_tag_end == false)
+    /// is_case3 = (0xc0 <= byte_value < 0xf8) && (is_tag_end == false) && (is_mpt_child == false)
     is_case3: Column<Advice>,
     /// Boolean to reduce the circuit's degree
+    /// is_mpt_child = (tag == MptChild)
+    is_mpt_child: Column<Advice>,
+    /// Boolean to reduce the circuit's degree
+    /// is_embedded_child = (0xc0 <= byte_value < 0xf8) && (is_mpt_child == true)
+    is_embedded_child: Column<Advice>,
+    /// Boolean to reduce the circuit's degree
     /// transit_to_new_rlp_instance = (is_tag_end == true) && (depth == 0) && (state' != End)
     transit_to_new_rlp_instance: Column<Advice>,
     /// Boolean to reduce the circuit's degree
     is_same_rlp_instance: Column<Advice>,
 
     /// Boolean to reduce the circuit's degree
-    /// Indicates the start of another new access list item
+    /// Indicates the start of another new access list item (or blob versioned hash)
     is_new_access_list_address: Column<Advice>,
     /// Boolean to reduce the circuit's degree
     /// Indicates the start of another new storage key for an access list address
@@ -411,6 +453,8 @@ impl<F: Field> RlpCircuitConfig<F> {
             is_tag_begin,
             is_tag_end,
             is_case3,
+            is_mpt_child_col,
+            is_embedded_child,
             transit_to_new_rlp_instance,
             is_same_rlp_instance,
             is_new_access_list_address,
@@ -440,6 +484,8 @@ impl<F: Field> RlpCircuitConfig<F> {
             meta.advice_column(),
             meta.advice_column(),
             meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
         );
 
         let tag_value_acc = meta.advice_column_in(SecondPhase);
@@ -489,6 +535,8 @@ impl<F: Field> RlpCircuitConfig<F> {
         is_tag!(is_tag_end_vector, EndVector);
         is_tag!(is_access_list_address, AccessListAddress);
         is_tag!(is_access_list_storage_key, AccessListStorageKey);
+        is_tag!(is_blob_versioned_hash, BlobVersionedHash);
+        is_tag!(is_mpt_child, MptChild);
 
         //////////////////////////////////////////////////////////
         //////////// data table checks. //////////////////////////
@@ -919,10 +967,27 @@ impl<F: Field> RlpCircuitConfig<F> {
                 sum::expr([is_tag_begin_object(meta), is_tag_begin_vector(meta)]),
             );
             cb.require_equal(
-                "is_case3 = (0xc0 <= byte_value < 0xf8) && (is_tag_end == false)",
+                "is_mpt_child = (tag == MptChild)",
+                meta.query_advice(is_mpt_child_col, Rotation::cur()),
+                is_mpt_child(meta),
+            );
+            // a child of a trie node which is a list is an embedded node, it is decoded as
+            // the bytes of the child instead of a nested list.
+            cb.require_equal(
+                "is_case3 = (0xc0 <= byte_value < 0xf8) && (is_tag_end == false) && (is_mpt_child == false)",
                 meta.query_advice(is_case3, Rotation::cur()),
                 and::expr([
                     not::expr(meta.query_advice(is_tag_end, Rotation::cur())),
+                    not::expr(meta.query_advice(is_mpt_child_col, Rotation::cur())),
+                    sum::expr([bv_gt_0xc0.expr(), bv_eq_0xc0.expr()]),
+                    bv_lt_0xf8.expr(),
+                ]),
+            );
+            cb.require_equal(
+                "is_embedded_child = (0xc0 <= byte_value < 0xf8) && (is_mpt_child == true)",
+                meta.query_advice(is_embedded_child, Rotation::cur()),
+                and::expr([
+                    meta.query_advice(is_mpt_child_col, Rotation::cur()),
                     sum::expr([bv_gt_0xc0, bv_eq_0xc0]),
                     bv_lt_0xf8,
                 ]),
@@ -1044,6 +1109,10 @@ impl<F: Field> RlpCircuitConfig<F> {
                 let case_1 = and::expr([bv_lt_0x80, not::expr(is_tag_end_expr(meta))]);
                 cb.condition(case_1.expr(), |cb| {
                     // assertions.
+                    cb.require_zero(
+                        "a child of a trie node is not a single byte",
+                        meta.query_advice(is_mpt_child_col, Rotation::cur()),
+                    );
                     emit_rlp_tag!(meta, cb, tag_expr, false);
 
                     // is_list = false, tag_value_acc = byte_value
@@ -1150,9 +1219,10 @@ impl<F: Field> RlpCircuitConfig<F> {
                         update_state!(meta, cb, state, DecodeTagStart);
 
                         cb.require_zero(
-                            "tag == TxType or tag == BeginObject",
+                            "tag == TxType or tag == BeginObject or tag == BeginVector",
                             (tag_next.expr() - TxType.expr())
-                                * (tag_next.expr() - BeginObject.expr()),
+                                * (tag_next.expr() - BeginObject.expr())
+                                * (tag_next.expr() - BeginVector.expr()),
                         );
                     },
                 );
@@ -1225,6 +1295,15 @@ impl<F: Field> RlpCircuitConfig<F> {
 
                 // depth is unchanged.
                 constrain_unchanged_fields!(meta, cb; rlp_table.tx_id, rlp_table.format, depth, tag, tag_next);
+
+                // a child of a trie node which is a string is the hash of a node.
+                cb.condition(meta.query_advice(is_mpt_child_col, Rotation::cur()), |cb| {
+                    cb.require_equal(
+                        "a hash child of a trie node is 32 bytes",
+                        byte_value_expr(meta),
+                        0xa0.expr(),
+                    );
+                });
             });
             // otherwise, we get an invalid rlp error.
 
@@ -1234,6 +1313,79 @@ impl<F: Field> RlpCircuitConfig<F> {
             ]))
         });
 
+        // DecodeTagStart => Bytes (embedded node)
+        //
+        // A trie node shorter than 32 bytes is embedded in its parent instead of its hash. The
+        // node is decoded as the bytes of the child, including the list header, so that the
+        // rlc of the child is the rlc of the node itself.
+        meta.create_gate(
+            "state transition: DecodeTagStart => Bytes (embedded node)",
+            |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                let (_, bv_eq_0xc0) = byte_value_gte_0xc0.expr(meta, None);
+                cb.require_zero(
+                    "a child of a trie node is not an empty list",
+                    meta.query_advice(is_mpt_child_col, Rotation::cur()) * bv_eq_0xc0,
+                );
+
+                cb.condition(
+                    meta.query_advice(is_embedded_child, Rotation::cur()),
+                    |cb| {
+                        // assertions
+                        do_not_emit!(meta, cb);
+                        constrain_eq!(meta, cb, is_list, false);
+
+                        // state transitions
+                        update_state!(meta, cb, tag_idx, 2);
+                        update_state!(
+                            meta,
+                            cb,
+                            tag_length,
+                            byte_value_expr(meta) - 0xc0.expr() + 1.expr()
+                        );
+                        update_state!(
+                            meta,
+                            cb,
+                            tag_value_acc,
+                            byte_value_expr(meta) * evm_word_rand.expr()
+                                + byte_value_next_expr(meta)
+                        );
+                        update_state!(
+                            meta,
+                            cb,
+                            rlp_table.tag_bytes_rlc,
+                            byte_value_expr(meta) * keccak_input_rand.expr()
+                                + byte_value_next_expr(meta)
+                        );
+                        update_state!(meta, cb, state, State::Bytes);
+
+                        // depth is unchanged.
+                        constrain_unchanged_fields!(meta, cb; rlp_table.tx_id, rlp_table.format, depth, tag, tag_next);
+                    },
+                );
+
+                cb.gate(and::expr([
+                    meta.query_fixed(q_enabled, Rotation::cur()),
+                    is_decode_tag_start(meta),
+                ]))
+            },
+        );
+        meta.lookup("embedded node is shorter than 32 bytes", |meta| {
+            let cond = and::expr([
+                meta.query_fixed(q_enabled, Rotation::cur()),
+                is_decode_tag_start(meta),
+                meta.query_advice(is_embedded_child, Rotation::cur()),
+            ]);
+
+            // 0xc0 + 31 - 1 >= byte_value, i.e. the node including its header is less than 32
+            // bytes
+            vec![(
+                cond * (0xde.expr() - meta.query_advice(byte_value, Rotation::cur())),
+                u8_table.into(),
+            )]
+        });
+
         // Bytes => Bytes
         // Bytes => DecodeTagStart
         meta.create_gate("state transition: Bytes", |meta| {
@@ -1526,10 +1678,13 @@ impl<F: Field> RlpCircuitConfig<F> {
         meta.create_gate("booleans for reducing degree (part four)", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
+            // the blob versioned hashes are indexed by access_list_idx as well, since they
+            // are listed at the same depth as the access list items, and so are the children
+            // of trie nodes
             cb.require_equal(
                 "is_new_access_list_address",
                 meta.query_advice(is_new_access_list_address, Rotation::cur()),
-                is_access_list_address(meta),
+                is_access_list_address(meta) + is_blob_versioned_hash(meta) + is_mpt_child(meta),
             );
             cb.require_equal(
                 "is_new_access_list_storage_key",
@@ -1553,10 +1708,11 @@ impl<F: Field> RlpCircuitConfig<F> {
         meta.create_gate("boolean for reducing degree (part five)", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
+            // the children of trie nodes are the only vector ending at depth 1
             cb.require_equal(
                 "is_access_list_end",
                 meta.query_advice(is_access_list_end, Rotation::cur()),
-                depth_eq_two.is_equal_expression.expr(),
+                depth_eq_two.is_equal_expression.expr() + depth_eq_one.is_equal_expression.expr(),
             );
             cb.require_equal(
                 "is_storage_key_list_end",
@@ -2252,6 +2408,8 @@ impl<F: Field> RlpCircuitConfig<F> {
             is_tag_begin,
             is_tag_end,
             is_case3,
+            is_mpt_child: is_mpt_child_col,
+            is_embedded_child,
             transit_to_new_rlp_instance,
             is_same_rlp_instance,
 
@@ -2491,7 +2649,9 @@ impl<F: Field> RlpCircuitConfig<F> {
         )?;
 
         let is_new_access_list_address = witness.state_machine.state == DecodeTagStart
-            && witness.state_machine.tag == AccessListAddress;
+            && (witness.state_machine.tag == AccessListAddress
+                || witness.state_machine.tag == BlobVersionedHash
+                || witness.state_machine.tag == MptChild);
         region.assign_advice(
             || "is_new_access_list_address",
             self.is_new_access_list_address,
@@ -2506,8 +2666,8 @@ impl<F: Field> RlpCircuitConfig<F> {
             row,
             || Value::known(F::from(is_new_access_list_storage_key as u64)),
         )?;
-        let is_access_list_end =
-            witness.state_machine.tag == EndVector && witness.state_machine.depth == 2;
+        let is_access_list_end = witness.state_machine.tag == EndVector
+            && (witness.state_machine.depth == 2 || witness.state_machine.depth == 1);
         region.assign_advice(
             || "is_access_list_end",
             self.is_access_list_end,
@@ -2673,7 +2833,11 @@ impl<F: Field> RlpCircuitConfig<F> {
 
         // assign to intermediates
         let byte_value = witness.state_machine.byte_value;
-        let is_case3 = (0xc0..0xf8).contains(&byte_value) && !witness.state_machine.tag.is_end();
+        let is_mpt_child = witness.state_machine.tag == MptChild;
+        let is_case3 = (0xc0..0xf8).contains(&byte_value)
+            && !witness.state_machine.tag.is_end()
+            && !is_mpt_child;
+        let is_embedded_child = (0xc0..0xf8).contains(&byte_value) && is_mpt_child;
         let transit_to_new = witness.state_machine.tag.is_end()
             && (witness.state_machine.depth == 0)
             && witness_next.is_some();
@@ -2695,6 +2859,18 @@ impl<F: Field> RlpCircuitConfig<F> {
             row,
             || Value::known(F::from(is_case3 as u64)),
         )?;
+        region.assign_advice(
+            || "is_mpt_child",
+            self.is_mpt_child,
+            row,
+            || Value::known(F::from(is_mpt_child as u64)),
+        )?;
+        region.assign_advice(
+            || "is_embedded_child",
+            self.is_embedded_child,
+            row,
+            || Value::known(F::from(is_embedded_child as u64)),
+        )?;
         region.assign_advice(
             || "transit_to_new_rlp_instance",
             self.transit_to_new_rlp_instance,
@@ -2894,21 +3070,33 @@ impl<F: Field> RlpCircuitConfig<F> {
         Ok(())
     }
 
-    /// Assign witness to the RLP circuit.
+    /// Assign witness to the RLP circuit. The trie nodes are decoded after the
+    /// inputs, their ids following the ids of the inputs.
     pub(crate) fn assign<RLP: RlpFsmWitnessGen<F>>(
         &self,
         layouter: &mut impl Layouter<F>,
         inputs: &[RLP],
+        trie_nodes: &[TrieNodeRlp],
         last_row: usize,
         challenges: &Challenges<Value<F>>,
     ) -> Result<(), Error> {
         let dt_rows = inputs
             .iter()
             .flat_map(|input| input.gen_data_table(challenges))
+            .chain(
+                trie_nodes
+                    .iter()
+                    .flat_map(|node| node.gen_data_table(challenges)),
+            )
             .collect::<Vec<_>>();
         let sm_rows = inputs
             .iter()
             .flat_map(|input| input.gen_sm_witness(challenges))
+            .chain(
+                trie_nodes
+                    .iter()
+                    .flat_map(|node| node.gen_sm_witness(challenges)),
+            )
             .collect::<Vec<_>>();
 
         debug_assert!(sm_rows.len() <= last_row);
@@ -2968,6 +3156,8 @@ impl<F: Field> RlpCircuitConfig<F> {
 pub struct RlpCircuitConfigArgs<F: Field> {
     /// RLP table.
     pub rlp_table: RlpFsmRlpTable,
+    /// Data table of the bytes being decoded.
+    pub data_table: RlpFsmDataTable,
     /// u8 table
     pub u8_table: U8Table,
     /// Challenge API.
@@ -2978,14 +3168,13 @@ impl<F: Field> SubCircuitConfig<F> for RlpCircuitConfig<F> {
     type ConfigArgs = RlpCircuitConfigArgs<F>;
 
     fn new(meta: &mut ConstraintSystem<F>, args: Self::ConfigArgs) -> Self {
-        let data_table = RlpFsmDataTable::construct(meta);
         let rom_table = RlpFsmRomTable::construct(meta);
         let decoding_table = RlpDecodingTable::construct(meta);
 
         Self::configure(
             meta,
             rom_table,
-            data_table,
+            args.data_table,
             args.u8_table,
             args.rlp_table,
             decoding_table,
@@ -3001,6 +3190,9 @@ pub struct RlpCircuit<F, RLP> {
     pub txs: Vec<RLP>,
     /// Maximum number of txs supported.
     pub max_txs: usize,
+    /// Trie nodes of the keccak MPT state proofs, then the receipts and the
+    /// nodes of the receipts trie, with ids after `max_txs`.
+    pub trie_nodes: Vec<TrieNodeRlp>,
     /// Maximum number of rows supported.
     pub size: usize,
     _marker: PhantomData<F>,
@@ -3011,6 +3203,7 @@ impl<F: Field, RLP> Default for RlpCircuit<F, RLP> {
         Self {
             txs: vec![],
             max_txs: 0,
+            trie_nodes: vec![],
             size: 0,
             _marker: PhantomData,
         }
@@ -3033,10 +3226,12 @@ impl<F: Field> SubCircuit<F> for RlpCircuit<F, Transaction> {
             })
             .collect::<Vec<Transaction>>();
         let txs = [block.txs.clone(), padding_txs].concat();
+        let trie_nodes = block.trie_node_rlps();
 
         Self {
             txs,
             max_txs,
+            trie_nodes,
             size,
             _marker: Default::default(),
         }
@@ -3048,7 +3243,7 @@ impl<F: Field> SubCircuit<F> for RlpCircuit<F, Transaction> {
         challenges: &Challenges<Value<F>>,
         layouter: &mut impl Layouter<F>,
     ) -> Result<(), Error> {
-        config.assign(layouter, &self.txs, self.size, challenges)
+        config.assign(layouter, &self.txs, &self.trie_nodes, self.size, challenges)
     }
 
     fn min_num_rows_block(block: &Block<F>) -> (usize, usize) {
@@ -3058,7 +3253,12 @@ impl<F: Field> SubCircuit<F> for RlpCircuit<F, Transaction> {
             .txs
             .iter()
             .map(|tx| tx.gen_sm_witness(&challenges).len())
-            .sum();
+            .sum::<usize>()
+            + block
+                .trie_node_rlps()
+                .iter()
+                .map(|node| node.gen_sm_witness(&challenges).len())
+                .sum::<usize>();
         let max_num_rows = block.circuits_params.max_rlp_rows;
 
         (sm_rows, max_num_rows)
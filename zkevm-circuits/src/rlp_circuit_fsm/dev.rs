@@ -1,5 +1,5 @@
 use crate::{
-    rlp_circuit_fsm::{RlpCircuit, RlpCircuitConfig, RlpCircuitConfigArgs},
+    rlp_circuit_fsm::{RlpCircuit, RlpCircuitConfig, RlpCircuitConfigArgs, RlpFsmDataTable},
     table::{RlpFsmRlpTable, U8Table},
     util::{Challenges, SubCircuit, SubCircuitConfig},
     witness::Transaction,
@@ -25,11 +25,13 @@ impl<F: Field> Circuit<F> for RlpCircuit<F, Transaction> {
         let challenges = Challenges::construct(meta);
         let challenge_exprs = challenges.exprs(meta);
         let u8_table = U8Table::construct(meta);
+        let data_table = RlpFsmDataTable::construct(meta);
 
         let config = RlpCircuitConfig::new(
             meta,
             RlpCircuitConfigArgs {
                 rlp_table,
+                data_table,
                 u8_table,
                 challenges: challenge_exprs,
             },
@@ -1,17 +1,31 @@
 #![allow(unused_imports)]
-use crate::{rlp_circuit_fsm::RlpCircuit, witness::Transaction};
-use eth_types::{geth_types::TxType, word, Address};
+use crate::{
+    rlp_circuit_fsm::RlpCircuit,
+    witness::{EthAccount, EthTrieState, MptUpdates, Rw, Transaction, TrieNodeRlp},
+};
+use eth_types::{
+    geth_types::{self, get_rlp_signed, get_rlp_unsigned, TxType},
+    word, Address, Word, H256, U256,
+};
 use ethers_core::{
     types::{
-        transaction::eip2718::TypedTransaction, Eip1559TransactionRequest,
-        Eip2930TransactionRequest, Transaction as EthTransaction, TransactionRequest,
+        transaction::{
+            eip2718::TypedTransaction,
+            eip2930::{AccessList, AccessListItem},
+        },
+        Eip1559TransactionRequest, Eip2930TransactionRequest, Transaction as EthTransaction,
+        TransactionRequest,
+    },
+    utils::{
+        keccak256,
+        rlp::{Decodable, Rlp},
     },
-    utils::rlp::{Decodable, Rlp},
 };
-use ethers_signers::Wallet;
+use ethers_signers::{Signer, Wallet};
 use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
 use mock::{eth, MOCK_CHAIN_ID};
 use rand::rngs::OsRng;
+use std::collections::BTreeMap;
 
 #[cfg(test)]
 fn get_tx(is_eip155: bool) -> Transaction {
@@ -42,12 +56,120 @@ fn get_tx(is_eip155: bool) -> Transaction {
     Transaction::new_from_rlp_bytes(1, tx_type, signed_bytes, unsigned_bytes)
 }
 
+/// An access list of two items, the first one with two storage keys and the
+/// second one with none.
+#[cfg(test)]
+fn get_access_list() -> AccessList {
+    AccessList(vec![
+        AccessListItem {
+            address: Address::repeat_byte(1),
+            storage_keys: vec![H256::from_low_u64_be(1), H256::repeat_byte(0xab)],
+        },
+        AccessListItem {
+            address: Address::repeat_byte(2),
+            storage_keys: vec![],
+        },
+    ])
+}
+
+#[cfg(test)]
+fn get_eip1559_tx(tx_id: usize) -> Transaction {
+    let typed_tx: TypedTransaction = Eip1559TransactionRequest::new()
+        .chain_id(MOCK_CHAIN_ID)
+        .to(Address::random())
+        .value(eth(10))
+        .data(vec![0x12, 0x34])
+        .max_priority_fee_per_gas(word!("0x1234"))
+        .max_fee_per_gas(word!("0x4321"))
+        .gas(word!("0x77320"))
+        .nonce(word!("0x7f"))
+        .access_list(get_access_list())
+        .into();
+    let sig = Wallet::new(&mut OsRng)
+        .with_chain_id(MOCK_CHAIN_ID)
+        .sign_transaction_sync(&typed_tx)
+        .unwrap();
+
+    Transaction::new_from_rlp_bytes(
+        tx_id,
+        TxType::Eip1559,
+        typed_tx.rlp_signed(&sig).to_vec(),
+        typed_tx.rlp().to_vec(),
+    )
+}
+
+/// An EIP-4844 tx with an access list and two blob versioned hashes. The RLP
+/// circuit does not check the signature.
+#[cfg(test)]
+fn get_eip4844_tx(tx_id: usize) -> Transaction {
+    let mut tx = EthTransaction::from(&geth_types::Transaction {
+        tx_type: TxType::Eip4844,
+        to: Some(Address::random()),
+        nonce: word!("0x7f"),
+        gas_limit: word!("0x77320"),
+        value: eth(10),
+        gas_tip_cap: Some(word!("0x1234")),
+        gas_fee_cap: Some(word!("0x4321")),
+        call_data: vec![0x12, 0x34].into(),
+        access_list: Some(get_access_list()),
+        max_fee_per_blob_gas: word!("0x1000"),
+        blob_versioned_hashes: vec![H256::repeat_byte(1), H256::repeat_byte(2)],
+        v: 1,
+        r: word!("0x563304e8f2306c3fafed471bee76db83690ec113965c6775a8a94625dcb03774"),
+        s: word!("0x5bcc59f5737520f7d0dc8b4f967635473e0a58526ce9ddd69c4a2454c9955f12"),
+        ..Default::default()
+    });
+    tx.chain_id = Some(MOCK_CHAIN_ID.into());
+
+    Transaction::new_from_rlp_bytes(
+        tx_id,
+        TxType::Eip4844,
+        get_rlp_signed(&tx),
+        get_rlp_unsigned(&tx),
+    )
+}
+
+/// The trie nodes along the paths of an insertion and a deletion of storage
+/// slots, the leaf of the inserted slot being embedded in its branch.
+#[cfg(test)]
+fn get_trie_nodes(first_node_id: u64) -> Vec<TrieNodeRlp> {
+    let address = Address::repeat_byte(5);
+    let account = EthAccount {
+        nonce: 1,
+        balance: U256::from(1000),
+        storage_root: H256::zero(),
+        code_hash: H256(keccak256([])),
+    };
+    // the keys of slots 40364 and 105566 share their first 8 nibbles
+    let storage = BTreeMap::from([
+        (Word::from(7), Word::from(0x1234)),
+        (Word::from(40364), Word::from(1)),
+    ]);
+    let trie =
+        EthTrieState::from_accounts(&BTreeMap::from([(address, (account, storage))])).unwrap();
+
+    let storage_rw = |storage_key: u64, value_prev: u64, value: u64| Rw::AccountStorage {
+        rw_counter: 1,
+        is_write: true,
+        account_address: address,
+        storage_key: storage_key.into(),
+        value: value.into(),
+        value_prev: value_prev.into(),
+        tx_id: 1,
+        committed_value: value_prev.into(),
+    };
+    let mut updates = MptUpdates::mock_from(&[storage_rw(7, 0x1234, 0), storage_rw(105566, 0, 2)]);
+    updates.fill_state_roots_eth(&trie).unwrap();
+    updates.trie_node_rlps(first_node_id)
+}
+
 #[test]
 fn test_eip_155_tx() {
     let tx = get_tx(true);
     let rlp_circuit = RlpCircuit::<Fr, Transaction> {
         txs: vec![tx],
         max_txs: 10,
+        trie_nodes: vec![],
         size: 500,
         _marker: Default::default(),
     };
@@ -68,6 +190,7 @@ fn test_pre_eip155_tx() {
     let rlp_circuit = RlpCircuit::<Fr, Transaction> {
         txs: vec![tx],
         max_txs: 10,
+        trie_nodes: vec![],
         size: 500,
         _marker: Default::default(),
     };
@@ -95,6 +218,7 @@ fn test_l1_msg_tx() {
     let rlp_circuit = RlpCircuit::<Fr, Transaction> {
         txs: vec![tx],
         max_txs: 10,
+        trie_nodes: vec![],
         size: 1000,
         _marker: Default::default(),
     };
@@ -148,6 +272,7 @@ fn test_eip1559_tx() {
     let rlp_circuit = RlpCircuit::<Fr, Transaction> {
         txs,
         max_txs: 10,
+        trie_nodes: vec![],
         size: 2 << 13,
         _marker: Default::default(),
     };
@@ -179,6 +304,7 @@ fn test_eip2930_tx() {
     let rlp_circuit = RlpCircuit::<Fr, Transaction> {
         txs: vec![tx],
         max_txs: 10,
+        trie_nodes: vec![],
         size: 1000,
         _marker: Default::default(),
     };
@@ -192,3 +318,34 @@ fn test_eip2930_tx() {
 
     mock_prover.assert_satisfied_par();
 }
+
+#[test]
+fn test_eip4844_tx() {
+    let rlp_circuit = RlpCircuit::<Fr, Transaction> {
+        txs: vec![get_eip4844_tx(1)],
+        max_txs: 10,
+        trie_nodes: vec![],
+        size: 1000,
+        _marker: Default::default(),
+    };
+
+    let mock_prover = MockProver::run(14, &rlp_circuit, vec![]).unwrap();
+    mock_prover.assert_satisfied_par();
+}
+
+// The children of trie nodes share access_list_idx with the access list items
+// and the blob versioned hashes of the txs decoded before them.
+#[test]
+fn test_txs_with_access_lists_and_trie_nodes() {
+    let txs = vec![get_eip1559_tx(1), get_eip4844_tx(2)];
+    let rlp_circuit = RlpCircuit::<Fr, Transaction> {
+        trie_nodes: get_trie_nodes(txs.len() as u64 + 1),
+        max_txs: txs.len(),
+        txs,
+        size: 1 << 13,
+        _marker: Default::default(),
+    };
+
+    let mock_prover = MockProver::run(14, &rlp_circuit, vec![]).unwrap();
+    mock_prover.assert_satisfied_par();
+}
@@ -77,6 +77,13 @@ impl<F: Field> SHA256Circuit<F> {
             * TABLE16_BLOCK_ROWS
     }
 
+    /// Minimum and total number of rows of the circuit for the sha256 events.
+    pub(crate) fn min_num_rows(events: Vec<SHA256>, max_keccak_rows: usize) -> (usize, usize) {
+        let real_row = Self(events, 0, Default::default()).expected_rows();
+
+        (real_row, real_row.max(max_keccak_rows).max(4096))
+    }
+
     fn with_row_limit(self, row_limit: usize) -> Self {
         if row_limit != 0 {
             let expected_rows = self.expected_rows();
@@ -106,14 +113,7 @@ impl SubCircuit<Fr> for SHA256Circuit<Fr> {
     }
 
     fn min_num_rows_block(block: &witness::Block<Fr>) -> (usize, usize) {
-        let real_row = Self(block.get_sha256(), 0, Default::default()).expected_rows();
-
-        (
-            real_row,
-            real_row
-                .max(block.circuits_params.max_keccak_rows)
-                .max(4096),
-        )
+        Self::min_num_rows(block.get_sha256(), block.circuits_params.max_keccak_rows)
     }
 
     fn synthesize_sub(
@@ -258,30 +258,16 @@ impl<F: Field> SubCircuit<F> for SigCircuit<F> {
     // Since sig circuit / halo2-lib use veticle cell assignment,
     // so the returned pair is consisted of same values
     fn min_num_rows_block(block: &crate::witness::Block<F>) -> (usize, usize) {
-        let row_num = if block.circuits_params.max_vertical_circuit_rows == 0 {
-            Self::min_num_rows()
-        } else {
-            block.circuits_params.max_vertical_circuit_rows
-        };
-
         let ecdsa_verif_count = block
             .txs
             .iter()
             .filter(|tx| !tx.tx_type.is_l1_msg())
             .count()
             + block.precompile_events.get_ecrecover_events().len();
-        // Reserve one ecdsa verification for padding tx such that the bad case in which some tx
-        // calls MAX_NUM_SIG - 1 ecrecover precompile won't happen. If that case happens, the sig
-        // circuit won't have more space for the padding tx's ECDSA verification. Then the
-        // prover won't be able to produce any valid proof.
-        let max_num_verif = MAX_NUM_SIG - 1;
-
-        // Instead of showing actual minimum row usage,
-        // halo2-lib based circuits use min_row_num to represent a percentage of total-used capacity
-        // This functionality allows l2geth to decide if additional ops can be added.
-        let min_row_num = (row_num / max_num_verif) * ecdsa_verif_count;
-
-        (min_row_num, row_num)
+        Self::min_num_rows_of(
+            ecdsa_verif_count,
+            block.circuits_params.max_vertical_circuit_rows,
+        )
     }
 }
 
@@ -312,6 +298,32 @@ impl<F: Field> SigCircuit<F> {
         // same formula as halo2-lib's FlexGate
         (1 << LOG_TOTAL_NUM_ROWS) - (max_blinding_factor + 3)
     }
+
+    /// Minimum and total number of rows of the circuit for `ecdsa_verif_count` ECDSA
+    /// verifications.
+    pub(crate) fn min_num_rows_of(
+        ecdsa_verif_count: usize,
+        max_vertical_circuit_rows: usize,
+    ) -> (usize, usize) {
+        let row_num = if max_vertical_circuit_rows == 0 {
+            Self::min_num_rows()
+        } else {
+            max_vertical_circuit_rows
+        };
+
+        // Reserve one ecdsa verification for padding tx such that the bad case in which some tx
+        // calls MAX_NUM_SIG - 1 ecrecover precompile won't happen. If that case happens, the sig
+        // circuit won't have more space for the padding tx's ECDSA verification. Then the
+        // prover won't be able to produce any valid proof.
+        let max_num_verif = MAX_NUM_SIG - 1;
+
+        // Instead of showing actual minimum row usage,
+        // halo2-lib based circuits use min_row_num to represent a percentage of total-used capacity
+        // This functionality allows l2geth to decide if additional ops can be added.
+        let min_row_num = (row_num / max_num_verif) * ecdsa_verif_count;
+
+        (min_row_num, row_num)
+    }
 }
 
 impl<F: Field> SigCircuit<F> {
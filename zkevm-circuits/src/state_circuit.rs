@@ -893,6 +893,15 @@ impl<F: Field> StateCircuit<F> {
             _marker: PhantomData,
         }
     }
+
+    /// Minimum and total number of rows of the circuit for `rw_count` rws.
+    pub(crate) fn min_num_rows(rw_count: usize, max_rws: usize) -> (usize, usize) {
+        let total_rw_count = rw_count + 1;
+        (
+            total_rw_count,
+            std::cmp::max(1 << 16, max_rws.max(total_rw_count)),
+        )
+    }
 }
 
 impl<F: Field> SubCircuit<F> for StateCircuit<F> {
@@ -920,10 +929,9 @@ impl<F: Field> SubCircuit<F> for StateCircuit<F> {
 
     /// Return the minimum number of rows required to prove the block
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
-        let total_rw_count = block.rws.0.values().flatten().count() + 1;
-        (
-            total_rw_count,
-            std::cmp::max(1 << 16, block.circuits_params.max_rws.max(total_rw_count)),
+        Self::min_num_rows(
+            block.rws.0.values().flatten().count(),
+            block.circuits_params.max_rws,
         )
     }
 
@@ -130,6 +130,15 @@ impl<F: Field> ConstraintBuilder<F> {
         self.condition(q.tag_matches(RwTableTag::TxLog), |cb| {
             cb.build_tx_log_constraints(q)
         });
+        self.condition(q.tag_matches(RwTableTag::TransientStorage), |cb| {
+            cb.build_transient_storage_constraints(q)
+        });
+        self.condition(q.tag_matches(RwTableTag::TxCreatedAccount), |cb| {
+            cb.build_tx_created_account_constraints(q)
+        });
+        self.condition(q.tag_matches(RwTableTag::TxDestructedAccount), |cb| {
+            cb.build_tx_destructed_account_constraints(q)
+        });
     }
 
     fn build_general_constraints(&mut self, q: &Queries<F>) {
@@ -524,6 +533,88 @@ impl<F: Field> ConstraintBuilder<F> {
         );
     }
 
+    fn build_transient_storage_constraints(&mut self, q: &Queries<F>) {
+        self.require_zero("field_tag is 0 for TransientStorage", q.field_tag());
+        // The tx id is part of the key, so every transaction starts from an
+        // empty transient storage.
+        self.require_zero("initial TransientStorage value is 0", q.initial_value());
+
+        self.require_equal(
+            "state_root is unchanged for TransientStorage",
+            q.state_root(),
+            q.state_root_prev(),
+        );
+
+        self.condition(q.not_first_access.clone(), |cb| {
+            cb.require_equal(
+                "value column at Rotation::prev() equals value_prev at Rotation::cur()",
+                q.rw_table.value_prev.clone(),
+                q.value_prev_column(),
+            );
+        });
+    }
+
+    fn build_tx_created_account_constraints(&mut self, q: &Queries<F>) {
+        self.require_zero("field_tag is 0 for TxCreatedAccount", q.field_tag());
+        self.require_zero(
+            "storage_key is 0 for TxCreatedAccount",
+            q.rw_table.storage_key.clone(),
+        );
+        self.require_boolean("TxCreatedAccount value is boolean", q.value());
+        // An account can only be marked as created by a write within the same
+        // transaction, since the tx id is part of the key.
+        self.require_zero("initial TxCreatedAccount value is false", q.initial_value());
+
+        self.require_equal(
+            "state_root is unchanged for TxCreatedAccount",
+            q.state_root(),
+            q.state_root_prev(),
+        );
+
+        self.condition(q.not_first_access.clone(), |cb| {
+            cb.require_equal(
+                "value column at Rotation::prev() equals value_prev at Rotation::cur()",
+                q.rw_table.value_prev.clone(),
+                q.value_prev_column(),
+            );
+        });
+    }
+
+    fn build_tx_destructed_account_constraints(&mut self, q: &Queries<F>) {
+        self.require_zero("field_tag is 0 for TxDestructedAccount", q.field_tag());
+        self.require_zero(
+            "storage_key is 0 for TxDestructedAccount",
+            q.rw_table.storage_key.clone(),
+        );
+        self.require_boolean("TxDestructedAccount value is boolean", q.value());
+        self.require_zero(
+            "initial TxDestructedAccount value is false",
+            q.initial_value(),
+        );
+        // A self destructed account must be destructed by a DestructAccount step before the end
+        // of the transaction, which writes the value back to false.
+        self.condition(q.last_access(), |cb| {
+            cb.require_zero(
+                "TxDestructedAccount value is false at last access",
+                q.value(),
+            );
+        });
+
+        self.require_equal(
+            "state_root is unchanged for TxDestructedAccount",
+            q.state_root(),
+            q.state_root_prev(),
+        );
+
+        self.condition(q.not_first_access.clone(), |cb| {
+            cb.require_equal(
+                "value column at Rotation::prev() equals value_prev at Rotation::cur()",
+                q.rw_table.value_prev.clone(),
+                q.value_prev_column(),
+            );
+        });
+    }
+
     fn require_zero(&mut self, name: &'static str, e: Expression<F>) {
         self.constraints.push((name, self.condition.clone() * e));
     }
@@ -943,6 +943,95 @@ fn bad_initial_tx_refund_value() {
     );
 }
 
+#[test]
+fn bad_initial_transient_storage_value() {
+    let rows = vec![Rw::TransientStorage {
+        rw_counter: 1,
+        is_write: false,
+        tx_id: 1,
+        account_address: Address::default(),
+        storage_key: U256::from(3),
+        value: U256::zero(),
+        value_prev: U256::zero(),
+    }];
+    let v = Fr::from(10);
+    let overrides = HashMap::from([
+        ((AdviceColumn::IsWrite, 0), Fr::from(1)),
+        ((AdviceColumn::Value, 0), v),
+        ((AdviceColumn::ValuePrev, 0), v),
+        ((AdviceColumn::IsZero, 0), Fr::zero()),
+        ((AdviceColumn::NonEmptyWitness, 0), v.invert().unwrap()),
+        ((AdviceColumn::InitialValue, 0), v),
+    ]);
+
+    assert_error_matches(
+        verify_with_overrides(rows, overrides),
+        "initial TransientStorage value is 0",
+    );
+}
+
+#[test]
+fn bad_initial_tx_created_account_value() {
+    let rows = vec![Rw::TxCreatedAccount {
+        rw_counter: 1,
+        is_write: false,
+        tx_id: 1,
+        account_address: Address::default(),
+        is_created: false,
+        is_created_prev: false,
+    }];
+    let overrides = HashMap::from([
+        ((AdviceColumn::Value, 0), Fr::one()),
+        ((AdviceColumn::ValuePrev, 0), Fr::one()),
+        ((AdviceColumn::IsZero, 0), Fr::zero()),
+        ((AdviceColumn::NonEmptyWitness, 0), Fr::one()),
+        ((AdviceColumn::InitialValue, 0), Fr::one()),
+    ]);
+
+    assert_error_matches(
+        verify_with_overrides(rows, overrides),
+        "initial TxCreatedAccount value is false",
+    );
+}
+
+#[test]
+fn bad_initial_tx_destructed_account_value() {
+    let rows = vec![Rw::TxDestructedAccount {
+        rw_counter: 1,
+        is_write: true,
+        tx_id: 1,
+        account_address: Address::default(),
+        is_destructed: false,
+        is_destructed_prev: false,
+    }];
+    let overrides = HashMap::from([
+        ((AdviceColumn::ValuePrev, 0), Fr::one()),
+        ((AdviceColumn::InitialValue, 0), Fr::one()),
+    ]);
+
+    assert_error_matches(
+        verify_with_overrides(rows, overrides),
+        "initial TxDestructedAccount value is false",
+    );
+}
+
+#[test]
+fn pending_tx_destructed_account() {
+    let rows = vec![Rw::TxDestructedAccount {
+        rw_counter: 1,
+        is_write: true,
+        tx_id: 1,
+        account_address: Address::default(),
+        is_destructed: true,
+        is_destructed_prev: false,
+    }];
+
+    assert_error_matches(
+        verify(rows),
+        "TxDestructedAccount value is false at last access",
+    );
+}
+
 #[test]
 fn bad_initial_tx_log_value() {
     let rows = vec![Rw::TxLog {
@@ -49,6 +49,8 @@
 //!   - [x] Bytecode Circuit
 //!   - [x] Tx Circuit
 //!   - [ ] MPT Circuit
+mod attribution;
+mod estimation;
 pub(crate) mod precompile_block_trace;
 #[cfg(any(feature = "test", test))]
 pub(crate) mod test;
@@ -60,19 +62,27 @@ use crate::bytecode_circuit::circuit::to_poseidon_hash::{
 #[cfg(not(feature = "poseidon-codehash"))]
 use crate::bytecode_circuit::circuit::BytecodeCircuitConfig;
 use crate::{
+    blake2f_circuit::{
+        Blake2fCircuit, CircuitConfig as Blake2fCircuitConfig,
+        CircuitConfigArgs as Blake2fCircuitConfigArgs,
+    },
     bytecode_circuit::circuit::{BytecodeCircuit, BytecodeCircuitConfigArgs},
     copy_circuit::{CopyCircuit, CopyCircuitConfig, CopyCircuitConfigArgs},
     ecc_circuit::{EccCircuit, EccCircuitConfig, EccCircuitConfigArgs},
-    evm_circuit::{EvmCircuit, EvmCircuitConfig, EvmCircuitConfigArgs},
+    evm_circuit::{EvmCircuit, EvmCircuitConfig, EvmCircuitConfigArgs, ExecutionState},
     exp_circuit::{ExpCircuit, ExpCircuitArgs, ExpCircuitConfig},
     keccak_circuit::{
         keccak_packed_multi::get_num_rows_per_round, KeccakCircuit, KeccakCircuitConfig,
         KeccakCircuitConfigArgs,
     },
-    modexp_circuit::{ModExpCircuit, ModExpCircuitConfig},
+    modexp_circuit::{ModExpCircuit, ModExpCircuitConfig, ModExpCircuitConfigArgs},
     pi_circuit::{PiCircuit, PiCircuitConfig, PiCircuitConfigArgs},
     poseidon_circuit::{PoseidonCircuit, PoseidonCircuitConfig, PoseidonCircuitConfigArgs},
-    rlp_circuit_fsm::{RlpCircuit, RlpCircuitConfig, RlpCircuitConfigArgs},
+    ripemd160_circuit::{
+        CircuitConfig as Ripemd160CircuitConfig, CircuitConfigArgs as Ripemd160CircuitConfigArgs,
+        Ripemd160Circuit,
+    },
+    rlp_circuit_fsm::{RlpCircuit, RlpCircuitConfig, RlpCircuitConfigArgs, RlpFsmDataTable},
     sha256_circuit::{
         CircuitConfig as SHA256CircuitConfig, CircuitConfigArgs as SHA256CircuitConfigArgs,
         SHA256Circuit,
@@ -80,30 +90,36 @@ use crate::{
     sig_circuit::{SigCircuit, SigCircuitConfig, SigCircuitConfigArgs},
     state_circuit::{StateCircuit, StateCircuitConfig, StateCircuitConfigArgs},
     table::{
-        BlockTable, BytecodeTable, CopyTable, EccTable, ExpTable, KeccakTable, ModExpTable,
-        MptTable, PoseidonTable, PowOfRandTable, RlpFsmRlpTable as RlpTable, RwTable, SHA256Table,
-        SigTable, TxTable, U16Table, U8Table,
+        Blake2fTable, BlockTable, BytecodeTable, CopyTable, EccTable, ExpTable, KeccakTable,
+        ModExpTable, MptTable, PoseidonTable, PowOfRandTable, Ripemd160Table,
+        RlpFsmRlpTable as RlpTable, RwTable, RwTableTag, SHA256Table, SigTable, TxTable, U16Table,
+        U8Table,
     },
     tx_circuit::{TxCircuit, TxCircuitConfig, TxCircuitConfigArgs},
     util::{circuit_stats, log2_ceil, Challenges, SubCircuit, SubCircuitConfig},
     witness::{block_convert, Block, Transaction},
 };
 
+#[cfg(feature = "eth-mpt")]
+use crate::eth_mpt_circuit::{EthMptCircuit, EthMptCircuitConfig, EthMptCircuitConfigArgs};
 #[cfg(feature = "zktrie")]
 use crate::mpt_circuit::{MptCircuit, MptCircuitConfig, MptCircuitConfigArgs};
 
 use bus_mapping::{
-    circuit_input_builder::{CircuitInputBuilder, CircuitsParams},
+    circuit_input_builder::{CircuitInputBuilder, CircuitsParams, CopyDataType, KeccakSource},
     mock::BlockData,
+    precompile::PrecompileCalls,
 };
-use eth_types::{geth_types::GethData, Field};
+use eth_types::{geth_types::GethData, Field, H256};
 use halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner, Value},
     halo2curves::bn256::Fr,
     plonk::{Circuit, ConstraintSystem, Error},
 };
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use snark_verifier_sdk::CircuitExt;
+use std::fmt;
 
 /// Configuration of the Super Circuit
 #[derive(Clone)]
@@ -122,6 +138,8 @@ pub struct SuperCircuitConfig<F: Field> {
     modexp_circuit: ModExpCircuitConfig,
     ecc_circuit: EccCircuitConfig<F>,
     sha256_circuit: SHA256CircuitConfig,
+    ripemd160_circuit: Ripemd160CircuitConfig,
+    blake2f_circuit: Blake2fCircuitConfig,
     #[cfg(not(feature = "poseidon-codehash"))]
     bytecode_circuit: BytecodeCircuitConfig<F>,
     #[cfg(feature = "poseidon-codehash")]
@@ -135,6 +153,9 @@ pub struct SuperCircuitConfig<F: Field> {
     /// Mpt Circuit
     #[cfg(feature = "zktrie")]
     mpt_circuit: MptCircuitConfig<F>,
+    /// Keccak Mpt Circuit
+    #[cfg(feature = "eth-mpt")]
+    mpt_circuit: EthMptCircuitConfig<F>,
 }
 
 /// Circuit configuration arguments
@@ -196,6 +217,10 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
         log_circuit_info(meta, "keccak table");
         let sha256_table = SHA256Table::construct(meta);
         log_circuit_info(meta, "sha256 table");
+        let ripemd160_table = Ripemd160Table::construct(meta);
+        log_circuit_info(meta, "ripemd160 table");
+        let blake2f_table = Blake2fTable::construct(meta);
+        log_circuit_info(meta, "blake2f table");
         let sig_table = SigTable::construct(meta);
         log_circuit_info(meta, "sig table");
         let modexp_table = ModExpTable::construct(meta);
@@ -229,14 +254,36 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
         );
         log_circuit_info(meta, "sha256 circuit");
 
+        let ripemd160_circuit = Ripemd160CircuitConfig::new(
+            meta,
+            Ripemd160CircuitConfigArgs {
+                ripemd160_table: ripemd160_table.clone(),
+                challenges: challenges_expr.clone(),
+            },
+        );
+        log_circuit_info(meta, "ripemd160 circuit");
+
+        let blake2f_circuit = Blake2fCircuitConfig::new(
+            meta,
+            Blake2fCircuitConfigArgs {
+                blake2f_table: blake2f_table.clone(),
+                challenges: challenges_expr.clone(),
+            },
+        );
+        log_circuit_info(meta, "blake2f circuit");
+
         let poseidon_circuit =
             PoseidonCircuitConfig::new(meta, PoseidonCircuitConfigArgs { poseidon_table });
         log_circuit_info(meta, "poseidon circuit");
 
+        // the data table of the rlp circuit is shared with the eth mpt circuit, which reads the
+        // bytes of the trie nodes
+        let rlp_data_table = RlpFsmDataTable::construct(meta);
         let rlp_circuit = RlpCircuitConfig::new(
             meta,
             RlpCircuitConfigArgs {
                 rlp_table,
+                data_table: rlp_data_table,
                 u8_table,
                 challenges: challenges_expr.clone(),
             },
@@ -249,6 +296,9 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
                 block_table: block_table.clone(),
                 keccak_table: keccak_table.clone(),
                 tx_table: tx_table.clone(),
+                rw_table,
+                rlp_table,
+                u8_table,
                 challenges: challenges_expr.clone(),
             },
         );
@@ -318,7 +368,31 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
         #[cfg(feature = "zktrie")]
         log_circuit_info(meta, "zktrie circuit");
 
-        let modexp_circuit = ModExpCircuitConfig::new(meta, modexp_table);
+        #[cfg(feature = "eth-mpt")]
+        let mpt_circuit = EthMptCircuitConfig::new(
+            meta,
+            EthMptCircuitConfigArgs {
+                mpt_table,
+                keccak_table: keccak_table.clone(),
+                rlp_table,
+                rlp_data_table,
+                pow_of_rand_table,
+                u8_table,
+                challenges: challenges_expr.clone(),
+            },
+        );
+        #[cfg(feature = "eth-mpt")]
+        log_circuit_info(meta, "eth mpt circuit");
+
+        let modexp_circuit = ModExpCircuitConfig::new(
+            meta,
+            ModExpCircuitConfigArgs {
+                modexp_table,
+                u8_table,
+                u16_table,
+                challenges: challenges_expr.clone(),
+            },
+        );
         log_circuit_info(meta, "modexp circuit");
         let state_circuit = StateCircuitConfig::new(
             meta,
@@ -350,6 +424,8 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
                 copy_table,
                 keccak_table: keccak_table.clone(),
                 sha256_table,
+                ripemd160_table,
+                blake2f_table,
                 exp_table,
                 sig_table,
                 modexp_table,
@@ -400,6 +476,8 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
             bytecode_circuit,
             keccak_circuit,
             sha256_circuit,
+            ripemd160_circuit,
+            blake2f_circuit,
             poseidon_circuit,
             pi_circuit,
             rlp_circuit,
@@ -408,7 +486,7 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
             sig_circuit,
             modexp_circuit,
             ecc_circuit,
-            #[cfg(feature = "zktrie")]
+            #[cfg(any(feature = "zktrie", feature = "eth-mpt"))]
             mpt_circuit,
         }
     }
@@ -426,6 +504,162 @@ pub struct SubcircuitRowUsage {
     pub row_num_total: usize,
 }
 
+/// Rows of a sub circuit attributed to one of their sources
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubcircuitRowAttribution {
+    /// Subcircuit
+    pub subcircuit: Subcircuit,
+    /// Source of the rows, e.g. a tx and its execution state
+    pub source: RowSource,
+    /// Without padding
+    pub row_num_real: usize,
+}
+
+/// Source of the rows of a sub circuit. The txs are identified by their hash, `None` for the
+/// rows of the block outside of the txs, e.g. the EndBlock step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RowSource {
+    /// Rows of the block without a more specific source, e.g. the fixed rows of the circuit
+    Block,
+    /// Execution steps in an execution state
+    Step(Option<H256>, ExecutionState),
+    /// Rws of a rw table
+    Rw(Option<H256>, RwTableTag),
+    /// Copy events from a data type to another
+    Copy(Option<H256>, CopyDataType, CopyDataType),
+    /// Keccak inputs not accumulated by a copy event
+    Keccak(KeccakSource),
+    /// A tx, i.e. its call data, its rlp encoding and its signature
+    Tx(H256),
+    /// The receipts and their trie
+    Receipts,
+    /// A bytecode, by its code hash
+    Bytecode(H256),
+    /// Exponentiations
+    Exp,
+    /// Precompile calls
+    Precompile(PrecompileCalls),
+}
+
+impl fmt::Display for RowSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Origin(Option<H256>);
+        impl fmt::Display for Origin {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self.0 {
+                    Some(hash) => write!(f, "tx {hash:?}"),
+                    None => write!(f, "block"),
+                }
+            }
+        }
+
+        match self {
+            Self::Block => write!(f, "block"),
+            Self::Step(tx, state) => write!(f, "{} {state}", Origin(*tx)),
+            Self::Rw(tx, tag) => write!(f, "{} {tag:?}", Origin(*tx)),
+            Self::Copy(tx, src, dst) => write!(f, "{} {src:?} -> {dst:?}", Origin(*tx)),
+            Self::Keccak(source) => write!(f, "{source:?} hashes"),
+            Self::Tx(hash) => write!(f, "{}", Origin(Some(*hash))),
+            Self::Receipts => write!(f, "receipts"),
+            Self::Bytecode(hash) => write!(f, "bytecode {hash:?}"),
+            Self::Exp => write!(f, "exponentiations"),
+            Self::Precompile(call) => write!(f, "{call:?} calls"),
+        }
+    }
+}
+
+/// Sub circuits of the [`SuperCircuit`], in the order of their row usage in
+/// [`SuperCircuit::min_num_rows_block_subcircuits`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Subcircuit {
+    /// EVM Circuit
+    Evm,
+    /// State Circuit
+    State,
+    /// Bytecode Circuit
+    Bytecode,
+    /// Copy Circuit
+    Copy,
+    /// Keccak Circuit
+    Keccak,
+    /// Tx Circuit
+    Tx,
+    /// RLP Circuit
+    Rlp,
+    /// Exp Circuit
+    Exp,
+    /// ModExp Circuit
+    ModExp,
+    /// Pi Circuit
+    Pi,
+    /// Poseidon Circuit
+    Poseidon,
+    /// Sig Circuit
+    Sig,
+    /// Ecc Circuit
+    Ecc,
+    /// MPT Circuit, its rows are zero without the `zktrie` or `eth-mpt` feature
+    Mpt,
+    /// SHA256 Circuit
+    Sha256,
+    /// RIPEMD160 Circuit
+    Ripemd160,
+    /// Blake2f Circuit
+    Blake2f,
+}
+
+impl Subcircuit {
+    /// All the sub circuits, in the order of their row usage. The row usage is read by position,
+    /// so new sub circuits are only appended.
+    pub const ALL: [Self; 17] = [
+        Self::Evm,
+        Self::State,
+        Self::Bytecode,
+        Self::Copy,
+        Self::Keccak,
+        Self::Tx,
+        Self::Rlp,
+        Self::Exp,
+        Self::ModExp,
+        Self::Pi,
+        Self::Poseidon,
+        Self::Sig,
+        Self::Ecc,
+        Self::Mpt,
+        Self::Sha256,
+        Self::Ripemd160,
+        Self::Blake2f,
+    ];
+
+    /// Name of the sub circuit in its row usage.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Evm => "evm",
+            Self::State => "state",
+            Self::Bytecode => "bytecode",
+            Self::Copy => "copy",
+            Self::Keccak => "keccak",
+            Self::Sha256 => "sha256",
+            Self::Ripemd160 => "ripemd160",
+            Self::Blake2f => "blake2f",
+            Self::Tx => "tx",
+            Self::Rlp => "rlp",
+            Self::Exp => "exp",
+            Self::ModExp => "mod_exp",
+            Self::Pi => "pi",
+            Self::Poseidon => "poseidon",
+            Self::Sig => "sig",
+            Self::Ecc => "ecc",
+            Self::Mpt => "mpt",
+        }
+    }
+
+    /// Index of the sub circuit in the row usage.
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
 /// The Super Circuit contains all the zkEVM circuits
 #[derive(Clone, Debug)]
 pub struct SuperCircuit<
@@ -453,6 +687,10 @@ pub struct SuperCircuit<
     pub keccak_circuit: KeccakCircuit<F>,
     /// SHA256 Circuit
     pub sha256_circuit: SHA256Circuit<F>,
+    /// RIPEMD160 Circuit
+    pub ripemd160_circuit: Ripemd160Circuit<F>,
+    /// BLAKE2F Circuit
+    pub blake2f_circuit: Blake2fCircuit<F>,
     /// Poseidon hash Circuit
     pub poseidon_circuit: PoseidonCircuit<F>,
     /// Sig Circuit
@@ -466,6 +704,9 @@ pub struct SuperCircuit<
     /// Mpt Circuit
     #[cfg(feature = "zktrie")]
     pub mpt_circuit: MptCircuit<F>,
+    /// Keccak Mpt Circuit
+    #[cfg(feature = "eth-mpt")]
+    pub mpt_circuit: EthMptCircuit<F>,
 
     circuit_params: CircuitsParams,
 }
@@ -492,43 +733,55 @@ impl<
     pub fn min_num_rows_block_subcircuits(block: &Block<Fr>) -> Vec<SubcircuitRowUsage> {
         log::debug!("start min_num_rows_block_subcircuits");
         let mut rows = Vec::new();
-        let mut push = |name, usage| {
-            log::debug!("{name} circuit row: {usage:?}");
-            rows.push((name, usage));
+        let mut push = |subcircuit: Subcircuit, usage| {
+            log::debug!("{} circuit row: {usage:?}", subcircuit.name());
+            debug_assert_eq!(rows.len(), subcircuit.index());
+            rows.push((subcircuit.name(), usage));
         };
         let evm = EvmCircuit::min_num_rows_block(block);
-        push("evm", evm);
+        push(Subcircuit::Evm, evm);
         let state = StateCircuit::min_num_rows_block(block);
-        push("state", state);
+        push(Subcircuit::State, state);
         let bytecode = BytecodeCircuit::min_num_rows_block(block);
-        push("bytecode", bytecode);
+        push(Subcircuit::Bytecode, bytecode);
         let copy = CopyCircuit::min_num_rows_block(block);
-        push("copy", copy);
+        push(Subcircuit::Copy, copy);
         let keccak = KeccakCircuit::min_num_rows_block(block);
-        push("keccak", keccak);
-        let sha256 = SHA256Circuit::min_num_rows_block(block);
-        push("sha256", sha256);
+        push(Subcircuit::Keccak, keccak);
         let tx = TxCircuit::min_num_rows_block(block);
-        push("tx", tx);
+        push(Subcircuit::Tx, tx);
         let rlp = RlpCircuit::min_num_rows_block(block);
-        push("rlp", rlp);
+        push(Subcircuit::Rlp, rlp);
         let exp = ExpCircuit::min_num_rows_block(block);
-        push("exp", exp);
-        let mod_exp = ModExpCircuit::min_num_rows_block(block);
-        push("mod_exp", mod_exp);
+        push(Subcircuit::Exp, exp);
+        let mod_exp = ModExpCircuit::<Fr>::min_num_rows_block(block);
+        push(Subcircuit::ModExp, mod_exp);
         let pi = PiCircuit::min_num_rows_block(block);
-        push("pi", pi);
+        push(Subcircuit::Pi, pi);
         let poseidon = PoseidonCircuit::min_num_rows_block(block);
-        push("poseidon", poseidon);
+        push(Subcircuit::Poseidon, poseidon);
         let sig = SigCircuit::min_num_rows_block(block);
-        push("sig", sig);
+        push(Subcircuit::Sig, sig);
         let ecc = EccCircuit::<Fr, 9>::min_num_rows_block(block);
-        push("ecc", ecc);
+        push(Subcircuit::Ecc, ecc);
         #[cfg(feature = "zktrie")]
         {
             let mpt = MptCircuit::<Fr>::min_num_rows_block(block);
-            push("mpt", mpt);
+            push(Subcircuit::Mpt, mpt);
         }
+        #[cfg(feature = "eth-mpt")]
+        {
+            let mpt = EthMptCircuit::<Fr>::min_num_rows_block(block);
+            push(Subcircuit::Mpt, mpt);
+        }
+        #[cfg(not(any(feature = "zktrie", feature = "eth-mpt")))]
+        push(Subcircuit::Mpt, (0, 0));
+        let sha256 = SHA256Circuit::min_num_rows_block(block);
+        push(Subcircuit::Sha256, sha256);
+        let ripemd160 = Ripemd160Circuit::min_num_rows_block(block);
+        push(Subcircuit::Ripemd160, ripemd160);
+        let blake2f = Blake2fCircuit::min_num_rows_block(block);
+        push(Subcircuit::Blake2f, blake2f);
 
         let row_usage_details = rows
             .into_iter()
@@ -590,12 +843,16 @@ impl<
         let modexp_circuit = ModExpCircuit::new_from_block(block);
         let keccak_circuit = KeccakCircuit::new_from_block(block);
         let sha256_circuit = SHA256Circuit::new_from_block(block);
+        let ripemd160_circuit = Ripemd160Circuit::new_from_block(block);
+        let blake2f_circuit = Blake2fCircuit::new_from_block(block);
         let poseidon_circuit = PoseidonCircuit::new_from_block(block);
         let rlp_circuit = RlpCircuit::new_from_block(block);
         let sig_circuit = SigCircuit::new_from_block(block);
         let ecc_circuit = EccCircuit::new_from_block(block);
         #[cfg(feature = "zktrie")]
         let mpt_circuit = MptCircuit::new_from_block(block);
+        #[cfg(feature = "eth-mpt")]
+        let mpt_circuit = EthMptCircuit::new_from_block(block);
         SuperCircuit::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS> {
             evm_circuit,
             state_circuit,
@@ -606,12 +863,14 @@ impl<
             exp_circuit,
             keccak_circuit,
             sha256_circuit,
+            ripemd160_circuit,
+            blake2f_circuit,
             poseidon_circuit,
             rlp_circuit,
             sig_circuit,
             modexp_circuit,
             ecc_circuit,
-            #[cfg(feature = "zktrie")]
+            #[cfg(any(feature = "zktrie", feature = "eth-mpt"))]
             mpt_circuit,
             circuit_params: block.circuits_params,
         }
@@ -667,6 +926,12 @@ impl<
         log::debug!("assigning sha256_circuit");
         self.sha256_circuit
             .synthesize_sub(&config.sha256_circuit, challenges, layouter)?;
+        log::debug!("assigning ripemd160_circuit");
+        self.ripemd160_circuit
+            .synthesize_sub(&config.ripemd160_circuit, challenges, layouter)?;
+        log::debug!("assigning blake2f_circuit");
+        self.blake2f_circuit
+            .synthesize_sub(&config.blake2f_circuit, challenges, layouter)?;
         log::debug!("assigning poseidon_circuit");
         self.poseidon_circuit
             .synthesize_sub(&config.poseidon_circuit, challenges, layouter)?;
@@ -711,7 +976,7 @@ impl<
             .synthesize_sub(&config.rlp_circuit, challenges, layouter)?;
 
         // load both poseidon table and zktrie table
-        #[cfg(feature = "zktrie")]
+        #[cfg(any(feature = "zktrie", feature = "eth-mpt"))]
         {
             log::debug!("assigning mpt_circuit");
             self.mpt_circuit
@@ -0,0 +1,430 @@
+//! Attribution of the rows used by the subcircuits of the [`SuperCircuit`] to their sources,
+//! to explain which txs, execution states, rws, copy events, keccak inputs and precompile
+//! calls fill a subcircuit.
+//!
+//! The rows are the ones of [`SuperCircuit::estimate_num_rows_subcircuits`]. The rows of a
+//! subcircuit computed from the whole block, e.g. the rows of the Tx circuit which grow with
+//! the total call data length, are shared among the sources in proportion to their
+//! contribution. The rows without a more specific source, e.g. the fixed rows of a
+//! subcircuit, are attributed to [`RowSource::Block`], so that the attributed rows of each
+//! subcircuit sum to its estimated rows.
+
+use super::{RowSource, Subcircuit, SubcircuitRowAttribution, SubcircuitRowUsage, SuperCircuit};
+use crate::{
+    ecc_circuit::EccCircuit,
+    evm_circuit::ExecutionState,
+    keccak_circuit::KeccakCircuit,
+    poseidon_circuit::PoseidonCircuit,
+    table::RwTableTag,
+    witness::{receipt_rlps, rlp_fsm::rlp_fsm_num_rows},
+};
+use bus_mapping::{
+    circuit_input_builder::{
+        keccak_input_lens, Block, CopyDataType, KeccakSource, ReceiptLog, Transaction,
+    },
+    operation::{Op, Operation},
+    precompile::PrecompileCalls,
+    state_db::CodeDB,
+    Error,
+};
+#[cfg(any(feature = "zktrie", feature = "eth-mpt"))]
+use eth_types::{Address, Word};
+use eth_types::{Field, H256};
+use halo2_proofs::halo2curves::bn256::Fr;
+#[cfg(any(feature = "zktrie", feature = "eth-mpt"))]
+use std::collections::BTreeSet;
+use std::{cmp::Reverse, collections::HashMap};
+
+impl<
+        F: Field,
+        const MAX_TXS: usize,
+        const MAX_CALLDATA: usize,
+        const MAX_INNER_BLOCKS: usize,
+        const MOCK_RANDOMNESS: u64,
+    > SuperCircuit<F, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
+{
+    /// Attribute the rows of [`Self::estimate_num_rows_subcircuits`] to their sources, in
+    /// the finalized bus-mapping block.
+    ///
+    /// The attribution costs more than the estimation, it is meant to explain a row usage
+    /// over the limit rather than to be computed for every block.
+    pub fn row_attributions_subcircuits(
+        block: &Block,
+        code_db: &CodeDB,
+    ) -> Result<Vec<SubcircuitRowAttribution>, Error> {
+        let rows = Self::estimate_num_rows_subcircuits(block, code_db)?;
+        let real_rows = |subcircuit: Subcircuit| rows[subcircuit.index()].row_num_real;
+        let params = &block.circuits_params;
+        let locator = TxLocator::new(block);
+        let mut attributions = Attributions::default();
+
+        for tx in block.txs() {
+            for step in tx.steps() {
+                let state = ExecutionState::from(step);
+                attributions.add(
+                    Subcircuit::Evm,
+                    RowSource::Step(Some(tx.hash), state),
+                    state.get_step_height(),
+                );
+            }
+        }
+
+        let container = &block.container;
+        attributions.add_rws(&locator, RwTableTag::Memory, &container.memory);
+        attributions.add_rws(&locator, RwTableTag::Stack, &container.stack);
+        attributions.add_rws(&locator, RwTableTag::AccountStorage, &container.storage);
+        attributions.add_rws(
+            &locator,
+            RwTableTag::TxAccessListAccount,
+            &container.tx_access_list_account,
+        );
+        attributions.add_rws(
+            &locator,
+            RwTableTag::TxAccessListAccountStorage,
+            &container.tx_access_list_account_storage,
+        );
+        attributions.add_rws(&locator, RwTableTag::TxRefund, &container.tx_refund);
+        attributions.add_rws(&locator, RwTableTag::Account, &container.account);
+        attributions.add_rws(&locator, RwTableTag::CallContext, &container.call_context);
+        attributions.add_rws(&locator, RwTableTag::TxReceipt, &container.tx_receipt);
+        attributions.add_rws(&locator, RwTableTag::TxLog, &container.tx_log);
+        attributions.add_rws(
+            &locator,
+            RwTableTag::TransientStorage,
+            &container.transient_storage,
+        );
+        attributions.add_rws(
+            &locator,
+            RwTableTag::TxCreatedAccount,
+            &container.tx_created_account,
+        );
+        attributions.add_rws(
+            &locator,
+            "tx_destructed_account",
+            &container.tx_destructed_account,
+        );
+        attributions.add_rws(&locator, RwTableTag::Start, &container.start);
+
+        for (hash, bytecode) in &code_db.0 {
+            let source = RowSource::Bytecode(*hash);
+            attributions.add(Subcircuit::Bytecode, source, bytecode.len() + 1);
+            attributions.add(
+                Subcircuit::Poseidon,
+                source,
+                PoseidonCircuit::<Fr>::min_num_rows(0, [bytecode.len()].into_iter(), 0).0,
+            );
+        }
+
+        for event in &block.copy_events {
+            attributions.add(
+                Subcircuit::Copy,
+                RowSource::Copy(
+                    locator.tx(event.rw_counter_start.0),
+                    event.src_type,
+                    event.dst_type,
+                ),
+                event.full_length() as usize * 2,
+            );
+        }
+
+        // The inputs hashed by the opcodes are attributed to the copy events accumulating
+        // them, the remaining ones (e.g. contract addresses) to their source.
+        let mut copy_keccak_rows = 0;
+        for event in block
+            .copy_events
+            .iter()
+            .filter(|event| event.dst_type == CopyDataType::RlcAcc)
+        {
+            let rows = KeccakCircuit::<Fr>::num_rows_of_input(event.full_length() as usize);
+            copy_keccak_rows += rows;
+            attributions.add(
+                Subcircuit::Keccak,
+                RowSource::Copy(
+                    locator.tx(event.rw_counter_start.0),
+                    event.src_type,
+                    event.dst_type,
+                ),
+                rows,
+            );
+        }
+        let mut keccak_rows = HashMap::<KeccakSource, usize>::new();
+        for (source, len) in keccak_input_lens(block) {
+            *keccak_rows.entry(source).or_default() += KeccakCircuit::<Fr>::num_rows_of_input(len);
+        }
+        for (source, rows) in keccak_rows {
+            let rows = if source == KeccakSource::Opcodes {
+                rows.saturating_sub(copy_keccak_rows)
+            } else {
+                rows
+            };
+            attributions.add(Subcircuit::Keccak, RowSource::Keccak(source), rows);
+        }
+        let receipts = block.receipts();
+        let receipt_rlps = receipt_rlps(&receipts, 0);
+        let receipts_keccak_rows = receipts
+            .iter()
+            .flat_map(|receipt| &receipt.logs)
+            .flat_map(ReceiptLog::bloom_items)
+            .map(<[u8]>::len)
+            .chain(
+                receipt_rlps[receipts.len()..]
+                    .iter()
+                    .map(|rlp| rlp.bytes.len()),
+            )
+            .map(KeccakCircuit::<Fr>::num_rows_of_input)
+            .sum();
+        attributions.add(
+            Subcircuit::Keccak,
+            RowSource::Keccak(KeccakSource::Receipts),
+            receipts_keccak_rows,
+        );
+
+        let precompile_events = &block.precompile_events;
+        for (subcircuit, call, num_events) in [
+            (
+                Subcircuit::Sha256,
+                PrecompileCalls::Sha256,
+                precompile_events.get_sha256_events().len(),
+            ),
+            (
+                Subcircuit::Ripemd160,
+                PrecompileCalls::Ripemd160,
+                precompile_events.get_ripemd160_events().len(),
+            ),
+            (
+                Subcircuit::Blake2f,
+                PrecompileCalls::Blake2F,
+                precompile_events.get_blake2f_events().len(),
+            ),
+            (
+                Subcircuit::ModExp,
+                PrecompileCalls::Modexp,
+                precompile_events.get_modexp_events().len(),
+            ),
+        ] {
+            if num_events > 0 {
+                attributions.add(
+                    subcircuit,
+                    RowSource::Precompile(call),
+                    real_rows(subcircuit),
+                );
+            }
+        }
+        if !block.exp_events.is_empty() {
+            attributions.add(Subcircuit::Exp, RowSource::Exp, real_rows(Subcircuit::Exp));
+        }
+
+        attributions.add_shares(
+            Subcircuit::Tx,
+            real_rows(Subcircuit::Tx),
+            block
+                .txs()
+                .iter()
+                .map(|tx| (RowSource::Tx(tx.hash), tx.input.len())),
+        );
+        attributions.add_shares(
+            Subcircuit::Pi,
+            real_rows(Subcircuit::Pi),
+            block.txs().iter().map(|tx| (RowSource::Tx(tx.hash), 1)),
+        );
+
+        for tx in block.txs() {
+            let sign_rows = if tx.tx_type.is_l1_msg() {
+                0
+            } else {
+                rlp_fsm_num_rows(&tx.rlp_unsigned_bytes)
+            };
+            attributions.add(
+                Subcircuit::Rlp,
+                RowSource::Tx(tx.hash),
+                sign_rows + rlp_fsm_num_rows(&tx.rlp_bytes),
+            );
+        }
+        attributions.add(
+            Subcircuit::Rlp,
+            RowSource::Receipts,
+            receipt_rlps
+                .iter()
+                .map(|rlp| rlp_fsm_num_rows(&rlp.bytes))
+                .sum(),
+        );
+
+        attributions.add_shares(
+            Subcircuit::Sig,
+            real_rows(Subcircuit::Sig),
+            block
+                .txs()
+                .iter()
+                .filter(|tx| !tx.tx_type.is_l1_msg())
+                .map(|tx| (RowSource::Tx(tx.hash), 1))
+                .chain([(
+                    RowSource::Precompile(PrecompileCalls::Ecrecover),
+                    precompile_events.get_ecrecover_events().len(),
+                )]),
+        );
+
+        // the ecc circuit uses the rows of the op kind closest to its limit
+        let (ec_adds, ec_muls, ec_pairings) = (
+            precompile_events.get_ec_add_events().len(),
+            precompile_events.get_ec_mul_events().len(),
+            precompile_events.get_ec_pairing_events().len(),
+        );
+        attributions.add_shares(
+            Subcircuit::Ecc,
+            real_rows(Subcircuit::Ecc),
+            [
+                (
+                    PrecompileCalls::Bn128Add,
+                    EccCircuit::<Fr, 9>::min_num_rows_of(ec_adds, 0, 0, params).0,
+                ),
+                (
+                    PrecompileCalls::Bn128Mul,
+                    EccCircuit::<Fr, 9>::min_num_rows_of(0, ec_muls, 0, params).0,
+                ),
+                (
+                    PrecompileCalls::Bn128Pairing,
+                    EccCircuit::<Fr, 9>::min_num_rows_of(0, 0, ec_pairings, params).0,
+                ),
+            ]
+            .map(|(call, rows)| (RowSource::Precompile(call), rows)),
+        );
+
+        #[cfg(any(feature = "zktrie", feature = "eth-mpt"))]
+        {
+            let account_keys: BTreeSet<(Address, _)> = container
+                .account
+                .iter()
+                .map(|op| (op.op().address, op.op().field))
+                .collect();
+            let storage_keys: BTreeSet<(usize, Address, Word)> = container
+                .storage
+                .iter()
+                .map(|op| (op.op().tx_id, op.op().address, op.op().key))
+                .collect();
+            let mut updates = HashMap::<RowSource, usize>::new();
+            *updates
+                .entry(RowSource::Rw(None, RwTableTag::Account))
+                .or_default() += account_keys.len();
+            for (tx_id, _, _) in storage_keys {
+                let tx = block.txs().get(tx_id.wrapping_sub(1)).map(|tx| tx.hash);
+                *updates
+                    .entry(RowSource::Rw(tx, RwTableTag::AccountStorage))
+                    .or_default() += 1;
+            }
+            attributions.add_shares(Subcircuit::Mpt, real_rows(Subcircuit::Mpt), updates);
+        }
+
+        Ok(attributions.into_vec(&rows))
+    }
+}
+
+/// Finds the tx a rw counter belongs to.
+struct TxLocator<'a> {
+    txs: &'a [Transaction],
+    /// Rw counter of the first step of each tx.
+    starts: Vec<usize>,
+    /// Rw counter of the EndBlock step.
+    end: usize,
+}
+
+impl<'a> TxLocator<'a> {
+    fn new(block: &'a Block) -> Self {
+        let starts: Vec<_> = block
+            .txs()
+            .iter()
+            .map(|tx| tx.steps().first().map_or(0, |step| step.rwc.0))
+            .collect();
+        let end = block.block_steps.end_block_last.rwc.0;
+        Self {
+            txs: block.txs(),
+            // the EndBlock step is only set when the block is finalized
+            end: if starts.last().map_or(false, |start| end <= *start) {
+                usize::MAX
+            } else {
+                end
+            },
+            starts,
+        }
+    }
+
+    /// Hash of the tx of the rw counter, `None` before the first tx and from the EndBlock
+    /// step.
+    fn tx(&self, rwc: usize) -> Option<H256> {
+        if rwc >= self.end {
+            return None;
+        }
+        match self.starts.partition_point(|start| *start <= rwc) {
+            0 => None,
+            idx => Some(self.txs[idx - 1].hash),
+        }
+    }
+}
+
+/// Rows accumulated by (subcircuit, source).
+#[derive(Default)]
+struct Attributions(HashMap<(Subcircuit, RowSource), usize>);
+
+impl Attributions {
+    fn add(&mut self, subcircuit: Subcircuit, source: RowSource, rows: usize) {
+        if rows > 0 {
+            *self.0.entry((subcircuit, source)).or_default() += rows;
+        }
+    }
+
+    fn add_rws<T: Op>(&mut self, locator: &TxLocator, tag: RwTableTag, ops: &[Operation<T>]) {
+        for op in ops {
+            self.add(
+                Subcircuit::State,
+                RowSource::Rw(locator.tx(op.rwc().0), tag),
+                1,
+            );
+        }
+    }
+
+    /// Share the `rows` among the sources, in proportion to their weight.
+    fn add_shares(
+        &mut self,
+        subcircuit: Subcircuit,
+        rows: usize,
+        shares: impl IntoIterator<Item = (RowSource, usize)>,
+    ) {
+        let shares: Vec<_> = shares.into_iter().collect();
+        let total_weight: usize = shares.iter().map(|(_, weight)| weight).sum();
+        if total_weight == 0 {
+            return;
+        }
+        for (source, weight) in shares {
+            self.add(subcircuit, source, rows * weight / total_weight);
+        }
+    }
+
+    /// The attributions of each subcircuit, the rows not attributed yet going to the block.
+    fn into_vec(mut self, rows: &[SubcircuitRowUsage]) -> Vec<SubcircuitRowAttribution> {
+        for (subcircuit, row_usage) in Subcircuit::ALL.into_iter().zip(rows) {
+            let attributed_rows: usize = self
+                .0
+                .iter()
+                .filter(|((s, _), _)| *s == subcircuit)
+                .map(|(_, rows)| rows)
+                .sum();
+            self.add(
+                subcircuit,
+                RowSource::Block,
+                row_usage.row_num_real.saturating_sub(attributed_rows),
+            );
+        }
+        let mut attributions: Vec<_> = self
+            .0
+            .into_iter()
+            .map(
+                |((subcircuit, source), row_num_real)| SubcircuitRowAttribution {
+                    subcircuit,
+                    source,
+                    row_num_real,
+                },
+            )
+            .collect();
+        attributions.sort_by_key(|x| (x.subcircuit, Reverse(x.row_num_real)));
+        attributions
+    }
+}
@@ -0,0 +1,331 @@
+//! Fast estimation of the rows used by the subcircuits of the [`SuperCircuit`].
+//!
+//! [`block_convert`](crate::witness::block_convert) spends most of its time on the rw table,
+//! the mpt updates derived from it and the keccak inputs, which recover the signer of every tx.
+//! Here no witness block is built: the rows of each subcircuit are counted from the execution
+//! steps, the operations and the events of the bus-mapping block, with the same formulas as
+//! the `min_num_rows_block` of the subcircuit. The EVM circuit rows come from a table of the
+//! number of steps of each [`ExecutionState`], weighted by its height.
+//!
+//! The mpt witness is not generated, so the poseidon hashes of the zktrie paths, and the trie
+//! nodes of the Ethereum state proofs, are left to the caller.
+
+use super::{Subcircuit, SubcircuitRowUsage, SuperCircuit};
+#[cfg(feature = "eth-mpt")]
+use crate::eth_mpt_circuit::EthMptCircuit;
+#[cfg(feature = "zktrie")]
+use crate::mpt_circuit::MptCircuit;
+use crate::{
+    blake2f_circuit::Blake2fCircuit,
+    bytecode_circuit::circuit::BytecodeCircuit,
+    copy_circuit::CopyCircuit,
+    ecc_circuit::EccCircuit,
+    evm_circuit::{is_bitwise_opcode, EvmCircuit, ExecutionState},
+    exp_circuit::ExpCircuitConfig,
+    keccak_circuit::KeccakCircuit,
+    modexp_circuit::ModExpCircuit,
+    pi_circuit::PiCircuit,
+    poseidon_circuit::PoseidonCircuit,
+    ripemd160_circuit::Ripemd160Circuit,
+    sha256_circuit::SHA256Circuit,
+    sig_circuit::SigCircuit,
+    state_circuit::StateCircuit,
+    tx_circuit::TxCircuit,
+    witness::{receipt_rlps, rlp_fsm::rlp_fsm_num_rows},
+};
+use bus_mapping::{
+    circuit_input_builder::{keccak_input_lens, Block, ExecState, ReceiptLog},
+    operation::OperationContainer,
+    state_db::CodeDB,
+    Error,
+};
+use eth_types::Field;
+use halo2_proofs::halo2curves::bn256::Fr;
+#[cfg(any(feature = "zktrie", feature = "eth-mpt"))]
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+
+impl<
+        F: Field,
+        const MAX_TXS: usize,
+        const MAX_CALLDATA: usize,
+        const MAX_INNER_BLOCKS: usize,
+        const MOCK_RANDOMNESS: u64,
+    > SuperCircuit<F, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
+{
+    /// Estimate the number of rows required to prove the bus-mapping block without building
+    /// the witness block. The estimation is never lower than
+    /// [`Self::min_num_rows_block_subcircuits`] of the converted block.
+    pub fn estimate_num_rows_subcircuits(
+        block: &Block,
+        code_db: &CodeDB,
+    ) -> Result<Vec<SubcircuitRowUsage>, Error> {
+        let params = &block.circuits_params;
+        let receipts = block.receipts();
+        let receipt_rlps = receipt_rlps(&receipts, 0);
+
+        let mut rows = Vec::new();
+        let mut push = |subcircuit: Subcircuit, (row_num_real, row_num_total)| {
+            log::debug!(
+                "{} circuit row (estimated): ({row_num_real}, {row_num_total})",
+                subcircuit.name()
+            );
+            debug_assert_eq!(rows.len(), subcircuit.index());
+            rows.push(SubcircuitRowUsage {
+                name: subcircuit.name().to_string(),
+                row_num_real,
+                row_num_total,
+            });
+        };
+
+        let execution_state_counts = execution_state_counts(block);
+        let num_rows_required_for_execution_steps = 1 // for the unused `next` row
+            + execution_state_counts
+                .iter()
+                .map(|(execution_state, count)| execution_state.get_step_height() * count)
+                .sum::<usize>()
+            + 1; // EndBlock
+        let need_bitwise_lookup = block
+            .txs
+            .iter()
+            .flat_map(|tx| tx.steps())
+            .any(|step| matches!(step.exec_state, ExecState::Op(op) if is_bitwise_opcode(op)));
+        push(
+            Subcircuit::Evm,
+            EvmCircuit::<Fr>::min_num_rows(
+                num_rows_required_for_execution_steps,
+                need_bitwise_lookup,
+                params.max_evm_rows,
+            ),
+        );
+
+        let max_rws = if params.max_rws == 0 {
+            let end_block_last = &block.block_steps.end_block_last;
+            end_block_last.rwc.0 + end_block_last.bus_mapping_instance.len() + 1
+        } else {
+            params.max_rws
+        };
+        push(
+            Subcircuit::State,
+            StateCircuit::<Fr>::min_num_rows(num_rws(&block.container), max_rws),
+        );
+
+        push(
+            Subcircuit::Bytecode,
+            BytecodeCircuit::<Fr>::min_num_rows(
+                code_db.0.values().map(Vec::len),
+                params.max_bytecode,
+            ),
+        );
+
+        push(
+            Subcircuit::Copy,
+            CopyCircuit::<Fr>::min_num_rows(&block.copy_events, params.max_copy_rows),
+        );
+
+        let keccak_input_lens = keccak_input_lens(block)
+            .into_iter()
+            .map(|(_, len)| len)
+            .chain(
+                receipts
+                    .iter()
+                    .flat_map(|receipt| &receipt.logs)
+                    .flat_map(ReceiptLog::bloom_items)
+                    .map(<[u8]>::len),
+            )
+            // the receipts themselves are hashed as the values of the trie leaves
+            .chain(
+                receipt_rlps[receipts.len()..]
+                    .iter()
+                    .map(|rlp| rlp.bytes.len()),
+            );
+        push(
+            Subcircuit::Keccak,
+            KeccakCircuit::<Fr>::min_num_rows(keccak_input_lens, params.max_keccak_rows),
+        );
+
+        let precompile_events = &block.precompile_events;
+        push(
+            Subcircuit::Tx,
+            TxCircuit::<Fr>::min_num_rows_of(
+                block.txs.iter().map(|tx| tx.input.len()).sum(),
+                params,
+            ),
+        );
+
+        let sm_rows = block
+            .txs
+            .iter()
+            .map(|tx| {
+                let sign_rows = if tx.tx_type.is_l1_msg() {
+                    0
+                } else {
+                    rlp_fsm_num_rows(&tx.rlp_unsigned_bytes)
+                };
+                sign_rows + rlp_fsm_num_rows(&tx.rlp_bytes)
+            })
+            .chain(receipt_rlps.iter().map(|rlp| rlp_fsm_num_rows(&rlp.bytes)))
+            .sum();
+        push(Subcircuit::Rlp, (sm_rows, params.max_rlp_rows));
+
+        push(
+            Subcircuit::Exp,
+            (
+                ExpCircuitConfig::<Fr>::min_num_rows(&block.exp_events),
+                params.max_exp_steps,
+            ),
+        );
+
+        push(
+            Subcircuit::ModExp,
+            ModExpCircuit::<Fr>::min_num_rows_of(
+                &precompile_events.get_modexp_events(),
+                params.max_keccak_rows,
+            ),
+        );
+
+        push(
+            Subcircuit::Pi,
+            PiCircuit::<Fr>::min_num_rows(block.txs.len(), params),
+        );
+
+        push(
+            Subcircuit::Poseidon,
+            PoseidonCircuit::<Fr>::min_num_rows(
+                0,
+                code_db.0.values().map(Vec::len),
+                params.max_poseidon_rows,
+            ),
+        );
+
+        let ecdsa_verif_count = block
+            .txs
+            .iter()
+            .filter(|tx| !tx.tx_type.is_l1_msg())
+            .count()
+            + precompile_events.get_ecrecover_events().len();
+        push(
+            Subcircuit::Sig,
+            SigCircuit::<Fr>::min_num_rows_of(ecdsa_verif_count, params.max_vertical_circuit_rows),
+        );
+
+        push(
+            Subcircuit::Ecc,
+            EccCircuit::<Fr, 9>::min_num_rows_of(
+                precompile_events.get_ec_add_events().len(),
+                precompile_events.get_ec_mul_events().len(),
+                precompile_events.get_ec_pairing_events().len(),
+                params,
+            ),
+        );
+
+        #[cfg(feature = "zktrie")]
+        {
+            let mpt =
+                MptCircuit::min_num_rows(num_mpt_updates(&block.container), params.max_mpt_rows);
+            push(Subcircuit::Mpt, mpt);
+        }
+        #[cfg(feature = "eth-mpt")]
+        {
+            let mpt = EthMptCircuit::<Fr>::min_num_rows(
+                num_mpt_updates(&block.container),
+                params.max_mpt_rows,
+            );
+            push(Subcircuit::Mpt, mpt);
+        }
+        #[cfg(not(any(feature = "zktrie", feature = "eth-mpt")))]
+        push(Subcircuit::Mpt, (0, 0));
+
+        push(
+            Subcircuit::Sha256,
+            SHA256Circuit::<Fr>::min_num_rows(
+                precompile_events.get_sha256_events(),
+                params.max_keccak_rows,
+            ),
+        );
+        push(
+            Subcircuit::Ripemd160,
+            Ripemd160Circuit::<Fr>::min_num_rows(
+                precompile_events.get_ripemd160_events(),
+                params.max_keccak_rows,
+            ),
+        );
+        push(
+            Subcircuit::Blake2f,
+            Blake2fCircuit::<Fr>::min_num_rows(
+                precompile_events.get_blake2f_events(),
+                params.max_keccak_rows,
+            ),
+        );
+
+        Ok(rows)
+    }
+}
+
+/// Number of steps of each [`ExecutionState`] assigned by the EVM circuit for the txs of the
+/// block, including the EndInnerBlock steps which follow the last tx of each block.
+fn execution_state_counts(block: &Block) -> HashMap<ExecutionState, usize> {
+    let mut counts = HashMap::new();
+    for step in block.txs.iter().flat_map(|tx| tx.steps()) {
+        *counts.entry(ExecutionState::from(step)).or_default() += 1;
+    }
+
+    let last_block_num = block
+        .headers
+        .keys()
+        .next_back()
+        .copied()
+        .unwrap_or_default();
+    let num_end_inner_blocks: u64 = block
+        .txs
+        .iter()
+        .enumerate()
+        .map(|(idx, tx)| {
+            let next_block_num = block
+                .txs
+                .get(idx + 1)
+                .map_or(last_block_num + 1, |next_tx| next_tx.block_num);
+            next_block_num - tx.block_num
+        })
+        .sum();
+    if num_end_inner_blocks > 0 {
+        *counts.entry(ExecutionState::EndInnerBlock).or_default() += num_end_inner_blocks as usize;
+    }
+
+    counts
+}
+
+/// Number of rws in the container, each of them is assigned to a row of the State circuit.
+fn num_rws(container: &OperationContainer) -> usize {
+    container.memory.len()
+        + container.stack.len()
+        + container.storage.len()
+        + container.tx_access_list_account.len()
+        + container.tx_access_list_account_storage.len()
+        + container.tx_refund.len()
+        + container.account.len()
+        + container.call_context.len()
+        + container.tx_receipt.len()
+        + container.tx_log.len()
+        + container.transient_storage.len()
+        + container.tx_created_account.len()
+        + container.tx_destructed_account.len()
+        + container.start.len()
+}
+
+/// Number of mpt updates, one for each account field and each storage slot (per tx) touched
+/// in the block.
+#[cfg(any(feature = "zktrie", feature = "eth-mpt"))]
+fn num_mpt_updates(container: &OperationContainer) -> usize {
+    let account_keys: BTreeSet<_> = container
+        .account
+        .iter()
+        .map(|op| (op.op().address, op.op().field))
+        .collect();
+    let storage_keys: BTreeSet<_> = container
+        .storage
+        .iter()
+        .map(|op| (op.op().tx_id, op.op().address, op.op().key))
+        .collect();
+    account_keys.len() + storage_keys.len()
+}
@@ -7,6 +7,7 @@ use bus_mapping::{
 };
 use ethers_signers::{LocalWallet, Signer};
 use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+use itertools::Itertools;
 use log::error;
 #[cfg(not(feature = "scroll"))]
 use mock::MOCK_DIFFICULTY;
@@ -485,3 +486,203 @@ fn serial_test_super_circuit_precompile_sha256() {
 
     test_super_circuit::<MAX_TXS, MAX_CALLDATA, 1, TEST_MOCK_RANDOMNESS>(block, circuits_params);
 }
+
+fn block_1tx_storage_ctx() -> TestContext<2, 1> {
+    let bytecode = bytecode! {
+        PUSH1(0x20)
+        PUSH1(0)
+        PUSH1(0)
+        CALLDATACOPY
+        PUSH1(0x20)
+        PUSH1(0)
+        SHA3
+        PUSH1(1)
+        SSTORE
+        PUSH1(0x20)
+        PUSH1(0)
+        LOG0
+        STOP
+    };
+
+    TestContext::<2, 1>::new(
+        None,
+        |accs| {
+            accs[0]
+                .address(address!("0x000000000000000000000000000000000000cafe"))
+                .code(bytecode);
+            accs[1]
+                .address(address!("0x000000000000000000000000000000000000beef"))
+                .balance(eth(10));
+        },
+        |mut txs, accs| {
+            txs[0]
+                .from(accs[1].address)
+                .to(accs[0].address)
+                .input(vec![0xab; 0x40].into())
+                .gas(Word::from(1_000_000u64));
+        },
+        |block, _tx| block.number(0xcafeu64),
+    )
+    .unwrap()
+}
+
+#[test]
+fn super_circuit_estimated_rows_not_lower_than_witness_rows() {
+    for block in [block_1tx(), block_2tx(), block_1tx_storage_ctx().into()] {
+        let circuits_params = CircuitsParams {
+            max_txs: 2,
+            max_calldata: 256,
+            ..Default::default()
+        };
+        let mut builder = BlockData::new_from_geth_data_with_params(block.clone(), circuits_params)
+            .new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let witness_block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+        type Circuit = SuperCircuit<Fr, 2, 256, 64, 0x100>;
+        let rows = Circuit::min_num_rows_block_subcircuits(&witness_block);
+        let estimated_rows =
+            Circuit::estimate_num_rows_subcircuits(&builder.block, &builder.code_db).unwrap();
+
+        assert_eq!(rows.len(), estimated_rows.len());
+        for (row, estimated_row) in rows.iter().zip(estimated_rows.iter()) {
+            assert_eq!(row.name, estimated_row.name);
+            assert!(
+                estimated_row.row_num_real >= row.row_num_real,
+                "{} circuit rows underestimated: {} < {}",
+                row.name,
+                estimated_row.row_num_real,
+                row.row_num_real
+            );
+            assert!(estimated_row.row_num_total >= row.row_num_total);
+        }
+        // the step heights, the rws and the rlp encodings are counted exactly, the trie nodes
+        // of the eth-mpt state proofs aside
+        let exact_subcircuits = [Subcircuit::Evm, Subcircuit::State]
+            .into_iter()
+            .chain((!cfg!(feature = "eth-mpt")).then_some(Subcircuit::Rlp));
+        for subcircuit in exact_subcircuits {
+            assert_eq!(
+                estimated_rows[subcircuit.index()].row_num_real,
+                rows[subcircuit.index()].row_num_real,
+                "{} circuit rows",
+                subcircuit.name()
+            );
+        }
+    }
+}
+
+#[test]
+fn super_circuit_row_attributions_cover_witness_rows() {
+    let block: GethData = block_1tx_storage_ctx().into();
+    let circuits_params = CircuitsParams {
+        max_txs: 2,
+        max_calldata: 256,
+        ..Default::default()
+    };
+    let mut builder = BlockData::new_from_geth_data_with_params(block.clone(), circuits_params)
+        .new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    let witness_block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+    type Circuit = SuperCircuit<Fr, 2, 256, 64, 0x100>;
+    let rows = Circuit::min_num_rows_block_subcircuits(&witness_block);
+    let estimated_rows =
+        Circuit::estimate_num_rows_subcircuits(&builder.block, &builder.code_db).unwrap();
+    let attributions =
+        Circuit::row_attributions_subcircuits(&builder.block, &builder.code_db).unwrap();
+    let attributed_rows = |subcircuit: Subcircuit| -> usize {
+        attributions
+            .iter()
+            .filter(|attribution| attribution.subcircuit == subcircuit)
+            .map(|attribution| attribution.row_num_real)
+            .sum()
+    };
+
+    for (subcircuit, estimated) in Subcircuit::ALL.into_iter().zip(&estimated_rows) {
+        assert_eq!(
+            attributed_rows(subcircuit),
+            estimated.row_num_real,
+            "{} circuit rows",
+            subcircuit.name()
+        );
+    }
+    assert_eq!(
+        attributed_rows(Subcircuit::Evm),
+        rows[Subcircuit::Evm.index()].row_num_real
+    );
+    assert_eq!(
+        attributed_rows(Subcircuit::State),
+        rows[Subcircuit::State.index()].row_num_real
+    );
+    let tx_hash = builder.block.txs()[0].hash;
+    assert!(attributions.iter().any(|attribution| {
+        attribution.subcircuit == Subcircuit::State
+            && attribution.source == RowSource::Rw(Some(tx_hash), RwTableTag::AccountStorage)
+    }));
+    assert!(attributions.iter().any(|attribution| {
+        attribution.subcircuit == Subcircuit::Rlp && attribution.source == RowSource::Tx(tx_hash)
+    }));
+}
+
+#[test]
+fn super_circuit_from_serialized_witness_block() {
+    let block: GethData = block_1tx_storage_ctx().into();
+    let circuits_params = CircuitsParams {
+        max_txs: 2,
+        max_calldata: 256,
+        ..Default::default()
+    };
+    let mut builder = BlockData::new_from_geth_data_with_params(block.clone(), circuits_params)
+        .new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    let witness_block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+    type Circuit = SuperCircuit<Fr, 2, 256, 64, 0x100>;
+    let (k, _, instance) = Circuit::build_from_witness_block(witness_block.clone()).unwrap();
+
+    let bytes = witness_block.to_bytes().unwrap();
+    let decoded = Block::<Fr>::from_bytes(&bytes).unwrap();
+    // the geth block and the smt traces are stored as is, not re-derived
+    for (ctx, decoded_ctx) in witness_block
+        .context
+        .ctxs
+        .values()
+        .zip_eq(decoded.context.ctxs.values())
+    {
+        assert_eq!(decoded_ctx.eth_block, ctx.eth_block);
+    }
+    assert_eq!(
+        serde_json::to_value(&decoded.mpt_updates.smt_traces).unwrap(),
+        serde_json::to_value(&witness_block.mpt_updates.smt_traces).unwrap()
+    );
+    assert_eq!(
+        decoded
+            .mpt_updates
+            .proof_types
+            .iter()
+            .map(|&proof_type| proof_type as u64)
+            .collect_vec(),
+        witness_block
+            .mpt_updates
+            .proof_types
+            .iter()
+            .map(|&proof_type| proof_type as u64)
+            .collect_vec()
+    );
+    let (decoded_k, _, decoded_instance) = Circuit::build_from_witness_block(decoded).unwrap();
+    assert_eq!(decoded_k, k);
+    assert_eq!(decoded_instance, instance);
+
+    let json = serde_json::to_string(&witness_block).unwrap();
+    let decoded: Block<Fr> = serde_json::from_str(&json).unwrap();
+    let (decoded_k, _, decoded_instance) = Circuit::build_from_witness_block(decoded).unwrap();
+    assert_eq!(decoded_k, k);
+    assert_eq!(decoded_instance, instance);
+}
@@ -16,10 +16,10 @@ use crate::{
 };
 use bus_mapping::{
     circuit_input_builder::{
-        BigModExp, CopyDataType, CopyEvent, CopyStep, EcAddOp, EcMulOp, EcPairingOp, ExpEvent,
-        PrecompileEcParams, N_BYTES_PER_PAIR, N_PAIRING_PER_OP,
+        BigModExp, Blake2fOp, CopyDataType, CopyEvent, CopyStep, EcAddOp, EcMulOp, EcPairingOp,
+        ExpEvent, PrecompileEcParams, N_BYTES_PER_PAIR, N_PAIRING_PER_OP,
     },
-    precompile::PrecompileCalls,
+    precompile::{PrecompileCalls, MODEXP_INPUT_LIMIT, MODEXP_SIZE_LIMIT},
 };
 use core::iter::once;
 use eth_types::{sign_types::SignData, Field, ToLittleEndian, ToScalar, ToWord, Word, U256};
@@ -33,6 +33,7 @@ use halo2_proofs::{
     plonk::{Advice, Any, Column, ConstraintSystem, Error, Expression, Fixed, VirtualCells},
     poly::Rotation,
 };
+use serde::{Deserialize, Serialize};
 use snark_verifier::util::arithmetic::PrimeCurveAffine;
 
 use std::iter::repeat;
@@ -45,6 +46,7 @@ use halo2_proofs::plonk::SecondPhase;
 use halo2_proofs::plonk::TableColumn;
 use itertools::Itertools;
 use keccak256::plain::Keccak;
+use num::BigUint;
 use std::array;
 use strum_macros::{EnumCount, EnumIter};
 
@@ -190,6 +192,12 @@ pub enum TxFieldTag {
     MaxPriorityFeePerGas,
     /// Max Fee Per Gas (EIP1559)
     MaxFeePerGas,
+    /// Max Fee Per Blob Gas (EIP4844)
+    MaxFeePerBlobGas,
+    /// Blob versioned hash count (EIP4844)
+    BlobVersionedHashesLen,
+    /// Blob versioned hash (EIP4844)
+    BlobVersionedHash,
 }
 impl_expr!(TxFieldTag);
 
@@ -348,7 +356,7 @@ impl TxTable {
                     }
                 }
 
-                // Assign dynamic calldata and access list section
+                // Assign dynamic calldata, access list and blob versioned hashes section
                 for tx in txs.iter().chain(padding_txs.iter()) {
                     for row in tx.table_assignments_dyn(*challenges).into_iter() {
                         assign_row(
@@ -377,6 +385,21 @@ impl TxTable {
                         )?;
                         offset += 1;
                     }
+                    for row in tx
+                        .table_assignments_blob_hashes_dyn(*challenges)
+                        .into_iter()
+                    {
+                        assign_row(
+                            &mut region,
+                            offset,
+                            self.q_enable,
+                            &advice_columns,
+                            &self.tag,
+                            &row,
+                            "",
+                        )?;
+                        offset += 1;
+                    }
                 }
 
                 Ok(tx_value_cells)
@@ -421,7 +444,7 @@ impl<F: Field> LookupTable<F> for TxTable {
 }
 
 /// Tag to identify the operation type in a RwTable row
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
 pub enum RwTableTag {
     /// Start (used for padding)
     Start = 1,
@@ -445,6 +468,12 @@ pub enum RwTableTag {
     TxLog,
     /// Tx Receipt operation
     TxReceipt,
+    /// Transient Storage operation
+    TransientStorage,
+    /// Tx Created Account operation
+    TxCreatedAccount,
+    /// Tx Destructed Account operation
+    TxDestructedAccount,
 }
 impl_expr!(RwTableTag);
 
@@ -458,6 +487,9 @@ impl RwTableTag {
                 | RwTableTag::TxRefund
                 | RwTableTag::Account
                 | RwTableTag::AccountStorage
+                | RwTableTag::TransientStorage
+                | RwTableTag::TxCreatedAccount
+                | RwTableTag::TxDestructedAccount
         )
     }
 }
@@ -469,7 +501,9 @@ impl From<RwTableTag> for usize {
 }
 
 /// Tag for an AccountField in RwTable
-#[derive(Clone, Copy, Debug, EnumIter, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Clone, Copy, Debug, EnumIter, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
 pub enum AccountFieldTag {
     /// Nonce field
     Nonce,
@@ -487,7 +521,7 @@ pub enum AccountFieldTag {
 impl_expr!(AccountFieldTag);
 
 /// Tag for a TxLogField in RwTable
-#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
 pub enum TxLogFieldTag {
     /// Address field
     Address = 1,
@@ -499,7 +533,7 @@ pub enum TxLogFieldTag {
 impl_expr!(TxLogFieldTag);
 
 /// Tag for a TxReceiptField in RwTable
-#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, EnumCount)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, EnumCount, Serialize, Deserialize)]
 pub enum TxReceiptFieldTag {
     /// Tx result
     PostStateOrStatus = 1,
@@ -511,7 +545,7 @@ pub enum TxReceiptFieldTag {
 impl_expr!(TxReceiptFieldTag);
 
 /// Tag for a CallContextField in RwTable
-#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
 pub enum CallContextFieldTag {
     /// RwCounterEndOfReversion
     RwCounterEndOfReversion = 1,
@@ -1684,6 +1718,242 @@ impl SHA256Table {
     }
 }
 
+/// RIPEMD160 Table, used to verify RIPEMD-160 hashing from RLC'ed input in precompile.
+#[derive(Clone, Debug)]
+pub struct Ripemd160Table {
+    /// True when the row is enabled
+    pub q_enable: Column<Fixed>,
+    /// True when the row is final
+    pub is_final: Column<Advice>,
+    /// Byte array input as `RLC(reversed(input))`
+    pub input_rlc: Column<Advice>, // RLC of input bytes
+    /// Byte array input length
+    pub input_len: Column<Advice>,
+    /// RLC of the hash result
+    pub output_rlc: Column<Advice>, // RLC of hash of input bytes
+}
+
+impl<F: Field> LookupTable<F> for Ripemd160Table {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.q_enable.into(),
+            self.is_final.into(),
+            self.input_rlc.into(),
+            self.input_len.into(),
+            self.output_rlc.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("q_enable"),
+            String::from("is_final"),
+            String::from("input_rlc"),
+            String::from("input_len"),
+            String::from("output_rlc"),
+        ]
+    }
+}
+
+impl Ripemd160Table {
+    /// Construct a new Ripemd160Table
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            q_enable: meta.fixed_column(),
+            is_final: meta.advice_column(),
+            input_len: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            output_rlc: meta.advice_column_in(SecondPhase),
+        }
+    }
+
+    /// Generate the ripemd160 table assignments from a byte array pair of input/output.
+    /// Used only for dev_load
+    pub fn assignments<F: Field>(
+        entry: (&[u8], &[u8; 20]),
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 4]> {
+        let (input, output) = entry;
+        let input_len = Value::known(F::from(input.len() as u64));
+        let input_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(input.iter().rev(), challenge));
+        let output_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(output.iter().rev(), challenge));
+
+        vec![[Value::known(F::one()), input_rlc, input_len, output_rlc]]
+    }
+
+    /// Provide this function for the case that we want to consume a ripemd160
+    /// table but without running the full ripemd160 circuit
+    pub fn dev_load<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        entries: impl IntoIterator<Item = (&'a Vec<u8>, &'a [u8; 20])> + Clone,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "ripemd160 table dev",
+            |mut region| {
+                let mut offset = 0;
+                for column in <Self as LookupTable<F>>::advice_columns(self) {
+                    region.assign_fixed(
+                        || "ripemd160 table all-zero row",
+                        self.q_enable,
+                        offset,
+                        || Value::known(F::one()),
+                    )?;
+                    region.assign_advice(
+                        || "ripemd160 table all-zero row",
+                        column,
+                        offset,
+                        || Value::known(F::zero()),
+                    )?;
+                }
+                offset += 1;
+
+                let table_columns = <Self as LookupTable<F>>::advice_columns(self);
+                for (input, digest) in entries.clone() {
+                    for row in Self::assignments((input, digest), challenges) {
+                        region.assign_fixed(
+                            || format!("table row {offset}"),
+                            self.q_enable,
+                            offset,
+                            || Value::known(F::one()),
+                        )?;
+                        for (&column, value) in table_columns.iter().zip_eq(row) {
+                            region.assign_advice(
+                                || format!("table row {offset}"),
+                                column,
+                                offset,
+                                || value,
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Blake2f Table, used to verify the blake2b compression function F from RLC'ed input in
+/// precompile.
+#[derive(Clone, Debug)]
+pub struct Blake2fTable {
+    /// True when the row is enabled
+    pub q_enable: Column<Fixed>,
+    /// True when the row is final, i.e. the compression with all rounds done
+    pub is_final: Column<Advice>,
+    /// The 213 input bytes as `RLC(reversed(input))`
+    pub input_rlc: Column<Advice>,
+    /// The 64 output bytes as `RLC(reversed(output))`
+    pub output_rlc: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for Blake2fTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.q_enable.into(),
+            self.is_final.into(),
+            self.input_rlc.into(),
+            self.output_rlc.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("q_enable"),
+            String::from("is_final"),
+            String::from("input_rlc"),
+            String::from("output_rlc"),
+        ]
+    }
+}
+
+impl Blake2fTable {
+    /// Construct a new Blake2fTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            q_enable: meta.fixed_column(),
+            is_final: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            output_rlc: meta.advice_column_in(SecondPhase),
+        }
+    }
+
+    /// Generate the blake2f table assignments from a blake2f operation.
+    /// Used only for dev_load
+    pub fn assignments<F: Field>(
+        op: &Blake2fOp,
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 3]> {
+        let input_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(op.input_bytes().iter().rev(), challenge));
+        let output_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(op.output.iter().rev(), challenge));
+
+        vec![[Value::known(F::one()), input_rlc, output_rlc]]
+    }
+
+    /// Provide this function for the case that we want to consume a blake2f
+    /// table but without running the full blake2f circuit
+    pub fn dev_load<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        entries: impl IntoIterator<Item = &'a Blake2fOp> + Clone,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "blake2f table dev",
+            |mut region| {
+                let mut offset = 0;
+                for column in <Self as LookupTable<F>>::advice_columns(self) {
+                    region.assign_fixed(
+                        || "blake2f table all-zero row",
+                        self.q_enable,
+                        offset,
+                        || Value::known(F::one()),
+                    )?;
+                    region.assign_advice(
+                        || "blake2f table all-zero row",
+                        column,
+                        offset,
+                        || Value::known(F::zero()),
+                    )?;
+                }
+                offset += 1;
+
+                let table_columns = <Self as LookupTable<F>>::advice_columns(self);
+                for op in entries.clone() {
+                    for row in Self::assignments(op, challenges) {
+                        region.assign_fixed(
+                            || format!("table row {offset}"),
+                            self.q_enable,
+                            offset,
+                            || Value::known(F::one()),
+                        )?;
+                        for (&column, value) in table_columns.iter().zip_eq(row) {
+                            region.assign_advice(
+                                || format!("table row {offset}"),
+                                column,
+                                offset,
+                                || value,
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
 /// Copy Table, used to verify copies of byte chunks between Memory, Bytecode,
 /// TxLogs and TxCallData.
 #[derive(Clone, Copy, Debug)]
@@ -1726,7 +1996,7 @@ pub struct CopyTable {
 }
 
 type CopyTableRow<F> = [(Value<F>, &'static str); 8];
-type CopyCircuitRow<F> = [(Value<F>, &'static str); 10];
+type CopyCircuitRow<F> = [(Value<F>, &'static str); 11];
 
 /// CopyThread is the state used while generating rows of the copy table.
 struct CopyThread<F: Field> {
@@ -1805,6 +2075,13 @@ impl CopyTable {
 
         let mut rw_counter = copy_event.rw_counter_start();
         let mut rwc_inc_left = copy_event.rw_counter_delta();
+        // For copies within the memory of a call (MCOPY), all the reads happen before all the
+        // writes, so the writer rows are offset by the number of words.
+        let rwc_gap = if copy_event.is_memory_copy() {
+            copy_event.full_length() / 32
+        } else {
+            0
+        };
 
         let mut reader = CopyThread {
             tag: copy_event.src_type,
@@ -1922,6 +2199,12 @@ impl CopyTable {
 
             let word_index = (step_idx as u64 / 2) % 32;
 
+            let (row_rw_counter, row_rwc_inc_left) = if is_read_step {
+                (rw_counter, rwc_inc_left)
+            } else {
+                (rw_counter + rwc_gap, rwc_inc_left - rwc_gap)
+            };
+
             // For LOG, format the address including the log_id.
             let addr = if thread.tag == CopyDataType::TxLog {
                 build_tx_log_address(thread.addr, TxLogFieldTag::Data, copy_event.log_id.unwrap())
@@ -1940,8 +2223,8 @@ impl CopyTable {
                     (Value::known(F::from(thread.addr_end)), "src_addr_end"),
                     (Value::known(F::from(thread.bytes_left)), "real_bytes_left"),
                     (rlc_acc, "rlc_acc"),
-                    (Value::known(F::from(rw_counter)), "rw_counter"),
-                    (Value::known(F::from(rwc_inc_left)), "rwc_inc_left"),
+                    (Value::known(F::from(row_rw_counter)), "rw_counter"),
+                    (Value::known(F::from(row_rwc_inc_left)), "rwc_inc_left"),
                 ],
                 [
                     (Value::known(F::from(is_last)), "is_last"),
@@ -1954,6 +2237,7 @@ impl CopyTable {
                     (Value::known(F::from(copy_step.mask)), "mask"),
                     (Value::known(F::from(thread.front_mask)), "front_mask"),
                     (Value::known(F::from(word_index)), "word_index"),
+                    (Value::known(F::from(rwc_gap)), "rwc_gap"),
                 ],
             ));
 
@@ -1967,8 +2251,8 @@ impl CopyTable {
             }
             // No word operation for access list data types.
             let is_row_end = is_access_list || (step_idx / 2) % 32 == 31;
-            // Update the RW counter.
-            if is_row_end && thread.is_rw {
+            // Update the RW counter. With a gap, the writer row advances both threads.
+            if is_row_end && thread.is_rw && (rwc_gap == 0 || !is_read_step) {
                 rw_counter += 1;
                 rwc_inc_left -= 1;
             }
@@ -2363,15 +2647,15 @@ impl RlpFsmRlpTable {
     }
 
     /// Load the RLP table (only for dev).
-    pub fn dev_load<F: Field>(
+    pub fn dev_load<F: Field, RLP: RlpFsmWitnessGen<F>>(
         &self,
         layouter: &mut impl Layouter<F>,
-        txs: Vec<Transaction>,
+        inputs: Vec<RLP>,
         challenges: &Challenges<Value<F>>,
     ) -> Result<(), Error> {
-        let rows = txs
+        let rows = inputs
             .into_iter()
-            .flat_map(|tx| tx.gen_sm_witness(challenges))
+            .flat_map(|input| input.gen_sm_witness(challenges))
             .filter(|row| row.rlp_table.is_output)
             .map(|row| row.rlp_table)
             .collect::<Vec<_>>();
@@ -2595,6 +2879,9 @@ impl<F: Field> LookupTable<F> for SigTable {
 ///    - arg*_rlc <- 0
 ///    - input_rlc <- RLC over all input bytes
 ///    - output1_rlc <- success {0, 1}
+///
+///    where a call with more than `N_PAIRING_PER_OP` pairs spans several chained rows, the last
+///    of which holds the RLC over all input bytes and the result of the entire call.
 #[derive(Clone, Copy, Debug)]
 pub struct EccTable {
     /// Since the current design of the ECC circuit reserves fixed number of rows for EcAdd, EcMul
@@ -2739,21 +3026,40 @@ impl EccTable {
             ]);
         }
 
-        // assign EcPairing
+        // assign EcPairing, where a chained op continues the input RLC and validity of the previous
+        // op.
+        let (mut prev_is_valid, mut prev_input_rlc) = (true, Value::known(F::zero()));
         for pairing_op in pairing_ops
             .iter()
             .filter(|pairing_op| !pairing_op.skip_by_ecc_circuit())
             .chain(std::iter::repeat(&EcPairingOp::default()))
             .take(params.ec_pairing)
         {
+            let input_rlc =
+                keccak_rand.map(|r| rlc::value(pairing_op.to_bytes_be().iter().rev(), r));
+            let (is_valid, input_rlc) = if pairing_op.chained {
+                (
+                    prev_is_valid && pairing_op.is_valid(),
+                    prev_input_rlc.zip(keccak_rand).zip(input_rlc).map(
+                        |((prev_input_rlc, r), input_rlc)| {
+                            prev_input_rlc
+                                * r.pow([(N_PAIRING_PER_OP * N_BYTES_PER_PAIR) as u64, 0, 0, 0])
+                                + input_rlc
+                        },
+                    ),
+                )
+            } else {
+                (pairing_op.is_valid(), input_rlc)
+            };
+            (prev_is_valid, prev_input_rlc) = (is_valid, input_rlc);
             assignments.push([
                 Value::known(F::from(u64::from(PrecompileCalls::Bn128Pairing))),
-                Value::known(F::from(pairing_op.is_valid() as u64)),
+                Value::known(F::from(is_valid as u64)),
                 Value::known(F::zero()),
                 Value::known(F::zero()),
                 Value::known(F::zero()),
                 Value::known(F::zero()),
-                keccak_rand.map(|r| rlc::value(pairing_op.to_bytes_be().iter().rev(), r)),
+                input_rlc,
                 Value::known(
                     pairing_op
                         .output
@@ -2793,78 +3099,78 @@ impl EccTable {
     }
 }
 
-/// Lookup table embedded in the modexp circuit for precompile.
+/// Lookup table embedded in the modexp circuit for precompile. An event has a row with the RLCs
+/// of the big-endian bytes of its operands and result, which don't depend on the number of
+/// leading zero bytes, and with the sizes of the operands in the call data, which bound them so
+/// that the RLC of the call data splits into the operands in a unique way. The operands are of up
+/// to `SIZE_LIMIT` bytes.
 #[derive(Clone, Copy, Debug)]
-pub struct ModExpTable {
-    /// Use for indicate beginning of a limbs group
-    pub q_head: Column<Fixed>,
-    /// base represented by limbs
+pub struct ModExpTable<const SIZE_LIMIT: usize = MODEXP_SIZE_LIMIT> {
+    /// True when the row is enabled
+    pub q_enable: Column<Fixed>,
+    /// Whether the row is the one of an event
+    pub q_head: Column<Advice>,
+    /// RLC of the base
     pub base: Column<Advice>,
-    /// exp represented by limbs
+    /// RLC of the exponent
     pub exp: Column<Advice>,
-    /// modulus represented by limbs
+    /// RLC of the modulus
     pub modulus: Column<Advice>,
-    /// result represented by limbs
+    /// RLC of the result
     pub result: Column<Advice>,
+    /// Bit length of the exponent, which the gas cost depends on
+    pub exp_bit_len: Column<Advice>,
+    /// Size of the base in bytes, the base is less than 256^base_len, and base_len is at most
+    /// SIZE_LIMIT
+    pub base_len: Column<Advice>,
+    /// Size of the exponent in bytes
+    pub exp_len: Column<Advice>,
+    /// Size of the modulus in bytes
+    pub modulus_len: Column<Advice>,
 }
 
-impl ModExpTable {
+impl<const SIZE_LIMIT: usize> ModExpTable<SIZE_LIMIT> {
     /// Construct the modexp table.
     pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
-        let ret = Self {
-            q_head: meta.fixed_column(),
-            base: meta.advice_column(),
-            exp: meta.advice_column(),
-            modulus: meta.advice_column(),
-            result: meta.advice_column(),
-        };
-        meta.enable_equality(ret.base);
-        meta.enable_equality(ret.exp);
-        meta.enable_equality(ret.modulus);
-        meta.enable_equality(ret.result);
-        ret
-    }
-
-    /// helper for devide a U256 into 3 108bit limbs
-    pub fn split_u256_108bit_limbs(word: &Word) -> [u128; 3] {
-        let bit108 = 1u128 << 108;
-        let (next, limb0) = word.div_mod(U256::from(bit108));
-        let (limb2, limb1) = next.div_mod(U256::from(bit108));
-        [limb0.as_u128(), limb1.as_u128(), limb2.as_u128()]
+        Self {
+            q_enable: meta.fixed_column(),
+            q_head: meta.advice_column(),
+            base: meta.advice_column_in(SecondPhase),
+            exp: meta.advice_column_in(SecondPhase),
+            modulus: meta.advice_column_in(SecondPhase),
+            result: meta.advice_column_in(SecondPhase),
+            exp_bit_len: meta.advice_column(),
+            base_len: meta.advice_column(),
+            exp_len: meta.advice_column(),
+            modulus_len: meta.advice_column(),
+        }
     }
 
-    /// helper for obtain the modulus of a U256 in Fr
-    pub fn native_u256<F: Field>(word: &Word) -> F {
-        let minus1 = -F::one();
-        let div = Word::from_little_endian(minus1.to_repr().as_ref()) + Word::from(1u64);
-        let (_, remainder) = word.div_mod(div);
-
-        let mut bytes = [0u8; 64];
-        remainder.to_little_endian(&mut bytes[..32]);
-        F::from_uniform_bytes(&bytes)
+    /// RLC of the big-endian bytes of a big integer.
+    pub fn value_rlc<F: Field>(value: &BigUint, challenges: &Challenges<Value<F>>) -> Value<F> {
+        challenges
+            .keccak_input()
+            .map(|r| rlc::value(value.to_bytes_be().iter().rev(), r))
     }
 
-    /// fill a blank 4-row region start from offset for empty lookup
+    /// fill a blank row for empty lookup, it's a valid event with all values zero
     pub fn fill_blank<F: Field>(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
         layouter.assign_region(
             || "modexp table blank region",
             |mut region| {
-                for i in 0..4 {
-                    // fill last totally 0 row
-                    region.assign_fixed(
-                        || "modexp table blank head row",
-                        self.q_head,
-                        i,
+                region.assign_fixed(
+                    || "modexp table blank row",
+                    self.q_enable,
+                    0,
+                    || Value::known(F::zero()),
+                )?;
+                for &col in <Self as LookupTable<F>>::advice_columns(self).iter() {
+                    region.assign_advice(
+                        || "modexp table blank row",
+                        col,
+                        0,
                         || Value::known(F::zero()),
                     )?;
-                    for &col in [&self.base, &self.exp, &self.modulus, &self.result] {
-                        region.assign_advice(
-                            || "modexp table blank limb row",
-                            col,
-                            i,
-                            || Value::known(F::zero()),
-                        )?;
-                    }
                 }
                 Ok(())
             },
@@ -2876,56 +3182,62 @@ impl ModExpTable {
         &self,
         layouter: &mut impl Layouter<F>,
         events: &[BigModExp],
+        challenges: &Challenges<Value<F>>,
     ) -> Result<(), Error> {
         layouter.assign_region(
             || "modexp table",
             |mut region| {
-                let mut offset = 0usize;
-
-                for event in events {
-                    for i in 0..4 {
-                        region.assign_fixed(
-                            || format!("modexp table head {}", offset + i),
-                            self.q_head,
-                            offset + i,
-                            || Value::known(if i == 0 { F::one() } else { F::zero() }),
-                        )?;
-                    }
-
-                    let base_limbs = Self::split_u256_108bit_limbs(&event.base);
-                    let exp_limbs = Self::split_u256_108bit_limbs(&event.exponent);
-                    let modulus_limbs = Self::split_u256_108bit_limbs(&event.modulus);
-                    let result_limbs = Self::split_u256_108bit_limbs(&event.result);
-
-                    for i in 0..3 {
-                        for (limbs, &col) in [base_limbs, exp_limbs, modulus_limbs, result_limbs]
-                            .iter()
-                            .zip([&self.base, &self.exp, &self.modulus, &self.result])
-                        {
-                            region.assign_advice(
-                                || format!("modexp table limb row {}", offset + i),
-                                col,
-                                offset + i,
-                                || Value::known(F::from_u128(limbs[i])),
-                            )?;
-                        }
-                    }
-
-                    // native is not used by lookup (and in fact it can be omitted in dev)
-                    for (word, &col) in
+                for (offset, event) in events.iter().enumerate() {
+                    assert!(
+                        [event.base_len, event.exponent_len, event.modulus_len]
+                            .into_iter()
+                            .all(|len| len <= SIZE_LIMIT),
+                        "modexp operand over the size limit of {SIZE_LIMIT} bytes"
+                    );
+                    region.assign_fixed(
+                        || format!("modexp table q_enable {offset}"),
+                        self.q_enable,
+                        offset,
+                        || Value::known(F::one()),
+                    )?;
+                    region.assign_advice(
+                        || format!("modexp table head {offset}"),
+                        self.q_head,
+                        offset,
+                        || Value::known(F::one()),
+                    )?;
+                    for (value, col) in
                         [&event.base, &event.exponent, &event.modulus, &event.result]
-                            .iter()
-                            .zip([&self.base, &self.exp, &self.modulus, &self.result])
+                            .into_iter()
+                            .zip([self.base, self.exp, self.modulus, self.result])
                     {
                         region.assign_advice(
-                            || format!("modexp table native row {}", offset + 3),
+                            || format!("modexp table row {offset}"),
                             col,
-                            offset + 3,
-                            || Value::<F>::known(Self::native_u256(word)),
+                            offset,
+                            || Self::value_rlc(value, challenges),
+                        )?;
+                    }
+                    for (value, col) in [
+                        event.exponent.bits(),
+                        event.base_len as u64,
+                        event.exponent_len as u64,
+                        event.modulus_len as u64,
+                    ]
+                    .into_iter()
+                    .zip([
+                        self.exp_bit_len,
+                        self.base_len,
+                        self.exp_len,
+                        self.modulus_len,
+                    ]) {
+                        region.assign_advice(
+                            || format!("modexp table row {offset}"),
+                            col,
+                            offset,
+                            || Value::known(F::from(value)),
                         )?;
                     }
-
-                    offset += 4;
                 }
 
                 Ok(())
@@ -2935,57 +3247,55 @@ impl ModExpTable {
     }
 }
 
-impl<F: Field> LookupTable<F> for ModExpTable {
+impl<F: Field, const SIZE_LIMIT: usize> LookupTable<F> for ModExpTable<SIZE_LIMIT> {
     fn columns(&self) -> Vec<Column<Any>> {
         vec![
+            self.q_enable.into(),
             self.q_head.into(),
             self.base.into(),
             self.exp.into(),
             self.modulus.into(),
             self.result.into(),
+            self.exp_bit_len.into(),
+            self.base_len.into(),
+            self.exp_len.into(),
+            self.modulus_len.into(),
         ]
     }
 
     fn annotations(&self) -> Vec<String> {
         vec![
+            String::from("q_enable"),
             String::from("is_head"),
             String::from("base"),
             String::from("exp"),
             String::from("modulus"),
             String::from("result"),
-        ]
-    }
-
-    fn table_exprs(&self, meta: &mut VirtualCells<F>) -> Vec<Expression<F>> {
-        vec![
-            // ignore the is_valid field as the EVM circuit's use-case (Ecrecover precompile) does
-            // not care whether the signature is valid or not. It only cares about the recovered
-            // address.
-            meta.query_fixed(self.q_head, Rotation::cur()),
-            meta.query_advice(self.base, Rotation::cur()),
-            meta.query_advice(self.exp, Rotation::cur()),
-            meta.query_advice(self.modulus, Rotation::cur()),
-            meta.query_advice(self.result, Rotation::cur()),
-            meta.query_advice(self.base, Rotation::next()),
-            meta.query_advice(self.exp, Rotation::next()),
-            meta.query_advice(self.modulus, Rotation::next()),
-            meta.query_advice(self.result, Rotation::next()),
-            meta.query_advice(self.base, Rotation(2)),
-            meta.query_advice(self.exp, Rotation(2)),
-            meta.query_advice(self.modulus, Rotation(2)),
-            meta.query_advice(self.result, Rotation(2)),
+            String::from("exp_bit_len"),
+            String::from("base_len"),
+            String::from("exp_len"),
+            String::from("modulus_len"),
         ]
     }
 }
 
-/// Lookup table for powers of keccak randomness up to exponent in [0, 128)
+/// Number of the powers of keccak randomness in the [`PowOfRandTable`], enough for the inputs of
+/// the ecPairing and modexp precompiles.
+pub const POW_OF_RAND_TABLE_LEN: usize = if N_PAIRING_PER_OP * N_BYTES_PER_PAIR > MODEXP_INPUT_LIMIT
+{
+    N_PAIRING_PER_OP * N_BYTES_PER_PAIR
+} else {
+    MODEXP_INPUT_LIMIT
+};
+
+/// Lookup table for powers of keccak randomness up to exponent in [0, POW_OF_RAND_TABLE_LEN)
 #[derive(Clone, Copy, Debug)]
 pub struct PowOfRandTable {
     /// Whether the row is enabled.
     pub q_enable: Column<Fixed>,
     /// Whether the row is the first enabled row.
     pub is_first: Column<Fixed>,
-    /// exponent = [0, 1, 2, ..., 126, 127] for enabled rows.
+    /// exponent = [0, 1, 2, ..., POW_OF_RAND_TABLE_LEN - 1] for enabled rows.
     /// exponent = 0 for all other rows (disabled).
     pub exponent: Column<Fixed>,
     /// power of keccak randomness.
@@ -3046,7 +3356,7 @@ impl PowOfRandTable {
             |mut region| {
                 let pows_of_rand =
                     std::iter::successors(Some(Value::known(F::one())), |&v| Some(v * r))
-                        .take(N_PAIRING_PER_OP * N_BYTES_PER_PAIR);
+                        .take(POW_OF_RAND_TABLE_LEN);
 
                 for (idx, pow_of_rand) in pows_of_rand.enumerate() {
                     region.assign_fixed(
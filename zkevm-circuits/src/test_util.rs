@@ -20,6 +20,9 @@ use mock::TestContext;
 #[cfg(feature = "scroll")]
 use bus_mapping::circuit_input_builder::CircuitInputBuilder;
 
+mod diagnostics;
+pub use diagnostics::{Diagnostic, DiagnosticReport, RwContext, StepContext, WitnessLayout};
+
 #[cfg(test)]
 #[ctor::ctor]
 fn init_env_logger() {
@@ -27,6 +30,42 @@ fn init_env_logger() {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("error")).init();
 }
 
+#[allow(clippy::type_complexity)]
+/// Checks run by the [`CircuitTestBuilder`] on the [`MockProver`] of a circuit.
+enum CircuitChecks {
+    /// Verify the active rows, panicking with a [`DiagnosticReport`] on failure.
+    Default,
+    /// Checks provided by the test.
+    Custom(Box<dyn Fn(MockProver<Fr>, &Vec<usize>, &Vec<usize>)>),
+}
+
+impl CircuitChecks {
+    #[allow(clippy::ptr_arg)]
+    fn run(
+        &self,
+        prover: MockProver<Fr>,
+        layout: &WitnessLayout,
+        gate_rows: &Vec<usize>,
+        lookup_rows: &Vec<usize>,
+    ) {
+        match self {
+            CircuitChecks::Default => {
+                let result = prover
+                    .verify_at_rows_par(gate_rows.iter().cloned(), lookup_rows.iter().cloned());
+                if result.is_err() {
+                    let rows = gate_rows.iter().chain(lookup_rows.iter());
+                    let start = rows.clone().min().cloned().unwrap_or_default();
+                    let end = rows.max().map_or(0, |row| row + 1);
+                    let report = DiagnosticReport::new(&prover, layout, start..end);
+                    log::debug!("diagnostics: {}", report.to_json());
+                    panic!("circuit was not satisfied\n{report}");
+                }
+            }
+            CircuitChecks::Custom(checks) => checks(prover, gate_rows, lookup_rows),
+        }
+    }
+}
+
 #[allow(clippy::type_complexity)]
 /// Struct used to easily generate tests for EVM &| State circuits being able to
 /// customize all of the steps involved in the testing itself.
@@ -82,9 +121,9 @@ pub struct CircuitTestBuilder<const NACC: usize, const NTX: usize> {
     test_ctx: Option<TestContext<NACC, NTX>>,
     circuits_params: Option<CircuitsParams>,
     block: Option<Block<Fr>>,
-    evm_checks: Option<Box<dyn Fn(MockProver<Fr>, &Vec<usize>, &Vec<usize>)>>,
-    state_checks: Option<Box<dyn Fn(MockProver<Fr>, &Vec<usize>, &Vec<usize>)>>,
-    copy_checks: Option<Box<dyn Fn(MockProver<Fr>, &Vec<usize>, &Vec<usize>)>>,
+    evm_checks: Option<CircuitChecks>,
+    state_checks: Option<CircuitChecks>,
+    copy_checks: Option<CircuitChecks>,
     block_modifiers: Vec<Box<dyn Fn(&mut Block<Fr>)>>,
 }
 
@@ -95,24 +134,9 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
             test_ctx: None,
             circuits_params: None,
             block: None,
-            evm_checks: Some(Box::new(|prover, gate_rows, lookup_rows| {
-                assert_eq!(prover.verify_at_rows_par(
-                    gate_rows.iter().cloned(),
-                    lookup_rows.iter().cloned(),
-                ), Ok(()));
-            })),
-            state_checks: Some(Box::new(|prover, gate_rows, lookup_rows| {
-                assert_eq!(prover.verify_at_rows_par(
-                    gate_rows.iter().cloned(),
-                    lookup_rows.iter().cloned(),
-                ), Ok(()));
-            })),
-            copy_checks: Some(Box::new(|prover, gate_rows, lookup_rows| {
-                assert_eq!(prover.verify_at_rows_par(
-                    gate_rows.iter().cloned(),
-                    lookup_rows.iter().cloned(),
-                ), Ok(()));
-            })),
+            evm_checks: Some(CircuitChecks::Default),
+            state_checks: Some(CircuitChecks::Default),
+            copy_checks: Some(CircuitChecks::Default),
             block_modifiers: vec![],
         }
     }
@@ -160,7 +184,7 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
         mut self,
         state_checks: Option<Box<dyn Fn(MockProver<Fr>, &Vec<usize>, &Vec<usize>)>>,
     ) -> Self {
-        self.state_checks = state_checks;
+        self.state_checks = state_checks.map(CircuitChecks::Custom);
         self
     }
 
@@ -171,7 +195,7 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
         mut self,
         evm_checks: Option<Box<dyn Fn(MockProver<Fr>, &Vec<usize>, &Vec<usize>)>>,
     ) -> Self {
-        self.evm_checks = evm_checks;
+        self.evm_checks = evm_checks.map(CircuitChecks::Custom);
         self
     }
 
@@ -182,7 +206,7 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
         mut self,
         copy_checks: Option<Box<dyn Fn(MockProver<Fr>, &Vec<usize>, &Vec<usize>)>>,
     ) -> Self {
-        self.copy_checks = copy_checks;
+        self.copy_checks = copy_checks.map(CircuitChecks::Custom);
         self
     }
 
@@ -269,7 +293,12 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
             let circuit = EvmCircuit::get_test_cicuit_from_block(block.clone());
             let prover = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
 
-            evm_checks(prover, &active_gate_rows, &active_lookup_rows)
+            evm_checks.run(
+                prover,
+                &WitnessLayout::evm(&block),
+                &active_gate_rows,
+                &active_lookup_rows,
+            )
         }
 
         // Run state circuit test
@@ -288,7 +317,7 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
                 .count();
             let rows = (rows_needed - non_start_rows_len..rows_needed).collect();
 
-            state_checks(prover, &rows, &rows);
+            state_checks.run(prover, &WitnessLayout::state(&state_circuit), &rows, &rows);
         }
 
         // Run copy circuit test
@@ -302,7 +331,7 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
             let prover = MockProver::<Fr>::run(k, &copy_circuit, instance).unwrap();
             let rows = (0..active_rows).collect();
 
-            copy_checks(prover, &rows, &rows);
+            copy_checks.run(prover, &WitnessLayout::default(), &rows, &rows);
         }
     }
 }
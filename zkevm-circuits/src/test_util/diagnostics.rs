@@ -0,0 +1,419 @@
+//! Diagnostics for unsatisfied constraints found by the [`MockProver`].
+//!
+//! The failing rows are located by bisecting the active rows with
+//! [`MockProver::verify_at_rows_par`], and then mapped back to the witness that was assigned
+//! to them: the [`ExecStep`] for the EVM circuit and the [`Rw`] for the State circuit.
+//! Circuits like the SuperCircuit share the rows between their subcircuits, so a failure is
+//! only mapped to the witness of the subcircuit named by its region or gate.
+
+use crate::{
+    evm_circuit::EvmCircuit,
+    state_circuit::StateCircuit,
+    witness::{Block, ExecStep, Rw, RwMap},
+};
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    halo2curves::bn256::Fr,
+};
+use serde::Serialize;
+use std::{fmt, ops::Range};
+
+/// Location of an execution step in the EVM circuit.
+#[derive(Debug, Clone)]
+struct StepLocation {
+    /// First row of the step.
+    offset: usize,
+    /// Index of the transaction in the block, `None` for the EndBlock padding.
+    tx_index: Option<usize>,
+    /// Index of the step in the transaction.
+    step_index: usize,
+}
+
+/// Subcircuit with a known row layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Subcircuit {
+    Evm,
+    State,
+}
+
+impl Subcircuit {
+    /// Region names of the subcircuits, as quoted in the location of a failure.
+    const REGIONS: [(&'static str, Self); 2] = [
+        ("('Execution step region", Self::Evm),
+        ("('state circuit (StateCircuitConfig)')", Self::State),
+    ];
+    /// Gates that may fail outside a region, as quoted in a constraint failure.
+    const GATES: [(&'static str, Self); 4] = [
+        ("('Constrain execution state')", Self::Evm),
+        ("('q_step')", Self::Evm),
+        ("('Constrain state machine transitions')", Self::Evm),
+        ("('state circuit constraints')", Self::State),
+    ];
+
+    /// Subcircuit of the region or gate of the failure, `None` for the other subcircuits.
+    fn of(failure: &str) -> Option<Self> {
+        Self::REGIONS
+            .iter()
+            .chain(Self::GATES.iter())
+            .find(|(name, _)| failure.contains(name))
+            .map(|(_, subcircuit)| *subcircuit)
+    }
+}
+
+/// Maps the rows of a circuit to the witness assigned to them.
+#[derive(Debug, Clone, Default)]
+pub struct WitnessLayout<'a> {
+    block: Option<&'a Block<Fr>>,
+    /// Steps of the EVM circuit, sorted by offset.
+    steps: Vec<StepLocation>,
+    /// Number of rows used by the EVM circuit.
+    evm_rows: usize,
+    /// Rws of the State circuit, including the padding at the beginning.
+    rws: Vec<Rw>,
+}
+
+impl<'a> WitnessLayout<'a> {
+    /// Layout of the EVM circuit. The steps are assigned from the first row, followed by the
+    /// EndBlock steps.
+    pub fn evm(block: &'a Block<Fr>) -> Self {
+        let mut steps = Vec::new();
+        let mut offset = 0;
+        for (tx_index, tx) in block.txs.iter().enumerate() {
+            for (step_index, step) in tx.steps.iter().enumerate() {
+                steps.push(StepLocation {
+                    offset,
+                    tx_index: Some(tx_index),
+                    step_index,
+                });
+                offset += step.execution_state.get_step_height();
+            }
+        }
+        steps.push(StepLocation {
+            offset,
+            tx_index: None,
+            step_index: 0,
+        });
+
+        Self {
+            block: Some(block),
+            steps,
+            evm_rows: EvmCircuit::<Fr>::get_num_rows_required(block),
+            rws: Vec::new(),
+        }
+    }
+
+    /// Layout of the State circuit.
+    pub fn state(circuit: &StateCircuit<Fr>) -> Self {
+        Self::default().with_state(circuit)
+    }
+
+    /// Add the layout of the State circuit, used for circuits containing both the EVM and
+    /// State circuits like the SuperCircuit. Both layouts start at the first row, and the
+    /// failures of the other subcircuits are left unmapped.
+    pub fn with_state(mut self, circuit: &StateCircuit<Fr>) -> Self {
+        self.rws = RwMap::table_assignments_prepad(&circuit.rows, circuit.n_rows).0;
+        self
+    }
+
+    /// Number of rows covered by the layout.
+    pub fn num_rows(&self) -> usize {
+        self.evm_rows.max(self.rws.len())
+    }
+
+    fn step_at(&self, row: usize) -> Option<StepContext> {
+        let block = self.block?;
+        if row >= self.evm_rows {
+            return None;
+        }
+        let idx = self.steps.partition_point(|step| step.offset <= row) - 1;
+        let location = &self.steps[idx];
+        let (tx, step) = match location.tx_index {
+            Some(tx_index) => {
+                let tx = &block.txs[tx_index];
+                (Some((tx_index, tx.id)), &tx.steps[location.step_index])
+            }
+            None => (None, &block.end_block_not_last),
+        };
+
+        Some(StepContext::new(
+            tx,
+            location.step_index,
+            step,
+            row - location.offset,
+        ))
+    }
+
+    fn rw_at(&self, row: usize) -> Option<RwContext> {
+        self.rws.get(row).map(|rw| RwContext {
+            rw_counter: rw.rw_counter(),
+            tag: format!("{:?}", rw.tag()),
+            rw: format!("{rw:?}"),
+        })
+    }
+}
+
+/// Execution step assigned to a failing row of the EVM circuit.
+#[derive(Debug, Clone, Serialize)]
+pub struct StepContext {
+    /// Index of the transaction in the block, `None` for the EndBlock padding.
+    pub tx_index: Option<usize>,
+    /// Identifier of the transaction.
+    pub tx_id: Option<usize>,
+    /// Index of the call in the transaction.
+    pub call_index: usize,
+    /// Index of the step in the transaction.
+    pub step_index: usize,
+    /// Program counter.
+    pub pc: u64,
+    /// Opcode of the step, if any.
+    pub opcode: Option<String>,
+    /// Execution state of the step.
+    pub execution_state: String,
+    /// Rw counter before the step.
+    pub rw_counter: usize,
+    /// Offset of the failing row from the first row of the step.
+    pub row_offset: usize,
+}
+
+impl StepContext {
+    fn new(
+        tx: Option<(usize, usize)>,
+        step_index: usize,
+        step: &ExecStep,
+        row_offset: usize,
+    ) -> Self {
+        Self {
+            tx_index: tx.map(|(index, _)| index),
+            tx_id: tx.map(|(_, id)| id),
+            call_index: step.call_index,
+            step_index,
+            pc: step.program_counter,
+            opcode: step.opcode.map(|op| format!("{op:?}")),
+            execution_state: format!("{:?}", step.execution_state),
+            rw_counter: step.rw_counter,
+            row_offset,
+        }
+    }
+}
+
+/// Rw assigned to a failing row of the State circuit.
+#[derive(Debug, Clone, Serialize)]
+pub struct RwContext {
+    /// Rw counter of the entry.
+    pub rw_counter: usize,
+    /// Table tag of the entry.
+    pub tag: String,
+    /// The entry itself.
+    pub rw: String,
+}
+
+/// A single unsatisfied constraint with the witness it was checked against.
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    /// Failing row, `None` for failures not bound to a row (e.g. permutation failures).
+    pub row: Option<usize>,
+    /// The failure reported by the [`MockProver`].
+    pub failure: String,
+    /// Execution step assigned to the row.
+    pub exec_step: Option<StepContext>,
+    /// Rw assigned to the row.
+    pub rw: Option<RwContext>,
+}
+
+/// Report of all the unsatisfied constraints of a [`MockProver`] run.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DiagnosticReport {
+    /// Failures sorted by row.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl DiagnosticReport {
+    /// Verify the `rows` of the `prover` and map the failures to the witness.
+    pub fn new(prover: &MockProver<Fr>, layout: &WitnessLayout, rows: Range<usize>) -> Self {
+        // Failures reported regardless of the verified rows.
+        let global = prover
+            .verify_at_rows_par(0..0, 0..0)
+            .err()
+            .unwrap_or_default();
+        let mut diagnostics: Vec<_> = global
+            .iter()
+            .map(|failure| Diagnostic {
+                row: None,
+                failure: failure.to_string(),
+                exec_step: None,
+                rw: None,
+            })
+            .collect();
+
+        let mut failing_rows = Vec::new();
+        bisect(prover, &global, rows, &mut failing_rows);
+        for (row, failures) in failing_rows {
+            diagnostics.extend(failures.into_iter().map(|failure| {
+                let subcircuit = Subcircuit::of(&failure);
+                Diagnostic {
+                    row: Some(row),
+                    exec_step: (subcircuit == Some(Subcircuit::Evm))
+                        .then(|| layout.step_at(row))
+                        .flatten(),
+                    rw: (subcircuit == Some(Subcircuit::State))
+                        .then(|| layout.rw_at(row))
+                        .flatten(),
+                    failure,
+                }
+            }));
+        }
+
+        Self { diagnostics }
+    }
+
+    /// Returns true if no failure is found.
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Serialize the report to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report is serializable")
+    }
+}
+
+impl fmt::Display for DiagnosticReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} unsatisfied constraint(s)", self.diagnostics.len())?;
+        for diagnostic in &self.diagnostics {
+            match diagnostic.row {
+                Some(row) => writeln!(f, "row {row}: {}", diagnostic.failure)?,
+                None => writeln!(f, "{}", diagnostic.failure)?,
+            }
+            if let Some(step) = &diagnostic.exec_step {
+                match (step.tx_index, step.tx_id) {
+                    (Some(tx_index), Some(tx_id)) => {
+                        write!(f, "  step: tx {tx_index} (id {tx_id}), ")?
+                    }
+                    _ => write!(f, "  step: end block, ")?,
+                }
+                writeln!(
+                    f,
+                    "call {}, step {}, pc {}, opcode {}, state {}, rw_counter {}, row offset {}",
+                    step.call_index,
+                    step.step_index,
+                    step.pc,
+                    step.opcode.as_deref().unwrap_or("-"),
+                    step.execution_state,
+                    step.rw_counter,
+                    step.row_offset,
+                )?;
+            }
+            if let Some(rw) = &diagnostic.rw {
+                writeln!(f, "  rw: rw_counter {}, {}", rw.rw_counter, rw.rw)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Find the rows of `rows` with failures other than the `global` ones.
+fn bisect(
+    prover: &MockProver<Fr>,
+    global: &[VerifyFailure],
+    rows: Range<usize>,
+    failing_rows: &mut Vec<(usize, Vec<String>)>,
+) {
+    if rows.is_empty() {
+        return;
+    }
+    let failures: Vec<_> = match prover.verify_at_rows_par(rows.clone(), rows.clone()) {
+        Ok(()) => return,
+        Err(failures) => failures
+            .into_iter()
+            .filter(|failure| !global.contains(failure))
+            .collect(),
+    };
+    if failures.is_empty() {
+        return;
+    }
+    if rows.len() == 1 {
+        failing_rows.push((
+            rows.start,
+            failures.iter().map(ToString::to_string).collect(),
+        ));
+        return;
+    }
+    let mid = rows.start + rows.len() / 2;
+    bisect(prover, global, rows.start..mid, failing_rows);
+    bisect(prover, global, mid..rows.end, failing_rows);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::witness::block_convert;
+    use bus_mapping::mock::BlockData;
+    use eth_types::{bytecode, evm_types::OpcodeId, geth_types::GethData};
+    use halo2_proofs::{dev::FailureLocation, plonk::Any};
+    use mock::TestContext;
+
+    #[test]
+    fn diagnostics_subcircuit_of_failure() {
+        let failure_in = |region: &'static str| {
+            VerifyFailure::Permutation {
+                column: (Any::advice(), 0).into(),
+                location: FailureLocation::InRegion {
+                    region: (1, region).into(),
+                    offset: 0,
+                },
+            }
+            .to_string()
+        };
+
+        assert_eq!(
+            Subcircuit::of(&failure_in("Execution step region1")),
+            Some(Subcircuit::Evm)
+        );
+        assert_eq!(
+            Subcircuit::of(&failure_in("state circuit (StateCircuitConfig)")),
+            Some(Subcircuit::State)
+        );
+        assert_eq!(Subcircuit::of(&failure_in("assign copy table")), None);
+    }
+
+    #[test]
+    fn diagnostics_locate_exec_step() {
+        let code = bytecode! {
+            PUSH1(1)
+            PUSH1(2)
+            ADD
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let mut block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+        // break the gas transition of the ADD step
+        let step = block.txs[0]
+            .steps
+            .iter_mut()
+            .find(|step| step.opcode == Some(OpcodeId::ADD))
+            .unwrap();
+        step.gas_left += 1;
+
+        let k = block.get_evm_test_circuit_degree();
+        let layout = WitnessLayout::evm(&block);
+        let circuit = EvmCircuit::<Fr>::get_test_cicuit_from_block(block.clone());
+        let prover = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+        let report = DiagnosticReport::new(&prover, &layout, 0..layout.num_rows());
+
+        assert!(!report.is_empty());
+        assert!(report.diagnostics.iter().any(|diagnostic| {
+            diagnostic.exec_step.as_ref().map_or(false, |step| {
+                step.tx_index == Some(0) && step.opcode.as_deref() == Some("ADD")
+            })
+        }));
+        serde_json::from_str::<serde_json::Value>(&report.to_json()).unwrap();
+    }
+}
@@ -19,9 +19,10 @@ use crate::{
         BlockContextFieldTag::{CumNumTxs, NumAllTxs, NumTxs},
         BlockTable, KeccakTable, LookupTable, RlpFsmRlpTable as RlpTable, SigTable, TxFieldTag,
         TxFieldTag::{
-            AccessListAddressesLen, AccessListRLC, AccessListStorageKeysLen, BlockNumber, CallData,
-            CallDataGasCost, CallDataLength, CallDataRLC, CalleeAddress, CallerAddress, ChainID,
-            Gas, GasPrice, IsCreate, MaxFeePerGas, MaxPriorityFeePerGas, Nonce, SigR, SigS, SigV,
+            AccessListAddressesLen, AccessListRLC, AccessListStorageKeysLen, BlobVersionedHash,
+            BlobVersionedHashesLen, BlockNumber, CallData, CallDataGasCost, CallDataLength,
+            CallDataRLC, CalleeAddress, CallerAddress, ChainID, Gas, GasPrice, IsCreate,
+            MaxFeePerBlobGas, MaxFeePerGas, MaxPriorityFeePerGas, Nonce, SigR, SigS, SigV,
             TxDataGasCost, TxHashLength, TxHashRLC, TxSignHash, TxSignLength, TxSignRLC,
         },
         TxTable, U16Table, U8Table,
@@ -34,8 +35,8 @@ use crate::{
     witness::{
         rlp_fsm::{Tag, ValueTagLength},
         Format::{
-            L1MsgHash, TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashPreEip155, TxSignEip155,
-            TxSignEip1559, TxSignEip2930, TxSignPreEip155,
+            L1MsgHash, TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashEip4844, TxHashPreEip155,
+            TxSignEip155, TxSignEip1559, TxSignEip2930, TxSignEip4844, TxSignPreEip155,
         },
         RlpTag,
         RlpTag::{GasCost, Len, Null, RLC},
@@ -43,11 +44,11 @@ use crate::{
         Transaction,
     },
 };
-use bus_mapping::circuit_input_builder::keccak_inputs_sign_verify;
+use bus_mapping::circuit_input_builder::{keccak_inputs_sign_verify, CircuitsParams};
 use eth_types::{
     geth_types::{
         access_list_size, TxType,
-        TxType::{Eip155, Eip1559, Eip2930, L1Msg, PreEip155},
+        TxType::{Eip155, Eip1559, Eip2930, Eip4844, L1Msg, PreEip155},
     },
     sign_types::SignData,
     AccessList, Address, Field, ToAddress, ToBigEndian, ToScalar,
@@ -83,7 +84,7 @@ use halo2_proofs::plonk::SecondPhase;
 use itertools::Itertools;
 
 /// Number of rows of one tx occupies in the fixed part of tx table
-pub const TX_LEN: usize = 28;
+pub const TX_LEN: usize = 30;
 /// Offset of TxHash tag in the tx table
 pub const TX_HASH_OFFSET: usize = 21;
 /// Offset of ChainID tag in the tx table
@@ -101,6 +102,8 @@ enum LookupCondition {
     Keccak,
     // lookup into dynamic access list section of tx table
     TxAccessList,
+    // lookup into dynamic blob versioned hashes section of tx table
+    TxBlobHashes,
 }
 
 #[derive(Clone, Debug)]
@@ -122,7 +125,7 @@ pub struct TxCircuitConfig<F: Field> {
     // A selector which is enabled at 1st row
     q_first: Column<Fixed>,
     tx_table: TxTable,
-    tx_tag_bits: BinaryNumberConfig<TxFieldTag, 5>,
+    tx_tag_bits: BinaryNumberConfig<TxFieldTag, 6>,
 
     tx_type: Column<Advice>,
     tx_type_bits: BinaryNumberConfig<TxType, 3>,
@@ -146,12 +149,14 @@ pub struct TxCircuitConfig<F: Field> {
     tx_id_unchanged: IsEqualConfig<F>,
 
     /// Columns used to reduce degree
+    is_tag_nonce: Column<Advice>,
     is_tag_block_num: Column<Advice>,
     is_calldata: Column<Advice>,
     is_caller_address: Column<Advice>,
     is_l1_msg: Column<Advice>,
     is_eip2930: Column<Advice>,
     is_eip1559: Column<Advice>,
+    is_eip4844: Column<Advice>,
     is_chain_id: Column<Advice>,
     is_tx_id_zero: Column<Advice>,
     lookup_conditions: HashMap<LookupCondition, Column<Advice>>,
@@ -211,6 +216,8 @@ pub struct TxCircuitConfig<F: Field> {
     // works together with section_rlc to ensure
     // no ommittance in access list dynamic section
     field_rlc: Column<Advice>,
+    // section denoter for blob versioned hashes (EIP-4844)
+    is_blob_hash: Column<Advice>,
 
     _marker: PhantomData<F>,
 }
@@ -304,10 +311,12 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         let is_l1_msg = meta.advice_column();
         let is_eip2930 = meta.advice_column();
         let is_eip1559 = meta.advice_column();
+        let is_eip4844 = meta.advice_column();
         let is_calldata = meta.advice_column();
         let is_tx_id_zero = meta.advice_column();
         let is_caller_address = meta.advice_column();
         let is_chain_id = meta.advice_column();
+        let is_tag_nonce = meta.advice_column();
         let is_tag_block_num = meta.advice_column();
         let lookup_conditions = [
             LookupCondition::TxCalldata,
@@ -316,6 +325,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             LookupCondition::RlpHashTag,
             LookupCondition::Keccak,
             LookupCondition::TxAccessList,
+            LookupCondition::TxBlobHashes,
         ]
         .into_iter()
         .map(|condition| (condition, meta.advice_column()))
@@ -330,6 +340,9 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         let is_access_list_storage_key = meta.advice_column();
         let field_rlc = meta.advice_column();
 
+        // blob versioned hashes columns
+        let is_blob_hash = meta.advice_column();
+
         // TODO: add lookup to SignVerify table for sv_address
         let sv_address = meta.advice_column();
         meta.enable_equality(tx_table.value);
@@ -389,6 +402,9 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         is_tx_tag!(is_tag_access_list_storage_key, AccessListStorageKey);
         is_tx_tag!(is_max_fee_per_gas, MaxFeePerGas);
         is_tx_tag!(is_max_priority_fee_per_gas, MaxPriorityFeePerGas);
+        is_tx_tag!(is_max_fee_per_blob_gas, MaxFeePerBlobGas);
+        is_tx_tag!(is_blob_versioned_hashes_len, BlobVersionedHashesLen);
+        is_tx_tag!(is_tag_blob_versioned_hash, BlobVersionedHash);
 
         let tx_id_unchanged = IsEqualChip::configure(
             meta,
@@ -435,7 +451,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             let mut cb = BaseConstraintBuilder::default();
 
             // if tag_next == Nonce, then tx_id' = tx_id + 1
-            cb.condition(tag_bits.value_equals(Nonce, Rotation::next())(meta), |cb| {
+            cb.condition(meta.query_advice(is_tag_nonce, Rotation::next()), |cb| {
                 cb.require_equal(
                     "tx_id increments",
                     meta.query_advice(tx_table.tx_id, Rotation::next()),
@@ -444,7 +460,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             });
             // if tag_next != Nonce, then tx_id' = tx_id, tx_type' = tx_type
             cb.condition(
-                not::expr(tag_bits.value_equals(Nonce, Rotation::next())(meta)),
+                not::expr(meta.query_advice(is_tag_nonce, Rotation::next())),
                 |cb| {
                     cb.require_equal(
                         "tx_id does not change",
@@ -482,6 +498,8 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 meta.query_fixed(q_enable, Rotation::cur()),
                 not::expr(meta.query_advice(is_calldata, Rotation::cur())),
                 not::expr(meta.query_advice(is_calldata, Rotation::next())),
+                not::expr(meta.query_advice(is_blob_hash, Rotation::cur())),
+                not::expr(meta.query_advice(is_blob_hash, Rotation::next())),
             ]))
         });
 
@@ -518,6 +536,11 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                     is_max_priority_fee_per_gas(meta),
                     Tag::MaxPriorityFeePerGas.into(),
                 ),
+                (is_max_fee_per_blob_gas(meta), Tag::MaxFeePerBlobGas.into()),
+                (
+                    is_tag_blob_versioned_hash(meta),
+                    Tag::BlobVersionedHash.into(),
+                ),
                 // tx tags which correspond to Null
                 (is_null(meta), Null),
                 (is_create(meta), Null),
@@ -532,6 +555,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 (is_access_list_addresses_len(meta), Null),
                 (is_access_list_storage_keys_len(meta), Null),
                 (is_access_list_rlc(meta), RLC),
+                (is_blob_versioned_hashes_len(meta), Null),
             ];
 
             cb.require_boolean(
@@ -548,6 +572,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                     usize::from(L1Msg).expr(),
                     usize::from(Eip2930).expr(),
                     usize::from(Eip1559).expr(),
+                    usize::from(Eip4844).expr(),
                 ],
             );
 
@@ -606,12 +631,32 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             });
 
             // CallData is not none => CallDataLength != 0
+            // (the calldata lookup condition on CallDataLength row is equivalent to
+            // CallDataLength != 0, we use it here to reduce degree)
             cb.condition(
                 and::expr([is_data_rlc(meta), not::expr(is_none_expr)]),
                 |cb| {
-                    cb.require_zero(
+                    cb.require_equal(
                         "CallDataLength != 0",
-                        value_is_zero.expr(Rotation::next())(meta),
+                        meta.query_advice(
+                            lookup_conditions[&LookupCondition::TxCalldata],
+                            Rotation::next(),
+                        ),
+                        1.expr(),
+                    );
+                },
+            );
+
+            // only eip4844 tx has blob versioned hashes
+            cb.condition(
+                and::expr([
+                    is_blob_versioned_hashes_len(meta),
+                    not::expr(meta.query_advice(is_eip4844, Rotation::cur())),
+                ]),
+                |cb| {
+                    cb.require_zero(
+                        "BlobVersionedHashesLen == 0",
+                        meta.query_advice(tx_table.value, Rotation::cur()),
                     );
                 },
             );
@@ -682,6 +727,18 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             },
         );
 
+        meta.create_gate("is_blob_hash", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "is_blob_hash",
+                is_tag_blob_versioned_hash(meta),
+                meta.query_advice(is_blob_hash, Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
         meta.create_gate("is_caller_address", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
@@ -706,6 +763,18 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
         });
 
+        meta.create_gate("is_tag_nonce", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "is_tag_nonce = (tag == Nonce)",
+                is_nonce(meta),
+                meta.query_advice(is_tag_nonce, Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
         meta.create_gate("is_tag_block_num", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
@@ -719,7 +788,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         });
 
         meta.create_gate(
-            "distinguish tx type: is_l1_msg, is_eip2930, is_eip1559",
+            "distinguish tx type: is_l1_msg, is_eip2930, is_eip1559, is_eip4844",
             |meta| {
                 let mut cb = BaseConstraintBuilder::default();
 
@@ -741,6 +810,12 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                     tx_type_bits.value_equals(Eip1559, Rotation::cur())(meta),
                 );
 
+                cb.require_equal(
+                    "is_eip4844 = (tx_type == Eip4844)",
+                    meta.query_advice(is_eip4844, Rotation::cur()),
+                    tx_type_bits.value_equals(Eip4844, Rotation::cur())(meta),
+                );
+
                 cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
             },
         );
@@ -781,13 +856,35 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
         });
 
+        meta.create_gate("lookup to blob hashes dynamic section condition", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            // a blob tx must have at least one blob versioned hash (EIP-4844)
+            cb.require_equal(
+                "condition",
+                and::expr([
+                    is_blob_versioned_hashes_len(meta),
+                    meta.query_advice(is_eip4844, Rotation::cur()),
+                ]),
+                meta.query_advice(
+                    lookup_conditions[&LookupCondition::TxBlobHashes],
+                    Rotation::cur(),
+                ),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
         meta.create_gate("sign tag lookup into RLP table condition", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
             let is_tag_in_tx_sign = sum::expr([
                 is_nonce(meta),
                 and::expr([
-                    not::expr(meta.query_advice(is_eip1559, Rotation::cur())),
+                    not::expr(sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                    ])),
                     is_gas_price(meta),
                 ]),
                 is_gas(meta),
@@ -800,16 +897,27 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                         tx_type_bits.value_equals(Eip155, Rotation::cur())(meta),
                         meta.query_advice(is_eip2930, Rotation::cur()),
                         meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
                     ]),
                 ]),
                 and::expr([
-                    meta.query_advice(is_eip1559, Rotation::cur()),
+                    sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                    ]),
                     is_max_fee_per_gas(meta),
                 ]),
                 and::expr([
-                    meta.query_advice(is_eip1559, Rotation::cur()),
+                    sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                    ]),
                     is_max_priority_fee_per_gas(meta),
                 ]),
+                and::expr([
+                    meta.query_advice(is_eip4844, Rotation::cur()),
+                    is_max_fee_per_blob_gas(meta),
+                ]),
                 is_sign_length(meta),
                 is_sign_rlc(meta),
             ]);
@@ -835,7 +943,10 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             let is_tag_in_tx_hash = sum::expr([
                 is_nonce(meta),
                 and::expr([
-                    not::expr(meta.query_advice(is_eip1559, Rotation::cur())),
+                    not::expr(sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                    ])),
                     is_gas_price(meta),
                 ]),
                 is_gas(meta),
@@ -849,13 +960,23 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 is_hash_length(meta),
                 is_hash_rlc(meta),
                 and::expr([
-                    meta.query_advice(is_eip1559, Rotation::cur()),
+                    sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                    ]),
                     is_max_fee_per_gas(meta),
                 ]),
                 and::expr([
-                    meta.query_advice(is_eip1559, Rotation::cur()),
+                    sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                    ]),
                     is_max_priority_fee_per_gas(meta),
                 ]),
+                and::expr([
+                    meta.query_advice(is_eip4844, Rotation::cur()),
+                    is_max_fee_per_blob_gas(meta),
+                ]),
             ]);
 
             cb.require_equal(
@@ -937,6 +1058,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             is_l1_msg,
             is_eip2930,
             is_eip1559,
+            is_eip4844,
             sv_address,
             calldata_gas_cost_acc,
             section_rlc,
@@ -951,6 +1073,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             al_idx,
             sk_idx,
             sks_acc,
+            is_blob_hash,
         );
 
         meta.create_gate("tx_gas_cost == 0 for L1 msg", |meta| {
@@ -1024,6 +1147,8 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 not::expr(meta.query_advice(is_padding_tx, Rotation::next())),
                 // next row should not be in the calldata region
                 not::expr(meta.query_advice(is_calldata, Rotation::next())),
+                // next row should not be in the blob hashes region
+                not::expr(meta.query_advice(is_blob_hash, Rotation::next())),
                 meta.query_advice(is_tag_block_num, Rotation::cur()),
             ]);
 
@@ -1057,6 +1182,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 and::expr([
                     // see the comment below
                     not::expr(meta.query_advice(is_calldata, Rotation::next())),
+                    not::expr(meta.query_advice(is_blob_hash, Rotation::next())),
                     block_num_unchanged.expr(),
                 ]),
                 |cb| {
@@ -1098,8 +1224,10 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                     // Therefore we can skip assign any values to fixed part related cols
                     // (e.g. block_num, tx_type, is_padding_tx, ....). The witness assignment of
                     // calldata part need only make sure that (is_final,
-                    // calldata_gas_cost_acc) are correctly assigned.
+                    // calldata_gas_cost_acc) are correctly assigned. Same for the blob hashes
+                    // part.
                     not::expr(meta.query_advice(is_calldata, Rotation::next())),
+                    not::expr(meta.query_advice(is_blob_hash, Rotation::next())),
                     not::expr(block_num_unchanged.expr()),
                 ]),
                 |cb| {
@@ -1240,7 +1368,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             let mut cb = BaseConstraintBuilder::default();
 
             let (lt_expr, eq_expr) = tx_id_cmp_cum_num_txs.expr(meta, None);
-            cb.condition(is_block_num(meta), |cb| {
+            cb.condition(meta.query_advice(is_tag_block_num, Rotation::cur()), |cb| {
                 cb.require_equal("lt or eq", sum::expr([lt_expr, eq_expr]), true.expr());
             });
 
@@ -1392,13 +1520,17 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
 
             // End of calldata bytes transition:
             // on the final call data byte, must transition to another
-            // calldata section or an access list section for the same tx
+            // calldata section, an access list section or a blob hashes section for the same tx
 
-            // on the final call data byte, if there's no access list, tx_id must change.
+            // on the final call data byte, if there's no access list or blob hashes, tx_id must
+            // change.
             cb.condition(
                 and::expr([
                     is_final_cur.expr(),
-                    not::expr(meta.query_advice(is_access_list, Rotation::next())),
+                    not::expr(sum::expr([
+                        meta.query_advice(is_access_list, Rotation::next()),
+                        meta.query_advice(is_blob_hash, Rotation::next()),
+                    ])),
                 ]),
                 |cb| {
                     cb.require_zero(
@@ -1613,6 +1745,60 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             ]))
         });
 
+        ////////////////////////////////////////////////////////////////////////
+        ///////////  Blob Versioned Hashes (if available on tx)  //////////////
+        ////////////////////////////////////////////////////////////////////////
+        meta.create_gate("tx blob versioned hashes", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_final_cur = meta.query_advice(is_final, Rotation::cur());
+            cb.require_boolean("is_final is boolean", is_final_cur.clone());
+
+            // the section of a tx starts with index 0
+            cb.condition(
+                not::expr(meta.query_advice(is_blob_hash, Rotation::prev())),
+                |cb| {
+                    cb.require_zero(
+                        "index starts with 0",
+                        meta.query_advice(tx_table.index, Rotation::cur()),
+                    );
+                },
+            );
+
+            // checks for any row, except the final blob versioned hash.
+            cb.condition(not::expr(is_final_cur.clone()), |cb| {
+                cb.require_equal(
+                    "index::next == index::cur + 1",
+                    meta.query_advice(tx_table.index, Rotation::next()),
+                    meta.query_advice(tx_table.index, Rotation::cur()) + 1.expr(),
+                );
+                cb.require_equal(
+                    "tx_id::next == tx_id::cur",
+                    tx_id_unchanged.is_equal_expression.clone(),
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "is_blob_hash::next == true",
+                    meta.query_advice(is_blob_hash, Rotation::next()),
+                    1.expr(),
+                );
+            });
+
+            // the blob hashes section is the last dynamic section of a tx
+            cb.condition(is_final_cur, |cb| {
+                cb.require_zero(
+                    "tx_id changes at is_final == 1",
+                    tx_id_unchanged.is_equal_expression.clone(),
+                );
+            });
+
+            cb.gate(and::expr(vec![
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(is_blob_hash, Rotation::cur()),
+                not::expr(tx_id_is_zero.expr(Rotation::cur())(meta)),
+            ]))
+        });
+
         ////////////////////////////////////////////////////////////////////////
         ///////////   SignVerify recover CallerAddress    //////////////////////
         ////////////////////////////////////////////////////////////////////////
@@ -1665,6 +1851,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             // TODO:
             //  4. eip1559 tx: v Є {0, 1}
             //  5. eip2930 tx: v Є {0, 1}
+            //  6. eip4844 tx: v Є {0, 1}
 
             cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
         });
@@ -1725,6 +1912,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             is_l1_msg,
             is_eip2930,
             is_eip1559,
+            is_eip4844,
             is_chain_id,
             is_final,
             calldata_gas_cost_acc,
@@ -1736,6 +1924,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             tx_table,
             keccak_table,
             rlp_table,
+            is_tag_nonce,
             is_tag_block_num,
             al_idx,
             sk_idx,
@@ -1744,6 +1933,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             is_access_list_address,
             is_access_list_storage_key,
             field_rlc,
+            is_blob_hash,
             _marker: PhantomData,
             num_txs,
         }
@@ -1768,6 +1958,7 @@ impl<F: Field> TxCircuitConfig<F> {
         is_l1_msg_col: Column<Advice>,
         is_eip2930: Column<Advice>,
         is_eip1559: Column<Advice>,
+        is_eip4844: Column<Advice>,
         sv_address: Column<Advice>,
         calldata_gas_cost_acc: Column<Advice>,
         section_rlc: Column<Advice>,
@@ -1782,6 +1973,7 @@ impl<F: Field> TxCircuitConfig<F> {
         al_idx: Column<Advice>,
         sk_idx: Column<Advice>,
         sks_acc: Column<Advice>,
+        is_blob_hash: Column<Advice>,
     ) {
         macro_rules! is_tx_type {
             ($var:ident, $type_variant:ident) => {
@@ -1984,6 +2176,61 @@ impl<F: Field> TxCircuitConfig<F> {
                 .map(|(input, table)| (input * enable.expr(), table))
                 .collect()
         });
+        meta.lookup_any("lookup BlobVersionedHashesLen in the TxTable", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(is_blob_hash, Rotation::cur()),
+                meta.query_advice(is_final, Rotation::cur()),
+            ]);
+
+            let input_exprs = vec![
+                meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                BlobVersionedHashesLen.expr(),
+                meta.query_advice(tx_table.index, Rotation::cur()) + 1.expr(), /* index starts
+                                                                                * from 0 */
+            ];
+            let table_exprs = vec![
+                meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                meta.query_fixed(tx_table.tag, Rotation::cur()),
+                meta.query_advice(tx_table.value, Rotation::cur()),
+            ];
+
+            input_exprs
+                .into_iter()
+                .zip(table_exprs)
+                .map(|(input, table)| (input * enable.expr(), table))
+                .collect()
+        });
+        meta.lookup_any(
+            "is_final blob versioned hash row should be present",
+            |meta| {
+                let enable = and::expr(vec![
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                    meta.query_advice(
+                        lookup_conditions[&LookupCondition::TxBlobHashes],
+                        Rotation::cur(),
+                    ),
+                ]);
+                let input_exprs = vec![
+                    meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                    1.expr(),
+                    1.expr(),
+                    meta.query_advice(tx_table.value, Rotation::cur()) - 1.expr(), // index
+                ];
+                let table_exprs = vec![
+                    meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                    meta.query_advice(is_blob_hash, Rotation::cur()),
+                    meta.query_advice(is_final, Rotation::cur()),
+                    meta.query_advice(tx_table.index, Rotation::cur()),
+                ];
+
+                input_exprs
+                    .into_iter()
+                    .zip(table_exprs)
+                    .map(|(input, table)| (input * enable.expr(), table))
+                    .collect()
+            },
+        );
 
         /////////////////////////////////////////////////////////////////
         /////////////////    RLP table lookups     //////////////////////
@@ -2036,7 +2283,8 @@ impl<F: Field> TxCircuitConfig<F> {
             let sign_format = is_pre_eip155(meta) * TxSignPreEip155.expr()
                 + is_eip155(meta) * TxSignEip155.expr()
                 + meta.query_advice(is_eip2930, Rotation::cur()) * TxSignEip2930.expr()
-                + meta.query_advice(is_eip1559, Rotation::cur()) * TxSignEip1559.expr();
+                + meta.query_advice(is_eip1559, Rotation::cur()) * TxSignEip1559.expr()
+                + meta.query_advice(is_eip4844, Rotation::cur()) * TxSignEip4844.expr();
 
             // q_enable, tx_id, format, rlp_tag, tag_value, is_output, is_none
             vec![
@@ -2079,7 +2327,8 @@ impl<F: Field> TxCircuitConfig<F> {
                 + is_eip155(meta) * TxHashEip155.expr()
                 + is_l1_msg(meta) * L1MsgHash.expr()
                 + meta.query_advice(is_eip2930, Rotation::cur()) * TxHashEip2930.expr()
-                + meta.query_advice(is_eip1559, Rotation::cur()) * TxHashEip1559.expr();
+                + meta.query_advice(is_eip1559, Rotation::cur()) * TxHashEip1559.expr()
+                + meta.query_advice(is_eip4844, Rotation::cur()) * TxHashEip4844.expr();
 
             vec![
                 1.expr(), // q_enable = true
@@ -2111,10 +2360,11 @@ impl<F: Field> TxCircuitConfig<F> {
                     meta.query_advice(is_access_list_address, Rotation::cur()),
                 ]);
 
-                // only eip2930 and eip1559 contains an access list
+                // only eip2930, eip1559 and eip4844 contains an access list
                 let sign_format = meta.query_advice(is_eip2930, Rotation::cur())
                     * TxSignEip2930.expr()
-                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxSignEip1559.expr();
+                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxSignEip1559.expr()
+                    + meta.query_advice(is_eip4844, Rotation::cur()) * TxSignEip4844.expr();
 
                 vec![
                     1.expr(), // q_enable = true
@@ -2144,10 +2394,11 @@ impl<F: Field> TxCircuitConfig<F> {
                     meta.query_advice(is_access_list_address, Rotation::cur()),
                 ]);
 
-                // only eip2930 and eip1559 contains an access list
+                // only eip2930, eip1559 and eip4844 contains an access list
                 let hash_format = meta.query_advice(is_eip2930, Rotation::cur())
                     * TxHashEip2930.expr()
-                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxHashEip1559.expr();
+                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxHashEip1559.expr()
+                    + meta.query_advice(is_eip4844, Rotation::cur()) * TxHashEip4844.expr();
 
                 vec![
                     1.expr(), // q_enable = true
@@ -2180,10 +2431,11 @@ impl<F: Field> TxCircuitConfig<F> {
                     meta.query_advice(is_access_list_storage_key, Rotation::cur()),
                 ]);
 
-                // only eip2930 and eip1559 contains an access list
+                // only eip2930, eip1559 and eip4844 contains an access list
                 let sign_format = meta.query_advice(is_eip2930, Rotation::cur())
                     * TxSignEip2930.expr()
-                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxSignEip1559.expr();
+                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxSignEip1559.expr()
+                    + meta.query_advice(is_eip4844, Rotation::cur()) * TxSignEip4844.expr();
 
                 vec![
                     1.expr(), // q_enable = true
@@ -2213,10 +2465,11 @@ impl<F: Field> TxCircuitConfig<F> {
                     meta.query_advice(is_access_list_storage_key, Rotation::cur()),
                 ]);
 
-                // only eip2930 and eip1559 contains an access list
+                // only eip2930, eip1559 and eip4844 contains an access list
                 let hash_format = meta.query_advice(is_eip2930, Rotation::cur())
                     * TxHashEip2930.expr()
-                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxHashEip1559.expr();
+                    + meta.query_advice(is_eip1559, Rotation::cur()) * TxHashEip1559.expr()
+                    + meta.query_advice(is_eip4844, Rotation::cur()) * TxHashEip4844.expr();
 
                 vec![
                     1.expr(), // q_enable = true
@@ -2238,6 +2491,75 @@ impl<F: Field> TxCircuitConfig<F> {
             },
         );
 
+        // lookup blob versioned hash in RLP table
+        // 1. ensure field_rlc is correct
+        // 2. ensure value of blob versioned hash is correct
+        // note: the blob versioned hashes are indexed by access_list_idx in the RLP table as
+        // they're listed at the same depth as the access list items.
+        meta.lookup_any(
+            "Lookup blob versioned hash in RLP Table from tx circuit dynamic section (Signing)",
+            |meta| {
+                let enable = and::expr(vec![
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                    meta.query_advice(is_blob_hash, Rotation::cur()),
+                ]);
+
+                // only eip4844 contains blob versioned hashes
+                let sign_format =
+                    meta.query_advice(is_eip4844, Rotation::cur()) * TxSignEip4844.expr();
+
+                vec![
+                    1.expr(), // q_enable = true
+                    meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                    sign_format,
+                    meta.query_advice(rlp_tag, Rotation::cur()),
+                    meta.query_advice(tx_table.value, Rotation::cur()),
+                    meta.query_advice(field_rlc, Rotation::cur()),
+                    32.expr(), // 32 bytes for blob versioned hashes
+                    1.expr(),  // is_output = true
+                    0.expr(),  // is_none = false. must have value
+                    meta.query_advice(tx_table.index, Rotation::cur()) + 1.expr(), // access_list_idx
+                    0.expr(), // storage_key_idx
+                ]
+                .into_iter()
+                .zip_eq(rlp_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.clone() * arg, table))
+                .collect()
+            },
+        );
+
+        meta.lookup_any(
+            "Lookup blob versioned hash in RLP Table from tx circuit dynamic section (Hashing)",
+            |meta| {
+                let enable = and::expr(vec![
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                    meta.query_advice(is_blob_hash, Rotation::cur()),
+                ]);
+
+                // only eip4844 contains blob versioned hashes
+                let hash_format =
+                    meta.query_advice(is_eip4844, Rotation::cur()) * TxHashEip4844.expr();
+
+                vec![
+                    1.expr(), // q_enable = true
+                    meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                    hash_format,
+                    meta.query_advice(rlp_tag, Rotation::cur()),
+                    meta.query_advice(tx_table.value, Rotation::cur()),
+                    meta.query_advice(field_rlc, Rotation::cur()),
+                    32.expr(), // 32 bytes for blob versioned hashes
+                    1.expr(),  // is_output = true
+                    0.expr(),  // is_none = false. must have value
+                    meta.query_advice(tx_table.index, Rotation::cur()) + 1.expr(), // access_list_idx
+                    0.expr(), // storage_key_idx
+                ]
+                .into_iter()
+                .zip_eq(rlp_table.table_exprs(meta))
+                .map(|(arg, table)| (enable.clone() * arg, table))
+                .collect()
+            },
+        );
+
         ////////////////////////////////////////////////////////////////////
         /////////////////    Sig table lookups     //////////////////////
         ///////////////// //////////////////////////////////////////////////
@@ -2601,6 +2923,24 @@ impl<F: Field> TxCircuitConfig<F> {
                 }),
                 rlc_be_bytes(&tx.max_priority_fee_per_gas.to_be_bytes(), evm_word),
             ),
+            (
+                MaxFeePerBlobGas,
+                Some(RlpTableInputValue {
+                    tag: Tag::MaxFeePerBlobGas.into(),
+                    is_none: tx.max_fee_per_blob_gas.is_zero(),
+                    be_bytes_len: tx.max_fee_per_blob_gas.tag_length(),
+                    be_bytes_rlc: rlc_be_bytes(
+                        &tx.max_fee_per_blob_gas.to_be_bytes(),
+                        keccak_input,
+                    ),
+                }),
+                rlc_be_bytes(&tx.max_fee_per_blob_gas.to_be_bytes(), evm_word),
+            ),
+            (
+                BlobVersionedHashesLen,
+                None,
+                Value::known(F::from(tx.blob_versioned_hashes.len() as u64)),
+            ),
             (BlockNumber, None, Value::known(F::from(tx.block_number))),
         ];
         for (tx_tag, rlp_input, tx_value) in fixed_rows {
@@ -2675,6 +3015,11 @@ impl<F: Field> TxCircuitConfig<F> {
                     F::from((tx_tag == CallData) as u64),
                 ),
                 // tx_tag related indicator columns
+                (
+                    "is_tag_nonce",
+                    self.is_tag_nonce,
+                    F::from((tx_tag == Nonce) as u64),
+                ),
                 (
                     "is_tag_block_num",
                     self.is_tag_block_num,
@@ -2724,6 +3069,11 @@ impl<F: Field> TxCircuitConfig<F> {
                     F::zero()
                 }
             });
+            // 2.1 lookup to ensure the final row in the blob hashes dynamic section is present.
+            conditions.insert(LookupCondition::TxBlobHashes, {
+                let tag_enable = tx_tag == BlobVersionedHashesLen;
+                F::from((tag_enable && tx.tx_type.is_eip4844()) as u64)
+            });
             // 3. lookup to RLP table for signing (non L1 msg)
             conditions.insert(LookupCondition::RlpSignTag, {
                 let sign_set = [
@@ -2738,10 +3088,12 @@ impl<F: Field> TxCircuitConfig<F> {
                 let is_tag_in_set = sign_set.into_iter().filter(|tag| tx_tag == *tag).count() == 1;
                 let case1 = is_tag_in_set && !is_l1_msg;
                 let case2 = !tx.tx_type.is_pre_eip155() && !is_l1_msg && (tx_tag == ChainID);
-                let case3 = !tx.tx_type.is_eip1559() && !is_l1_msg && (tx_tag == GasPrice);
-                let case4 = tx.tx_type.is_eip1559()
-                    && (tx_tag == MaxFeePerGas || tx_tag == MaxPriorityFeePerGas);
-                F::from((case1 || case2 || case3 || case4) as u64)
+                let is_dynamic_fee = tx.tx_type.is_eip1559() || tx.tx_type.is_eip4844();
+                let case3 = !is_dynamic_fee && !is_l1_msg && (tx_tag == GasPrice);
+                let case4 =
+                    is_dynamic_fee && (tx_tag == MaxFeePerGas || tx_tag == MaxPriorityFeePerGas);
+                let case5 = tx.tx_type.is_eip4844() && (tx_tag == MaxFeePerBlobGas);
+                F::from((case1 || case2 || case3 || case4 || case5) as u64)
             });
             // 4. lookup to RLP table for hashing (non L1 msg)
             conditions.insert(LookupCondition::RlpHashTag, {
@@ -2760,10 +3112,12 @@ impl<F: Field> TxCircuitConfig<F> {
                 ];
                 let is_tag_in_set = hash_set.into_iter().filter(|tag| tx_tag == *tag).count() == 1;
                 let case1 = is_tag_in_set && !is_l1_msg;
-                let case2 = !tx.tx_type.is_eip1559() && !is_l1_msg && (tx_tag == GasPrice);
-                let case3 = tx.tx_type.is_eip1559()
-                    && (tx_tag == MaxFeePerGas || tx_tag == MaxPriorityFeePerGas);
-                F::from((case1 || case2 || case3) as u64)
+                let is_dynamic_fee = tx.tx_type.is_eip1559() || tx.tx_type.is_eip4844();
+                let case2 = !is_dynamic_fee && !is_l1_msg && (tx_tag == GasPrice);
+                let case3 =
+                    is_dynamic_fee && (tx_tag == MaxFeePerGas || tx_tag == MaxPriorityFeePerGas);
+                let case4 = tx.tx_type.is_eip4844() && (tx_tag == MaxFeePerBlobGas);
+                F::from((case1 || case2 || case3 || case4) as u64)
             });
             // 5. lookup to RLP table for hashing (L1 msg)
             conditions.insert(LookupCondition::L1MsgHash, {
@@ -2846,7 +3200,7 @@ impl<F: Field> TxCircuitConfig<F> {
                 .zip(challenges.keccak_input())
                 .map(|(rlc, keccak_input)| rlc * keccak_input + F::from(*byte as u64));
             // the tx id of next row
-            let tx_id_next = if !is_final {
+            let tx_id_next = if !is_final || has_access_list(tx) || has_blob_hashes(tx) {
                 tx.id
             } else {
                 next_tx.map_or(0, |tx| tx.id)
@@ -2927,7 +3281,7 @@ impl<F: Field> TxCircuitConfig<F> {
                     rlc_be_bytes(&al.address.to_fixed_bytes(), challenges.keccak_input());
                 section_rlc = section_rlc * r32 + field_rlc;
 
-                let tx_id_next = if curr_row == total_rows {
+                let tx_id_next = if curr_row == total_rows && !has_blob_hashes(tx) {
                     next_tx.map_or(0, |tx| tx.id)
                 } else {
                     tx.id
@@ -2985,7 +3339,7 @@ impl<F: Field> TxCircuitConfig<F> {
                         section_rlc * r20 + field_rlc
                     };
 
-                    let tx_id_next = if curr_row == total_rows {
+                    let tx_id_next = if curr_row == total_rows && !has_blob_hashes(tx) {
                         next_tx.map_or(0, |tx| tx.id)
                     } else {
                         tx.id
@@ -3043,6 +3397,57 @@ impl<F: Field> TxCircuitConfig<F> {
         Ok(())
     }
 
+    /// Assign blob versioned hash rows of each tx
+    fn assign_blob_hash_rows(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: &mut usize,
+        tx: &Transaction,
+        next_tx: Option<&Transaction>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        for (idx, blob_hash) in tx.blob_versioned_hashes.iter().enumerate() {
+            let is_final = idx == (tx.blob_versioned_hashes.len() - 1);
+            let tx_id_next = if !is_final {
+                tx.id
+            } else {
+                next_tx.map_or(0, |tx| tx.id)
+            };
+
+            self.assign_common_part(
+                region,
+                *offset,
+                Some(tx),
+                tx_id_next,
+                BlobVersionedHash,
+                idx as u64,
+                rlc_be_bytes(&blob_hash.to_fixed_bytes(), challenges.evm_word()),
+                Value::known(F::zero()),
+            )?;
+
+            // 1st phase columns
+            for (col_anno, col, col_val) in [
+                ("block_num", self.block_num, F::from(tx.block_number)),
+                (
+                    "rlp_tag",
+                    self.rlp_tag,
+                    F::from(usize::from(Tag::BlobVersionedHash) as u64),
+                ),
+                ("is_final", self.is_final, F::from(is_final as u64)),
+                ("is_blob_hash", self.is_blob_hash, F::one()),
+            ] {
+                region.assign_advice(|| col_anno, col, *offset, || Value::known(col_val))?;
+            }
+
+            let field_rlc = rlc_be_bytes(&blob_hash.to_fixed_bytes(), challenges.keccak_input());
+            region.assign_advice(|| "field_rlc", self.field_rlc, *offset, || field_rlc)?;
+
+            *offset += 1;
+        }
+
+        Ok(())
+    }
+
     // Assigns to common columns in different parts of tx circuit
     // 1. 1st all zero row
     // 2. fixed rows of each tx
@@ -3115,6 +3520,11 @@ impl<F: Field> TxCircuitConfig<F> {
                 self.is_eip1559,
                 F::from(tx_type.is_eip1559() as u64),
             ),
+            (
+                "is_eip4844",
+                self.is_eip4844,
+                F::from(tx_type.is_eip4844() as u64),
+            ),
             (
                 "is_tx_id_zero",
                 self.is_tx_id_zero,
@@ -3318,6 +3728,23 @@ impl<F: Field> TxCircuit<F> {
         txs_len * TX_LEN + call_data_len
     }
 
+    /// Minimum and total number of rows of the circuit for txs of `call_data_len` bytes of
+    /// call data in total.
+    pub(crate) fn min_num_rows_of(
+        call_data_len: usize,
+        circuits_params: &CircuitsParams,
+    ) -> (usize, usize) {
+        // Since each call data byte at least takes one row in RLP circuit.
+        // For L2 tx, each call data byte takes two row in RLP circuit.
+        assert!(circuits_params.max_calldata < circuits_params.max_rlp_rows);
+        let tx_usage = call_data_len as f32 / circuits_params.max_calldata as f32;
+
+        (
+            (tx_usage * circuits_params.max_vertical_circuit_rows as f32).ceil() as usize,
+            Self::min_num_rows(circuits_params.max_txs, circuits_params.max_calldata),
+        )
+    }
+
     // assign num_txs, cum_num_txs, num_all_txs only as we only lookup into
     // block table for these three fields and this is mainly used for unit-test
     fn assign_dev_block_table(
@@ -3493,7 +3920,7 @@ impl<F: Field> TxCircuit<F> {
                     }
                     let is_last_tx = i == (sigs.len() - 1);
                     let next_tx = if is_last_tx {
-                        self.txs.iter().find(|tx| !tx.call_data.is_empty())
+                        self.txs.iter().find(|tx| has_dynamic_rows(tx))
                     } else {
                         Some(get_tx(i+1))
                     };
@@ -3533,7 +3960,7 @@ impl<F: Field> TxCircuit<F> {
                         .txs
                         .iter()
                         .skip(i + 1)
-                        .find(|tx| !tx.call_data.is_empty());
+                        .find(|tx| has_dynamic_rows(tx));
                     config.assign_calldata_rows(
                         &mut region,
                         &mut offset,
@@ -3548,6 +3975,13 @@ impl<F: Field> TxCircuit<F> {
                         next_tx,
                         challenges,
                     )?;
+                    config.assign_blob_hash_rows(
+                        &mut region,
+                        &mut offset,
+                        tx,
+                        next_tx,
+                        challenges,
+                    )?;
                 }
                 assert!(offset <= calldata_last_row, "{offset}, {calldata_last_row}");
                 // 3.2 pad calldata with zeros
@@ -3597,18 +4031,9 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
 
     /// Return the minimum number of rows required to prove the block
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
-        // Since each call data byte at least takes one row in RLP circuit.
-        // For L2 tx, each call data byte takes two row in RLP circuit.
-        assert!(block.circuits_params.max_calldata < block.circuits_params.max_rlp_rows);
-        let tx_usage = (block.txs.iter().map(|tx| tx.call_data.len()).sum::<usize>()) as f32
-            / block.circuits_params.max_calldata as f32;
-
-        (
-            (tx_usage * block.circuits_params.max_vertical_circuit_rows as f32).ceil() as usize,
-            Self::min_num_rows(
-                block.circuits_params.max_txs,
-                block.circuits_params.max_calldata,
-            ),
+        Self::min_num_rows_of(
+            block.txs.iter().map(|tx| tx.call_data.len()).sum(),
+            &block.circuits_params,
         )
     }
 
@@ -3746,3 +4171,20 @@ pub fn access_list_rlc<F: Field>(
         Value::known(F::zero())
     }
 }
+
+// Whether the tx has rows in the dynamic access list section
+fn has_access_list(tx: &Transaction) -> bool {
+    tx.access_list
+        .as_ref()
+        .map_or(false, |access_list| !access_list.0.is_empty())
+}
+
+// Whether the tx has rows in the dynamic blob versioned hashes section
+fn has_blob_hashes(tx: &Transaction) -> bool {
+    !tx.blob_versioned_hashes.is_empty()
+}
+
+// Whether the tx has any row in the dynamic part of tx table
+fn has_dynamic_rows(tx: &Transaction) -> bool {
+    !tx.call_data.is_empty() || has_access_list(tx) || has_blob_hashes(tx)
+}
@@ -4,8 +4,8 @@
 
 mod block;
 pub use block::{
-    block_apply_mpt_state, block_convert, block_convert_with_l1_queue_index,
-    block_mocking_apply_mpt, Block, BlockContext, BlockContexts,
+    block_apply_eth_mpt_state, block_apply_mpt_state, block_convert,
+    block_convert_with_l1_queue_index, block_mocking_apply_mpt, Block, BlockContext, BlockContexts,
 };
 
 mod bytecode;
@@ -14,11 +14,15 @@ pub use bytecode::Bytecode;
 mod call;
 pub use call::Call;
 
+pub(crate) mod eth_mpt;
+pub use eth_mpt::{empty_trie_root, EthAccount, EthMptTrace, EthTrieState, TrieNodeRlp, TriePath};
+
 mod mpt;
 pub use mpt::{MptUpdate, MptUpdateRow, MptUpdates, WithdrawProof};
 
 mod receipt;
-pub use receipt::Receipt;
+pub use receipt::{logs_bloom, receipts_data_bytes, Receipt, ReceiptLog, ReceiptsTrie};
+pub(crate) use receipt::{receipt_rlps, receipts_from_rws};
 
 pub(crate) mod rlp_fsm;
 pub use rlp_fsm::{
@@ -11,25 +11,29 @@ use crate::{
 };
 use bus_mapping::{
     circuit_input_builder::{
-        self, BigModExp, CircuitsParams, CopyEvent, EcAddOp, EcMulOp, EcPairingOp, ExpEvent,
-        PrecompileEvents, SHA256,
+        self, BigModExp, Blake2fOp, CircuitsParams, CopyEvent, EcAddOp, EcMulOp, EcPairingOp,
+        ExpEvent, PrecompileEvents, RIPEMD160, SHA256,
     },
     Error,
 };
-use eth_types::{sign_types::SignData, Address, Field, ToLittleEndian, ToScalar, Word, U256};
+use eth_types::{
+    serde_utils::eth_block, sign_types::SignData, Address, Field, ToLittleEndian, ToScalar, Word,
+    U256,
+};
 use halo2_proofs::circuit::Value;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 use super::{
-    mpt::ZktrieState as MptState, step::step_convert, tx::tx_convert, Bytecode, ExecStep,
-    MptUpdates, RwMap, Transaction,
+    mpt::ZktrieState as MptState, step::step_convert, tx::tx_convert, Bytecode, EthTrieState,
+    ExecStep, MptUpdates, RwMap, Transaction,
 };
 use crate::util::Challenges;
 
 // TODO: Remove fields that are duplicated in`eth_block`
 /// Block is the struct used by all circuits, which contains all the needed
 /// data for witness generation.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Block<F> {
     /// For historical reasons..
     pub _marker: std::marker::PhantomData<F>,
@@ -77,7 +81,7 @@ pub struct Block<F> {
 }
 
 /// ...
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct BlockContexts {
     /// Hashmap that maps block number to its block context.
     pub ctxs: BTreeMap<u64, BlockContext>,
@@ -86,6 +90,17 @@ pub struct BlockContexts {
 }
 
 impl<F: Field> Block<F> {
+    /// Encode the block in a compact binary format, so that it can be proved
+    /// on another machine or replayed without re-running the tracer.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, bincode::Error> {
+        bincode::serialize(self)
+    }
+
+    /// Decode a block encoded with [`Block::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(bytes)
+    }
+
     /// For each tx, for each step, print the rwc at the beginning of the step,
     /// and all the rw operations of the step.
     pub(crate) fn debug_print_txs_steps_rw_ops(&self) {
@@ -143,6 +158,16 @@ impl<F: Field> Block<F> {
         self.precompile_events.get_sha256_events()
     }
 
+    /// Get ripemd160 operations from all precompiled contract calls in this block.
+    pub(crate) fn get_ripemd160(&self) -> Vec<RIPEMD160> {
+        self.precompile_events.get_ripemd160_events()
+    }
+
+    /// Get blake2f operations from all precompiled contract calls in this block.
+    pub(crate) fn get_blake2f(&self) -> Vec<Blake2fOp> {
+        self.precompile_events.get_blake2f_events()
+    }
+
     pub(crate) fn print_evm_circuit_row_usage(&self) {
         let mut num_rows = 0;
         let mut counter = HashMap::new();
@@ -235,6 +260,18 @@ impl<F: Field> Block<F> {
         );
         log::debug!("tx_receipt num: {}", self.rws.rw_num(RwTableTag::TxReceipt));
         log::debug!("tx_log num: {}", self.rws.rw_num(RwTableTag::TxLog));
+        log::debug!(
+            "transient_storage num: {}",
+            self.rws.rw_num(RwTableTag::TransientStorage)
+        );
+        log::debug!(
+            "tx_created_account num: {}",
+            self.rws.rw_num(RwTableTag::TxCreatedAccount)
+        );
+        log::debug!(
+            "tx_destructed_account num: {}",
+            self.rws.rw_num(RwTableTag::TxDestructedAccount)
+        );
         log::debug!("start num: {}", self.rws.rw_num(RwTableTag::Start));
     }
 }
@@ -271,7 +308,11 @@ impl<F: Field> Block<F> {
         let num_rows_required_for_keccak_table: usize = self.keccak_inputs.len();
         // tx_table load only does tx padding, no calldata padding
         let num_rows_required_for_tx_table: usize = self.circuits_params.max_txs * TX_LEN
-            + self.txs.iter().map(|tx| tx.call_data.len()).sum::<usize>();
+            + self
+                .txs
+                .iter()
+                .map(|tx| tx.call_data.len() + tx.blob_versioned_hashes.len())
+                .sum::<usize>();
         let num_rows_required_for_exp_table: usize = self
             .exp_events
             .iter()
@@ -308,7 +349,7 @@ impl<F: Field> Block<F> {
 }
 
 /// Block context for execution
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockContext {
     /// The address of the miner for the block
     pub coinbase: Address,
@@ -327,6 +368,7 @@ pub struct BlockContext {
     /// The chain id
     pub chain_id: u64,
     /// Original Block from geth
+    #[serde(with = "eth_block")]
     pub eth_block: eth_types::Block<eth_types::Transaction>,
 }
 
@@ -599,6 +641,20 @@ pub fn block_apply_mpt_state<F: Field>(block: &mut Block<F>, mpt_state: &MptStat
     block.state_root = Some(block.mpt_updates.new_root());
 }
 
+/// Attach witness block with the paths of its mpt updates in the Ethereum
+/// state trie, for the keccak MPT circuit
+pub fn block_apply_eth_mpt_state<F: Field>(
+    block: &mut Block<F>,
+    trie: &EthTrieState,
+) -> Result<(), Error> {
+    block.mpt_updates.fill_state_roots_eth(trie)?;
+    block.state_root = Some(block.mpt_updates.new_root());
+    block
+        .keccak_inputs
+        .extend(block.mpt_updates.eth_keccak_inputs());
+    Ok(())
+}
+
 /// Mocking generate mpt witness from mpt states
 pub fn block_mocking_apply_mpt<F: Field>(block: &mut Block<F>) {
     block.mpt_updates.mock_fill_state_roots();
@@ -0,0 +1,832 @@
+//! Witness of the keccak MPT circuit, which proves the MPT updates of a block
+//! against the hexary Merkle-Patricia tries of Ethereum instead of the zktrie.
+use super::{
+    rlp_fsm::{gen_rlp_data_table, gen_rlp_fsm_witness},
+    DataTable, Format, RlpFsmWitnessGen, RlpFsmWitnessRow,
+};
+use crate::util::Challenges;
+use eth_types::{
+    Address, EIP1186ProofResponse, Error, Field, ToBigEndian, ToWord, Word, H256, U256,
+};
+use ethers_core::utils::{
+    keccak256,
+    rlp::{Rlp, RlpStream},
+};
+use halo2_proofs::circuit::Value;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Root of the empty trie, the hash of the RLP encoding of the empty string.
+pub fn empty_trie_root() -> H256 {
+    H256(keccak256([0x80]))
+}
+
+/// Path of `key` in the tries of Ethereum, which are keyed by the keccak hash
+/// of the account address or of the storage slot.
+pub(crate) fn key_nibbles(key: H256) -> Vec<u8> {
+    key.as_bytes()
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0xf])
+        .collect()
+}
+
+fn mpt_error(msg: impl std::fmt::Display) -> Error {
+    Error::MptProof(msg.to_string())
+}
+
+/// Hex-prefix encoding of a path of nibbles.
+pub(crate) fn hp_encode(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let is_odd = nibbles.len() % 2 == 1;
+    let flag = 2 * is_leaf as u8 + is_odd as u8;
+    let (first, rest) = if is_odd {
+        ((flag << 4) | nibbles[0], &nibbles[1..])
+    } else {
+        (flag << 4, nibbles)
+    };
+    std::iter::once(first)
+        .chain(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]))
+        .collect()
+}
+
+fn hp_decode(bytes: &[u8]) -> Result<(Vec<u8>, bool), Error> {
+    let (&first, rest) = bytes
+        .split_first()
+        .ok_or_else(|| mpt_error("empty hex-prefix path"))?;
+    let flag = first >> 4;
+    if flag > 3 || (flag & 1 == 0 && first & 0xf != 0) {
+        return Err(mpt_error(format!("invalid hex-prefix flag {first:#x}")));
+    }
+    let nibbles = (flag & 1 == 1)
+        .then_some(first & 0xf)
+        .into_iter()
+        .chain(rest.iter().flat_map(|byte| [byte >> 4, byte & 0xf]))
+        .collect();
+    Ok((nibbles, flag & 2 == 2))
+}
+
+/// A node of a hexary Merkle-Patricia trie. The references to children are
+/// either empty, the 32 bytes hash of the child, or the RLP encoding of the
+/// child itself when it is shorter than 32 bytes: the child is then embedded in
+/// its parent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum TrieNode {
+    /// The 16 children of a branch, its value being always empty in the tries
+    /// of Ethereum.
+    Branch(Vec<Vec<u8>>),
+    /// The path and the child of an extension.
+    Extension(Vec<u8>, Vec<u8>),
+    /// The path and the value of a leaf.
+    Leaf(Vec<u8>, Vec<u8>),
+}
+
+impl TrieNode {
+    pub(crate) fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let invalid = || mpt_error(format!("invalid trie node {:?}", H256(keccak256(bytes))));
+        let rlp = Rlp::new(bytes);
+        let item = |i: usize| -> Result<Vec<u8>, Error> {
+            let item = rlp.at(i).map_err(|_| invalid())?;
+            if item.is_list() {
+                return Err(invalid());
+            }
+            item.data().map(<[u8]>::to_vec).map_err(|_| invalid())
+        };
+        // an embedded child is a list shorter than 32 bytes, kept encoded
+        let child = |i: usize| -> Result<Vec<u8>, Error> {
+            let item = rlp.at(i).map_err(|_| invalid())?;
+            let child = if item.is_list() {
+                item.as_raw().to_vec()
+            } else {
+                item.data().map(<[u8]>::to_vec).map_err(|_| invalid())?
+            };
+            match (item.is_list(), child.len()) {
+                (true, len) if len < 32 => Ok(child),
+                (false, 0 | 32) => Ok(child),
+                _ => Err(invalid()),
+            }
+        };
+
+        match rlp.item_count().map_err(|_| invalid())? {
+            17 => {
+                let children = (0..16).map(child).collect::<Result<Vec<_>, _>>()?;
+                if !item(16)?.is_empty() {
+                    return Err(mpt_error("branch values are not supported"));
+                }
+                Ok(Self::Branch(children))
+            }
+            2 => {
+                let (path, is_leaf) = hp_decode(&item(0)?)?;
+                if is_leaf {
+                    Ok(Self::Leaf(path, item(1)?))
+                } else {
+                    match child(1)? {
+                        child if child.is_empty() => Err(invalid()),
+                        child => Ok(Self::Extension(path, child)),
+                    }
+                }
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let append_child = |stream: &mut RlpStream, child: &Vec<u8>| {
+            if is_embedded(child) {
+                stream.append_raw(child, 1);
+            } else {
+                stream.append(child);
+            }
+        };
+
+        let mut stream = RlpStream::new();
+        match self {
+            Self::Branch(children) => {
+                stream.begin_list(17);
+                for child in children {
+                    append_child(&mut stream, child);
+                }
+                stream.append_empty_data();
+            }
+            Self::Extension(path, child) => {
+                stream.begin_list(2);
+                stream.append(&hp_encode(path, false));
+                append_child(&mut stream, child);
+            }
+            Self::Leaf(path, value) => {
+                stream.begin_list(2);
+                stream.append(&hp_encode(path, true));
+                stream.append(value);
+            }
+        }
+        stream.out().to_vec()
+    }
+
+    /// The format the node is decoded with in the RLP circuit.
+    pub(crate) fn format(&self) -> Format {
+        match self {
+            Self::Branch(_) => Format::MptBranch,
+            Self::Extension(..) => Format::MptExtension,
+            Self::Leaf(..) => Format::MptLeaf,
+        }
+    }
+
+    /// The item of the node on the path of a key, with its index in the node:
+    /// the child at `nibble` of a branch, the child of an extension or the
+    /// value of a leaf.
+    pub(crate) fn item(&self, nibble: u8) -> (usize, &[u8]) {
+        match self {
+            Self::Branch(children) => (nibble as usize, &children[nibble as usize]),
+            Self::Extension(_, child) => (1, child),
+            Self::Leaf(_, value) => (1, value),
+        }
+    }
+
+    /// The number of nibbles of the key consumed by the node.
+    pub(crate) fn path_len(&self) -> usize {
+        match self {
+            Self::Branch(_) => 1,
+            Self::Extension(path, _) | Self::Leaf(path, _) => path.len(),
+        }
+    }
+}
+
+/// Whether a reference to a child is the child itself and not its hash.
+pub(crate) fn is_embedded(child: &[u8]) -> bool {
+    !child.is_empty() && child.len() != 32
+}
+
+/// The number of nibbles `a` and `b` start with in common.
+pub(crate) fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+/// The lengths of the parts of the RLP encoded list `bytes` around its item
+/// `index`: the list header, the items before it, the item and the items
+/// after it.
+pub(crate) fn item_offsets(bytes: &[u8], index: usize) -> [usize; 4] {
+    let rlp = Rlp::new(bytes);
+    let header_len = rlp
+        .payload_info()
+        .expect("trie nodes are valid RLP lists")
+        .header_len;
+    let item_len = |i| {
+        rlp.at(i)
+            .expect("trie nodes are valid RLP lists")
+            .as_raw()
+            .len()
+    };
+    let prefix_len = (0..index).map(item_len).sum::<usize>();
+    let len = item_len(index);
+    [
+        header_len,
+        prefix_len,
+        len,
+        bytes.len() - header_len - prefix_len - len,
+    ]
+}
+
+/// An account of the Ethereum state trie.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EthAccount {
+    /// Nonce
+    pub nonce: u64,
+    /// Balance
+    pub balance: U256,
+    /// Root of the storage trie
+    pub storage_root: H256,
+    /// Keccak hash of the code
+    pub code_hash: H256,
+}
+
+impl EthAccount {
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(4);
+        stream
+            .append(&self.nonce)
+            .append(&self.balance)
+            .append(&self.storage_root)
+            .append(&self.code_hash);
+        stream.out().to_vec()
+    }
+
+    pub(crate) fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let rlp = Rlp::new(bytes);
+        let decode = || -> Result<Self, ethers_core::utils::rlp::DecoderError> {
+            Ok(Self {
+                nonce: rlp.val_at(0)?,
+                balance: rlp.val_at(1)?,
+                storage_root: rlp.val_at(2)?,
+                code_hash: rlp.val_at(3)?,
+            })
+        };
+        decode().map_err(|e| mpt_error(format!("invalid account: {e}")))
+    }
+}
+
+/// The nodes along the path of a key, from the root of a trie, before and
+/// after an update, embedded nodes included. A path proving that the key is
+/// absent is left unchanged, unless the update inserts or deletes the key.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TriePath {
+    /// Hash of the key
+    pub key: H256,
+    /// RLP encoding of the nodes before the update
+    pub old_nodes: Vec<Vec<u8>>,
+    /// RLP encoding of the nodes after the update
+    pub new_nodes: Vec<Vec<u8>>,
+    /// RLP encoding of the other child of the branch created by the insertion
+    /// of the key, or removed by its deletion, when it is derived from the node
+    /// where the path without the key diverges from it
+    #[serde(default)]
+    pub sibling: Option<Vec<u8>>,
+}
+
+impl TriePath {
+    /// The old and new values stored at the key, read from the leaf ending
+    /// each path, `None` where the key is absent.
+    pub(crate) fn values(&self) -> Result<[Option<Vec<u8>>; 2], Error> {
+        let leaf_value = |nodes: &[Vec<u8>]| -> Result<Option<Vec<u8>>, Error> {
+            let mut depth = 0;
+            for node in nodes {
+                let node = TrieNode::decode(node)?;
+                depth += node.path_len();
+                if let TrieNode::Leaf(path, value) = node {
+                    let key = key_nibbles(self.key);
+                    return Ok((depth == key.len() && key.ends_with(&path)).then_some(value));
+                }
+            }
+            Ok(None)
+        };
+        Ok([leaf_value(&self.old_nodes)?, leaf_value(&self.new_nodes)?])
+    }
+}
+
+/// The paths proving an MPT update in the Ethereum state trie and, for storage
+/// updates, in the storage trie of the account.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EthMptTrace {
+    /// Path of the account in the state trie
+    pub account: TriePath,
+    /// Path of the storage slot in the storage trie of the account
+    pub storage: Option<TriePath>,
+}
+
+impl EthMptTrace {
+    /// The RLP instances decoded by the RLP circuit for this trace: the old
+    /// nodes, the new nodes and the sibling of the account path, the old and
+    /// new accounts where they exist, then the nodes of the storage path in
+    /// the same order.
+    pub(crate) fn rlp_instances(&self) -> Result<Vec<(Format, Vec<u8>)>, Error> {
+        fn push_path(instances: &mut Vec<(Format, Vec<u8>)>, path: &TriePath) -> Result<(), Error> {
+            for node in path
+                .old_nodes
+                .iter()
+                .chain(&path.new_nodes)
+                .chain(&path.sibling)
+            {
+                instances.push((TrieNode::decode(node)?.format(), node.clone()));
+            }
+            Ok(())
+        }
+
+        let mut instances = vec![];
+        push_path(&mut instances, &self.account)?;
+        for account in self.account.values()?.into_iter().flatten() {
+            instances.push((Format::MptAccount, account));
+        }
+        if let Some(storage) = &self.storage {
+            push_path(&mut instances, storage)?;
+        }
+        Ok(instances)
+    }
+
+    /// The inputs hashed by the keccak MPT circuit: the nodes of the paths.
+    pub(crate) fn keccak_inputs(&self) -> impl Iterator<Item = &Vec<u8>> {
+        [Some(&self.account), self.storage.as_ref()]
+            .into_iter()
+            .flatten()
+            .flat_map(|path| {
+                path.old_nodes
+                    .iter()
+                    .chain(&path.new_nodes)
+                    .chain(&path.sibling)
+            })
+    }
+}
+
+/// A trie node, an account or a receipt, decoded by the RLP circuit as the instance
+/// `id`, which follows the ids of the transactions.
+#[derive(Clone, Debug)]
+pub struct TrieNodeRlp {
+    /// Id of the instance in the RLP table
+    pub id: u64,
+    /// Format of the instance
+    pub format: Format,
+    /// RLP encoding of the node
+    pub bytes: Vec<u8>,
+}
+
+impl<F: Field> RlpFsmWitnessGen<F> for TrieNodeRlp {
+    fn gen_sm_witness(&self, challenges: &Challenges<Value<F>>) -> Vec<RlpFsmWitnessRow<F>> {
+        gen_rlp_fsm_witness(self.id, self.format, &self.bytes, challenges)
+    }
+
+    fn gen_data_table(&self, challenges: &Challenges<Value<F>>) -> Vec<DataTable<F>> {
+        gen_rlp_data_table(self.id, self.format, &self.bytes, challenges)
+    }
+}
+
+/// The nodes of the Ethereum state trie and storage tries needed to prove the
+/// MPT updates of a block, keyed by their hash.
+#[derive(Clone, Debug, Default)]
+pub struct EthTrieState {
+    /// Root of the state trie
+    pub state_root: H256,
+    nodes: HashMap<H256, Vec<u8>>,
+}
+
+impl EthTrieState {
+    /// Collect the nodes of the `eth_getProof` responses for the accounts and
+    /// storage slots touched by a block, in the state trie of root
+    /// `state_root`.
+    pub fn from_proofs(state_root: H256, proofs: &[EIP1186ProofResponse]) -> Self {
+        let mut state = Self {
+            state_root,
+            nodes: HashMap::new(),
+        };
+        for proof in proofs {
+            let storage_nodes = proof.storage_proof.iter().flat_map(|p| &p.proof);
+            for node in proof.account_proof.iter().chain(storage_nodes) {
+                state.insert_node(node.to_vec());
+            }
+        }
+        state
+    }
+
+    /// Build the state trie of the given accounts and their storage. The
+    /// storage roots of the accounts are replaced by the roots of the storage
+    /// tries built from the given slots.
+    pub fn from_accounts(
+        accounts: &BTreeMap<Address, (EthAccount, BTreeMap<Word, Word>)>,
+    ) -> Result<Self, Error> {
+        let mut state = Self::default();
+        let mut leaves = BTreeMap::new();
+        for (address, (account, storage)) in accounts {
+            let slots = storage
+                .iter()
+                .filter(|(_, value)| !value.is_zero())
+                .map(|(slot, value)| (storage_key_hash(*slot), rlp_word(*value)))
+                .collect();
+            let account = EthAccount {
+                storage_root: state.build_trie(&slots),
+                ..*account
+            };
+            leaves.insert(account_key_hash(*address), account.encode());
+        }
+        state.state_root = state.build_trie(&leaves);
+        Ok(state)
+    }
+
+    fn insert_node(&mut self, bytes: Vec<u8>) -> H256 {
+        let hash = H256(keccak256(&bytes));
+        self.nodes.insert(hash, bytes);
+        hash
+    }
+
+    fn node(&self, hash: H256) -> Result<TrieNode, Error> {
+        self.nodes
+            .get(&hash)
+            .ok_or_else(|| mpt_error(format!("missing trie node {hash:?}")))
+            .and_then(|bytes| TrieNode::decode(bytes))
+    }
+
+    /// The reference to `node` in its parent: the node itself if its encoding
+    /// is shorter than 32 bytes, its hash otherwise.
+    fn node_ref(&mut self, node: &TrieNode) -> Vec<u8> {
+        let bytes = node.encode();
+        if bytes.len() < 32 {
+            bytes
+        } else {
+            self.insert_node(bytes).as_bytes().to_vec()
+        }
+    }
+
+    /// The node referenced by the non empty `child`.
+    fn resolve(&self, child: &[u8]) -> Result<TrieNode, Error> {
+        if is_embedded(child) {
+            TrieNode::decode(child)
+        } else {
+            self.node(H256::from_slice(child))
+        }
+    }
+
+    /// The root of a trie is always hashed, even when it is shorter than 32
+    /// bytes.
+    fn root_hash(&mut self, root: Option<TrieNode>) -> H256 {
+        root.map_or_else(empty_trie_root, |root| self.insert_node(root.encode()))
+    }
+
+    fn build_trie(&mut self, leaves: &BTreeMap<H256, Vec<u8>>) -> H256 {
+        if leaves.is_empty() {
+            return empty_trie_root();
+        }
+        let leaves = leaves
+            .iter()
+            .map(|(key, value)| (key_nibbles(*key), value.clone()))
+            .collect::<Vec<_>>();
+        let root = self.build_node(&leaves, 0);
+        self.root_hash(Some(root))
+    }
+
+    // The leaves are sorted by key, so their common prefix is the one of the
+    // first and last leaves.
+    fn build_node(&mut self, leaves: &[(Vec<u8>, Vec<u8>)], depth: usize) -> TrieNode {
+        if let [(key, value)] = leaves {
+            return TrieNode::Leaf(key[depth..].to_vec(), value.clone());
+        }
+        let (first, last) = (&leaves[0].0, &leaves[leaves.len() - 1].0);
+        let prefix_len = common_prefix_len(&first[depth..], &last[depth..]);
+        if prefix_len > 0 {
+            let child = self.build_node(leaves, depth + prefix_len);
+            let path = first[depth..depth + prefix_len].to_vec();
+            TrieNode::Extension(path, self.node_ref(&child))
+        } else {
+            let mut children = vec![vec![]; 16];
+            for (nibble, child) in children.iter_mut().enumerate() {
+                let group = leaves
+                    .iter()
+                    .filter(|(key, _)| key[depth] as usize == nibble)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if !group.is_empty() {
+                    let node = self.build_node(&group, depth + 1);
+                    *child = self.node_ref(&node);
+                }
+            }
+            TrieNode::Branch(children)
+        }
+    }
+
+    /// The nodes along the path of `key` from `root`, and the value stored at
+    /// `key` if it exists. The path of an absent key ends with a branch whose
+    /// child at the key is empty, or with a leaf or an extension whose path
+    /// diverges from the key.
+    fn path(&self, root: H256, key: H256) -> Result<(Vec<TrieNode>, Option<Vec<u8>>), Error> {
+        let nibbles = key_nibbles(key);
+        let mut nodes = vec![];
+        let mut depth = 0;
+        if root == empty_trie_root() {
+            return Ok((nodes, None));
+        }
+        let mut node = self.node(root)?;
+        loop {
+            let (child, is_diverged) = match &node {
+                TrieNode::Branch(children) => {
+                    let child = nibbles
+                        .get(depth)
+                        .map(|nibble| children[*nibble as usize].clone())
+                        .ok_or_else(|| mpt_error("trie deeper than its keys"))?;
+                    depth += 1;
+                    (child, false)
+                }
+                TrieNode::Extension(path, child) => {
+                    let is_diverged = !nibbles[depth..].starts_with(path);
+                    depth += path.len();
+                    (child.clone(), is_diverged)
+                }
+                TrieNode::Leaf(path, value) => {
+                    let value = (nibbles[depth..] == path[..]).then(|| value.clone());
+                    nodes.push(node);
+                    return Ok((nodes, value));
+                }
+            };
+            nodes.push(node);
+            if is_diverged || child.is_empty() {
+                return Ok((nodes, None));
+            }
+            node = self.resolve(&child)?;
+        }
+    }
+
+    /// Set the value of the key of nibbles `nibbles` in the subtrie of `node`,
+    /// deleting the key if `value` is `None`, and return the new subtrie, kept
+    /// in the canonical form of the tries: a branch has at least two children,
+    /// and an extension is followed by a branch.
+    fn set(
+        &mut self,
+        node: Option<TrieNode>,
+        nibbles: &[u8],
+        value: Option<Vec<u8>>,
+    ) -> Result<Option<TrieNode>, Error> {
+        let Some(node) = node else {
+            return Ok(value.map(|value| TrieNode::Leaf(nibbles.to_vec(), value)));
+        };
+        match node {
+            TrieNode::Branch(mut children) => {
+                let nibble = nibbles[0] as usize;
+                let child = (!children[nibble].is_empty())
+                    .then(|| self.resolve(&children[nibble]))
+                    .transpose()?;
+                children[nibble] = match self.set(child, &nibbles[1..], value)? {
+                    Some(child) => self.node_ref(&child),
+                    None => vec![],
+                };
+                let remaining = children
+                    .iter()
+                    .positions(|child| !child.is_empty())
+                    .collect::<Vec<_>>();
+                match remaining[..] {
+                    [] => Ok(None),
+                    // the branch collapses into its last child
+                    [nibble] => {
+                        let child = self.resolve(&children[nibble])?;
+                        Ok(Some(self.prepend(&[nibble as u8], child)))
+                    }
+                    _ => Ok(Some(TrieNode::Branch(children))),
+                }
+            }
+            TrieNode::Extension(path, child) if nibbles.starts_with(&path) => {
+                let child = self.resolve(&child)?;
+                Ok(self
+                    .set(Some(child), &nibbles[path.len()..], value)?
+                    .map(|child| self.prepend(&path, child)))
+            }
+            TrieNode::Leaf(path, _) if path == nibbles => {
+                Ok(value.map(|value| TrieNode::Leaf(path, value)))
+            }
+            // the path of the node diverges from the key, which is absent
+            node => Ok(Some(match value {
+                Some(value) => self.split(node, nibbles, value),
+                None => node,
+            })),
+        }
+    }
+
+    /// The node `node` under the nibbles `path`: an extension to it if it is a
+    /// branch, or the node with its path prepended by `path` otherwise.
+    fn prepend(&mut self, path: &[u8], node: TrieNode) -> TrieNode {
+        match node {
+            TrieNode::Branch(_) => TrieNode::Extension(path.to_vec(), self.node_ref(&node)),
+            TrieNode::Extension(node_path, child) => {
+                TrieNode::Extension([path, &node_path].concat(), child)
+            }
+            TrieNode::Leaf(node_path, value) => TrieNode::Leaf([path, &node_path].concat(), value),
+        }
+    }
+
+    /// Insert the leaf of `value` next to the leaf or the extension `node`,
+    /// whose path diverges from `nibbles`, in a branch at the first nibble
+    /// they differ, under an extension of their common prefix.
+    fn split(&mut self, node: TrieNode, nibbles: &[u8], value: Vec<u8>) -> TrieNode {
+        let path = match &node {
+            TrieNode::Extension(path, _) | TrieNode::Leaf(path, _) => path.clone(),
+            TrieNode::Branch(_) => unreachable!("the path of a branch never diverges"),
+        };
+        let lcp = common_prefix_len(&path, nibbles);
+        let sibling = match node {
+            TrieNode::Extension(_, child) if lcp + 1 == path.len() => child,
+            TrieNode::Extension(_, child) => {
+                self.node_ref(&TrieNode::Extension(path[lcp + 1..].to_vec(), child))
+            }
+            TrieNode::Leaf(_, old_value) => {
+                self.node_ref(&TrieNode::Leaf(path[lcp + 1..].to_vec(), old_value))
+            }
+            TrieNode::Branch(_) => unreachable!("the path of a branch never diverges"),
+        };
+        let mut children = vec![vec![]; 16];
+        children[path[lcp] as usize] = sibling;
+        children[nibbles[lcp] as usize] =
+            self.node_ref(&TrieNode::Leaf(nibbles[lcp + 1..].to_vec(), value));
+        let branch = TrieNode::Branch(children);
+        if lcp == 0 {
+            branch
+        } else {
+            TrieNode::Extension(nibbles[..lcp].to_vec(), self.node_ref(&branch))
+        }
+    }
+
+    /// The other child of the branch created by an insertion or removed by a
+    /// deletion, given the path `nodes` of the key in the trie without it,
+    /// when the path ends with a leaf or an extension diverging from the key:
+    /// the node without the nibbles down to the branch, or the child of an
+    /// extension which diverges at its last nibble, needed for deletions only.
+    fn sibling(
+        &self,
+        nodes: &[TrieNode],
+        nibbles: &[u8],
+        is_deletion: bool,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let Some((last, parents)) = nodes.split_last() else {
+            return Ok(None);
+        };
+        let depth = parents.iter().map(TrieNode::path_len).sum::<usize>();
+        let path = match last {
+            TrieNode::Extension(path, _) | TrieNode::Leaf(path, _) => path,
+            TrieNode::Branch(_) => return Ok(None),
+        };
+        let lcp = common_prefix_len(path, &nibbles[depth..]);
+        if depth + lcp + 1 == nibbles.len() {
+            return Err(mpt_error("keys differing by their last nibble only"));
+        }
+        let sibling = match last {
+            TrieNode::Extension(path, child) if lcp + 1 == path.len() => {
+                if !is_deletion {
+                    return Ok(None);
+                }
+                self.resolve(child)?
+            }
+            TrieNode::Extension(path, child) => {
+                TrieNode::Extension(path[lcp + 1..].to_vec(), child.clone())
+            }
+            TrieNode::Leaf(path, value) => TrieNode::Leaf(path[lcp + 1..].to_vec(), value.clone()),
+            TrieNode::Branch(_) => unreachable!("the path of a branch never diverges"),
+        };
+        Ok(Some(sibling.encode()))
+    }
+
+    /// Set the value of `key` in the trie of root `root`, inserting the key if
+    /// it is absent, or deleting it if `value` is `None`.
+    fn update(
+        &mut self,
+        root: H256,
+        key: H256,
+        value: Option<Vec<u8>>,
+    ) -> Result<(H256, TriePath), Error> {
+        let nibbles = key_nibbles(key);
+        let (old_nodes, old_value) = self.path(root, key)?;
+        let root_node = old_nodes.first().cloned();
+        let new_root = self.set(root_node, &nibbles, value)?;
+        let new_root = self.root_hash(new_root);
+        let (new_nodes, new_value) = self.path(new_root, key)?;
+
+        let sibling = match (&old_value, &new_value) {
+            (None, Some(_)) => self.sibling(&old_nodes, &nibbles, false)?,
+            (Some(_), None) => self.sibling(&new_nodes, &nibbles, true)?,
+            _ => None,
+        };
+        let encode = |nodes: Vec<TrieNode>| nodes.iter().map(TrieNode::encode).collect();
+        let path = TriePath {
+            key,
+            old_nodes: encode(old_nodes),
+            new_nodes: encode(new_nodes),
+            sibling,
+        };
+        Ok((new_root, path))
+    }
+
+    /// The path of `address` in the state trie, and the account if it exists.
+    pub(crate) fn account(
+        &self,
+        address: Address,
+    ) -> Result<(TriePath, Option<EthAccount>), Error> {
+        let key = account_key_hash(address);
+        let (nodes, value) = self.path(self.state_root, key)?;
+        let nodes = nodes.iter().map(TrieNode::encode).collect::<Vec<_>>();
+        let path = TriePath {
+            key,
+            old_nodes: nodes.clone(),
+            new_nodes: nodes,
+            sibling: None,
+        };
+        Ok((path, value.as_deref().map(EthAccount::decode).transpose()?))
+    }
+
+    /// Set the account at `address`, creating it if it is missing.
+    pub(crate) fn update_account(
+        &mut self,
+        address: Address,
+        account: &EthAccount,
+    ) -> Result<TriePath, Error> {
+        let key = account_key_hash(address);
+        let (root, path) = self.update(self.state_root, key, Some(account.encode()))?;
+        self.state_root = root;
+        Ok(path)
+    }
+
+    /// The path of `slot` in the storage trie of root `storage_root`, and its
+    /// value.
+    pub(crate) fn storage(
+        &self,
+        storage_root: H256,
+        slot: Word,
+    ) -> Result<(TriePath, Word), Error> {
+        let key = storage_key_hash(slot);
+        let (nodes, value) = self.path(storage_root, key)?;
+        let nodes = nodes.iter().map(TrieNode::encode).collect::<Vec<_>>();
+        let path = TriePath {
+            key,
+            old_nodes: nodes.clone(),
+            new_nodes: nodes,
+            sibling: None,
+        };
+        let value = value
+            .map(|value| Rlp::new(&value).as_val::<U256>())
+            .transpose()
+            .map_err(|e| mpt_error(format!("invalid storage value: {e}")))?;
+        Ok((path, value.unwrap_or_default()))
+    }
+
+    /// Set `slot` to `value` in the storage trie of root `storage_root`,
+    /// inserting the slot if it was 0 and deleting it if `value` is 0, and
+    /// return the new root.
+    pub(crate) fn update_storage(
+        &mut self,
+        storage_root: H256,
+        slot: Word,
+        value: Word,
+    ) -> Result<(H256, TriePath), Error> {
+        let value = (!value.is_zero()).then(|| rlp_word(value));
+        self.update(storage_root, storage_key_hash(slot), value)
+    }
+}
+
+/// Key of an account in the state trie.
+pub(crate) fn account_key_hash(address: Address) -> H256 {
+    H256(keccak256(address))
+}
+
+/// Key of a storage slot in a storage trie.
+pub(crate) fn storage_key_hash(slot: Word) -> H256 {
+    H256(keccak256(slot.to_be_bytes()))
+}
+
+/// Storage values are stored as the RLP encoding of the value without leading
+/// zeros.
+fn rlp_word(value: Word) -> Vec<u8> {
+    let mut stream = RlpStream::new();
+    stream.append(&value);
+    stream.out().to_vec()
+}
+
+impl EthAccount {
+    /// The value of an account field as a word, like in the MPT table.
+    pub(crate) fn field(&self, field: EthAccountField) -> Word {
+        match field {
+            EthAccountField::Nonce => self.nonce.into(),
+            EthAccountField::Balance => self.balance,
+            EthAccountField::CodeHash => self.code_hash.to_word(),
+        }
+    }
+
+    pub(crate) fn set_field(&mut self, field: EthAccountField, value: Word) -> Result<(), Error> {
+        match field {
+            EthAccountField::Nonce => {
+                if value > u64::MAX.into() {
+                    return Err(mpt_error("nonce overflow"));
+                }
+                self.nonce = value.as_u64();
+            }
+            EthAccountField::Balance => self.balance = value,
+            EthAccountField::CodeHash => self.code_hash = H256(value.to_be_bytes()),
+        }
+        Ok(())
+    }
+}
+
+/// The account fields which can be updated in the state trie.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum EthAccountField {
+    Nonce,
+    Balance,
+    CodeHash,
+}
@@ -1,15 +1,25 @@
+use super::eth_mpt::{
+    empty_trie_root, EthAccount, EthAccountField, EthMptTrace, EthTrieState, TrieNodeRlp,
+};
 use crate::{
     evm_circuit::{util::rlc, witness::Rw},
     table::AccountFieldTag,
 };
-use eth_types::{Address, Field, ToLittleEndian, ToScalar, Word, U256};
+use eth_types::{
+    serde_utils::biguint, Address, Error, Field, ToBigEndian, ToLittleEndian, ToScalar, ToWord,
+    Word, U256,
+};
 use halo2_proofs::circuit::Value;
 use itertools::Itertools;
 use mpt_zktrie::{
-    mpt_circuits::{serde::SMTTrace, MPTProofType},
+    mpt_circuits::{
+        serde::{AccountData as SMTAccount, HexBytes, SMTNode, SMTPath, SMTTrace, StateData},
+        MPTProofType,
+    },
     state,
     state::{builder::init_hash_scheme, witness::WitnessGenerator},
 };
+use num_bigint::BigUint;
 use serde::{Deserialize, Serialize};
 pub use state::ZktrieState;
 use std::collections::BTreeMap;
@@ -26,7 +36,7 @@ pub struct WithdrawProof {
 }
 
 /// An MPT update whose validity is proved by the MptCircuit
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MptUpdate {
     key: Key,
     old_value: Word,
@@ -35,6 +45,7 @@ pub struct MptUpdate {
     new_root: Word,
     // for debugging
     #[cfg(debug_assertions)]
+    #[serde(skip)]
     original_rws: Vec<Rw>,
 }
 
@@ -89,7 +100,8 @@ impl Default for MptUpdate {
 }
 
 /// All the MPT updates in the MptCircuit, accessible by their key
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "MptUpdatesRepr", into = "MptUpdatesRepr")]
 pub struct MptUpdates {
     old_root: Word,
     new_root: Word,
@@ -100,6 +112,224 @@ pub struct MptUpdates {
     /// The detailed mpt witness
     pub smt_traces: Vec<SMTTrace>,
     pub(crate) proof_types: Vec<MPTProofType>,
+    /// The paths in the Ethereum tries proving the updates, in the same order
+    pub eth_traces: Vec<EthMptTrace>,
+}
+
+// The updates are stored as a list since their keys aren't strings.
+#[derive(Serialize, Deserialize)]
+struct MptUpdatesRepr {
+    old_root: Word,
+    new_root: Word,
+    updates: Vec<(Key, MptUpdate)>,
+    withdraw_proof: WithdrawProof,
+    smt_traces: Vec<SMTTraceRepr>,
+    #[serde(with = "proof_types")]
+    proof_types: Vec<MPTProofType>,
+    #[serde(default)]
+    eth_traces: Vec<EthMptTrace>,
+}
+
+impl From<MptUpdatesRepr> for MptUpdates {
+    fn from(repr: MptUpdatesRepr) -> Self {
+        Self {
+            old_root: repr.old_root,
+            new_root: repr.new_root,
+            updates: repr.updates.into_iter().collect(),
+            withdraw_proof: repr.withdraw_proof,
+            smt_traces: repr.smt_traces.into_iter().map(Into::into).collect(),
+            proof_types: repr.proof_types,
+            eth_traces: repr.eth_traces,
+        }
+    }
+}
+
+impl From<MptUpdates> for MptUpdatesRepr {
+    fn from(mpt_updates: MptUpdates) -> Self {
+        Self {
+            old_root: mpt_updates.old_root,
+            new_root: mpt_updates.new_root,
+            updates: mpt_updates.updates.into_iter().collect(),
+            withdraw_proof: mpt_updates.withdraw_proof,
+            smt_traces: mpt_updates.smt_traces.into_iter().map(Into::into).collect(),
+            proof_types: mpt_updates.proof_types,
+            eth_traces: mpt_updates.eth_traces,
+        }
+    }
+}
+
+/// [`MPTProofType`]s, encoded as their discriminant.
+mod proof_types {
+    use super::MPTProofType;
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    const PROOF_TYPES: [MPTProofType; 8] = [
+        MPTProofType::NonceChanged,
+        MPTProofType::BalanceChanged,
+        MPTProofType::CodeHashExists,
+        MPTProofType::PoseidonCodeHashExists,
+        MPTProofType::CodeSizeExists,
+        MPTProofType::AccountDoesNotExist,
+        MPTProofType::StorageChanged,
+        MPTProofType::StorageDoesNotExist,
+    ];
+
+    pub(super) fn serialize<S: Serializer>(
+        proof_types: &[MPTProofType],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        proof_types
+            .iter()
+            .map(|&proof_type| proof_type as u64)
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<MPTProofType>, D::Error> {
+        Vec::<u64>::deserialize(deserializer)?
+            .into_iter()
+            .map(|value| {
+                PROOF_TYPES
+                    .into_iter()
+                    .find(|&proof_type| proof_type as u64 == value)
+                    .ok_or_else(|| D::Error::custom(format!("unknown mpt proof type {value}")))
+            })
+            .collect()
+    }
+}
+
+// The serde implementation of the smt traces relies on a self-describing format, so they are
+// stored field by field.
+#[derive(Serialize, Deserialize)]
+struct SMTTraceRepr {
+    address: [u8; 20],
+    account_path: [SMTPathRepr; 2],
+    account_update: [Option<SMTAccountRepr>; 2],
+    account_key: [u8; 32],
+    state_path: [Option<SMTPathRepr>; 2],
+    common_state_root: Option<[u8; 32]>,
+    state_key: Option<[u8; 32]>,
+    state_update: Option<[Option<([u8; 32], [u8; 32])>; 2]>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SMTPathRepr {
+    root: [u8; 32],
+    leaf: Option<(u64, [u8; 32], [u8; 32])>,
+    path: Vec<(u64, [u8; 32], [u8; 32])>,
+    #[serde(with = "biguint")]
+    path_part: BigUint,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SMTAccountRepr {
+    nonce: u64,
+    #[serde(with = "biguint")]
+    balance: BigUint,
+    #[serde(with = "biguint")]
+    code_hash: BigUint,
+    #[serde(with = "biguint")]
+    poseidon_code_hash: BigUint,
+    code_size: u64,
+}
+
+fn smt_node_to_repr(node: &SMTNode) -> (u64, [u8; 32], [u8; 32]) {
+    (node.node_type, node.value.0, node.sibling.0)
+}
+
+fn smt_node_from_repr((node_type, value, sibling): (u64, [u8; 32], [u8; 32])) -> SMTNode {
+    SMTNode {
+        node_type,
+        value: HexBytes(value),
+        sibling: HexBytes(sibling),
+    }
+}
+
+impl From<SMTPath> for SMTPathRepr {
+    fn from(path: SMTPath) -> Self {
+        Self {
+            root: path.root.0,
+            leaf: path.leaf.as_ref().map(smt_node_to_repr),
+            path: path.path.iter().map(smt_node_to_repr).collect(),
+            path_part: path.path_part,
+        }
+    }
+}
+
+impl From<SMTPathRepr> for SMTPath {
+    fn from(repr: SMTPathRepr) -> Self {
+        Self {
+            root: HexBytes(repr.root),
+            leaf: repr.leaf.map(smt_node_from_repr),
+            path: repr.path.into_iter().map(smt_node_from_repr).collect(),
+            path_part: repr.path_part,
+        }
+    }
+}
+
+impl From<SMTAccount> for SMTAccountRepr {
+    fn from(account: SMTAccount) -> Self {
+        Self {
+            nonce: account.nonce,
+            balance: account.balance,
+            code_hash: account.code_hash,
+            poseidon_code_hash: account.poseidon_code_hash,
+            code_size: account.code_size,
+        }
+    }
+}
+
+impl From<SMTAccountRepr> for SMTAccount {
+    fn from(repr: SMTAccountRepr) -> Self {
+        Self {
+            nonce: repr.nonce,
+            balance: repr.balance,
+            code_hash: repr.code_hash,
+            poseidon_code_hash: repr.poseidon_code_hash,
+            code_size: repr.code_size,
+        }
+    }
+}
+
+impl From<SMTTrace> for SMTTraceRepr {
+    fn from(trace: SMTTrace) -> Self {
+        Self {
+            address: trace.address.0,
+            account_path: trace.account_path.map(Into::into),
+            account_update: trace.account_update.map(|account| account.map(Into::into)),
+            account_key: trace.account_key.0,
+            state_path: trace.state_path.map(|path| path.map(Into::into)),
+            common_state_root: trace.common_state_root.map(|root| root.0),
+            state_key: trace.state_key.map(|key| key.0),
+            state_update: trace
+                .state_update
+                .map(|update| update.map(|data| data.map(|data| (data.key.0, data.value.0)))),
+        }
+    }
+}
+
+impl From<SMTTraceRepr> for SMTTrace {
+    fn from(repr: SMTTraceRepr) -> Self {
+        Self {
+            address: HexBytes(repr.address),
+            account_path: repr.account_path.map(Into::into),
+            account_update: repr.account_update.map(|account| account.map(Into::into)),
+            account_key: HexBytes(repr.account_key),
+            state_path: repr.state_path.map(|path| path.map(Into::into)),
+            common_state_root: repr.common_state_root.map(HexBytes),
+            state_key: repr.state_key.map(HexBytes),
+            state_update: repr.state_update.map(|update| {
+                update.map(|data| {
+                    data.map(|(key, value)| StateData {
+                        key: HexBytes(key),
+                        value: HexBytes(value),
+                    })
+                })
+            }),
+        }
+    }
 }
 
 /// The field element encoding of an MPT update, which is used by the MptTable
@@ -261,6 +491,175 @@ impl MptUpdates {
         wit_gen
     }
 
+    /// Fill the state roots by applying the updates to the Ethereum state trie
+    /// `trie`, and keep the paths proving them for the keccak MPT circuit.
+    /// A missing account has all its fields 0, and is created by the update of
+    /// one of them to a non zero value. A storage slot is inserted by its
+    /// update from 0, and deleted by its update to 0.
+    pub(crate) fn fill_state_roots_eth(&mut self, trie: &EthTrieState) -> Result<(), Error> {
+        let mut trie = trie.clone();
+        self.old_root = trie.state_root.to_word();
+        self.eth_traces = Vec::new();
+
+        for update in self.updates.values_mut() {
+            log::trace!("apply update {:?}", update);
+            let old_root = trie.state_root;
+            let trace = match update.key {
+                Key::Account { address, field_tag } => {
+                    let (path, account) = trie.account(address)?;
+                    let field = match field_tag {
+                        AccountFieldTag::NonExisting if account.is_none() => None,
+                        AccountFieldTag::NonExisting => {
+                            return Err(Error::MptProof(format!("account {address:?} exists")))
+                        }
+                        AccountFieldTag::Nonce => Some(EthAccountField::Nonce),
+                        AccountFieldTag::Balance => Some(EthAccountField::Balance),
+                        AccountFieldTag::CodeHash => Some(EthAccountField::CodeHash),
+                        _ => {
+                            return Err(Error::MptProof(format!(
+                                "account field {field_tag:?} is not in the state trie"
+                            )))
+                        }
+                    };
+                    let path = match field {
+                        Some(field) => {
+                            let exists = account.is_some() || !update.new_value.is_zero();
+                            let mut account = account.unwrap_or(EthAccount {
+                                storage_root: empty_trie_root(),
+                                ..Default::default()
+                            });
+                            if account.field(field) != update.old_value {
+                                return Err(Error::MptProof(format!(
+                                    "{field:?} of account {address:?} is not {:#x}",
+                                    update.old_value
+                                )));
+                            }
+                            if exists {
+                                account.set_field(field, update.new_value)?;
+                                trie.update_account(address, &account)?
+                            } else {
+                                path
+                            }
+                        }
+                        None => path,
+                    };
+                    EthMptTrace {
+                        account: path,
+                        storage: None,
+                    }
+                }
+                Key::AccountStorage {
+                    address,
+                    storage_key,
+                    ..
+                } => {
+                    let (account_path, account) = trie.account(address)?;
+                    // the storage of a missing account is empty
+                    let storage_root =
+                        account.map_or_else(empty_trie_root, |account| account.storage_root);
+                    let (storage_path, value) = trie.storage(storage_root, storage_key)?;
+                    if value != update.old_value {
+                        return Err(Error::MptProof(format!(
+                            "storage {storage_key:#x} of account {address:?} is not {:#x}",
+                            update.old_value
+                        )));
+                    }
+                    if update.old_value.is_zero() && update.new_value.is_zero() {
+                        EthMptTrace {
+                            account: account_path,
+                            storage: Some(storage_path),
+                        }
+                    } else {
+                        let mut account = account.ok_or_else(|| {
+                            Error::MptProof(format!("storage of missing account {address:?}"))
+                        })?;
+                        let (storage_root, storage_path) = trie.update_storage(
+                            account.storage_root,
+                            storage_key,
+                            update.new_value,
+                        )?;
+                        account.storage_root = storage_root;
+                        EthMptTrace {
+                            account: trie.update_account(address, &account)?,
+                            storage: Some(storage_path),
+                        }
+                    }
+                }
+            };
+            update.old_root = old_root.to_word();
+            update.new_root = trie.state_root.to_word();
+            self.eth_traces.push(trace);
+        }
+        self.new_root = trie.state_root.to_word();
+        log::debug!(
+            "mpt update roots (after eth trie) {:#x} {:#x}",
+            self.old_root,
+            self.new_root
+        );
+        Ok(())
+    }
+
+    /// The trie nodes and accounts of the Ethereum tries to decode in the RLP
+    /// circuit, numbered from `first_id`, grouped by update.
+    pub(crate) fn eth_trie_node_rlps(&self, first_id: u64) -> Vec<Vec<TrieNodeRlp>> {
+        let mut id = first_id;
+        self.eth_traces
+            .iter()
+            .map(|trace| {
+                trace
+                    .rlp_instances()
+                    .expect("trie nodes are decoded when building the traces")
+                    .into_iter()
+                    .map(|(format, bytes)| {
+                        id += 1;
+                        TrieNodeRlp {
+                            id: id - 1,
+                            format,
+                            bytes,
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// All the trie nodes and accounts decoded by the RLP circuit, see
+    /// [`Self::eth_trie_node_rlps`].
+    pub(crate) fn trie_node_rlps(&self, first_id: u64) -> Vec<TrieNodeRlp> {
+        self.eth_trie_node_rlps(first_id).concat()
+    }
+
+    /// The inputs hashed by the keccak MPT circuit: the trie nodes, and the
+    /// addresses and storage keys which are hashed into trie keys.
+    pub(crate) fn eth_keccak_inputs(&self) -> Vec<Vec<u8>> {
+        self.updates
+            .values()
+            .zip(&self.eth_traces)
+            .flat_map(|(update, trace)| {
+                let preimages = match update.key {
+                    Key::Account { address, .. } => vec![address.as_bytes().to_vec()],
+                    Key::AccountStorage {
+                        address,
+                        storage_key,
+                        ..
+                    } => vec![
+                        address.as_bytes().to_vec(),
+                        storage_key.to_be_bytes().to_vec(),
+                    ],
+                };
+                preimages
+                    .into_iter()
+                    .chain(trace.keccak_inputs().cloned())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// The updates with the paths proving them in the Ethereum tries.
+    pub(crate) fn eth_updates(&self) -> impl Iterator<Item = (&MptUpdate, &EthMptTrace)> {
+        self.updates.values().zip(&self.eth_traces)
+    }
+
     pub(crate) fn mock_from(rows: &[Rw]) -> Self {
         Self::from_rws_with_mock_state_roots(rows, 0xcafeu64.into(), 0xdeadbeefu64.into())
     }
@@ -348,6 +747,23 @@ impl MptUpdates {
 }
 
 impl MptUpdate {
+    pub(crate) fn address(&self) -> Address {
+        match self.key {
+            Key::Account { address, .. } | Key::AccountStorage { address, .. } => address,
+        }
+    }
+
+    pub(crate) fn storage_key(&self) -> Option<Word> {
+        match self.key {
+            Key::Account { .. } => None,
+            Key::AccountStorage { storage_key, .. } => Some(storage_key),
+        }
+    }
+
+    pub(crate) fn roots(&self) -> (Word, Word) {
+        (self.new_root, self.old_root)
+    }
+
     pub(crate) fn values(&self) -> (Word, Word) {
         (self.new_value, self.old_value)
     }
@@ -402,7 +818,8 @@ impl MptUpdate {
             old_value,
         ])
     }
-    fn proof_type(&self) -> MPTProofType {
+
+    pub(crate) fn proof_type(&self) -> MPTProofType {
         match self.key {
             Key::AccountStorage { .. } => {
                 if self.old_value.is_zero() && self.new_value.is_zero() {
@@ -425,7 +842,7 @@ impl MptUpdate {
     }
 }
 
-#[derive(Eq, PartialEq, Hash, Clone, Debug, Copy, PartialOrd, Ord)]
+#[derive(Eq, PartialEq, Hash, Clone, Debug, Copy, PartialOrd, Ord, Serialize, Deserialize)]
 enum Key {
     Account {
         address: Address,
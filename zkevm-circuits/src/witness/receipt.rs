@@ -1,35 +1,137 @@
-use ethers_core::{
-    types::{Bloom, Log},
-    utils::rlp::{Encodable, RlpStream},
+//! The receipts of the txs of a block, rebuilt from the rw table.
+
+pub use bus_mapping::circuit_input_builder::{
+    logs_bloom, receipts_data_bytes, Receipt, ReceiptLog, ReceiptsTrie,
 };
 
-/// EVM log's receipt.
-#[derive(Clone, Debug, Default)]
-pub struct Receipt {
-    /// Denotes the ID of the tx.
-    pub id: usize,
-    /// Denotes whether or not the tx was executed successfully.
-    pub status: u8,
-    /// Denotes the cumulative gas used by the tx execution.
-    pub cumulative_gas_used: u64,
-    /// Represents the 256-bytes bloom filter.
-    pub bloom: Bloom,
-    /// List of logs generated by the tx.
-    pub logs: Vec<Log>,
-}
+use super::{eth_mpt::TrieNode, Block, Format, Rw, TrieNodeRlp};
+use crate::{
+    table::{RwTableTag, TxLogFieldTag, TxReceiptFieldTag},
+    witness::Transaction,
+};
+use eth_types::{Field, ToAddress, ToBigEndian, H256};
 
-impl Encodable for Receipt {
-    fn rlp_append(&self, s: &mut RlpStream) {
-        s.begin_list(4);
-        s.append(&self.status);
-        s.append(&self.cumulative_gas_used);
-        s.append(&self.bloom);
-        s.begin_list(self.logs.len());
-        for log in self.logs.iter() {
-            s.begin_list(3);
-            s.append(&log.address);
-            s.append_list(&log.topics);
-            s.append(&log.data.0);
+/// The receipts of `txs`, from their TxReceipt and TxLog rws.
+pub(crate) fn receipts_from_rws<'a>(
+    txs: &[Transaction],
+    rws: impl IntoIterator<Item = &'a Rw>,
+) -> Vec<Receipt> {
+    let mut receipts = txs
+        .iter()
+        .map(|tx| Receipt {
+            tx_type: tx.tx_type.eip2718_type(),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    for rw in rws {
+        match *rw {
+            Rw::TxReceipt {
+                tx_id,
+                field_tag,
+                value,
+                ..
+            } => {
+                let receipt = &mut receipts[tx_id - 1];
+                match field_tag {
+                    TxReceiptFieldTag::PostStateOrStatus => receipt.status = value as u8,
+                    TxReceiptFieldTag::CumulativeGasUsed => receipt.cumulative_gas_used = value,
+                    TxReceiptFieldTag::LogLength => {
+                        let len = receipt.logs.len().max(value as usize);
+                        receipt.logs.resize(len, ReceiptLog::default())
+                    }
+                }
+            }
+            Rw::TxLog {
+                tx_id,
+                log_id,
+                field_tag,
+                index,
+                value,
+                ..
+            } => {
+                let logs = &mut receipts[tx_id - 1].logs;
+                if logs.len() < log_id as usize {
+                    logs.resize(log_id as usize, ReceiptLog::default());
+                }
+                let log = &mut logs[log_id as usize - 1];
+                match field_tag {
+                    TxLogFieldTag::Address => log.address = value.to_address(),
+                    TxLogFieldTag::Topic => {
+                        log.topics
+                            .resize(log.topics.len().max(index + 1), H256::zero());
+                        log.topics[index] = H256(value.to_be_bytes());
+                    }
+                    TxLogFieldTag::Data => {
+                        log.data.resize(log.data.len().max(index + 1), 0);
+                        log.data[index] = value.as_u64() as u8;
+                    }
+                }
+            }
+            _ => {}
         }
     }
+    receipts
+}
+
+/// The receipts and the nodes of the receipts trie decoded by the RLP circuit,
+/// numbered from `first_id`.
+pub(crate) fn receipt_rlps(receipts: &[Receipt], first_id: u64) -> Vec<TrieNodeRlp> {
+    let receipt_rlps = receipts.iter().map(|receipt| {
+        let format = if receipt.tx_type == 0 {
+            Format::Receipt
+        } else {
+            Format::TypedReceipt
+        };
+        (format, receipt.rlp_bytes())
+    });
+    let node_rlps = ReceiptsTrie::new(receipts).nodes.into_iter().map(|bytes| {
+        let node = TrieNode::decode(&bytes).expect("receipts trie nodes are well formed");
+        (node.format(), bytes)
+    });
+    receipt_rlps
+        .chain(node_rlps)
+        .zip(first_id..)
+        .map(|((format, bytes), id)| TrieNodeRlp { id, format, bytes })
+        .collect()
+}
+
+impl<F: Field> Block<F> {
+    /// The receipts of the txs, from their TxReceipt and TxLog rws.
+    pub fn receipts(&self) -> Vec<Receipt> {
+        receipts_from_rws(&self.txs, &self.receipt_rws())
+    }
+
+    /// The TxReceipt and TxLog rws of the txs, sorted by key as in the rw
+    /// table.
+    pub(crate) fn receipt_rws(&self) -> Vec<Rw> {
+        let mut rws = [RwTableTag::TxReceipt, RwTableTag::TxLog]
+            .iter()
+            .flat_map(|tag| self.rws.0.get(tag).into_iter().flatten())
+            .copied()
+            .collect::<Vec<_>>();
+        rws.sort_by_cached_key(Rw::as_key);
+        rws
+    }
+
+    /// All the instances decoded by the RLP circuit after the txs: the trie
+    /// nodes of the state proofs, then the receipts and the receipts trie.
+    pub(crate) fn trie_node_rlps(&self) -> Vec<TrieNodeRlp> {
+        let mut rlps = self
+            .mpt_updates
+            .trie_node_rlps(self.circuits_params.max_txs as u64 + 1);
+        rlps.extend(receipt_rlps(&self.receipts(), self.first_receipt_rlp_id()));
+        rlps
+    }
+
+    /// The id of the first receipt in the RLP table, after the txs and the
+    /// trie nodes of the state proofs.
+    pub(crate) fn first_receipt_rlp_id(&self) -> u64 {
+        let n_state_nodes = self
+            .mpt_updates
+            .eth_trie_node_rlps(0)
+            .iter()
+            .map(Vec::len)
+            .sum::<usize>();
+        (self.circuits_params.max_txs + n_state_nodes) as u64 + 1
+    }
 }
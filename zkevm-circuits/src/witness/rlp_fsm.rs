@@ -1,6 +1,7 @@
 use eth_types::{Address, Field, H160, U256};
 use gadgets::{impl_expr, util::Expr};
 use halo2_proofs::{circuit::Value, halo2curves::ff::PrimeField, plonk::Expression};
+use std::{cmp::Ordering, collections::BTreeMap};
 use strum_macros::EnumIter;
 
 use crate::util::Challenges;
@@ -135,6 +136,22 @@ pub enum Tag {
     // L1MsgHash
     /// Sender
     Sender,
+
+    // EIP-4844
+    /// Max fee per blob gas
+    MaxFeePerBlobGas,
+    /// Versioned hash in blob_versioned_hashes
+    BlobVersionedHash,
+
+    // Merkle-Patricia trie nodes
+    /// Child reference of a branch or extension node
+    MptChild,
+    /// Hex-prefix encoded path of an extension or leaf node
+    MptPath,
+    /// Storage root of an account
+    StorageRoot,
+    /// Keccak code hash of an account
+    CodeHash,
 }
 
 impl From<Tag> for usize {
@@ -177,6 +194,16 @@ impl Tag {
     pub fn is_access_list_storage_key(&self) -> bool {
         matches!(self, Self::AccessListStorageKey)
     }
+
+    /// If the tag is BlobVersionedHash
+    pub fn is_blob_versioned_hash(&self) -> bool {
+        matches!(self, Self::BlobVersionedHash)
+    }
+
+    /// If the tag is MptChild
+    pub fn is_mpt_child(&self) -> bool {
+        matches!(self, Self::MptChild)
+    }
 }
 
 /// RLP tags
@@ -218,13 +245,16 @@ use crate::{
     witness::{
         l1_msg,
         Format::{
-            TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashPreEip155, TxSignEip155,
-            TxSignEip1559, TxSignEip2930, TxSignPreEip155,
+            MptAccount, MptBranch, MptExtension, MptLeaf, Receipt, TxHashEip155, TxHashEip1559,
+            TxHashEip2930, TxHashEip4844, TxHashPreEip155, TxSignEip155, TxSignEip1559,
+            TxSignEip2930, TxSignEip4844, TxSignPreEip155, TypedReceipt,
         },
+        State::DecodeTagStart,
         Tag::{
-            AccessListAddress, AccessListStorageKey, BeginObject, BeginVector, ChainId, Data,
-            EndObject, EndVector, Gas, GasPrice, MaxFeePerGas, MaxPriorityFeePerGas, Nonce, SigR,
-            SigS, SigV, To, TxType, Value as TxValue, Zero1, Zero2,
+            AccessListAddress, AccessListStorageKey, BeginObject, BeginVector, BlobVersionedHash,
+            ChainId, CodeHash, Data, EndObject, EndVector, Gas, GasPrice, MaxFeePerBlobGas,
+            MaxFeePerGas, MaxPriorityFeePerGas, MptChild, MptPath, Nonce, SigR, SigS, SigV,
+            StorageRoot, To, TxType, Value as TxValue, Zero1, Zero2,
         },
     },
 };
@@ -234,6 +264,7 @@ use crate::{
 // For example, [0xf9, 0xff, 0xff] has tag_length = 2 and has 0xffff bytes inside.
 pub(crate) const MAX_TAG_LENGTH_OF_LIST: usize = 3;
 pub(crate) const N_BYTES_CALLDATA: usize = 1 << 24;
+pub(crate) const N_BYTES_BLOOM: usize = 256;
 
 fn eip155_tx_sign_rom_table_rows() -> Vec<RomTableRow> {
     let rows = vec![
@@ -544,6 +575,288 @@ pub fn eip1559_tx_sign_rom_table_rows() -> Vec<RomTableRow> {
         .collect()
 }
 
+pub fn eip4844_tx_sign_rom_table_rows() -> Vec<RomTableRow> {
+    let rows = vec![
+        (TxType, BeginObject, 1, vec![1]),
+        (BeginObject, ChainId, MAX_TAG_LENGTH_OF_LIST, vec![2]),
+        (ChainId, Nonce, N_BYTES_U64, vec![3]),
+        (Nonce, MaxPriorityFeePerGas, N_BYTES_U64, vec![4]),
+        (MaxPriorityFeePerGas, MaxFeePerGas, N_BYTES_WORD, vec![5]),
+        (MaxFeePerGas, Gas, N_BYTES_WORD, vec![6]),
+        (Gas, To, N_BYTES_U64, vec![7]),
+        (To, TxValue, N_BYTES_ACCOUNT_ADDRESS, vec![8]),
+        (TxValue, Data, N_BYTES_WORD, vec![9]),
+        (Data, BeginVector, N_BYTES_CALLDATA, vec![10, 11]),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![21]), // access_list is none
+        (BeginVector, BeginObject, MAX_TAG_LENGTH_OF_LIST, vec![12]),
+        (
+            BeginObject,
+            AccessListAddress,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![13],
+        ),
+        (
+            AccessListAddress,
+            BeginVector,
+            N_BYTES_ACCOUNT_ADDRESS,
+            vec![14, 15],
+        ),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![18]), /* access_list.storage_keys
+                                                                     * is none */
+        (
+            BeginVector,
+            AccessListStorageKey,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![16, 17],
+        ),
+        (AccessListStorageKey, EndVector, N_BYTES_WORD, vec![18]), // finished parsing storage keys
+        (
+            AccessListStorageKey,
+            AccessListStorageKey,
+            N_BYTES_WORD,
+            vec![16, 17],
+        ), // keep parsing storage_keys
+        (EndVector, EndObject, 0, vec![19, 20]),
+        (EndObject, EndVector, 0, vec![21]), // finished parsing access_list
+        (EndObject, BeginObject, 0, vec![12]), // parse another access_list entry
+        (EndVector, MaxFeePerBlobGas, 0, vec![22]),
+        (MaxFeePerBlobGas, BeginVector, N_BYTES_WORD, vec![23, 24]),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![27]), /* blob_versioned_hashes is
+                                                                     * none */
+        (
+            BeginVector,
+            BlobVersionedHash,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![25, 26],
+        ),
+        (BlobVersionedHash, EndVector, N_BYTES_WORD, vec![27]), // finished parsing blob hashes
+        (
+            BlobVersionedHash,
+            BlobVersionedHash,
+            N_BYTES_WORD,
+            vec![25, 26],
+        ), // keep parsing
+        (EndVector, EndObject, 0, vec![28]),
+        (EndObject, EndObject, 0, vec![29]),
+        // used to emit TxGasCostInL1
+        (EndObject, BeginObject, 0, vec![]),
+    ];
+
+    rows.into_iter()
+        .map(|row| (row.0, row.1, row.2, TxSignEip4844, row.3).into())
+        .collect()
+}
+
+pub fn eip4844_tx_hash_rom_table_rows() -> Vec<RomTableRow> {
+    let rows = vec![
+        (TxType, BeginObject, 1, vec![1]),
+        (BeginObject, ChainId, MAX_TAG_LENGTH_OF_LIST, vec![2]),
+        (ChainId, Nonce, N_BYTES_U64, vec![3]),
+        (Nonce, MaxPriorityFeePerGas, N_BYTES_U64, vec![4]),
+        (MaxPriorityFeePerGas, MaxFeePerGas, N_BYTES_WORD, vec![5]),
+        (MaxFeePerGas, Gas, N_BYTES_WORD, vec![6]),
+        (Gas, To, N_BYTES_U64, vec![7]),
+        (To, TxValue, N_BYTES_ACCOUNT_ADDRESS, vec![8]),
+        (TxValue, Data, N_BYTES_WORD, vec![9]),
+        (Data, BeginVector, N_BYTES_CALLDATA, vec![10, 11]),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![21]), // access_list is none
+        (BeginVector, BeginObject, MAX_TAG_LENGTH_OF_LIST, vec![12]),
+        (
+            BeginObject,
+            AccessListAddress,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![13],
+        ),
+        (
+            AccessListAddress,
+            BeginVector,
+            N_BYTES_ACCOUNT_ADDRESS,
+            vec![14, 15],
+        ),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![18]), /* access_list.storage_keys
+                                                                     * is none */
+        (
+            BeginVector,
+            AccessListStorageKey,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![16, 17],
+        ),
+        (AccessListStorageKey, EndVector, N_BYTES_WORD, vec![18]), // finished parsing storage keys
+        (
+            AccessListStorageKey,
+            AccessListStorageKey,
+            N_BYTES_WORD,
+            vec![16, 17],
+        ), // keep parsing storage_keys
+        (EndVector, EndObject, 0, vec![19, 20]),
+        (EndObject, EndVector, 0, vec![21]), // finished parsing access_list
+        (EndObject, BeginObject, 0, vec![12]), // parse another access_list entry
+        (EndVector, MaxFeePerBlobGas, 0, vec![22]),
+        (MaxFeePerBlobGas, BeginVector, N_BYTES_WORD, vec![23, 24]),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![27]), /* blob_versioned_hashes is
+                                                                     * none */
+        (
+            BeginVector,
+            BlobVersionedHash,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![25, 26],
+        ),
+        (BlobVersionedHash, EndVector, N_BYTES_WORD, vec![27]), // finished parsing blob hashes
+        (
+            BlobVersionedHash,
+            BlobVersionedHash,
+            N_BYTES_WORD,
+            vec![25, 26],
+        ), // keep parsing
+        (EndVector, SigV, 0, vec![28]),
+        (SigV, SigR, N_BYTES_U64, vec![29]),
+        (SigR, SigS, N_BYTES_WORD, vec![30]),
+        (SigS, EndObject, N_BYTES_WORD, vec![31]),
+        (EndObject, EndObject, 0, vec![32]),
+        // used to exit TxGasCostInL1
+        (EndObject, BeginObject, 0, vec![]),
+    ];
+
+    rows.into_iter()
+        .map(|row| (row.0, row.1, row.2, TxHashEip4844, row.3).into())
+        .collect()
+}
+
+/// A branch node is a list of 16 child references followed by the value
+/// slot, which is always empty in the secure tries used by Ethereum. Its
+/// items are indexed by `access_list_idx` (1 to 17) in the RLP table.
+pub fn mpt_branch_rom_table_rows() -> Vec<RomTableRow> {
+    let rows = vec![
+        (BeginVector, MptChild, MAX_TAG_LENGTH_OF_LIST, vec![1, 2]),
+        (MptChild, EndVector, N_BYTES_WORD, vec![3]), // finished parsing children
+        (MptChild, MptChild, N_BYTES_WORD, vec![1, 2]), // keep parsing children
+        (EndVector, EndObject, 0, vec![4]),
+        // used to emit TxGasCostInL1
+        (EndObject, BeginObject, 0, vec![]),
+    ];
+
+    rows.into_iter()
+        .map(|row| (row.0, row.1, row.2, MptBranch, row.3).into())
+        .collect()
+}
+
+/// An extension node is a list of its hex-prefix encoded path and the
+/// reference to its child, the child having `access_list_idx` 1 as in a branch.
+pub fn mpt_extension_rom_table_rows() -> Vec<RomTableRow> {
+    let rows = vec![
+        (BeginVector, MptPath, MAX_TAG_LENGTH_OF_LIST, vec![1]),
+        (MptPath, MptChild, N_BYTES_WORD + 1, vec![2]),
+        (MptChild, EndVector, N_BYTES_WORD, vec![3]),
+        (EndVector, EndObject, 0, vec![4]),
+        // used to emit TxGasCostInL1
+        (EndObject, BeginObject, 0, vec![]),
+    ];
+
+    rows.into_iter()
+        .map(|row| (row.0, row.1, row.2, MptExtension, row.3).into())
+        .collect()
+}
+
+/// A leaf node is a list of its hex-prefix encoded path and its value.
+pub fn mpt_leaf_rom_table_rows() -> Vec<RomTableRow> {
+    let rows = vec![
+        (BeginObject, MptPath, MAX_TAG_LENGTH_OF_LIST, vec![1]),
+        (MptPath, Data, N_BYTES_WORD + 1, vec![2]),
+        (Data, EndObject, N_BYTES_CALLDATA, vec![3]),
+        (EndObject, EndObject, 0, vec![4]),
+        // used to emit TxGasCostInL1
+        (EndObject, BeginObject, 0, vec![]),
+    ];
+
+    rows.into_iter()
+        .map(|row| (row.0, row.1, row.2, MptLeaf, row.3).into())
+        .collect()
+}
+
+/// An account in the state trie is the list [nonce, balance, storage_root,
+/// code_hash].
+pub fn mpt_account_rom_table_rows() -> Vec<RomTableRow> {
+    let rows = vec![
+        (BeginObject, Nonce, MAX_TAG_LENGTH_OF_LIST, vec![1]),
+        (Nonce, TxValue, N_BYTES_U64, vec![2]),
+        (TxValue, StorageRoot, N_BYTES_WORD, vec![3]),
+        (StorageRoot, CodeHash, N_BYTES_WORD, vec![4]),
+        (CodeHash, EndObject, N_BYTES_WORD, vec![5]),
+        (EndObject, EndObject, 0, vec![6]),
+        // used to emit TxGasCostInL1
+        (EndObject, BeginObject, 0, vec![]),
+    ];
+
+    rows.into_iter()
+        .map(|row| (row.0, row.1, row.2, MptAccount, row.3).into())
+        .collect()
+}
+
+/// A receipt is the list [status, cumulative_gas_used, bloom, logs], where
+/// each log is the list [address, topics, data]. The logs are at depth 2 as
+/// the entries of an access list, so that the address of a log has the index
+/// of the log as `access_list_idx` and its topics are indexed by
+/// `storage_key_idx`. The receipt of a typed tx is prefixed by the tx type.
+pub fn receipt_rom_table_rows(is_typed: bool) -> Vec<RomTableRow> {
+    let (format, shift) = if is_typed {
+        (TypedReceipt, 1)
+    } else {
+        (Receipt, 0)
+    };
+    let rows = vec![
+        (BeginObject, Nonce, MAX_TAG_LENGTH_OF_LIST, vec![1]),
+        (Nonce, Gas, 1, vec![2]), // status
+        (Gas, Data, N_BYTES_U64, vec![3]),
+        (Data, BeginVector, N_BYTES_BLOOM, vec![4, 5]), // bloom
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![16]), // logs is none
+        (BeginVector, BeginObject, MAX_TAG_LENGTH_OF_LIST, vec![6]),
+        (
+            BeginObject,
+            AccessListAddress,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![7],
+        ),
+        (
+            AccessListAddress,
+            BeginVector,
+            N_BYTES_ACCOUNT_ADDRESS,
+            vec![8, 9],
+        ),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![12]), // topics is none
+        (
+            BeginVector,
+            AccessListStorageKey,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![10, 11],
+        ),
+        (AccessListStorageKey, EndVector, N_BYTES_WORD, vec![12]), // finished parsing topics
+        (
+            AccessListStorageKey,
+            AccessListStorageKey,
+            N_BYTES_WORD,
+            vec![10, 11],
+        ), // keep parsing topics
+        (EndVector, Data, 0, vec![13]),
+        (Data, EndObject, N_BYTES_CALLDATA, vec![14, 15]), // log data
+        (EndObject, EndVector, 0, vec![16]),               // finished parsing logs
+        (EndObject, BeginObject, 0, vec![6]),              // parse another log
+        (EndVector, EndObject, 0, vec![17]),
+        (EndObject, EndObject, 0, vec![18]),
+        // used to emit TxGasCostInL1
+        (EndObject, BeginObject, 0, vec![]),
+    ];
+
+    is_typed
+        .then_some((TxType, BeginObject, 1, vec![0]))
+        .into_iter()
+        .chain(rows)
+        .map(|row| {
+            let next_idx = row.3.into_iter().map(|idx| idx + shift).collect();
+            (row.0, row.1, row.2, format, next_idx).into()
+        })
+        .collect()
+}
+
 /// Read-only Memory table row.
 #[derive(Debug, Clone)]
 pub struct RomTableRow {
@@ -602,6 +915,22 @@ pub enum Format {
     TxHashEip2930,
     /// L1 Msg
     L1MsgHash,
+    /// Sign for EIP4844 tx
+    TxSignEip4844,
+    /// Hash for EIP4844 tx
+    TxHashEip4844,
+    /// Branch node of a Merkle-Patricia trie
+    MptBranch,
+    /// Extension node of a Merkle-Patricia trie
+    MptExtension,
+    /// Leaf node of a Merkle-Patricia trie
+    MptLeaf,
+    /// Account stored in the leaves of the state trie
+    MptAccount,
+    /// Receipt of a legacy tx
+    Receipt,
+    /// Receipt of a typed tx
+    TypedReceipt,
 }
 
 impl From<Format> for usize {
@@ -628,6 +957,14 @@ impl Format {
             TxSignEip2930 => eip2930_tx_sign_rom_table_rows(),
             TxHashEip2930 => eip2930_tx_hash_rom_table_rows(),
             Self::L1MsgHash => l1_msg::rom_table_rows(),
+            TxSignEip4844 => eip4844_tx_sign_rom_table_rows(),
+            TxHashEip4844 => eip4844_tx_hash_rom_table_rows(),
+            MptBranch => mpt_branch_rom_table_rows(),
+            MptExtension => mpt_extension_rom_table_rows(),
+            MptLeaf => mpt_leaf_rom_table_rows(),
+            MptAccount => mpt_account_rom_table_rows(),
+            Receipt => receipt_rom_table_rows(false),
+            TypedReceipt => receipt_rom_table_rows(true),
         }
     }
 }
@@ -941,3 +1278,667 @@ pub(crate) struct SmState<F: Field> {
     pub(crate) tag_value_acc: Value<F>,
     pub(crate) tag_bytes_rlc: Value<F>,
 }
+
+/// Number of rows of the witness generated by [`gen_rlp_fsm_witness`] for `rlp_bytes`: a row
+/// for each byte, a row for the end of each list, and a row for the end of the object at
+/// depth 0.
+pub(crate) fn rlp_fsm_num_rows(rlp_bytes: &[u8]) -> usize {
+    let mut num_lists = 0;
+    let mut byte_idx = 0;
+    while byte_idx < rlp_bytes.len() {
+        let byte_value = rlp_bytes[byte_idx];
+        byte_idx += match byte_value {
+            0x00..=0x80 => 1,
+            0x81..=0xb7 => 1 + usize::from(byte_value - 0x80),
+            0xb8..=0xbf => {
+                let len_of_len = usize::from(byte_value - 0xb7);
+                let len = rlp_bytes[byte_idx + 1..]
+                    .iter()
+                    .take(len_of_len)
+                    .fold(0, |len, &byte| len * 256 + usize::from(byte));
+                1 + len_of_len + len
+            }
+            // the items of a list follow its header
+            0xc0..=0xf7 => {
+                num_lists += 1;
+                1
+            }
+            0xf8..=0xff => {
+                num_lists += 1;
+                1 + usize::from(byte_value - 0xf7)
+            }
+        };
+    }
+    rlp_bytes.len() + num_lists + 1
+}
+
+/// Generate the witness of the RLP state machine decoding `rlp_bytes` as the
+/// instance `(tx_id, format)` of the RLP table. Instances other than
+/// transactions, like trie nodes, use their own id in place of the tx id.
+pub(crate) fn gen_rlp_fsm_witness<F: Field>(
+    tx_id: u64,
+    format: Format,
+    rlp_bytes: &[u8],
+    challenges: &Challenges<Value<F>>,
+) -> Vec<RlpFsmWitnessRow<F>> {
+    let mut witness = vec![];
+    let rom_table = format.rom_table_rows();
+    let keccak_rand = challenges.keccak_input();
+    let word_rand = challenges.evm_word();
+    let rlp_bytes_rlc = rlp_bytes
+        .iter()
+        .scan(Value::known(F::zero()), |rlc, &byte| {
+            *rlc = *rlc * keccak_rand + Value::known(F::from(byte as u64));
+
+            Some(*rlc)
+        })
+        .collect::<Vec<_>>();
+    let rlp_gas_cost_acc = rlp_bytes
+        .iter()
+        .scan(Value::known(F::zero()), |acc, &byte| {
+            let cost = if byte == 0 { 4 } else { 16 };
+            *acc = *acc + Value::known(F::from(cost));
+
+            Some(*acc)
+        })
+        .collect::<Vec<_>>();
+    let mut cur = SmState {
+        tag: rom_table[0].tag,
+        state: DecodeTagStart,
+        tag_idx: 0,
+        tag_length: 0,
+        tag_value_acc: Value::known(F::zero()),
+        tag_bytes_rlc: Value::known(F::zero()),
+        byte_idx: 0,
+        depth: 0,
+    };
+
+    // Queue up stack operations
+    let mut stack_ops: Vec<RlpStackOp<F>> = vec![];
+    // This variable tracks how many bytes were left on a depth level before the last UPDATE.
+    // Unlike the remaining_bytes variable, it tracks the byte count before the UPDATE
+    // operation took place. This is a utility variable for correctly filling the
+    // value_prev field in a POP record.
+    let mut prev_bytes_on_depth: [usize; 4] = [0, 0, 0, 0];
+
+    // When we are decoding a vector of element type `t`, at the beginning
+    // we actually do not know the next tag is `EndVector` or not. After we
+    // parsed the current tag, if the remaining bytes to decode in this layer
+    // is zero, then the next tag is `EndVector`.
+    let mut cur_rom_row = vec![0];
+    let mut remaining_bytes = vec![rlp_bytes.len()];
+    // initialize stack
+    stack_ops.push(RlpStackOp::init(
+        tx_id,
+        format,
+        rlp_bytes.len(),
+        keccak_rand,
+    ));
+    let mut witness_table_idx = 0;
+
+    // This map keeps track
+    // - the last row in the witness table of each parsed tag,
+    // - the row in the rom table of each parsed tag.
+    // And this map is used to fill the tag_next column in the witness table
+    let mut tag_rom_row_map = BTreeMap::new();
+    let mut is_output;
+    let mut is_none;
+    let mut rlp_tag;
+    let mut lb_len = 0;
+    // These two variables keep track
+    // unique identifier of addresses and storage keys included in access list
+    let mut access_list_idx: u64 = 0;
+    let mut storage_key_idx: u64 = 0;
+
+    loop {
+        // default behavior
+        is_none = false;
+        is_output = false;
+        rlp_tag = RlpTag::Tag(cur.tag);
+
+        let mut next = cur.clone();
+        match cur.state {
+            DecodeTagStart => {
+                if cur.tag.is_end() {
+                    // assertions
+                    assert_eq!(
+                        remaining_bytes
+                            .pop()
+                            .expect("remaining_bytes shall not be empty"),
+                        0
+                    );
+
+                    if cur.depth == 1 {
+                        assert_eq!(remaining_bytes.len(), 1);
+                        assert_eq!(remaining_bytes[0], 0);
+                        assert_eq!(cur.byte_idx, rlp_bytes.len() - 1);
+                        is_output = true;
+                        rlp_tag = RlpTag::RLC;
+                        // end of the children of a trie node
+                        access_list_idx = 0;
+                    } else if cur.depth == 4 {
+                        // end of storage keys list
+                        // note: depth alone currently is sufficient to ascertain
+                        // the end of a storage keys list as there's no other nested
+                        // structure at depth 4 specified in EIP standards
+                        storage_key_idx = 0;
+                    } else if cur.depth == 2 {
+                        // end of access list
+                        // note: depth alone currently is sufficient to ascertain
+                        // the end of an access list as there's no other nested
+                        // structure at depth 2 specified in EIP standards
+                        access_list_idx = 0;
+                    } else if cur.depth == 0 {
+                        // emit GasCost
+                        is_output = true;
+                        rlp_tag = RlpTag::GasCost;
+                    }
+
+                    if !remaining_bytes.is_empty() {
+                        let byte_remained = *remaining_bytes.last().unwrap();
+
+                        stack_ops.push(RlpStackOp::pop(
+                            tx_id,
+                            format,
+                            cur.byte_idx + 1,
+                            cur.depth - 1,
+                            byte_remained,
+                            prev_bytes_on_depth[cur.depth - 1],
+                            access_list_idx,
+                            storage_key_idx,
+                            keccak_rand,
+                        ));
+                    }
+
+                    // state transitions
+                    // if cur.depth == 0 then we are at the end of decoding
+                    if cur.depth > 0 {
+                        next.depth = cur.depth - 1;
+                    }
+                    next.state = DecodeTagStart;
+                } else {
+                    let byte_value = rlp_bytes[cur.byte_idx];
+
+                    // the children of a trie node are indexed by their position,
+                    // empty ones included
+                    if cur.tag.is_mpt_child() {
+                        access_list_idx += 1;
+                    }
+                    if byte_value > 0x80 && byte_value < 0xb8 {
+                        // detect start of access list address, the blob versioned
+                        // hashes share the index as they are also listed at depth 2
+                        if cur.tag.is_access_list_address() || cur.tag.is_blob_versioned_hash() {
+                            access_list_idx += 1;
+                        }
+                        // detect start of access list storage key
+                        if cur.tag.is_access_list_storage_key() {
+                            storage_key_idx += 1;
+                        }
+                    }
+
+                    if let Some(rem) = remaining_bytes.last_mut() {
+                        // read one more byte
+                        assert!(*rem >= 1);
+
+                        if !(0xc0..=0xf7).contains(&byte_value) || cur.tag.is_mpt_child() {
+                            // Note: if the byte_value is in the range [0xc0..=0xf7],
+                            // then we anticipate a PUSH onto a higher depth, unless it is
+                            // a node embedded in a trie node, which is read as bytes.
+
+                            // add stack op on same depth
+                            stack_ops.push(RlpStackOp::update(
+                                tx_id,
+                                format,
+                                cur.byte_idx + 1,
+                                cur.depth,
+                                *rem - 1,
+                                stack_al_idx(&cur.tag, access_list_idx),
+                                storage_key_idx,
+                                keccak_rand,
+                            ));
+                        }
+
+                        *rem -= 1;
+                    }
+
+                    if byte_value < 0x80 {
+                        // assertions
+                        assert!(!cur.tag.is_list());
+                        is_output = true;
+                        cur.tag_value_acc = Value::known(F::from(byte_value as u64));
+                        cur.tag_bytes_rlc = cur.tag_value_acc;
+                        cur.tag_length = 1;
+
+                        // state transitions
+                        next.state = DecodeTagStart;
+                    } else if byte_value == 0x80 {
+                        // assertions
+                        assert!(!cur.tag.is_list());
+                        is_output = true;
+                        is_none = true;
+                        cur.tag_value_acc = Value::known(F::zero());
+                        cur.tag_bytes_rlc = cur.tag_value_acc;
+                        cur.tag_length = 0;
+
+                        // state transitions
+                        next.state = DecodeTagStart;
+                    } else if byte_value < 0xb8 {
+                        // assertions
+                        assert!(!cur.tag.is_list());
+
+                        // state transitions
+                        next.tag_idx = 1;
+                        next.tag_length = (byte_value - 0x80) as usize;
+                        next.tag_value_acc =
+                            Value::known(F::from(rlp_bytes[cur.byte_idx + 1] as u64));
+                        next.tag_bytes_rlc = next.tag_value_acc;
+                        next.state = State::Bytes;
+                    } else if byte_value < 0xc0 {
+                        // assertions
+                        assert!(!cur.tag.is_list());
+
+                        // state transitions
+                        next.tag_idx = 1;
+                        next.tag_length = (byte_value - 0xb7) as usize;
+                        lb_len = rlp_bytes[cur.byte_idx + 1] as usize;
+                        next.tag_value_acc = Value::known(F::from(lb_len as u64));
+                        next.state = State::LongBytes;
+                    } else if cur.tag.is_mpt_child() && byte_value < 0xf8 {
+                        // a node shorter than 32 bytes embedded in its parent, the child is
+                        // the encoding of the node including its header.
+                        let byte_next = Value::known(F::from(rlp_bytes[cur.byte_idx + 1] as u64));
+
+                        // state transitions
+                        next.tag_idx = 2;
+                        next.tag_length = (byte_value - 0xc0) as usize + 1;
+                        next.tag_value_acc =
+                            word_rand * Value::known(F::from(byte_value as u64)) + byte_next;
+                        next.tag_bytes_rlc =
+                            keccak_rand * Value::known(F::from(byte_value as u64)) + byte_next;
+                        next.state = State::Bytes;
+                    } else if byte_value < 0xf8 {
+                        // assertions
+                        assert!(cur.tag.is_begin());
+                        if cur.depth == 0 {
+                            is_output = true;
+                            rlp_tag = RlpTag::Len;
+                        }
+                        cur.tag_value_acc = Value::known(F::from(u64::from(byte_value - 0xc0)));
+                        cur.tag_length = 1;
+
+                        // state transitions
+                        let num_bytes_of_new_list = usize::from(byte_value - 0xc0);
+                        if let Some(rem) = remaining_bytes.last_mut() {
+                            // Since we are going to decode a new list inside current list,
+                            // after that the remaining bytes of
+                            // current list should be subtracted by
+                            // the number of bytes of the new list.
+                            assert!(*rem >= num_bytes_of_new_list);
+                            prev_bytes_on_depth[cur.depth] = *rem + 1;
+
+                            *rem -= num_bytes_of_new_list;
+                        }
+                        remaining_bytes.push(num_bytes_of_new_list);
+
+                        let al_inc: u64 = if cur.depth == 2 { 1 } else { 0 };
+                        let sk_inc: u64 = if cur.depth == 3 { 1 } else { 0 };
+
+                        stack_ops.push(RlpStackOp::push(
+                            tx_id,
+                            format,
+                            cur.byte_idx + 1,
+                            cur.depth + 1,
+                            num_bytes_of_new_list,
+                            access_list_idx + al_inc,
+                            storage_key_idx + sk_inc,
+                            keccak_rand,
+                        ));
+
+                        next.depth = cur.depth + 1;
+                        next.state = DecodeTagStart;
+                    } else {
+                        // assertions
+                        assert!(cur.tag.is_begin());
+                        // TODO: assert first leading byte is non-zero
+
+                        // state transitions
+                        next.tag_idx = 1;
+                        next.tag_length = (byte_value - 0xf7) as usize;
+                        lb_len = rlp_bytes[cur.byte_idx + 1] as usize;
+                        next.tag_value_acc = Value::known(F::from(lb_len as u64));
+                        next.state = State::LongList;
+                    }
+                }
+            }
+            State::Bytes => {
+                if let Some(rem) = remaining_bytes.last_mut() {
+                    assert!(*rem >= 1);
+
+                    let sk_inc = if cur.depth == 4 && cur.tag_idx >= cur.tag_length && *rem - 1 > 0
+                    {
+                        1
+                    } else {
+                        0
+                    };
+
+                    // add stack op on same depth
+                    stack_ops.push(RlpStackOp::update(
+                        tx_id,
+                        format,
+                        cur.byte_idx + 1,
+                        cur.depth,
+                        *rem - 1,
+                        stack_al_idx(&cur.tag, access_list_idx),
+                        storage_key_idx + sk_inc,
+                        keccak_rand,
+                    ));
+
+                    *rem -= 1;
+                }
+                if cur.tag_idx < cur.tag_length {
+                    // state transitions
+                    let max_length = rom_table[cur_rom_row[0]].max_length;
+                    let b = match max_length.cmp(&32) {
+                        Ordering::Less => Value::known(F::from(256_u64)),
+                        Ordering::Equal => word_rand,
+                        Ordering::Greater => keccak_rand,
+                    };
+                    next.tag_idx = cur.tag_idx + 1;
+                    next.tag_value_acc = cur.tag_value_acc * b
+                        + Value::known(F::from(rlp_bytes[cur.byte_idx + 1] as u64));
+                    next.tag_bytes_rlc = cur.tag_bytes_rlc * keccak_rand
+                        + Value::known(F::from(rlp_bytes[cur.byte_idx + 1] as u64));
+                } else {
+                    // assertions
+                    is_output = true;
+
+                    // state transitions
+                    next.state = DecodeTagStart;
+                }
+            }
+            State::LongBytes => {
+                if let Some(rem) = remaining_bytes.last_mut() {
+                    assert!(*rem >= 1);
+
+                    // add stack op on same depth
+                    stack_ops.push(RlpStackOp::update(
+                        tx_id,
+                        format,
+                        cur.byte_idx + 1,
+                        cur.depth,
+                        *rem - 1,
+                        access_list_idx,
+                        storage_key_idx,
+                        keccak_rand,
+                    ));
+
+                    *rem -= 1;
+                }
+
+                if cur.tag_idx < cur.tag_length {
+                    // state transitions
+                    next.tag_idx = cur.tag_idx + 1;
+                    lb_len = lb_len * 256 + usize::from(rlp_bytes[cur.byte_idx + 1]);
+                    next.tag_value_acc = Value::known(F::from(lb_len as u64));
+                } else {
+                    // we're dealing with case cur.tag_idx == cur.tag_length
+
+                    // state transitions
+                    next.tag_idx = 1;
+                    next.tag_length = lb_len;
+                    next.tag_value_acc =
+                        Value::known(F::from(u64::from(rlp_bytes[cur.byte_idx + 1])));
+                    next.tag_bytes_rlc = next.tag_value_acc;
+                    next.state = State::Bytes;
+                }
+            }
+            State::LongList => {
+                if let Some(rem) = remaining_bytes.last_mut() {
+                    // read one more byte
+                    assert!(*rem >= 1);
+
+                    // add stack op on same depth
+                    if cur.tag_idx < cur.tag_length {
+                        stack_ops.push(RlpStackOp::update(
+                            tx_id,
+                            format,
+                            cur.byte_idx + 1,
+                            cur.depth,
+                            *rem - 1,
+                            access_list_idx,
+                            storage_key_idx,
+                            keccak_rand,
+                        ));
+                    }
+
+                    *rem -= 1;
+                }
+                if cur.tag_idx < cur.tag_length {
+                    // state transitions
+                    next.tag_idx = cur.tag_idx + 1;
+                    lb_len = lb_len * 256 + usize::from(rlp_bytes[cur.byte_idx + 1]);
+                    next.tag_value_acc = Value::known(F::from(lb_len as u64));
+                } else {
+                    // assertions
+                    if cur.depth == 0 {
+                        is_output = true;
+                        rlp_tag = RlpTag::Len;
+                    }
+                    if let Some(rem) = remaining_bytes.last_mut() {
+                        assert!(*rem >= lb_len);
+                        prev_bytes_on_depth[cur.depth] = *rem + 1;
+
+                        *rem -= lb_len;
+                    }
+                    remaining_bytes.push(lb_len);
+
+                    let al_inc: u64 = if cur.depth == 2 { 1 } else { 0 };
+                    let sk_inc: u64 = if cur.depth == 3 { 1 } else { 0 };
+
+                    stack_ops.push(RlpStackOp::push(
+                        tx_id,
+                        format,
+                        cur.byte_idx + 1,
+                        cur.depth + 1,
+                        lb_len,
+                        access_list_idx + al_inc,
+                        storage_key_idx + sk_inc,
+                        keccak_rand,
+                    ));
+                    next.depth = cur.depth + 1;
+                    next.state = DecodeTagStart;
+                }
+            }
+            State::End => {
+                unreachable!()
+            }
+        }
+
+        if next.state == DecodeTagStart {
+            // we finished parsing current tag
+            let row = if cur_rom_row.len() == 1 {
+                cur_rom_row[0]
+            } else if cur_rom_row.len() == 2 {
+                // only cur_rom_row[0].tag_next is EndVector.
+                assert_eq!(rom_table[cur_rom_row[0]].tag_next, EndVector);
+
+                let rem = remaining_bytes.last().expect("");
+                if *rem == 0 {
+                    // we have finished parsing the vector.
+                    cur_rom_row[0]
+                } else {
+                    // we have not finished parsing the vector.
+                    cur_rom_row[1]
+                }
+            } else {
+                unreachable!()
+            };
+
+            assert_eq!(cur.tag, rom_table[row].tag);
+
+            tag_rom_row_map.insert(witness_table_idx, row);
+            next.tag = rom_table[row].tag_next;
+            cur_rom_row = rom_table[row].tag_next_idx.clone();
+
+            if next.tag.is_end() {
+                // Since the EndObject or EndVector tag does not read any byte from the data
+                // table.
+                next.byte_idx = cur.byte_idx;
+            } else {
+                next.byte_idx = cur.byte_idx + 1;
+            }
+        } else {
+            // next.state is one of { Bytes, LongBytes, LongList }
+            // the sm in these states need to read new byte from data table
+            next.byte_idx = cur.byte_idx + 1;
+        }
+
+        assert!(cur.byte_idx < rlp_bytes.len());
+        let (byte_value, bytes_rlc) = (rlp_bytes[cur.byte_idx], rlp_bytes_rlc[cur.byte_idx]);
+        let gas_cost_acc = rlp_gas_cost_acc[cur.byte_idx];
+
+        let tag_value = match rlp_tag {
+            RlpTag::Len => cur.tag_value_acc + Value::known(F::from((cur.byte_idx + 1) as u64)),
+            RlpTag::RLC => bytes_rlc,
+            RlpTag::GasCost => gas_cost_acc,
+            RlpTag::Tag(_) => cur.tag_value_acc,
+            RlpTag::Null => unreachable!("Null is not used"),
+        };
+        let (tag_bytes_rlc, tag_length) = match rlp_tag {
+            // Len | RLC | GasCost are just meta-info extracted from keccak input bytes
+            RlpTag::Len => (Value::known(F::zero()), cur.tag_length),
+            RlpTag::RLC | RlpTag::GasCost => (Value::known(F::zero()), 0),
+            RlpTag::Tag(_) => (cur.tag_bytes_rlc, cur.tag_length),
+            RlpTag::Null => unreachable!("Null is not used"),
+        };
+
+        witness.push(RlpFsmWitnessRow {
+            rlp_table: RlpTable {
+                tx_id,
+                format,
+                rlp_tag,
+                tag_value,
+                tag_bytes_rlc,
+                tag_length,
+                is_output,
+                is_none,
+                access_list_idx,
+                storage_key_idx,
+            },
+            state_machine: StateMachine {
+                state: cur.state,
+                tag: cur.tag,
+                max_length: Default::default(), // will be filled up later
+                tag_next: Default::default(),   // will be filled up later
+                byte_idx: cur.byte_idx + 1,
+                byte_rev_idx: rlp_bytes.len() - cur.byte_idx,
+                byte_value,
+                tag_idx: cur.tag_idx,
+                tag_acc_value: cur.tag_value_acc,
+                depth: cur.depth,
+                bytes_rlc,
+                gas_cost_acc,
+            },
+            // The stack operations will be later sorted and assigned
+            rlp_decoding_table: RlpStackOp::default(),
+        });
+
+        witness_table_idx += 1;
+
+        if cur.tag == EndObject && cur.depth == 0 {
+            break;
+        }
+        cur = next;
+    }
+
+    assert_eq!(
+        stack_ops.len(),
+        witness.len(),
+        "Number of stack_ops must be equal to witness length"
+    );
+
+    // Sort the RlpStackOps and assign to the RlpDecodingTable part of witness
+    stack_ops.sort_by(|a, b| {
+        if (
+            a.tx_id,
+            a.format as u64,
+            a.depth,
+            a.al_idx,
+            a.sk_idx,
+            a.byte_idx,
+            a.stack_op.clone() as u64,
+        ) > (
+            b.tx_id,
+            b.format as u64,
+            b.depth,
+            a.al_idx,
+            a.sk_idx,
+            b.byte_idx,
+            b.stack_op.clone() as u64,
+        ) {
+            std::cmp::Ordering::Greater
+        } else {
+            std::cmp::Ordering::Less
+        }
+    });
+
+    for (idx, op) in stack_ops.into_iter().enumerate() {
+        witness[idx].rlp_decoding_table = op;
+    }
+
+    // filling up the `tag_next` col of the witness table
+    let mut idx = 0;
+    for (witness_idx, rom_table_row) in tag_rom_row_map {
+        while idx <= witness_idx {
+            witness[idx].state_machine.tag_next = rom_table[rom_table_row].tag_next;
+            witness[idx].state_machine.max_length = rom_table[rom_table_row].max_length;
+            idx += 1;
+        }
+    }
+
+    witness
+}
+
+/// Generate the data table rows holding the bytes of the RLP instance
+/// `(tx_id, format)`.
+pub(crate) fn gen_rlp_data_table<F: Field>(
+    tx_id: u64,
+    format: Format,
+    rlp_bytes: &[u8],
+    challenges: &Challenges<Value<F>>,
+) -> Vec<DataTable<F>> {
+    let r = challenges.keccak_input();
+    let n = rlp_bytes.len();
+    rlp_bytes
+        .iter()
+        .enumerate()
+        .scan(
+            (Value::known(F::zero()), Value::known(F::zero())),
+            |(rlc, gas_cost_acc), (i, &byte_value)| {
+                let byte_cost = if byte_value == 0 { 4 } else { 16 };
+                *rlc = *rlc * r + Value::known(F::from(byte_value as u64));
+                *gas_cost_acc = *gas_cost_acc + Value::known(F::from(byte_cost));
+                Some(DataTable {
+                    tx_id,
+                    format,
+                    byte_idx: i + 1,
+                    byte_rev_idx: n - i,
+                    byte_value,
+                    bytes_rlc: *rlc,
+                    gas_cost_acc: *gas_cost_acc,
+                })
+            },
+        )
+        .collect::<Vec<_>>()
+}
+
+/// The al_idx in the key of stack ops. The blob versioned hashes are listed directly
+/// at depth 2, so unlike the access list items, decoding them must not change the stack key.
+/// Likewise for the children of trie nodes, listed at depth 1.
+fn stack_al_idx(tag: &Tag, access_list_idx: u64) -> u64 {
+    if tag.is_blob_versioned_hash() || tag.is_mpt_child() {
+        0
+    } else {
+        access_list_idx
+    }
+}
@@ -10,6 +10,7 @@ use eth_types::{Address, Field, ToLittleEndian, ToScalar, Word, U256};
 use halo2_proofs::{circuit::Value, halo2curves::bn256::Fr};
 use itertools::Itertools;
 use rayon::prelude::{ParallelBridge, ParallelIterator};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     evm_circuit::util::rlc,
@@ -23,7 +24,7 @@ const ERR_MSG_FIRST: &str = "first access reads don't change value";
 const ERR_MSG_NON_FIRST: &str = "non-first access reads don't change value";
 
 /// Rw constainer for a witness block
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct RwMap(pub HashMap<RwTableTag, Vec<Rw>>);
 
 impl std::ops::Index<(RwTableTag, usize)> for RwMap {
@@ -221,7 +222,7 @@ pub type RwKey = (u64, usize, Address, u64, Word);
 
 /// Read-write records in execution. Rws are used for connecting evm circuit and
 /// state circuits.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Rw {
     /// Start
     Start { rw_counter: usize },
@@ -244,6 +245,24 @@ pub enum Rw {
         is_warm: bool,
         is_warm_prev: bool,
     },
+    /// TxCreatedAccount
+    TxCreatedAccount {
+        rw_counter: usize,
+        is_write: bool,
+        tx_id: usize,
+        account_address: Address,
+        is_created: bool,
+        is_created_prev: bool,
+    },
+    /// TxDestructedAccount
+    TxDestructedAccount {
+        rw_counter: usize,
+        is_write: bool,
+        tx_id: usize,
+        account_address: Address,
+        is_destructed: bool,
+        is_destructed_prev: bool,
+    },
     /// TxRefund
     TxRefund {
         rw_counter: usize,
@@ -272,6 +291,16 @@ pub enum Rw {
         tx_id: usize,
         committed_value: Word,
     },
+    /// TransientStorage
+    TransientStorage {
+        rw_counter: usize,
+        is_write: bool,
+        tx_id: usize,
+        account_address: Address,
+        storage_key: Word,
+        value: Word,
+        value_prev: Word,
+    },
     /// CallContext
     CallContext {
         rw_counter: usize,
@@ -383,6 +412,28 @@ impl Rw {
         }
     }
 
+    pub fn tx_created_account_value_pair(&self) -> (bool, bool) {
+        match self {
+            Self::TxCreatedAccount {
+                is_created,
+                is_created_prev,
+                ..
+            } => (*is_created, *is_created_prev),
+            _ => unreachable!("{:?}", self),
+        }
+    }
+
+    pub fn tx_destructed_account_value_pair(&self) -> (bool, bool) {
+        match self {
+            Self::TxDestructedAccount {
+                is_destructed,
+                is_destructed_prev,
+                ..
+            } => (*is_destructed, *is_destructed_prev),
+            _ => unreachable!("{:?}", self),
+        }
+    }
+
     pub fn tx_refund_value_pair(&self) -> (u64, u64) {
         match self {
             Self::TxRefund {
@@ -456,6 +507,15 @@ impl Rw {
         }
     }
 
+    pub fn transient_storage_value_pair(&self) -> (Word, Word) {
+        match self {
+            Self::TransientStorage {
+                value, value_prev, ..
+            } => (*value, *value_prev),
+            _ => unreachable!("{:?}", self),
+        }
+    }
+
     pub fn call_context_value(&self) -> Word {
         match self {
             Self::CallContext { value, .. } => *value,
@@ -549,8 +609,11 @@ impl Rw {
             | Self::Memory { rw_counter, .. }
             | Self::Stack { rw_counter, .. }
             | Self::AccountStorage { rw_counter, .. }
+            | Self::TransientStorage { rw_counter, .. }
             | Self::TxAccessListAccount { rw_counter, .. }
             | Self::TxAccessListAccountStorage { rw_counter, .. }
+            | Self::TxCreatedAccount { rw_counter, .. }
+            | Self::TxDestructedAccount { rw_counter, .. }
             | Self::TxRefund { rw_counter, .. }
             | Self::Account { rw_counter, .. }
             | Self::CallContext { rw_counter, .. }
@@ -565,8 +628,11 @@ impl Rw {
             Self::Memory { is_write, .. }
             | Self::Stack { is_write, .. }
             | Self::AccountStorage { is_write, .. }
+            | Self::TransientStorage { is_write, .. }
             | Self::TxAccessListAccount { is_write, .. }
             | Self::TxAccessListAccountStorage { is_write, .. }
+            | Self::TxCreatedAccount { is_write, .. }
+            | Self::TxDestructedAccount { is_write, .. }
             | Self::TxRefund { is_write, .. }
             | Self::Account { is_write, .. }
             | Self::CallContext { is_write, .. }
@@ -581,8 +647,11 @@ impl Rw {
             Self::Memory { .. } => RwTableTag::Memory,
             Self::Stack { .. } => RwTableTag::Stack,
             Self::AccountStorage { .. } => RwTableTag::AccountStorage,
+            Self::TransientStorage { .. } => RwTableTag::TransientStorage,
             Self::TxAccessListAccount { .. } => RwTableTag::TxAccessListAccount,
             Self::TxAccessListAccountStorage { .. } => RwTableTag::TxAccessListAccountStorage,
+            Self::TxCreatedAccount { .. } => RwTableTag::TxCreatedAccount,
+            Self::TxDestructedAccount { .. } => RwTableTag::TxDestructedAccount,
             Self::TxRefund { .. } => RwTableTag::TxRefund,
             Self::Account { .. } => RwTableTag::Account,
             Self::CallContext { .. } => RwTableTag::CallContext,
@@ -595,8 +664,11 @@ impl Rw {
     pub fn id(&self) -> Option<usize> {
         match self {
             Self::AccountStorage { tx_id, .. }
+            | Self::TransientStorage { tx_id, .. }
             | Self::TxAccessListAccount { tx_id, .. }
             | Self::TxAccessListAccountStorage { tx_id, .. }
+            | Self::TxCreatedAccount { tx_id, .. }
+            | Self::TxDestructedAccount { tx_id, .. }
             | Self::TxRefund { tx_id, .. }
             | Self::TxLog { tx_id, .. }
             | Self::TxReceipt { tx_id, .. } => Some(*tx_id),
@@ -616,11 +688,20 @@ impl Rw {
             | Self::TxAccessListAccountStorage {
                 account_address, ..
             }
+            | Self::TxCreatedAccount {
+                account_address, ..
+            }
+            | Self::TxDestructedAccount {
+                account_address, ..
+            }
             | Self::Account {
                 account_address, ..
             }
             | Self::AccountStorage {
                 account_address, ..
+            }
+            | Self::TransientStorage {
+                account_address, ..
             } => Some(*account_address),
             Self::Memory { memory_address, .. } => Some(Address::from_low_u64_be(*memory_address)),
             Self::Stack { stack_pointer, .. } => {
@@ -656,6 +737,9 @@ impl Rw {
             | Self::Stack { .. }
             | Self::TxAccessListAccount { .. }
             | Self::TxAccessListAccountStorage { .. }
+            | Self::TxCreatedAccount { .. }
+            | Self::TxDestructedAccount { .. }
+            | Self::TransientStorage { .. }
             | Self::TxRefund { .. }
             | Self::TxLog { .. } => None,
         }
@@ -665,7 +749,8 @@ impl Rw {
     pub fn storage_key(&self) -> Option<Word> {
         match self {
             Self::AccountStorage { storage_key, .. }
-            | Self::TxAccessListAccountStorage { storage_key, .. } => Some(*storage_key),
+            | Self::TxAccessListAccountStorage { storage_key, .. }
+            | Self::TransientStorage { storage_key, .. } => Some(*storage_key),
             Self::Start { .. }
             | Self::CallContext { .. }
             | Self::Stack { .. }
@@ -673,6 +758,8 @@ impl Rw {
             | Self::TxRefund { .. }
             | Self::Account { .. }
             | Self::TxAccessListAccount { .. }
+            | Self::TxCreatedAccount { .. }
+            | Self::TxDestructedAccount { .. }
             | Self::TxLog { .. }
             | Self::TxReceipt { .. } => None,
         }
@@ -715,9 +802,9 @@ impl Rw {
                 | AccountFieldTag::NonExisting
                 | AccountFieldTag::CodeSize => value.to_scalar().unwrap(),
             },
-            Self::AccountStorage { value, .. } | Self::Stack { value, .. } => {
-                rlc::value(&value.to_le_bytes(), randomness)
-            }
+            Self::AccountStorage { value, .. }
+            | Self::TransientStorage { value, .. }
+            | Self::Stack { value, .. } => rlc::value(&value.to_le_bytes(), randomness),
 
             Self::TxLog {
                 field_tag, value, ..
@@ -729,6 +816,8 @@ impl Rw {
 
             Self::TxAccessListAccount { is_warm, .. }
             | Self::TxAccessListAccountStorage { is_warm, .. } => F::from(*is_warm as u64),
+            Self::TxCreatedAccount { is_created, .. } => F::from(*is_created as u64),
+            Self::TxDestructedAccount { is_destructed, .. } => F::from(*is_destructed as u64),
             Self::Memory { value, .. } => rlc::value(&value.to_le_bytes(), randomness),
             Self::TxRefund { value, .. } | Self::TxReceipt { value, .. } => F::from(*value),
         }
@@ -740,11 +829,14 @@ impl Rw {
             Self::CallContext { value, .. } => *value,
             Self::Account { value, .. }
             | Self::AccountStorage { value, .. }
+            | Self::TransientStorage { value, .. }
             | Self::Stack { value, .. }
             | Self::Memory { value, .. }
             | Self::TxLog { value, .. } => *value,
             Self::TxAccessListAccount { is_warm, .. }
             | Self::TxAccessListAccountStorage { is_warm, .. } => U256::from(*is_warm as u64),
+            Self::TxCreatedAccount { is_created, .. } => U256::from(*is_created as u64),
+            Self::TxDestructedAccount { is_destructed, .. } => U256::from(*is_destructed as u64),
             Self::TxRefund { value, .. } | Self::TxReceipt { value, .. } => U256::from(*value),
         }
     }
@@ -770,7 +862,7 @@ impl Rw {
                 | AccountFieldTag::NonExisting
                 | AccountFieldTag::CodeSize => value_prev.to_scalar().unwrap(),
             }),
-            Self::AccountStorage { value_prev, .. } => {
+            Self::AccountStorage { value_prev, .. } | Self::TransientStorage { value_prev, .. } => {
                 Some(rlc::value(&value_prev.to_le_bytes(), randomness))
             }
             Self::Memory { value_prev, .. } => {
@@ -780,6 +872,12 @@ impl Rw {
             | Self::TxAccessListAccountStorage { is_warm_prev, .. } => {
                 Some(F::from(*is_warm_prev as u64))
             }
+            Self::TxCreatedAccount {
+                is_created_prev, ..
+            } => Some(F::from(*is_created_prev as u64)),
+            Self::TxDestructedAccount {
+                is_destructed_prev, ..
+            } => Some(F::from(*is_destructed_prev as u64)),
             Self::TxRefund { value_prev, .. } => Some(F::from(*value_prev)),
             Self::Start { .. }
             | Self::Stack { .. }
@@ -839,6 +937,36 @@ impl From<&operation::OperationContainer> for RwMap {
                 })
                 .collect(),
         );
+        rws.insert(
+            RwTableTag::TxCreatedAccount,
+            container
+                .tx_created_account
+                .iter()
+                .map(|op| Rw::TxCreatedAccount {
+                    rw_counter: op.rwc().into(),
+                    is_write: op.rw().is_write(),
+                    tx_id: op.op().tx_id,
+                    account_address: op.op().address,
+                    is_created: op.op().is_created,
+                    is_created_prev: op.op().is_created_prev,
+                })
+                .collect(),
+        );
+        rws.insert(
+            RwTableTag::TxDestructedAccount,
+            container
+                .tx_destructed_account
+                .iter()
+                .map(|op| Rw::TxDestructedAccount {
+                    rw_counter: op.rwc().into(),
+                    is_write: op.rw().is_write(),
+                    tx_id: op.op().tx_id,
+                    account_address: op.op().address,
+                    is_destructed: op.op().is_destructed,
+                    is_destructed_prev: op.op().is_destructed_prev,
+                })
+                .collect(),
+        );
         rws.insert(
             RwTableTag::TxAccessListAccountStorage,
             container
@@ -907,6 +1035,22 @@ impl From<&operation::OperationContainer> for RwMap {
                 })
                 .collect(),
         );
+        rws.insert(
+            RwTableTag::TransientStorage,
+            container
+                .transient_storage
+                .iter()
+                .map(|op| Rw::TransientStorage {
+                    rw_counter: op.rwc().into(),
+                    is_write: op.rw().is_write(),
+                    tx_id: op.op().tx_id,
+                    account_address: op.op().address,
+                    storage_key: op.op().key,
+                    value: op.op().value,
+                    value_prev: op.op().value_prev,
+                })
+                .collect(),
+        );
         rws.insert(
             RwTableTag::CallContext,
             container
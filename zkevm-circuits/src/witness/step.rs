@@ -8,6 +8,7 @@ use bus_mapping::{
     operation,
     precompile::{PrecompileAuxData, PrecompileCalls},
 };
+use serde::{Deserialize, Serialize};
 
 use crate::{
     evm_circuit::{
@@ -18,7 +19,7 @@ use crate::{
 };
 
 /// Step executed in a transaction
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExecStep {
     /// The index in the Transaction calls
     pub call_index: usize,
@@ -47,6 +48,7 @@ pub struct ExecStep {
     /// The counter for log index within tx
     pub log_id: usize,
     /// The opcode corresponds to the step
+    #[serde(with = "opcode_opt")]
     pub opcode: Option<OpcodeId>,
     /// The block number in which this step exists.
     pub block_num: u64,
@@ -54,6 +56,26 @@ pub struct ExecStep {
     pub aux_data: Option<PrecompileAuxData>,
 }
 
+// `OpcodeId` deserializes from its mnemonic but serializes as an enum variant, so
+// store the opcode byte instead to keep the encoding symmetric.
+mod opcode_opt {
+    use bus_mapping::evm::OpcodeId;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        opcode: &Option<OpcodeId>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        opcode.map(|opcode| opcode.as_u8()).serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<OpcodeId>, D::Error> {
+        Ok(Option::<u8>::deserialize(deserializer)?.map(OpcodeId::from))
+    }
+}
+
 impl ExecStep {
     /// The memory size in word **before** this step
     pub fn memory_word_size(&self) -> u64 {
@@ -143,13 +165,6 @@ impl From<&circuit_input_builder::ExecStep> for ExecutionState {
                     return ExecutionState::LOG;
                 }
 
-                macro_rules! dummy {
-                    ($name:expr) => {{
-                        log::warn!("{:?} is implemented with DummyGadget", $name);
-                        $name
-                    }};
-                }
-
                 match op {
                     OpcodeId::ADD | OpcodeId::SUB => ExecutionState::ADD_SUB,
                     OpcodeId::ADDMOD => ExecutionState::ADDMOD,
@@ -199,6 +214,14 @@ impl From<&circuit_input_builder::ExecStep> for ExecutionState {
                     OpcodeId::SHL | OpcodeId::SHR => ExecutionState::SHL_SHR,
                     OpcodeId::SLOAD => ExecutionState::SLOAD,
                     OpcodeId::SSTORE => ExecutionState::SSTORE,
+                    #[cfg(feature = "cancun")]
+                    OpcodeId::TLOAD => ExecutionState::TLOAD,
+                    #[cfg(feature = "cancun")]
+                    OpcodeId::TSTORE => ExecutionState::TSTORE,
+                    #[cfg(feature = "cancun")]
+                    OpcodeId::MCOPY => ExecutionState::MCOPY,
+                    #[cfg(feature = "cancun")]
+                    OpcodeId::BLOBHASH => ExecutionState::BLOBHASH,
                     OpcodeId::CALLDATASIZE => ExecutionState::CALLDATASIZE,
                     OpcodeId::CALLDATACOPY => ExecutionState::CALLDATACOPY,
                     OpcodeId::CHAINID => ExecutionState::CHAINID,
@@ -217,8 +240,7 @@ impl From<&circuit_input_builder::ExecStep> for ExecutionState {
                     OpcodeId::RETURNDATACOPY => ExecutionState::RETURNDATACOPY,
                     OpcodeId::CREATE => ExecutionState::CREATE,
                     OpcodeId::CREATE2 => ExecutionState::CREATE2,
-                    // dummy ops
-                    OpcodeId::SELFDESTRUCT => dummy!(ExecutionState::SELFDESTRUCT),
+                    OpcodeId::SELFDESTRUCT => ExecutionState::SELFDESTRUCT,
                     _ => unimplemented!("unimplemented opcode {:?}", op),
                 }
             }
@@ -235,6 +257,7 @@ impl From<&circuit_input_builder::ExecStep> for ExecutionState {
             },
             circuit_input_builder::ExecState::BeginTx => ExecutionState::BeginTx,
             circuit_input_builder::ExecState::EndTx => ExecutionState::EndTx,
+            circuit_input_builder::ExecState::DestructAccount => ExecutionState::DestructAccount,
             circuit_input_builder::ExecState::EndBlock => ExecutionState::EndBlock,
         }
     }
@@ -260,6 +283,9 @@ pub(super) fn step_convert(step: &circuit_input_builder::ExecStep, block_num: u6
                     operation::Target::CallContext => RwTableTag::CallContext,
                     operation::Target::TxReceipt => RwTableTag::TxReceipt,
                     operation::Target::TxLog => RwTableTag::TxLog,
+                    operation::Target::TransientStorage => RwTableTag::TransientStorage,
+                    operation::Target::TxCreatedAccount => RwTableTag::TxCreatedAccount,
+                    operation::Target::TxDestructedAccount => RwTableTag::TxDestructedAccount,
                     operation::Target::Start => RwTableTag::Start,
                 };
                 (tag, x.as_usize())
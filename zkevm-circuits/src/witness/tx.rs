@@ -3,16 +3,13 @@ use crate::{
     table::TxContextFieldTag,
     util::{rlc_be_bytes, Challenges},
     witness::{
-        rlp_fsm::{RlpStackOp, SmState},
-        DataTable, Format,
+        rlp_fsm::{gen_rlp_data_table, gen_rlp_fsm_witness},
+        DataTable,
         Format::{
-            L1MsgHash, TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashPreEip155, TxSignEip155,
-            TxSignEip1559, TxSignEip2930, TxSignPreEip155,
+            L1MsgHash, TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashEip4844, TxHashPreEip155,
+            TxSignEip155, TxSignEip1559, TxSignEip2930, TxSignEip4844, TxSignPreEip155,
         },
-        RlpFsmWitnessGen, RlpFsmWitnessRow, RlpTable, RlpTag, State,
-        State::DecodeTagStart,
-        StateMachine,
-        Tag::{EndObject, EndVector},
+        RlpFsmWitnessGen, RlpFsmWitnessRow,
     },
 };
 use bus_mapping::circuit_input_builder::{self, get_dummy_tx_hash, TxL1Fee};
@@ -36,12 +33,12 @@ use halo2_proofs::{
 use mock::MockTransaction;
 use num::Integer;
 use num_bigint::BigUint;
-use std::{cmp::Ordering, collections::BTreeMap};
+use serde::{Deserialize, Serialize};
 
 use super::{step::step_convert, Call, ExecStep};
 
 /// Transaction in a witness block
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Transaction {
     /// The block number in which this tx is included in
     pub block_number: u64,
@@ -97,6 +94,10 @@ pub struct Transaction {
     pub l1_fee_committed: TxL1Fee,
     /// Optional access list for EIP-2930
     pub access_list: Option<AccessList>,
+    /// Max fee per blob gas (EIP-4844)
+    pub max_fee_per_blob_gas: Word,
+    /// Blob versioned hashes (EIP-4844)
+    pub blob_versioned_hashes: Vec<H256>,
     /// The calls made in the transaction
     pub calls: Vec<Call>,
     /// The steps executioned in the transaction
@@ -380,6 +381,22 @@ impl Transaction {
                 }),
                 Value::known(F::zero()),
             ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::MaxFeePerBlobGas as u64)),
+                Value::known(F::zero()),
+                challenges.evm_word().map(|challenge| {
+                    rlc::value(&self.max_fee_per_blob_gas.to_le_bytes(), challenge)
+                }),
+                Value::known(F::zero()),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::BlobVersionedHashesLen as u64)),
+                Value::known(F::zero()),
+                Value::known(F::from(self.blob_versioned_hashes.len() as u64)),
+                Value::known(F::zero()),
+            ],
             [
                 Value::known(F::from(self.id as u64)),
                 Value::known(F::from(TxContextFieldTag::BlockNumber as u64)),
@@ -444,6 +461,26 @@ impl Transaction {
         assignments
     }
 
+    /// Assignments for tx table blob versioned hashes
+    pub fn table_assignments_blob_hashes_dyn<F: Field>(
+        &self,
+        challenges: Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 5]> {
+        self.blob_versioned_hashes
+            .iter()
+            .enumerate()
+            .map(|(idx, blob_hash)| {
+                [
+                    Value::known(F::from(self.id as u64)),
+                    Value::known(F::from(TxContextFieldTag::BlobVersionedHash as u64)),
+                    Value::known(F::from(idx as u64)),
+                    rlc_be_bytes(&blob_hash.to_fixed_bytes(), challenges.evm_word()),
+                    Value::known(F::zero()),
+                ]
+            })
+            .collect()
+    }
+
     pub(crate) fn gen_rlp_witness<F: Field>(
         &self,
         is_hash: bool,
@@ -458,6 +495,7 @@ impl Transaction {
                     TxType::Eip1559 => TxHashEip1559,
                     TxType::L1Msg => L1MsgHash,
                     TxType::Eip2930 => TxHashEip2930,
+                    TxType::Eip4844 => TxHashEip4844,
                 },
             )
         } else {
@@ -468,566 +506,13 @@ impl Transaction {
                     TxType::PreEip155 => TxSignPreEip155,
                     TxType::Eip1559 => TxSignEip1559,
                     TxType::Eip2930 => TxSignEip2930,
+                    TxType::Eip4844 => TxSignEip4844,
                     _ => unreachable!("tx type {:?} not supported", self.tx_type),
                 },
             )
         };
 
-        let tx_id = self.id as u64;
-        let mut witness = vec![];
-        let rom_table = format.rom_table_rows();
-        let keccak_rand = challenges.keccak_input();
-        let word_rand = challenges.evm_word();
-        let rlp_bytes_rlc = rlp_bytes
-            .iter()
-            .scan(Value::known(F::zero()), |rlc, &byte| {
-                *rlc = *rlc * keccak_rand + Value::known(F::from(byte as u64));
-
-                Some(*rlc)
-            })
-            .collect::<Vec<_>>();
-        let rlp_gas_cost_acc = rlp_bytes
-            .iter()
-            .scan(Value::known(F::zero()), |acc, &byte| {
-                let cost = if byte == 0 { 4 } else { 16 };
-                *acc = *acc + Value::known(F::from(cost));
-
-                Some(*acc)
-            })
-            .collect::<Vec<_>>();
-        let mut cur = SmState {
-            tag: rom_table[0].tag,
-            state: DecodeTagStart,
-            tag_idx: 0,
-            tag_length: 0,
-            tag_value_acc: Value::known(F::zero()),
-            tag_bytes_rlc: Value::known(F::zero()),
-            byte_idx: 0,
-            depth: 0,
-        };
-
-        // Queue up stack operations
-        let mut stack_ops: Vec<RlpStackOp<F>> = vec![];
-        // This variable tracks how many bytes were left on a depth level before the last UPDATE.
-        // Unlike the remaining_bytes variable, it tracks the byte count before the UPDATE
-        // operation took place. This is a utility variable for correctly filling the
-        // value_prev field in a POP record.
-        let mut prev_bytes_on_depth: [usize; 4] = [0, 0, 0, 0];
-
-        // When we are decoding a vector of element type `t`, at the beginning
-        // we actually do not know the next tag is `EndVector` or not. After we
-        // parsed the current tag, if the remaining bytes to decode in this layer
-        // is zero, then the next tag is `EndVector`.
-        let mut cur_rom_row = vec![0];
-        let mut remaining_bytes = vec![rlp_bytes.len()];
-        // initialize stack
-        stack_ops.push(RlpStackOp::init(
-            tx_id,
-            format,
-            rlp_bytes.len(),
-            keccak_rand,
-        ));
-        let mut witness_table_idx = 0;
-
-        // This map keeps track
-        // - the last row in the witness table of each parsed tag,
-        // - the row in the rom table of each parsed tag.
-        // And this map is used to fill the tag_next column in the witness table
-        let mut tag_rom_row_map = BTreeMap::new();
-        let mut is_output;
-        let mut is_none;
-        let mut rlp_tag;
-        let mut lb_len = 0;
-        // These two variables keep track
-        // unique identifier of addresses and storage keys included in access list
-        let mut access_list_idx: u64 = 0;
-        let mut storage_key_idx: u64 = 0;
-
-        loop {
-            // default behavior
-            is_none = false;
-            is_output = false;
-            rlp_tag = RlpTag::Tag(cur.tag);
-
-            let mut next = cur.clone();
-            match cur.state {
-                DecodeTagStart => {
-                    if cur.tag.is_end() {
-                        // assertions
-                        assert_eq!(
-                            remaining_bytes
-                                .pop()
-                                .expect("remaining_bytes shall not be empty"),
-                            0
-                        );
-
-                        if cur.depth == 1 {
-                            assert_eq!(remaining_bytes.len(), 1);
-                            assert_eq!(remaining_bytes[0], 0);
-                            assert_eq!(cur.byte_idx, rlp_bytes.len() - 1);
-                            is_output = true;
-                            rlp_tag = RlpTag::RLC;
-                        } else if cur.depth == 4 {
-                            // end of storage keys list
-                            // note: depth alone currently is sufficient to ascertain
-                            // the end of a storage keys list as there's no other nested
-                            // structure at depth 4 specified in EIP standards
-                            storage_key_idx = 0;
-                        } else if cur.depth == 2 {
-                            // end of access list
-                            // note: depth alone currently is sufficient to ascertain
-                            // the end of an access list as there's no other nested
-                            // structure at depth 2 specified in EIP standards
-                            access_list_idx = 0;
-                        } else if cur.depth == 0 {
-                            // emit GasCost
-                            is_output = true;
-                            rlp_tag = RlpTag::GasCost;
-                        }
-
-                        if !remaining_bytes.is_empty() {
-                            let byte_remained = *remaining_bytes.last().unwrap();
-
-                            stack_ops.push(RlpStackOp::pop(
-                                tx_id,
-                                format,
-                                cur.byte_idx + 1,
-                                cur.depth - 1,
-                                byte_remained,
-                                prev_bytes_on_depth[cur.depth - 1],
-                                access_list_idx,
-                                storage_key_idx,
-                                keccak_rand,
-                            ));
-                        }
-
-                        // state transitions
-                        // if cur.depth == 0 then we are at the end of decoding
-                        if cur.depth > 0 {
-                            next.depth = cur.depth - 1;
-                        }
-                        next.state = DecodeTagStart;
-                    } else {
-                        let byte_value = rlp_bytes[cur.byte_idx];
-
-                        if byte_value > 0x80 && byte_value < 0xb8 {
-                            // detect start of access list address
-                            if cur.tag.is_access_list_address() {
-                                access_list_idx += 1;
-                            }
-                            // detect start of access list storage key
-                            if cur.tag.is_access_list_storage_key() {
-                                storage_key_idx += 1;
-                            }
-                        }
-
-                        if let Some(rem) = remaining_bytes.last_mut() {
-                            // read one more byte
-                            assert!(*rem >= 1);
-
-                            if !(0xc0..=0xf7).contains(&byte_value) {
-                                // Note: if the byte_value is in the range [0xc0..=0xf7],
-                                // then we anticipate a PUSH onto a higher depth.
-
-                                // add stack op on same depth
-                                stack_ops.push(RlpStackOp::update(
-                                    tx_id,
-                                    format,
-                                    cur.byte_idx + 1,
-                                    cur.depth,
-                                    *rem - 1,
-                                    access_list_idx,
-                                    storage_key_idx,
-                                    keccak_rand,
-                                ));
-                            }
-
-                            *rem -= 1;
-                        }
-
-                        if byte_value < 0x80 {
-                            // assertions
-                            assert!(!cur.tag.is_list());
-                            is_output = true;
-                            cur.tag_value_acc = Value::known(F::from(byte_value as u64));
-                            cur.tag_bytes_rlc = cur.tag_value_acc;
-                            cur.tag_length = 1;
-
-                            // state transitions
-                            next.state = DecodeTagStart;
-                        } else if byte_value == 0x80 {
-                            // assertions
-                            assert!(!cur.tag.is_list());
-                            is_output = true;
-                            is_none = true;
-                            cur.tag_value_acc = Value::known(F::zero());
-                            cur.tag_bytes_rlc = cur.tag_value_acc;
-                            cur.tag_length = 0;
-
-                            // state transitions
-                            next.state = DecodeTagStart;
-                        } else if byte_value < 0xb8 {
-                            // assertions
-                            assert!(!cur.tag.is_list());
-
-                            // state transitions
-                            next.tag_idx = 1;
-                            next.tag_length = (byte_value - 0x80) as usize;
-                            next.tag_value_acc =
-                                Value::known(F::from(rlp_bytes[cur.byte_idx + 1] as u64));
-                            next.tag_bytes_rlc = next.tag_value_acc;
-                            next.state = State::Bytes;
-                        } else if byte_value < 0xc0 {
-                            // assertions
-                            assert!(!cur.tag.is_list());
-
-                            // state transitions
-                            next.tag_idx = 1;
-                            next.tag_length = (byte_value - 0xb7) as usize;
-                            lb_len = rlp_bytes[cur.byte_idx + 1] as usize;
-                            next.tag_value_acc = Value::known(F::from(lb_len as u64));
-                            next.state = State::LongBytes;
-                        } else if byte_value < 0xf8 {
-                            // assertions
-                            assert!(cur.tag.is_begin());
-                            if cur.depth == 0 {
-                                is_output = true;
-                                rlp_tag = RlpTag::Len;
-                            }
-                            cur.tag_value_acc = Value::known(F::from(u64::from(byte_value - 0xc0)));
-                            cur.tag_length = 1;
-
-                            // state transitions
-                            let num_bytes_of_new_list = usize::from(byte_value - 0xc0);
-                            if let Some(rem) = remaining_bytes.last_mut() {
-                                // Since we are going to decode a new list inside current list,
-                                // after that the remaining bytes of
-                                // current list should be subtracted by
-                                // the number of bytes of the new list.
-                                assert!(*rem >= num_bytes_of_new_list);
-                                prev_bytes_on_depth[cur.depth] = *rem + 1;
-
-                                *rem -= num_bytes_of_new_list;
-                            }
-                            remaining_bytes.push(num_bytes_of_new_list);
-
-                            let al_inc: u64 = if cur.depth == 2 { 1 } else { 0 };
-                            let sk_inc: u64 = if cur.depth == 3 { 1 } else { 0 };
-
-                            stack_ops.push(RlpStackOp::push(
-                                tx_id,
-                                format,
-                                cur.byte_idx + 1,
-                                cur.depth + 1,
-                                num_bytes_of_new_list,
-                                access_list_idx + al_inc,
-                                storage_key_idx + sk_inc,
-                                keccak_rand,
-                            ));
-
-                            next.depth = cur.depth + 1;
-                            next.state = DecodeTagStart;
-                        } else {
-                            // assertions
-                            assert!(cur.tag.is_begin());
-                            // TODO: assert first leading byte is non-zero
-
-                            // state transitions
-                            next.tag_idx = 1;
-                            next.tag_length = (byte_value - 0xf7) as usize;
-                            lb_len = rlp_bytes[cur.byte_idx + 1] as usize;
-                            next.tag_value_acc = Value::known(F::from(lb_len as u64));
-                            next.state = State::LongList;
-                        }
-                    }
-                }
-                State::Bytes => {
-                    if let Some(rem) = remaining_bytes.last_mut() {
-                        assert!(*rem >= 1);
-
-                        let sk_inc =
-                            if cur.depth == 4 && cur.tag_idx >= cur.tag_length && *rem - 1 > 0 {
-                                1
-                            } else {
-                                0
-                            };
-
-                        // add stack op on same depth
-                        stack_ops.push(RlpStackOp::update(
-                            tx_id,
-                            format,
-                            cur.byte_idx + 1,
-                            cur.depth,
-                            *rem - 1,
-                            access_list_idx,
-                            storage_key_idx + sk_inc,
-                            keccak_rand,
-                        ));
-
-                        *rem -= 1;
-                    }
-                    if cur.tag_idx < cur.tag_length {
-                        // state transitions
-                        let max_length = rom_table[cur_rom_row[0]].max_length;
-                        let b = match max_length.cmp(&32) {
-                            Ordering::Less => Value::known(F::from(256_u64)),
-                            Ordering::Equal => word_rand,
-                            Ordering::Greater => keccak_rand,
-                        };
-                        next.tag_idx = cur.tag_idx + 1;
-                        next.tag_value_acc = cur.tag_value_acc * b
-                            + Value::known(F::from(rlp_bytes[cur.byte_idx + 1] as u64));
-                        next.tag_bytes_rlc = cur.tag_bytes_rlc * keccak_rand
-                            + Value::known(F::from(rlp_bytes[cur.byte_idx + 1] as u64));
-                    } else {
-                        // assertions
-                        is_output = true;
-
-                        // state transitions
-                        next.state = DecodeTagStart;
-                    }
-                }
-                State::LongBytes => {
-                    if let Some(rem) = remaining_bytes.last_mut() {
-                        assert!(*rem >= 1);
-
-                        // add stack op on same depth
-                        stack_ops.push(RlpStackOp::update(
-                            tx_id,
-                            format,
-                            cur.byte_idx + 1,
-                            cur.depth,
-                            *rem - 1,
-                            access_list_idx,
-                            storage_key_idx,
-                            keccak_rand,
-                        ));
-
-                        *rem -= 1;
-                    }
-
-                    if cur.tag_idx < cur.tag_length {
-                        // state transitions
-                        next.tag_idx = cur.tag_idx + 1;
-                        lb_len = lb_len * 256 + usize::from(rlp_bytes[cur.byte_idx + 1]);
-                        next.tag_value_acc = Value::known(F::from(lb_len as u64));
-                    } else {
-                        // we're dealing with case cur.tag_idx == cur.tag_length
-
-                        // state transitions
-                        next.tag_idx = 1;
-                        next.tag_length = lb_len;
-                        next.tag_value_acc =
-                            Value::known(F::from(u64::from(rlp_bytes[cur.byte_idx + 1])));
-                        next.tag_bytes_rlc = next.tag_value_acc;
-                        next.state = State::Bytes;
-                    }
-                }
-                State::LongList => {
-                    if let Some(rem) = remaining_bytes.last_mut() {
-                        // read one more byte
-                        assert!(*rem >= 1);
-
-                        // add stack op on same depth
-                        if cur.tag_idx < cur.tag_length {
-                            stack_ops.push(RlpStackOp::update(
-                                tx_id,
-                                format,
-                                cur.byte_idx + 1,
-                                cur.depth,
-                                *rem - 1,
-                                access_list_idx,
-                                storage_key_idx,
-                                keccak_rand,
-                            ));
-                        }
-
-                        *rem -= 1;
-                    }
-                    if cur.tag_idx < cur.tag_length {
-                        // state transitions
-                        next.tag_idx = cur.tag_idx + 1;
-                        lb_len = lb_len * 256 + usize::from(rlp_bytes[cur.byte_idx + 1]);
-                        next.tag_value_acc = Value::known(F::from(lb_len as u64));
-                    } else {
-                        // assertions
-                        if cur.depth == 0 {
-                            is_output = true;
-                            rlp_tag = RlpTag::Len;
-                        }
-                        if let Some(rem) = remaining_bytes.last_mut() {
-                            assert!(*rem >= lb_len);
-                            prev_bytes_on_depth[cur.depth] = *rem + 1;
-
-                            *rem -= lb_len;
-                        }
-                        remaining_bytes.push(lb_len);
-
-                        let al_inc: u64 = if cur.depth == 2 { 1 } else { 0 };
-                        let sk_inc: u64 = if cur.depth == 3 { 1 } else { 0 };
-
-                        stack_ops.push(RlpStackOp::push(
-                            tx_id,
-                            format,
-                            cur.byte_idx + 1,
-                            cur.depth + 1,
-                            lb_len,
-                            access_list_idx + al_inc,
-                            storage_key_idx + sk_inc,
-                            keccak_rand,
-                        ));
-                        next.depth = cur.depth + 1;
-                        next.state = DecodeTagStart;
-                    }
-                }
-                State::End => {
-                    unreachable!()
-                }
-            }
-
-            if next.state == DecodeTagStart {
-                // we finished parsing current tag
-                let row = if cur_rom_row.len() == 1 {
-                    cur_rom_row[0]
-                } else if cur_rom_row.len() == 2 {
-                    // only cur_rom_row[0].tag_next is EndVector.
-                    assert_eq!(rom_table[cur_rom_row[0]].tag_next, EndVector);
-
-                    let rem = remaining_bytes.last().expect("");
-                    if *rem == 0 {
-                        // we have finished parsing the vector.
-                        cur_rom_row[0]
-                    } else {
-                        // we have not finished parsing the vector.
-                        cur_rom_row[1]
-                    }
-                } else {
-                    unreachable!()
-                };
-
-                assert_eq!(cur.tag, rom_table[row].tag);
-
-                tag_rom_row_map.insert(witness_table_idx, row);
-                next.tag = rom_table[row].tag_next;
-                cur_rom_row = rom_table[row].tag_next_idx.clone();
-
-                if next.tag.is_end() {
-                    // Since the EndObject or EndVector tag does not read any byte from the data
-                    // table.
-                    next.byte_idx = cur.byte_idx;
-                } else {
-                    next.byte_idx = cur.byte_idx + 1;
-                }
-            } else {
-                // next.state is one of { Bytes, LongBytes, LongList }
-                // the sm in these states need to read new byte from data table
-                next.byte_idx = cur.byte_idx + 1;
-            }
-
-            assert!(cur.byte_idx < rlp_bytes.len());
-            let (byte_value, bytes_rlc) = (rlp_bytes[cur.byte_idx], rlp_bytes_rlc[cur.byte_idx]);
-            let gas_cost_acc = rlp_gas_cost_acc[cur.byte_idx];
-
-            let tag_value = match rlp_tag {
-                RlpTag::Len => cur.tag_value_acc + Value::known(F::from((cur.byte_idx + 1) as u64)),
-                RlpTag::RLC => bytes_rlc,
-                RlpTag::GasCost => gas_cost_acc,
-                RlpTag::Tag(_) => cur.tag_value_acc,
-                RlpTag::Null => unreachable!("Null is not used"),
-            };
-            let (tag_bytes_rlc, tag_length) = match rlp_tag {
-                // Len | RLC | GasCost are just meta-info extracted from keccak input bytes
-                RlpTag::Len => (Value::known(F::zero()), cur.tag_length),
-                RlpTag::RLC | RlpTag::GasCost => (Value::known(F::zero()), 0),
-                RlpTag::Tag(_) => (cur.tag_bytes_rlc, cur.tag_length),
-                RlpTag::Null => unreachable!("Null is not used"),
-            };
-
-            witness.push(RlpFsmWitnessRow {
-                rlp_table: RlpTable {
-                    tx_id,
-                    format,
-                    rlp_tag,
-                    tag_value,
-                    tag_bytes_rlc,
-                    tag_length,
-                    is_output,
-                    is_none,
-                    access_list_idx,
-                    storage_key_idx,
-                },
-                state_machine: StateMachine {
-                    state: cur.state,
-                    tag: cur.tag,
-                    max_length: Default::default(), // will be filled up later
-                    tag_next: Default::default(),   // will be filled up later
-                    byte_idx: cur.byte_idx + 1,
-                    byte_rev_idx: rlp_bytes.len() - cur.byte_idx,
-                    byte_value,
-                    tag_idx: cur.tag_idx,
-                    tag_acc_value: cur.tag_value_acc,
-                    depth: cur.depth,
-                    bytes_rlc,
-                    gas_cost_acc,
-                },
-                // The stack operations will be later sorted and assigned
-                rlp_decoding_table: RlpStackOp::default(),
-            });
-
-            witness_table_idx += 1;
-
-            if cur.tag == EndObject && cur.depth == 0 {
-                break;
-            }
-            cur = next;
-        }
-
-        assert_eq!(
-            stack_ops.len(),
-            witness.len(),
-            "Number of stack_ops must be equal to witness length"
-        );
-
-        // Sort the RlpStackOps and assign to the RlpDecodingTable part of witness
-        stack_ops.sort_by(|a, b| {
-            if (
-                a.tx_id,
-                a.format as u64,
-                a.depth,
-                a.al_idx,
-                a.sk_idx,
-                a.byte_idx,
-                a.stack_op.clone() as u64,
-            ) > (
-                b.tx_id,
-                b.format as u64,
-                b.depth,
-                a.al_idx,
-                a.sk_idx,
-                b.byte_idx,
-                b.stack_op.clone() as u64,
-            ) {
-                std::cmp::Ordering::Greater
-            } else {
-                std::cmp::Ordering::Less
-            }
-        });
-
-        for (idx, op) in stack_ops.into_iter().enumerate() {
-            witness[idx].rlp_decoding_table = op;
-        }
-
-        // filling up the `tag_next` col of the witness table
-        let mut idx = 0;
-        for (witness_idx, rom_table_row) in tag_rom_row_map {
-            while idx <= witness_idx {
-                witness[idx].state_machine.tag_next = rom_table[rom_table_row].tag_next;
-                witness[idx].state_machine.max_length = rom_table[rom_table_row].max_length;
-                idx += 1;
-            }
-        }
-
-        witness
+        gen_rlp_fsm_witness(self.id as u64, format, &rlp_bytes, challenges)
     }
 
     #[cfg(test)]
@@ -1091,44 +576,19 @@ impl<F: Field> RlpFsmWitnessGen<F> for Transaction {
 
     fn gen_data_table(&self, challenges: &Challenges<Value<F>>) -> Vec<DataTable<F>> {
         let tx_id = self.id as u64;
-        let r = challenges.keccak_input();
 
         let (hash_format, sign_format) = match self.tx_type {
             TxType::Eip155 => (TxHashEip155, Some(TxSignEip155)),
             TxType::PreEip155 => (TxHashPreEip155, Some(TxSignPreEip155)),
             TxType::Eip1559 => (TxHashEip1559, Some(TxSignEip1559)),
             TxType::Eip2930 => (TxHashEip2930, Some(TxSignEip2930)),
+            TxType::Eip4844 => (TxHashEip4844, Some(TxSignEip4844)),
             TxType::L1Msg => (L1MsgHash, None),
         };
 
-        let get_table = |rlp_bytes: &Vec<u8>, format: Format| {
-            let n = rlp_bytes.len();
-            rlp_bytes
-                .iter()
-                .enumerate()
-                .scan(
-                    (Value::known(F::zero()), Value::known(F::zero())),
-                    |(rlc, gas_cost_acc), (i, &byte_value)| {
-                        let byte_cost = if byte_value == 0 { 4 } else { 16 };
-                        *rlc = *rlc * r + Value::known(F::from(byte_value as u64));
-                        *gas_cost_acc = *gas_cost_acc + Value::known(F::from(byte_cost));
-                        Some(DataTable {
-                            tx_id,
-                            format,
-                            byte_idx: i + 1,
-                            byte_rev_idx: n - i,
-                            byte_value,
-                            bytes_rlc: *rlc,
-                            gas_cost_acc: *gas_cost_acc,
-                        })
-                    },
-                )
-                .collect::<Vec<_>>()
-        };
-
-        let hash_table = get_table(&self.rlp_signed, hash_format);
+        let hash_table = gen_rlp_data_table(tx_id, hash_format, &self.rlp_signed, challenges);
         if let Some(sign_format) = sign_format {
-            let sign_table = get_table(&self.rlp_unsigned, sign_format);
+            let sign_table = gen_rlp_data_table(tx_id, sign_format, &self.rlp_unsigned, challenges);
             [sign_table, hash_table].concat()
         } else {
             hash_table
@@ -1193,6 +653,8 @@ impl From<MockTransaction> for Transaction {
             l1_fee: Default::default(),
             l1_fee_committed: Default::default(),
             access_list,
+            max_fee_per_blob_gas: Word::zero(),
+            blob_versioned_hashes: vec![],
             calls: vec![],
             steps: vec![],
         }
@@ -1228,12 +690,12 @@ pub(super) fn tx_convert(
         nonce: tx.nonce,
         gas: tx.gas,
         gas_price: tx.gas_price,
-        max_fee_per_gas: if tx.tx_type.is_eip1559() {
+        max_fee_per_gas: if tx.tx_type.is_eip1559() || tx.tx_type.is_eip4844() {
             tx.gas_fee_cap
         } else {
             tx.gas_price
         },
-        max_priority_fee_per_gas: if tx.tx_type.is_eip1559() {
+        max_priority_fee_per_gas: if tx.tx_type.is_eip1559() || tx.tx_type.is_eip4844() {
             tx.gas_tip_cap
         } else {
             tx.gas_price
@@ -1256,6 +718,8 @@ pub(super) fn tx_convert(
         l1_fee: tx.l1_fee,
         l1_fee_committed: tx.l1_fee_committed,
         access_list: tx.access_list.clone(),
+        max_fee_per_blob_gas: tx.max_fee_per_blob_gas,
+        blob_versioned_hashes: tx.blob_versioned_hashes.clone(),
         calls: tx
             .calls()
             .iter()